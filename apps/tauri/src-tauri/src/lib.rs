@@ -63,6 +63,8 @@ fn start_embedded_server() {
             host: "127.0.0.1".to_string(),
             port: 3000,
             static_dir: None, // Tauri WebView serves frontend, server is API-only
+            config_path: None,
+            skip_migrations: false,
         };
 
         if let Err(e) = my_movies_server::start_server(config).await {
@@ -95,7 +97,9 @@ pub fn run() {
     std::thread::sleep(std::time::Duration::from_millis(500));
 
     #[allow(unused_mut)]
-    let mut builder = tauri::Builder::default().plugin(tauri_plugin_http::init());
+    let mut builder = tauri::Builder::default()
+        .plugin(tauri_plugin_http::init())
+        .plugin(tauri_plugin_dialog::init());
 
     // Add barcode scanner plugin on mobile platforms
     // Note: The {} block is required because #[cfg] cannot be applied directly to assignments
@@ -2,13 +2,13 @@ import { useRef, useMemo, useState, useEffect, useImperativeHandle, forwardRef }
 import { useVirtualizer } from '@tanstack/react-virtual'
 import { Link } from '@tanstack/react-router'
 import { Check } from 'lucide-react'
-import { Movie } from '@/lib/api'
+import { MovieSummary } from '@/lib/api'
 import { PosterImage } from './PosterImage'
 
 type CardSize = 'small' | 'medium' | 'large'
 
 interface VirtualizedMovieGridProps {
-  movies: Movie[]
+  movies: MovieSummary[]
   cardSize: CardSize
   onMovieClick?: () => void
 }
@@ -70,7 +70,7 @@ export function VirtualizedMovieGrid({ movies, cardSize, onMovieClick }: Virtual
   
   // Group movies into rows
   const rows = useMemo(() => {
-    const result: Movie[][] = []
+    const result: MovieSummary[][] = []
     for (let i = 0; i < movies.length; i += columns) {
       result.push(movies.slice(i, i + columns))
     }
@@ -133,7 +133,7 @@ export function VirtualizedMovieGrid({ movies, cardSize, onMovieClick }: Virtual
 
 // Grouped version for alphabet sections
 interface VirtualizedMovieGridGroupedProps {
-  moviesByLetter: Record<string, Movie[]>
+  moviesByLetter: Record<string, MovieSummary[]>
   availableLetters: string[]
   cardSize: CardSize
   onMovieClick?: () => void
@@ -180,7 +180,7 @@ export const VirtualizedMovieGridGrouped = forwardRef<VirtualizedMovieGridGroupe
       type: 'header' | 'movies'
       letter?: string
       count?: number
-      movies?: Movie[]
+      movies?: MovieSummary[]
     }
     
     const { items, letterIndices } = useMemo(() => {
@@ -297,7 +297,7 @@ export const VirtualizedMovieGridGrouped = forwardRef<VirtualizedMovieGridGroupe
   }
 )
 
-function MovieCard({ movie, size, onClick }: { movie: Movie; size: CardSize; onClick?: () => void }) {
+function MovieCard({ movie, size, onClick }: { movie: MovieSummary; size: CardSize; onClick?: () => void }) {
   const showDetails = size !== 'small'
   
   const handleClick = () => {
@@ -33,6 +33,13 @@ export function useWebSocketSync() {
           }
           break
 
+        case 'movies_updated': {
+          queryClient.invalidateQueries({ queryKey: ['movies'] })
+          const { ids } = message.payload as { ids: string[] }
+          ids.forEach((id) => queryClient.invalidateQueries({ queryKey: ['movie', id] }))
+          break
+        }
+
         // Series events
         case 'series_added':
         case 'series_updated':
@@ -45,6 +52,7 @@ export function useWebSocketSync() {
 
         // Collection events
         case 'collection_imported':
+        case 'collection_split':
           queryClient.invalidateQueries({ queryKey: ['movies'] })
           queryClient.invalidateQueries({ queryKey: ['series'] })
           queryClient.invalidateQueries({ queryKey: ['collections'] })
@@ -67,10 +75,11 @@ export function useWebSocketSync() {
           }
           break
 
-        // TMDB enrichment progress events - don't invalidate, just for UI updates
+        // Progress events - don't invalidate, just for UI updates
         case 'tmdb_enrich_started':
         case 'tmdb_enrich_progress':
-          // These are handled by the import page directly
+        case 'collection_split_progress':
+          // Only used for progress displays
           break
 
         default:
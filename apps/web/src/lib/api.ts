@@ -39,8 +39,12 @@ type RequestOptions = {
   headers?: Record<string, string>
 }
 
+type AuthResult = { token: string; refresh_token: string; user: User }
+
 class ApiClient {
   private token: string | null = null
+  // Shared by concurrent requests: a refresh token can only be used once
+  private refreshing: Promise<boolean> | null = null
 
   setToken(token: string | null) {
     this.token = token
@@ -49,6 +53,40 @@ class ApiClient {
       return
     }
     localStorage.removeItem('token')
+    localStorage.removeItem('refresh_token')
+  }
+
+  private setSession(result: AuthResult) {
+    this.setToken(result.token)
+    localStorage.setItem('refresh_token', result.refresh_token)
+  }
+
+  // Get a new access token with the refresh token; false if the session is gone
+  private refreshSession(): Promise<boolean> {
+    const refreshToken = localStorage.getItem('refresh_token')
+    if (!refreshToken) {
+      return Promise.resolve(false)
+    }
+    if (!this.refreshing) {
+      this.refreshing = tauriFetch(`${API_BASE}/auth/refresh`, {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify({ refresh_token: refreshToken }),
+      })
+        .then(async (response) => {
+          if (!response.ok) {
+            this.setToken(null)
+            return false
+          }
+          this.setSession(await response.json())
+          return true
+        })
+        .catch(() => false)
+        .finally(() => {
+          this.refreshing = null
+        })
+    }
+    return this.refreshing
   }
 
   getToken(): string | null {
@@ -58,7 +96,7 @@ class ApiClient {
     return this.token
   }
 
-  private async request<T>(endpoint: string, options: RequestOptions = {}): Promise<T> {
+  private async request<T>(endpoint: string, options: RequestOptions = {}, retried = false): Promise<T> {
     const { method = 'GET', body, headers = {} } = options
 
     const requestHeaders: Record<string, string> = {
@@ -80,6 +118,16 @@ class ApiClient {
       body: body instanceof FormData ? body : body ? JSON.stringify(body) : undefined,
     })
 
+    if (
+      response.status === 401 &&
+      !retried &&
+      token &&
+      !endpoint.startsWith('/auth/login') &&
+      (await this.refreshSession())
+    ) {
+      return this.request<T>(endpoint, options, true)
+    }
+
     if (!response.ok) {
       const error = await response.json().catch(() => ({ error: 'Unknown error' }))
       throw new Error(error.error || `HTTP ${response.status}`)
@@ -94,20 +142,20 @@ class ApiClient {
 
   // Auth
   async login(username: string, password: string) {
-    const result = await this.request<{ token: string; user: User }>('/auth/login', {
+    const result = await this.request<AuthResult>('/auth/login', {
       method: 'POST',
       body: { username, password },
     })
-    this.setToken(result.token)
+    this.setSession(result)
     return result
   }
 
-  async register(username: string, email: string, password: string) {
-    const result = await this.request<{ token: string; user: User }>('/auth/register', {
+  async register(username: string, email: string, password: string, inviteCode?: string) {
+    const result = await this.request<AuthResult>('/auth/register', {
       method: 'POST',
-      body: { username, email, password },
+      body: { username, email, password, invite_code: inviteCode },
     })
-    this.setToken(result.token)
+    this.setSession(result)
     return result
   }
 
@@ -115,7 +163,15 @@ class ApiClient {
     return this.request<User>('/auth/me')
   }
 
+  async getCapabilities() {
+    return this.request<Capabilities>('/capabilities')
+  }
+
   logout() {
+    if (this.getToken()) {
+      // Revoke the session on the server; signing out locally doesn't wait for it
+      this.request('/auth/logout', { method: 'POST' }).catch(() => {})
+    }
     this.setToken(null)
   }
 
@@ -139,7 +195,7 @@ class ApiClient {
     const query = params ? '?' + new URLSearchParams(
       Object.fromEntries(Object.entries(params).filter(([_, v]) => v !== undefined)) as Record<string, string>
     ).toString() : ''
-    return this.request<PaginatedResponse<Movie>>(`/movies${query}`)
+    return this.request<PaginatedResponse<MovieSummary>>(`/movies${query}`)
   }
 
   async getMovie(id: string) {
@@ -199,7 +255,7 @@ class ApiClient {
       throw new Error(errorData.error || 'Import failed')
     }
     
-    return response.json() as Promise<{ imported: number; skipped: number; posters_restored: number; errors: string[] }>
+    return response.json() as Promise<{ imported: number; skipped: number; posters_restored: number; account_restored: boolean; errors: string[] }>
   }
 
   async refreshMovieTmdb(id: string, force: boolean = false) {
@@ -230,6 +286,17 @@ class ApiClient {
     return response.json() as Promise<{ message: string; movie: Movie }>
   }
 
+  async getTmdbPosters(id: string) {
+    return this.request<TmdbImages>(`/movies/${id}/tmdb-posters`)
+  }
+
+  async setPosterFromTmdb(id: string, file_path: string) {
+    return this.request<Movie>(`/movies/${id}/poster/from-tmdb`, {
+      method: 'POST',
+      body: { file_path },
+    })
+  }
+
   async setPosterFromUrl(id: string, url: string) {
     return this.request<{ message: string; movie: Movie }>(`/movies/${id}/set-poster-url`, {
       method: 'POST',
@@ -237,10 +304,11 @@ class ApiClient {
     })
   }
 
-  async checkMovieDuplicates(title: string, barcode?: string, tmdb_id?: number) {
+  async checkMovieDuplicates(title: string, barcode?: string, tmdb_id?: number, household?: boolean) {
     const params = new URLSearchParams({ title })
     if (barcode) params.set('barcode', barcode)
     if (tmdb_id) params.set('tmdb_id', tmdb_id.toString())
+    if (household) params.set('household', 'true')
     return this.request<DuplicateCheckResult>(`/movies/check-duplicates?${params}`)
   }
 
@@ -320,6 +388,12 @@ class ApiClient {
     return this.request<ImportResult>('/import/csv', { method: 'POST', body: formData })
   }
 
+  async importDvdProfiler(file: File) {
+    const formData = new FormData()
+    formData.append('file', file)
+    return this.request<ImportResult>('/import/dvdprofiler', { method: 'POST', body: formData })
+  }
+
   async enrichMoviesTmdb(force: boolean = false) {
     const query = force ? '?force=true' : ''
     return this.request<EnrichResult>(`/import/enrich-tmdb${query}`, { method: 'POST' })
@@ -333,6 +407,22 @@ class ApiClient {
     return this.request<EnrichStatus>('/import/enrich-tmdb/status')
   }
 
+  // Scans a folder on the server (the embedded one in the desktop app), admin only
+  async scanMediaFolder(path: string, recursive: boolean = true) {
+    return this.request<MediaFolderScanResult>('/import/media-folder', {
+      method: 'POST',
+      body: { path, recursive },
+    })
+  }
+
+  // Native folder picker, only available in the desktop app
+  async pickMediaFolder(): Promise<string | null> {
+    if (!isTauri) return null
+    const { open } = await import('@tauri-apps/plugin-dialog')
+    const selected = await open({ directory: true, multiple: false })
+    return typeof selected === 'string' ? selected : null
+  }
+
   // Settings (admin only)
   async getSettings() {
     return this.request<SettingStatus[]>('/settings')
@@ -361,6 +451,21 @@ class ApiClient {
     })
   }
 
+  async getInvites() {
+    return this.request<Invite[]>('/invites')
+  }
+
+  async createInvite(input: CreateInvite) {
+    return this.request<CreatedInvite>('/invites', {
+      method: 'POST',
+      body: input,
+    })
+  }
+
+  async deleteInvite(id: string) {
+    return this.request<void>(`/invites/${id}`, { method: 'DELETE' })
+  }
+
   // User Settings
   async updateLanguage(language: string | null) {
     return this.request<User>('/auth/language', {
@@ -427,6 +532,50 @@ class ApiClient {
 export const api = new ApiClient()
 
 // Types
+export type CapabilityStatus = 'configured' | 'unconfigured'
+
+export interface Capabilities {
+  version: string
+  schema_version: number | null
+  tmdb: CapabilityStatus
+  email: CapabilityStatus
+  integrations: {
+    omdb: CapabilityStatus
+    discord: CapabilityStatus
+    error_reporting: CapabilityStatus
+    push: CapabilityStatus
+  }
+  registration: boolean
+  registration_invite_only: boolean
+  read_only: boolean
+  demo_mode: boolean
+  sharing: boolean
+  barcode_providers: string[]
+  storage: 'db' | 'fs' | 's3'
+}
+
+export interface Invite {
+  id: string
+  role: 'admin' | 'user'
+  max_uses: number
+  uses: number
+  note: string | null
+  created_by: string | null
+  created_at: string
+  expires_at: string | null
+}
+
+export interface CreateInvite {
+  role?: 'admin' | 'user'
+  max_uses?: number
+  note?: string
+  expires_at?: string
+}
+
+export interface CreatedInvite extends Invite {
+  code: string
+}
+
 export interface User {
   id: string
   username: string
@@ -469,10 +618,39 @@ export interface Movie {
   revenue?: number
   is_collection: boolean
   parent_collection_id?: string
+  // Set on movies of other household members
+  owner_name?: string
+  // Set once a backdrop is stored; load it from /movies/{id}/backdrop
+  backdrop_hash?: string
   created_at: string
   updated_at: string
 }
 
+/** Movie in list responses; `getMovie` returns all fields */
+export type MovieSummary = Pick<
+  Movie,
+  | 'id'
+  | 'user_id'
+  | 'title'
+  | 'original_title'
+  | 'sort_title'
+  | 'barcode'
+  | 'tmdb_id'
+  | 'production_year'
+  | 'running_time'
+  | 'director'
+  | 'genres'
+  | 'disc_type'
+  | 'watched'
+  | 'personal_rating'
+  | 'location'
+  | 'is_collection'
+  | 'parent_collection_id'
+  | 'owner_name'
+  | 'created_at'
+  | 'updated_at'
+>
+
 export interface CreateMovie {
   title: string
   barcode?: string
@@ -490,6 +668,7 @@ export interface MovieFilter {
   watched?: string
   is_collection?: string
   exclude_collection_children?: string
+  include_household?: string
   sort_by?: string
   sort_order?: string
   limit?: string
@@ -532,7 +711,10 @@ export interface BarcodeResult {
   barcode: string
   title?: string
   vendor?: string
+  source?: string
+  disc_type?: string
   tmdb_results: TmdbSearchResult[]
+  cached: boolean
 }
 
 export interface TmdbSearchResult {
@@ -543,6 +725,19 @@ export interface TmdbSearchResult {
   poster_path?: string
 }
 
+export interface TmdbImage {
+  file_path: string
+  width: number
+  height: number
+  iso_639_1?: string
+  vote_average?: number
+}
+
+export interface TmdbImages {
+  posters: TmdbImage[]
+  backdrops: TmdbImage[]
+}
+
 export interface TmdbMovieDetails {
   id: number
   title: string
@@ -567,6 +762,7 @@ export interface TmdbTvDetails {
   tagline?: string
   overview?: string
   poster_path?: string
+  backdrop_path?: string
   first_air_date?: string
   number_of_episodes?: number
   number_of_seasons?: number
@@ -582,6 +778,34 @@ export interface ImportResult {
   errors: string[]
 }
 
+export interface MediaFile {
+  path: string
+  file_name: string
+  size: number
+  title: string
+  year: number | null
+  resolution: string | null
+  source: string | null
+  edition: string | null
+}
+
+export interface MediaFolderProposal {
+  file: MediaFile
+  title: string
+  original_title: string | null
+  production_year: number | null
+  tmdb_id: number | null
+  poster_path: string | null
+  disc_type: string
+  existing_movie_id: string | null
+}
+
+export interface MediaFolderScanResult {
+  total: number
+  tmdb_matched: number
+  proposals: MediaFolderProposal[]
+}
+
 export interface EnrichResult {
   total: number
   enriched: number
@@ -663,8 +887,8 @@ export interface SelectedMovie {
 
 export interface SplitCollectionResult {
   message: string
-  created_movies: string[]
-  errors: string[]
+  job_id: string
+  total: number
 }
 
 export interface TmdbTestResult {
@@ -2,7 +2,7 @@ import { createFileRoute, redirect, useNavigate, getRouteApi } from '@tanstack/r
 import { useQuery } from '@tanstack/react-query'
 import { useState, useEffect, useRef, useMemo, useCallback } from 'react'
 import { Film, Search, X, SlidersHorizontal } from 'lucide-react'
-import { api, MovieFilter, Movie } from '@/lib/api'
+import { api, MovieFilter, MovieSummary } from '@/lib/api'
 import { useI18n } from '@/hooks/useI18n'
 import { VirtualizedMovieGrid, VirtualizedMovieGridGrouped, VirtualizedMovieGridGroupedHandle } from '@/components/VirtualizedMovieGrid'
 import { FAB } from '@/components/FAB'
@@ -135,7 +135,7 @@ function MoviesPage() {
 
   // Group movies by first letter
   const moviesByLetter = useMemo(() => {
-    const grouped: Record<string, Movie[]> = {}
+    const grouped: Record<string, MovieSummary[]> = {}
     
     for (const movie of movies) {
       const sortTitle = movie.sort_title || movie.title
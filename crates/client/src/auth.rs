@@ -0,0 +1,53 @@
+use reqwest::Method;
+use serde_json::json;
+
+use crate::client::Client;
+use crate::error::{Error, Result};
+use crate::models::{AuthResponse, RegisterRequest, User};
+
+impl Client {
+    /// Sign in; later requests use the returned tokens
+    pub async fn login(&self, username: &str, password: &str) -> Result<AuthResponse> {
+        let request = self.request(Method::POST, "/auth/login")?.json(&json!({
+            "username": username,
+            "password": password,
+            "device": concat!("my-movies-client/", env!("CARGO_PKG_VERSION")),
+        }));
+        let auth: AuthResponse = self.send(request).await?;
+        self.store_tokens(auth.token.clone(), auth.refresh_token.clone());
+        Ok(auth)
+    }
+
+    /// Create an account and sign in to it
+    pub async fn register(&self, input: &RegisterRequest) -> Result<AuthResponse> {
+        let request = self.request(Method::POST, "/auth/register")?.json(input);
+        let auth: AuthResponse = self.send(request).await?;
+        self.store_tokens(auth.token.clone(), auth.refresh_token.clone());
+        Ok(auth)
+    }
+
+    /// Exchange the refresh token of the last sign-in for new tokens
+    pub async fn refresh(&self) -> Result<AuthResponse> {
+        let refresh_token = self.refresh_token().ok_or(Error::Unauthenticated)?;
+        let request = self
+            .request(Method::POST, "/auth/refresh")?
+            .json(&json!({ "refresh_token": refresh_token }));
+        let auth: AuthResponse = self.send(request).await?;
+        self.store_tokens(auth.token.clone(), auth.refresh_token.clone());
+        Ok(auth)
+    }
+
+    /// The signed-in user
+    pub async fn me(&self) -> Result<User> {
+        let request = self.request(Method::GET, "/auth/me")?;
+        self.send(request).await
+    }
+
+    /// End the session on the server and forget the tokens
+    pub async fn logout(&self) -> Result<()> {
+        let request = self.request(Method::POST, "/auth/logout")?;
+        let result = self.send_empty(request).await;
+        self.clear_tokens();
+        result
+    }
+}
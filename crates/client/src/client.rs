@@ -0,0 +1,164 @@
+use std::sync::RwLock;
+
+use reqwest::{Method, RequestBuilder, Response, Url};
+use serde::de::DeserializeOwned;
+
+use crate::error::{Error, Result};
+
+/// Prefix of all API routes
+const API_PREFIX: &str = "/api/v1";
+
+#[derive(Debug, Default)]
+struct Tokens {
+    access: Option<String>,
+    refresh: Option<String>,
+}
+
+/// Client for one server. Signing in stores the tokens, so later requests
+/// are authenticated; an API key works as a token too.
+#[derive(Debug)]
+pub struct Client {
+    http: reqwest::Client,
+    /// Server URL without a trailing slash, e.g. `https://movies.example.com`
+    base_url: String,
+    tokens: RwLock<Tokens>,
+}
+
+impl Client {
+    /// Client for the server at `base_url`, e.g. `http://localhost:3000` or
+    /// a reverse proxy path like `https://example.com/movies`
+    pub fn new(base_url: &str) -> Result<Self> {
+        Self::with_http_client(base_url, reqwest::Client::new())
+    }
+
+    /// Like [`Client::new`] with a configured reqwest client (timeouts,
+    /// proxies, certificates)
+    pub fn with_http_client(base_url: &str, http: reqwest::Client) -> Result<Self> {
+        let url = Url::parse(base_url).map_err(|e| Error::Url(format!("{}: {}", base_url, e)))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(Error::Url(format!("{}: not an HTTP URL", base_url)));
+        }
+
+        Ok(Self {
+            http,
+            base_url: url.as_str().trim_end_matches('/').to_string(),
+            tokens: RwLock::default(),
+        })
+    }
+
+    /// Authenticate requests with an access token or API key
+    pub fn with_token(self, token: impl Into<String>) -> Self {
+        self.set_token(Some(token.into()));
+        self
+    }
+
+    /// Replace the access token; `None` signs out locally
+    pub fn set_token(&self, token: Option<String>) {
+        if let Ok(mut tokens) = self.tokens.write() {
+            tokens.access = token;
+        }
+    }
+
+    /// The access token requests are sent with
+    pub fn token(&self) -> Option<String> {
+        self.tokens.read().ok().and_then(|t| t.access.clone())
+    }
+
+    pub(crate) fn refresh_token(&self) -> Option<String> {
+        self.tokens.read().ok().and_then(|t| t.refresh.clone())
+    }
+
+    pub(crate) fn store_tokens(&self, access: String, refresh: String) {
+        if let Ok(mut tokens) = self.tokens.write() {
+            tokens.access = Some(access);
+            tokens.refresh = Some(refresh);
+        }
+    }
+
+    pub(crate) fn clear_tokens(&self) {
+        if let Ok(mut tokens) = self.tokens.write() {
+            *tokens = Tokens::default();
+        }
+    }
+
+    /// URL of an API route, e.g. `/movies` → `{base}/api/v1/movies`
+    pub fn url(&self, path: &str) -> Result<Url> {
+        let url = format!("{}{}{}", self.base_url, API_PREFIX, path);
+        Url::parse(&url).map_err(|e| Error::Url(format!("{}: {}", url, e)))
+    }
+
+    /// Request to an API route with the access token, if there is one
+    pub(crate) fn request(&self, method: Method, path: &str) -> Result<RequestBuilder> {
+        let request = self.http.request(method, self.url(path)?);
+        Ok(match self.token() {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        })
+    }
+
+    /// Send a request and decode the JSON body of the response
+    pub(crate) async fn send<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T> {
+        let response = check(request.send().await?).await?;
+        let body = response.bytes().await?;
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    /// Send a request whose response has no body worth decoding
+    pub(crate) async fn send_empty(&self, request: RequestBuilder) -> Result<()> {
+        check(request.send().await?).await?;
+        Ok(())
+    }
+}
+
+/// Turn error statuses into [`Error::Api`]
+async fn check(response: Response) -> Result<Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await.unwrap_or_default();
+    Err(Error::from_body(status.as_u16(), &body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url() {
+        let client = Client::new("http://localhost:3000").unwrap();
+        assert_eq!(
+            client.url("/movies").unwrap().as_str(),
+            "http://localhost:3000/api/v1/movies"
+        );
+
+        // Behind a reverse proxy, with or without trailing slash
+        let client = Client::new("https://example.com/movies/").unwrap();
+        assert_eq!(
+            client.url("/auth/me").unwrap().as_str(),
+            "https://example.com/movies/api/v1/auth/me"
+        );
+
+        assert!(matches!(Client::new("localhost"), Err(Error::Url(_))));
+        assert!(matches!(
+            Client::new("ftp://example.com"),
+            Err(Error::Url(_))
+        ));
+    }
+
+    #[test]
+    fn test_tokens() {
+        let client = Client::new("http://localhost:3000")
+            .unwrap()
+            .with_token("mm_key");
+        assert_eq!(client.token().as_deref(), Some("mm_key"));
+        assert_eq!(client.refresh_token(), None);
+
+        client.store_tokens("access".into(), "refresh".into());
+        assert_eq!(client.token().as_deref(), Some("access"));
+        assert_eq!(client.refresh_token().as_deref(), Some("refresh"));
+
+        client.clear_tokens();
+        assert_eq!(client.token(), None);
+    }
+}
@@ -0,0 +1,67 @@
+use reqwest::Method;
+use uuid::Uuid;
+
+use crate::client::Client;
+use crate::error::Result;
+use crate::models::{
+    AddCollectionItem, Collection, CollectionFilter, CollectionItem, CreateCollection,
+    UpdateCollection,
+};
+
+impl Client {
+    pub async fn list_collections(&self, filter: &CollectionFilter) -> Result<Vec<Collection>> {
+        let request = self.request(Method::GET, "/collections")?.query(filter);
+        self.send(request).await
+    }
+
+    pub async fn get_collection(&self, id: Uuid) -> Result<Collection> {
+        let request = self.request(Method::GET, &format!("/collections/{}", id))?;
+        self.send(request).await
+    }
+
+    pub async fn create_collection(&self, input: &CreateCollection) -> Result<Collection> {
+        let request = self.request(Method::POST, "/collections")?.json(input);
+        self.send(request).await
+    }
+
+    pub async fn update_collection(
+        &self,
+        id: Uuid,
+        input: &UpdateCollection,
+    ) -> Result<Collection> {
+        let request = self
+            .request(Method::PUT, &format!("/collections/{}", id))?
+            .json(input);
+        self.send(request).await
+    }
+
+    pub async fn delete_collection(&self, id: Uuid) -> Result<()> {
+        let request = self.request(Method::DELETE, &format!("/collections/{}", id))?;
+        self.send_empty(request).await
+    }
+
+    /// Movies and series in a collection, in order
+    pub async fn collection_items(&self, id: Uuid) -> Result<Vec<CollectionItem>> {
+        let request = self.request(Method::GET, &format!("/collections/{}/items", id))?;
+        self.send(request).await
+    }
+
+    pub async fn add_collection_item(
+        &self,
+        id: Uuid,
+        input: &AddCollectionItem,
+    ) -> Result<CollectionItem> {
+        let request = self
+            .request(Method::POST, &format!("/collections/{}/items", id))?
+            .json(input);
+        self.send(request).await
+    }
+
+    pub async fn remove_collection_item(&self, id: Uuid, item_id: Uuid) -> Result<()> {
+        let request = self.request(
+            Method::DELETE,
+            &format!("/collections/{}/items/{}", id, item_id),
+        )?;
+        self.send_empty(request).await
+    }
+}
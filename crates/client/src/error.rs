@@ -0,0 +1,95 @@
+use thiserror::Error;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Invalid URL: {0}")]
+    Url(String),
+
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    /// The server answered with an error status; `message` and `code` are
+    /// the `error` and `code` of the response body
+    #[error("API error ({status}): {message}")]
+    Api {
+        status: u16,
+        message: String,
+        code: Option<String>,
+    },
+
+    #[error("Unexpected response: {0}")]
+    Decode(#[from] serde_json::Error),
+
+    #[error("Not signed in")]
+    Unauthenticated,
+}
+
+impl Error {
+    /// Status of an error response
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            Error::Api { status, .. } => Some(*status),
+            Error::Http(e) => e.status().map(|s| s.as_u16()),
+            _ => None,
+        }
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        self.status() == Some(404)
+    }
+
+    pub fn is_unauthorized(&self) -> bool {
+        matches!(self, Error::Unauthenticated) || self.status() == Some(401)
+    }
+
+    /// Error of a response body; `{"error": "...", "code": "..."}` from the
+    /// server, or the body as is from a proxy in between
+    pub(crate) fn from_body(status: u16, body: &str) -> Self {
+        #[derive(serde::Deserialize)]
+        struct ErrorBody {
+            error: String,
+            code: Option<String>,
+        }
+
+        match serde_json::from_str::<ErrorBody>(body) {
+            Ok(body) => Error::Api {
+                status,
+                message: body.error,
+                code: body.code,
+            },
+            Err(_) => Error::Api {
+                status,
+                message: body.trim().to_string(),
+                code: None,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_body() {
+        let err = Error::from_body(
+            503,
+            r#"{"error":"TMDB API key not configured","code":"tmdb_unconfigured"}"#,
+        );
+        assert_eq!(err.status(), Some(503));
+        match err {
+            Error::Api { message, code, .. } => {
+                assert_eq!(message, "TMDB API key not configured");
+                assert_eq!(code.as_deref(), Some("tmdb_unconfigured"));
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+
+        let err = Error::from_body(502, "Bad Gateway\n");
+        assert!(matches!(err, Error::Api { ref message, .. } if message == "Bad Gateway"));
+        assert!(Error::from_body(404, r#"{"error":"Item not found"}"#).is_not_found());
+        assert!(Error::Unauthenticated.is_unauthorized());
+    }
+}
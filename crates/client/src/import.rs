@@ -0,0 +1,23 @@
+use reqwest::Method;
+use reqwest::multipart::{Form, Part};
+
+use crate::client::Client;
+use crate::error::Result;
+use crate::models::ImportResult;
+
+impl Client {
+    /// Import a CSV export; `preset` names an import preset for the CSV of
+    /// another collection manager
+    pub async fn import_csv(&self, csv: Vec<u8>, preset: Option<&str>) -> Result<ImportResult> {
+        let part = Part::bytes(csv)
+            .file_name("import.csv")
+            .mime_str("text/csv")?;
+        let mut request = self
+            .request(Method::POST, "/import/csv")?
+            .multipart(Form::new().part("file", part));
+        if let Some(preset) = preset {
+            request = request.query(&[("preset", preset)]);
+        }
+        self.send(request).await
+    }
+}
@@ -0,0 +1,32 @@
+//! Typed async client for the My Movies API (`/api/v1`), for scripts, the CLI
+//! and third-party tools.
+//!
+//! ```no_run
+//! # async fn run() -> my_movies_client::Result<()> {
+//! use my_movies_client::{Client, MovieFilter};
+//!
+//! let client = Client::new("http://localhost:3000")?;
+//! client.login("alice", "secret").await?;
+//! let movies = client
+//!     .list_movies(&MovieFilter {
+//!         search: Some("matrix".into()),
+//!         ..Default::default()
+//!     })
+//!     .await?;
+//! println!("{} of {} movies", movies.items.len(), movies.total);
+//! # Ok(())
+//! # }
+//! ```
+
+mod auth;
+mod client;
+mod collections;
+pub mod error;
+mod import;
+pub mod models;
+mod movies;
+mod scan;
+
+pub use client::Client;
+pub use error::{Error, Result};
+pub use models::*;
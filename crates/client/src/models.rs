@@ -0,0 +1,459 @@
+//! Request and response types of the API. Responses keep the fields they
+//! don't name in `extra`, so the client keeps working as the server adds
+//! fields.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+// ============ Auth ============
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct User {
+    pub id: Uuid,
+    pub username: String,
+    pub email: String,
+    /// `admin` or `user`
+    pub role: String,
+    pub language: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl User {
+    pub fn is_admin(&self) -> bool {
+        self.role == "admin"
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthResponse {
+    /// Access token, valid for `expires_in` seconds
+    pub token: String,
+    /// Exchange for a new access token with [`crate::Client::refresh`]
+    pub refresh_token: String,
+    pub expires_in: i64,
+    pub user: User,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RegisterRequest {
+    pub username: String,
+    pub email: String,
+    pub password: String,
+    /// Required while open registration is disabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invite_code: Option<String>,
+}
+
+// ============ Movies ============
+
+/// A movie as listed by [`crate::Client::list_movies`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MovieSummary {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub barcode: Option<String>,
+    pub tmdb_id: Option<i64>,
+    pub title: String,
+    pub original_title: Option<String>,
+    pub sort_title: Option<String>,
+    pub personal_title: Option<String>,
+    pub production_year: Option<i32>,
+    pub running_time: Option<i32>,
+    pub director: Option<String>,
+    pub personal_rating: Option<f64>,
+    pub disc_type: Option<String>,
+    pub genres: Option<String>,
+    pub tags: Option<String>,
+    pub is_collection: bool,
+    pub parent_collection_id: Option<Uuid>,
+    pub watched: bool,
+    pub favorite: bool,
+    pub status: Option<String>,
+    pub location: Option<String>,
+    /// `available`, `lent`, `missing`, `sold` or `ordered`
+    pub availability: Option<String>,
+    pub lent_to: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// A movie with all its details
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Movie {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub barcode: Option<String>,
+    pub tmdb_id: Option<i64>,
+    pub imdb_id: Option<String>,
+    pub title: String,
+    pub original_title: Option<String>,
+    pub sort_title: Option<String>,
+    pub personal_title: Option<String>,
+    pub description: Option<String>,
+    pub tagline: Option<String>,
+    pub production_year: Option<i32>,
+    pub release_date: Option<NaiveDate>,
+    pub running_time: Option<i32>,
+    pub director: Option<String>,
+    pub actors: Option<String>,
+    pub personal_rating: Option<f64>,
+    pub disc_type: Option<String>,
+    pub media_type: Option<String>,
+    pub genres: Option<String>,
+    pub tags: Option<String>,
+    pub is_collection: bool,
+    pub parent_collection_id: Option<Uuid>,
+    pub watched: bool,
+    pub favorite: bool,
+    pub status: Option<String>,
+    pub condition: Option<String>,
+    pub purchase_date: Option<NaiveDate>,
+    pub price: Option<f64>,
+    pub currency: Option<String>,
+    pub lent_to: Option<String>,
+    pub lent_due: Option<NaiveDate>,
+    pub location: Option<String>,
+    pub notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// A page of movies
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MovieList {
+    pub items: Vec<MovieSummary>,
+    /// Movies matching the filter, over all pages
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+/// Query of [`crate::Client::list_movies`]; unset fields don't filter
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MovieFilter {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub search: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub genre: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disc_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub watched: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub favorites: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_collection: Option<bool>,
+    /// Hide movies that belong to a collection
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exclude_collection_children: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub year_from: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub year_to: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collection_id: Option<Uuid>,
+    /// `owned` (default), `wishlist` or `ordered`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub availability: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort_by: Option<String>,
+    /// `asc` or `desc`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort_order: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CreateMovie {
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub barcode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tmdb_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disc_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub production_year: Option<i32>,
+}
+
+/// Fields to change; unset fields are left as they are. Fields not named
+/// here can be set through `extra`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UpdateMovie {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort_title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub personal_title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub barcode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tmdb_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub production_year: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub running_time: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub director: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub personal_rating: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disc_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub genres: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub watched: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub favorite: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purchase_date: Option<NaiveDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lent_to: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lent_due: Option<NaiveDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+// ============ Collections ============
+
+/// A box set or other physical collection of movies and series
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Collection {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub barcode: Option<String>,
+    pub title: String,
+    pub sort_title: Option<String>,
+    pub description: Option<String>,
+    pub disc_type: Option<String>,
+    pub location: Option<String>,
+    pub notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CollectionFilter {
+    /// Matches title or personal title (substring) or the exact barcode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub search: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort_by: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort_order: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disc_type: Option<String>,
+    /// Only collections containing this movie
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contains_movie: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CreateCollection {
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub barcode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disc_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UpdateCollection {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort_title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+}
+
+/// Sent and received as `Movie` or `Series`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CollectionItemType {
+    Movie,
+    Series,
+}
+
+/// A movie or series in a collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollectionItem {
+    pub id: Uuid,
+    pub collection_id: Uuid,
+    pub item_type: CollectionItemType,
+    pub movie_id: Option<Uuid>,
+    pub series_id: Option<Uuid>,
+    pub position: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AddCollectionItem {
+    pub item_type: CollectionItemType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub movie_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub series_id: Option<Uuid>,
+    /// Appended at the end if unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<i32>,
+}
+
+impl AddCollectionItem {
+    pub fn movie(movie_id: Uuid) -> Self {
+        Self {
+            item_type: CollectionItemType::Movie,
+            movie_id: Some(movie_id),
+            series_id: None,
+            position: None,
+        }
+    }
+
+    pub fn series(series_id: Uuid) -> Self {
+        Self {
+            item_type: CollectionItemType::Series,
+            movie_id: None,
+            series_id: Some(series_id),
+            position: None,
+        }
+    }
+}
+
+// ============ Scan & lookup ============
+
+/// Product found for a barcode and the TMDB movies matching its title
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BarcodeLookup {
+    pub barcode: String,
+    pub title: Option<String>,
+    pub vendor: Option<String>,
+    /// Barcode provider the title comes from
+    pub source: Option<String>,
+    /// DVD, Blu-ray or 4K UHD when the product says so
+    pub disc_type: Option<String>,
+    pub tmdb_results: Vec<TmdbSearchResult>,
+    /// Answered from the server's barcode cache
+    pub cached: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TmdbSearchResult {
+    pub id: i64,
+    pub title: String,
+    pub year: Option<String>,
+    pub poster_url: Option<String>,
+    pub poster_path: Option<String>,
+}
+
+// ============ Import ============
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportResult {
+    pub movies_imported: u32,
+    pub series_imported: u32,
+    pub collections_imported: u32,
+    /// Rows that could not be imported
+    pub errors: Vec<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_unset_fields_are_not_sent() {
+        let filter = MovieFilter {
+            search: Some("matrix".into()),
+            watched: Some(false),
+            limit: Some(20),
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_value(&filter).unwrap(),
+            json!({ "search": "matrix", "watched": false, "limit": 20 })
+        );
+
+        let mut update = UpdateMovie {
+            location: Some("Shelf A".into()),
+            ..Default::default()
+        };
+        update.extra.insert("edition".into(), json!("Steelbook"));
+        assert_eq!(
+            serde_json::to_value(&update).unwrap(),
+            json!({ "location": "Shelf A", "edition": "Steelbook" })
+        );
+    }
+
+    #[test]
+    fn test_unknown_fields_are_kept() {
+        let collection: Collection = serde_json::from_value(json!({
+            "id": Uuid::nil(),
+            "user_id": Uuid::nil(),
+            "barcode": null,
+            "title": "Nolan",
+            "sort_title": null,
+            "description": null,
+            "disc_type": "Blu-ray",
+            "location": null,
+            "notes": null,
+            "slip_cover": true,
+            "created_at": "2024-01-01T00:00:00Z",
+            "updated_at": "2024-01-01T00:00:00Z",
+        }))
+        .unwrap();
+        assert_eq!(collection.title, "Nolan");
+        assert_eq!(collection.extra["slip_cover"], json!(true));
+    }
+}
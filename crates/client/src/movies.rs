@@ -0,0 +1,44 @@
+use reqwest::Method;
+use uuid::Uuid;
+
+use crate::client::Client;
+use crate::error::Result;
+use crate::models::{CreateMovie, Movie, MovieFilter, MovieList, UpdateMovie};
+
+impl Client {
+    /// A page of the movies matching `filter`
+    pub async fn list_movies(&self, filter: &MovieFilter) -> Result<MovieList> {
+        let request = self.request(Method::GET, "/movies")?.query(filter);
+        self.send(request).await
+    }
+
+    pub async fn get_movie(&self, id: Uuid) -> Result<Movie> {
+        let request = self.request(Method::GET, &format!("/movies/{}", id))?;
+        self.send(request).await
+    }
+
+    pub async fn create_movie(&self, input: &CreateMovie) -> Result<Movie> {
+        let request = self.request(Method::POST, "/movies")?.json(input);
+        self.send(request).await
+    }
+
+    pub async fn update_movie(&self, id: Uuid, input: &UpdateMovie) -> Result<Movie> {
+        let request = self
+            .request(Method::PUT, &format!("/movies/{}", id))?
+            .json(input);
+        self.send(request).await
+    }
+
+    pub async fn delete_movie(&self, id: Uuid) -> Result<()> {
+        let request = self.request(Method::DELETE, &format!("/movies/{}", id))?;
+        self.send_empty(request).await
+    }
+
+    /// Fill in details from TMDB; `force` reloads fields that are already set
+    pub async fn refresh_movie_tmdb(&self, id: Uuid, force: bool) -> Result<Movie> {
+        let request = self
+            .request(Method::POST, &format!("/movies/{}/refresh-tmdb", id))?
+            .query(&[("force", force)]);
+        self.send(request).await
+    }
+}
@@ -0,0 +1,32 @@
+use reqwest::Method;
+use serde_json::json;
+
+use crate::client::Client;
+use crate::error::Result;
+use crate::models::{BarcodeLookup, TmdbSearchResult};
+
+impl Client {
+    /// Look up the product of a barcode and matching TMDB movies;
+    /// `refresh` skips the server's barcode cache
+    pub async fn scan_barcode(&self, barcode: &str, refresh: bool) -> Result<BarcodeLookup> {
+        let request = self
+            .request(Method::POST, "/scan")?
+            .json(&json!({ "barcode": barcode, "refresh": refresh }));
+        self.send(request).await
+    }
+
+    /// Search TMDB for movies by title, optionally of a release year
+    pub async fn search_tmdb_movies(
+        &self,
+        query: &str,
+        year: Option<i32>,
+    ) -> Result<Vec<TmdbSearchResult>> {
+        let mut request = self
+            .request(Method::GET, "/tmdb/search/movies")?
+            .query(&[("query", query)]);
+        if let Some(year) = year {
+            request = request.query(&[("year", year)]);
+        }
+        self.send(request).await
+    }
+}
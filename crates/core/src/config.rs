@@ -1,12 +1,57 @@
+use std::collections::HashMap;
+use std::path::Path;
+
 use serde::Deserialize;
 
+use crate::db::DatabaseOptions;
+use crate::error::{Error, Result};
+
+/// Default config file looked up in the working directory if no path is given
+pub const DEFAULT_CONFIG_FILE: &str = "config.toml";
+
+/// Environment variable pointing to a config file
+pub const CONFIG_PATH_ENV: &str = "MY_MOVIES_CONFIG";
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct Config {
     #[serde(default = "default_database_url")]
     pub database_url: String,
 
+    /// Start without applying pending migrations (`--skip-migrations`)
+    #[serde(default)]
+    pub skip_migrations: bool,
+
+    /// Snapshot the database before applying migrations
+    #[serde(default = "default_backup_before_migrate")]
+    pub backup_before_migrate: bool,
+
+    /// Log queries taking longer than this many milliseconds (off if unset)
+    #[serde(default)]
+    pub slow_query_ms: Option<u64>,
+
+    /// Session token key for HS256; unused with RS256 and EdDSA
     pub jwt_secret: String,
 
+    /// Session token signing: HS256 (with `jwt_secret`), RS256 or EdDSA
+    #[serde(default)]
+    pub jwt_algorithm: Option<String>,
+
+    /// PEM key pair for RS256 and EdDSA
+    #[serde(default)]
+    pub jwt_private_key_file: Option<String>,
+
+    #[serde(default)]
+    pub jwt_public_key_file: Option<String>,
+
+    /// Still accepted for tokens during a key rotation
+    #[serde(default)]
+    pub jwt_previous_secret: Option<String>,
+
+    #[serde(default)]
+    pub jwt_previous_public_key_file: Option<String>,
+
+    /// Empty until configured; TMDB features are unavailable until then
+    #[serde(default)]
     pub tmdb_api_key: String,
 
     #[serde(default = "default_host")]
@@ -14,6 +59,179 @@ pub struct Config {
 
     #[serde(default = "default_port")]
     pub port: u16,
+
+    /// Directory for rotating log files (file logging is disabled if unset)
+    #[serde(default)]
+    pub log_dir: Option<String>,
+
+    /// Number of daily log files to keep
+    #[serde(default = "default_log_max_files")]
+    pub log_max_files: usize,
+
+    /// Directory containing the frontend static files
+    #[serde(default)]
+    pub static_dir: Option<String>,
+
+    /// Allowed CORS origins (empty = allow any origin)
+    #[serde(default)]
+    pub cors_allowed_origins: Vec<String>,
+
+    /// Serve Swagger UI for the OpenAPI document at `/api/docs`
+    #[serde(default)]
+    pub swagger_ui: bool,
+
+    /// Fallbacks for integration settings (env vars and Settings UI take priority)
+    #[serde(default)]
+    pub discord_webhook_url: Option<String>,
+
+    #[serde(default)]
+    pub discord_events: Option<String>,
+
+    #[serde(default)]
+    pub error_report_url: Option<String>,
+
+    /// Public demo: seed a sample library, block destructive endpoints, reset periodically
+    #[serde(default)]
+    pub demo_mode: bool,
+
+    /// Minutes between demo database resets
+    #[serde(default = "default_demo_reset_minutes")]
+    pub demo_reset_minutes: u64,
+}
+
+/// Layout of `config.toml`. Every value can be overridden by the
+/// corresponding environment variable (e.g. `[server] port` by `PORT`).
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct FileConfig {
+    server: ServerSection,
+    database: DatabaseSection,
+    auth: AuthSection,
+    tmdb: TmdbSection,
+    cors: CorsSection,
+    logging: LoggingSection,
+    integrations: IntegrationsSection,
+    demo: DemoSection,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ServerSection {
+    host: Option<String>,
+    port: Option<u16>,
+    static_dir: Option<String>,
+    swagger_ui: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct DatabaseSection {
+    url: Option<String>,
+    skip_migrations: Option<bool>,
+    backup_before_migrate: Option<bool>,
+    slow_query_ms: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct AuthSection {
+    jwt_secret: Option<String>,
+    algorithm: Option<String>,
+    private_key_file: Option<String>,
+    public_key_file: Option<String>,
+    previous_secret: Option<String>,
+    previous_public_key_file: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct TmdbSection {
+    api_key: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct CorsSection {
+    allowed_origins: Option<Vec<String>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct LoggingSection {
+    dir: Option<String>,
+    max_files: Option<usize>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct IntegrationsSection {
+    discord_webhook_url: Option<String>,
+    discord_events: Option<String>,
+    error_report_url: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct DemoSection {
+    enabled: Option<bool>,
+    reset_minutes: Option<u64>,
+}
+
+impl FileConfig {
+    /// Flatten into environment-style key/value pairs understood by `Config`
+    fn into_vars(self) -> HashMap<String, String> {
+        let entries = [
+            ("HOST", self.server.host),
+            ("PORT", self.server.port.map(|p| p.to_string())),
+            ("STATIC_DIR", self.server.static_dir),
+            ("SWAGGER_UI", self.server.swagger_ui.map(|b| b.to_string())),
+            ("DATABASE_URL", self.database.url),
+            (
+                "SKIP_MIGRATIONS",
+                self.database.skip_migrations.map(|b| b.to_string()),
+            ),
+            (
+                "BACKUP_BEFORE_MIGRATE",
+                self.database.backup_before_migrate.map(|b| b.to_string()),
+            ),
+            (
+                "SLOW_QUERY_MS",
+                self.database.slow_query_ms.map(|n| n.to_string()),
+            ),
+            ("JWT_SECRET", self.auth.jwt_secret),
+            ("JWT_ALGORITHM", self.auth.algorithm),
+            ("JWT_PRIVATE_KEY_FILE", self.auth.private_key_file),
+            ("JWT_PUBLIC_KEY_FILE", self.auth.public_key_file),
+            ("JWT_PREVIOUS_SECRET", self.auth.previous_secret),
+            (
+                "JWT_PREVIOUS_PUBLIC_KEY_FILE",
+                self.auth.previous_public_key_file,
+            ),
+            ("TMDB_API_KEY", self.tmdb.api_key),
+            (
+                "CORS_ALLOWED_ORIGINS",
+                self.cors.allowed_origins.map(|o| o.join(",")),
+            ),
+            ("LOG_DIR", self.logging.dir),
+            (
+                "LOG_MAX_FILES",
+                self.logging.max_files.map(|n| n.to_string()),
+            ),
+            ("DISCORD_WEBHOOK_URL", self.integrations.discord_webhook_url),
+            ("DISCORD_EVENTS", self.integrations.discord_events),
+            ("ERROR_REPORT_URL", self.integrations.error_report_url),
+            ("DEMO_MODE", self.demo.enabled.map(|b| b.to_string())),
+            (
+                "DEMO_RESET_MINUTES",
+                self.demo.reset_minutes.map(|n| n.to_string()),
+            ),
+        ];
+
+        entries
+            .into_iter()
+            .filter_map(|(key, value)| value.map(|v| (key.to_string(), v)))
+            .collect()
+    }
 }
 
 fn default_database_url() -> String {
@@ -35,6 +253,10 @@ fn default_database_url() -> String {
     "sqlite:./data/my-movies.db?mode=rwc".to_string()
 }
 
+fn default_backup_before_migrate() -> bool {
+    true
+}
+
 fn default_host() -> String {
     "0.0.0.0".to_string()
 }
@@ -43,12 +265,182 @@ fn default_port() -> u16 {
     3000
 }
 
+fn default_log_max_files() -> usize {
+    7
+}
+
+fn default_demo_reset_minutes() -> u64 {
+    60
+}
+
 impl Config {
-    pub fn from_env() -> Result<Self, envy::Error> {
+    pub fn from_env() -> std::result::Result<Self, envy::Error> {
         envy::from_env::<Config>()
     }
 
+    /// Load configuration from a TOML file with environment variable overrides.
+    ///
+    /// The file is taken from `path`, then `$MY_MOVIES_CONFIG`, then `./config.toml`.
+    /// An explicitly given file must exist; the default location is optional.
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        Self::load_from(path, Path::new(DEFAULT_CONFIG_FILE), std::env::vars())
+    }
+
+    /// `load` with the default file location and the environment given
+    fn load_from(
+        path: Option<&Path>,
+        default_file: &Path,
+        env: impl IntoIterator<Item = (String, String)>,
+    ) -> Result<Self> {
+        let env: HashMap<String, String> = env
+            .into_iter()
+            .map(|(k, v)| (k.to_uppercase(), v))
+            .collect();
+        let explicit = path
+            .map(Path::to_path_buf)
+            .or_else(|| env.get(CONFIG_PATH_ENV).map(Into::into));
+
+        let file_vars = match explicit {
+            Some(path) => Self::read_file(&path)?,
+            None if default_file.exists() => Self::read_file(default_file)?,
+            None => HashMap::new(),
+        };
+
+        // Environment variables win over values from the file
+        let mut vars = file_vars;
+        vars.extend(env);
+
+        envy::from_iter::<_, Config>(vars)
+            .map_err(|e| Error::Configuration(format!("Failed to load config: {}", e)))
+    }
+
+    fn read_file(path: &Path) -> Result<HashMap<String, String>> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            Error::Configuration(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        let file: FileConfig = toml::from_str(&content).map_err(|e| {
+            Error::Configuration(format!("Invalid config file {}: {}", path.display(), e))
+        })?;
+
+        Ok(file.into_vars())
+    }
+
+    pub fn database_options(&self) -> DatabaseOptions {
+        DatabaseOptions {
+            skip: self.skip_migrations,
+            backup: self.backup_before_migrate,
+            slow_query_threshold: self
+                .slow_query_ms
+                .filter(|ms| *ms > 0)
+                .map(std::time::Duration::from_millis),
+        }
+    }
+
     pub fn server_addr(&self) -> String {
         format!("{}:{}", self.host, self.port)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+        vars.iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn write_config(dir: &Path, content: &str) -> std::path::PathBuf {
+        let path = dir.join("config.toml");
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_env_overrides_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_config(
+            dir.path(),
+            "[server]\nport = 8080\nhost = \"127.0.0.1\"\n\n[auth]\njwt_secret = \"from-file\"\n\n[cors]\nallowed_origins = [\"https://a.example\", \"https://b.example\"]\n",
+        );
+
+        let config = Config::load_from(
+            Some(&path),
+            Path::new("missing.toml"),
+            env(&[("PORT", "9090"), ("jwt_secret", "from-env")]),
+        )
+        .unwrap();
+        assert_eq!(config.port, 9090);
+        assert_eq!(config.jwt_secret, "from-env");
+        // Values only in the file are kept
+        assert_eq!(config.host, "127.0.0.1");
+        assert_eq!(
+            config.cors_allowed_origins,
+            ["https://a.example", "https://b.example"]
+        );
+    }
+
+    #[test]
+    fn test_unknown_keys_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_config(dir.path(), "[server]\nprot = 8080\n");
+
+        let err = Config::load_from(
+            Some(&path),
+            Path::new("missing.toml"),
+            env(&[("JWT_SECRET", "secret")]),
+        )
+        .unwrap_err();
+        assert!(
+            matches!(&err, Error::Configuration(msg) if msg.contains("prot")),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_explicit_path_must_exist() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("nope.toml");
+
+        assert!(matches!(
+            Config::load_from(
+                Some(&missing),
+                Path::new("missing.toml"),
+                env(&[("JWT_SECRET", "secret")]),
+            ),
+            Err(Error::Configuration(_))
+        ));
+        // Also when given through the environment
+        assert!(matches!(
+            Config::load_from(
+                None,
+                Path::new("missing.toml"),
+                env(&[
+                    ("JWT_SECRET", "secret"),
+                    (CONFIG_PATH_ENV, &missing.display().to_string()),
+                ]),
+            ),
+            Err(Error::Configuration(_))
+        ));
+    }
+
+    #[test]
+    fn test_default_file_is_optional() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let config = Config::load_from(
+            None,
+            &dir.path().join(DEFAULT_CONFIG_FILE),
+            env(&[("JWT_SECRET", "secret")]),
+        )
+        .unwrap();
+        assert_eq!(config.jwt_secret, "secret");
+        assert_eq!(config.port, 3000);
+
+        // Read when it exists
+        let path = write_config(dir.path(), "[server]\nport = 8080\n");
+        let config = Config::load_from(None, &path, env(&[("JWT_SECRET", "secret")])).unwrap();
+        assert_eq!(config.port, 8080);
+    }
+}
@@ -0,0 +1,16 @@
+-- API keys for integrations (e.g. Home Assistant), limited by scopes
+CREATE TABLE IF NOT EXISTS api_keys (
+    id BLOB PRIMARY KEY NOT NULL,
+    user_id BLOB NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    -- SHA-256 of the key; the key itself is only shown once on creation
+    token_hash TEXT NOT NULL UNIQUE,
+    -- First characters of the key, to recognize it in the UI
+    token_prefix TEXT NOT NULL,
+    -- Comma-separated scopes, e.g. "movies:read,movies:write"
+    scopes TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    last_used_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_api_keys_user_id ON api_keys(user_id);
@@ -0,0 +1,32 @@
+-- Full-text index over movie titles for search-as-you-type suggestions.
+-- Standalone (not external content) because movies has no stable integer rowid.
+CREATE VIRTUAL TABLE IF NOT EXISTS movies_title_fts USING fts5(
+    movie_id UNINDEXED,
+    user_id UNINDEXED,
+    title,
+    original_title,
+    personal_title,
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+INSERT INTO movies_title_fts (movie_id, user_id, title, original_title, personal_title)
+SELECT id, user_id, title, original_title, personal_title FROM movies;
+
+CREATE TRIGGER IF NOT EXISTS movies_title_fts_insert AFTER INSERT ON movies
+BEGIN
+    INSERT INTO movies_title_fts (movie_id, user_id, title, original_title, personal_title)
+    VALUES (new.id, new.user_id, new.title, new.original_title, new.personal_title);
+END;
+
+CREATE TRIGGER IF NOT EXISTS movies_title_fts_delete AFTER DELETE ON movies
+BEGIN
+    DELETE FROM movies_title_fts WHERE movie_id = old.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS movies_title_fts_update
+AFTER UPDATE OF title, original_title, personal_title ON movies
+BEGIN
+    DELETE FROM movies_title_fts WHERE movie_id = old.id;
+    INSERT INTO movies_title_fts (movie_id, user_id, title, original_title, personal_title)
+    VALUES (new.id, new.user_id, new.title, new.original_title, new.personal_title);
+END;
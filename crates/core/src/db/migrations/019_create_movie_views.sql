@@ -0,0 +1,11 @@
+-- Per-user "recently viewed" history (one row per movie, capped in MovieService)
+CREATE TABLE IF NOT EXISTS movie_views (
+    user_id BLOB NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    movie_id BLOB NOT NULL REFERENCES movies(id) ON DELETE CASCADE,
+    view_count INTEGER NOT NULL DEFAULT 1,
+    last_viewed_at TEXT NOT NULL,
+    PRIMARY KEY (user_id, movie_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_movie_views_user_last_viewed ON movie_views(user_id, last_viewed_at);
+
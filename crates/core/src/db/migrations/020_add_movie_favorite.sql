@@ -0,0 +1,4 @@
+-- Pinned/favorite movies, independent of the personal rating
+ALTER TABLE movies ADD COLUMN favorite INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_movies_user_favorite ON movies(user_id, favorite);
@@ -0,0 +1,6 @@
+-- Ownership completeness of box sets (is_collection) against the linked TMDB collection
+ALTER TABLE movies ADD COLUMN tmdb_collection_id INTEGER;
+ALTER TABLE movies ADD COLUMN collection_owned_parts INTEGER;
+ALTER TABLE movies ADD COLUMN collection_total_parts INTEGER;
+-- Percentage 0-100, NULL until computed
+ALTER TABLE movies ADD COLUMN collection_completeness REAL;
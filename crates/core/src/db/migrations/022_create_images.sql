@@ -0,0 +1,50 @@
+-- Content-addressed image storage: identical posters (e.g. box set children)
+-- are stored once and referenced by hash. ref_count is maintained by triggers.
+CREATE TABLE IF NOT EXISTS images (
+    hash TEXT PRIMARY KEY,
+    data BLOB NOT NULL,
+    size INTEGER NOT NULL,
+    ref_count INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL
+);
+
+ALTER TABLE movies ADD COLUMN poster_hash TEXT DEFAULT NULL;
+CREATE INDEX IF NOT EXISTS idx_movies_poster_hash ON movies(poster_hash);
+
+-- One image per distinct poster. SQLite has no SHA-256, so existing posters get
+-- temporary 'legacy:' keys that are replaced on startup (ImageService::rehash_legacy)
+INSERT INTO images (hash, data, size, ref_count, created_at)
+SELECT 'legacy:' || lower(hex(randomblob(16))), poster_data, length(poster_data), COUNT(*), datetime('now')
+FROM movies
+WHERE poster_data IS NOT NULL
+GROUP BY poster_data;
+
+UPDATE movies
+SET poster_hash = (
+    SELECT hash FROM images
+    WHERE images.size = length(movies.poster_data) AND images.data = movies.poster_data
+)
+WHERE poster_data IS NOT NULL;
+
+ALTER TABLE movies DROP COLUMN poster_data;
+
+CREATE TRIGGER IF NOT EXISTS movies_poster_ref_insert AFTER INSERT ON movies
+WHEN new.poster_hash IS NOT NULL
+BEGIN
+    UPDATE images SET ref_count = ref_count + 1 WHERE hash = new.poster_hash;
+END;
+
+CREATE TRIGGER IF NOT EXISTS movies_poster_ref_update AFTER UPDATE OF poster_hash ON movies
+WHEN old.poster_hash IS NOT new.poster_hash
+BEGIN
+    UPDATE images SET ref_count = ref_count + 1 WHERE hash = new.poster_hash;
+    UPDATE images SET ref_count = ref_count - 1 WHERE hash = old.poster_hash;
+    DELETE FROM images WHERE hash = old.poster_hash AND ref_count <= 0;
+END;
+
+CREATE TRIGGER IF NOT EXISTS movies_poster_ref_delete AFTER DELETE ON movies
+WHEN old.poster_hash IS NOT NULL
+BEGIN
+    UPDATE images SET ref_count = ref_count - 1 WHERE hash = old.poster_hash;
+    DELETE FROM images WHERE hash = old.poster_hash AND ref_count <= 0;
+END;
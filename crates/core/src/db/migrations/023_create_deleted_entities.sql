@@ -0,0 +1,11 @@
+-- Tombstones for deleted entities, so sync clients can drop their cached copies
+CREATE TABLE IF NOT EXISTS deleted_entities (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id BLOB NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    entity_type TEXT NOT NULL,
+    entity_id BLOB NOT NULL,
+    deleted_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_deleted_entities_user_deleted_at
+    ON deleted_entities(user_id, deleted_at);
@@ -0,0 +1,2 @@
+-- Regions of the user's players (e.g. "R2,RB"), used to report discs that won't play
+ALTER TABLE users ADD COLUMN player_regions TEXT DEFAULT NULL;
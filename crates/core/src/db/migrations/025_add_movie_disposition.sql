@@ -0,0 +1,9 @@
+-- Movies that left the collection (sold, given away, traded) stay in the
+-- database for history and value reports but are hidden from active lists
+ALTER TABLE movies ADD COLUMN disposition TEXT DEFAULT NULL;
+ALTER TABLE movies ADD COLUMN disposed_at TEXT DEFAULT NULL;
+ALTER TABLE movies ADD COLUMN disposal_price REAL DEFAULT NULL;
+ALTER TABLE movies ADD COLUMN disposal_currency TEXT DEFAULT NULL;
+ALTER TABLE movies ADD COLUMN disposed_to TEXT DEFAULT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_movies_disposition ON movies(user_id, disposition);
@@ -0,0 +1,5 @@
+-- Items that came with a disc besides the slipcover (booklet, digital code, ...),
+-- stored as a comma separated list of snake_case keys
+ALTER TABLE movies ADD COLUMN included_extras TEXT DEFAULT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_movies_condition ON movies(user_id, condition);
@@ -0,0 +1,2 @@
+-- Currency value reports are converted into (ISO 4217 code, e.g. "EUR")
+ALTER TABLE users ADD COLUMN preferred_currency TEXT DEFAULT NULL;
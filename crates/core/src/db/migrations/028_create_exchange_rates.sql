@@ -0,0 +1,7 @@
+-- Exchange rates fetched from the configured rate endpoint, as units per euro.
+-- Manual rates live in the exchange_rates setting and take precedence.
+CREATE TABLE IF NOT EXISTS exchange_rates (
+    currency TEXT PRIMARY KEY NOT NULL,
+    rate REAL NOT NULL,
+    updated_at TEXT NOT NULL
+);
@@ -0,0 +1,5 @@
+-- Values new movies get when they are created without them
+ALTER TABLE users ADD COLUMN default_disc_type TEXT DEFAULT NULL;
+ALTER TABLE users ADD COLUMN default_location TEXT DEFAULT NULL;
+ALTER TABLE users ADD COLUMN default_media_type TEXT DEFAULT NULL;
+ALTER TABLE users ADD COLUMN default_currency TEXT DEFAULT NULL;
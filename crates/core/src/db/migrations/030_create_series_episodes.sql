@@ -0,0 +1,17 @@
+-- Episodes of a series; `owned` marks episodes the user has on disc
+CREATE TABLE IF NOT EXISTS series_episodes (
+    id BLOB PRIMARY KEY NOT NULL,
+    series_id BLOB NOT NULL REFERENCES series(id) ON DELETE CASCADE,
+    season_number INTEGER NOT NULL,
+    episode_number INTEGER NOT NULL,
+    title TEXT,
+    air_date TEXT,
+    runtime INTEGER,
+    watched INTEGER NOT NULL DEFAULT 0,
+    owned INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    UNIQUE (series_id, season_number, episode_number)
+);
+
+CREATE INDEX IF NOT EXISTS idx_series_episodes_series ON series_episodes(series_id, season_number);
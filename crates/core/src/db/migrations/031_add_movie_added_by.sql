@@ -0,0 +1,8 @@
+-- Users who added and last changed a movie. Existing movies are attributed
+-- to their owner.
+ALTER TABLE movies ADD COLUMN added_by BLOB DEFAULT NULL;
+ALTER TABLE movies ADD COLUMN updated_by BLOB DEFAULT NULL;
+
+UPDATE movies SET added_by = user_id, updated_by = user_id WHERE added_by IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_movies_added_by ON movies(user_id, added_by);
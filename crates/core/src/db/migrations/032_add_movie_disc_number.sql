@@ -0,0 +1,2 @@
+-- Disc of the parent box set a film is on (set when splitting collections)
+ALTER TABLE movies ADD COLUMN disc_number INTEGER DEFAULT NULL;
@@ -0,0 +1,28 @@
+-- Series created from a box set are linked to it like movies, and get posters
+-- from the shared image store
+ALTER TABLE series ADD COLUMN parent_collection_id BLOB DEFAULT NULL;
+ALTER TABLE series ADD COLUMN poster_hash TEXT DEFAULT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_series_parent_collection ON series(parent_collection_id);
+CREATE INDEX IF NOT EXISTS idx_series_poster_hash ON series(poster_hash);
+
+CREATE TRIGGER IF NOT EXISTS series_poster_ref_insert AFTER INSERT ON series
+WHEN new.poster_hash IS NOT NULL
+BEGIN
+    UPDATE images SET ref_count = ref_count + 1 WHERE hash = new.poster_hash;
+END;
+
+CREATE TRIGGER IF NOT EXISTS series_poster_ref_update AFTER UPDATE OF poster_hash ON series
+WHEN old.poster_hash IS NOT new.poster_hash
+BEGIN
+    UPDATE images SET ref_count = ref_count + 1 WHERE hash = new.poster_hash;
+    UPDATE images SET ref_count = ref_count - 1 WHERE hash = old.poster_hash;
+    DELETE FROM images WHERE hash = old.poster_hash AND ref_count <= 0;
+END;
+
+CREATE TRIGGER IF NOT EXISTS series_poster_ref_delete AFTER DELETE ON series
+WHEN old.poster_hash IS NOT NULL
+BEGIN
+    UPDATE images SET ref_count = ref_count - 1 WHERE hash = old.poster_hash;
+    DELETE FROM images WHERE hash = old.poster_hash AND ref_count <= 0;
+END;
@@ -0,0 +1,45 @@
+-- Keep updated_at when a statement sets it explicitly (sync copies the remote
+-- timestamp); only bump it when an update leaves it unchanged
+DROP TRIGGER IF EXISTS movies_updated_at;
+CREATE TRIGGER IF NOT EXISTS movies_updated_at
+    AFTER UPDATE ON movies
+    FOR EACH ROW
+    WHEN NEW.updated_at IS OLD.updated_at
+BEGIN
+    UPDATE movies SET updated_at = datetime('now') WHERE id = NEW.id;
+END;
+
+DROP TRIGGER IF EXISTS series_updated_at;
+CREATE TRIGGER IF NOT EXISTS series_updated_at
+    AFTER UPDATE ON series
+    FOR EACH ROW
+    WHEN NEW.updated_at IS OLD.updated_at
+BEGIN
+    UPDATE series SET updated_at = datetime('now') WHERE id = NEW.id;
+END;
+
+DROP TRIGGER IF EXISTS collections_updated_at;
+CREATE TRIGGER IF NOT EXISTS collections_updated_at
+    AFTER UPDATE ON collections
+    FOR EACH ROW
+    WHEN NEW.updated_at IS OLD.updated_at
+BEGIN
+    UPDATE collections SET updated_at = datetime('now') WHERE id = NEW.id;
+END;
+
+-- Other my-movies servers a user syncs their library with
+CREATE TABLE IF NOT EXISTS sync_peers (
+    id BLOB PRIMARY KEY NOT NULL,
+    user_id BLOB NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    url TEXT NOT NULL,
+    -- API key issued by the peer (pairing token)
+    token TEXT NOT NULL,
+    last_synced_at TEXT,
+    -- JSON summary of the last run, including conflicts
+    last_result TEXT,
+    last_error TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_sync_peers_user ON sync_peers(user_id);
@@ -0,0 +1,40 @@
+-- Where an image's bytes live: 'db' (images.data) or an external blob store
+-- ('fs', 's3'); external images keep an empty data column
+ALTER TABLE images ADD COLUMN storage TEXT NOT NULL DEFAULT 'db';
+
+-- Bytes of deleted external images, removed from the store by maintenance
+CREATE TABLE IF NOT EXISTS image_deletions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    hash TEXT NOT NULL,
+    storage TEXT NOT NULL
+);
+
+CREATE TRIGGER IF NOT EXISTS images_external_delete AFTER DELETE ON images
+WHEN old.storage != 'db'
+BEGIN
+    INSERT INTO image_deletions (hash, storage) VALUES (old.hash, old.storage);
+END;
+
+-- Avatars move to the image store; avatar_data is only read for older uploads
+ALTER TABLE users ADD COLUMN avatar_hash TEXT DEFAULT NULL;
+
+CREATE TRIGGER IF NOT EXISTS users_avatar_ref_insert AFTER INSERT ON users
+WHEN new.avatar_hash IS NOT NULL
+BEGIN
+    UPDATE images SET ref_count = ref_count + 1 WHERE hash = new.avatar_hash;
+END;
+
+CREATE TRIGGER IF NOT EXISTS users_avatar_ref_update AFTER UPDATE OF avatar_hash ON users
+WHEN old.avatar_hash IS NOT new.avatar_hash
+BEGIN
+    UPDATE images SET ref_count = ref_count + 1 WHERE hash = new.avatar_hash;
+    UPDATE images SET ref_count = ref_count - 1 WHERE hash = old.avatar_hash;
+    DELETE FROM images WHERE hash = old.avatar_hash AND ref_count <= 0;
+END;
+
+CREATE TRIGGER IF NOT EXISTS users_avatar_ref_delete AFTER DELETE ON users
+WHEN old.avatar_hash IS NOT NULL
+BEGIN
+    UPDATE images SET ref_count = ref_count - 1 WHERE hash = old.avatar_hash;
+    DELETE FROM images WHERE hash = old.avatar_hash AND ref_count <= 0;
+END;
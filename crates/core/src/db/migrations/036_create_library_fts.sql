@@ -0,0 +1,71 @@
+-- Full-text index over movies and series for /search: titles, descriptions,
+-- actors, notes and genres. Standalone like movies_title_fts.
+CREATE VIRTUAL TABLE IF NOT EXISTS library_fts USING fts5(
+    entity_type UNINDEXED,
+    entity_id UNINDEXED,
+    user_id UNINDEXED,
+    title,
+    original_title,
+    personal_title,
+    description,
+    actors,
+    notes,
+    genres,
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+INSERT INTO library_fts (entity_type, entity_id, user_id, title, original_title, personal_title,
+                         description, actors, notes, genres)
+SELECT 'movie', id, user_id, title, original_title, personal_title, description, actors, notes, genres
+FROM movies;
+
+INSERT INTO library_fts (entity_type, entity_id, user_id, title, original_title, personal_title,
+                         description, actors, notes, genres)
+SELECT 'series', id, user_id, title, original_title, personal_title, description, actors, notes, genres
+FROM series;
+
+CREATE TRIGGER IF NOT EXISTS movies_library_fts_insert AFTER INSERT ON movies
+BEGIN
+    INSERT INTO library_fts (entity_type, entity_id, user_id, title, original_title, personal_title,
+                             description, actors, notes, genres)
+    VALUES ('movie', new.id, new.user_id, new.title, new.original_title, new.personal_title,
+            new.description, new.actors, new.notes, new.genres);
+END;
+
+CREATE TRIGGER IF NOT EXISTS movies_library_fts_delete AFTER DELETE ON movies
+BEGIN
+    DELETE FROM library_fts WHERE entity_type = 'movie' AND entity_id = old.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS movies_library_fts_update
+AFTER UPDATE OF title, original_title, personal_title, description, actors, notes, genres ON movies
+BEGIN
+    DELETE FROM library_fts WHERE entity_type = 'movie' AND entity_id = old.id;
+    INSERT INTO library_fts (entity_type, entity_id, user_id, title, original_title, personal_title,
+                             description, actors, notes, genres)
+    VALUES ('movie', new.id, new.user_id, new.title, new.original_title, new.personal_title,
+            new.description, new.actors, new.notes, new.genres);
+END;
+
+CREATE TRIGGER IF NOT EXISTS series_library_fts_insert AFTER INSERT ON series
+BEGIN
+    INSERT INTO library_fts (entity_type, entity_id, user_id, title, original_title, personal_title,
+                             description, actors, notes, genres)
+    VALUES ('series', new.id, new.user_id, new.title, new.original_title, new.personal_title,
+            new.description, new.actors, new.notes, new.genres);
+END;
+
+CREATE TRIGGER IF NOT EXISTS series_library_fts_delete AFTER DELETE ON series
+BEGIN
+    DELETE FROM library_fts WHERE entity_type = 'series' AND entity_id = old.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS series_library_fts_update
+AFTER UPDATE OF title, original_title, personal_title, description, actors, notes, genres ON series
+BEGIN
+    DELETE FROM library_fts WHERE entity_type = 'series' AND entity_id = old.id;
+    INSERT INTO library_fts (entity_type, entity_id, user_id, title, original_title, personal_title,
+                             description, actors, notes, genres)
+    VALUES ('series', new.id, new.user_id, new.title, new.original_title, new.personal_title,
+            new.description, new.actors, new.notes, new.genres);
+END;
@@ -0,0 +1,13 @@
+-- Per-user loan reminder preferences: remind `loan_reminder_days` days before
+-- a loan is due and again once it is overdue
+ALTER TABLE users ADD COLUMN loan_reminders BOOLEAN NOT NULL DEFAULT 1;
+ALTER TABLE users ADD COLUMN loan_reminder_days INTEGER NOT NULL DEFAULT 3;
+
+-- Reminders already sent, so each loan gets one of each kind
+CREATE TABLE IF NOT EXISTS loan_reminders (
+    movie_id BLOB NOT NULL REFERENCES movies(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL,
+    lent_due DATE NOT NULL,
+    sent_at TEXT NOT NULL,
+    PRIMARY KEY (movie_id, kind, lent_due)
+);
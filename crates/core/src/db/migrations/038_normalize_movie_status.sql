@@ -0,0 +1,12 @@
+-- movies.status becomes a workflow: owned, wishlist, ordered or sold.
+-- NULL keeps meaning owned; free-text values from older imports are mapped.
+UPDATE movies SET status = CASE
+    WHEN disposition = 'sold' THEN 'sold'
+    WHEN replace(lower(trim(status)), ' ', '') IN ('wishlist', 'wish', 'wunschliste') THEN 'wishlist'
+    WHEN replace(lower(trim(status)), ' ', '') IN ('ordered', 'onorder', 'bestellt') THEN 'ordered'
+    WHEN lower(trim(status)) IN ('sold', 'verkauft') THEN 'sold'
+    ELSE 'owned'
+END
+WHERE status IS NOT NULL OR disposition = 'sold';
+
+CREATE INDEX IF NOT EXISTS idx_movies_user_status ON movies(user_id, status);
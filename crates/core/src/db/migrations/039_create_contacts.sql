@@ -0,0 +1,66 @@
+-- People movies are lent to
+CREATE TABLE IF NOT EXISTS contacts (
+    id BLOB PRIMARY KEY NOT NULL,
+    user_id BLOB NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    email TEXT,
+    phone TEXT,
+    notes TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_contacts_user_name ON contacts(user_id, name COLLATE NOCASE);
+
+-- Loan history. Rows are written by the triggers below whenever movies.lent_to
+-- changes; a loan belongs to the contact whose name matches lent_to.
+CREATE TABLE IF NOT EXISTS loans (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id BLOB NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    movie_id BLOB NOT NULL REFERENCES movies(id) ON DELETE CASCADE,
+    contact_id BLOB REFERENCES contacts(id) ON DELETE SET NULL,
+    lent_to TEXT NOT NULL,
+    lent_at TEXT NOT NULL,
+    due DATE,
+    returned_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_loans_contact ON loans(contact_id, returned_at);
+CREATE INDEX IF NOT EXISTS idx_loans_movie ON loans(movie_id, returned_at);
+
+INSERT INTO loans (user_id, movie_id, contact_id, lent_to, lent_at, due)
+SELECT user_id, id, NULL, trim(lent_to), updated_at, lent_due
+FROM movies
+WHERE lent_to IS NOT NULL AND trim(lent_to) != '';
+
+CREATE TRIGGER IF NOT EXISTS movies_loan_insert AFTER INSERT ON movies
+WHEN NEW.lent_to IS NOT NULL AND trim(NEW.lent_to) != ''
+BEGIN
+    INSERT INTO loans (user_id, movie_id, contact_id, lent_to, lent_at, due)
+    VALUES (
+        NEW.user_id, NEW.id,
+        (SELECT id FROM contacts WHERE user_id = NEW.user_id AND name = trim(NEW.lent_to) COLLATE NOCASE),
+        trim(NEW.lent_to), strftime('%Y-%m-%dT%H:%M:%SZ', 'now'), NEW.lent_due
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS movies_loan_update AFTER UPDATE OF lent_to, lent_due ON movies
+WHEN COALESCE(trim(OLD.lent_to), '') != COALESCE(trim(NEW.lent_to), '')
+    OR OLD.lent_due IS NOT NEW.lent_due
+BEGIN
+    -- Returned, or lent to someone else. Renaming a contact renames its loans
+    -- first, so those stay open.
+    UPDATE loans SET returned_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+    WHERE movie_id = NEW.id AND returned_at IS NULL
+      AND lent_to != COALESCE(trim(NEW.lent_to), '') COLLATE NOCASE;
+
+    UPDATE loans SET due = NEW.lent_due WHERE movie_id = NEW.id AND returned_at IS NULL;
+
+    INSERT INTO loans (user_id, movie_id, contact_id, lent_to, lent_at, due)
+    SELECT
+        NEW.user_id, NEW.id,
+        (SELECT id FROM contacts WHERE user_id = NEW.user_id AND name = trim(NEW.lent_to) COLLATE NOCASE),
+        trim(NEW.lent_to), strftime('%Y-%m-%dT%H:%M:%SZ', 'now'), NEW.lent_due
+    WHERE COALESCE(trim(NEW.lent_to), '') != ''
+      AND NOT EXISTS (SELECT 1 FROM loans WHERE movie_id = NEW.id AND returned_at IS NULL);
+END;
@@ -0,0 +1,19 @@
+-- Guests asking to borrow a movie; the owner approves (the movie is lent to
+-- the requester) or declines
+CREATE TABLE IF NOT EXISTS borrow_requests (
+    id BLOB PRIMARY KEY NOT NULL,
+    user_id BLOB NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    movie_id BLOB NOT NULL REFERENCES movies(id) ON DELETE CASCADE,
+    requester_name TEXT NOT NULL,
+    requester_contact TEXT,
+    message TEXT,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'approved', 'declined')),
+    created_at TEXT NOT NULL,
+    decided_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_borrow_requests_user ON borrow_requests(user_id, status);
+
+-- One open request per person and movie
+CREATE UNIQUE INDEX IF NOT EXISTS idx_borrow_requests_pending
+    ON borrow_requests(movie_id, requester_name COLLATE NOCASE) WHERE status = 'pending';
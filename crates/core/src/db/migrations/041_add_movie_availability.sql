@@ -0,0 +1,17 @@
+-- Set by hand when a disc can't be found
+ALTER TABLE movies ADD COLUMN missing BOOLEAN NOT NULL DEFAULT 0;
+
+-- Where a disc is right now, derived from dispositions, the wishlist status,
+-- `missing` and loans. NULL for wishlist entries.
+ALTER TABLE movies ADD COLUMN availability TEXT GENERATED ALWAYS AS (
+    CASE
+        WHEN disposition IS NOT NULL OR status = 'sold' THEN 'sold'
+        WHEN status = 'ordered' THEN 'ordered'
+        WHEN status = 'wishlist' THEN NULL
+        WHEN missing THEN 'missing'
+        WHEN lent_to IS NOT NULL AND trim(lent_to) != '' THEN 'lent'
+        ELSE 'available'
+    END
+) VIRTUAL;
+
+CREATE INDEX IF NOT EXISTS idx_movies_user_availability ON movies(user_id, availability);
@@ -0,0 +1,20 @@
+-- Login sessions. The client holds an opaque refresh token (only its hash is
+-- stored) and exchanges it for short-lived access tokens; every refresh
+-- rotates it. Access tokens carry the session id, so revoking a session
+-- locks out its access tokens immediately.
+CREATE TABLE IF NOT EXISTS sessions (
+    id BLOB PRIMARY KEY NOT NULL,
+    user_id BLOB NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    refresh_token_hash TEXT NOT NULL UNIQUE,
+    -- Hash of the token replaced by the last rotation; presenting it again
+    -- means the token was copied, and the session is revoked
+    previous_token_hash TEXT,
+    device TEXT,
+    created_at TEXT NOT NULL,
+    last_used_at TEXT NOT NULL,
+    expires_at TEXT NOT NULL,
+    revoked_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_sessions_user ON sessions(user_id);
+CREATE INDEX IF NOT EXISTS idx_sessions_previous ON sessions(previous_token_hash);
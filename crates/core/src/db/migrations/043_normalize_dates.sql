@@ -0,0 +1,147 @@
+-- Timezone for dates shown to the user and for the scheduler, as a UTC offset
+-- such as "+01:00"; NULL means UTC
+ALTER TABLE users ADD COLUMN timezone TEXT;
+
+-- Timestamps are RFC3339 in UTC. Column defaults and the updated_at triggers
+-- wrote SQLite's "YYYY-MM-DD HH:MM:SS", which sorts before RFC3339 values of
+-- the same day.
+DROP TRIGGER IF EXISTS movies_updated_at;
+CREATE TRIGGER IF NOT EXISTS movies_updated_at
+    AFTER UPDATE ON movies
+    FOR EACH ROW
+    WHEN NEW.updated_at IS OLD.updated_at
+BEGIN
+    UPDATE movies SET updated_at = strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now') WHERE id = NEW.id;
+END;
+
+DROP TRIGGER IF EXISTS series_updated_at;
+CREATE TRIGGER IF NOT EXISTS series_updated_at
+    AFTER UPDATE ON series
+    FOR EACH ROW
+    WHEN NEW.updated_at IS OLD.updated_at
+BEGIN
+    UPDATE series SET updated_at = strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now') WHERE id = NEW.id;
+END;
+
+DROP TRIGGER IF EXISTS collections_updated_at;
+CREATE TRIGGER IF NOT EXISTS collections_updated_at
+    AFTER UPDATE ON collections
+    FOR EACH ROW
+    WHEN NEW.updated_at IS OLD.updated_at
+BEGIN
+    UPDATE collections SET updated_at = strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now') WHERE id = NEW.id;
+END;
+
+UPDATE users SET created_at = replace(created_at, ' ', 'T') || '+00:00'
+WHERE created_at GLOB '[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9] [0-9][0-9]:[0-9][0-9]:[0-9][0-9]*';
+UPDATE users SET updated_at = replace(updated_at, ' ', 'T') || '+00:00'
+WHERE updated_at GLOB '[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9] [0-9][0-9]:[0-9][0-9]:[0-9][0-9]*';
+
+UPDATE movies SET created_at = replace(created_at, ' ', 'T') || '+00:00'
+WHERE created_at GLOB '[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9] [0-9][0-9]:[0-9][0-9]:[0-9][0-9]*';
+UPDATE movies SET updated_at = replace(updated_at, ' ', 'T') || '+00:00'
+WHERE updated_at GLOB '[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9] [0-9][0-9]:[0-9][0-9]:[0-9][0-9]*';
+
+UPDATE series SET created_at = replace(created_at, ' ', 'T') || '+00:00'
+WHERE created_at GLOB '[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9] [0-9][0-9]:[0-9][0-9]:[0-9][0-9]*';
+UPDATE series SET updated_at = replace(updated_at, ' ', 'T') || '+00:00'
+WHERE updated_at GLOB '[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9] [0-9][0-9]:[0-9][0-9]:[0-9][0-9]*';
+
+UPDATE collections SET created_at = replace(created_at, ' ', 'T') || '+00:00'
+WHERE created_at GLOB '[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9] [0-9][0-9]:[0-9][0-9]:[0-9][0-9]*';
+UPDATE collections SET updated_at = replace(updated_at, ' ', 'T') || '+00:00'
+WHERE updated_at GLOB '[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9] [0-9][0-9]:[0-9][0-9]:[0-9][0-9]*';
+
+UPDATE series_episodes SET created_at = replace(created_at, ' ', 'T') || '+00:00'
+WHERE created_at GLOB '[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9] [0-9][0-9]:[0-9][0-9]:[0-9][0-9]*';
+UPDATE series_episodes SET updated_at = replace(updated_at, ' ', 'T') || '+00:00'
+WHERE updated_at GLOB '[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9] [0-9][0-9]:[0-9][0-9]:[0-9][0-9]*';
+
+UPDATE contacts SET created_at = replace(created_at, ' ', 'T') || '+00:00'
+WHERE created_at GLOB '[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9] [0-9][0-9]:[0-9][0-9]:[0-9][0-9]*';
+UPDATE contacts SET updated_at = replace(updated_at, ' ', 'T') || '+00:00'
+WHERE updated_at GLOB '[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9] [0-9][0-9]:[0-9][0-9]:[0-9][0-9]*';
+
+-- Dates entered by hand or imported are `YYYY-MM-DD`. Timestamps are cut to
+-- their date, "DD.MM.YYYY" and "YYYY/MM/DD" are reformatted; anything else is
+-- left for `POST /admin/repair`.
+UPDATE movies SET purchase_date = CASE
+    WHEN purchase_date GLOB '[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9][ T]*' THEN substr(purchase_date, 1, 10)
+    WHEN purchase_date GLOB '[0-9][0-9].[0-9][0-9].[0-9][0-9][0-9][0-9]'
+        THEN substr(purchase_date, 7, 4) || '-' || substr(purchase_date, 4, 2) || '-' || substr(purchase_date, 1, 2)
+    WHEN purchase_date GLOB '[0-9][0-9][0-9][0-9]/[0-9][0-9]/[0-9][0-9]' THEN replace(purchase_date, '/', '-')
+    ELSE purchase_date
+END
+WHERE purchase_date NOT GLOB '[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9]';
+
+UPDATE movies SET value_date = CASE
+    WHEN value_date GLOB '[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9][ T]*' THEN substr(value_date, 1, 10)
+    WHEN value_date GLOB '[0-9][0-9].[0-9][0-9].[0-9][0-9][0-9][0-9]'
+        THEN substr(value_date, 7, 4) || '-' || substr(value_date, 4, 2) || '-' || substr(value_date, 1, 2)
+    WHEN value_date GLOB '[0-9][0-9][0-9][0-9]/[0-9][0-9]/[0-9][0-9]' THEN replace(value_date, '/', '-')
+    ELSE value_date
+END
+WHERE value_date NOT GLOB '[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9]';
+
+UPDATE movies SET lent_due = CASE
+    WHEN lent_due GLOB '[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9][ T]*' THEN substr(lent_due, 1, 10)
+    WHEN lent_due GLOB '[0-9][0-9].[0-9][0-9].[0-9][0-9][0-9][0-9]'
+        THEN substr(lent_due, 7, 4) || '-' || substr(lent_due, 4, 2) || '-' || substr(lent_due, 1, 2)
+    WHEN lent_due GLOB '[0-9][0-9][0-9][0-9]/[0-9][0-9]/[0-9][0-9]' THEN replace(lent_due, '/', '-')
+    ELSE lent_due
+END
+WHERE lent_due NOT GLOB '[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9]';
+
+UPDATE series SET purchase_date = CASE
+    WHEN purchase_date GLOB '[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9][ T]*' THEN substr(purchase_date, 1, 10)
+    WHEN purchase_date GLOB '[0-9][0-9].[0-9][0-9].[0-9][0-9][0-9][0-9]'
+        THEN substr(purchase_date, 7, 4) || '-' || substr(purchase_date, 4, 2) || '-' || substr(purchase_date, 1, 2)
+    WHEN purchase_date GLOB '[0-9][0-9][0-9][0-9]/[0-9][0-9]/[0-9][0-9]' THEN replace(purchase_date, '/', '-')
+    ELSE purchase_date
+END
+WHERE purchase_date NOT GLOB '[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9]';
+
+UPDATE series SET value_date = CASE
+    WHEN value_date GLOB '[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9][ T]*' THEN substr(value_date, 1, 10)
+    WHEN value_date GLOB '[0-9][0-9].[0-9][0-9].[0-9][0-9][0-9][0-9]'
+        THEN substr(value_date, 7, 4) || '-' || substr(value_date, 4, 2) || '-' || substr(value_date, 1, 2)
+    WHEN value_date GLOB '[0-9][0-9][0-9][0-9]/[0-9][0-9]/[0-9][0-9]' THEN replace(value_date, '/', '-')
+    ELSE value_date
+END
+WHERE value_date NOT GLOB '[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9]';
+
+UPDATE series SET lent_due = CASE
+    WHEN lent_due GLOB '[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9][ T]*' THEN substr(lent_due, 1, 10)
+    WHEN lent_due GLOB '[0-9][0-9].[0-9][0-9].[0-9][0-9][0-9][0-9]'
+        THEN substr(lent_due, 7, 4) || '-' || substr(lent_due, 4, 2) || '-' || substr(lent_due, 1, 2)
+    WHEN lent_due GLOB '[0-9][0-9][0-9][0-9]/[0-9][0-9]/[0-9][0-9]' THEN replace(lent_due, '/', '-')
+    ELSE lent_due
+END
+WHERE lent_due NOT GLOB '[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9]';
+
+UPDATE collections SET purchase_date = CASE
+    WHEN purchase_date GLOB '[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9][ T]*' THEN substr(purchase_date, 1, 10)
+    WHEN purchase_date GLOB '[0-9][0-9].[0-9][0-9].[0-9][0-9][0-9][0-9]'
+        THEN substr(purchase_date, 7, 4) || '-' || substr(purchase_date, 4, 2) || '-' || substr(purchase_date, 1, 2)
+    WHEN purchase_date GLOB '[0-9][0-9][0-9][0-9]/[0-9][0-9]/[0-9][0-9]' THEN replace(purchase_date, '/', '-')
+    ELSE purchase_date
+END
+WHERE purchase_date NOT GLOB '[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9]';
+
+UPDATE collections SET value_date = CASE
+    WHEN value_date GLOB '[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9][ T]*' THEN substr(value_date, 1, 10)
+    WHEN value_date GLOB '[0-9][0-9].[0-9][0-9].[0-9][0-9][0-9][0-9]'
+        THEN substr(value_date, 7, 4) || '-' || substr(value_date, 4, 2) || '-' || substr(value_date, 1, 2)
+    WHEN value_date GLOB '[0-9][0-9][0-9][0-9]/[0-9][0-9]/[0-9][0-9]' THEN replace(value_date, '/', '-')
+    ELSE value_date
+END
+WHERE value_date NOT GLOB '[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9]';
+
+UPDATE collections SET lent_due = CASE
+    WHEN lent_due GLOB '[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9][ T]*' THEN substr(lent_due, 1, 10)
+    WHEN lent_due GLOB '[0-9][0-9].[0-9][0-9].[0-9][0-9][0-9][0-9]'
+        THEN substr(lent_due, 7, 4) || '-' || substr(lent_due, 4, 2) || '-' || substr(lent_due, 1, 2)
+    WHEN lent_due GLOB '[0-9][0-9][0-9][0-9]/[0-9][0-9]/[0-9][0-9]' THEN replace(lent_due, '/', '-')
+    ELSE lent_due
+END
+WHERE lent_due NOT GLOB '[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9]';
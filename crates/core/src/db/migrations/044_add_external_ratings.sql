@@ -0,0 +1,3 @@
+-- Ratings from OMDb: IMDb user score (0 - 10) and Rotten Tomatoes Tomatometer (percent)
+ALTER TABLE movies ADD COLUMN imdb_rating REAL;
+ALTER TABLE movies ADD COLUMN rotten_tomatoes INTEGER;
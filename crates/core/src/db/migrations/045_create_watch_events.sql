@@ -0,0 +1,18 @@
+-- Every viewing of a movie. `watched_on` is NULL when the date is unknown,
+-- e.g. for movies marked as watched before the history existed.
+CREATE TABLE IF NOT EXISTS watch_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id BLOB NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    movie_id BLOB NOT NULL REFERENCES movies(id) ON DELETE CASCADE,
+    watched_on DATE,
+    notes TEXT,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_watch_events_movie ON watch_events(movie_id, watched_on);
+CREATE INDEX IF NOT EXISTS idx_watch_events_user ON watch_events(user_id, watched_on);
+
+INSERT INTO watch_events (user_id, movie_id, watched_on, notes, created_at)
+SELECT user_id, id, NULL, NULL, updated_at
+FROM movies
+WHERE watched = 1;
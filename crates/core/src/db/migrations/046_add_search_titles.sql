@@ -0,0 +1,162 @@
+-- Folded titles ("König" -> "koenig", "Amélie" -> "amelie") for search and
+-- duplicate detection, written by the server (`parsing::title::fold_title`).
+-- NULL until computed; changing a title resets it.
+ALTER TABLE movies ADD COLUMN search_title TEXT;
+ALTER TABLE series ADD COLUMN search_title TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_movies_user_search_title ON movies(user_id, search_title);
+CREATE INDEX IF NOT EXISTS idx_series_user_search_title ON series(user_id, search_title);
+
+CREATE TRIGGER IF NOT EXISTS movies_search_title_reset
+AFTER UPDATE OF title ON movies
+WHEN NEW.title IS NOT OLD.title AND NEW.search_title IS OLD.search_title
+BEGIN
+    UPDATE movies SET search_title = NULL WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS series_search_title_reset
+AFTER UPDATE OF title ON series
+WHEN NEW.title IS NOT OLD.title AND NEW.search_title IS OLD.search_title
+BEGIN
+    UPDATE series SET search_title = NULL WHERE id = NEW.id;
+END;
+
+-- Filling in search_title is not a change of the entry
+DROP TRIGGER IF EXISTS movies_updated_at;
+CREATE TRIGGER IF NOT EXISTS movies_updated_at
+    AFTER UPDATE ON movies
+    FOR EACH ROW
+    WHEN NEW.updated_at IS OLD.updated_at AND NEW.search_title IS OLD.search_title
+BEGIN
+    UPDATE movies SET updated_at = strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now') WHERE id = NEW.id;
+END;
+
+DROP TRIGGER IF EXISTS series_updated_at;
+CREATE TRIGGER IF NOT EXISTS series_updated_at
+    AFTER UPDATE ON series
+    FOR EACH ROW
+    WHEN NEW.updated_at IS OLD.updated_at AND NEW.search_title IS OLD.search_title
+BEGIN
+    UPDATE series SET updated_at = strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now') WHERE id = NEW.id;
+END;
+
+-- Both full-text indexes get the folded title as an extra column
+DROP TRIGGER IF EXISTS movies_title_fts_insert;
+DROP TRIGGER IF EXISTS movies_title_fts_delete;
+DROP TRIGGER IF EXISTS movies_title_fts_update;
+DROP TABLE IF EXISTS movies_title_fts;
+
+CREATE VIRTUAL TABLE IF NOT EXISTS movies_title_fts USING fts5(
+    movie_id UNINDEXED,
+    user_id UNINDEXED,
+    title,
+    original_title,
+    personal_title,
+    search_title,
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+INSERT INTO movies_title_fts (movie_id, user_id, title, original_title, personal_title, search_title)
+SELECT id, user_id, title, original_title, personal_title, search_title FROM movies;
+
+CREATE TRIGGER IF NOT EXISTS movies_title_fts_insert AFTER INSERT ON movies
+BEGIN
+    INSERT INTO movies_title_fts (movie_id, user_id, title, original_title, personal_title, search_title)
+    VALUES (new.id, new.user_id, new.title, new.original_title, new.personal_title, new.search_title);
+END;
+
+CREATE TRIGGER IF NOT EXISTS movies_title_fts_delete AFTER DELETE ON movies
+BEGIN
+    DELETE FROM movies_title_fts WHERE movie_id = old.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS movies_title_fts_update
+AFTER UPDATE OF title, original_title, personal_title, search_title ON movies
+BEGIN
+    DELETE FROM movies_title_fts WHERE movie_id = old.id;
+    INSERT INTO movies_title_fts (movie_id, user_id, title, original_title, personal_title, search_title)
+    VALUES (new.id, new.user_id, new.title, new.original_title, new.personal_title, new.search_title);
+END;
+
+DROP TRIGGER IF EXISTS movies_library_fts_insert;
+DROP TRIGGER IF EXISTS movies_library_fts_delete;
+DROP TRIGGER IF EXISTS movies_library_fts_update;
+DROP TRIGGER IF EXISTS series_library_fts_insert;
+DROP TRIGGER IF EXISTS series_library_fts_delete;
+DROP TRIGGER IF EXISTS series_library_fts_update;
+DROP TABLE IF EXISTS library_fts;
+
+CREATE VIRTUAL TABLE IF NOT EXISTS library_fts USING fts5(
+    entity_type UNINDEXED,
+    entity_id UNINDEXED,
+    user_id UNINDEXED,
+    title,
+    original_title,
+    personal_title,
+    description,
+    actors,
+    notes,
+    genres,
+    search_title,
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+INSERT INTO library_fts (entity_type, entity_id, user_id, title, original_title, personal_title,
+                         description, actors, notes, genres, search_title)
+SELECT 'movie', id, user_id, title, original_title, personal_title, description, actors, notes,
+       genres, search_title
+FROM movies;
+
+INSERT INTO library_fts (entity_type, entity_id, user_id, title, original_title, personal_title,
+                         description, actors, notes, genres, search_title)
+SELECT 'series', id, user_id, title, original_title, personal_title, description, actors, notes,
+       genres, search_title
+FROM series;
+
+CREATE TRIGGER IF NOT EXISTS movies_library_fts_insert AFTER INSERT ON movies
+BEGIN
+    INSERT INTO library_fts (entity_type, entity_id, user_id, title, original_title, personal_title,
+                             description, actors, notes, genres, search_title)
+    VALUES ('movie', new.id, new.user_id, new.title, new.original_title, new.personal_title,
+            new.description, new.actors, new.notes, new.genres, new.search_title);
+END;
+
+CREATE TRIGGER IF NOT EXISTS movies_library_fts_delete AFTER DELETE ON movies
+BEGIN
+    DELETE FROM library_fts WHERE entity_type = 'movie' AND entity_id = old.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS movies_library_fts_update
+AFTER UPDATE OF title, original_title, personal_title, description, actors, notes, genres,
+    search_title ON movies
+BEGIN
+    DELETE FROM library_fts WHERE entity_type = 'movie' AND entity_id = old.id;
+    INSERT INTO library_fts (entity_type, entity_id, user_id, title, original_title, personal_title,
+                             description, actors, notes, genres, search_title)
+    VALUES ('movie', new.id, new.user_id, new.title, new.original_title, new.personal_title,
+            new.description, new.actors, new.notes, new.genres, new.search_title);
+END;
+
+CREATE TRIGGER IF NOT EXISTS series_library_fts_insert AFTER INSERT ON series
+BEGIN
+    INSERT INTO library_fts (entity_type, entity_id, user_id, title, original_title, personal_title,
+                             description, actors, notes, genres, search_title)
+    VALUES ('series', new.id, new.user_id, new.title, new.original_title, new.personal_title,
+            new.description, new.actors, new.notes, new.genres, new.search_title);
+END;
+
+CREATE TRIGGER IF NOT EXISTS series_library_fts_delete AFTER DELETE ON series
+BEGIN
+    DELETE FROM library_fts WHERE entity_type = 'series' AND entity_id = old.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS series_library_fts_update
+AFTER UPDATE OF title, original_title, personal_title, description, actors, notes, genres,
+    search_title ON series
+BEGIN
+    DELETE FROM library_fts WHERE entity_type = 'series' AND entity_id = old.id;
+    INSERT INTO library_fts (entity_type, entity_id, user_id, title, original_title, personal_title,
+                             description, actors, notes, genres, search_title)
+    VALUES ('series', new.id, new.user_id, new.title, new.original_title, new.personal_title,
+            new.description, new.actors, new.notes, new.genres, new.search_title);
+END;
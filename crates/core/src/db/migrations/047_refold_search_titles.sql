@@ -0,0 +1,4 @@
+-- Folded titles now write numbers as digits ("Rocky II" -> "rocky 2");
+-- recompute them at the next start
+UPDATE movies SET search_title = NULL;
+UPDATE series SET search_title = NULL;
@@ -0,0 +1,12 @@
+-- Read-only links to a user's library or one of their collections. The
+-- token is the id signed by the server, so only the id is stored.
+CREATE TABLE IF NOT EXISTS share_links (
+    id BLOB PRIMARY KEY NOT NULL,
+    user_id BLOB NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    collection_id BLOB REFERENCES collections(id) ON DELETE CASCADE,
+    name TEXT,
+    created_at TEXT NOT NULL,
+    expires_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_share_links_user ON share_links(user_id);
@@ -0,0 +1,19 @@
+-- Barcode lookups, so repeated scans don't hit the product databases.
+-- Rows without title remember barcodes nobody knows (shorter expiry).
+CREATE TABLE IF NOT EXISTS barcode_cache (
+    barcode TEXT PRIMARY KEY NOT NULL,
+    title TEXT,
+    vendor TEXT,
+    category TEXT,
+    source TEXT,
+    disc_type TEXT,
+    tmdb_id INTEGER,
+    -- JSON array of the TMDB candidates shown for the scan
+    tmdb_results TEXT,
+    hits INTEGER NOT NULL DEFAULT 0,
+    misses INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL,
+    expires_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_barcode_cache_expires ON barcode_cache(expires_at);
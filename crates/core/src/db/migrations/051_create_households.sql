@@ -0,0 +1,15 @@
+-- Households: users who see each other's movies. A user belongs to at
+-- most one household; movies stay owned (and editable) by one user.
+CREATE TABLE IF NOT EXISTS households (
+    id BLOB PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL,
+    owner_id BLOB NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS household_members (
+    household_id BLOB NOT NULL REFERENCES households(id) ON DELETE CASCADE,
+    user_id BLOB NOT NULL UNIQUE REFERENCES users(id) ON DELETE CASCADE,
+    joined_at TEXT NOT NULL,
+    PRIMARY KEY (household_id, user_id)
+);
@@ -0,0 +1,24 @@
+-- Wide backdrop (fanart) next to the poster, stored in the images table as well
+ALTER TABLE movies ADD COLUMN backdrop_hash TEXT DEFAULT NULL;
+CREATE INDEX IF NOT EXISTS idx_movies_backdrop_hash ON movies(backdrop_hash);
+
+CREATE TRIGGER IF NOT EXISTS movies_backdrop_ref_insert AFTER INSERT ON movies
+WHEN new.backdrop_hash IS NOT NULL
+BEGIN
+    UPDATE images SET ref_count = ref_count + 1 WHERE hash = new.backdrop_hash;
+END;
+
+CREATE TRIGGER IF NOT EXISTS movies_backdrop_ref_update AFTER UPDATE OF backdrop_hash ON movies
+WHEN old.backdrop_hash IS NOT new.backdrop_hash
+BEGIN
+    UPDATE images SET ref_count = ref_count + 1 WHERE hash = new.backdrop_hash;
+    UPDATE images SET ref_count = ref_count - 1 WHERE hash = old.backdrop_hash;
+    DELETE FROM images WHERE hash = old.backdrop_hash AND ref_count <= 0;
+END;
+
+CREATE TRIGGER IF NOT EXISTS movies_backdrop_ref_delete AFTER DELETE ON movies
+WHEN old.backdrop_hash IS NOT NULL
+BEGIN
+    UPDATE images SET ref_count = ref_count - 1 WHERE hash = old.backdrop_hash;
+    DELETE FROM images WHERE hash = old.backdrop_hash AND ref_count <= 0;
+END;
@@ -0,0 +1,13 @@
+-- Push tokens of a user's devices. A token belongs to one user; registering
+-- it again (e.g. after another login on the same phone) moves it.
+CREATE TABLE IF NOT EXISTS devices (
+    id BLOB PRIMARY KEY NOT NULL,
+    user_id BLOB NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    platform TEXT NOT NULL,
+    token TEXT NOT NULL UNIQUE,
+    name TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_devices_user ON devices(user_id);
@@ -0,0 +1,14 @@
+-- Poster downloads that failed, retried with backoff by the scheduler.
+-- next_attempt_at is NULL once retries are given up.
+CREATE TABLE IF NOT EXISTS poster_retries (
+    movie_id BLOB PRIMARY KEY NOT NULL REFERENCES movies(id) ON DELETE CASCADE,
+    user_id BLOB NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    source TEXT NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 1,
+    last_error TEXT NOT NULL,
+    next_attempt_at TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_poster_retries_next ON poster_retries(next_attempt_at);
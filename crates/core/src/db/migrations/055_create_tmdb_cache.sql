@@ -0,0 +1,9 @@
+-- TMDB responses kept across restarts when tmdb_cache is set to sqlite.
+-- key is endpoint, query (without API key) and language.
+CREATE TABLE IF NOT EXISTS tmdb_cache (
+    key TEXT PRIMARY KEY NOT NULL,
+    body TEXT NOT NULL,
+    fetched_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_tmdb_cache_fetched ON tmdb_cache(fetched_at);
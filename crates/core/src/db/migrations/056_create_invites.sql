@@ -0,0 +1,13 @@
+-- Invite codes for registration while open registration is disabled.
+-- Only a hash of the code is stored; `uses` counts accounts created with it.
+CREATE TABLE IF NOT EXISTS invites (
+    id BLOB PRIMARY KEY NOT NULL,
+    code_hash TEXT NOT NULL UNIQUE,
+    role TEXT NOT NULL DEFAULT 'user',
+    max_uses INTEGER NOT NULL DEFAULT 1,
+    uses INTEGER NOT NULL DEFAULT 0,
+    note TEXT,
+    created_by BLOB REFERENCES users(id) ON DELETE SET NULL,
+    created_at TEXT NOT NULL,
+    expires_at TEXT
+);
@@ -0,0 +1,6 @@
+-- Weekly digest email: opt-in, sent on `digest_weekday` (0 = Monday) from
+-- `digest_hour` on in the user's timezone
+ALTER TABLE users ADD COLUMN digest_enabled BOOLEAN NOT NULL DEFAULT 0;
+ALTER TABLE users ADD COLUMN digest_weekday INTEGER NOT NULL DEFAULT 6;
+ALTER TABLE users ADD COLUMN digest_hour INTEGER NOT NULL DEFAULT 9;
+ALTER TABLE users ADD COLUMN digest_sent_at TEXT;
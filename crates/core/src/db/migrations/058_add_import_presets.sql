@@ -0,0 +1,2 @@
+-- Named CSV column mappings of a user, as a JSON array of `ImportPreset`
+ALTER TABLE users ADD COLUMN import_presets TEXT NOT NULL DEFAULT '[]';
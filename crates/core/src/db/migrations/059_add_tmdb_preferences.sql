@@ -0,0 +1,4 @@
+-- Per-user TMDB image sizes and metadata languages; NULL = server setting
+ALTER TABLE users ADD COLUMN tmdb_poster_size TEXT;
+ALTER TABLE users ADD COLUMN tmdb_backdrop_size TEXT;
+ALTER TABLE users ADD COLUMN tmdb_languages TEXT;
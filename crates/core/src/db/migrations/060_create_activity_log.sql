@@ -0,0 +1,18 @@
+-- Audit log of created, changed, deleted and imported entries
+CREATE TABLE IF NOT EXISTS activity_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id BLOB NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    action TEXT NOT NULL,
+    entity_type TEXT NOT NULL,
+    entity_id BLOB NOT NULL,
+    title TEXT,
+    -- JSON object of changed fields: {"field": {"old": ..., "new": ...}}
+    changes TEXT,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_activity_log_user_created_at
+    ON activity_log(user_id, created_at);
+
+CREATE INDEX IF NOT EXISTS idx_activity_log_entity
+    ON activity_log(user_id, entity_type, entity_id);
@@ -0,0 +1,15 @@
+-- Daily totals of purchase prices and current values, to chart what a
+-- collection is worth over time. One row per user, day and currency, as
+-- amounts are summed up without converting them.
+CREATE TABLE IF NOT EXISTS valuation_snapshots (
+    user_id BLOB NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    date TEXT NOT NULL,
+    -- As stored with the amounts; '' for amounts without currency
+    currency TEXT NOT NULL,
+    purchase_total REAL NOT NULL,
+    value_total REAL NOT NULL,
+    -- Items with a purchase price / current value in this currency
+    priced_count INTEGER NOT NULL,
+    valued_count INTEGER NOT NULL,
+    PRIMARY KEY (user_id, date, currency)
+);
@@ -0,0 +1,21 @@
+-- Timezones are IANA names such as "Europe/Berlin" instead of UTC offsets, so
+-- reminders and digests follow daylight saving time. Stored offsets become the
+-- matching fixed zone; Etc/GMT zones count westwards (UTC+1 is Etc/GMT-1).
+-- Offsets without a zone of their own are cleared (UTC).
+UPDATE users SET timezone = CASE
+    WHEN timezone = '-03:30' THEN 'America/St_Johns'
+    WHEN timezone = '+03:30' THEN 'Asia/Tehran'
+    WHEN timezone = '+04:30' THEN 'Asia/Kabul'
+    WHEN timezone = '+05:30' THEN 'Asia/Kolkata'
+    WHEN timezone = '+05:45' THEN 'Asia/Kathmandu'
+    WHEN timezone = '+06:30' THEN 'Asia/Yangon'
+    WHEN timezone = '+09:30' THEN 'Australia/Darwin'
+    WHEN substr(timezone, 4) = ':00'
+        AND CAST(substr(timezone, 2, 2) AS INTEGER) BETWEEN 1 AND 12
+        THEN 'Etc/GMT' || CASE substr(timezone, 1, 1) WHEN '+' THEN '-' ELSE '+' END
+            || CAST(substr(timezone, 2, 2) AS INTEGER)
+    WHEN timezone IN ('+13:00', '+14:00')
+        THEN 'Etc/GMT-' || CAST(substr(timezone, 2, 2) AS INTEGER)
+    ELSE NULL
+END
+WHERE timezone LIKE '+__:__' OR timezone LIKE '-__:__';
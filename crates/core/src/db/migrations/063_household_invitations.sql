@@ -0,0 +1,21 @@
+-- Users join a household by accepting an invitation, so their movies are
+-- only shown to households they agreed to. Members added before are invited
+-- again.
+CREATE TABLE IF NOT EXISTS household_invitations (
+    household_id BLOB NOT NULL REFERENCES households(id) ON DELETE CASCADE,
+    user_id BLOB NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    invited_at TEXT NOT NULL,
+    PRIMARY KEY (household_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_household_invitations_user ON household_invitations(user_id);
+
+INSERT INTO household_invitations (household_id, user_id, invited_at)
+SELECT m.household_id, m.user_id, m.joined_at
+FROM household_members m JOIN households h ON h.id = m.household_id
+WHERE m.user_id != h.owner_id;
+
+DELETE FROM household_members WHERE NOT EXISTS (
+    SELECT 1 FROM households h
+    WHERE h.id = household_members.household_id AND h.owner_id = household_members.user_id
+);
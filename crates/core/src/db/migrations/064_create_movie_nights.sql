@@ -0,0 +1,10 @@
+-- Movie nights scheduled from the planner proposals
+CREATE TABLE IF NOT EXISTS movie_nights (
+    id BLOB PRIMARY KEY NOT NULL,
+    user_id BLOB NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    movie_id BLOB NOT NULL REFERENCES movies(id) ON DELETE CASCADE,
+    scheduled_for TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_movie_nights_user ON movie_nights(user_id, scheduled_for);
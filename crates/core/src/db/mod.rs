@@ -1,16 +1,58 @@
-use sqlx::sqlite::{SqlitePool, SqlitePoolOptions};
+use sqlx::ConnectOptions;
+use sqlx::migrate::Migrator;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use std::time::Duration;
 
+use crate::error::{Error, Result};
+
+pub mod postgres;
+pub(crate) mod query;
+pub mod slow_queries;
+
 pub type DbPool = SqlitePool;
 
-pub async fn create_pool(database_url: &str) -> Result<DbPool, sqlx::Error> {
+static MIGRATOR: Migrator = sqlx::migrate!("src/db/migrations");
+
+/// How `create_pool` handles pending migrations and slow queries
+#[derive(Debug, Clone, Copy)]
+pub struct DatabaseOptions {
+    /// Leave the schema as it is (`--skip-migrations`)
+    pub skip: bool,
+    /// Snapshot the database file before applying pending migrations
+    pub backup: bool,
+    /// Log queries taking longer (see [`slow_queries`])
+    pub slow_query_threshold: Option<Duration>,
+}
+
+impl Default for DatabaseOptions {
+    fn default() -> Self {
+        Self {
+            skip: false,
+            backup: true,
+            slow_query_threshold: None,
+        }
+    }
+}
+
+pub async fn create_pool(database_url: &str) -> Result<DbPool> {
+    create_pool_with(database_url, DatabaseOptions::default()).await
+}
+
+pub async fn create_pool_with(database_url: &str, options: DatabaseOptions) -> Result<DbPool> {
+    let db_path = database_path(database_url);
+
     // Ensure the data directory exists
-    if let Some(path) = database_url.strip_prefix("sqlite:")
-        && let Some(parent) = std::path::Path::new(path).parent()
-    {
+    if let Some(parent) = db_path.as_deref().and_then(Path::parent) {
         std::fs::create_dir_all(parent).ok();
     }
 
+    let mut connect_options = SqliteConnectOptions::from_str(database_url)?;
+    if let Some(threshold) = options.slow_query_threshold {
+        connect_options = connect_options.log_slow_statements(log::LevelFilter::Warn, threshold);
+    }
+
     let pool = SqlitePoolOptions::new()
         .max_connections(5)
         .acquire_timeout(Duration::from_secs(3))
@@ -23,11 +65,248 @@ pub async fn create_pool(database_url: &str) -> Result<DbPool, sqlx::Error> {
                 Ok(())
             })
         })
-        .connect(database_url)
+        .connect_with(connect_options)
         .await?;
 
-    // Run migrations
-    sqlx::migrate!("src/db/migrations").run(&pool).await?;
+    if let Some(threshold) = options.slow_query_threshold {
+        slow_queries::enable(&pool, threshold);
+    }
+
+    let pending = pending_migrations(&pool).await?;
+    if pending.is_empty() {
+        return Ok(pool);
+    }
+
+    if options.skip {
+        tracing::warn!(
+            "Skipping {} pending database migration(s) ({}); the server may not work correctly",
+            pending.len(),
+            pending.join(", ")
+        );
+        return Ok(pool);
+    }
+
+    // A new database has nothing worth saving
+    let backup = match db_path.as_deref() {
+        Some(path) if options.backup && has_applied_migrations(&pool).await? => {
+            Some(backup_database(&pool, path).await?)
+        }
+        _ => None,
+    };
+
+    tracing::info!("Applying {} database migration(s)", pending.len());
+    if let Err(e) = MIGRATOR.run(&pool).await {
+        return Err(Error::Migration(migration_failure_message(
+            &e.to_string(),
+            db_path.as_deref(),
+            backup.as_deref(),
+        )));
+    }
 
     Ok(pool)
 }
+
+/// File path of a SQLite URL, `None` for in-memory databases
+fn database_path(database_url: &str) -> Option<PathBuf> {
+    let path = database_url
+        .strip_prefix("sqlite://")
+        .or_else(|| database_url.strip_prefix("sqlite:"))?;
+    let path = path.split('?').next().unwrap_or_default();
+    if path.is_empty() || path == ":memory:" {
+        return None;
+    }
+    Some(PathBuf::from(path))
+}
+
+/// Migrations not yet applied, as `version_description`
+async fn pending_migrations(pool: &DbPool) -> Result<Vec<String>> {
+    let applied: Vec<i64> = if has_migrations_table(pool).await? {
+        sqlx::query_scalar("SELECT version FROM _sqlx_migrations WHERE success = 1")
+            .fetch_all(pool)
+            .await?
+    } else {
+        Vec::new()
+    };
+
+    Ok(MIGRATOR
+        .iter()
+        .filter(|m| !applied.contains(&m.version))
+        .map(|m| format!("{:03}_{}", m.version, m.description))
+        .collect())
+}
+
+async fn has_migrations_table(pool: &DbPool) -> Result<bool> {
+    Ok(sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = '_sqlx_migrations')",
+    )
+    .fetch_one(pool)
+    .await?)
+}
+
+async fn has_applied_migrations(pool: &DbPool) -> Result<bool> {
+    if !has_migrations_table(pool).await? {
+        return Ok(false);
+    }
+    Ok(
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM _sqlx_migrations WHERE success = 1)")
+            .fetch_one(pool)
+            .await?,
+    )
+}
+
+/// Copy the database next to itself with `VACUUM INTO` (consistent even while
+/// the WAL holds changes) and return the snapshot's path
+async fn backup_database(pool: &DbPool, db_path: &Path) -> Result<PathBuf> {
+    let stem = db_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "database".to_string());
+    let file_name = format!(
+        "{}.pre-migration-{}.db",
+        stem,
+        chrono::Utc::now().format("%Y%m%d-%H%M%S")
+    );
+    let backup = db_path.with_file_name(file_name);
+
+    sqlx::query("VACUUM INTO ?")
+        .bind(backup.to_string_lossy().into_owned())
+        .execute(pool)
+        .await
+        .map_err(|e| {
+            Error::Migration(format!(
+                "Could not back up the database to {} before migrating, nothing was changed: {}",
+                backup.display(),
+                e
+            ))
+        })?;
+
+    tracing::info!("Backed up the database to {}", backup.display());
+    Ok(backup)
+}
+
+fn migration_failure_message(error: &str, db_path: Option<&Path>, backup: Option<&Path>) -> String {
+    let mut message = format!("{}\n\n", error);
+    match (db_path, backup) {
+        (Some(db_path), Some(backup)) => message.push_str(&format!(
+            "A copy of the database from before the upgrade is at {}.\n\
+             To roll back: stop the server, replace {} with that copy \
+             (and delete any -wal/-shm files next to it), then start the previous version.\n",
+            backup.display(),
+            db_path.display()
+        )),
+        _ => message.push_str("No backup was taken before migrating.\n"),
+    }
+    message.push_str(
+        "Start with --skip-migrations to run this version on the current schema anyway (not recommended).",
+    );
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_database_path() {
+        assert_eq!(
+            database_path("sqlite:./data/my-movies.db?mode=rwc"),
+            Some(PathBuf::from("./data/my-movies.db"))
+        );
+        assert_eq!(
+            database_path("sqlite:///var/lib/my-movies.db"),
+            Some(PathBuf::from("/var/lib/my-movies.db"))
+        );
+        assert_eq!(database_path("sqlite::memory:"), None);
+        assert_eq!(database_path("postgres://localhost/movies"), None);
+    }
+
+    #[tokio::test]
+    async fn test_backup_before_migrating() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = format!("sqlite:{}?mode=rwc", dir.path().join("movies.db").display());
+
+        // A new database is migrated without a backup
+        let pool = create_pool(&url).await.unwrap();
+        let snapshots = |dir: &Path| {
+            std::fs::read_dir(dir)
+                .unwrap()
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_name().to_string_lossy().contains("pre-migration"))
+                .count()
+        };
+        assert_eq!(snapshots(dir.path()), 0);
+
+        // Pretend the latest migration hasn't run yet
+        let latest = MIGRATOR.iter().map(|m| m.version).max().unwrap();
+        sqlx::query("DELETE FROM _sqlx_migrations WHERE version = ?")
+            .bind(latest)
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool.close().await;
+
+        let skipped = create_pool_with(
+            &url,
+            DatabaseOptions {
+                skip: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(pending_migrations(&skipped).await.unwrap().len(), 1);
+        assert_eq!(snapshots(dir.path()), 0);
+        skipped.close().await;
+
+        let result = create_pool(&url).await;
+        // Re-running the latest migration may fail (e.g. ALTER TABLE ADD COLUMN);
+        // either way a snapshot was taken first
+        assert_eq!(snapshots(dir.path()), 1);
+        if let Err(e) = result {
+            assert!(e.to_string().contains("To roll back"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_timezone_offsets_become_names() {
+        let pool = crate::test_helpers::create_test_db_with_users().await;
+        let user_id = crate::test_helpers::fixtures::test_user_id();
+
+        for (offset, zone) in [
+            ("+01:00", Some("Etc/GMT-1")),
+            ("-05:00", Some("Etc/GMT+5")),
+            ("+14:00", Some("Etc/GMT-14")),
+            ("+05:30", Some("Asia/Kolkata")),
+            ("+01:15", None),
+            ("Europe/Berlin", Some("Europe/Berlin")),
+        ] {
+            sqlx::query("UPDATE users SET timezone = ? WHERE id = ?")
+                .bind(offset)
+                .bind(user_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+            sqlx::raw_sql(include_str!("migrations/062_timezone_names.sql"))
+                .execute(&pool)
+                .await
+                .unwrap();
+
+            let stored: Option<String> =
+                sqlx::query_scalar("SELECT timezone FROM users WHERE id = ?")
+                    .bind(user_id)
+                    .fetch_one(&pool)
+                    .await
+                    .unwrap();
+            assert_eq!(stored.as_deref(), zone, "{}", offset);
+            // Offsets sent by older clients end up the same way
+            if let Some(zone) = zone {
+                assert_eq!(
+                    crate::models::normalize_timezone(offset)
+                        .unwrap()
+                        .as_deref(),
+                    Some(zone)
+                );
+            }
+        }
+    }
+}
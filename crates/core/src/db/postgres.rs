@@ -0,0 +1,985 @@
+//! Copy of a SQLite library into a PostgreSQL database, for moving from the
+//! desktop app to a server. The PostgreSQL schema is derived from the SQLite
+//! database the migrations built, so it can't drift from them: columns with
+//! their types, defaults and NOT NULL, primary and unique keys, CHECK
+//! constraints, foreign keys, indexes and triggers. BLOBs (IDs, posters,
+//! avatars) become `BYTEA`, `COLLATE NOCASE` a case-insensitive ICU collation.
+//! Full-text search tables become plain tables with a GIN index, kept up to
+//! date by the same triggers as in SQLite.
+
+use std::sync::LazyLock;
+
+use regex::{Captures, Regex};
+use serde::Serialize;
+use sqlx::postgres::{PgArguments, PgPool, PgPoolOptions};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Arguments, Row, TypeInfo, ValueRef};
+
+use super::DbPool;
+use crate::error::{Error, Result};
+
+/// Bind parameters per statement are limited to 65535 in PostgreSQL
+const MAX_PARAMETERS: usize = 65_535;
+
+/// Case-insensitive collation standing in for SQLite's `NOCASE`
+const NOCASE_COLLATION: &str = "CREATE COLLATION IF NOT EXISTS nocase \
+     (provider = icu, locale = 'und-u-ks-level2', deterministic = false)";
+
+/// SQLite date functions used by the schema and their PostgreSQL equivalent
+const DATE_FUNCTIONS: &[(&str, &str)] = &[
+    (
+        "strftime('%Y-%m-%dT%H:%M:%SZ', 'now')",
+        "to_char(now() AT TIME ZONE 'UTC', 'YYYY-MM-DD\"T\"HH24:MI:SS\"Z\"')",
+    ),
+    (
+        "strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')",
+        "to_char(now() AT TIME ZONE 'UTC', 'YYYY-MM-DD\"T\"HH24:MI:SS.MS\"+00:00\"')",
+    ),
+    (
+        "datetime('now')",
+        "to_char(now() AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS')",
+    ),
+];
+
+/// `x IS y` and `x IS NOT y`, which only compare with NULL in PostgreSQL
+static IS_COMPARISON: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\bIS\s+(NOT\s+)?([A-Za-z_][\w.]*)").unwrap());
+
+static LINE_COMMENT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"--[^\n]*").unwrap());
+
+static CHECK: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)\bCHECK\s*\(").unwrap());
+
+static GENERATED: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\bGENERATED\s+ALWAYS\s+AS\s*\(").unwrap());
+
+static BEGIN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)\bBEGIN\b").unwrap());
+
+static WHEN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)\bWHEN\b").unwrap());
+
+static FOR_EACH_ROW: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\bFOR\s+EACH\s+ROW\b").unwrap());
+
+/// Column type in PostgreSQL for a declared SQLite type, following SQLite's
+/// affinity rules; columns without type (and UUIDs stored as BLOB) are BYTEA
+pub fn postgres_type(declared: &str) -> &'static str {
+    let declared = declared.to_uppercase();
+    if declared == "BOOLEAN" || declared == "BOOL" {
+        "BOOLEAN"
+    } else if declared.contains("INT") {
+        "BIGINT"
+    } else if declared.contains("CHAR") || declared.contains("CLOB") || declared.contains("TEXT") {
+        "TEXT"
+    } else if declared.is_empty() || declared.contains("BLOB") {
+        "BYTEA"
+    } else if declared.contains("REAL") || declared.contains("FLOA") || declared.contains("DOUB") {
+        "DOUBLE PRECISION"
+    } else {
+        // NUMERIC affinity: dates, DATETIME, DECIMAL are stored as text in this schema
+        "TEXT"
+    }
+}
+
+/// Rewrite a SQLite expression or statement of the schema for PostgreSQL:
+/// date functions, `IS [NOT]` comparisons and `COLLATE NOCASE` (which
+/// resolves to the `nocase` collation). Fails on date functions it doesn't know.
+pub fn translate_sql(sql: &str) -> Result<String> {
+    let mut sql = LINE_COMMENT.replace_all(sql, "").into_owned();
+    for (sqlite, postgres) in DATE_FUNCTIONS {
+        sql = sql.replace(sqlite, postgres);
+    }
+    if sql.contains("strftime(") || sql.contains("datetime(") || sql.contains("julianday(") {
+        return Err(Error::Validation(format!(
+            "Can't translate date function for PostgreSQL: {}",
+            sql.trim()
+        )));
+    }
+
+    let sql = IS_COMPARISON.replace_all(&sql, |caps: &Captures| {
+        let operand = &caps[2];
+        if operand.eq_ignore_ascii_case("NULL") || operand.eq_ignore_ascii_case("DISTINCT") {
+            caps[0].to_string()
+        } else if caps.get(1).is_some() {
+            format!("IS DISTINCT FROM {}", operand)
+        } else {
+            format!("IS NOT DISTINCT FROM {}", operand)
+        }
+    });
+    Ok(sql.into_owned())
+}
+
+#[derive(Debug, Clone)]
+struct Table {
+    name: String,
+    sql: String,
+    /// FTS5 table, copied as a plain table with a search index
+    full_text: bool,
+}
+
+#[derive(Debug, Clone)]
+struct Column {
+    name: String,
+    declared: String,
+    pg_type: &'static str,
+    not_null: bool,
+    default: Option<String>,
+    /// Expression of a generated column, which isn't copied
+    generated: Option<String>,
+    /// Position in the primary key (1-based), 0 if not part of it
+    pk: i64,
+}
+
+/// Rows copied for one table
+#[derive(Debug, Clone, Serialize)]
+pub struct TableCopy {
+    pub table: String,
+    pub sqlite_rows: i64,
+    pub postgres_rows: i64,
+}
+
+impl TableCopy {
+    pub fn matches(&self) -> bool {
+        self.sqlite_rows == self.postgres_rows
+    }
+}
+
+fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+/// Tables to copy: no SQLite internals, migration bookkeeping or the shadow
+/// tables behind full-text search tables
+async fn tables(sqlite: &DbPool) -> Result<Vec<Table>> {
+    let all: Vec<(String, String)> = sqlx::query_as(
+        "SELECT name, COALESCE(sql, '') FROM sqlite_master WHERE type = 'table' \
+         AND name NOT LIKE 'sqlite_%' AND name != '_sqlx_migrations' ORDER BY name",
+    )
+    .fetch_all(sqlite)
+    .await?;
+
+    let virtual_tables: Vec<&str> = all
+        .iter()
+        .filter(|(_, sql)| sql.to_uppercase().starts_with("CREATE VIRTUAL TABLE"))
+        .map(|(name, _)| name.as_str())
+        .collect();
+
+    Ok(all
+        .iter()
+        .filter(|(name, _)| {
+            !virtual_tables
+                .iter()
+                .any(|v| name.starts_with(&format!("{}_", v)))
+        })
+        .map(|(name, sql)| Table {
+            name: name.clone(),
+            sql: sql.clone(),
+            full_text: virtual_tables.contains(&name.as_str()),
+        })
+        .collect())
+}
+
+/// Columns of a full-text search table and whether each is searched
+fn full_text_columns(sql: &str) -> Vec<(String, bool)> {
+    let args = sql
+        .split_once('(')
+        .and_then(|(_, rest)| rest.rsplit_once(')'))
+        .map_or("", |(args, _)| args);
+    args.split(',')
+        .map(str::trim)
+        .filter(|arg| !arg.is_empty() && !arg.contains('='))
+        .map(|arg| {
+            let mut words = arg.split_whitespace();
+            let name = words.next().unwrap_or_default().to_string();
+            let unindexed = words.any(|w| w.eq_ignore_ascii_case("UNINDEXED"));
+            (name, !unindexed)
+        })
+        .collect()
+}
+
+async fn columns(sqlite: &DbPool, table: &Table) -> Result<Vec<Column>> {
+    // Hidden columns of full-text tables are left out, generated ones are kept
+    let rows: Vec<(String, String, bool, Option<String>, i64, i64)> = sqlx::query_as(
+        "SELECT name, type, \"notnull\", dflt_value, pk, hidden FROM pragma_table_xinfo(?) \
+         WHERE hidden != 1 ORDER BY cid",
+    )
+    .bind(&table.name)
+    .fetch_all(sqlite)
+    .await?;
+
+    let searched = full_text_columns(&table.sql);
+    rows.into_iter()
+        .map(|(name, declared, not_null, default, pk, hidden)| {
+            // Full-text columns have no type: the searched ones hold text,
+            // the others IDs (BLOB) or text such as the entity type
+            let pg_type = if !table.full_text {
+                postgres_type(&declared)
+            } else if searched.contains(&(name.clone(), true)) || !name.ends_with("_id") {
+                "TEXT"
+            } else {
+                "BYTEA"
+            };
+            let generated = match hidden > 1 {
+                true => Some(generated_expression(&table.sql, &name).ok_or_else(|| {
+                    Error::Validation(format!(
+                        "Can't find the expression of {}.{}",
+                        table.name, name
+                    ))
+                })?),
+                false => None,
+            };
+            Ok(Column {
+                name,
+                declared,
+                pg_type,
+                not_null,
+                default,
+                generated,
+                pk,
+            })
+        })
+        .collect()
+}
+
+/// `INTEGER PRIMARY KEY` columns are assigned by SQLite when left out
+fn is_row_id(column: &Column, columns: &[Column]) -> bool {
+    column.pk > 0
+        && column.declared.eq_ignore_ascii_case("INTEGER")
+        && columns.iter().filter(|c| c.pk > 0).count() == 1
+}
+
+fn postgres_default(column: &Column) -> Result<Option<String>> {
+    let Some(default) = column.default.as_deref().map(str::trim) else {
+        return Ok(None);
+    };
+    let default = match (column.pg_type, default.to_uppercase().as_str()) {
+        (_, "NULL") => return Ok(None),
+        ("BOOLEAN", "0" | "FALSE") => "FALSE".to_string(),
+        ("BOOLEAN", "1" | "TRUE") => "TRUE".to_string(),
+        ("TEXT", "CURRENT_TIMESTAMP") => translate_sql("datetime('now')")?,
+        _ => translate_sql(default)?,
+    };
+    Ok(Some(format!("({})", default)))
+}
+
+/// The parenthesized expression opening at byte `open`, parentheses included
+fn parenthesized(sql: &str, open: usize) -> Option<&str> {
+    let mut depth = 0;
+    let mut quoted = false;
+    for (i, c) in sql[open..].char_indices() {
+        match c {
+            '\'' => quoted = !quoted,
+            '(' if !quoted => depth += 1,
+            ')' if !quoted => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&sql[open..=open + i]);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// `CHECK (...)` constraints in a `CREATE TABLE` statement
+fn check_constraints(sql: &str) -> Vec<String> {
+    let sql = LINE_COMMENT.replace_all(sql, "");
+    CHECK
+        .find_iter(&sql)
+        .filter_map(|m| parenthesized(&sql, m.end() - 1))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Expression of a generated column in a `CREATE TABLE` statement
+fn generated_expression(sql: &str, column: &str) -> Option<String> {
+    let sql = LINE_COMMENT.replace_all(sql, "");
+    let definition = Regex::new(&format!(r#"[\s,(]"?{}"?\s"#, regex::escape(column)))
+        .ok()?
+        .find(&sql)?;
+    let generated = GENERATED.find_at(&sql, definition.end())?;
+    parenthesized(&sql, generated.end() - 1).map(str::to_string)
+}
+
+/// Column lists of the table's `UNIQUE` constraints
+async fn unique_constraints(sqlite: &DbPool, table: &str) -> Result<Vec<Vec<String>>> {
+    let indexes: Vec<(String,)> =
+        sqlx::query_as("SELECT name FROM pragma_index_list(?) WHERE origin = 'u' ORDER BY name")
+            .bind(table)
+            .fetch_all(sqlite)
+            .await?;
+
+    let mut constraints = Vec::with_capacity(indexes.len());
+    for (index,) in indexes {
+        let columns: Vec<(String,)> =
+            sqlx::query_as("SELECT name FROM pragma_index_info(?) ORDER BY seqno")
+                .bind(&index)
+                .fetch_all(sqlite)
+                .await?;
+        constraints.push(columns.into_iter().map(|(c,)| c).collect());
+    }
+    Ok(constraints)
+}
+
+fn create_table_sql(
+    table: &str,
+    columns: &[Column],
+    unique: &[Vec<String>],
+    checks: &[String],
+) -> Result<String> {
+    let mut definitions = Vec::with_capacity(columns.len() + unique.len() + checks.len() + 1);
+    for column in columns {
+        let mut definition = format!("{} {}", quote(&column.name), column.pg_type);
+        if let Some(expression) = &column.generated {
+            definition.push_str(&format!(
+                " GENERATED ALWAYS AS {} STORED",
+                translate_sql(expression)?
+            ));
+        } else if is_row_id(column, columns) {
+            definition.push_str(" GENERATED BY DEFAULT AS IDENTITY");
+        } else if let Some(default) = postgres_default(column)? {
+            definition.push_str(&format!(" DEFAULT {}", default));
+        }
+        if column.not_null || column.pk > 0 {
+            definition.push_str(" NOT NULL");
+        }
+        definitions.push(definition);
+    }
+
+    let mut pk: Vec<&Column> = columns.iter().filter(|c| c.pk > 0).collect();
+    pk.sort_by_key(|c| c.pk);
+    if !pk.is_empty() {
+        let names: Vec<String> = pk.iter().map(|c| quote(&c.name)).collect();
+        definitions.push(format!("PRIMARY KEY ({})", names.join(", ")));
+    }
+    for columns in unique {
+        let names: Vec<String> = columns.iter().map(|c| quote(c)).collect();
+        definitions.push(format!("UNIQUE ({})", names.join(", ")));
+    }
+    for check in checks {
+        definitions.push(format!("CHECK {}", translate_sql(check)?));
+    }
+
+    Ok(format!(
+        "CREATE TABLE {} ({})",
+        quote(table),
+        definitions.join(", ")
+    ))
+}
+
+/// GIN index over the searched columns of a full-text table
+fn search_index_sql(table: &str, columns: &[(String, bool)]) -> String {
+    let searched: Vec<String> = columns
+        .iter()
+        .filter(|(_, searched)| *searched)
+        .map(|(name, _)| format!("COALESCE({}, '')", quote(name)))
+        .collect();
+    format!(
+        "CREATE INDEX {} ON {} USING GIN (to_tsvector('simple', {}))",
+        quote(&format!("{}_search", table)),
+        quote(table),
+        searched.join(" || ' ' || ")
+    )
+}
+
+/// `ALTER TABLE ... ADD FOREIGN KEY` for each foreign key of the table
+async fn foreign_keys_sql(sqlite: &DbPool, table: &str) -> Result<Vec<String>> {
+    let rows: Vec<(i64, String, String, Option<String>, String, String)> = sqlx::query_as(
+        "SELECT id, \"table\", \"from\", \"to\", on_update, on_delete \
+         FROM pragma_foreign_key_list(?) ORDER BY id, seq",
+    )
+    .bind(table)
+    .fetch_all(sqlite)
+    .await?;
+
+    let mut statements = Vec::new();
+    let mut rows = rows.into_iter().peekable();
+    while let Some((id, parent, from, to, on_update, on_delete)) = rows.next() {
+        let mut from = vec![quote(&from)];
+        let mut to: Vec<String> = to.iter().map(|c| quote(c)).collect();
+        while let Some((_, _, next_from, next_to, _, _)) = rows.next_if(|row| row.0 == id) {
+            from.push(quote(&next_from));
+            to.extend(next_to.iter().map(|c| quote(c)));
+        }
+        let to = if to.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", to.join(", "))
+        };
+        statements.push(format!(
+            "ALTER TABLE {} ADD FOREIGN KEY ({}) REFERENCES {}{} ON UPDATE {} ON DELETE {}",
+            quote(table),
+            from.join(", "),
+            quote(&parent),
+            to,
+            on_update,
+            on_delete
+        ));
+    }
+    Ok(statements)
+}
+
+/// A trigger function with the trigger's body and the trigger calling it
+fn trigger_sql(name: &str, sql: &str) -> Result<[String; 2]> {
+    let invalid = || Error::Validation(format!("Can't translate trigger {}", name));
+    let begin = BEGIN.find(sql).ok_or_else(invalid)?;
+    let end = sql.to_uppercase().rfind("END").ok_or_else(invalid)?;
+    let header = &sql[..begin.start()];
+    let body = &sql[begin.end()..end];
+
+    // `CREATE TRIGGER [IF NOT EXISTS] name` comes before the event
+    let event_start = header
+        .to_lowercase()
+        .find(&name.to_lowercase())
+        .map(|i| i + name.len())
+        .ok_or_else(invalid)?;
+    let event = &header[event_start..];
+    let (event, condition) = match WHEN.find(event) {
+        Some(when) => (&event[..when.start()], Some(&event[when.end()..])),
+        None => (event, None),
+    };
+    let event = FOR_EACH_ROW.replace(event, "");
+    let event = event.split_whitespace().collect::<Vec<_>>().join(" ");
+    let condition = match condition {
+        Some(condition) => format!(" WHEN ({})", translate_sql(condition.trim())?),
+        None => String::new(),
+    };
+
+    Ok([
+        format!(
+            "CREATE FUNCTION {}() RETURNS trigger LANGUAGE plpgsql AS $$\nBEGIN\n{}\nRETURN NULL;\nEND\n$$",
+            quote(name),
+            translate_sql(body)?.trim_end()
+        ),
+        format!(
+            "CREATE TRIGGER {} {} FOR EACH ROW{} EXECUTE FUNCTION {}()",
+            quote(name),
+            event,
+            condition,
+            quote(name)
+        ),
+    ])
+}
+
+/// Bind the value of column `index` converted to the column's PostgreSQL
+/// type. SQLite doesn't enforce declared types, so the stored value decides
+/// how it is read.
+fn bind_value(
+    args: &mut PgArguments,
+    row: &SqliteRow,
+    index: usize,
+    column: &Column,
+) -> Result<()> {
+    let raw = row.try_get_raw(index)?;
+    let storage = if raw.is_null() {
+        "NULL".to_string()
+    } else {
+        raw.type_info().name().to_string()
+    };
+    let invalid = |storage: &str| {
+        Error::Validation(format!(
+            "Can't copy {} value of column {} to {}",
+            storage, column.name, column.pg_type
+        ))
+    };
+
+    let bound = match (column.pg_type, storage.as_str()) {
+        ("BIGINT", "NULL") => args.add(None::<i64>),
+        ("BIGINT", "INTEGER") => args.add(row.try_get_unchecked::<i64, _>(index)?),
+        ("BIGINT", "REAL") => args.add(row.try_get_unchecked::<f64, _>(index)? as i64),
+        ("BIGINT", "TEXT") => {
+            let text: String = row.try_get_unchecked(index)?;
+            args.add(text.trim().parse::<i64>().map_err(|_| invalid("TEXT"))?)
+        }
+        ("BOOLEAN", "NULL") => args.add(None::<bool>),
+        ("BOOLEAN", "INTEGER") => args.add(row.try_get_unchecked::<i64, _>(index)? != 0),
+        ("DOUBLE PRECISION", "NULL") => args.add(None::<f64>),
+        ("DOUBLE PRECISION", "INTEGER" | "REAL") => {
+            args.add(row.try_get_unchecked::<f64, _>(index)?)
+        }
+        ("BYTEA", "NULL") => args.add(None::<Vec<u8>>),
+        ("BYTEA", _) => args.add(row.try_get_unchecked::<Vec<u8>, _>(index)?),
+        ("TEXT", "NULL") => args.add(None::<String>),
+        ("TEXT", "BLOB") => {
+            let bytes: Vec<u8> = row.try_get_unchecked(index)?;
+            args.add(String::from_utf8(bytes).map_err(|_| invalid("BLOB"))?)
+        }
+        // Numbers in text columns are copied as their text
+        ("TEXT", _) => args.add(row.try_get_unchecked::<String, _>(index)?),
+        (_, storage) => return Err(invalid(storage)),
+    };
+    bound.map_err(|e| Error::Internal(format!("Failed to bind {}: {}", column.name, e)))
+}
+
+/// Value of a key column in SQLite, bound again to fetch the next page
+enum KeyValue {
+    Integer(i64),
+    Real(f64),
+    Text(String),
+    Blob(Vec<u8>),
+}
+
+fn key_value(row: &SqliteRow, index: usize, table: &str) -> Result<KeyValue> {
+    let raw = row.try_get_raw(index)?;
+    if raw.is_null() {
+        return Err(Error::Validation(format!(
+            "A row of {} has no primary key",
+            table
+        )));
+    }
+    Ok(match raw.type_info().name() {
+        "INTEGER" => KeyValue::Integer(row.try_get_unchecked(index)?),
+        "REAL" => KeyValue::Real(row.try_get_unchecked(index)?),
+        "TEXT" => KeyValue::Text(row.try_get_unchecked(index)?),
+        _ => KeyValue::Blob(row.try_get_unchecked(index)?),
+    })
+}
+
+/// Copy the table's rows into the created PostgreSQL table, `batch_size` per
+/// query. Pages follow the primary key (the rowid without one), so every row
+/// is read exactly once.
+async fn copy_rows(
+    sqlite: &DbPool,
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    table: &str,
+    columns: &[Column],
+    batch_size: usize,
+) -> Result<i64> {
+    let mut key: Vec<&Column> = columns.iter().filter(|c| c.pk > 0).collect();
+    key.sort_by_key(|c| c.pk);
+    let key: Vec<String> = match key.is_empty() {
+        true => vec!["rowid".to_string()],
+        false => key.iter().map(|c| quote(&c.name)).collect(),
+    };
+
+    let row_id = columns.iter().find(|c| is_row_id(c, columns));
+    let columns: Vec<&Column> = columns.iter().filter(|c| c.generated.is_none()).collect();
+    let column_list: Vec<String> = columns.iter().map(|c| quote(&c.name)).collect();
+    let column_list = column_list.join(", ");
+    let key_list = key.join(", ");
+    let placeholders = vec!["?"; key.len()].join(", ");
+    let select = format!("SELECT {}, {} FROM {}", column_list, key_list, quote(table));
+    let first_page = format!("{} ORDER BY {} LIMIT ?", select, key_list);
+    let next_page = format!(
+        "{} WHERE ({}) > ({}) ORDER BY {} LIMIT ?",
+        select, key_list, placeholders, key_list
+    );
+    let batch_size = batch_size.clamp(1, MAX_PARAMETERS / columns.len().max(1));
+
+    let mut copied = 0i64;
+    let mut last: Option<Vec<KeyValue>> = None;
+    loop {
+        let mut query = sqlx::query(match last {
+            Some(_) => &next_page,
+            None => &first_page,
+        });
+        for value in last.iter().flatten() {
+            query = match value {
+                KeyValue::Integer(v) => query.bind(*v),
+                KeyValue::Real(v) => query.bind(*v),
+                KeyValue::Text(v) => query.bind(v.clone()),
+                KeyValue::Blob(v) => query.bind(v.clone()),
+            };
+        }
+        let rows = query.bind(batch_size as i64).fetch_all(sqlite).await?;
+        let Some(last_row) = rows.last() else {
+            break;
+        };
+        last = Some(
+            (columns.len()..columns.len() + key.len())
+                .map(|index| key_value(last_row, index, table))
+                .collect::<Result<_>>()?,
+        );
+
+        let mut args = PgArguments::default();
+        let mut values = Vec::with_capacity(rows.len());
+        for (r, row) in rows.iter().enumerate() {
+            let placeholders: Vec<String> = (0..columns.len())
+                .map(|c| format!("${}", r * columns.len() + c + 1))
+                .collect();
+            values.push(format!("({})", placeholders.join(", ")));
+            for (index, column) in columns.iter().enumerate() {
+                bind_value(&mut args, row, index, column)?;
+            }
+        }
+
+        sqlx::query_with(
+            &format!(
+                "INSERT INTO {} ({}) VALUES {}",
+                quote(table),
+                column_list,
+                values.join(", ")
+            ),
+            args,
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        copied += rows.len() as i64;
+        if rows.len() < batch_size {
+            break;
+        }
+    }
+
+    // Continue generated keys after the copied ones
+    if let Some(row_id) = row_id {
+        sqlx::query(&format!(
+            "SELECT setval(pg_get_serial_sequence($1, $2), MAX({})) FROM {}",
+            quote(&row_id.name),
+            quote(table)
+        ))
+        .bind(quote(table))
+        .bind(&row_id.name)
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    Ok(copied)
+}
+
+/// Connect to the target database
+pub async fn connect(url: &str) -> Result<PgPool> {
+    PgPoolOptions::new()
+        .max_connections(2)
+        .connect(url)
+        .await
+        .map_err(|e| Error::Configuration(format!("Could not connect to PostgreSQL: {}", e)))
+}
+
+/// Create the schema in `postgres` and copy every table in one transaction,
+/// then count the rows on both sides. Nothing is written if a table fails to
+/// copy. Indexes, foreign keys and triggers are created after the rows are
+/// copied, so the copy doesn't fire the triggers and the keys are checked
+/// once against the complete data.
+pub async fn copy_to_postgres(
+    sqlite: &DbPool,
+    postgres: &PgPool,
+    batch_size: usize,
+) -> Result<Vec<TableCopy>> {
+    let tables = tables(sqlite).await?;
+    let names: Vec<String> = tables.iter().map(|t| t.name.clone()).collect();
+
+    let mut tx = postgres.begin().await?;
+    let existing: Vec<String> = sqlx::query_scalar(
+        "SELECT table_name::text FROM information_schema.tables \
+         WHERE table_schema = current_schema() AND table_name::text = ANY($1) ORDER BY 1",
+    )
+    .bind(&names)
+    .fetch_all(&mut *tx)
+    .await?;
+    if !existing.is_empty() {
+        return Err(Error::Validation(format!(
+            "PostgreSQL already has the tables {}; copy into an empty database",
+            existing.join(", ")
+        )));
+    }
+    sqlx::query(NOCASE_COLLATION).execute(&mut *tx).await?;
+
+    let mut schema = Vec::new();
+    for table in &tables {
+        let columns = columns(sqlite, table).await?;
+        let (unique, checks) = match table.full_text {
+            true => (Vec::new(), Vec::new()),
+            false => (
+                unique_constraints(sqlite, &table.name).await?,
+                check_constraints(&table.sql),
+            ),
+        };
+        sqlx::query(&create_table_sql(&table.name, &columns, &unique, &checks)?)
+            .execute(&mut *tx)
+            .await?;
+
+        let copied = copy_rows(sqlite, &mut tx, &table.name, &columns, batch_size).await?;
+        tracing::info!("Copied {} rows of {}", copied, table.name);
+
+        if table.full_text {
+            schema.push(search_index_sql(
+                &table.name,
+                &full_text_columns(&table.sql),
+            ));
+        } else {
+            schema.extend(foreign_keys_sql(sqlite, &table.name).await?);
+        }
+    }
+
+    let indexes: Vec<(String,)> = sqlx::query_as(
+        "SELECT sql FROM sqlite_master WHERE type = 'index' AND sql IS NOT NULL ORDER BY name",
+    )
+    .fetch_all(sqlite)
+    .await?;
+    for (sql,) in indexes {
+        schema.push(translate_sql(&sql)?);
+    }
+    let triggers: Vec<(String, String)> =
+        sqlx::query_as("SELECT name, sql FROM sqlite_master WHERE type = 'trigger' ORDER BY name")
+            .fetch_all(sqlite)
+            .await?;
+    for (name, sql) in triggers {
+        schema.extend(trigger_sql(&name, &sql)?);
+    }
+
+    for statement in &schema {
+        sqlx::query(statement).execute(&mut *tx).await?;
+    }
+    tx.commit().await?;
+
+    let mut report = Vec::with_capacity(names.len());
+    for table in names {
+        let sqlite_rows: i64 =
+            sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", quote(&table)))
+                .fetch_one(sqlite)
+                .await?;
+        let postgres_rows: i64 =
+            sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", quote(&table)))
+                .fetch_one(postgres)
+                .await?;
+        report.push(TableCopy {
+            table,
+            sqlite_rows,
+            postgres_rows,
+        });
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::create_test_db;
+
+    #[test]
+    fn test_postgres_type() {
+        assert_eq!(postgres_type("INTEGER"), "BIGINT");
+        assert_eq!(postgres_type("BOOLEAN"), "BOOLEAN");
+        assert_eq!(postgres_type("TEXT"), "TEXT");
+        assert_eq!(postgres_type("VARCHAR(20)"), "TEXT");
+        assert_eq!(postgres_type("BLOB"), "BYTEA");
+        assert_eq!(postgres_type(""), "BYTEA");
+        assert_eq!(postgres_type("REAL"), "DOUBLE PRECISION");
+        assert_eq!(postgres_type("DATETIME"), "TEXT");
+    }
+
+    #[test]
+    fn test_translate_sql() {
+        assert_eq!(
+            translate_sql("NEW.updated_at IS OLD.updated_at AND old.hash IS NOT new.hash").unwrap(),
+            "NEW.updated_at IS NOT DISTINCT FROM OLD.updated_at \
+             AND old.hash IS DISTINCT FROM new.hash"
+        );
+        assert_eq!(
+            translate_sql("a IS NULL OR b IS NOT NULL").unwrap(),
+            "a IS NULL OR b IS NOT NULL"
+        );
+        assert_eq!(
+            translate_sql("datetime('now') -- comment").unwrap().trim(),
+            "to_char(now() AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS')"
+        );
+        assert!(translate_sql("strftime('%s', 'now')").is_err());
+    }
+
+    #[test]
+    fn test_check_constraints() {
+        let sql = "CREATE TABLE t (\n    kind TEXT CHECK (kind IN ('a', 'b)')),\n    \
+                   -- a CHECK in a comment\n    n INTEGER,\n    CHECK ((n > 0) OR kind = 'a')\n)";
+        assert_eq!(
+            check_constraints(sql),
+            vec![
+                "(kind IN ('a', 'b)'))".to_string(),
+                "((n > 0) OR kind = 'a')".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_trigger_sql() {
+        let [function, trigger] = trigger_sql(
+            "movies_updated_at",
+            "CREATE TRIGGER movies_updated_at\n    AFTER UPDATE ON movies\n    FOR EACH ROW\n    \
+             WHEN NEW.updated_at IS OLD.updated_at\nBEGIN\n    \
+             UPDATE movies SET updated_at = datetime('now') WHERE id = NEW.id;\nEND",
+        )
+        .unwrap();
+        assert!(function.starts_with("CREATE FUNCTION \"movies_updated_at\"() RETURNS trigger"));
+        assert!(function.contains("SET updated_at = to_char(now()"));
+        assert!(function.contains("RETURN NULL;"));
+        assert_eq!(
+            trigger,
+            "CREATE TRIGGER \"movies_updated_at\" AFTER UPDATE ON movies FOR EACH ROW \
+             WHEN (NEW.updated_at IS NOT DISTINCT FROM OLD.updated_at) \
+             EXECUTE FUNCTION \"movies_updated_at\"()"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_schema_from_sqlite() {
+        let pool = create_test_db().await;
+        let tables = tables(&pool).await.unwrap();
+        assert!(tables.iter().any(|t| t.name == "movies" && !t.full_text));
+        // Search tables are copied, their SQLite internals and migration bookkeeping aren't
+        let library_fts = tables.iter().find(|t| t.name == "library_fts").unwrap();
+        assert!(library_fts.full_text);
+        assert!(!tables.iter().any(|t| t.name.starts_with("library_fts_")));
+        assert!(!tables.iter().any(|t| t.name == "_sqlx_migrations"));
+
+        let users = tables.iter().find(|t| t.name == "users").unwrap();
+        let columns = columns(&pool, users).await.unwrap();
+        let unique = unique_constraints(&pool, "users").await.unwrap();
+        let sql =
+            create_table_sql("users", &columns, &unique, &check_constraints(&users.sql)).unwrap();
+        assert!(sql.starts_with("CREATE TABLE \"users\" (\"id\" BYTEA NOT NULL"));
+        assert!(sql.contains("\"role\" TEXT DEFAULT ('user') NOT NULL"));
+        assert!(sql.contains("\"loan_reminders\" BOOLEAN DEFAULT (TRUE) NOT NULL"));
+        assert!(sql.contains("PRIMARY KEY (\"id\")"));
+        assert!(sql.contains("UNIQUE (\"username\")"));
+        assert!(sql.contains("CHECK (role IN ('admin', 'user'))"));
+
+        let fts = columns_of(&pool, library_fts).await;
+        assert_eq!(fts[..3], ["TEXT", "BYTEA", "BYTEA"]);
+        assert!(fts[3..].iter().all(|t| *t == "TEXT"));
+
+        let keys = foreign_keys_sql(&pool, "loans").await.unwrap();
+        assert!(keys.iter().any(|k| k.contains(
+            "FOREIGN KEY (\"movie_id\") REFERENCES \"movies\" (\"id\") ON UPDATE NO ACTION ON DELETE CASCADE"
+        )));
+    }
+
+    /// Copy a library into the PostgreSQL server at `TEST_POSTGRES_URL`, in a
+    /// schema of its own; skipped without one
+    #[tokio::test]
+    async fn test_copy_round_trip() {
+        use std::str::FromStr;
+
+        use sqlx::postgres::PgConnectOptions;
+
+        use crate::models::CreateMovie;
+        use crate::services::MovieService;
+        use crate::test_helpers::{create_test_db_with_users, fixtures};
+
+        let Ok(url) = std::env::var("TEST_POSTGRES_URL") else {
+            eprintln!("TEST_POSTGRES_URL is not set, skipping the PostgreSQL copy");
+            return;
+        };
+        let server = connect(&url).await.unwrap();
+        let schema = format!("copy_test_{}", uuid::Uuid::new_v4().simple());
+        sqlx::query(&format!("CREATE SCHEMA {}", schema))
+            .execute(&server)
+            .await
+            .unwrap();
+        let options = PgConnectOptions::from_str(&url)
+            .unwrap()
+            .options([("search_path", schema.as_str())]);
+        let postgres = PgPoolOptions::new().connect_with(options).await.unwrap();
+
+        let sqlite = create_test_db_with_users().await;
+        let user_id = fixtures::test_user_id();
+        let movies = MovieService::new(sqlite.clone());
+        for title in ["Alien", "Heat", "Ronin", "Se7en", "Zodiac"] {
+            movies
+                .create(
+                    user_id,
+                    CreateMovie {
+                        barcode: None,
+                        tmdb_id: None,
+                        title: title.to_string(),
+                        original_title: None,
+                        disc_type: None,
+                        production_year: None,
+                    },
+                )
+                .await
+                .unwrap();
+        }
+        sqlx::query("UPDATE movies SET lent_to = 'Anna' WHERE title = 'Heat'")
+            .execute(&sqlite)
+            .await
+            .unwrap();
+
+        // Pages of two rows, so keyset paging continues across pages
+        let report = copy_to_postgres(&sqlite, &postgres, 2).await.unwrap();
+        assert!(report.iter().all(TableCopy::matches), "{:?}", report);
+        for table in ["movies", "loans", "library_fts", "users"] {
+            let copy = report.iter().find(|t| t.table == table).unwrap();
+            assert!(copy.postgres_rows > 0, "{} is empty", table);
+        }
+
+        let sqlite_titles: Vec<(Vec<u8>, String, Option<String>)> =
+            sqlx::query_as("SELECT id, title, availability FROM movies ORDER BY id")
+                .fetch_all(&sqlite)
+                .await
+                .unwrap();
+        let postgres_titles: Vec<(Vec<u8>, String, Option<String>)> =
+            sqlx::query_as("SELECT id, title, availability FROM movies ORDER BY id")
+                .fetch_all(&postgres)
+                .await
+                .unwrap();
+        assert_eq!(sqlite_titles, postgres_titles);
+
+        // Triggers keep the search table and loans up to date
+        let (heat,): (Vec<u8>,) = sqlx::query_as("SELECT id FROM movies WHERE title = 'Heat'")
+            .fetch_one(&postgres)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE movies SET title = 'Heat (1995)', lent_to = 'Ben' WHERE id = $1")
+            .bind(&heat)
+            .execute(&postgres)
+            .await
+            .unwrap();
+        let (title,): (String,) =
+            sqlx::query_as("SELECT title FROM library_fts WHERE entity_id = $1")
+                .bind(&heat)
+                .fetch_one(&postgres)
+                .await
+                .unwrap();
+        assert_eq!(title, "Heat (1995)");
+        let loans: Vec<(i64, String, Option<String>)> = sqlx::query_as(
+            "SELECT id, lent_to, returned_at FROM loans WHERE movie_id = $1 ORDER BY id",
+        )
+        .bind(&heat)
+        .fetch_all(&postgres)
+        .await
+        .unwrap();
+        assert_eq!(loans.len(), 2);
+        assert!(loans[0].2.is_some());
+        assert_eq!((loans[1].1.as_str(), loans[1].2.as_deref()), ("Ben", None));
+
+        // Constraints came along
+        let invalid_role = sqlx::query(
+            "INSERT INTO users (id, username, email, password_hash, role, created_at, updated_at) \
+             VALUES ('\\x01', 'x', 'x@test.com', 'hash', 'owner', '', '')",
+        )
+        .execute(&postgres)
+        .await
+        .unwrap_err();
+        let code = invalid_role.as_database_error().and_then(|e| e.code());
+        assert_eq!(code.as_deref(), Some("23514"), "{}", invalid_role);
+        sqlx::query("DELETE FROM users WHERE id = $1")
+            .bind(user_id.as_bytes().as_slice())
+            .execute(&postgres)
+            .await
+            .unwrap();
+        let (left,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM movies")
+            .fetch_one(&postgres)
+            .await
+            .unwrap();
+        assert_eq!(left, 0);
+
+        // Only into an empty database
+        assert!(matches!(
+            copy_to_postgres(&sqlite, &postgres, 2).await,
+            Err(Error::Validation(_))
+        ));
+
+        sqlx::query(&format!("DROP SCHEMA {} CASCADE", schema))
+            .execute(&server)
+            .await
+            .unwrap();
+    }
+
+    async fn columns_of(pool: &DbPool, table: &Table) -> Vec<&'static str> {
+        columns(pool, table)
+            .await
+            .unwrap()
+            .iter()
+            .map(|c| c.pg_type)
+            .collect()
+    }
+}
@@ -0,0 +1,193 @@
+//! WHERE clauses built together with their parameters, so a `?` placeholder
+//! and the value bound to it can't get out of order. The same conditions can
+//! back a SELECT and its COUNT.
+
+use sqlx::Sqlite;
+use sqlx::query::QueryAs;
+use sqlx::sqlite::SqliteArguments;
+use uuid::Uuid;
+
+/// A value bound to a placeholder
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Param {
+    Null,
+    Text(String),
+    Int(i64),
+    Bool(bool),
+    Uuid(Uuid),
+}
+
+impl<T: Into<Param>> From<Option<T>> for Param {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Param::Null, Into::into)
+    }
+}
+
+impl From<String> for Param {
+    fn from(value: String) -> Self {
+        Param::Text(value)
+    }
+}
+
+impl From<&str> for Param {
+    fn from(value: &str) -> Self {
+        Param::Text(value.to_string())
+    }
+}
+
+impl From<i32> for Param {
+    fn from(value: i32) -> Self {
+        Param::Int(value.into())
+    }
+}
+
+impl From<i64> for Param {
+    fn from(value: i64) -> Self {
+        Param::Int(value)
+    }
+}
+
+impl From<bool> for Param {
+    fn from(value: bool) -> Self {
+        Param::Bool(value)
+    }
+}
+
+impl From<Uuid> for Param {
+    fn from(value: Uuid) -> Self {
+        Param::Uuid(value)
+    }
+}
+
+/// Conditions joined with AND, each with the parameters of its placeholders
+#[derive(Debug, Default)]
+pub(crate) struct Conditions {
+    clauses: Vec<String>,
+    params: Vec<Param>,
+}
+
+impl Conditions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a condition without placeholders
+    pub fn push(&mut self, clause: impl Into<String>) {
+        self.push_with(clause, Vec::<Param>::new());
+    }
+
+    /// Add a condition; `params` are bound to its placeholders in order
+    ///
+    /// # Panics
+    ///
+    /// If the number of placeholders and parameters differ
+    pub fn push_with<P: Into<Param>>(
+        &mut self,
+        clause: impl Into<String>,
+        params: impl IntoIterator<Item = P>,
+    ) {
+        let clause = clause.into();
+        let start = self.params.len();
+        self.params.extend(params.into_iter().map(Into::into));
+        assert_eq!(
+            placeholders(&clause),
+            self.params.len() - start,
+            "placeholders and parameters of `{}` differ",
+            clause
+        );
+        self.clauses.push(clause);
+    }
+
+    /// Add a condition with a single placeholder
+    pub fn push_bind(&mut self, clause: impl Into<String>, param: impl Into<Param>) {
+        self.push_with(clause, [param.into()]);
+    }
+
+    /// The conditions for a WHERE clause; `1 = 1` if there are none
+    pub fn sql(&self) -> String {
+        if self.clauses.is_empty() {
+            "1 = 1".to_string()
+        } else {
+            self.clauses.join(" AND ")
+        }
+    }
+
+    #[cfg(test)]
+    pub fn params(&self) -> &[Param] {
+        &self.params
+    }
+
+    /// Bind the parameters to a query containing [`Conditions::sql`];
+    /// placeholders after the conditions (LIMIT, OFFSET) are bound afterwards
+    pub fn bind<'q, O>(
+        &self,
+        mut query: QueryAs<'q, Sqlite, O, SqliteArguments<'q>>,
+    ) -> QueryAs<'q, Sqlite, O, SqliteArguments<'q>> {
+        for param in &self.params {
+            query = match param.clone() {
+                Param::Null => query.bind(None::<String>),
+                Param::Text(value) => query.bind(value),
+                Param::Int(value) => query.bind(value),
+                Param::Bool(value) => query.bind(value),
+                Param::Uuid(value) => query.bind(value),
+            };
+        }
+        query
+    }
+}
+
+/// Number of `?` placeholders outside of string literals
+pub(crate) fn placeholders(sql: &str) -> usize {
+    let mut in_literal = false;
+    sql.chars()
+        .filter(|&c| {
+            if c == '\'' {
+                in_literal = !in_literal;
+            }
+            c == '?' && !in_literal
+        })
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::create_test_db;
+
+    #[test]
+    fn test_placeholders() {
+        assert_eq!(placeholders("title LIKE ? OR director LIKE ?"), 2);
+        assert_eq!(placeholders("region_codes LIKE '%,R0,%' OR x = '?'"), 0);
+        assert_eq!(placeholders("a = 'it''s' AND b = ?"), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "placeholders and parameters")]
+    fn test_param_count_must_match() {
+        Conditions::new().push_with("a = ? AND b = ?", [1]);
+    }
+
+    #[tokio::test]
+    async fn test_bind_in_order() {
+        let pool = create_test_db().await;
+        let mut conditions = Conditions::new();
+        conditions.push_bind("x > ?", 1);
+        conditions.push("x < 10");
+        conditions.push_with("(x = ? OR x = ?)", [3, 5]);
+        conditions.push_bind("? = 'a'", "a");
+
+        let sql = format!(
+            "WITH t(x) AS (VALUES (1), (3), (5), (7)) SELECT x FROM t WHERE {} ORDER BY x",
+            conditions.sql()
+        );
+        let rows: Vec<(i64,)> = conditions
+            .bind(sqlx::query_as(&sql))
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(rows, vec![(3,), (5,)]);
+        assert_eq!(conditions.params().len(), 4);
+
+        assert_eq!(Conditions::new().sql(), "1 = 1");
+    }
+}
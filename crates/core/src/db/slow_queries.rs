@@ -0,0 +1,288 @@
+//! Opt-in logging of slow queries (`SLOW_QUERY_MS`). SQLx reports statements
+//! over the threshold; [`SlowQueryLayer`] picks those reports up and logs the
+//! statement with its parameter count and string literals redacted, and in
+//! debug builds the `EXPLAIN QUERY PLAN` of the statement. The reports of
+//! SQLx themselves are kept out of the log outputs
+//! ([`is_sqlx_slow_statement`]).
+
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use tracing::field::{Field, Visit};
+use tracing_subscriber::layer::{Context, Layer};
+
+use super::DbPool;
+
+/// Target of the statement logs of SQLx
+const SQLX_TARGET: &str = "sqlx::query";
+
+/// Set when slow query logging is enabled; the pool runs `EXPLAIN QUERY PLAN`
+static SLOW_QUERY_LOG: OnceLock<SlowQueryLog> = OnceLock::new();
+
+struct SlowQueryLog {
+    pool: DbPool,
+    threshold: Duration,
+}
+
+/// Enable slow query logging for queries on `pool` (first call wins)
+pub(crate) fn enable(pool: &DbPool, threshold: Duration) {
+    if SLOW_QUERY_LOG
+        .set(SlowQueryLog {
+            pool: pool.clone(),
+            threshold,
+        })
+        .is_ok()
+    {
+        tracing::info!("Logging queries slower than {} ms", threshold.as_millis());
+    }
+}
+
+/// Whether an event is a slow statement report of SQLx. It carries the
+/// statement unredacted, so only [`SlowQueryLayer`] may see it; layers writing
+/// logs out filter it away.
+pub fn is_sqlx_slow_statement(metadata: &tracing::Metadata<'_>) -> bool {
+    metadata.target() == SQLX_TARGET && *metadata.level() == tracing::Level::WARN
+}
+
+/// Tracing layer logging the slow statements reported by SQLx; does nothing
+/// until slow query logging is enabled by `create_pool_with`
+pub struct SlowQueryLayer;
+
+impl<S: tracing::Subscriber> Layer<S> for SlowQueryLayer {
+    fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+        if event.metadata().target() != SQLX_TARGET {
+            return;
+        }
+        let Some(log) = SLOW_QUERY_LOG.get() else {
+            return;
+        };
+
+        let mut fields = StatementFields::default();
+        event.record(&mut fields);
+        if !fields.message.starts_with("slow statement") {
+            return;
+        }
+        let sql = if fields.statement.is_empty() {
+            fields.summary
+        } else {
+            fields.statement
+        };
+        // The plan is queried through SQLx too; it must not report itself
+        if sql.is_empty() || is_explain(&sql) {
+            return;
+        }
+
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let pool = log.pool.clone();
+        let threshold = log.threshold;
+        let elapsed = Duration::from_secs_f64(fields.elapsed_secs.max(0.0));
+        runtime.spawn(async move {
+            let (redacted, params) = redact(&sql);
+            let plan = if cfg!(debug_assertions) {
+                explain(&pool, &sql).await
+            } else {
+                None
+            };
+            match plan {
+                Some(plan) => tracing::warn!(
+                    elapsed_ms = elapsed.as_millis() as u64,
+                    threshold_ms = threshold.as_millis() as u64,
+                    params,
+                    "Slow query: {}\nQuery plan:\n{}",
+                    redacted,
+                    plan
+                ),
+                None => tracing::warn!(
+                    elapsed_ms = elapsed.as_millis() as u64,
+                    threshold_ms = threshold.as_millis() as u64,
+                    params,
+                    "Slow query: {}",
+                    redacted
+                ),
+            }
+        });
+    }
+}
+
+#[derive(Default)]
+struct StatementFields {
+    message: String,
+    summary: String,
+    statement: String,
+    elapsed_secs: f64,
+}
+
+impl Visit for StatementFields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "summary" => self.summary = value.to_string(),
+            "db.statement" => self.statement = value.to_string(),
+            _ => {}
+        }
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        if field.name() == "elapsed_secs" {
+            self.elapsed_secs = value;
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        }
+    }
+}
+
+fn is_explain(sql: &str) -> bool {
+    sql.trim_start()
+        .get(..7)
+        .is_some_and(|s| s.eq_ignore_ascii_case("explain"))
+}
+
+/// Collapse whitespace, replace string literals with `'?'` and count the
+/// bound parameters (`?`, `?NNN`, `$NNN`); the values are never logged
+fn redact(sql: &str) -> (String, usize) {
+    let mut redacted = String::with_capacity(sql.len());
+    let mut params = 0;
+    let mut chars = sql.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                // '' inside a literal is an escaped quote
+                loop {
+                    match chars.next() {
+                        Some('\'') if chars.peek() == Some(&'\'') => {
+                            chars.next();
+                        }
+                        Some('\'') | None => break,
+                        Some(_) => {}
+                    }
+                }
+                redacted.push_str("'?'");
+            }
+            '?' | '$' => {
+                params += 1;
+                redacted.push(c);
+            }
+            c if c.is_whitespace() => {
+                if !redacted.is_empty() && !redacted.ends_with(' ') {
+                    redacted.push(' ');
+                }
+            }
+            c => redacted.push(c),
+        }
+    }
+
+    (redacted.trim_end().to_string(), params)
+}
+
+/// `EXPLAIN QUERY PLAN` of a single statement as an indented tree; unbound
+/// parameters are NULL, which doesn't change the plan
+async fn explain(pool: &DbPool, sql: &str) -> Option<String> {
+    let sql = sql.trim().trim_end_matches(';');
+    if sql.contains(';') {
+        return None;
+    }
+
+    let rows: Vec<(i64, i64, i64, String)> =
+        match sqlx::query_as(&format!("EXPLAIN QUERY PLAN {}", sql))
+            .fetch_all(pool)
+            .await
+        {
+            Ok(rows) => rows,
+            Err(e) => {
+                tracing::debug!("Could not explain a slow query: {}", e);
+                return None;
+            }
+        };
+
+    Some(format_plan(&rows))
+}
+
+/// Indent each step of the plan under its parent step
+fn format_plan(rows: &[(i64, i64, i64, String)]) -> String {
+    let mut depths: Vec<(i64, usize)> = Vec::new();
+    let mut lines = Vec::with_capacity(rows.len());
+
+    for (id, parent, _, detail) in rows {
+        let depth = depths
+            .iter()
+            .find(|(step, _)| step == parent)
+            .map(|(_, depth)| depth + 1)
+            .unwrap_or(0);
+        depths.push((*id, depth));
+        lines.push(format!("{}{}", "  ".repeat(depth), detail));
+    }
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::create_test_db;
+
+    #[test]
+    fn test_redact() {
+        let (sql, params) = redact(
+            "SELECT * FROM movies\n    WHERE user_id = ? AND title LIKE ?\n    AND location = 'Shelf ''A'''",
+        );
+        assert_eq!(
+            sql,
+            "SELECT * FROM movies WHERE user_id = ? AND title LIKE ? AND location = '?'"
+        );
+        assert_eq!(params, 2);
+
+        let (sql, params) = redact("UPDATE movies SET title = $1 WHERE id = $2");
+        assert_eq!(sql, "UPDATE movies SET title = $1 WHERE id = $2");
+        assert_eq!(params, 2);
+    }
+
+    #[test]
+    fn test_is_explain() {
+        assert!(is_explain("EXPLAIN QUERY PLAN SELECT 1"));
+        assert!(is_explain("  explain select 1"));
+        assert!(!is_explain("SELECT 1"));
+        assert!(!is_explain("EXP"));
+    }
+
+    #[tokio::test]
+    async fn test_explain_slow_query() {
+        let pool = create_test_db().await;
+
+        let plan = explain(
+            &pool,
+            "SELECT * FROM movies WHERE user_id = ? AND title LIKE ? ORDER BY title",
+        )
+        .await
+        .unwrap();
+        assert!(plan.contains("movies"), "{plan}");
+
+        // Several statements can't be explained at once
+        assert_eq!(explain(&pool, "SELECT 1; SELECT 2").await, None);
+        assert_eq!(explain(&pool, "SELECT * FROM nowhere").await, None);
+    }
+
+    #[test]
+    fn test_format_plan() {
+        let rows = vec![
+            (2, 0, 0, "SCAN movies".to_string()),
+            (5, 0, 0, "CORRELATED SCALAR SUBQUERY 1".to_string()),
+            (
+                9,
+                5,
+                0,
+                "SEARCH loans USING INDEX idx_loans_movie".to_string(),
+            ),
+            (20, 0, 0, "USE TEMP B-TREE FOR ORDER BY".to_string()),
+        ];
+        assert_eq!(
+            format_plan(&rows),
+            "SCAN movies\nCORRELATED SCALAR SUBQUERY 1\n  SEARCH loans USING INDEX idx_loans_movie\nUSE TEMP B-TREE FOR ORDER BY"
+        );
+    }
+}
@@ -45,17 +45,31 @@ pub enum Error {
 
     #[error("Configuration error: {0}")]
     Configuration(String),
+
+    #[error("TMDB API key not configured. Please set it in Settings.")]
+    TmdbNotConfigured,
+
+    #[error("Database migration failed: {0}")]
+    Migration(String),
 }
 
 impl Error {
     pub fn status_code(&self) -> u16 {
         match self {
-            Error::InvalidCredentials | Error::TokenExpired => 401,
+            Error::InvalidCredentials | Error::TokenExpired | Error::Auth(_) => 401,
             Error::Forbidden => 403,
             Error::NotFound | Error::UserNotFound => 404,
             Error::Duplicate(_) | Error::Validation(_) | Error::InvalidResetToken => 400,
-            Error::Configuration(_) => 503,
+            Error::Configuration(_) | Error::TmdbNotConfigured => 503,
             _ => 500,
         }
     }
+
+    /// Machine-readable code for errors clients handle specifically
+    pub fn code(&self) -> Option<&'static str> {
+        match self {
+            Error::TmdbNotConfigured => Some("tmdb_unconfigured"),
+            _ => None,
+        }
+    }
 }
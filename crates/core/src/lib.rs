@@ -2,6 +2,7 @@ pub mod config;
 pub mod db;
 pub mod error;
 pub mod models;
+pub mod parsing;
 pub mod services;
 
 #[cfg(test)]
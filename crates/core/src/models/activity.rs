@@ -0,0 +1,46 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::EntityType;
+
+/// What happened to an entry in the activity log
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ActivityAction {
+    Create,
+    Update,
+    Delete,
+    /// Created by a CSV or DVD Profiler import
+    Import,
+}
+
+/// Change of a movie, series or collection, newest first in `GET /activity`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityEntry {
+    pub id: i64,
+    pub action: ActivityAction,
+    pub entity_type: EntityType,
+    pub entity_id: Uuid,
+    /// Title at the time of the change, so deleted entries can be recognized
+    pub title: Option<String>,
+    /// Changed fields of an update: `{"field": {"old": ..., "new": ...}}`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub changes: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Query of `GET /activity`; all filters are optional
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ActivityFilter {
+    pub entity_type: Option<EntityType>,
+    pub entity_id: Option<Uuid>,
+    pub action: Option<ActivityAction>,
+    /// First day included (UTC)
+    pub from: Option<NaiveDate>,
+    /// Last day included (UTC)
+    pub to: Option<NaiveDate>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
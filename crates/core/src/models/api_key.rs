@@ -0,0 +1,123 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Permission granted to an API key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ApiScope {
+    #[serde(rename = "movies:read")]
+    MoviesRead,
+    #[serde(rename = "movies:write")]
+    MoviesWrite,
+    #[serde(rename = "admin")]
+    Admin,
+}
+
+impl ApiScope {
+    pub const ALL: [ApiScope; 3] = [ApiScope::MoviesRead, ApiScope::MoviesWrite, ApiScope::Admin];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiScope::MoviesRead => "movies:read",
+            ApiScope::MoviesWrite => "movies:write",
+            ApiScope::Admin => "admin",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.as_str() == key.trim())
+    }
+
+    /// Whether this scope allows access to routes requiring `required`.
+    /// `admin` grants everything, `movies:write` includes `movies:read`.
+    pub fn grants(&self, required: ApiScope) -> bool {
+        match self {
+            ApiScope::Admin => true,
+            ApiScope::MoviesWrite => {
+                matches!(required, ApiScope::MoviesRead | ApiScope::MoviesWrite)
+            }
+            ApiScope::MoviesRead => required == ApiScope::MoviesRead,
+        }
+    }
+
+    /// Parse a comma-separated scope list as stored in the database (unknown scopes are ignored)
+    pub fn parse_list(value: &str) -> Vec<ApiScope> {
+        value.split(',').filter_map(ApiScope::from_key).collect()
+    }
+}
+
+/// API key as stored in the database
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ApiKey {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub token_hash: String,
+    pub token_prefix: String,
+    pub scopes: String,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+/// API key without the hash, returned to the owner
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiKeyPublic {
+    pub id: Uuid,
+    pub name: String,
+    pub token_prefix: String,
+    pub scopes: Vec<ApiScope>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+impl From<ApiKey> for ApiKeyPublic {
+    fn from(key: ApiKey) -> Self {
+        Self {
+            id: key.id,
+            scopes: ApiScope::parse_list(&key.scopes),
+            name: key.name,
+            token_prefix: key.token_prefix,
+            created_at: key.created_at,
+            last_used_at: key.last_used_at,
+        }
+    }
+}
+
+/// API key in a backup. The hash (never the key itself) is included, so
+/// restored keys keep working on the new instance.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedApiKey {
+    pub name: String,
+    pub token_hash: String,
+    pub token_prefix: String,
+    pub scopes: Vec<ApiScope>,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+impl From<ApiKey> for ExportedApiKey {
+    fn from(key: ApiKey) -> Self {
+        Self {
+            name: key.name,
+            token_hash: key.token_hash,
+            token_prefix: key.token_prefix,
+            scopes: ApiScope::parse_list(&key.scopes),
+            created_at: key.created_at,
+            last_used_at: key.last_used_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateApiKey {
+    pub name: String,
+    pub scopes: Vec<ApiScope>,
+}
+
+/// Response when creating a key; `token` is only returned this once
+#[derive(Debug, Serialize)]
+pub struct CreatedApiKey {
+    pub token: String,
+    pub key: ApiKeyPublic,
+}
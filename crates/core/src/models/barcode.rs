@@ -0,0 +1,35 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Cached result of a barcode lookup; `title` is `None` for barcodes no
+/// provider knew
+#[derive(Debug, Clone, Default, Serialize, Deserialize, sqlx::FromRow)]
+pub struct BarcodeCacheEntry {
+    pub barcode: String,
+    pub title: Option<String>,
+    pub vendor: Option<String>,
+    pub category: Option<String>,
+    /// Barcode provider the title came from
+    pub source: Option<String>,
+    pub disc_type: Option<String>,
+    /// Best TMDB match
+    pub tmdb_id: Option<i64>,
+    /// TMDB candidates as JSON, replayed on a cache hit
+    #[serde(skip)]
+    pub tmdb_results: Option<String>,
+    /// Scans answered from this entry
+    pub hits: i64,
+    /// Lookups that went to the providers for this barcode
+    pub misses: i64,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Totals over the cache (counters are reset when the cache is cleared)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, sqlx::FromRow)]
+pub struct BarcodeCacheStats {
+    pub entries: i64,
+    pub expired: i64,
+    pub hits: i64,
+    pub misses: i64,
+}
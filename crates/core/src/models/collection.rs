@@ -61,6 +61,19 @@ pub struct Collection {
     pub updated_at: DateTime<Utc>,
 }
 
+/// Collection with aggregates over its items, for the collections overview
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct CollectionWithStats {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub collection: Collection,
+    pub item_count: i64,
+    /// Minutes; series count with all their episodes
+    pub total_runtime: i64,
+    /// Share of items marked watched (0.0 - 1.0)
+    pub watched_fraction: f64,
+}
+
 /// Links movies/series to a collection
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct CollectionItem {
@@ -73,6 +86,23 @@ pub struct CollectionItem {
     pub created_at: DateTime<Utc>,
 }
 
+/// Collection item with the details needed to render it (`?expand=true`)
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct CollectionItemDetails {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub item: CollectionItem,
+    /// `None` if the movie or series no longer exists
+    pub title: Option<String>,
+    pub year: Option<i32>,
+    pub watched: bool,
+    /// Thumbnail URL, if the movie or series has a poster
+    #[sqlx(skip)]
+    pub thumb: Option<String>,
+    #[serde(skip)]
+    pub has_poster: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type)]
 #[sqlx(type_name = "TEXT", rename_all = "lowercase")]
 pub enum CollectionItemType {
@@ -108,9 +138,18 @@ pub struct UpdateCollection {
 
 #[derive(Debug, Deserialize, Default)]
 pub struct CollectionFilter {
+    /// Matches title or personal title (substring) or the exact barcode
     pub search: Option<String>,
+    /// title (default), sort_title, created_at, updated_at, purchase_date or price
     pub sort_by: Option<String>,
+    /// asc (default) or desc
     pub sort_order: Option<String>,
+    pub disc_type: Option<String>,
+    /// Only collections containing this movie
+    pub contains_movie: Option<Uuid>,
     pub limit: Option<i64>,
     pub offset: Option<i64>,
+    /// Include item count, total runtime and watched fraction
+    #[serde(default)]
+    pub with_stats: bool,
 }
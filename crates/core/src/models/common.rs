@@ -31,14 +31,6 @@ pub enum Condition {
     Poor,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type)]
-#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
-pub enum VideoStandard {
-    Ntsc,
-    Pal,
-    Secam,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LentInfo {
     pub lent_to: Option<String>,
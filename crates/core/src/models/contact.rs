@@ -0,0 +1,49 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Someone movies are lent to. Loans are linked by name: a movie whose
+/// `lent_to` matches the contact's name (case-insensitive) counts as lent to them.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Contact {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub notes: Option<String>,
+    /// Movies the contact currently has
+    #[sqlx(default)]
+    pub active_loans: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateContact {
+    pub name: String,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct UpdateContact {
+    /// Renaming also renames `lent_to` on the movies the contact has
+    pub name: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub notes: Option<String>,
+}
+
+/// One loan of a movie to a contact
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct ContactLoan {
+    pub id: i64,
+    pub movie_id: Uuid,
+    pub title: String,
+    pub lent_at: DateTime<Utc>,
+    pub due: Option<NaiveDate>,
+    /// `None` while the movie is still lent
+    pub returned_at: Option<DateTime<Utc>>,
+}
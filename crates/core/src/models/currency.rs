@@ -0,0 +1,52 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Where an exchange rate comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum RateSource {
+    /// `exchange_rates` setting
+    Manual,
+    /// Fetched from `exchange_rate_url`
+    Fetched,
+}
+
+/// Units of `currency` per euro
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct ExchangeRate {
+    pub currency: String,
+    pub rate: f64,
+    pub source: RateSource,
+    /// `None` for manual rates
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Value report totals converted into one currency
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConvertedTotals {
+    pub currency: String,
+    pub purchase_total: f64,
+    pub value_total: f64,
+    pub disposed_purchase_total: f64,
+    pub disposal_total: f64,
+    /// Currencies without exchange rate; their amounts are left out
+    pub unconverted: Vec<String>,
+}
+
+/// ISO 4217 code for a currency code, symbol or name ("€", "euro", "usd")
+pub fn normalize_currency(input: &str) -> Result<String, String> {
+    let trimmed = input.trim();
+    let code = match trimmed.to_lowercase().as_str() {
+        "€" | "euro" | "eur" => "EUR".to_string(),
+        "$" | "us$" | "dollar" => "USD".to_string(),
+        "£" | "pound" => "GBP".to_string(),
+        "¥" | "yen" => "JPY".to_string(),
+        "fr." | "sfr" | "franken" => "CHF".to_string(),
+        other if other.len() == 3 && other.chars().all(|c| c.is_ascii_alphabetic()) => {
+            other.to_uppercase()
+        }
+        _ => return Err(format!("Unknown currency: {}", trimmed)),
+    };
+    Ok(code)
+}
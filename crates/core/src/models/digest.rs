@@ -0,0 +1,63 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// When a user gets the weekly digest email
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DigestSettings {
+    pub enabled: bool,
+    /// Day of the week, 0 = Monday to 6 = Sunday
+    pub weekday: i32,
+    /// Hour of the day in the user's timezone (0-23)
+    pub hour: i32,
+}
+
+impl Default for DigestSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            weekday: 6,
+            hour: 9,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct DigestMovie {
+    pub id: Uuid,
+    pub title: String,
+    pub production_year: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct DigestLoan {
+    pub id: Uuid,
+    pub title: String,
+    pub lent_to: String,
+    pub lent_due: NaiveDate,
+}
+
+/// Summary of a user's week, sent as email by the digest job
+#[derive(Debug, Clone, Serialize)]
+pub struct Digest {
+    pub user_id: Uuid,
+    pub username: String,
+    pub email: String,
+    /// First day of the summarized week
+    pub since: NaiveDate,
+    /// Day the digest is for, in the user's timezone
+    pub today: NaiveDate,
+    /// Movies added to the collection since `since`
+    pub additions: Vec<DigestMovie>,
+    /// Lent movies due within the coming week or overdue
+    pub loans_due: Vec<DigestLoan>,
+    /// A few unwatched movies from the collection
+    pub suggestions: Vec<DigestMovie>,
+}
+
+impl Digest {
+    /// Nothing to report; no email is sent
+    pub fn is_empty(&self) -> bool {
+        self.additions.is_empty() && self.loans_due.is_empty() && self.suggestions.is_empty()
+    }
+}
@@ -0,0 +1,385 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Playback region of a disc or player.
+///
+/// Stored as `R0`–`R8` (DVD, `R0` = region free) and `RA`–`RC` (Blu-ray).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RegionCode {
+    Dvd(u8),
+    BluRay(char),
+}
+
+impl RegionCode {
+    pub const FREE: RegionCode = RegionCode::Dvd(0);
+}
+
+impl fmt::Display for RegionCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegionCode::Dvd(n) => write!(f, "R{}", n),
+            RegionCode::BluRay(c) => write!(f, "R{}", c),
+        }
+    }
+}
+
+impl FromStr for RegionCode {
+    type Err = String;
+
+    /// Accepts `2`, `R2`, `B`, `RB` and `free`/`all` (case-insensitive)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.trim().to_lowercase();
+        if matches!(lower.as_str(), "0" | "r0" | "free" | "all" | "rf") {
+            return Ok(RegionCode::FREE);
+        }
+        let code = match lower.strip_prefix('r') {
+            Some(rest) if !rest.is_empty() => rest,
+            _ => lower.as_str(),
+        };
+        match code {
+            "1" | "2" | "3" | "4" | "5" | "6" | "7" | "8" => {
+                Ok(RegionCode::Dvd(code.as_bytes()[0] - b'0'))
+            }
+            "a" | "b" | "c" => Ok(RegionCode::BluRay(
+                code.chars().next().unwrap_or('a').to_ascii_uppercase(),
+            )),
+            _ => Err(format!("Unknown region code: {}", s.trim())),
+        }
+    }
+}
+
+/// Parse a free-text region list like `"2, 4"`, `"Region B"` or `"ABC"`.
+/// Returns the codes sorted and deduplicated; empty input gives an empty list.
+pub fn parse_region_codes(input: &str) -> Result<Vec<RegionCode>, String> {
+    let lower = input.to_lowercase().replace("region", " ");
+    let mut codes = Vec::new();
+
+    for token in lower
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|t| !t.is_empty())
+    {
+        let letters = token.strip_prefix('r').unwrap_or(token);
+        if letters.len() > 1 && letters.chars().all(|c| matches!(c, 'a' | 'b' | 'c')) {
+            // "ABC", "RAB"
+            codes.extend(
+                letters
+                    .chars()
+                    .map(|c| RegionCode::BluRay(c.to_ascii_uppercase())),
+            );
+        } else {
+            codes.push(token.parse::<RegionCode>()?);
+        }
+    }
+
+    codes.sort();
+    codes.dedup();
+    Ok(codes)
+}
+
+/// Stored form of a region list: `"R2,RB"`; `None` for empty input
+pub fn normalize_region_codes(input: &str) -> Result<Option<String>, String> {
+    let codes = parse_region_codes(input)?;
+    if codes.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(
+        codes
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(","),
+    ))
+}
+
+/// Whether a disc with `disc` regions plays on a player set up for `player`.
+/// Discs without region information are assumed to play.
+pub fn region_plays_on(disc: &[RegionCode], player: &[RegionCode]) -> bool {
+    disc.is_empty()
+        || disc.contains(&RegionCode::FREE)
+        || disc.iter().any(|code| player.contains(code))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum VideoStandard {
+    Pal,
+    Ntsc,
+}
+
+impl VideoStandard {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            VideoStandard::Pal => "PAL",
+            VideoStandard::Ntsc => "NTSC",
+        }
+    }
+}
+
+impl FromStr for VideoStandard {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_uppercase().as_str() {
+            "PAL" => Ok(VideoStandard::Pal),
+            "NTSC" => Ok(VideoStandard::Ntsc),
+            _ => Err(format!(
+                "Unknown video standard: {} (expected PAL or NTSC)",
+                s.trim()
+            )),
+        }
+    }
+}
+
+/// Stored form of a video standard; `None` for empty input
+pub fn normalize_video_standard(input: &str) -> Result<Option<String>, String> {
+    if input.trim().is_empty() {
+        return Ok(None);
+    }
+    input
+        .parse::<VideoStandard>()
+        .map(|v| Some(v.as_str().to_string()))
+}
+
+/// Physical condition of a disc, worst to best
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConditionGrade {
+    Poor,
+    Fair,
+    Good,
+    VeryGood,
+    NearMint,
+    Mint,
+}
+
+impl ConditionGrade {
+    pub const ALL: [ConditionGrade; 6] = [
+        ConditionGrade::Poor,
+        ConditionGrade::Fair,
+        ConditionGrade::Good,
+        ConditionGrade::VeryGood,
+        ConditionGrade::NearMint,
+        ConditionGrade::Mint,
+    ];
+
+    /// Stored form in `movies.condition`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ConditionGrade::Poor => "poor",
+            ConditionGrade::Fair => "fair",
+            ConditionGrade::Good => "good",
+            ConditionGrade::VeryGood => "very_good",
+            ConditionGrade::NearMint => "near_mint",
+            ConditionGrade::Mint => "mint",
+        }
+    }
+}
+
+impl FromStr for ConditionGrade {
+    type Err = String;
+
+    /// Accepts the stored form and common English/German descriptions
+    /// ("Sehr gut", "wie neu", "OVP")
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = s
+            .trim()
+            .to_lowercase()
+            .replace(['-', '_'], " ")
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        match normalized.as_str() {
+            "mint" | "new" | "neu" | "sealed" | "ovp" | "originalverpackt" => {
+                Ok(ConditionGrade::Mint)
+            }
+            "near mint" | "like new" | "wie neu" | "neuwertig" => Ok(ConditionGrade::NearMint),
+            "very good" | "sehr gut" => Ok(ConditionGrade::VeryGood),
+            "good" | "gut" => Ok(ConditionGrade::Good),
+            "fair" | "acceptable" | "akzeptabel" | "befriedigend" => Ok(ConditionGrade::Fair),
+            "poor" | "damaged" | "schlecht" | "beschädigt" => Ok(ConditionGrade::Poor),
+            _ => Err(format!("Unknown condition: {}", s.trim())),
+        }
+    }
+}
+
+/// Stored form of a condition; `None` for empty input
+pub fn normalize_condition(input: &str) -> Result<Option<String>, String> {
+    if input.trim().is_empty() {
+        return Ok(None);
+    }
+    input
+        .parse::<ConditionGrade>()
+        .map(|c| Some(c.as_str().to_string()))
+}
+
+/// Items that came with a disc. The slipcover is stored in `movies.slip_cover`,
+/// the others as a comma separated list in `movies.included_extras`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IncludedExtra {
+    Slipcover,
+    Booklet,
+    DigitalCode,
+    Poster,
+    ArtCards,
+    BonusDisc,
+}
+
+impl IncludedExtra {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            IncludedExtra::Slipcover => "slipcover",
+            IncludedExtra::Booklet => "booklet",
+            IncludedExtra::DigitalCode => "digital_code",
+            IncludedExtra::Poster => "poster",
+            IncludedExtra::ArtCards => "art_cards",
+            IncludedExtra::BonusDisc => "bonus_disc",
+        }
+    }
+
+    fn from_stored(s: &str) -> Option<Self> {
+        [
+            IncludedExtra::Slipcover,
+            IncludedExtra::Booklet,
+            IncludedExtra::DigitalCode,
+            IncludedExtra::Poster,
+            IncludedExtra::ArtCards,
+            IncludedExtra::BonusDisc,
+        ]
+        .into_iter()
+        .find(|e| e.as_str() == s.trim())
+    }
+}
+
+/// Extras from the stored columns; unknown entries are ignored
+pub fn included_extras(slip_cover: bool, stored: Option<&str>) -> Vec<IncludedExtra> {
+    let mut extras: Vec<IncludedExtra> = stored
+        .unwrap_or("")
+        .split(',')
+        .filter_map(IncludedExtra::from_stored)
+        .filter(|e| *e != IncludedExtra::Slipcover)
+        .collect();
+    if slip_cover {
+        extras.push(IncludedExtra::Slipcover);
+    }
+    extras.sort();
+    extras.dedup();
+    extras
+}
+
+/// Condition and extras of a disc, edited via `/movies/{id}/condition`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscCondition {
+    #[serde(default)]
+    pub grade: Option<ConditionGrade>,
+    #[serde(default)]
+    pub extras: Vec<IncludedExtra>,
+}
+
+/// Disc that won't play on the user's players, or whose disc fields can't be read
+#[derive(Debug, Clone, Serialize)]
+pub struct RegionReportEntry {
+    pub id: Uuid,
+    pub title: String,
+    pub disc_type: Option<String>,
+    pub region_codes: Option<String>,
+    pub video_standard: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RegionReport {
+    pub player_regions: Vec<String>,
+    /// Discs locked to regions none of the players support
+    pub mismatched: Vec<RegionReportEntry>,
+    /// Discs with region codes or video standard that couldn't be parsed
+    pub invalid: Vec<RegionReportEntry>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_region_codes() {
+        let codes = |s: &str| {
+            parse_region_codes(s)
+                .unwrap()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(codes("2"), vec!["R2"]);
+        assert_eq!(codes("R2, r4"), vec!["R2", "R4"]);
+        assert_eq!(codes("Region B"), vec!["RB"]);
+        assert_eq!(codes("ABC"), vec!["RA", "RB", "RC"]);
+        assert_eq!(codes("B/2"), vec!["R2", "RB"]);
+        assert_eq!(codes("free"), vec!["R0"]);
+        assert_eq!(codes("2;2"), vec!["R2"]);
+        assert!(codes("  ").is_empty());
+    }
+
+    #[test]
+    fn test_parse_region_codes_rejects_unknown() {
+        assert!(parse_region_codes("9").is_err());
+        assert!(parse_region_codes("RD").is_err());
+        assert!(parse_region_codes("Europe").is_err());
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(
+            normalize_region_codes("b, 2").unwrap().as_deref(),
+            Some("R2,RB")
+        );
+        assert_eq!(normalize_region_codes("").unwrap(), None);
+        assert_eq!(
+            normalize_video_standard(" pal ").unwrap().as_deref(),
+            Some("PAL")
+        );
+        assert_eq!(normalize_video_standard("").unwrap(), None);
+        assert!(normalize_video_standard("SECAM").is_err());
+    }
+
+    #[test]
+    fn test_condition_grades() {
+        assert_eq!("Sehr gut".parse(), Ok(ConditionGrade::VeryGood));
+        assert_eq!("near_mint".parse(), Ok(ConditionGrade::NearMint));
+        assert_eq!("Wie  neu".parse(), Ok(ConditionGrade::NearMint));
+        assert_eq!("OVP".parse(), Ok(ConditionGrade::Mint));
+        assert!("zerkratzt".parse::<ConditionGrade>().is_err());
+        assert!(ConditionGrade::Mint > ConditionGrade::VeryGood);
+        assert_eq!(normalize_condition("gut").unwrap().as_deref(), Some("good"));
+    }
+
+    #[test]
+    fn test_included_extras() {
+        assert_eq!(
+            included_extras(true, Some("digital_code,booklet,unknown")),
+            vec![
+                IncludedExtra::Slipcover,
+                IncludedExtra::Booklet,
+                IncludedExtra::DigitalCode
+            ]
+        );
+        // The slipcover column wins over a stale list entry
+        assert!(included_extras(false, Some("slipcover")).is_empty());
+        assert!(included_extras(false, None).is_empty());
+    }
+
+    #[test]
+    fn test_region_plays_on() {
+        let player = parse_region_codes("R2,RB").unwrap();
+        assert!(region_plays_on(
+            &parse_region_codes("2,4").unwrap(),
+            &player
+        ));
+        assert!(region_plays_on(&parse_region_codes("B").unwrap(), &player));
+        assert!(region_plays_on(&parse_region_codes("0").unwrap(), &player));
+        assert!(region_plays_on(&[], &player));
+        assert!(!region_plays_on(&parse_region_codes("1").unwrap(), &player));
+        assert!(!region_plays_on(&parse_region_codes("A").unwrap(), &player));
+    }
+}
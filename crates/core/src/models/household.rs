@@ -0,0 +1,45 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Users who see each other's movies; see `MovieFilter::include_household`
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Household {
+    pub id: Uuid,
+    pub name: String,
+    /// Creator; the only one who can invite and remove members
+    pub owner_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    #[sqlx(skip)]
+    pub members: Vec<HouseholdMember>,
+    /// Invitations not accepted yet; only listed for the owner
+    #[sqlx(skip)]
+    pub invitations: Vec<HouseholdInvitation>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct HouseholdMember {
+    pub user_id: Uuid,
+    pub username: String,
+    pub joined_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateHousehold {
+    pub name: String,
+}
+
+/// A user asked to join a household; they become a member by accepting
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct HouseholdInvitation {
+    pub household_id: Uuid,
+    pub household_name: String,
+    pub user_id: Uuid,
+    pub username: String,
+    pub invited_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct InviteHouseholdMember {
+    pub username: String,
+}
@@ -0,0 +1,36 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Named CSV column mapping, chosen by name when importing a CSV export of
+/// another collection manager
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportPreset {
+    pub name: String,
+    /// Column of the file (matched case-insensitively) to the column of our
+    /// own CSV format it is read as, e.g. `"UPC" -> "Barcode"`. Several file
+    /// columns may feed the same column; the first non-empty one in the file
+    /// is used.
+    pub columns: BTreeMap<String, String>,
+    /// Separator of multi-value columns (actors, genres, tags, ...) in the
+    /// file; they are stored comma-separated like our own
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub list_separator: Option<String>,
+    /// Per column of ours, values of the file (matched case-insensitively)
+    /// replaced on import, e.g. `"Watched": {"completed": "true"}`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub values: BTreeMap<String, BTreeMap<String, String>>,
+    /// Shipped with the app; can't be changed or deleted
+    #[serde(default, skip_deserializing)]
+    pub builtin: bool,
+}
+
+/// Body of `PUT /import/presets/{name}`
+#[derive(Debug, Clone, Deserialize)]
+pub struct SaveImportPreset {
+    pub columns: BTreeMap<String, String>,
+    #[serde(default)]
+    pub list_separator: Option<String>,
+    #[serde(default)]
+    pub values: BTreeMap<String, BTreeMap<String, String>>,
+}
@@ -0,0 +1,44 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::UserRole;
+
+/// Admin-created invite; `POST /auth/register` accepts its code while open
+/// registration is disabled
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Invite {
+    pub id: Uuid,
+    #[serde(skip)]
+    pub code_hash: String,
+    /// Role of the accounts created with the invite
+    pub role: UserRole,
+    pub max_uses: i64,
+    pub uses: i64,
+    /// Label for the admin, e.g. who got the invite
+    pub note: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CreateInvite {
+    #[serde(default)]
+    pub role: UserRole,
+    /// Accounts that can be created with the code; 1 if not given
+    #[serde(default)]
+    pub max_uses: Option<i64>,
+    #[serde(default)]
+    pub note: Option<String>,
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// A new invite with its code; the code is only returned here
+#[derive(Debug, Clone, Serialize)]
+pub struct CreatedInvite {
+    pub code: String,
+    #[serde(flatten)]
+    pub invite: Invite,
+}
@@ -0,0 +1,109 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// When a user is reminded of movies they lent out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoanReminderSettings {
+    pub enabled: bool,
+    /// Days before the due date for the first reminder (0 = on the due date)
+    pub days_before: i32,
+}
+
+impl Default for LoanReminderSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            days_before: 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LoanReminderKind {
+    /// The due date is within the user's `days_before`
+    DueSoon,
+    /// The due date has passed
+    Overdue,
+}
+
+impl LoanReminderKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LoanReminderKind::DueSoon => "due_soon",
+            LoanReminderKind::Overdue => "overdue",
+        }
+    }
+}
+
+/// A lent movie to remind its owner of
+#[derive(Debug, Clone, Serialize)]
+pub struct LoanReminder {
+    pub kind: LoanReminderKind,
+    pub user_id: Uuid,
+    pub movie_id: Uuid,
+    pub title: String,
+    pub lent_to: String,
+    pub lent_due: NaiveDate,
+    /// Owner's name and address for the reminder email
+    #[serde(skip)]
+    pub username: String,
+    #[serde(skip)]
+    pub email: String,
+}
+
+impl LoanReminder {
+    /// Where the movie is and when it is due, shown under the title
+    pub fn text(&self) -> String {
+        let due = self.lent_due.format("%d.%m.%Y");
+        match self.kind {
+            LoanReminderKind::DueSoon => {
+                format!("Ist bei {} und soll am {} zurückkommen.", self.lent_to, due)
+            }
+            LoanReminderKind::Overdue => {
+                format!("Ist bei {} seit {} überfällig.", self.lent_to, due)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum BorrowRequestStatus {
+    Pending,
+    Approved,
+    Declined,
+}
+
+/// A guest asking to borrow one of the owner's movies
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct BorrowRequest {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub movie_id: Uuid,
+    /// Title of the requested movie
+    pub title: String,
+    pub requester_name: String,
+    /// Email or phone number, as given by the guest
+    pub requester_contact: Option<String>,
+    pub message: Option<String>,
+    pub status: BorrowRequestStatus,
+    pub created_at: DateTime<Utc>,
+    pub decided_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateBorrowRequest {
+    pub name: String,
+    pub contact: Option<String>,
+    pub message: Option<String>,
+}
+
+/// Approving lends the movie to the requester
+#[derive(Debug, Default, Deserialize)]
+pub struct ApproveBorrowRequest {
+    #[serde(default)]
+    pub due: Option<NaiveDate>,
+}
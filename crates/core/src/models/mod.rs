@@ -1,13 +1,51 @@
+pub mod activity;
+pub mod api_key;
+pub mod barcode;
 pub mod collection;
 pub mod common;
+pub mod contact;
+pub mod currency;
+pub mod digest;
+pub mod disc;
+pub mod household;
+pub mod import_preset;
+pub mod invite;
+pub mod loan;
 pub mod movie;
+pub mod notification;
+pub mod poster_retry;
+pub mod search;
 pub mod series;
 pub mod setting;
+pub mod share;
+pub mod sync;
+pub mod tombstone;
 pub mod user;
+pub mod valuation;
+pub mod watch;
 
+pub use activity::*;
+pub use api_key::*;
+pub use barcode::*;
 pub use collection::*;
 pub use common::*;
+pub use contact::*;
+pub use currency::*;
+pub use digest::*;
+pub use disc::*;
+pub use household::*;
+pub use import_preset::*;
+pub use invite::*;
+pub use loan::*;
 pub use movie::*;
+pub use notification::*;
+pub use poster_retry::*;
+pub use search::*;
 pub use series::*;
 pub use setting::*;
+pub use share::*;
+pub use sync::*;
+pub use tombstone::*;
 pub use user::*;
+pub use valuation::*;
+pub use watch::*;
@@ -2,6 +2,8 @@ use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::models::{ConditionGrade, ConvertedTotals, IncludedExtra};
+
 /// Movie struct with proper Uuid types
 /// UUIDs are stored as BLOB (16 bytes) in SQLite
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
@@ -62,9 +64,29 @@ pub struct Movie {
     // Collection support
     pub is_collection: bool,
     pub parent_collection_id: Option<Uuid>,
+    /// Disc of the parent collection this film is on
+    #[sqlx(default)]
+    pub disc_number: Option<i32>,
+    /// Linked TMDB collection (box sets only)
+    pub tmdb_collection_id: Option<i64>,
+    /// Parts of the TMDB collection owned by the user
+    pub collection_owned_parts: Option<i32>,
+    pub collection_total_parts: Option<i32>,
+    /// Owned parts / total parts in percent, refreshed during TMDB enrichment
+    pub collection_completeness: Option<f64>,
 
     // User Status
     pub watched: bool,
+    /// Number of logged viewings, see `WatchEvent`; only filled in lists
+    #[serde(default)]
+    #[sqlx(default)]
+    pub times_watched: i64,
+    #[serde(default)]
+    #[sqlx(default)]
+    pub last_watched_at: Option<NaiveDate>,
+    /// Pinned by the user for quick access
+    #[serde(default)]
+    pub favorite: bool,
     pub digital_copies: Option<String>,
     pub status: Option<String>,
 
@@ -74,6 +96,9 @@ pub struct Movie {
     pub cover_type: Option<String>,
     pub edition: Option<String>,
     pub extra_features: Option<String>,
+    /// Included items besides the slipcover, see [`crate::models::included_extras`]
+    #[sqlx(default)]
+    pub included_extras: Option<String>,
 
     // Financial
     pub purchase_date: Option<NaiveDate>,
@@ -90,6 +115,35 @@ pub struct Movie {
 
     // Location
     pub location: Option<String>,
+    #[serde(default)]
+    #[sqlx(default)]
+    pub missing: bool,
+    /// Derived by the database from disposition, status, `missing` and `lent_to`
+    #[serde(default)]
+    #[sqlx(default)]
+    pub availability: Option<MovieAvailability>,
+
+    // Disposition: set when the movie left the collection
+    #[sqlx(default)]
+    pub disposition: Option<Disposition>,
+    #[sqlx(default)]
+    pub disposed_at: Option<NaiveDate>,
+    #[sqlx(default)]
+    pub disposal_price: Option<f64>,
+    #[sqlx(default)]
+    pub disposal_currency: Option<String>,
+    #[sqlx(default)]
+    pub disposed_to: Option<String>,
+
+    // Attribution: users who added and last changed the movie
+    #[sqlx(default)]
+    pub added_by: Option<Uuid>,
+    #[sqlx(default)]
+    pub updated_by: Option<Uuid>,
+    /// Username of the owner; only set on other household members' movies
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[sqlx(default)]
+    pub owner_name: Option<String>,
 
     // Notes
     pub notes: Option<String>,
@@ -99,20 +153,228 @@ pub struct Movie {
     pub revenue: Option<i64>,
     pub spoken_languages: Option<String>,
 
-    // Poster image stored as BLOB in database
+    // Ratings from OMDb: IMDb score (0 - 10) and Rotten Tomatoes (percent)
+    #[sqlx(default)]
+    pub imdb_rating: Option<f64>,
+    #[sqlx(default)]
+    pub rotten_tomatoes: Option<i32>,
+
+    // Poster image, stored deduplicated in the images table (movies.poster_hash)
     // Skip serialization to avoid sending large BLOBs in JSON responses
     // Use #[sqlx(default)] so it defaults to None when not in SELECT (list() excludes it)
-    // But loads correctly when using SELECT * (get_by_id, export)
+    // But loads correctly when joined in (get_by_id, find_by_*)
     #[serde(skip)]
     #[sqlx(default)]
     pub poster_data: Option<Vec<u8>>,
 
+    // Wide backdrop from TMDB, also in the images table; served by /movies/:id/backdrop
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[sqlx(default)]
+    pub backdrop_hash: Option<String>,
+
     // Timestamps
     pub added_date: Option<NaiveDate>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// A movie with all fields, as returned by `GET /movies/:id` and delta syncs
+pub type MovieDetail = Movie;
+
+/// A movie in list responses: what lists and grids show, without descriptions,
+/// cast, technical and purchase details (see [`MovieDetail`]). The columns are
+/// [`MOVIE_SUMMARY_FIELDS`].
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct MovieSummary {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub barcode: Option<String>,
+    pub tmdb_id: Option<i64>,
+    pub title: String,
+    pub original_title: Option<String>,
+    pub sort_title: Option<String>,
+    pub personal_title: Option<String>,
+    pub personal_sort_title: Option<String>,
+    pub production_year: Option<i32>,
+    pub running_time: Option<i32>,
+    pub director: Option<String>,
+    pub rating: Option<String>,
+    pub personal_rating: Option<f64>,
+    pub disc_type: Option<String>,
+    pub media_type: Option<String>,
+    pub genres: Option<String>,
+    pub tags: Option<String>,
+    pub is_collection: bool,
+    pub parent_collection_id: Option<Uuid>,
+    pub disc_number: Option<i32>,
+    pub collection_completeness: Option<f64>,
+    pub watched: bool,
+    pub times_watched: i64,
+    pub last_watched_at: Option<NaiveDate>,
+    pub favorite: bool,
+    pub status: Option<String>,
+    pub condition: Option<String>,
+    pub location: Option<String>,
+    pub availability: Option<MovieAvailability>,
+    pub lent_to: Option<String>,
+    pub lent_due: Option<NaiveDate>,
+    pub disposition: Option<Disposition>,
+    /// Username of the owner; only set on other household members' movies
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[sqlx(default)]
+    pub owner_name: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Where a movie is in the buying workflow. Stored in `movies.status`;
+/// `NULL` means owned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MovieStatus {
+    Owned,
+    Wishlist,
+    Ordered,
+    Sold,
+}
+
+impl MovieStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MovieStatus::Owned => "owned",
+            MovieStatus::Wishlist => "wishlist",
+            MovieStatus::Ordered => "ordered",
+            MovieStatus::Sold => "sold",
+        }
+    }
+}
+
+/// Where a disc is right now. Computed by the database (`movies.availability`),
+/// wishlist entries have none.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum MovieAvailability {
+    Available,
+    Lent,
+    Missing,
+    Sold,
+    Ordered,
+}
+
+impl MovieAvailability {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MovieAvailability::Available => "available",
+            MovieAvailability::Lent => "lent",
+            MovieAvailability::Missing => "missing",
+            MovieAvailability::Sold => "sold",
+            MovieAvailability::Ordered => "ordered",
+        }
+    }
+}
+
+/// Movie counts per availability, for the stats endpoint
+#[derive(Debug, Clone, Default, Serialize, sqlx::FromRow)]
+pub struct MovieStats {
+    pub total: i64,
+    pub available: i64,
+    pub lent: i64,
+    pub missing: i64,
+    pub sold: i64,
+    pub ordered: i64,
+    pub wishlist: i64,
+    /// Movies with a viewing logged this year, and the number of viewings
+    pub watched_this_year: i64,
+    pub watches_this_year: i64,
+}
+
+/// Parse a status, including the spellings of DVD Profiler exports
+/// ("Wish List", "On Order") and German ones
+pub fn normalize_movie_status(input: &str) -> Result<MovieStatus, String> {
+    let value = input.trim().to_lowercase().replace([' ', '-', '_'], "");
+    match value.as_str() {
+        "" | "owned" | "collection" | "vorhanden" | "besitz" => Ok(MovieStatus::Owned),
+        "wishlist" | "wish" | "wunschliste" => Ok(MovieStatus::Wishlist),
+        "ordered" | "onorder" | "bestellt" => Ok(MovieStatus::Ordered),
+        "sold" | "verkauft" => Ok(MovieStatus::Sold),
+        _ => Err(format!(
+            "Unknown status '{}' (owned, wishlist, ordered or sold)",
+            input.trim()
+        )),
+    }
+}
+
+/// Turn a wishlist or ordered movie into an owned one
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AcquireMovie {
+    /// Defaults to today
+    #[serde(default)]
+    pub purchase_date: Option<NaiveDate>,
+    #[serde(default)]
+    pub price: Option<f64>,
+    #[serde(default)]
+    pub currency: Option<String>,
+    #[serde(default)]
+    pub purchase_place: Option<String>,
+    /// Fetch the TMDB data again (e.g. the wishlist entry was added from a search)
+    #[serde(default)]
+    pub refresh_tmdb: bool,
+}
+
+/// How a movie left the collection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum Disposition {
+    Sold,
+    GivenAway,
+    Traded,
+}
+
+/// Mark a movie as sold, given away or traded
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisposeMovie {
+    pub disposition: Disposition,
+    /// Defaults to today
+    #[serde(default)]
+    pub date: Option<NaiveDate>,
+    #[serde(default)]
+    pub price: Option<f64>,
+    #[serde(default)]
+    pub currency: Option<String>,
+    /// Buyer or recipient
+    #[serde(default)]
+    pub recipient: Option<String>,
+}
+
+/// Purchase, value and sale totals in one currency
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CurrencyTotals {
+    /// `None` for prices without currency
+    pub currency: Option<String>,
+    /// Purchase prices of movies still in the collection
+    pub purchase_total: f64,
+    /// Current values of movies still in the collection
+    pub value_total: f64,
+    /// Purchase prices of movies that left the collection
+    pub disposed_purchase_total: f64,
+    /// Sale proceeds
+    pub disposal_total: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ValueReport {
+    pub owned_count: i64,
+    pub disposed_count: i64,
+    /// Amounts are not converted, so there is one entry per currency
+    pub totals: Vec<CurrencyTotals>,
+    /// Totals in the requested or preferred currency, when one is set
+    pub converted: Option<ConvertedTotals>,
+    /// Date of the report in the user's timezone
+    pub as_of: Option<NaiveDate>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct CreateMovie {
     pub barcode: Option<String>,
@@ -163,7 +425,9 @@ pub struct UpdateMovie {
     pub group: Option<String>,
     pub is_collection: Option<bool>,
     pub parent_collection_id: Option<Uuid>,
+    pub disc_number: Option<i32>,
     pub watched: Option<bool>,
+    pub favorite: Option<bool>,
     pub digital_copies: Option<String>,
     pub status: Option<String>,
     pub condition: Option<String>,
@@ -179,27 +443,377 @@ pub struct UpdateMovie {
     pub value_price: Option<f64>,
     pub value_currency: Option<String>,
     pub lent_to: Option<String>,
+    /// Lend to a contact; takes precedence over `lent_to`
+    pub lent_to_contact: Option<Uuid>,
     pub lent_due: Option<NaiveDate>,
     pub location: Option<String>,
+    /// Mark the disc as missing or found again
+    pub missing: Option<bool>,
     pub notes: Option<String>,
     pub budget: Option<i64>,
     pub revenue: Option<i64>,
     pub spoken_languages: Option<String>,
+    pub imdb_rating: Option<f64>,
+    pub rotten_tomatoes: Option<i32>,
     pub poster_data: Option<Vec<u8>>,
 }
 
-#[derive(Debug, Deserialize, Default)]
+/// Movies whose `parent_collection_id` points to a collection that no longer exists
+#[derive(Debug, Clone, Serialize)]
+pub struct OrphanGroup {
+    pub parent_collection_id: Uuid,
+    pub movies: Vec<Movie>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrphanFixAction {
+    /// Clear the dangling parent link
+    Unlink,
+    /// Create a new collection per missing parent and attach the children to it
+    Regroup,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FixOrphans {
+    pub action: OrphanFixAction,
+    /// Only fix these groups (default: all)
+    pub parent_ids: Option<Vec<Uuid>>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct OrphanFixResult {
+    pub unlinked: u64,
+    pub collections_created: Vec<Movie>,
+}
+
+/// Fields set on every movie of a bulk edit; fields left out stay unchanged
+#[derive(Debug, Default, Deserialize)]
+pub struct BulkMovieFields {
+    pub location: Option<String>,
+    pub watched: Option<bool>,
+    pub favorite: Option<bool>,
+    pub disc_type: Option<String>,
+    pub tags: Option<String>,
+    pub status: Option<String>,
+    pub condition: Option<String>,
+    pub lent_to: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BulkUpdateMovies {
+    pub ids: Vec<Uuid>,
+    pub fields: BulkMovieFields,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkUpdateResult {
+    /// Movies that were changed; ids not owned by the user are skipped
+    pub updated: Vec<Uuid>,
+}
+
+/// Lightweight search-as-you-type result
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct MovieSuggestion {
+    pub id: Uuid,
+    pub title: String,
+    pub year: Option<i32>,
+    /// Thumbnail URL, if the movie has a poster
+    #[sqlx(skip)]
+    pub thumb: Option<String>,
+    #[serde(skip)]
+    pub has_poster: bool,
+}
+
+/// Entry of a user's "recently viewed" history
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct RecentView {
+    pub movie_id: Uuid,
+    pub title: String,
+    pub production_year: Option<i32>,
+    pub disc_type: Option<String>,
+    pub view_count: i64,
+    pub last_viewed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
 pub struct MovieFilter {
     pub search: Option<String>,
     pub genre: Option<String>,
     pub disc_type: Option<String>,
     pub watched: Option<bool>,
+    pub favorites: Option<bool>,
     pub is_collection: Option<bool>,
     pub exclude_collection_children: Option<bool>, // Hide movies that belong to a collection
     pub year_from: Option<i32>,
     pub year_to: Option<i32>,
+    /// Region code such as "R2" or "RB"; region-free discs always match
+    pub region: Option<String>,
+    /// "PAL" or "NTSC"
+    pub video_standard: Option<String>,
+    /// Exact condition grade
+    pub condition: Option<ConditionGrade>,
+    /// Condition grade or better
+    pub min_condition: Option<ConditionGrade>,
+    /// Only movies that came with this item
+    pub extra: Option<IncludedExtra>,
+    /// Only movies missing this item
+    pub missing_extra: Option<IncludedExtra>,
+    /// `false`: only movies in the collection, `true`: only sold/given away ones.
+    /// `None` returns both.
+    pub disposed: Option<bool>,
+    /// Only movies with this status (wishlist, ordered, ...); `None` returns all
+    pub status: Option<MovieStatus>,
+    /// Only movies that are available, lent, missing, sold or ordered
+    pub availability: Option<MovieAvailability>,
+    /// Only movies added by this user
+    pub added_by: Option<Uuid>,
+    /// Only movies last changed by this user
+    pub updated_by: Option<Uuid>,
+    /// Only movies changed after this time (delta sync)
+    pub updated_since: Option<DateTime<Utc>>,
+    /// Only movies with this tag (one of the comma-separated `tags`)
+    pub tag: Option<String>,
+    /// Only movies in this collection
+    pub collection_id: Option<Uuid>,
+    /// Also list the movies of the other household members, with `owner_name` set
+    pub include_household: Option<bool>,
+    /// Comma-separated fields to return instead of summaries, e.g.
+    /// `id,title,production_year`; see `MOVIE_FIELDS`
+    pub fields: Option<String>,
+    /// Return full movies ([`MovieDetail`]) instead of summaries
+    pub full: Option<bool>,
     pub sort_by: Option<String>,
     pub sort_order: Option<String>,
     pub limit: Option<i64>,
     pub offset: Option<i64>,
 }
+
+/// How a column selected with `?fields=` is read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    Uuid,
+    Text,
+    Integer,
+    Real,
+    Bool,
+}
+
+/// Fields of a movie that can be selected one by one: JSON name, SQL
+/// expression on `movies` and kind. Names match the `Movie` serialization.
+pub const MOVIE_FIELDS: &[(&str, &str, FieldKind)] = &[
+    ("id", "id", FieldKind::Uuid),
+    ("user_id", "user_id", FieldKind::Uuid),
+    ("collection_number", "collection_number", FieldKind::Text),
+    ("barcode", "barcode", FieldKind::Text),
+    ("tmdb_id", "tmdb_id", FieldKind::Integer),
+    ("imdb_id", "imdb_id", FieldKind::Text),
+    ("title", "title", FieldKind::Text),
+    ("original_title", "original_title", FieldKind::Text),
+    ("sort_title", "sort_title", FieldKind::Text),
+    ("personal_title", "personal_title", FieldKind::Text),
+    (
+        "personal_sort_title",
+        "personal_sort_title",
+        FieldKind::Text,
+    ),
+    ("description", "description", FieldKind::Text),
+    ("tagline", "tagline", FieldKind::Text),
+    ("production_year", "production_year", FieldKind::Integer),
+    ("release_date", "release_date", FieldKind::Text),
+    ("running_time", "running_time", FieldKind::Integer),
+    ("director", "director", FieldKind::Text),
+    ("actors", "actors", FieldKind::Text),
+    (
+        "production_companies",
+        "production_companies",
+        FieldKind::Text,
+    ),
+    (
+        "production_countries",
+        "production_countries",
+        FieldKind::Text,
+    ),
+    ("studios", "studios", FieldKind::Text),
+    ("rating", "rating", FieldKind::Text),
+    ("personal_rating", "personal_rating", FieldKind::Real),
+    ("disc_type", "disc_type", FieldKind::Text),
+    ("media_type", "media_type", FieldKind::Text),
+    ("discs", "discs", FieldKind::Integer),
+    ("region_codes", "region_codes", FieldKind::Text),
+    ("video_standard", "video_standard", FieldKind::Text),
+    ("aspect_ratio", "aspect_ratio", FieldKind::Text),
+    ("audio_tracks", "audio_tracks", FieldKind::Text),
+    ("subtitles", "subtitles", FieldKind::Text),
+    ("is_3d", "is_3d", FieldKind::Bool),
+    ("mastered_in_4k", "mastered_in_4k", FieldKind::Bool),
+    ("genres", "genres", FieldKind::Text),
+    ("categories", "categories", FieldKind::Text),
+    ("tags", "tags", FieldKind::Text),
+    ("group", "movie_group", FieldKind::Text),
+    ("is_collection", "is_collection", FieldKind::Bool),
+    (
+        "parent_collection_id",
+        "parent_collection_id",
+        FieldKind::Uuid,
+    ),
+    ("disc_number", "disc_number", FieldKind::Integer),
+    (
+        "tmdb_collection_id",
+        "tmdb_collection_id",
+        FieldKind::Integer,
+    ),
+    (
+        "collection_owned_parts",
+        "collection_owned_parts",
+        FieldKind::Integer,
+    ),
+    (
+        "collection_total_parts",
+        "collection_total_parts",
+        FieldKind::Integer,
+    ),
+    (
+        "collection_completeness",
+        "collection_completeness",
+        FieldKind::Real,
+    ),
+    ("watched", "watched", FieldKind::Bool),
+    (
+        "times_watched",
+        "(SELECT COUNT(*) FROM watch_events w WHERE w.movie_id = movies.id)",
+        FieldKind::Integer,
+    ),
+    (
+        "last_watched_at",
+        "(SELECT MAX(watched_on) FROM watch_events w WHERE w.movie_id = movies.id)",
+        FieldKind::Text,
+    ),
+    ("favorite", "favorite", FieldKind::Bool),
+    ("digital_copies", "digital_copies", FieldKind::Text),
+    ("status", "status", FieldKind::Text),
+    ("condition", "condition", FieldKind::Text),
+    ("slip_cover", "slip_cover", FieldKind::Bool),
+    ("cover_type", "cover_type", FieldKind::Text),
+    ("edition", "edition", FieldKind::Text),
+    ("extra_features", "extra_features", FieldKind::Text),
+    ("included_extras", "included_extras", FieldKind::Text),
+    ("purchase_date", "purchase_date", FieldKind::Text),
+    ("price", "price", FieldKind::Real),
+    ("currency", "currency", FieldKind::Text),
+    ("purchase_place", "purchase_place", FieldKind::Text),
+    ("value_date", "value_date", FieldKind::Text),
+    ("value_price", "value_price", FieldKind::Real),
+    ("value_currency", "value_currency", FieldKind::Text),
+    ("lent_to", "lent_to", FieldKind::Text),
+    ("lent_due", "lent_due", FieldKind::Text),
+    ("location", "location", FieldKind::Text),
+    ("missing", "missing", FieldKind::Bool),
+    ("availability", "availability", FieldKind::Text),
+    ("disposition", "disposition", FieldKind::Text),
+    ("disposed_at", "disposed_at", FieldKind::Text),
+    ("disposal_price", "disposal_price", FieldKind::Real),
+    ("disposal_currency", "disposal_currency", FieldKind::Text),
+    ("disposed_to", "disposed_to", FieldKind::Text),
+    ("added_by", "added_by", FieldKind::Uuid),
+    ("updated_by", "updated_by", FieldKind::Uuid),
+    ("notes", "notes", FieldKind::Text),
+    ("budget", "budget", FieldKind::Integer),
+    ("revenue", "revenue", FieldKind::Integer),
+    ("spoken_languages", "spoken_languages", FieldKind::Text),
+    ("imdb_rating", "imdb_rating", FieldKind::Real),
+    ("rotten_tomatoes", "rotten_tomatoes", FieldKind::Integer),
+    ("added_date", "added_date", FieldKind::Text),
+    ("created_at", "created_at", FieldKind::Text),
+    ("updated_at", "updated_at", FieldKind::Text),
+];
+
+/// Fields of a [`MovieSummary`], from `MOVIE_FIELDS`
+pub const MOVIE_SUMMARY_FIELDS: &[&str] = &[
+    "id",
+    "user_id",
+    "barcode",
+    "tmdb_id",
+    "title",
+    "original_title",
+    "sort_title",
+    "personal_title",
+    "personal_sort_title",
+    "production_year",
+    "running_time",
+    "director",
+    "rating",
+    "personal_rating",
+    "disc_type",
+    "media_type",
+    "genres",
+    "tags",
+    "is_collection",
+    "parent_collection_id",
+    "disc_number",
+    "collection_completeness",
+    "watched",
+    "times_watched",
+    "last_watched_at",
+    "favorite",
+    "status",
+    "condition",
+    "location",
+    "availability",
+    "lent_to",
+    "lent_due",
+    "disposition",
+    "created_at",
+    "updated_at",
+];
+
+/// Field names of a `?fields=` list, in the given order without repeats.
+/// Unknown names are an error.
+pub fn parse_movie_fields(input: &str) -> Result<Vec<&'static str>, String> {
+    let mut fields = Vec::new();
+    for name in input.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+        let Some((field, _, _)) = MOVIE_FIELDS.iter().find(|(field, _, _)| *field == name) else {
+            return Err(format!("Unknown field: {}", name));
+        };
+        if !fields.contains(field) {
+            fields.push(*field);
+        }
+    }
+    if fields.is_empty() {
+        return Err("No fields given".to_string());
+    }
+    Ok(fields)
+}
+
+/// A movie with only the fields selected by `?fields=`
+#[derive(Debug, Clone, Serialize)]
+#[serde(transparent)]
+pub struct MovieFields(pub serde_json::Map<String, serde_json::Value>);
+
+impl<'r> sqlx::FromRow<'r, sqlx::sqlite::SqliteRow> for MovieFields {
+    fn from_row(row: &'r sqlx::sqlite::SqliteRow) -> sqlx::Result<Self> {
+        use serde_json::Value;
+        use sqlx::{Column, Row};
+
+        let mut fields = serde_json::Map::new();
+        for column in row.columns() {
+            let name = column.name();
+            let Some((_, _, kind)) = MOVIE_FIELDS.iter().find(|(field, _, _)| *field == name)
+            else {
+                continue;
+            };
+            let value = match kind {
+                FieldKind::Uuid => Value::from(
+                    row.try_get::<Option<Uuid>, _>(name)?
+                        .map(|id| id.to_string()),
+                ),
+                FieldKind::Text => Value::from(row.try_get::<Option<String>, _>(name)?),
+                FieldKind::Integer => Value::from(row.try_get::<Option<i64>, _>(name)?),
+                FieldKind::Real => Value::from(row.try_get::<Option<f64>, _>(name)?),
+                FieldKind::Bool => Value::from(row.try_get::<Option<bool>, _>(name)?),
+            };
+            fields.insert(name.to_string(), value);
+        }
+        Ok(Self(fields))
+    }
+}
@@ -0,0 +1,67 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Entry in a user's notification center
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Notification {
+    pub id: i64,
+    /// What the notification is about, e.g. `server_message`
+    pub kind: String,
+    pub level: MessageLevel,
+    pub message: String,
+    pub created_at: DateTime<Utc>,
+    pub read_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum MessageLevel {
+    #[default]
+    Info,
+    Warning,
+}
+
+/// Message from an admin to connected clients, e.g. "maintenance in 10 minutes"
+#[derive(Debug, Clone, Deserialize)]
+pub struct BroadcastMessage {
+    pub message: String,
+    #[serde(default)]
+    pub level: MessageLevel,
+    /// Recipients; everyone when not given
+    #[serde(default)]
+    pub user_ids: Option<Vec<Uuid>>,
+}
+
+/// Kind of push token a device registered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum PushPlatform {
+    /// Firebase Cloud Messaging registration token (Android, or iOS through Firebase)
+    Fcm,
+    /// Apple Push Notification service device token
+    Apns,
+}
+
+/// A phone or tablet that receives push notifications for a user
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Device {
+    pub id: Uuid,
+    pub platform: PushPlatform,
+    #[serde(skip_serializing)]
+    pub token: String,
+    pub name: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RegisterDevice {
+    pub token: String,
+    pub platform: PushPlatform,
+    /// Shown in the device list, e.g. "Pixel 8"
+    #[serde(default)]
+    pub name: Option<String>,
+}
@@ -0,0 +1,37 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A poster download that failed and is retried later
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct PosterRetry {
+    pub movie_id: Uuid,
+    #[serde(skip)]
+    pub user_id: Uuid,
+    pub title: String,
+    /// TMDB image path or full image URL
+    pub source: String,
+    pub attempts: i64,
+    pub last_error: String,
+    /// `None` once the download is given up
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct MissingMetadataEntry {
+    pub id: Uuid,
+    pub title: String,
+    pub production_year: Option<i32>,
+}
+
+/// Movies that TMDB enrichment hasn't completed, for the missing-metadata report
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MissingMetadataReport {
+    /// Not matched with TMDB
+    pub without_tmdb: Vec<MissingMetadataEntry>,
+    pub without_poster: Vec<MissingMetadataEntry>,
+    pub without_description: Vec<MissingMetadataEntry>,
+    /// Poster downloads that kept failing and are no longer retried
+    pub failing_posters: Vec<PosterRetry>,
+}
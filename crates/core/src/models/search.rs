@@ -0,0 +1,18 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::EntityType;
+
+/// Movie or series found by full-text search, best match first
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct SearchResult {
+    pub entity_type: EntityType,
+    pub id: Uuid,
+    pub title: String,
+    pub year: Option<i32>,
+    /// Thumbnail URL, if the movie or series has a poster
+    #[sqlx(skip)]
+    pub thumb: Option<String>,
+    #[serde(skip)]
+    pub has_poster: bool,
+}
@@ -91,6 +91,14 @@ pub struct Series {
     // Location
     pub location: Option<String>,
 
+    // Box set the series was split from
+    #[sqlx(default)]
+    pub parent_collection_id: Option<Uuid>,
+
+    // Poster image, stored deduplicated in the images table
+    #[sqlx(default)]
+    pub poster_hash: Option<String>,
+
     // Notes
     pub notes: Option<String>,
 
@@ -116,12 +124,21 @@ pub struct UpdateSeries {
     // Similar structure to UpdateMovie
     pub title: Option<String>,
     pub original_title: Option<String>,
+    pub imdb_id: Option<String>,
     pub description: Option<String>,
+    pub tagline: Option<String>,
+    pub production_year: Option<i32>,
+    pub first_aired: Option<NaiveDate>,
     pub network: Option<String>,
+    pub status: Option<String>,
     pub episodes_count: Option<i32>,
+    pub running_time: Option<i32>,
+    pub actors: Option<String>,
+    pub genres: Option<String>,
     pub watched: Option<bool>,
     pub personal_rating: Option<f64>,
     pub location: Option<String>,
+    pub parent_collection_id: Option<Uuid>,
     pub notes: Option<String>,
     // ... add other fields as needed
 }
@@ -137,3 +154,74 @@ pub struct SeriesFilter {
     pub limit: Option<i64>,
     pub offset: Option<i64>,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Episode {
+    pub id: Uuid,
+    pub series_id: Uuid,
+    pub season_number: i32,
+    pub episode_number: i32,
+    pub title: Option<String>,
+    pub air_date: Option<NaiveDate>,
+    /// Minutes
+    pub runtime: Option<i32>,
+    pub watched: bool,
+    /// On disc in the collection
+    pub owned: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateEpisode {
+    pub season_number: i32,
+    pub episode_number: i32,
+    pub title: Option<String>,
+    pub air_date: Option<NaiveDate>,
+    pub runtime: Option<i32>,
+    #[serde(default)]
+    pub watched: bool,
+    #[serde(default)]
+    pub owned: bool,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct UpdateEpisode {
+    pub title: Option<String>,
+    pub air_date: Option<NaiveDate>,
+    pub runtime: Option<i32>,
+    pub watched: Option<bool>,
+    pub owned: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct EpisodeFilter {
+    pub season: Option<i32>,
+    /// `true`: only episodes without air date or airing in the future
+    pub unaired: Option<bool>,
+    pub watched: Option<bool>,
+    pub owned: Option<bool>,
+    /// "episode" (default), "air_date", "title" or "runtime"
+    pub sort_by: Option<String>,
+    pub sort_order: Option<String>,
+    /// Only return the season summaries
+    pub seasons_only: Option<bool>,
+}
+
+/// Episode counts of one season, over all its episodes
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct SeasonSummary {
+    pub season_number: i32,
+    pub episode_count: i64,
+    pub watched_count: i64,
+    pub owned_count: i64,
+    pub unaired_count: i64,
+    /// Minutes
+    pub runtime_total: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EpisodeList {
+    pub seasons: Vec<SeasonSummary>,
+    pub episodes: Vec<Episode>,
+}
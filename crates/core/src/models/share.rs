@@ -0,0 +1,60 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Read-only link to a user's library, or to one collection with `collection_id`
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ShareLink {
+    pub id: Uuid,
+    #[serde(skip)]
+    pub user_id: Uuid,
+    pub collection_id: Option<Uuid>,
+    pub name: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CreateShareLink {
+    /// Share only this collection instead of the whole library
+    #[serde(default)]
+    pub collection_id: Option<Uuid>,
+    /// Label for the owner, e.g. who got the link
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// A new link with its token; the token is only returned here
+#[derive(Debug, Clone, Serialize)]
+pub struct CreatedShareLink {
+    pub token: String,
+    #[serde(flatten)]
+    pub link: ShareLink,
+}
+
+/// What guests see of a movie: no prices, locations, notes or loans
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct SharedMovie {
+    pub id: Uuid,
+    pub title: String,
+    pub original_title: Option<String>,
+    pub production_year: Option<i32>,
+    pub disc_type: Option<String>,
+    pub running_time: Option<i32>,
+    pub genres: Option<String>,
+    pub director: Option<String>,
+    pub description: Option<String>,
+    pub has_poster: bool,
+    /// False while lent out or missing
+    pub available: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SharedLibrary {
+    pub owner: String,
+    /// Title of the shared collection; `None` for the whole library
+    pub collection: Option<String>,
+    pub movies: Vec<SharedMovie>,
+}
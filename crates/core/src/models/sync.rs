@@ -0,0 +1,115 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{Collection, EntityType, Movie, Series, Tombstone};
+
+/// Another my-movies server this user's library is synced with, as stored in the database
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct SyncPeer {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub url: String,
+    /// API key issued by the peer, encrypted; see `SyncService::peer_token`
+    pub token: String,
+    pub last_synced_at: Option<DateTime<Utc>>,
+    /// `SyncOutcome` of the last run as JSON
+    pub last_result: Option<String>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Sync peer without its token, returned to the owner
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncPeerPublic {
+    pub id: Uuid,
+    pub name: String,
+    pub url: String,
+    pub last_synced_at: Option<DateTime<Utc>>,
+    pub last_result: Option<SyncOutcome>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<SyncPeer> for SyncPeerPublic {
+    fn from(peer: SyncPeer) -> Self {
+        Self {
+            id: peer.id,
+            last_result: peer
+                .last_result
+                .and_then(|json| serde_json::from_str(&json).ok()),
+            name: peer.name,
+            url: peer.url,
+            last_synced_at: peer.last_synced_at,
+            last_error: peer.last_error,
+            created_at: peer.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateSyncPeer {
+    pub name: String,
+    /// Base URL of the peer, e.g. `https://movies.example.com`
+    pub url: String,
+    /// Pairing token created on the peer
+    pub token: String,
+}
+
+/// Everything that changed in a library since a point in time
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyncChanges {
+    pub movies: Vec<Movie>,
+    pub series: Vec<Series>,
+    pub collections: Vec<Collection>,
+    pub deletions: Vec<Tombstone>,
+}
+
+/// Request body of `POST /sync/exchange`
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SyncExchangeRequest {
+    /// When the caller last synced with this server (None = first sync)
+    pub since: Option<DateTime<Utc>>,
+    pub changes: SyncChanges,
+}
+
+/// Response of `POST /sync/exchange`
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SyncExchangeResponse {
+    /// Changes on this server since `since`, computed before applying the caller's
+    pub changes: SyncChanges,
+    pub outcome: SyncOutcome,
+    pub server_time: DateTime<Utc>,
+}
+
+/// Which side's version was kept
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncWinner {
+    Local,
+    Remote,
+}
+
+/// An entity changed (or deleted) on both sides since the last sync
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncConflict {
+    pub entity_type: EntityType,
+    pub id: Uuid,
+    pub title: Option<String>,
+    pub local_updated_at: Option<DateTime<Utc>>,
+    pub remote_updated_at: Option<DateTime<Utc>>,
+    /// Set when one side deleted the entity
+    pub deleted: bool,
+    pub winner: SyncWinner,
+}
+
+/// Result of applying a peer's changes
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyncOutcome {
+    pub created: usize,
+    pub updated: usize,
+    pub deleted: usize,
+    pub conflicts: Vec<SyncConflict>,
+    pub errors: Vec<String>,
+}
@@ -0,0 +1,20 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum EntityType {
+    Movie,
+    Series,
+    Collection,
+}
+
+/// Record of a deleted entity for delta sync
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Tombstone {
+    pub entity_type: EntityType,
+    pub entity_id: Uuid,
+    pub deleted_at: DateTime<Utc>,
+}
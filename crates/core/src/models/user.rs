@@ -1,7 +1,10 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use super::{ApiScope, DigestSettings, ImportPreset, LoanReminderSettings};
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, Default)]
 #[sqlx(type_name = "TEXT", rename_all = "lowercase")]
 #[serde(rename_all = "lowercase")]
@@ -37,6 +40,249 @@ pub struct User {
     /// Card size preference: "small", "medium", or "large"
     #[sqlx(default)]
     pub card_size: Option<String>,
+    /// Regions the user's players support, e.g. "R2,RB"
+    #[sqlx(default)]
+    pub player_regions: Option<String>,
+    /// Currency value reports are converted into, e.g. "EUR"
+    #[sqlx(default)]
+    pub preferred_currency: Option<String>,
+    // Defaults for new movies, see `MovieDefaults`
+    #[sqlx(default)]
+    pub default_disc_type: Option<String>,
+    #[sqlx(default)]
+    pub default_location: Option<String>,
+    #[sqlx(default)]
+    pub default_media_type: Option<String>,
+    #[sqlx(default)]
+    pub default_currency: Option<String>,
+    #[sqlx(default)]
+    pub loan_reminders: bool,
+    #[sqlx(default)]
+    pub loan_reminder_days: i32,
+    /// IANA timezone such as "Europe/Berlin", see `normalize_timezone`
+    #[sqlx(default)]
+    pub timezone: Option<String>,
+    #[sqlx(default)]
+    pub digest_enabled: bool,
+    #[sqlx(default)]
+    pub digest_weekday: i32,
+    #[sqlx(default)]
+    pub digest_hour: i32,
+    /// When the last weekly digest was sent
+    #[serde(skip_serializing)]
+    #[sqlx(default)]
+    pub digest_sent_at: Option<DateTime<Utc>>,
+    // TMDB preferences, see `TmdbPreferences`
+    #[sqlx(default)]
+    pub tmdb_poster_size: Option<String>,
+    #[sqlx(default)]
+    pub tmdb_backdrop_size: Option<String>,
+    #[sqlx(default)]
+    pub tmdb_languages: Option<String>,
+    /// JSON array of `ImportPreset`, see `import_presets()`
+    #[serde(skip_serializing)]
+    #[sqlx(default)]
+    pub import_presets: String,
+}
+
+impl User {
+    pub fn movie_defaults(&self) -> MovieDefaults {
+        MovieDefaults {
+            disc_type: self.default_disc_type.clone(),
+            location: self.default_location.clone(),
+            media_type: self.default_media_type.clone(),
+            currency: self.default_currency.clone(),
+        }
+    }
+
+    pub fn preferences(&self) -> UserPreferences {
+        UserPreferences {
+            language: self.language.clone(),
+            include_adult: self.include_adult,
+            theme: self.theme.clone(),
+            card_size: self.card_size.clone(),
+            player_regions: self.player_regions.clone(),
+            preferred_currency: self.preferred_currency.clone(),
+            timezone: self.timezone.clone(),
+            movie_defaults: self.movie_defaults(),
+            loan_reminders: self.loan_reminder_settings(),
+            digest: self.digest_settings(),
+            tmdb: self.tmdb_preferences(),
+            import_presets: self.import_presets(),
+        }
+    }
+
+    pub fn tmdb_preferences(&self) -> TmdbPreferences {
+        TmdbPreferences {
+            poster_size: self.tmdb_poster_size.clone(),
+            backdrop_size: self.tmdb_backdrop_size.clone(),
+            languages: self.tmdb_languages.clone(),
+        }
+    }
+
+    /// The user's own CSV import presets; built-in ones are not included
+    pub fn import_presets(&self) -> Vec<ImportPreset> {
+        serde_json::from_str(&self.import_presets).unwrap_or_default()
+    }
+
+    pub fn loan_reminder_settings(&self) -> LoanReminderSettings {
+        LoanReminderSettings {
+            enabled: self.loan_reminders,
+            days_before: self.loan_reminder_days,
+        }
+    }
+
+    pub fn digest_settings(&self) -> DigestSettings {
+        DigestSettings {
+            enabled: self.digest_enabled,
+            weekday: self.digest_weekday,
+            hour: self.digest_hour,
+        }
+    }
+}
+
+/// Zones for UTC offsets that are not whole hours, as stored before timezones
+/// were IANA names (migration 062 maps them the same way)
+const OFFSET_ZONES: &[(i32, &str)] = &[
+    (-(3 * 60 + 30), "America/St_Johns"),
+    (3 * 60 + 30, "Asia/Tehran"),
+    (4 * 60 + 30, "Asia/Kabul"),
+    (5 * 60 + 30, "Asia/Kolkata"),
+    (5 * 60 + 45, "Asia/Kathmandu"),
+    (6 * 60 + 30, "Asia/Yangon"),
+    (9 * 60 + 30, "Australia/Darwin"),
+];
+
+/// IANA name of a timezone ("Europe/Berlin"); empty and "UTC" normalize to `None`.
+/// UTC offsets ("+01:00", "UTC-05:30") from older clients and backups become the
+/// matching fixed zone, e.g. "Etc/GMT-1".
+pub fn normalize_timezone(input: &str) -> Result<Option<String>, String> {
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+        return Ok(None);
+    }
+    let zone = match trimmed.parse::<Tz>() {
+        Ok(zone) => zone.name().to_string(),
+        Err(_) => offset_zone(trimmed).ok_or_else(|| {
+            format!(
+                "Invalid timezone, expected a name like Europe/Berlin: {}",
+                trimmed
+            )
+        })?,
+    };
+    Ok(Some(zone).filter(|z| !matches!(z.as_str(), "UTC" | "GMT" | "Etc/UTC" | "Etc/GMT")))
+}
+
+/// Fixed zone for a UTC offset such as "+2", "UTC+02:00" or "-0530"
+fn offset_zone(input: &str) -> Option<String> {
+    let offset = input
+        .strip_prefix("UTC")
+        .or_else(|| input.strip_prefix("GMT"))
+        .unwrap_or(input);
+    if offset.is_empty() || offset.eq_ignore_ascii_case("z") {
+        return Some("UTC".to_string());
+    }
+
+    let (sign, rest) = match offset.as_bytes()[0] {
+        b'+' => (1, &offset[1..]),
+        b'-' => (-1, &offset[1..]),
+        _ => return None,
+    };
+    if !rest.is_ascii() {
+        return None;
+    }
+    let (hours, minutes) = match rest.split_once(':') {
+        Some((h, m)) => (h, m),
+        None if rest.len() > 2 => rest.split_at(rest.len() - 2),
+        None => (rest, "0"),
+    };
+    let hours: i32 = hours.parse().ok()?;
+    let minutes: i32 = minutes.parse().ok()?;
+    if minutes >= 60 {
+        return None;
+    }
+
+    let total = sign * (hours * 60 + minutes);
+    match total {
+        0 => Some("UTC".to_string()),
+        // Etc/GMT zones count westwards: UTC+1 is Etc/GMT-1
+        _ if minutes == 0 && (-12..=14).contains(&(sign * hours)) => {
+            Some(format!("Etc/GMT{:+}", -sign * hours))
+        }
+        _ => OFFSET_ZONES
+            .iter()
+            .find(|(minutes, _)| *minutes == total)
+            .map(|(_, zone)| zone.to_string()),
+    }
+}
+
+/// Timezone stored by `normalize_timezone`; UTC when unset or unknown
+pub fn user_timezone(timezone: Option<&str>) -> Tz {
+    timezone.and_then(|tz| tz.parse().ok()).unwrap_or(Tz::UTC)
+}
+
+/// Date at `now` in a timezone stored by `normalize_timezone`
+pub fn local_date(now: DateTime<Utc>, timezone: Option<&str>) -> NaiveDate {
+    now.with_timezone(&user_timezone(timezone)).date_naive()
+}
+
+/// Values `MovieService::create` fills in for new movies that don't have them,
+/// e.g. when cataloging a shelf of Blu-rays
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct MovieDefaults {
+    #[serde(default)]
+    pub disc_type: Option<String>,
+    #[serde(default)]
+    pub location: Option<String>,
+    #[serde(default)]
+    pub media_type: Option<String>,
+    #[serde(default)]
+    pub currency: Option<String>,
+}
+
+/// TMDB image sizes and metadata languages of a user; unset values fall back
+/// to the server settings
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TmdbPreferences {
+    /// e.g. "w780", see `POSTER_SIZES`
+    #[serde(default)]
+    pub poster_size: Option<String>,
+    /// e.g. "w1280", see `BACKDROP_SIZES`
+    #[serde(default)]
+    pub backdrop_size: Option<String>,
+    /// Languages asked in order until one has an overview, e.g. "de-DE,en-US"
+    #[serde(default)]
+    pub languages: Option<String>,
+}
+
+/// Everything a user set up for themselves, carried along in backups so an
+/// account can move to another instance
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UserPreferences {
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub include_adult: bool,
+    #[serde(default)]
+    pub theme: Option<String>,
+    #[serde(default)]
+    pub card_size: Option<String>,
+    #[serde(default)]
+    pub player_regions: Option<String>,
+    #[serde(default)]
+    pub preferred_currency: Option<String>,
+    #[serde(default)]
+    pub timezone: Option<String>,
+    #[serde(default)]
+    pub movie_defaults: MovieDefaults,
+    #[serde(default)]
+    pub loan_reminders: LoanReminderSettings,
+    #[serde(default)]
+    pub digest: DigestSettings,
+    #[serde(default)]
+    pub tmdb: TmdbPreferences,
+    #[serde(default)]
+    pub import_presets: Vec<ImportPreset>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -46,18 +292,53 @@ pub struct CreateUser {
     pub password: String,
 }
 
+/// Body of `POST /auth/register`
+#[derive(Debug, Deserialize)]
+pub struct RegisterRequest {
+    #[serde(flatten)]
+    pub user: CreateUser,
+    /// Required while open registration is disabled
+    #[serde(default)]
+    pub invite_code: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct LoginRequest {
     pub username: String,
     pub password: String,
+    /// Shown in the session list; the server falls back to the User-Agent
+    #[serde(default)]
+    pub device: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct AuthResponse {
+    /// Access token, valid for `expires_in` seconds
     pub token: String,
+    /// Exchange for a new access token at `/auth/refresh`; rotated on every use
+    pub refresh_token: String,
+    pub expires_in: i64,
     pub user: UserPublic,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct RefreshRequest {
+    pub refresh_token: String,
+}
+
+/// A signed-in device
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct Session {
+    pub id: Uuid,
+    pub device: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    /// The session of the access token used for the request
+    #[sqlx(skip)]
+    pub current: bool,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct UserPublic {
     pub id: Uuid,
@@ -69,12 +350,30 @@ pub struct UserPublic {
     pub avatar_path: Option<String>,
     pub theme: Option<String>,
     pub card_size: Option<String>,
+    pub player_regions: Option<String>,
+    pub preferred_currency: Option<String>,
+    pub movie_defaults: MovieDefaults,
+    pub loan_reminders: LoanReminderSettings,
+    pub digest: DigestSettings,
+    pub tmdb: TmdbPreferences,
+    pub timezone: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+impl UserPublic {
+    /// The user's current date, e.g. for defaults and reports
+    pub fn today(&self) -> NaiveDate {
+        local_date(Utc::now(), self.timezone.as_deref())
+    }
+}
+
 impl From<User> for UserPublic {
     fn from(user: User) -> Self {
+        let movie_defaults = user.movie_defaults();
+        let loan_reminders = user.loan_reminder_settings();
+        let digest = user.digest_settings();
+        let tmdb = user.tmdb_preferences();
         Self {
             id: user.id,
             username: user.username,
@@ -85,6 +384,13 @@ impl From<User> for UserPublic {
             avatar_path: user.avatar_path,
             theme: user.theme,
             card_size: user.card_size,
+            player_regions: user.player_regions,
+            preferred_currency: user.preferred_currency,
+            movie_defaults,
+            loan_reminders,
+            digest,
+            tmdb,
+            timezone: user.timezone,
             created_at: user.created_at,
             updated_at: user.updated_at,
         }
@@ -98,6 +404,13 @@ pub struct Claims {
     pub role: UserRole,
     pub exp: i64,
     pub iat: i64,
+    /// Session the access token belongs to; `None` for API keys and tokens
+    /// issued before sessions existed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<Uuid>,
+    /// Set when authenticated with an API key; `None` for regular sessions (unrestricted)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scopes: Option<Vec<ApiScope>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -0,0 +1,93 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::EntityType;
+
+/// Body of `PUT /valuation/{entity_type}/{id}`: what an item is worth now
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SetItemValue {
+    /// `null` clears the value, its currency and date
+    pub value_price: Option<f64>,
+    /// Code, symbol or name; defaults to the purchase currency
+    pub value_currency: Option<String>,
+    /// Defaults to today
+    pub value_date: Option<NaiveDate>,
+}
+
+/// Purchase price and current value of a movie, series or collection
+#[derive(Debug, Clone, Serialize)]
+pub struct ItemValue {
+    pub entity_type: EntityType,
+    pub id: Uuid,
+    pub title: String,
+    pub price: Option<f64>,
+    pub currency: Option<String>,
+    pub value_price: Option<f64>,
+    pub value_currency: Option<String>,
+    pub value_date: Option<NaiveDate>,
+}
+
+/// Body of `POST /valuation/currency`: set the currency of purchase prices
+/// and values of all movies, series and collections
+#[derive(Debug, Clone, Deserialize)]
+pub struct BulkUpdateCurrency {
+    /// New currency (code, symbol or name)
+    pub currency: String,
+    /// Only amounts in this currency; amounts without currency if unset
+    pub from: Option<String>,
+    /// Convert the amounts with the exchange rates instead of only
+    /// relabeling them; needs `from`
+    #[serde(default)]
+    pub convert: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BulkUpdateCurrencyResult {
+    /// Amounts changed; an item with price and value counts twice
+    pub updated: u64,
+}
+
+/// Query of `GET /valuation/history`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ValuationHistoryQuery {
+    /// First day included
+    pub from: Option<NaiveDate>,
+    /// Last day included
+    pub to: Option<NaiveDate>,
+    /// Also sum up each day in this currency; defaults to the preferred currency
+    pub currency: Option<String>,
+}
+
+/// Totals of the amounts in one currency on one day
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ValuationTotals {
+    /// `None` for amounts without currency
+    pub currency: Option<String>,
+    /// Purchase prices of the items in the collection
+    pub purchase_total: f64,
+    /// Current values of the items in the collection
+    pub value_total: f64,
+    pub priced_count: i64,
+    pub valued_count: i64,
+}
+
+/// Valuation of a collection on one day
+#[derive(Debug, Clone, Serialize)]
+pub struct ValuationPoint {
+    pub date: NaiveDate,
+    /// One entry per currency
+    pub totals: Vec<ValuationTotals>,
+    /// Totals in the requested or preferred currency, at today's exchange rates
+    pub converted: Option<ConvertedValuation>,
+}
+
+/// Valuation totals converted into one currency
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConvertedValuation {
+    pub currency: String,
+    pub purchase_total: f64,
+    pub value_total: f64,
+    /// Currencies without exchange rate; their amounts are left out
+    pub unconverted: Vec<String>,
+}
@@ -0,0 +1,23 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// One viewing of a movie
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct WatchEvent {
+    pub id: i64,
+    pub movie_id: Uuid,
+    /// `None` when the date is unknown (watched before the history existed)
+    pub watched_on: Option<NaiveDate>,
+    pub notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LogWatch {
+    /// Defaults to today
+    #[serde(default)]
+    pub date: Option<NaiveDate>,
+    #[serde(default)]
+    pub notes: Option<String>,
+}
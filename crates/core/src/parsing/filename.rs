@@ -0,0 +1,508 @@
+//! Movie information from file names of ripped or downloaded media, e.g.
+//! `Movie.Name.2004.1080p.BluRay.x264-GROUP` or `Movie Name (2004) [Extended].mkv`
+
+/// File extensions of video files
+pub const VIDEO_EXTENSIONS: &[&str] = &[
+    "mkv", "mp4", "m4v", "avi", "mov", "wmv", "mpg", "mpeg", "ts", "m2ts", "iso", "webm",
+];
+
+/// Everything that could be read from a file name
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParsedFilename {
+    pub title: String,
+    pub year: Option<i32>,
+    /// Normalized, e.g. `1080p` or `2160p`
+    pub resolution: Option<String>,
+    /// `BluRay`, `DVD`, `WEB` or `HDTV`
+    pub source: Option<String>,
+    /// Canonical edition name, e.g. `Director's Cut` or `Extended`
+    pub edition: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    Word,
+    Year(i32),
+    Resolution(&'static str),
+    Source(&'static str),
+    /// Codec, audio and other technical tags
+    Technical,
+    /// Language and release tags that are also ordinary words ("German", "Proper")
+    Weak,
+    Language,
+    Edition(&'static str),
+    /// First word of a two-word edition tag; the edition itself is on the next token
+    EditionStart,
+}
+
+impl Token {
+    /// Tags that can never be part of a title
+    fn is_strong(self) -> bool {
+        matches!(
+            self,
+            Token::Resolution(_) | Token::Source(_) | Token::Technical
+        )
+    }
+
+    fn is_weak(self) -> bool {
+        matches!(self, Token::Weak | Token::Language)
+    }
+
+    fn is_edition(self) -> bool {
+        matches!(self, Token::Edition(_) | Token::EditionStart)
+    }
+}
+
+fn resolution(tag: &str) -> Option<&'static str> {
+    Some(match tag {
+        "2160p" | "4k" | "uhd" => "2160p",
+        "1080p" | "1080i" => "1080p",
+        "720p" => "720p",
+        "576p" | "576i" => "576p",
+        "480p" | "480i" => "480p",
+        _ => return None,
+    })
+}
+
+fn source(tag: &str) -> Option<&'static str> {
+    Some(match tag {
+        "bluray" | "blu-ray" | "bdrip" | "brrip" | "bdremux" | "bd25" | "bd50" => "BluRay",
+        "dvd" | "dvdrip" | "dvd5" | "dvd9" | "dvdr" => "DVD",
+        "web" | "web-dl" | "webdl" | "webrip" => "WEB",
+        "hdtv" => "HDTV",
+        _ => return None,
+    })
+}
+
+const TECHNICAL_TAGS: &[&str] = &[
+    "x264", "x265", "h264", "h265", "hevc", "avc", "xvid", "divx", "remux", "hdr", "hdr10", "dv",
+    "10bit", "ac3", "aac", "dts", "dts-hd", "truehd", "atmos", "dd5", "dd51",
+];
+
+const LANGUAGE_TAGS: &[&str] = &["german", "english", "french", "multi", "dual"];
+
+const WEAK_TAGS: &[&str] = &[
+    "dl", "ml", "dubbed", "subbed", "proper", "repack", "internal", "limited",
+];
+
+/// Single-word edition tags and their display names
+const EDITIONS: &[(&str, &str)] = &[
+    ("extended", "Extended"),
+    ("uncut", "Uncut"),
+    ("unrated", "Unrated"),
+    ("remastered", "Remastered"),
+    ("theatrical", "Theatrical"),
+    ("imax", "IMAX"),
+    ("criterion", "Criterion"),
+    ("ungekürzt", "Ungekürzt"),
+    ("ungekuerzt", "Ungekürzt"),
+    ("kinofassung", "Kinofassung"),
+];
+
+/// Two-word edition tags and their display names
+const TWO_WORD_EDITIONS: &[(&str, &str, &str)] = &[
+    ("directors", "cut", "Director's Cut"),
+    ("director's", "cut", "Director's Cut"),
+    ("dc", "cut", "Director's Cut"),
+    ("final", "cut", "Final Cut"),
+    ("extended", "cut", "Extended"),
+    ("extended", "edition", "Extended"),
+    ("special", "edition", "Special Edition"),
+    ("collectors", "edition", "Collector's Edition"),
+    ("collector's", "edition", "Collector's Edition"),
+    ("anniversary", "edition", "Anniversary Edition"),
+    ("ultimate", "edition", "Ultimate Edition"),
+];
+
+fn year(token: &str) -> Option<i32> {
+    let trimmed = token.trim_matches(|c| matches!(c, '(' | ')' | '[' | ']'));
+    if trimmed.len() != 4 || !trimmed.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    trimmed
+        .parse::<i32>()
+        .ok()
+        .filter(|y| (1900..=2100).contains(y))
+}
+
+/// Tag classification of a single lowercased token
+fn tag(lower: &str) -> Option<Token> {
+    if let Some(r) = resolution(lower) {
+        Some(Token::Resolution(r))
+    } else if let Some(s) = source(lower) {
+        Some(Token::Source(s))
+    } else if TECHNICAL_TAGS.contains(&lower) {
+        Some(Token::Technical)
+    } else if LANGUAGE_TAGS.contains(&lower) {
+        Some(Token::Language)
+    } else if WEAK_TAGS.contains(&lower) {
+        Some(Token::Weak)
+    } else {
+        EDITIONS
+            .iter()
+            .find(|(tag, _)| *tag == lower)
+            .map(|(_, name)| Token::Edition(name))
+    }
+}
+
+fn classify(tokens: &[String]) -> Vec<Token> {
+    let lower: Vec<String> = tokens
+        .iter()
+        .map(|t| {
+            t.trim_matches(|c| matches!(c, '(' | ')' | '[' | ']'))
+                .to_lowercase()
+        })
+        .collect();
+
+    let mut kinds = Vec::with_capacity(tokens.len());
+    let mut i = 0;
+    while i < tokens.len() {
+        let two_word = lower.get(i + 1).and_then(|next| {
+            TWO_WORD_EDITIONS
+                .iter()
+                .find(|(first, second, _)| *first == lower[i] && second == next)
+                .map(|(_, _, name)| *name)
+        });
+        if let Some(name) = two_word {
+            kinds.push(Token::EditionStart);
+            kinds.push(Token::Edition(name));
+            i += 2;
+            continue;
+        }
+
+        let kind = if let Some(y) = year(&tokens[i]) {
+            Token::Year(y)
+        } else if let Some(kind) = tag(&lower[i]) {
+            kind
+        } else if let Some((before, _)) = lower[i].rsplit_once('-')
+            && let Some(kind) = tag(before)
+        {
+            // Release group suffix: "x264-GROUP"
+            kind
+        } else {
+            Token::Word
+        };
+        kinds.push(kind);
+        i += 1;
+    }
+    kinds
+}
+
+/// Strip a known video extension
+fn strip_extension(name: &str) -> &str {
+    match name.rsplit_once('.') {
+        Some((stem, ext)) if VIDEO_EXTENSIONS.contains(&ext.to_lowercase().as_str()) => stem,
+        _ => name,
+    }
+}
+
+/// Parse a file name (with or without video extension)
+pub fn parse(file_name: &str) -> ParsedFilename {
+    let name = compose_umlauts(strip_extension(file_name.trim()));
+
+    let mut tokens: Vec<String> = name
+        .split(|c: char| c == '.' || c == '_' || c.is_whitespace())
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    // Leading "[Group]" tags
+    while tokens.len() > 1 && tokens[0].starts_with('[') && tokens[0].ends_with(']') {
+        tokens.remove(0);
+    }
+
+    let kinds = classify(&tokens);
+
+    // Anything from the first technical tag on is not part of the title; a
+    // leading token is always title ("300", "1917")
+    let strong_start = (1..kinds.len())
+        .find(|&i| kinds[i].is_strong())
+        .unwrap_or(kinds.len());
+    let edition_start = (1..kinds.len()).find(|&i| kinds[i].is_edition());
+
+    // The last year before the tags is the release year ("2001 A Space Odyssey 1968")
+    let year_index = (1..strong_start)
+        .rev()
+        .find(|&i| matches!(kinds[i], Token::Year(_)));
+
+    let mut title_end = strong_start;
+    if let Some(i) = year_index {
+        title_end = title_end.min(i);
+    }
+    if let Some(i) = edition_start {
+        title_end = title_end.min(i);
+    }
+
+    // Trailing release tags ("Movie.German.DL.1080p"). A language on its own
+    // may be a word of the title ("The German"), so only drop it before another tag.
+    while title_end > 1 && kinds[title_end - 1].is_weak() {
+        let next_is_weak = kinds.get(title_end).is_some_and(|k| k.is_weak());
+        if kinds[title_end - 1] == Token::Language && !next_is_weak {
+            break;
+        }
+        title_end -= 1;
+    }
+
+    let title = tokens[..title_end]
+        .iter()
+        .map(|t| t.trim_matches(|c| matches!(c, '(' | ')' | '[' | ']')))
+        .filter(|t| !t.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    ParsedFilename {
+        title: if title.is_empty() {
+            name.clone()
+        } else {
+            title
+        },
+        year: year_index.and_then(|i| match kinds[i] {
+            Token::Year(y) => Some(y),
+            _ => None,
+        }),
+        resolution: kinds.iter().find_map(|k| match k {
+            Token::Resolution(r) => Some(r.to_string()),
+            _ => None,
+        }),
+        source: kinds.iter().find_map(|k| match k {
+            Token::Source(s) => Some(s.to_string()),
+            _ => None,
+        }),
+        edition: kinds.iter().find_map(|k| match k {
+            Token::Edition(e) => Some(e.to_string()),
+            _ => None,
+        }),
+    }
+}
+
+/// Compose umlauts written as base letter + combining diaeresis (U+0308),
+/// which is how macOS stores file names
+pub fn compose_umlauts(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        if chars.peek() == Some(&'\u{308}') {
+            let composed = match c {
+                'a' => Some('ä'),
+                'o' => Some('ö'),
+                'u' => Some('ü'),
+                'A' => Some('Ä'),
+                'O' => Some('Ö'),
+                'U' => Some('Ü'),
+                _ => None,
+            };
+            if let Some(composed) = composed {
+                chars.next();
+                out.push(composed);
+                continue;
+            }
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Comparison key for titles from file names and the catalog: lowercase ASCII
+/// spelling of umlauts, punctuation dropped, single spaces.
+/// `"Die Fälscher"` and `"Die.Faelscher"` have the same key.
+pub fn title_key(title: &str) -> String {
+    super::title::transliterate(title)
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed(title: &str, year: Option<i32>) -> ParsedFilename {
+        ParsedFilename {
+            title: title.to_string(),
+            year,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_scene_name() {
+        let p = parse("Movie.Name.2004.1080p.BluRay.x264");
+        assert_eq!(p.title, "Movie Name");
+        assert_eq!(p.year, Some(2004));
+        assert_eq!(p.resolution.as_deref(), Some("1080p"));
+        assert_eq!(p.source.as_deref(), Some("BluRay"));
+        assert_eq!(p.edition, None);
+    }
+
+    #[test]
+    fn test_scene_name_with_group_and_extension() {
+        let p = parse("The.Dark.Knight.2008.720p.WEB-DL.x264-GROUP.mkv");
+        assert_eq!(p.title, "The Dark Knight");
+        assert_eq!(p.year, Some(2008));
+        assert_eq!(p.resolution.as_deref(), Some("720p"));
+        assert_eq!(p.source.as_deref(), Some("WEB"));
+    }
+
+    #[test]
+    fn test_plain_name_with_year_in_parentheses() {
+        assert_eq!(parse("Das Boot (1981).mp4"), parsed("Das Boot", Some(1981)));
+        assert_eq!(parse("Heat [1995]"), parsed("Heat", Some(1995)));
+    }
+
+    #[test]
+    fn test_name_without_year_or_tags() {
+        assert_eq!(parse("Casablanca.avi"), parsed("Casablanca", None));
+        assert_eq!(parse("casino_royale"), parsed("casino royale", None));
+    }
+
+    #[test]
+    fn test_unknown_extension_is_kept() {
+        assert_eq!(parse("Casablanca.nfo").title, "Casablanca nfo");
+    }
+
+    #[test]
+    fn test_numeric_titles() {
+        assert_eq!(
+            parse("2001.A.Space.Odyssey.1968.2160p"),
+            ParsedFilename {
+                title: "2001 A Space Odyssey".to_string(),
+                year: Some(1968),
+                resolution: Some("2160p".to_string()),
+                ..Default::default()
+            }
+        );
+        assert_eq!(parse("1917.2019.1080p").title, "1917");
+        assert_eq!(parse("1917.1080p").year, None);
+        assert_eq!(parse("300.2006.BDRip").title, "300");
+    }
+
+    #[test]
+    fn test_year_in_title() {
+        let p = parse("Blade.Runner.2049.2017.2160p.UHD.BluRay");
+        assert_eq!(p.title, "Blade Runner 2049");
+        assert_eq!(p.year, Some(2017));
+        assert_eq!(p.source.as_deref(), Some("BluRay"));
+    }
+
+    #[test]
+    fn test_single_word_editions() {
+        let p = parse("Alien.1979.Remastered.1080p.BluRay");
+        assert_eq!(p.title, "Alien");
+        assert_eq!(p.edition.as_deref(), Some("Remastered"));
+
+        let p = parse("Dawn.of.the.Dead.Uncut.2004.DVDRip");
+        assert_eq!(p.title, "Dawn of the Dead");
+        assert_eq!(p.year, Some(2004));
+        assert_eq!(p.edition.as_deref(), Some("Uncut"));
+        assert_eq!(p.source.as_deref(), Some("DVD"));
+    }
+
+    #[test]
+    fn test_two_word_editions() {
+        let p = parse("Blade.Runner.Final.Cut.1982.1080p");
+        assert_eq!(p.title, "Blade Runner");
+        assert_eq!(p.year, Some(1982));
+        assert_eq!(p.edition.as_deref(), Some("Final Cut"));
+
+        let p = parse("Kingdom.of.Heaven.2005.Directors.Cut.720p");
+        assert_eq!(p.title, "Kingdom of Heaven");
+        assert_eq!(p.edition.as_deref(), Some("Director's Cut"));
+
+        let p = parse("Aliens (1986) [Special Edition]");
+        assert_eq!(p.title, "Aliens");
+        assert_eq!(p.year, Some(1986));
+        assert_eq!(p.edition.as_deref(), Some("Special Edition"));
+
+        let p = parse("Der.Herr.der.Ringe.Extended.Edition.2001");
+        assert_eq!(p.title, "Der Herr der Ringe");
+        assert_eq!(p.edition.as_deref(), Some("Extended"));
+    }
+
+    #[test]
+    fn test_german_edition_tags() {
+        let p = parse("Das.Boot.1981.Ungekuerzt.German.DL.1080p");
+        assert_eq!(p.title, "Das Boot");
+        assert_eq!(p.edition.as_deref(), Some("Ungekürzt"));
+
+        assert_eq!(
+            parse("Das Boot (Kinofassung)").edition.as_deref(),
+            Some("Kinofassung")
+        );
+    }
+
+    #[test]
+    fn test_release_tags_without_year() {
+        assert_eq!(parse("Movie.German.DL.1080p.BluRay").title, "Movie");
+        assert_eq!(parse("Movie.PROPER.720p").title, "Movie");
+        // A language alone is kept, it may be part of the title
+        assert_eq!(parse("The.German.1080p").title, "The German");
+        assert_eq!(parse("The.German.2006.German.DL.720p").title, "The German");
+    }
+
+    #[test]
+    fn test_leading_group_tag() {
+        assert_eq!(
+            parse("[GRP] Spirited.Away.2001.1080p"),
+            ParsedFilename {
+                title: "Spirited Away".to_string(),
+                year: Some(2001),
+                resolution: Some("1080p".to_string()),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_hyphenated_title_words_are_kept() {
+        assert_eq!(parse("Spider-Man.2002.720p").title, "Spider-Man");
+        assert_eq!(
+            parse("Alien - Die Wiedergeburt (1997)").title,
+            "Alien - Die Wiedergeburt"
+        );
+    }
+
+    #[test]
+    fn test_umlauts() {
+        assert_eq!(parse("Die.Fälscher.2007.720p").title, "Die Fälscher");
+        // macOS: decomposed umlaut
+        assert_eq!(parse("Die.Fa\u{308}lscher.2007.720p").title, "Die Fälscher");
+        assert_eq!(parse("Die.Faelscher.2007.720p").title, "Die Faelscher");
+    }
+
+    #[test]
+    fn test_compose_umlauts() {
+        assert_eq!(
+            compose_umlauts("U\u{308}ber Mu\u{308}nchen"),
+            "Über München"
+        );
+        assert_eq!(compose_umlauts("e\u{308}"), "e\u{308}");
+    }
+
+    #[test]
+    fn test_title_key() {
+        assert_eq!(title_key("Die Fälscher"), title_key("Die.Faelscher"));
+        assert_eq!(title_key("Die Fa\u{308}lscher"), "die faelscher");
+        assert_eq!(title_key("Alien: Covenant"), "alien covenant");
+        assert_eq!(title_key("  Spider-Man  "), "spider man");
+    }
+
+    #[test]
+    fn test_resolution_aliases() {
+        assert_eq!(
+            parse("Dune.2021.4K.HDR").resolution.as_deref(),
+            Some("2160p")
+        );
+        assert_eq!(
+            parse("Dune.2021.1080i").resolution.as_deref(),
+            Some("1080p")
+        );
+        assert_eq!(parse("Dune.2021.DVD9").resolution, None);
+    }
+
+    #[test]
+    fn test_empty_title_falls_back_to_name() {
+        assert_eq!(parse("1080p").title, "1080p");
+        assert_eq!(parse("").title, "");
+    }
+}
@@ -0,0 +1,4 @@
+//! Parsers for text from outside the catalog, shared by importers and scanners
+
+pub mod filename;
+pub mod title;
@@ -0,0 +1,255 @@
+//! Title normalization shared by search, suggestions and duplicate detection
+
+use super::filename::compose_umlauts;
+
+/// Accented letters and the letter they are folded to
+const ACCENTED: &[(&str, char)] = &[
+    ("àáâãåāăą", 'a'),
+    ("çćĉċč", 'c'),
+    ("ďđð", 'd'),
+    ("èéêëēĕėęě", 'e'),
+    ("ĝğġģ", 'g'),
+    ("ĥħ", 'h'),
+    ("ìíîïĩīĭįı", 'i'),
+    ("ĵ", 'j'),
+    ("ķ", 'k'),
+    ("ĺļľŀł", 'l'),
+    ("ñńņňŉ", 'n'),
+    ("òóôõōŏőø", 'o'),
+    ("ŕŗř", 'r'),
+    ("śŝşšș", 's'),
+    ("ţťŧț", 't'),
+    ("ùúûũūŭůűų", 'u'),
+    ("ŵ", 'w'),
+    ("ýÿŷ", 'y'),
+    ("źżž", 'z'),
+];
+
+/// Letters written as two in ASCII; umlauts follow the German convention
+fn spelled_out(c: char) -> Option<&'static str> {
+    match c {
+        'ä' => Some("ae"),
+        'ö' => Some("oe"),
+        'ü' => Some("ue"),
+        'ß' => Some("ss"),
+        'æ' => Some("ae"),
+        'œ' => Some("oe"),
+        'þ' => Some("th"),
+        _ => None,
+    }
+}
+
+/// Lowercased title with umlauts, ß and ligatures written in ASCII:
+/// "Größe" gives "groesse", also when the umlaut is a combining diaeresis
+pub fn transliterate(title: &str) -> String {
+    let mut out = String::with_capacity(title.len() + 4);
+    for c in compose_umlauts(title).chars().flat_map(char::to_lowercase) {
+        match spelled_out(c) {
+            Some(ascii) => out.push_str(ascii),
+            None => out.push(c),
+        }
+    }
+    out
+}
+
+/// Number words up to ten, English and German (already folded). German
+/// "ein" is left out, it is mostly the article.
+const NUMBER_WORDS: &[(&str, u32)] = &[
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+    ("ten", 10),
+    ("eins", 1),
+    ("zwei", 2),
+    ("drei", 3),
+    ("vier", 4),
+    ("fuenf", 5),
+    ("sechs", 6),
+    ("sieben", 7),
+    ("acht", 8),
+    ("neun", 9),
+    ("zehn", 10),
+];
+
+/// Value of a roman numeral up to 39, only in its canonical spelling
+fn roman_value(word: &str) -> Option<u32> {
+    if word.is_empty() || word.len() > 6 {
+        return None;
+    }
+    let value = |c| match c {
+        'i' => Some(1),
+        'v' => Some(5),
+        'x' => Some(10),
+        _ => None,
+    };
+    let digits: Vec<i32> = word.chars().map(value).collect::<Option<_>>()?;
+    let mut total = 0;
+    for (i, digit) in digits.iter().enumerate() {
+        if digits.get(i + 1).is_some_and(|next| next > digit) {
+            total -= digit;
+        } else {
+            total += digit;
+        }
+    }
+    let total = u32::try_from(total).ok().filter(|t| (1..40).contains(t))?;
+    (to_roman(total) == word).then_some(total)
+}
+
+fn to_roman(mut value: u32) -> String {
+    let mut roman = String::new();
+    for (step, letters) in [(10, "x"), (9, "ix"), (5, "v"), (4, "iv"), (1, "i")] {
+        while value >= step {
+            roman.push_str(letters);
+            value -= step;
+        }
+    }
+    roman
+}
+
+/// Number spelled with one digit in place of a letter: "se7en" -> 7
+fn leet_number(word: &str) -> Option<u32> {
+    let mut digits = word.chars().filter(char::is_ascii_digit);
+    let digit = digits.next()?.to_digit(10)?;
+    if digits.next().is_some() {
+        return None;
+    }
+    NUMBER_WORDS
+        .iter()
+        .filter(|(_, value)| *value == digit)
+        .find(|(name, _)| {
+            name.len() == word.len()
+                && name
+                    .chars()
+                    .zip(word.chars())
+                    .all(|(a, b)| a == b || b.is_ascii_digit())
+        })
+        .map(|(_, value)| *value)
+}
+
+/// Write numbers as digits in a folded title: "rocky ii" and "rocky two" give
+/// "rocky 2", "sieben" and "se7en" give "7". Single-letter numerals (i, v, x)
+/// only count as the last of several words, so "I, Robot" and "X" stay.
+fn normalize_numerals(folded: &str) -> String {
+    let words: Vec<&str> = folded.split(' ').collect();
+    let last = words.len() - 1;
+    words
+        .iter()
+        .enumerate()
+        .map(|(i, word)| {
+            let number = NUMBER_WORDS
+                .iter()
+                .find(|(name, _)| name == word)
+                .map(|(_, value)| *value)
+                .or_else(|| leet_number(word))
+                .or_else(|| {
+                    roman_value(word).filter(|_| word.len() > 1 || (i == last && last > 0))
+                });
+            number.map_or_else(|| word.to_string(), |n| n.to_string())
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Whether two titles name the same thing once folded (see `fold_title`)
+pub fn titles_match(a: &str, b: &str) -> bool {
+    let a = fold_title(a);
+    !a.is_empty() && a == fold_title(b)
+}
+
+/// Lowercased, transliterated and unaccented form of a title, so differently
+/// written titles compare equal: "König" and "Koenig" both give "koenig",
+/// "Amélie" gives "amelie". Punctuation and whitespace runs become one space
+/// and numbers are written as digits ("Rocky II" gives "rocky 2").
+pub fn fold_title(title: &str) -> String {
+    let mut folded = String::with_capacity(title.len());
+    let mut after_space = true;
+    for c in transliterate(title).chars() {
+        if let Some((_, base)) = ACCENTED.iter().find(|(set, _)| set.contains(c)) {
+            folded.push(*base);
+        } else if c.is_alphanumeric() {
+            folded.push(c);
+        } else {
+            if !after_space {
+                folded.push(' ');
+                after_space = true;
+            }
+            continue;
+        }
+        after_space = false;
+    }
+    folded.truncate(folded.trim_end().len());
+    normalize_numerals(&folded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transliterate() {
+        assert_eq!(transliterate("Schöne Grüße"), "schoene gruesse");
+        assert_eq!(transliterate("Ärger Öl Übel"), "aerger oel uebel");
+        assert_eq!(transliterate("Die Fa\u{308}lscher"), "die faelscher");
+        assert_eq!(transliterate("Der Untergang"), "der untergang");
+    }
+
+    #[test]
+    fn test_fold_title() {
+        assert_eq!(fold_title("König der Löwen"), "koenig der loewen");
+        assert_eq!(fold_title("Koenig der Loewen"), "koenig der loewen");
+        assert_eq!(
+            fold_title("Die fabelhafte Welt der Amélie"),
+            "die fabelhafte welt der amelie"
+        );
+        assert_eq!(fold_title("Straße"), "strasse");
+        assert_eq!(fold_title("  Ocean's  Eleven! "), "ocean s eleven");
+        assert_eq!(
+            fold_title("Le Fabuleux Destin d'Amélie Poulain"),
+            "le fabuleux destin d amelie poulain"
+        );
+        assert_eq!(fold_title("Ærø"), "aero");
+        assert_eq!(fold_title(""), "");
+    }
+
+    #[test]
+    fn test_fold_title_numerals() {
+        assert_eq!(fold_title("Rocky II"), "rocky 2");
+        assert_eq!(fold_title("Rocky 2"), "rocky 2");
+        assert_eq!(
+            fold_title("Star Trek IV: Zurück in die Gegenwart"),
+            "star trek 4 zurueck in die gegenwart"
+        );
+        assert_eq!(fold_title("Sieben"), "7");
+        assert_eq!(fold_title("Se7en"), "7");
+        assert_eq!(fold_title("Seven"), "7");
+        assert_eq!(
+            fold_title("Final Destination 5"),
+            fold_title("Final Destination V")
+        );
+        assert_eq!(fold_title("Fast X"), "fast 10");
+        // Single letters elsewhere are words
+        assert_eq!(fold_title("I, Robot"), "i robot");
+        assert_eq!(fold_title("X"), "x");
+        assert_eq!(
+            fold_title("Ein Fisch namens Wanda"),
+            "ein fisch namens wanda"
+        );
+        // Not canonical, not numerals
+        assert_eq!(fold_title("Mix"), "mix");
+        assert_eq!(fold_title("Rocky IIII"), "rocky iiii");
+        assert_eq!(fold_title("Civil War"), "civil war");
+        assert_eq!(fold_title("2001: A Space Odyssey"), "2001 a space odyssey");
+        assert_eq!(fold_title("K2"), "k2");
+
+        assert!(titles_match("Rocky II", "ROCKY 2"));
+        assert!(titles_match("Sieben", "Se7en"));
+        assert!(!titles_match("Rocky II", "Rocky III"));
+        assert!(!titles_match("", ""));
+    }
+}
@@ -0,0 +1,411 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{Map, Value, json};
+use sqlx::SqliteExecutor;
+use uuid::Uuid;
+
+use crate::db::DbPool;
+use crate::error::{Error, Result};
+use crate::models::{ActivityAction, ActivityEntry, ActivityFilter, EntityType};
+
+const DEFAULT_LIMIT: i64 = 100;
+const MAX_LIMIT: i64 = 1000;
+
+/// Fields that change with every write or are derived from other fields
+const IGNORED_FIELDS: &[&str] = &["created_at", "updated_at", "updated_by", "search_title"];
+
+/// Record an activity. Takes an executor so it can run in the same
+/// transaction as the change.
+pub(crate) async fn record_activity(
+    executor: impl SqliteExecutor<'_>,
+    user_id: Uuid,
+    action: ActivityAction,
+    entity_type: EntityType,
+    entity_id: Uuid,
+    title: Option<&str>,
+    changes: Option<&Value>,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO activity_log (user_id, action, entity_type, entity_id, title, changes, created_at) \
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(user_id)
+    .bind(action)
+    .bind(entity_type)
+    .bind(entity_id)
+    .bind(title)
+    .bind(changes.map(Value::to_string))
+    .bind(Utc::now().to_rfc3339())
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// Record an update with the fields that differ between `before` and
+/// `after`; nothing is recorded if no field changed
+pub(crate) async fn record_changes<T: Serialize>(
+    executor: impl SqliteExecutor<'_>,
+    user_id: Uuid,
+    entity_type: EntityType,
+    entity_id: Uuid,
+    title: &str,
+    before: &T,
+    after: &T,
+) -> Result<()> {
+    let Some(changes) = changed_fields(before, after) else {
+        return Ok(());
+    };
+    record_activity(
+        executor,
+        user_id,
+        ActivityAction::Update,
+        entity_type,
+        entity_id,
+        Some(title),
+        Some(&changes),
+    )
+    .await
+}
+
+/// `{"field": {"old": ..., "new": ...}}` of the serialized fields that differ
+pub(crate) fn changed_fields<T: Serialize>(before: &T, after: &T) -> Option<Value> {
+    let (Ok(Value::Object(before)), Ok(Value::Object(after))) =
+        (serde_json::to_value(before), serde_json::to_value(after))
+    else {
+        return None;
+    };
+
+    let mut changes = Map::new();
+    for field in before.keys().chain(after.keys()) {
+        if IGNORED_FIELDS.contains(&field.as_str()) || changes.contains_key(field) {
+            continue;
+        }
+        // Fields skipped when empty are missing on one side
+        let old = before.get(field).unwrap_or(&Value::Null);
+        let new = after.get(field).unwrap_or(&Value::Null);
+        if old != new {
+            changes.insert(field.clone(), json!({ "old": old, "new": new }));
+        }
+    }
+
+    (!changes.is_empty()).then_some(Value::Object(changes))
+}
+
+#[derive(sqlx::FromRow)]
+struct ActivityRow {
+    id: i64,
+    action: ActivityAction,
+    entity_type: EntityType,
+    entity_id: Uuid,
+    title: Option<String>,
+    changes: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+impl From<ActivityRow> for ActivityEntry {
+    fn from(row: ActivityRow) -> Self {
+        Self {
+            id: row.id,
+            action: row.action,
+            entity_type: row.entity_type,
+            entity_id: row.entity_id,
+            title: row.title,
+            changes: row.changes.and_then(|c| serde_json::from_str(&c).ok()),
+            created_at: row.created_at,
+        }
+    }
+}
+
+/// Audit log of created, changed, deleted and imported movies, series and collections
+pub struct ActivityService {
+    pool: DbPool,
+}
+
+impl ActivityService {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Activity of a user, newest first
+    pub async fn list(&self, user_id: Uuid, filter: &ActivityFilter) -> Result<Vec<ActivityEntry>> {
+        if let (Some(from), Some(to)) = (filter.from, filter.to)
+            && from > to
+        {
+            return Err(Error::Validation("`from` must not be after `to`".into()));
+        }
+
+        let mut query = String::from(
+            "SELECT id, action, entity_type, entity_id, title, changes, created_at \
+             FROM activity_log WHERE user_id = ?",
+        );
+        if filter.entity_type.is_some() {
+            query.push_str(" AND entity_type = ?");
+        }
+        if filter.entity_id.is_some() {
+            query.push_str(" AND entity_id = ?");
+        }
+        if filter.action.is_some() {
+            query.push_str(" AND action = ?");
+        }
+        if filter.from.is_some() {
+            query.push_str(" AND date(created_at) >= ?");
+        }
+        if filter.to.is_some() {
+            query.push_str(" AND date(created_at) <= ?");
+        }
+        query.push_str(" ORDER BY id DESC LIMIT ? OFFSET ?");
+
+        let mut q = sqlx::query_as::<_, ActivityRow>(&query).bind(user_id);
+        if let Some(entity_type) = filter.entity_type {
+            q = q.bind(entity_type);
+        }
+        if let Some(entity_id) = filter.entity_id {
+            q = q.bind(entity_id);
+        }
+        if let Some(action) = filter.action {
+            q = q.bind(action);
+        }
+        if let Some(from) = filter.from {
+            q = q.bind(from.to_string());
+        }
+        if let Some(to) = filter.to {
+            q = q.bind(to.to_string());
+        }
+        let rows = q
+            .bind(filter.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT))
+            .bind(filter.offset.unwrap_or(0).max(0))
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.into_iter().map(ActivityEntry::from).collect())
+    }
+
+    /// Remove activity older than `before`
+    pub async fn prune(&self, before: DateTime<Utc>) -> Result<u64> {
+        let result =
+            sqlx::query("DELETE FROM activity_log WHERE julianday(created_at) < julianday(?)")
+                .bind(before.to_rfc3339())
+                .execute(&self.pool)
+                .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        BulkUpdateMovies, CreateCollection, CreateMovie, CreateSeries, UpdateMovie, UpdateSeries,
+    };
+    use crate::services::{CollectionService, ImportService, MovieService, SeriesService};
+    use crate::test_helpers::{create_test_db_with_users, fixtures};
+
+    #[test]
+    fn test_changed_fields() {
+        let before = json!({ "title": "Matrix", "year": 1999, "updated_at": "a", "tags": "x" });
+        let after = json!({ "title": "The Matrix", "year": 1999, "updated_at": "b" });
+
+        let changes = changed_fields(&before, &after).unwrap();
+        assert_eq!(
+            changes,
+            json!({
+                "title": { "old": "Matrix", "new": "The Matrix" },
+                "tags": { "old": "x", "new": null },
+            })
+        );
+        assert_eq!(changed_fields(&before, &before), None);
+    }
+
+    #[tokio::test]
+    async fn test_movie_changes_are_logged() {
+        let pool = create_test_db_with_users().await;
+        let service = ActivityService::new(pool.clone());
+        let movies = MovieService::new(pool.clone());
+        let user_id = fixtures::test_user_id();
+
+        let movie = movies
+            .create(
+                user_id,
+                CreateMovie {
+                    barcode: None,
+                    tmdb_id: None,
+                    title: "Matrix".to_string(),
+                    original_title: None,
+                    disc_type: None,
+                    production_year: None,
+                },
+            )
+            .await
+            .unwrap();
+        movies
+            .update(
+                user_id,
+                movie.id,
+                UpdateMovie {
+                    title: Some("The Matrix".to_string()),
+                    location: Some("Shelf A".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        // Nothing changed, nothing logged
+        movies
+            .update(
+                user_id,
+                movie.id,
+                UpdateMovie {
+                    location: Some("Shelf A".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        movies
+            .bulk_update(
+                user_id,
+                BulkUpdateMovies {
+                    ids: vec![movie.id],
+                    fields: serde_json::from_value(json!({ "watched": true })).unwrap(),
+                },
+            )
+            .await
+            .unwrap();
+        movies.delete(user_id, movie.id).await.unwrap();
+
+        let entries = service
+            .list(user_id, &ActivityFilter::default())
+            .await
+            .unwrap();
+        let actions: Vec<ActivityAction> = entries.iter().map(|e| e.action).collect();
+        assert_eq!(
+            actions,
+            vec![
+                ActivityAction::Delete,
+                ActivityAction::Update,
+                ActivityAction::Update,
+                ActivityAction::Create,
+            ]
+        );
+        assert!(entries.iter().all(|e| e.entity_id == movie.id));
+        assert_eq!(entries[0].title.as_deref(), Some("The Matrix"));
+
+        let changes = entries[2].changes.as_ref().unwrap();
+        assert_eq!(
+            changes["title"],
+            json!({ "old": "Matrix", "new": "The Matrix" })
+        );
+        assert_eq!(changes["location"]["new"], "Shelf A");
+        assert!(changes.get("updated_at").is_none());
+        assert_eq!(
+            entries[1].changes.as_ref().unwrap()["watched"],
+            json!({ "old": false, "new": true })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_filter_by_entity_action_and_date() {
+        let pool = create_test_db_with_users().await;
+        let service = ActivityService::new(pool.clone());
+        let user_id = fixtures::test_user_id();
+
+        let series = SeriesService::new(pool.clone())
+            .create(
+                user_id,
+                CreateSeries {
+                    barcode: None,
+                    tmdb_id: None,
+                    title: "Dark".to_string(),
+                    disc_type: None,
+                },
+            )
+            .await
+            .unwrap();
+        SeriesService::new(pool.clone())
+            .update(
+                user_id,
+                series.id,
+                UpdateSeries {
+                    network: Some("Netflix".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        CollectionService::new(pool.clone())
+            .create(
+                user_id,
+                CreateCollection {
+                    barcode: None,
+                    title: "Nolan".to_string(),
+                    description: None,
+                    disc_type: None,
+                },
+            )
+            .await
+            .unwrap();
+        let csv = "Title,Type\nAlien,\nFirefly,Series\n";
+        ImportService::new(pool.clone())
+            .import_csv(user_id, csv.as_bytes(), None)
+            .await
+            .unwrap();
+
+        let list = |filter: ActivityFilter| {
+            let service = &service;
+            async move { service.list(user_id, &filter).await.unwrap() }
+        };
+
+        let series_activity = list(ActivityFilter {
+            entity_type: Some(EntityType::Series),
+            entity_id: Some(series.id),
+            ..Default::default()
+        })
+        .await;
+        assert_eq!(series_activity.len(), 2);
+        assert_eq!(
+            series_activity[0].changes.as_ref().unwrap()["network"]["new"],
+            "Netflix"
+        );
+
+        let imported = list(ActivityFilter {
+            action: Some(ActivityAction::Import),
+            ..Default::default()
+        })
+        .await;
+        let titles: Vec<&str> = imported.iter().filter_map(|e| e.title.as_deref()).collect();
+        assert_eq!(titles, vec!["Firefly", "Alien"]);
+        assert_eq!(imported[0].entity_type, EntityType::Series);
+
+        let today = Utc::now().date_naive();
+        let all = list(ActivityFilter {
+            from: Some(today),
+            to: Some(today),
+            ..Default::default()
+        })
+        .await;
+        assert_eq!(all.len(), 5);
+        let none = list(ActivityFilter {
+            from: Some(today.succ_opt().unwrap()),
+            ..Default::default()
+        })
+        .await;
+        assert!(none.is_empty());
+
+        let paged = list(ActivityFilter {
+            limit: Some(2),
+            offset: Some(1),
+            ..Default::default()
+        })
+        .await;
+        assert_eq!(paged.len(), 2);
+        assert_eq!(paged[0].id, all[1].id);
+
+        // Other users see nothing
+        let other = service
+            .list(fixtures::test_admin_id(), &ActivityFilter::default())
+            .await
+            .unwrap();
+        assert!(other.is_empty());
+    }
+}
@@ -0,0 +1,374 @@
+use chrono::Utc;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::db::DbPool;
+use crate::error::{Error, Result};
+use crate::models::{
+    ApiKey, ApiKeyPublic, ApiScope, Claims, CreateApiKey, CreatedApiKey, ExportedApiKey, UserRole,
+};
+
+/// Prefix of all API keys, used to tell them apart from JWTs
+pub const API_KEY_PREFIX: &str = "mm_";
+
+/// Number of characters of the key stored in clear text for display
+const DISPLAY_PREFIX_LEN: usize = 11;
+
+pub struct ApiKeyService {
+    pool: DbPool,
+}
+
+impl ApiKeyService {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    pub fn is_api_key(token: &str) -> bool {
+        token.starts_with(API_KEY_PREFIX)
+    }
+
+    fn hash(token: &str) -> String {
+        format!("{:x}", Sha256::digest(token.as_bytes()))
+    }
+
+    /// Create a key for a user. Only admins may create keys with the `admin` scope.
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        role: &UserRole,
+        input: CreateApiKey,
+    ) -> Result<CreatedApiKey> {
+        let name = input.name.trim();
+        if name.is_empty() {
+            return Err(Error::Validation("Name is required".to_string()));
+        }
+        if input.scopes.is_empty() {
+            return Err(Error::Validation(
+                "At least one scope is required".to_string(),
+            ));
+        }
+        if input.scopes.contains(&ApiScope::Admin) && *role != UserRole::Admin {
+            return Err(Error::Forbidden);
+        }
+
+        // Canonical order without duplicates
+        let scopes = ApiScope::ALL
+            .iter()
+            .filter(|s| input.scopes.contains(s))
+            .map(|s| s.as_str())
+            .collect::<Vec<_>>()
+            .join(",");
+
+        // Two v4 UUIDs give 244 random bits
+        let token = format!(
+            "{}{}{}",
+            API_KEY_PREFIX,
+            Uuid::new_v4().simple(),
+            Uuid::new_v4().simple()
+        );
+        let id = Uuid::new_v4();
+        let now = Utc::now();
+
+        sqlx::query(
+            r#"
+            INSERT INTO api_keys (id, user_id, name, token_hash, token_prefix, scopes, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(name)
+        .bind(Self::hash(&token))
+        .bind(&token[..DISPLAY_PREFIX_LEN])
+        .bind(&scopes)
+        .bind(now.to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+
+        let key = sqlx::query_as::<_, ApiKey>("SELECT * FROM api_keys WHERE id = ?")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(CreatedApiKey {
+            token,
+            key: key.into(),
+        })
+    }
+
+    pub async fn list(&self, user_id: Uuid) -> Result<Vec<ApiKeyPublic>> {
+        let keys = sqlx::query_as::<_, ApiKey>(
+            "SELECT * FROM api_keys WHERE user_id = ? ORDER BY created_at DESC",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(keys.into_iter().map(Into::into).collect())
+    }
+
+    /// The user's keys for a backup
+    pub async fn export(&self, user_id: Uuid) -> Result<Vec<ExportedApiKey>> {
+        let keys = sqlx::query_as::<_, ApiKey>(
+            "SELECT * FROM api_keys WHERE user_id = ? ORDER BY created_at",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(keys.into_iter().map(Into::into).collect())
+    }
+
+    /// Re-create keys from a backup. Keys that already exist are skipped and
+    /// the `admin` scope is dropped for non-admins. Returns the number restored.
+    pub async fn restore(
+        &self,
+        user_id: Uuid,
+        role: &UserRole,
+        keys: &[ExportedApiKey],
+    ) -> Result<usize> {
+        let mut restored = 0;
+        for key in keys {
+            let scopes = ApiScope::ALL
+                .iter()
+                .filter(|s| key.scopes.contains(s))
+                .filter(|s| **s != ApiScope::Admin || *role == UserRole::Admin)
+                .map(|s| s.as_str())
+                .collect::<Vec<_>>()
+                .join(",");
+            if scopes.is_empty() || key.name.trim().is_empty() {
+                continue;
+            }
+
+            let result = sqlx::query(
+                r#"
+                INSERT INTO api_keys (id, user_id, name, token_hash, token_prefix, scopes,
+                    created_at, last_used_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                ON CONFLICT(token_hash) DO NOTHING
+                "#,
+            )
+            .bind(Uuid::new_v4())
+            .bind(user_id)
+            .bind(key.name.trim())
+            .bind(&key.token_hash)
+            .bind(&key.token_prefix)
+            .bind(&scopes)
+            .bind(key.created_at.to_rfc3339())
+            .bind(key.last_used_at.map(|at| at.to_rfc3339()))
+            .execute(&self.pool)
+            .await?;
+            restored += result.rows_affected() as usize;
+        }
+        Ok(restored)
+    }
+
+    pub async fn delete(&self, user_id: Uuid, id: Uuid) -> Result<()> {
+        let result = sqlx::query("DELETE FROM api_keys WHERE id = ? AND user_id = ?")
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(Error::NotFound);
+        }
+
+        Ok(())
+    }
+
+    /// Resolve an API key to claims of its owner, restricted to the key's scopes
+    pub async fn authenticate(&self, token: &str) -> Result<Claims> {
+        let row = sqlx::query_as::<_, (Uuid, Uuid, String, String, UserRole)>(
+            r#"
+            SELECT k.id, k.user_id, k.scopes, u.username, u.role
+            FROM api_keys k
+            JOIN users u ON u.id = k.user_id
+            WHERE k.token_hash = ?
+            "#,
+        )
+        .bind(Self::hash(token))
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let Some((key_id, user_id, scopes, username, role)) = row else {
+            return Err(Error::Auth("Invalid API key".to_string()));
+        };
+
+        sqlx::query("UPDATE api_keys SET last_used_at = ? WHERE id = ?")
+            .bind(Utc::now().to_rfc3339())
+            .bind(key_id)
+            .execute(&self.pool)
+            .await?;
+
+        let now = Utc::now().timestamp();
+        Ok(Claims {
+            id: user_id,
+            username,
+            role,
+            iat: now,
+            // API keys don't expire; they are revoked by deleting them
+            exp: i64::MAX,
+            sid: None,
+            scopes: Some(ApiScope::parse_list(&scopes)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{create_test_db_with_users, fixtures};
+
+    fn create_input(scopes: Vec<ApiScope>) -> CreateApiKey {
+        CreateApiKey {
+            name: "Home Assistant".to_string(),
+            scopes,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_and_authenticate() {
+        let pool = create_test_db_with_users().await;
+        let service = ApiKeyService::new(pool);
+        let user_id = fixtures::test_user_id();
+
+        let created = service
+            .create(
+                user_id,
+                &UserRole::User,
+                create_input(vec![ApiScope::MoviesRead]),
+            )
+            .await
+            .unwrap();
+        assert!(ApiKeyService::is_api_key(&created.token));
+        assert!(created.token.starts_with(&created.key.token_prefix));
+
+        let claims = service.authenticate(&created.token).await.unwrap();
+        assert_eq!(claims.id, user_id);
+        assert_eq!(claims.role, UserRole::User);
+        assert_eq!(claims.scopes, Some(vec![ApiScope::MoviesRead]));
+
+        let keys = service.list(user_id).await.unwrap();
+        assert_eq!(keys.len(), 1);
+        assert!(keys[0].last_used_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_export_and_restore() {
+        let pool = create_test_db_with_users().await;
+        let service = ApiKeyService::new(pool);
+        let admin_id = fixtures::test_admin_id();
+        let user_id = fixtures::test_user_id();
+
+        let created = service
+            .create(
+                admin_id,
+                &UserRole::Admin,
+                create_input(vec![ApiScope::MoviesRead, ApiScope::Admin]),
+            )
+            .await
+            .unwrap();
+        let exported = service.export(admin_id).await.unwrap();
+        assert_eq!(exported.len(), 1);
+        assert_eq!(
+            exported[0].scopes,
+            vec![ApiScope::MoviesRead, ApiScope::Admin]
+        );
+
+        // Same instance: the key exists already
+        assert_eq!(
+            service
+                .restore(admin_id, &UserRole::Admin, &exported)
+                .await
+                .unwrap(),
+            0
+        );
+
+        // Restored for another account, the key keeps working without `admin`
+        service.delete(admin_id, created.key.id).await.unwrap();
+        assert_eq!(
+            service
+                .restore(user_id, &UserRole::User, &exported)
+                .await
+                .unwrap(),
+            1
+        );
+        let claims = service.authenticate(&created.token).await.unwrap();
+        assert_eq!(claims.id, user_id);
+        assert_eq!(claims.scopes, Some(vec![ApiScope::MoviesRead]));
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_rejects_unknown_and_deleted_keys() {
+        let pool = create_test_db_with_users().await;
+        let service = ApiKeyService::new(pool);
+        let user_id = fixtures::test_user_id();
+
+        assert!(service.authenticate("mm_invalid").await.is_err());
+
+        let created = service
+            .create(
+                user_id,
+                &UserRole::User,
+                create_input(vec![ApiScope::MoviesWrite]),
+            )
+            .await
+            .unwrap();
+        service.delete(user_id, created.key.id).await.unwrap();
+
+        assert!(service.authenticate(&created.token).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_admin_scope_requires_admin_role() {
+        let pool = create_test_db_with_users().await;
+        let service = ApiKeyService::new(pool);
+
+        let result = service
+            .create(
+                fixtures::test_user_id(),
+                &UserRole::User,
+                create_input(vec![ApiScope::Admin]),
+            )
+            .await;
+        assert!(matches!(result, Err(Error::Forbidden)));
+
+        let result = service
+            .create(
+                fixtures::test_admin_id(),
+                &UserRole::Admin,
+                create_input(vec![ApiScope::Admin]),
+            )
+            .await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_delete_other_users_key_fails() {
+        let pool = create_test_db_with_users().await;
+        let service = ApiKeyService::new(pool);
+
+        let created = service
+            .create(
+                fixtures::test_user_id(),
+                &UserRole::User,
+                create_input(vec![ApiScope::MoviesRead]),
+            )
+            .await
+            .unwrap();
+
+        let result = service
+            .delete(fixtures::test_admin_id(), created.key.id)
+            .await;
+        assert!(matches!(result, Err(Error::NotFound)));
+    }
+
+    #[test]
+    fn test_scope_grants() {
+        assert!(ApiScope::Admin.grants(ApiScope::MoviesWrite));
+        assert!(ApiScope::MoviesWrite.grants(ApiScope::MoviesRead));
+        assert!(!ApiScope::MoviesRead.grants(ApiScope::MoviesWrite));
+        assert!(!ApiScope::MoviesWrite.grants(ApiScope::Admin));
+    }
+}
@@ -2,28 +2,58 @@ use argon2::{
     Argon2,
     password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString, rand_core::OsRng},
 };
-use chrono::{Duration, Utc};
-use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation, decode, encode};
+use chrono::{DateTime, Duration, Utc};
+use sha2::{Digest, Sha256};
 use uuid::Uuid;
 
 use crate::db::DbPool;
 use crate::error::{Error, Result};
 use crate::models::{
-    AuthResponse, Claims, CreateUser, ForgotPasswordRequest, LoginRequest, ResetPasswordRequest,
-    User, UserPublic, UserRole,
+    AuthResponse, Claims, CreateUser, DigestSettings, ForgotPasswordRequest, ImportPreset,
+    LoanReminderSettings, LoginRequest, MovieDefaults, ResetPasswordRequest, Session,
+    TmdbPreferences, User, UserPreferences, UserPublic, UserRole, normalize_currency,
+    normalize_region_codes, normalize_timezone,
 };
+use crate::services::tmdb::{
+    BACKDROP_SIZES, POSTER_SIZES, normalize_image_size, normalize_tmdb_languages,
+};
+use crate::services::{ImageService, JwtKeys, validate_import_presets};
+
+/// Longest lead time for loan reminders
+const MAX_LOAN_REMINDER_DAYS: i32 = 60;
+
+/// Lifetime of access tokens; clients get new ones with their refresh token
+pub const ACCESS_TOKEN_MINUTES: i64 = 60;
+
+/// Sessions expire after this many days without a refresh
+const SESSION_DAYS: i64 = 30;
+
+const MAX_DEVICE_LEN: usize = 200;
+
+/// (id, user_id, expires_at, revoked_at) of a stored session
+type SessionRow = (Uuid, Uuid, DateTime<Utc>, Option<DateTime<Utc>>);
 
 pub struct AuthService {
     pool: DbPool,
-    jwt_secret: String,
+    keys: JwtKeys,
 }
 
 impl AuthService {
+    /// Tokens signed with an HMAC secret
     pub fn new(pool: DbPool, jwt_secret: String) -> Self {
-        Self { pool, jwt_secret }
+        Self::with_keys(pool, JwtKeys::hmac(&jwt_secret))
+    }
+
+    pub fn with_keys(pool: DbPool, keys: JwtKeys) -> Self {
+        Self { pool, keys }
     }
 
     pub async fn register(&self, input: CreateUser) -> Result<AuthResponse> {
+        self.register_as(input, UserRole::User).await
+    }
+
+    /// Register with the role of an invite; the first user is admin anyway
+    pub async fn register_as(&self, input: CreateUser, role: UserRole) -> Result<AuthResponse> {
         // Check if username or email already exists
         let existing = sqlx::query_scalar::<_, i64>(
             "SELECT COUNT(*) FROM users WHERE username = ? OR email = ?",
@@ -56,7 +86,7 @@ impl AuthService {
         let role = if user_count == 0 {
             UserRole::Admin
         } else {
-            UserRole::User
+            role
         };
 
         let role_str = match role {
@@ -95,14 +125,34 @@ impl AuthService {
             reset_token_expires: None,
             theme: None,     // Will use system default
             card_size: None, // Will use medium default
+            player_regions: None,
+            preferred_currency: None,
+            default_disc_type: None,
+            default_location: None,
+            default_media_type: None,
+            default_currency: None,
+            loan_reminders: LoanReminderSettings::default().enabled,
+            loan_reminder_days: LoanReminderSettings::default().days_before,
+            timezone: None,
+            digest_enabled: DigestSettings::default().enabled,
+            digest_weekday: DigestSettings::default().weekday,
+            digest_hour: DigestSettings::default().hour,
+            digest_sent_at: None,
+            tmdb_poster_size: None,
+            tmdb_backdrop_size: None,
+            tmdb_languages: None,
+            import_presets: "[]".to_string(),
         };
 
-        let token = self.create_token(&user)?;
+        self.start_session(&user, None).await
+    }
 
-        Ok(AuthResponse {
-            token,
-            user: user.into(),
-        })
+    /// Whether anyone has registered yet (the first account is always allowed)
+    pub async fn has_users(&self) -> Result<bool> {
+        let found: Option<i64> = sqlx::query_scalar("SELECT 1 FROM users LIMIT 1")
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(found.is_some())
     }
 
     pub async fn login(&self, input: LoginRequest) -> Result<AuthResponse> {
@@ -120,17 +170,225 @@ impl AuthService {
             .verify_password(input.password.as_bytes(), &parsed_hash)
             .map_err(|_| Error::InvalidCredentials)?;
 
-        let token = self.create_token(&user)?;
+        self.start_session(&user, input.device.as_deref()).await
+    }
+
+    fn hash_token(token: &str) -> String {
+        format!("{:x}", Sha256::digest(token.as_bytes()))
+    }
+
+    fn new_refresh_token() -> String {
+        // Two v4 UUIDs give 244 random bits
+        format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+    }
+
+    async fn start_session(&self, user: &User, device: Option<&str>) -> Result<AuthResponse> {
+        let id = Uuid::new_v4();
+        let refresh_token = Self::new_refresh_token();
+        let now = Utc::now();
+        let device = device
+            .map(|d| d.trim().chars().take(MAX_DEVICE_LEN).collect::<String>())
+            .filter(|d| !d.is_empty());
+
+        sqlx::query(
+            r#"
+            INSERT INTO sessions (id, user_id, refresh_token_hash, device, created_at, last_used_at, expires_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id)
+        .bind(user.id)
+        .bind(Self::hash_token(&refresh_token))
+        .bind(device)
+        .bind(now.to_rfc3339())
+        .bind(now.to_rfc3339())
+        .bind((now + Duration::days(SESSION_DAYS)).to_rfc3339())
+        .execute(&self.pool)
+        .await?;
 
         Ok(AuthResponse {
-            token,
+            token: self.create_token(user, Some(id))?,
+            refresh_token,
+            expires_in: ACCESS_TOKEN_MINUTES * 60,
+            user: user.clone().into(),
+        })
+    }
+
+    /// Exchange a refresh token for a new access token. The refresh token is
+    /// rotated; presenting an already rotated one revokes the session, since
+    /// someone else must have a copy.
+    pub async fn refresh(&self, refresh_token: &str) -> Result<AuthResponse> {
+        let hash = Self::hash_token(refresh_token);
+        let now = Utc::now();
+
+        let session: Option<SessionRow> = sqlx::query_as(
+            "SELECT id, user_id, expires_at, revoked_at FROM sessions WHERE refresh_token_hash = ?",
+        )
+        .bind(&hash)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let Some((id, user_id, expires_at, revoked_at)) = session else {
+            let reused = sqlx::query(
+                "UPDATE sessions SET revoked_at = ? WHERE previous_token_hash = ? AND revoked_at IS NULL",
+            )
+            .bind(now.to_rfc3339())
+            .bind(&hash)
+            .execute(&self.pool)
+            .await?
+            .rows_affected();
+            if reused > 0 {
+                tracing::warn!("Rotated refresh token was used again, session revoked");
+            }
+            return Err(Error::Auth("Invalid refresh token".to_string()));
+        };
+        if revoked_at.is_some() || expires_at <= now {
+            return Err(Error::Auth("Session expired".to_string()));
+        }
+
+        let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = ?")
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or(Error::UserNotFound)?;
+
+        let new_token = Self::new_refresh_token();
+        let rotated = sqlx::query(
+            r#"
+            UPDATE sessions SET refresh_token_hash = ?, previous_token_hash = ?,
+                last_used_at = ?, expires_at = ?
+            WHERE id = ? AND refresh_token_hash = ?
+            "#,
+        )
+        .bind(Self::hash_token(&new_token))
+        .bind(&hash)
+        .bind(now.to_rfc3339())
+        .bind((now + Duration::days(SESSION_DAYS)).to_rfc3339())
+        .bind(id)
+        .bind(&hash)
+        .execute(&self.pool)
+        .await?
+        .rows_affected();
+        // Lost a race with a concurrent refresh of the same token
+        if rotated == 0 {
+            return Err(Error::Auth("Invalid refresh token".to_string()));
+        }
+
+        Ok(AuthResponse {
+            token: self.create_token(&user, Some(id))?,
+            refresh_token: new_token,
+            expires_in: ACCESS_TOKEN_MINUTES * 60,
             user: user.into(),
         })
     }
 
-    pub fn create_token(&self, user: &User) -> Result<String> {
+    /// Verify an access token and that its session is still active.
+    ///
+    /// The role is read from `users` rather than trusted from the token, so a
+    /// role change applies to tokens already issued.
+    pub async fn authenticate(&self, token: &str) -> Result<Claims> {
+        let mut claims = self.verify_token(token)?;
+        let role: Option<UserRole> = match claims.sid {
+            Some(sid) => {
+                sqlx::query_scalar(
+                    r#"
+                    SELECT u.role FROM sessions s JOIN users u ON u.id = s.user_id
+                    WHERE s.id = ? AND s.revoked_at IS NULL
+                      AND julianday(s.expires_at) > julianday(?)
+                    "#,
+                )
+                .bind(sid)
+                .bind(Utc::now().to_rfc3339())
+                .fetch_optional(&self.pool)
+                .await?
+            }
+            None => {
+                sqlx::query_scalar("SELECT role FROM users WHERE id = ?")
+                    .bind(claims.id)
+                    .fetch_optional(&self.pool)
+                    .await?
+            }
+        };
+        match role {
+            Some(role) => claims.role = role,
+            None if claims.sid.is_some() => {
+                return Err(Error::Auth("Session has been revoked".to_string()));
+            }
+            None => return Err(Error::Auth("User no longer exists".to_string())),
+        }
+        Ok(claims)
+    }
+
+    /// Active sessions of a user, most recently used first
+    pub async fn list_sessions(
+        &self,
+        user_id: Uuid,
+        current: Option<Uuid>,
+    ) -> Result<Vec<Session>> {
+        let mut sessions = sqlx::query_as::<_, Session>(
+            r#"
+            SELECT id, device, created_at, last_used_at, expires_at FROM sessions
+            WHERE user_id = ? AND revoked_at IS NULL AND julianday(expires_at) > julianday(?)
+            ORDER BY last_used_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .bind(Utc::now().to_rfc3339())
+        .fetch_all(&self.pool)
+        .await?;
+
+        for session in &mut sessions {
+            session.current = Some(session.id) == current;
+        }
+        Ok(sessions)
+    }
+
+    /// Sign out a device; its access tokens stop working right away
+    pub async fn revoke_session(&self, user_id: Uuid, id: Uuid) -> Result<()> {
+        let result = sqlx::query(
+            "UPDATE sessions SET revoked_at = ? WHERE id = ? AND user_id = ? AND revoked_at IS NULL",
+        )
+        .bind(Utc::now().to_rfc3339())
+        .bind(id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(Error::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Sign out everywhere, e.g. after the password changed
+    pub async fn revoke_all_sessions(&self, user_id: Uuid) -> Result<u64> {
+        let result = sqlx::query(
+            "UPDATE sessions SET revoked_at = ? WHERE user_id = ? AND revoked_at IS NULL",
+        )
+        .bind(Utc::now().to_rfc3339())
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Delete expired and revoked sessions
+    pub async fn purge_sessions(&self) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+                DELETE FROM sessions
+                WHERE revoked_at IS NOT NULL OR julianday(expires_at) <= julianday(?)
+                "#,
+        )
+        .bind(Utc::now().to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub fn create_token(&self, user: &User, sid: Option<Uuid>) -> Result<String> {
         let now = Utc::now();
-        let exp = now + Duration::days(7);
+        let exp = now + Duration::minutes(ACCESS_TOKEN_MINUTES);
 
         let claims = Claims {
             id: user.id,
@@ -138,27 +396,15 @@ impl AuthService {
             role: user.role.clone(),
             iat: now.timestamp(),
             exp: exp.timestamp(),
+            sid,
+            scopes: None,
         };
 
-        encode(
-            &Header::default(),
-            &claims,
-            &EncodingKey::from_secret(self.jwt_secret.as_bytes()),
-        )
-        .map_err(|e| Error::Internal(e.to_string()))
+        self.keys.sign(&claims)
     }
 
     pub fn verify_token(&self, token: &str) -> Result<Claims> {
-        decode::<Claims>(
-            token,
-            &DecodingKey::from_secret(self.jwt_secret.as_bytes()),
-            &Validation::default(),
-        )
-        .map(|data| data.claims)
-        .map_err(|e| match e.kind() {
-            jsonwebtoken::errors::ErrorKind::ExpiredSignature => Error::TokenExpired,
-            _ => Error::Auth(e.to_string()),
-        })
+        self.keys.verify(token)
     }
 
     pub async fn get_user(&self, user_id: Uuid) -> Result<UserPublic> {
@@ -178,7 +424,12 @@ impl AuthService {
             .ok_or(Error::UserNotFound)
     }
 
-    pub async fn request_password_reset(&self, input: ForgotPasswordRequest) -> Result<String> {
+    /// Store a reset token valid for one hour; returns the user and the
+    /// token, which only the email with the reset link should contain
+    pub async fn request_password_reset(
+        &self,
+        input: ForgotPasswordRequest,
+    ) -> Result<(User, String)> {
         // Find user by email
         let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE email = ?")
             .bind(&input.email)
@@ -209,21 +460,17 @@ impl AuthService {
         .execute(&self.pool)
         .await?;
 
-        // Log the reset link (in production, this would be sent via email)
-        let reset_link = format!("/reset-password?token={}", reset_token);
-        tracing::info!("=== PASSWORD RESET LINK ===");
-        tracing::info!("User: {} ({})", user.username, user.email);
-        tracing::info!("Reset link: {}", reset_link);
-        tracing::info!("Token expires: {}", expires);
-        tracing::info!("===========================");
-
-        Ok("If the email exists, a reset link has been sent.".to_string())
+        Ok((user, reset_token))
     }
 
-    pub async fn reset_password(&self, input: ResetPasswordRequest) -> Result<()> {
+    /// Set a new password with a reset token; returns the user's id
+    pub async fn reset_password(&self, input: ResetPasswordRequest) -> Result<Uuid> {
         // Find users with non-expired reset tokens
         let users = sqlx::query_as::<_, User>(
-            "SELECT * FROM users WHERE reset_token IS NOT NULL AND reset_token_expires > ?",
+            r#"
+            SELECT * FROM users
+            WHERE reset_token IS NOT NULL AND julianday(reset_token_expires) > julianday(?)
+            "#,
         )
         .bind(Utc::now().to_rfc3339())
         .fetch_all(&self.pool)
@@ -266,9 +513,11 @@ impl AuthService {
         .execute(&self.pool)
         .await?;
 
+        self.revoke_all_sessions(user.id).await?;
+
         tracing::info!("Password reset successful for user: {}", user.username);
 
-        Ok(())
+        Ok(user.id)
     }
 
     // ============ Admin User Management ============
@@ -402,6 +651,283 @@ impl AuthService {
         self.get_user(user_id).await
     }
 
+    /// Set the regions of the user's players; the input is validated and normalized
+    pub async fn update_user_player_regions(
+        &self,
+        user_id: Uuid,
+        player_regions: Option<String>,
+    ) -> Result<UserPublic> {
+        let player_regions = match player_regions {
+            Some(regions) => normalize_region_codes(&regions).map_err(Error::Validation)?,
+            None => None,
+        };
+
+        sqlx::query("UPDATE users SET player_regions = ?, updated_at = ? WHERE id = ?")
+            .bind(&player_regions)
+            .bind(Utc::now().to_rfc3339())
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        self.get_user(user_id).await
+    }
+
+    /// Set the currency value reports are converted into
+    pub async fn update_user_preferred_currency(
+        &self,
+        user_id: Uuid,
+        currency: Option<String>,
+    ) -> Result<UserPublic> {
+        let currency = match currency.filter(|c| !c.trim().is_empty()) {
+            Some(currency) => Some(normalize_currency(&currency).map_err(Error::Validation)?),
+            None => None,
+        };
+
+        sqlx::query("UPDATE users SET preferred_currency = ?, updated_at = ? WHERE id = ?")
+            .bind(&currency)
+            .bind(Utc::now().to_rfc3339())
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        self.get_user(user_id).await
+    }
+
+    /// Set the UTC offset used for the user's dates; `None` or "UTC" clears it
+    pub async fn update_user_timezone(
+        &self,
+        user_id: Uuid,
+        timezone: Option<String>,
+    ) -> Result<UserPublic> {
+        let timezone = match timezone {
+            Some(timezone) => normalize_timezone(&timezone).map_err(Error::Validation)?,
+            None => None,
+        };
+
+        sqlx::query("UPDATE users SET timezone = ?, updated_at = ? WHERE id = ?")
+            .bind(&timezone)
+            .bind(Utc::now().to_rfc3339())
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        self.get_user(user_id).await
+    }
+
+    /// Set the values new movies get when created without them. Empty values
+    /// are cleared, the currency is validated.
+    pub async fn update_user_movie_defaults(
+        &self,
+        user_id: Uuid,
+        defaults: MovieDefaults,
+    ) -> Result<UserPublic> {
+        let clean = |value: Option<String>| {
+            value
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        let currency = match clean(defaults.currency) {
+            Some(currency) => Some(normalize_currency(&currency).map_err(Error::Validation)?),
+            None => None,
+        };
+
+        sqlx::query(
+            r#"
+            UPDATE users SET default_disc_type = ?, default_location = ?, default_media_type = ?,
+                default_currency = ?, updated_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(clean(defaults.disc_type))
+        .bind(clean(defaults.location))
+        .bind(clean(defaults.media_type))
+        .bind(currency)
+        .bind(Utc::now().to_rfc3339())
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        self.get_user(user_id).await
+    }
+
+    /// Set when the user is reminded of lent movies
+    pub async fn update_user_loan_reminders(
+        &self,
+        user_id: Uuid,
+        settings: LoanReminderSettings,
+    ) -> Result<UserPublic> {
+        if !(0..=MAX_LOAN_REMINDER_DAYS).contains(&settings.days_before) {
+            return Err(Error::Validation(format!(
+                "days_before must be between 0 and {}",
+                MAX_LOAN_REMINDER_DAYS
+            )));
+        }
+
+        sqlx::query(
+            "UPDATE users SET loan_reminders = ?, loan_reminder_days = ?, updated_at = ? WHERE id = ?",
+        )
+        .bind(settings.enabled)
+        .bind(settings.days_before)
+        .bind(Utc::now().to_rfc3339())
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        self.get_user(user_id).await
+    }
+
+    /// Set when the user gets the weekly digest email
+    pub async fn update_user_digest(
+        &self,
+        user_id: Uuid,
+        settings: DigestSettings,
+    ) -> Result<UserPublic> {
+        if !(0..=6).contains(&settings.weekday) {
+            return Err(Error::Validation(
+                "weekday must be between 0 (Monday) and 6 (Sunday)".to_string(),
+            ));
+        }
+        if !(0..=23).contains(&settings.hour) {
+            return Err(Error::Validation(
+                "hour must be between 0 and 23".to_string(),
+            ));
+        }
+
+        sqlx::query(
+            "UPDATE users SET digest_enabled = ?, digest_weekday = ?, digest_hour = ?, updated_at = ? WHERE id = ?",
+        )
+        .bind(settings.enabled)
+        .bind(settings.weekday)
+        .bind(settings.hour)
+        .bind(Utc::now().to_rfc3339())
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        self.get_user(user_id).await
+    }
+
+    /// Set the user's TMDB image sizes and language chain; empty values fall
+    /// back to the server settings
+    pub async fn update_user_tmdb(
+        &self,
+        user_id: Uuid,
+        preferences: TmdbPreferences,
+    ) -> Result<UserPublic> {
+        let size = |value: Option<String>, sizes: &[&str]| {
+            normalize_image_size(value.as_deref().unwrap_or_default(), sizes)
+                .map_err(Error::Validation)
+        };
+        let poster_size = size(preferences.poster_size, POSTER_SIZES)?;
+        let backdrop_size = size(preferences.backdrop_size, BACKDROP_SIZES)?;
+        let languages =
+            normalize_tmdb_languages(preferences.languages.as_deref().unwrap_or_default())
+                .map_err(Error::Validation)?;
+
+        sqlx::query(
+            r#"
+            UPDATE users SET tmdb_poster_size = ?, tmdb_backdrop_size = ?, tmdb_languages = ?,
+                updated_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(poster_size)
+        .bind(backdrop_size)
+        .bind(languages)
+        .bind(Utc::now().to_rfc3339())
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        self.get_user(user_id).await
+    }
+
+    /// Replace the user's own CSV import presets, see `validate_import_presets`
+    pub async fn update_user_import_presets(
+        &self,
+        user_id: Uuid,
+        presets: Vec<ImportPreset>,
+    ) -> Result<Vec<ImportPreset>> {
+        let presets = validate_import_presets(presets)?;
+        let json = serde_json::to_string(&presets)
+            .map_err(|e| Error::Internal(format!("Failed to store import presets: {}", e)))?;
+
+        let result =
+            sqlx::query("UPDATE users SET import_presets = ?, updated_at = ? WHERE id = ?")
+                .bind(json)
+                .bind(Utc::now().to_rfc3339())
+                .bind(user_id)
+                .execute(&self.pool)
+                .await?;
+        if result.rows_affected() == 0 {
+            return Err(Error::UserNotFound);
+        }
+
+        Ok(presets)
+    }
+
+    /// Add a CSV import preset or replace the one with the same name
+    pub async fn save_import_preset(
+        &self,
+        user_id: Uuid,
+        preset: ImportPreset,
+    ) -> Result<ImportPreset> {
+        let mut presets = self.get_preferences(user_id).await?.import_presets;
+        presets.retain(|p| !p.name.eq_ignore_ascii_case(preset.name.trim()));
+        presets.push(preset);
+        let mut presets = self.update_user_import_presets(user_id, presets).await?;
+        presets.pop().ok_or(Error::NotFound)
+    }
+
+    pub async fn delete_import_preset(&self, user_id: Uuid, name: &str) -> Result<()> {
+        let mut presets = self.get_preferences(user_id).await?.import_presets;
+        let count = presets.len();
+        presets.retain(|p| !p.name.eq_ignore_ascii_case(name.trim()));
+        if presets.len() == count {
+            return Err(Error::NotFound);
+        }
+        self.update_user_import_presets(user_id, presets).await?;
+        Ok(())
+    }
+
+    pub async fn get_preferences(&self, user_id: Uuid) -> Result<UserPreferences> {
+        let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = ?")
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or(Error::UserNotFound)?;
+        Ok(user.preferences())
+    }
+
+    /// Apply preferences from a backup, validated like the single updates
+    pub async fn restore_preferences(
+        &self,
+        user_id: Uuid,
+        preferences: UserPreferences,
+    ) -> Result<UserPublic> {
+        self.update_user_language(user_id, preferences.language)
+            .await?;
+        self.update_user_include_adult(user_id, preferences.include_adult)
+            .await?;
+        self.update_user_theme(user_id, preferences.theme).await?;
+        self.update_user_card_size(user_id, preferences.card_size)
+            .await?;
+        self.update_user_player_regions(user_id, preferences.player_regions)
+            .await?;
+        self.update_user_preferred_currency(user_id, preferences.preferred_currency)
+            .await?;
+        self.update_user_timezone(user_id, preferences.timezone)
+            .await?;
+        self.update_user_movie_defaults(user_id, preferences.movie_defaults)
+            .await?;
+        self.update_user_loan_reminders(user_id, preferences.loan_reminders)
+            .await?;
+        self.update_user_tmdb(user_id, preferences.tmdb).await?;
+        self.update_user_import_presets(user_id, preferences.import_presets)
+            .await?;
+        self.update_user_digest(user_id, preferences.digest).await
+    }
+
     pub async fn update_user_avatar(
         &self,
         user_id: Uuid,
@@ -424,12 +950,17 @@ impl AuthService {
     ) -> Result<UserPublic> {
         // Set avatar_path to "db" to indicate it's stored in database
         let avatar_path = avatar_data.as_ref().map(|_| "db".to_string());
+        // Stored in the image store; avatar_data only holds avatars from before
+        let avatar_hash = match avatar_data {
+            Some(ref data) => Some(ImageService::new(self.pool.clone()).store(data).await?),
+            None => None,
+        };
 
         sqlx::query(
-            "UPDATE users SET avatar_path = ?, avatar_data = ?, updated_at = ? WHERE id = ?",
+            "UPDATE users SET avatar_path = ?, avatar_hash = ?, avatar_data = NULL, updated_at = ? WHERE id = ?",
         )
         .bind(&avatar_path)
-        .bind(&avatar_data)
+        .bind(&avatar_hash)
         .bind(Utc::now().to_rfc3339())
         .bind(user_id)
         .execute(&self.pool)
@@ -439,13 +970,17 @@ impl AuthService {
     }
 
     pub async fn get_user_avatar_data(&self, user_id: Uuid) -> Result<Option<Vec<u8>>> {
-        let data =
-            sqlx::query_scalar::<_, Option<Vec<u8>>>("SELECT avatar_data FROM users WHERE id = ?")
+        let row: Option<(Option<String>, Option<Vec<u8>>)> =
+            sqlx::query_as("SELECT avatar_hash, avatar_data FROM users WHERE id = ?")
                 .bind(user_id)
                 .fetch_optional(&self.pool)
                 .await?;
 
-        Ok(data.flatten())
+        match row {
+            Some((Some(hash), _)) => ImageService::new(self.pool.clone()).get(&hash).await,
+            Some((None, data)) => Ok(data),
+            None => Ok(None),
+        }
     }
 
     pub async fn admin_set_password(&self, user_id: Uuid, new_password: &str) -> Result<()> {
@@ -469,6 +1004,7 @@ impl AuthService {
         if result.rows_affected() == 0 {
             return Err(Error::UserNotFound);
         }
+        self.revoke_all_sessions(user_id).await?;
 
         Ok(())
     }
@@ -694,6 +1230,7 @@ mod tests {
             .login(LoginRequest {
                 username: "testuser".to_string(),
                 password: "password123".to_string(),
+                device: None,
             })
             .await;
 
@@ -719,6 +1256,7 @@ mod tests {
             .login(LoginRequest {
                 username: "testuser".to_string(),
                 password: "wrongpassword".to_string(),
+                device: None,
             })
             .await;
 
@@ -737,6 +1275,7 @@ mod tests {
             .login(LoginRequest {
                 username: "nonexistent".to_string(),
                 password: "password123".to_string(),
+                device: None,
             })
             .await;
 
@@ -766,6 +1305,64 @@ mod tests {
         assert_eq!(claims.username, "testuser");
     }
 
+    #[tokio::test]
+    async fn test_refresh_rotation_and_revocation() {
+        let auth = setup().await;
+
+        auth.register(CreateUser {
+            username: "testuser".to_string(),
+            email: "test@test.com".to_string(),
+            password: "password123".to_string(),
+        })
+        .await
+        .unwrap();
+        let login = || {
+            auth.login(LoginRequest {
+                username: "testuser".to_string(),
+                password: "password123".to_string(),
+                device: Some("Firefox".to_string()),
+            })
+        };
+
+        let first = login().await.unwrap();
+        let claims = auth.authenticate(&first.token).await.unwrap();
+        let sid = claims.sid.unwrap();
+
+        let refreshed = auth.refresh(&first.refresh_token).await.unwrap();
+        assert_ne!(refreshed.refresh_token, first.refresh_token);
+        let claims = auth.authenticate(&refreshed.token).await.unwrap();
+        assert_eq!(claims.sid, Some(sid));
+
+        // Using the rotated token again revokes the whole session
+        assert!(matches!(
+            auth.refresh(&first.refresh_token).await,
+            Err(Error::Auth(_))
+        ));
+        assert!(auth.authenticate(&refreshed.token).await.is_err());
+        assert!(auth.refresh(&refreshed.refresh_token).await.is_err());
+
+        let second = login().await.unwrap();
+        let third = login().await.unwrap();
+        let user_id = second.user.id;
+        let current = auth.authenticate(&third.token).await.unwrap().sid;
+        let sessions = auth.list_sessions(user_id, current).await.unwrap();
+        // Registration started a session of its own
+        assert_eq!(sessions.len(), 3);
+        assert_eq!(sessions.iter().filter(|s| s.current).count(), 1);
+        assert_eq!(sessions[0].device.as_deref(), Some("Firefox"));
+
+        let other = sessions.iter().find(|s| !s.current).unwrap();
+        auth.revoke_session(user_id, other.id).await.unwrap();
+        assert!(auth.authenticate(&second.token).await.is_err());
+        assert!(auth.authenticate(&third.token).await.is_ok());
+
+        auth.admin_set_password(user_id, "changed123")
+            .await
+            .unwrap();
+        assert!(auth.authenticate(&third.token).await.is_err());
+        assert_eq!(auth.purge_sessions().await.unwrap(), 4);
+    }
+
     #[tokio::test]
     async fn test_verify_invalid_token_fails() {
         let auth = setup().await;
@@ -915,6 +1512,7 @@ mod tests {
             .login(LoginRequest {
                 username: "testuser".to_string(),
                 password: "oldpassword".to_string(),
+                device: None,
             })
             .await;
         assert!(login_result.is_err());
@@ -924,6 +1522,7 @@ mod tests {
             .login(LoginRequest {
                 username: "testuser".to_string(),
                 password: "newpassword".to_string(),
+                device: None,
             })
             .await;
         assert!(login_result.is_ok());
@@ -955,6 +1554,108 @@ mod tests {
         assert_eq!(updated.language, None);
     }
 
+    #[tokio::test]
+    async fn test_restore_preferences() {
+        let auth = setup().await;
+
+        let response = auth
+            .register(CreateUser {
+                username: "testuser".to_string(),
+                email: "test@test.com".to_string(),
+                password: "password123".to_string(),
+            })
+            .await
+            .unwrap();
+        let user_id = response.user.id;
+
+        let preferences = UserPreferences {
+            language: Some("de".to_string()),
+            include_adult: true,
+            theme: Some("dark".to_string()),
+            player_regions: Some("R2,RB".to_string()),
+            timezone: Some("Europe/Berlin".to_string()),
+            movie_defaults: MovieDefaults {
+                disc_type: Some("Blu-ray".to_string()),
+                ..Default::default()
+            },
+            loan_reminders: LoanReminderSettings {
+                enabled: true,
+                days_before: 5,
+            },
+            digest: DigestSettings {
+                enabled: true,
+                weekday: 0,
+                hour: 7,
+            },
+            tmdb: TmdbPreferences {
+                poster_size: Some("w780".to_string()),
+                backdrop_size: None,
+                languages: Some("de-DE,en-US".to_string()),
+            },
+            import_presets: vec![ImportPreset {
+                name: "Spreadsheet".to_string(),
+                columns: [("Name".to_string(), "Title".to_string())].into(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        auth.restore_preferences(user_id, preferences.clone())
+            .await
+            .unwrap();
+        assert_eq!(auth.get_preferences(user_id).await.unwrap(), preferences);
+
+        // Invalid values are rejected as with the single updates
+        let invalid = UserPreferences {
+            timezone: Some("somewhere".to_string()),
+            ..Default::default()
+        };
+        assert!(auth.restore_preferences(user_id, invalid).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_import_presets() {
+        let auth = setup().await;
+
+        let response = auth
+            .register(CreateUser {
+                username: "testuser".to_string(),
+                email: "test@test.com".to_string(),
+                password: "password123".to_string(),
+            })
+            .await
+            .unwrap();
+        let user_id = response.user.id;
+        let preset = |name: &str, target: &str| ImportPreset {
+            name: name.to_string(),
+            columns: [("Name".to_string(), target.to_string())].into(),
+            ..Default::default()
+        };
+
+        auth.save_import_preset(user_id, preset("Mine", "Title"))
+            .await
+            .unwrap();
+        // Same name replaces the preset
+        let saved = auth
+            .save_import_preset(user_id, preset("mine", "sort title"))
+            .await
+            .unwrap();
+        assert_eq!(saved.columns["Name"], "Sort Title");
+        let presets = auth.get_preferences(user_id).await.unwrap().import_presets;
+        assert_eq!(presets, vec![saved]);
+
+        assert!(
+            auth.save_import_preset(user_id, preset("Libib", "Title"))
+                .await
+                .is_err()
+        );
+
+        auth.delete_import_preset(user_id, "MINE").await.unwrap();
+        assert!(matches!(
+            auth.delete_import_preset(user_id, "Mine").await,
+            Err(Error::NotFound)
+        ));
+    }
+
     #[tokio::test]
     async fn test_update_user_theme() {
         let auth = setup().await;
@@ -995,6 +1696,143 @@ mod tests {
         assert_eq!(updated.card_size, Some("large".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_update_user_preferred_currency() {
+        let auth = setup().await;
+
+        let response = auth
+            .register(CreateUser {
+                username: "testuser".to_string(),
+                email: "test@test.com".to_string(),
+                password: "password123".to_string(),
+            })
+            .await
+            .unwrap();
+
+        let updated = auth
+            .update_user_preferred_currency(response.user.id, Some("€".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(updated.preferred_currency, Some("EUR".to_string()));
+
+        assert!(
+            auth.update_user_preferred_currency(response.user.id, Some("Euros".to_string()))
+                .await
+                .is_err()
+        );
+
+        let cleared = auth
+            .update_user_preferred_currency(response.user.id, None)
+            .await
+            .unwrap();
+        assert_eq!(cleared.preferred_currency, None);
+    }
+
+    #[tokio::test]
+    async fn test_update_user_timezone() {
+        let auth = setup().await;
+
+        let response = auth
+            .register(CreateUser {
+                username: "testuser".to_string(),
+                email: "test@test.com".to_string(),
+                password: "password123".to_string(),
+            })
+            .await
+            .unwrap();
+
+        for (input, expected) in [
+            ("Europe/Berlin", Some("Europe/Berlin")),
+            (" America/New_York ", Some("America/New_York")),
+            // Offsets from older clients and backups
+            ("+2", Some("Etc/GMT-2")),
+            ("UTC-05:00", Some("Etc/GMT+5")),
+            ("+0530", Some("Asia/Kolkata")),
+            ("UTC", None),
+            ("+00:00", None),
+        ] {
+            let updated = auth
+                .update_user_timezone(response.user.id, Some(input.to_string()))
+                .await
+                .unwrap();
+            assert_eq!(updated.timezone.as_deref(), expected, "{}", input);
+        }
+
+        for invalid in ["Europe/Atlantis", "+15:00", "+01:75", "+01:15"] {
+            assert!(
+                auth.update_user_timezone(response.user.id, Some(invalid.to_string()))
+                    .await
+                    .is_err(),
+                "{}",
+                invalid
+            );
+        }
+
+        // Names follow daylight saving time, offsets could not
+        let user = auth
+            .update_user_timezone(response.user.id, Some("Europe/Berlin".to_string()))
+            .await
+            .unwrap();
+        use chrono::Offset;
+        let zone = crate::models::user_timezone(user.timezone.as_deref());
+        let winter = "2026-01-15T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let summer = "2026-07-15T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        assert_eq!(
+            winter.with_timezone(&zone).offset().fix().local_minus_utc(),
+            3600
+        );
+        assert_eq!(
+            summer.with_timezone(&zone).offset().fix().local_minus_utc(),
+            2 * 3600
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_user_movie_defaults() {
+        let auth = setup().await;
+
+        let response = auth
+            .register(CreateUser {
+                username: "testuser".to_string(),
+                email: "test@test.com".to_string(),
+                password: "password123".to_string(),
+            })
+            .await
+            .unwrap();
+
+        let updated = auth
+            .update_user_movie_defaults(
+                response.user.id,
+                MovieDefaults {
+                    disc_type: Some("Blu-ray".to_string()),
+                    location: Some(" Shelf 3 ".to_string()),
+                    media_type: Some(String::new()),
+                    currency: Some("eur".to_string()),
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            updated.movie_defaults,
+            MovieDefaults {
+                disc_type: Some("Blu-ray".to_string()),
+                location: Some("Shelf 3".to_string()),
+                media_type: None,
+                currency: Some("EUR".to_string()),
+            }
+        );
+
+        let invalid = MovieDefaults {
+            currency: Some("Taler".to_string()),
+            ..Default::default()
+        };
+        assert!(
+            auth.update_user_movie_defaults(response.user.id, invalid)
+                .await
+                .is_err()
+        );
+    }
+
     #[tokio::test]
     async fn test_admin_create_user_with_password() {
         let auth = setup().await;
@@ -1026,6 +1864,7 @@ mod tests {
             .login(LoginRequest {
                 username: "newuser".to_string(),
                 password: "userpassword".to_string(),
+                device: None,
             })
             .await;
         assert!(login.is_ok());
@@ -1169,16 +2008,35 @@ mod tests {
         .await
         .unwrap();
 
-        // Request password reset - returns a message, not the token
-        let message = auth
+        // The token is returned for the email and stored hashed
+        let (user, token) = auth
             .request_password_reset(ForgotPasswordRequest {
                 email: "test@test.com".to_string(),
             })
             .await
             .unwrap();
-
-        assert!(!message.is_empty());
-        // Token is stored hashed in DB and only logged - we can't use it directly in test
+        assert_eq!(user.username, "testuser");
+        let stored: Option<String> =
+            sqlx::query_scalar("SELECT reset_token FROM users WHERE id = ?")
+                .bind(user.id)
+                .fetch_one(&auth.pool)
+                .await
+                .unwrap();
+        assert_ne!(stored.as_deref(), Some(token.as_str()));
+
+        auth.reset_password(ResetPasswordRequest {
+            token,
+            password: "newpassword".to_string(),
+        })
+        .await
+        .unwrap();
+        auth.login(LoginRequest {
+            username: "testuser".to_string(),
+            password: "newpassword".to_string(),
+            device: None,
+        })
+        .await
+        .unwrap();
     }
 
     #[tokio::test]
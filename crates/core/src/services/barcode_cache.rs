@@ -0,0 +1,205 @@
+use chrono::{Duration, Utc};
+
+use crate::db::DbPool;
+use crate::error::Result;
+use crate::models::{BarcodeCacheEntry, BarcodeCacheStats};
+
+/// How long a found product is answered from the cache
+pub const CACHE_TTL_DAYS: i64 = 30;
+/// Unknown barcodes are retried sooner; the product databases keep growing
+pub const NOT_FOUND_TTL_DAYS: i64 = 1;
+
+/// Barcode lookups keyed by the normalized barcode (digits only)
+pub struct BarcodeCacheService {
+    pool: DbPool,
+}
+
+/// Digits only, as the providers are asked
+pub fn normalize_barcode(barcode: &str) -> String {
+    barcode.chars().filter(|c| c.is_ascii_digit()).collect()
+}
+
+impl BarcodeCacheService {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// The unexpired entry for a barcode; counts a hit when found
+    pub async fn get(&self, barcode: &str) -> Result<Option<BarcodeCacheEntry>> {
+        let entry = sqlx::query_as::<_, BarcodeCacheEntry>(
+            r#"
+            UPDATE barcode_cache SET hits = hits + 1
+            WHERE barcode = ? AND julianday(expires_at) > julianday(?)
+            RETURNING *
+            "#,
+        )
+        .bind(normalize_barcode(barcode))
+        .bind(Utc::now().to_rfc3339())
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(entry)
+    }
+
+    /// Store the result of a lookup that went to the providers. Counters of
+    /// an expired entry for the same barcode are kept.
+    pub async fn put(&self, entry: &BarcodeCacheEntry) -> Result<()> {
+        let now = Utc::now();
+        let ttl = if entry.title.is_some() {
+            CACHE_TTL_DAYS
+        } else {
+            NOT_FOUND_TTL_DAYS
+        };
+        sqlx::query(
+            r#"
+            INSERT INTO barcode_cache (barcode, title, vendor, category, source, disc_type,
+                tmdb_id, tmdb_results, misses, created_at, expires_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, 1, ?, ?)
+            ON CONFLICT(barcode) DO UPDATE SET
+                title = excluded.title,
+                vendor = excluded.vendor,
+                category = excluded.category,
+                source = excluded.source,
+                disc_type = excluded.disc_type,
+                tmdb_id = excluded.tmdb_id,
+                tmdb_results = excluded.tmdb_results,
+                misses = misses + 1,
+                created_at = excluded.created_at,
+                expires_at = excluded.expires_at
+            "#,
+        )
+        .bind(normalize_barcode(&entry.barcode))
+        .bind(&entry.title)
+        .bind(&entry.vendor)
+        .bind(&entry.category)
+        .bind(&entry.source)
+        .bind(&entry.disc_type)
+        .bind(entry.tmdb_id)
+        .bind(&entry.tmdb_results)
+        .bind(now.to_rfc3339())
+        .bind((now + Duration::days(ttl)).to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Most recently cached entries first, expired ones included
+    pub async fn list(&self, limit: i64, offset: i64) -> Result<Vec<BarcodeCacheEntry>> {
+        let entries = sqlx::query_as::<_, BarcodeCacheEntry>(
+            "SELECT * FROM barcode_cache ORDER BY created_at DESC LIMIT ? OFFSET ?",
+        )
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(entries)
+    }
+
+    pub async fn stats(&self) -> Result<BarcodeCacheStats> {
+        let stats = sqlx::query_as::<_, BarcodeCacheStats>(
+            r#"
+            SELECT COUNT(*) AS entries,
+                COALESCE(SUM(julianday(expires_at) <= julianday(?)), 0) AS expired,
+                COALESCE(SUM(hits), 0) AS hits,
+                COALESCE(SUM(misses), 0) AS misses
+            FROM barcode_cache
+            "#,
+        )
+        .bind(Utc::now().to_rfc3339())
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(stats)
+    }
+
+    /// Remove one barcode, or everything with `None`. Returns the number of
+    /// entries removed.
+    pub async fn clear(&self, barcode: Option<&str>) -> Result<u64> {
+        let result = match barcode {
+            Some(barcode) => {
+                sqlx::query("DELETE FROM barcode_cache WHERE barcode = ?")
+                    .bind(normalize_barcode(barcode))
+                    .execute(&self.pool)
+                    .await?
+            }
+            None => {
+                sqlx::query("DELETE FROM barcode_cache")
+                    .execute(&self.pool)
+                    .await?
+            }
+        };
+        Ok(result.rows_affected())
+    }
+
+    /// Drop expired entries (daily maintenance)
+    pub async fn purge_expired(&self) -> Result<u64> {
+        let result =
+            sqlx::query("DELETE FROM barcode_cache WHERE julianday(expires_at) <= julianday(?)")
+                .bind(Utc::now().to_rfc3339())
+                .execute(&self.pool)
+                .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::create_test_db;
+
+    #[tokio::test]
+    async fn test_barcode_cache() {
+        let pool = create_test_db().await;
+        let cache = BarcodeCacheService::new(pool.clone());
+
+        assert!(cache.get("5050582721478").await.unwrap().is_none());
+
+        let entry = BarcodeCacheEntry {
+            barcode: "5 050582-721478".to_string(),
+            title: Some("The Matrix".to_string()),
+            source: Some("upcitemdb".to_string()),
+            disc_type: Some("Blu-ray".to_string()),
+            tmdb_id: Some(603),
+            tmdb_results: Some("[]".to_string()),
+            ..Default::default()
+        };
+        cache.put(&entry).await.unwrap();
+        cache
+            .put(&BarcodeCacheEntry {
+                barcode: "4006680083094".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let cached = cache.get("5050582721478").await.unwrap().unwrap();
+        assert_eq!(cached.barcode, "5050582721478");
+        assert_eq!(cached.title.as_deref(), Some("The Matrix"));
+        assert_eq!(cached.tmdb_id, Some(603));
+        assert_eq!(cached.hits, 1);
+        assert_eq!(cached.misses, 1);
+
+        // Unknown barcodes are cached too, for a shorter time
+        let unknown = cache.get("4006680083094").await.unwrap().unwrap();
+        assert!(unknown.title.is_none());
+        assert!(unknown.expires_at < cached.expires_at);
+
+        let stats = cache.stats().await.unwrap();
+        assert_eq!(stats.entries, 2);
+        assert_eq!(stats.hits, 2);
+        assert_eq!(stats.misses, 2);
+        assert_eq!(stats.expired, 0);
+
+        // Expired entries are misses and get purged
+        sqlx::query("UPDATE barcode_cache SET expires_at = ? WHERE barcode = '4006680083094'")
+            .bind((Utc::now() - Duration::hours(1)).to_rfc3339())
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(cache.get("4006680083094").await.unwrap().is_none());
+        assert_eq!(cache.stats().await.unwrap().expired, 1);
+        assert_eq!(cache.purge_expired().await.unwrap(), 1);
+
+        assert_eq!(cache.list(10, 0).await.unwrap().len(), 1);
+        assert_eq!(cache.clear(Some("5050582721478")).await.unwrap(), 1);
+        assert_eq!(cache.clear(None).await.unwrap(), 0);
+    }
+}
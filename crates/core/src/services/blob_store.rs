@@ -0,0 +1,452 @@
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use chrono::Utc;
+use sha2::{Digest, Sha256};
+
+use crate::db::DbPool;
+use crate::error::{Error, Result};
+
+pub type BlobFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T>> + Send + 'a>>;
+
+/// Backend holding image bytes, keyed by the image hash. Which backend an
+/// image lives in is recorded in `images.storage`.
+pub trait BlobStore: Send + Sync {
+    /// Name stored in `images.storage`
+    fn kind(&self) -> &'static str;
+
+    fn put<'a>(&'a self, key: &'a str, data: &'a [u8]) -> BlobFuture<'a, ()>;
+
+    fn get<'a>(&'a self, key: &'a str) -> BlobFuture<'a, Option<Vec<u8>>>;
+
+    /// Remove a blob; removing a missing blob is not an error
+    fn delete<'a>(&'a self, key: &'a str) -> BlobFuture<'a, ()>;
+}
+
+/// Bytes in the `images.data` column (the default)
+pub struct SqliteBlobStore {
+    pool: DbPool,
+}
+
+impl SqliteBlobStore {
+    pub const KIND: &'static str = "db";
+
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+impl BlobStore for SqliteBlobStore {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    fn put<'a>(&'a self, key: &'a str, data: &'a [u8]) -> BlobFuture<'a, ()> {
+        Box::pin(async move {
+            sqlx::query("UPDATE images SET data = ? WHERE hash = ?")
+                .bind(data)
+                .bind(key)
+                .execute(&self.pool)
+                .await?;
+            Ok(())
+        })
+    }
+
+    fn get<'a>(&'a self, key: &'a str) -> BlobFuture<'a, Option<Vec<u8>>> {
+        Box::pin(async move {
+            Ok(
+                sqlx::query_scalar::<_, Vec<u8>>("SELECT data FROM images WHERE hash = ?")
+                    .bind(key)
+                    .fetch_optional(&self.pool)
+                    .await?,
+            )
+        })
+    }
+
+    fn delete<'a>(&'a self, _key: &'a str) -> BlobFuture<'a, ()> {
+        // The bytes go away with the images row
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// Files in a local directory, sharded by the first two characters of the key
+pub struct FsBlobStore {
+    root: PathBuf,
+}
+
+impl FsBlobStore {
+    pub const KIND: &'static str = "fs";
+
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        let shard = key.get(..2).unwrap_or("__");
+        self.root.join(shard).join(key)
+    }
+}
+
+fn io_error(e: std::io::Error) -> Error {
+    Error::Internal(format!("Image storage error: {}", e))
+}
+
+impl BlobStore for FsBlobStore {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    fn put<'a>(&'a self, key: &'a str, data: &'a [u8]) -> BlobFuture<'a, ()> {
+        Box::pin(async move {
+            let path = self.path(key);
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent).await.map_err(io_error)?;
+            }
+            // Write then rename, so readers never see a partial file
+            let tmp = path.with_extension("tmp");
+            tokio::fs::write(&tmp, data).await.map_err(io_error)?;
+            tokio::fs::rename(&tmp, &path).await.map_err(io_error)?;
+            Ok(())
+        })
+    }
+
+    fn get<'a>(&'a self, key: &'a str) -> BlobFuture<'a, Option<Vec<u8>>> {
+        Box::pin(async move {
+            match tokio::fs::read(self.path(key)).await {
+                Ok(data) => Ok(Some(data)),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(io_error(e)),
+            }
+        })
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> BlobFuture<'a, ()> {
+        Box::pin(async move {
+            match tokio::fs::remove_file(self.path(key)).await {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(io_error(e)),
+                _ => Ok(()),
+            }
+        })
+    }
+}
+
+/// Objects in an S3-compatible bucket (AWS, MinIO, R2, ...), path-style
+/// requests signed with AWS Signature Version 4
+pub struct S3BlobStore {
+    client: reqwest::Client,
+    endpoint: reqwest::Url,
+    bucket: String,
+    prefix: String,
+    region: String,
+    access_key_id: String,
+    secret_access_key: String,
+}
+
+impl S3BlobStore {
+    pub const KIND: &'static str = "s3";
+
+    pub fn new(
+        endpoint: &str,
+        bucket: &str,
+        prefix: &str,
+        region: &str,
+        access_key_id: &str,
+        secret_access_key: &str,
+    ) -> Result<Self> {
+        let endpoint = reqwest::Url::parse(endpoint)
+            .map_err(|e| Error::Validation(format!("Invalid S3 endpoint: {}", e)))?;
+        if endpoint.host_str().is_none() {
+            return Err(Error::Validation(
+                "Invalid S3 endpoint: no host".to_string(),
+            ));
+        }
+        if bucket.is_empty() {
+            return Err(Error::Validation("S3 bucket is required".to_string()));
+        }
+        if access_key_id.is_empty() || secret_access_key.is_empty() {
+            return Err(Error::Validation(
+                "s3_access_key_id and s3_secret_access_key are required for S3 storage".to_string(),
+            ));
+        }
+
+        let prefix = prefix.trim_matches('/');
+        Ok(Self {
+            client: reqwest::Client::new(),
+            endpoint,
+            bucket: bucket.to_string(),
+            prefix: if prefix.is_empty() {
+                String::new()
+            } else {
+                format!("{}/", prefix)
+            },
+            region: region.to_string(),
+            access_key_id: access_key_id.to_string(),
+            secret_access_key: secret_access_key.to_string(),
+        })
+    }
+
+    async fn send(
+        &self,
+        method: reqwest::Method,
+        key: &str,
+        body: &[u8],
+    ) -> Result<reqwest::Response> {
+        let path = format!("/{}/{}{}", self.bucket, self.prefix, key);
+        let host = match self.endpoint.port() {
+            Some(port) => format!("{}:{}", self.endpoint.host_str().unwrap_or_default(), port),
+            None => self.endpoint.host_str().unwrap_or_default().to_string(),
+        };
+        let payload_hash = hex(&Sha256::digest(body));
+        let amz_date = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+
+        let authorization = sigv4_authorization(
+            method.as_str(),
+            &path,
+            &host,
+            &payload_hash,
+            &amz_date,
+            &self.region,
+            &self.access_key_id,
+            &self.secret_access_key,
+        );
+
+        let mut url = self.endpoint.clone();
+        url.set_path(&path);
+        self.client
+            .request(method, url)
+            .header("host", host)
+            .header("x-amz-content-sha256", payload_hash)
+            .header("x-amz-date", amz_date)
+            .header("authorization", authorization)
+            .body(body.to_vec())
+            .send()
+            .await
+            .map_err(|e| Error::ExternalApi(format!("S3 request failed: {}", e)))
+    }
+}
+
+fn s3_error(response: &reqwest::Response) -> Error {
+    Error::ExternalApi(format!("S3 returned {}", response.status()))
+}
+
+impl BlobStore for S3BlobStore {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    fn put<'a>(&'a self, key: &'a str, data: &'a [u8]) -> BlobFuture<'a, ()> {
+        Box::pin(async move {
+            let response = self.send(reqwest::Method::PUT, key, data).await?;
+            if !response.status().is_success() {
+                return Err(s3_error(&response));
+            }
+            Ok(())
+        })
+    }
+
+    fn get<'a>(&'a self, key: &'a str) -> BlobFuture<'a, Option<Vec<u8>>> {
+        Box::pin(async move {
+            let response = self.send(reqwest::Method::GET, key, &[]).await?;
+            if response.status() == reqwest::StatusCode::NOT_FOUND {
+                return Ok(None);
+            }
+            if !response.status().is_success() {
+                return Err(s3_error(&response));
+            }
+            let bytes = response
+                .bytes()
+                .await
+                .map_err(|e| Error::ExternalApi(format!("S3 request failed: {}", e)))?;
+            Ok(Some(bytes.to_vec()))
+        })
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> BlobFuture<'a, ()> {
+        Box::pin(async move {
+            let response = self.send(reqwest::Method::DELETE, key, &[]).await?;
+            if !response.status().is_success()
+                && response.status() != reqwest::StatusCode::NOT_FOUND
+            {
+                return Err(s3_error(&response));
+            }
+            Ok(())
+        })
+    }
+}
+
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub(crate) fn hmac_sha256(key: &[u8], data: &[u8]) -> [u8; 32] {
+    let mut block = [0u8; 64];
+    if key.len() > block.len() {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner = Sha256::new();
+    inner.update(block.map(|b| b ^ 0x36));
+    inner.update(data);
+    let mut outer = Sha256::new();
+    outer.update(block.map(|b| b ^ 0x5c));
+    outer.update(inner.finalize());
+
+    let mut mac = [0u8; 32];
+    mac.copy_from_slice(&outer.finalize());
+    mac
+}
+
+/// `Authorization` header for a request signing `host`, `x-amz-content-sha256`
+/// and `x-amz-date`
+#[allow(clippy::too_many_arguments)]
+fn sigv4_authorization(
+    method: &str,
+    path: &str,
+    host: &str,
+    payload_hash: &str,
+    amz_date: &str,
+    region: &str,
+    access_key_id: &str,
+    secret_access_key: &str,
+) -> String {
+    let date = &amz_date[..8];
+    let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+    let canonical_request = format!(
+        "{}\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+        method, path, host, payload_hash, amz_date, signed_headers, payload_hash
+    );
+    let scope = format!("{}/{}/s3/aws4_request", date, region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex(&Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let mut key = hmac_sha256(
+        format!("AWS4{}", secret_access_key).as_bytes(),
+        date.as_bytes(),
+    );
+    for part in [region, "s3", "aws4_request"] {
+        key = hmac_sha256(&key, part.as_bytes());
+    }
+    let signature = hex(&hmac_sha256(&key, string_to_sign.as_bytes()));
+
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        access_key_id, scope, signed_headers, signature
+    )
+}
+
+/// Build the store configured by the `image_storage` setting: empty or `db`
+/// keeps images in the database (`None`), a path or `file://` URL uses a
+/// directory, `s3://bucket/prefix?endpoint=...&region=...` an S3 bucket.
+pub fn blob_store_from_settings(
+    storage: Option<&str>,
+    s3_access_key_id: Option<&str>,
+    s3_secret_access_key: Option<&str>,
+) -> Result<Option<Arc<dyn BlobStore>>> {
+    let storage = storage.map(str::trim).unwrap_or_default();
+    if storage.is_empty() || storage == SqliteBlobStore::KIND {
+        return Ok(None);
+    }
+
+    if let Some(rest) = storage.strip_prefix("s3://") {
+        let (location, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let (bucket, prefix) = location.split_once('/').unwrap_or((location, ""));
+        let mut endpoint = None;
+        let mut region = "us-east-1".to_string();
+        for pair in query.split('&').filter(|p| !p.is_empty()) {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let value = urlencoding::decode(value)
+                .map_err(|e| Error::Validation(e.to_string()))?
+                .into_owned();
+            match name {
+                "endpoint" => endpoint = Some(value),
+                "region" => region = value,
+                _ => {
+                    return Err(Error::Validation(format!(
+                        "Unknown image_storage option: {}",
+                        name
+                    )));
+                }
+            }
+        }
+        let endpoint = endpoint.unwrap_or_else(|| format!("https://s3.{}.amazonaws.com", region));
+
+        let store = S3BlobStore::new(
+            &endpoint,
+            bucket,
+            prefix,
+            &region,
+            s3_access_key_id.unwrap_or_default(),
+            s3_secret_access_key.unwrap_or_default(),
+        )?;
+        return Ok(Some(Arc::new(store)));
+    }
+
+    let path = storage.strip_prefix("file://").unwrap_or(storage);
+    Ok(Some(Arc::new(FsBlobStore::new(path))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hmac_sha256() {
+        // RFC 4231, test case 2
+        assert_eq!(
+            hex(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fs_store_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FsBlobStore::new(dir.path());
+
+        store.put("abcdef", b"poster").await.unwrap();
+        assert!(dir.path().join("ab").join("abcdef").exists());
+        assert_eq!(store.get("abcdef").await.unwrap(), Some(b"poster".to_vec()));
+
+        store.delete("abcdef").await.unwrap();
+        assert_eq!(store.get("abcdef").await.unwrap(), None);
+        // Deleting twice is fine
+        store.delete("abcdef").await.unwrap();
+    }
+
+    #[test]
+    fn test_blob_store_from_settings() {
+        assert!(
+            blob_store_from_settings(None, None, None)
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            blob_store_from_settings(Some("db"), None, None)
+                .unwrap()
+                .is_none()
+        );
+
+        let fs = blob_store_from_settings(Some("file:///data/images"), None, None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(fs.kind(), FsBlobStore::KIND);
+
+        // S3 needs credentials
+        let url = "s3://posters/my-movies?endpoint=http%3A%2F%2Fminio%3A9000&region=eu-central-1";
+        assert!(blob_store_from_settings(Some(url), None, None).is_err());
+        let s3 = blob_store_from_settings(Some(url), Some("key"), Some("secret"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(s3.kind(), S3BlobStore::KIND);
+
+        assert!(blob_store_from_settings(Some("s3://b?color=red"), Some("k"), Some("s")).is_err());
+    }
+}
@@ -4,9 +4,11 @@ use uuid::Uuid;
 use crate::db::DbPool;
 use crate::error::{Error, Result};
 use crate::models::{
-    AddCollectionItem, Collection, CollectionFilter, CollectionItem, CreateCollection,
-    UpdateCollection,
+    ActivityAction, AddCollectionItem, Collection, CollectionFilter, CollectionItem,
+    CollectionItemDetails, CollectionWithStats, CreateCollection, EntityType, UpdateCollection,
 };
+use crate::services::activity::{record_activity, record_changes};
+use crate::services::tombstones::record_deletions;
 
 pub struct CollectionService {
     pool: DbPool,
@@ -38,7 +40,18 @@ impl CollectionService {
         .execute(&self.pool)
         .await?;
 
-        self.get_by_id(user_id, id).await
+        let collection = self.get_by_id(user_id, id).await?;
+        record_activity(
+            &self.pool,
+            user_id,
+            ActivityAction::Create,
+            EntityType::Collection,
+            id,
+            Some(&collection.title),
+            None,
+        )
+        .await?;
+        Ok(collection)
     }
 
     pub async fn get_by_id(&self, user_id: Uuid, id: Uuid) -> Result<Collection> {
@@ -51,26 +64,99 @@ impl CollectionService {
     }
 
     pub async fn list(&self, user_id: Uuid, filter: CollectionFilter) -> Result<Vec<Collection>> {
-        let limit = filter.limit; // None = no limit
-        let offset = filter.offset.unwrap_or(0);
+        self.fetch_filtered("SELECT c.* FROM collections c", "", user_id, &filter)
+            .await
+    }
 
-        let collections = if let Some(lim) = limit {
-            sqlx::query_as::<_, Collection>(
-                "SELECT * FROM collections WHERE user_id = ? ORDER BY title LIMIT ? OFFSET ?",
-            )
-            .bind(user_id)
-            .bind(lim)
-            .bind(offset)
-            .fetch_all(&self.pool)
-            .await?
-        } else {
-            sqlx::query_as::<_, Collection>(
-                "SELECT * FROM collections WHERE user_id = ? ORDER BY title",
-            )
-            .bind(user_id)
-            .fetch_all(&self.pool)
-            .await?
+    /// Like `list`, with item aggregates computed in the same query
+    pub async fn list_with_stats(
+        &self,
+        user_id: Uuid,
+        filter: CollectionFilter,
+    ) -> Result<Vec<CollectionWithStats>> {
+        self.fetch_filtered(
+            r#"
+            SELECT c.*,
+                   COUNT(ci.id) AS item_count,
+                   COALESCE(SUM(COALESCE(m.running_time,
+                       s.running_time * COALESCE(s.episodes_count, 1))), 0) AS total_runtime,
+                   COALESCE(AVG(CASE WHEN ci.id IS NULL THEN NULL
+                       WHEN COALESCE(m.watched, s.watched, 0) THEN 1.0 ELSE 0.0 END), 0.0)
+                       AS watched_fraction
+            FROM collections c
+            LEFT JOIN collection_items ci ON ci.collection_id = c.id
+            LEFT JOIN movies m ON ci.item_type = 'movie' AND m.id = ci.movie_id
+            LEFT JOIN series s ON ci.item_type = 'series' AND s.id = ci.series_id
+            "#,
+            " GROUP BY c.id",
+            user_id,
+            &filter,
+        )
+        .await
+    }
+
+    /// Run `select` (with `collections` aliased as `c`) with the filter, sorting and paging
+    async fn fetch_filtered<T>(
+        &self,
+        select: &str,
+        group_by: &str,
+        user_id: Uuid,
+        filter: &CollectionFilter,
+    ) -> Result<Vec<T>>
+    where
+        T: for<'r> sqlx::FromRow<'r, sqlx::sqlite::SqliteRow> + Send + Unpin,
+    {
+        let mut query = format!("{} WHERE c.user_id = ?", select);
+        // Bound in this order after user_id
+        let mut text_binds = Vec::new();
+
+        if let Some(search) = filter.search.as_deref().filter(|s| !s.trim().is_empty()) {
+            query.push_str(" AND (c.title LIKE ? OR c.personal_title LIKE ? OR c.barcode = ?)");
+            let pattern = format!("%{}%", search.trim());
+            text_binds.push(pattern.clone());
+            text_binds.push(pattern);
+            text_binds.push(search.trim().to_string());
+        }
+        if let Some(ref disc_type) = filter.disc_type {
+            query.push_str(" AND c.disc_type = ? COLLATE NOCASE");
+            text_binds.push(disc_type.clone());
+        }
+        if filter.contains_movie.is_some() {
+            query.push_str(
+                " AND c.id IN (SELECT collection_id FROM collection_items WHERE movie_id = ?)",
+            );
+        }
+
+        let sort_column = match filter.sort_by.as_deref() {
+            Some("sort_title") => "COALESCE(c.sort_title, c.title) COLLATE NOCASE",
+            Some("created_at") => "c.created_at",
+            Some("updated_at") => "c.updated_at",
+            Some("purchase_date") => "c.purchase_date",
+            Some("price") => "c.price",
+            _ => "c.title COLLATE NOCASE",
         };
+        let order = match filter.sort_order.as_deref() {
+            Some(order) if order.eq_ignore_ascii_case("desc") => "DESC",
+            _ => "ASC",
+        };
+        // LIMIT -1 = no limit
+        query.push_str(&format!(
+            "{} ORDER BY {} {}, c.id LIMIT ? OFFSET ?",
+            group_by, sort_column, order
+        ));
+
+        let mut q = sqlx::query_as::<_, T>(&query).bind(user_id);
+        for value in &text_binds {
+            q = q.bind(value);
+        }
+        if let Some(movie_id) = filter.contains_movie {
+            q = q.bind(movie_id);
+        }
+        let collections = q
+            .bind(filter.limit.unwrap_or(-1))
+            .bind(filter.offset.unwrap_or(0))
+            .fetch_all(&self.pool)
+            .await?;
 
         Ok(collections)
     }
@@ -82,7 +168,7 @@ impl CollectionService {
         input: UpdateCollection,
     ) -> Result<Collection> {
         // Verify ownership
-        let _ = self.get_by_id(user_id, id).await?;
+        let before = self.get_by_id(user_id, id).await?;
 
         if let Some(ref title) = input.title {
             sqlx::query("UPDATE collections SET title = ? WHERE id = ? AND user_id = ?")
@@ -93,19 +179,48 @@ impl CollectionService {
                 .await?;
         }
 
-        self.get_by_id(user_id, id).await
+        let collection = self.get_by_id(user_id, id).await?;
+        record_changes(
+            &self.pool,
+            user_id,
+            EntityType::Collection,
+            id,
+            &collection.title,
+            &before,
+            &collection,
+        )
+        .await?;
+        Ok(collection)
     }
 
     pub async fn delete(&self, user_id: Uuid, id: Uuid) -> Result<()> {
-        let result = sqlx::query("DELETE FROM collections WHERE id = ? AND user_id = ?")
+        let mut tx = self.pool.begin().await?;
+        let title: String =
+            sqlx::query_scalar("SELECT title FROM collections WHERE id = ? AND user_id = ?")
+                .bind(id)
+                .bind(user_id)
+                .fetch_optional(&mut *tx)
+                .await?
+                .ok_or(Error::NotFound)?;
+
+        sqlx::query("DELETE FROM collections WHERE id = ? AND user_id = ?")
             .bind(id)
             .bind(user_id)
-            .execute(&self.pool)
+            .execute(&mut *tx)
             .await?;
 
-        if result.rows_affected() == 0 {
-            return Err(Error::NotFound);
-        }
+        record_deletions(&mut tx, user_id, EntityType::Collection, &[id]).await?;
+        record_activity(
+            &mut *tx,
+            user_id,
+            ActivityAction::Delete,
+            EntityType::Collection,
+            id,
+            Some(&title),
+            None,
+        )
+        .await?;
+        tx.commit().await?;
 
         Ok(())
     }
@@ -169,6 +284,37 @@ impl CollectionService {
         Ok(items)
     }
 
+    /// Items with title, year, watched state and poster flag of the linked
+    /// movie or series, in one query
+    pub async fn get_item_details(
+        &self,
+        user_id: Uuid,
+        collection_id: Uuid,
+    ) -> Result<Vec<CollectionItemDetails>> {
+        // Verify collection ownership
+        let _ = self.get_by_id(user_id, collection_id).await?;
+
+        let items = sqlx::query_as::<_, CollectionItemDetails>(
+            r#"
+            SELECT ci.*,
+                   COALESCE(m.title, s.title) AS title,
+                   COALESCE(m.production_year, s.production_year) AS year,
+                   COALESCE(m.watched, s.watched, 0) AS watched,
+                   COALESCE(m.poster_hash, s.poster_hash) IS NOT NULL AS has_poster
+            FROM collection_items ci
+            LEFT JOIN movies m ON ci.item_type = 'movie' AND m.id = ci.movie_id
+            LEFT JOIN series s ON ci.item_type = 'series' AND s.id = ci.series_id
+            WHERE ci.collection_id = ?
+            ORDER BY ci.position
+            "#,
+        )
+        .bind(collection_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(items)
+    }
+
     pub async fn remove_item(
         &self,
         user_id: Uuid,
@@ -584,4 +730,275 @@ mod tests {
             .unwrap();
         assert_eq!(collections.len(), 2);
     }
+
+    #[tokio::test]
+    async fn test_get_item_details() {
+        let pool = create_test_db_with_users().await;
+        let service = CollectionService::new(pool.clone());
+        let movies = crate::services::MovieService::new(pool);
+        let user_id = fixtures::test_user_id();
+
+        let collection = service
+            .create(
+                user_id,
+                CreateCollection {
+                    barcode: None,
+                    title: "Trilogie".to_string(),
+                    description: None,
+                    disc_type: None,
+                },
+            )
+            .await
+            .unwrap();
+        let movie = movies
+            .create(
+                user_id,
+                crate::models::CreateMovie {
+                    barcode: None,
+                    tmdb_id: None,
+                    title: "Zurück in die Zukunft".to_string(),
+                    original_title: None,
+                    disc_type: None,
+                    production_year: Some(1985),
+                },
+            )
+            .await
+            .unwrap();
+        movies
+            .update(
+                user_id,
+                movie.id,
+                crate::models::UpdateMovie {
+                    watched: Some(true),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        let sequel = movies
+            .create(
+                user_id,
+                crate::models::CreateMovie {
+                    barcode: None,
+                    tmdb_id: None,
+                    title: "Zurück in die Zukunft II".to_string(),
+                    original_title: None,
+                    disc_type: None,
+                    production_year: None,
+                },
+            )
+            .await
+            .unwrap();
+
+        for (movie_id, position) in [(movie.id, 1), (sequel.id, 2)] {
+            service
+                .add_item(
+                    user_id,
+                    collection.id,
+                    AddCollectionItem {
+                        item_type: crate::models::CollectionItemType::Movie,
+                        movie_id: Some(movie_id),
+                        series_id: None,
+                        position: Some(position),
+                    },
+                )
+                .await
+                .unwrap();
+        }
+
+        let items = service
+            .get_item_details(user_id, collection.id)
+            .await
+            .unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].item.movie_id, Some(movie.id));
+        assert_eq!(items[0].title.as_deref(), Some("Zurück in die Zukunft"));
+        assert_eq!(items[0].year, Some(1985));
+        assert!(items[0].watched);
+        assert!(!items[0].has_poster);
+        assert_eq!(items[1].title.as_deref(), Some("Zurück in die Zukunft II"));
+        assert_eq!(items[1].year, None);
+        assert!(!items[1].watched);
+    }
+
+    #[tokio::test]
+    async fn test_list_with_stats() {
+        let pool = create_test_db_with_users().await;
+        let service = CollectionService::new(pool.clone());
+        let movies = crate::services::MovieService::new(pool);
+        let user_id = fixtures::test_user_id();
+
+        let mut collection_ids = Vec::new();
+        for title in ["A", "B"] {
+            let collection = service
+                .create(
+                    user_id,
+                    CreateCollection {
+                        barcode: None,
+                        title: title.to_string(),
+                        description: None,
+                        disc_type: None,
+                    },
+                )
+                .await
+                .unwrap();
+            collection_ids.push(collection.id);
+        }
+
+        for (running_time, watched) in [(90, true), (120, false)] {
+            let movie = movies
+                .create(
+                    user_id,
+                    crate::models::CreateMovie {
+                        barcode: None,
+                        tmdb_id: None,
+                        title: format!("Film {}", running_time),
+                        original_title: None,
+                        disc_type: None,
+                        production_year: None,
+                    },
+                )
+                .await
+                .unwrap();
+            movies
+                .update(
+                    user_id,
+                    movie.id,
+                    crate::models::UpdateMovie {
+                        running_time: Some(running_time),
+                        watched: Some(watched),
+                        ..Default::default()
+                    },
+                )
+                .await
+                .unwrap();
+            service
+                .add_item(
+                    user_id,
+                    collection_ids[0],
+                    AddCollectionItem {
+                        item_type: crate::models::CollectionItemType::Movie,
+                        movie_id: Some(movie.id),
+                        series_id: None,
+                        position: None,
+                    },
+                )
+                .await
+                .unwrap();
+        }
+
+        let stats = service
+            .list_with_stats(user_id, CollectionFilter::default())
+            .await
+            .unwrap();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].collection.title, "A");
+        assert_eq!(stats[0].item_count, 2);
+        assert_eq!(stats[0].total_runtime, 210);
+        assert!((stats[0].watched_fraction - 0.5).abs() < f64::EPSILON);
+        assert_eq!(stats[1].item_count, 0);
+        assert_eq!(stats[1].total_runtime, 0);
+        assert_eq!(stats[1].watched_fraction, 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_list_filters() {
+        let pool = create_test_db_with_users().await;
+        let service = CollectionService::new(pool.clone());
+        let movies = crate::services::MovieService::new(pool);
+        let user_id = fixtures::test_user_id();
+
+        let mut ids = Vec::new();
+        for (title, disc_type) in [
+            ("Star Wars Saga", "Blu-ray"),
+            ("Indiana Jones", "DVD"),
+            ("Star Trek", "DVD"),
+        ] {
+            let collection = service
+                .create(
+                    user_id,
+                    CreateCollection {
+                        barcode: None,
+                        title: title.to_string(),
+                        description: None,
+                        disc_type: Some(disc_type.to_string()),
+                    },
+                )
+                .await
+                .unwrap();
+            ids.push(collection.id);
+        }
+
+        let titles = |collections: Vec<Collection>| {
+            collections.into_iter().map(|c| c.title).collect::<Vec<_>>()
+        };
+
+        let found = service
+            .list(
+                user_id,
+                CollectionFilter {
+                    search: Some("star".to_string()),
+                    sort_order: Some("desc".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(titles(found), vec!["Star Wars Saga", "Star Trek"]);
+
+        let found = service
+            .list(
+                user_id,
+                CollectionFilter {
+                    disc_type: Some("dvd".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(titles(found), vec!["Indiana Jones", "Star Trek"]);
+
+        let movie = movies
+            .create(
+                user_id,
+                crate::models::CreateMovie {
+                    barcode: None,
+                    tmdb_id: None,
+                    title: "Jäger des verlorenen Schatzes".to_string(),
+                    original_title: None,
+                    disc_type: None,
+                    production_year: None,
+                },
+            )
+            .await
+            .unwrap();
+        service
+            .add_item(
+                user_id,
+                ids[1],
+                AddCollectionItem {
+                    item_type: crate::models::CollectionItemType::Movie,
+                    movie_id: Some(movie.id),
+                    series_id: None,
+                    position: None,
+                },
+            )
+            .await
+            .unwrap();
+
+        let found = service
+            .list_with_stats(
+                user_id,
+                CollectionFilter {
+                    contains_movie: Some(movie.id),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].collection.id, ids[1]);
+        assert_eq!(found[0].item_count, 1);
+    }
 }
@@ -0,0 +1,349 @@
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::db::DbPool;
+use crate::error::{Error, Result};
+use crate::models::{Contact, ContactLoan, CreateContact, UpdateContact};
+
+const SELECT_CONTACT: &str = r#"
+    SELECT c.*,
+        (SELECT COUNT(*) FROM loans l WHERE l.contact_id = c.id AND l.returned_at IS NULL)
+            AS active_loans
+    FROM contacts c
+"#;
+
+/// Borrowers and their loan history
+pub struct ContactService {
+    pool: DbPool,
+}
+
+impl ContactService {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list(&self, user_id: Uuid) -> Result<Vec<Contact>> {
+        let query = format!(
+            "{} WHERE c.user_id = ? ORDER BY c.name COLLATE NOCASE",
+            SELECT_CONTACT
+        );
+        let contacts = sqlx::query_as::<_, Contact>(&query)
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(contacts)
+    }
+
+    pub async fn get_by_id(&self, user_id: Uuid, id: Uuid) -> Result<Contact> {
+        let query = format!("{} WHERE c.id = ? AND c.user_id = ?", SELECT_CONTACT);
+        sqlx::query_as::<_, Contact>(&query)
+            .bind(id)
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or(Error::NotFound)
+    }
+
+    /// Creates the contact and claims earlier loans made out to the same name
+    pub async fn create(&self, user_id: Uuid, input: CreateContact) -> Result<Contact> {
+        let name = Self::validate_name(&input.name)?;
+        self.ensure_unique(user_id, &name, None).await?;
+
+        let id = Uuid::new_v4();
+        let now = Utc::now().to_rfc3339();
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            r#"
+            INSERT INTO contacts (id, user_id, name, email, phone, notes, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(&name)
+        .bind(&input.email)
+        .bind(&input.phone)
+        .bind(&input.notes)
+        .bind(&now)
+        .bind(&now)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE loans SET contact_id = ?
+            WHERE user_id = ? AND contact_id IS NULL AND lent_to = ? COLLATE NOCASE
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(&name)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        self.get_by_id(user_id, id).await
+    }
+
+    pub async fn update(&self, user_id: Uuid, id: Uuid, input: UpdateContact) -> Result<Contact> {
+        let contact = self.get_by_id(user_id, id).await?;
+        let name = match input.name {
+            Some(ref name) => {
+                let name = Self::validate_name(name)?;
+                self.ensure_unique(user_id, &name, Some(id)).await?;
+                Some(name)
+            }
+            None => None,
+        };
+        let now = Utc::now().to_rfc3339();
+        let mut tx = self.pool.begin().await?;
+
+        if let Some(name) = name {
+            sqlx::query("UPDATE contacts SET name = ? WHERE id = ? AND user_id = ?")
+                .bind(&name)
+                .bind(id)
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await?;
+            // Loans first, so the movie trigger keeps the open ones open
+            sqlx::query("UPDATE loans SET lent_to = ? WHERE contact_id = ?")
+                .bind(&name)
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query(
+                r#"
+                UPDATE movies SET lent_to = ?
+                WHERE user_id = ? AND trim(lent_to) = ? COLLATE NOCASE
+                "#,
+            )
+            .bind(&name)
+            .bind(user_id)
+            .bind(&contact.name)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        let fields = [
+            ("email", &input.email),
+            ("phone", &input.phone),
+            ("notes", &input.notes),
+        ];
+        for (column, value) in fields
+            .into_iter()
+            .filter_map(|(column, value)| Some((column, value.as_ref()?)))
+        {
+            // Column names are fixed above
+            let query = format!(
+                "UPDATE contacts SET {} = ? WHERE id = ? AND user_id = ?",
+                column
+            );
+            sqlx::query(&query)
+                .bind(value)
+                .bind(id)
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        sqlx::query("UPDATE contacts SET updated_at = ? WHERE id = ?")
+            .bind(&now)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        self.get_by_id(user_id, id).await
+    }
+
+    /// Removes the contact; loans keep the name they were made out to
+    pub async fn delete(&self, user_id: Uuid, id: Uuid) -> Result<()> {
+        let result = sqlx::query("DELETE FROM contacts WHERE id = ? AND user_id = ?")
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(Error::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Loans of a contact, current ones first. `active_only` leaves out
+    /// returned movies ("what does Markus still have?").
+    pub async fn loans(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+        active_only: bool,
+    ) -> Result<Vec<ContactLoan>> {
+        let _ = self.get_by_id(user_id, id).await?;
+
+        let mut query = String::from(
+            r#"
+            SELECT l.id, l.movie_id, m.title, l.lent_at, l.due, l.returned_at
+            FROM loans l
+            JOIN movies m ON m.id = l.movie_id
+            WHERE l.contact_id = ? AND l.user_id = ?
+            "#,
+        );
+        if active_only {
+            query.push_str(" AND l.returned_at IS NULL");
+        }
+        query.push_str(" ORDER BY l.returned_at IS NOT NULL, l.lent_at DESC, l.id DESC");
+
+        let loans = sqlx::query_as::<_, ContactLoan>(&query)
+            .bind(id)
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(loans)
+    }
+
+    fn validate_name(name: &str) -> Result<String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(Error::Validation("Name must not be empty".into()));
+        }
+        Ok(name.to_string())
+    }
+
+    async fn ensure_unique(&self, user_id: Uuid, name: &str, except: Option<Uuid>) -> Result<()> {
+        let existing: Option<Uuid> = sqlx::query_scalar(
+            "SELECT id FROM contacts WHERE user_id = ? AND name = ? COLLATE NOCASE",
+        )
+        .bind(user_id)
+        .bind(name)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match existing {
+            Some(found) if Some(found) != except => Err(Error::Duplicate(format!(
+                "A contact named '{}' already exists",
+                name
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{CreateMovie, UpdateMovie};
+    use crate::services::MovieService;
+    use crate::test_helpers::{create_test_db_with_users, fixtures};
+
+    async fn create_movie(movies: &MovieService, title: &str) -> Uuid {
+        movies
+            .create(
+                fixtures::test_user_id(),
+                CreateMovie {
+                    barcode: None,
+                    tmdb_id: None,
+                    title: title.to_string(),
+                    original_title: None,
+                    disc_type: None,
+                    production_year: None,
+                },
+            )
+            .await
+            .unwrap()
+            .id
+    }
+
+    async fn lend(movies: &MovieService, id: Uuid, to: &str) {
+        let update = UpdateMovie {
+            lent_to: Some(to.to_string()),
+            ..Default::default()
+        };
+        movies
+            .update(fixtures::test_user_id(), id, update)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_contact_loan_history() {
+        let pool = create_test_db_with_users().await;
+        let contacts = ContactService::new(pool.clone());
+        let movies = MovieService::new(pool);
+        let user_id = fixtures::test_user_id();
+
+        let alien = create_movie(&movies, "Alien").await;
+        let brazil = create_movie(&movies, "Brazil").await;
+        let casino = create_movie(&movies, "Casino").await;
+
+        // Lent before the contact existed
+        lend(&movies, alien, "markus").await;
+
+        let markus = contacts
+            .create(
+                user_id,
+                CreateContact {
+                    name: " Markus ".to_string(),
+                    email: Some("markus@example.com".to_string()),
+                    phone: None,
+                    notes: None,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(markus.name, "Markus");
+        assert_eq!(markus.active_loans, 1);
+
+        let duplicate = CreateContact {
+            name: "MARKUS".to_string(),
+            email: None,
+            phone: None,
+            notes: None,
+        };
+        assert!(matches!(
+            contacts.create(user_id, duplicate).await,
+            Err(Error::Duplicate(_))
+        ));
+
+        let by_contact = UpdateMovie {
+            lent_to_contact: Some(markus.id),
+            ..Default::default()
+        };
+        let lent = movies.update(user_id, brazil, by_contact).await.unwrap();
+        assert_eq!(lent.lent_to.as_deref(), Some("Markus"));
+        lend(&movies, casino, "Markus").await;
+        lend(&movies, casino, "").await;
+
+        let active = contacts.loans(user_id, markus.id, true).await.unwrap();
+        let mut titles: Vec<_> = active.iter().map(|l| l.title.as_str()).collect();
+        titles.sort();
+        assert_eq!(titles, ["Alien", "Brazil"]);
+
+        let history = contacts.loans(user_id, markus.id, false).await.unwrap();
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[2].title, "Casino");
+        assert!(history[2].returned_at.is_some());
+
+        // Renaming keeps the loans open and renames lent_to
+        let renamed = contacts
+            .update(
+                user_id,
+                markus.id,
+                UpdateContact {
+                    name: Some("Markus B.".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(renamed.active_loans, 2);
+        assert_eq!(renamed.email.as_deref(), Some("markus@example.com"));
+        let movie = movies.get_by_id(user_id, alien).await.unwrap();
+        assert_eq!(movie.lent_to.as_deref(), Some("Markus B."));
+
+        contacts.delete(user_id, markus.id).await.unwrap();
+        assert!(matches!(
+            contacts.get_by_id(user_id, markus.id).await,
+            Err(Error::NotFound)
+        ));
+    }
+}
@@ -0,0 +1,397 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+use serde::Deserialize;
+use sqlx::SqlitePool;
+use tracing::warn;
+
+use crate::error::{Error, Result};
+use crate::models::{
+    ConvertedTotals, ConvertedValuation, ExchangeRate, RateSource, SettingKey, ValuationTotals,
+    ValueReport, normalize_currency,
+};
+use crate::services::SettingsService;
+
+/// All rates are stored as units per euro
+pub const BASE_CURRENCY: &str = "EUR";
+
+/// Response of the rate endpoint (frankfurter.app, exchangerate.host, ...)
+#[derive(Debug, Deserialize)]
+struct RatesResponse {
+    base: String,
+    rates: HashMap<String, f64>,
+}
+
+/// Parse the `exchange_rates` setting ("USD=1.08, GBP=0.85")
+pub fn parse_manual_rates(input: &str) -> std::result::Result<HashMap<String, f64>, String> {
+    let mut rates = HashMap::new();
+    for entry in input.split([',', ';', '\n']).map(str::trim) {
+        if entry.is_empty() {
+            continue;
+        }
+        let (currency, rate) = entry
+            .split_once('=')
+            .ok_or_else(|| format!("Expected CURRENCY=RATE, got \"{}\"", entry))?;
+        let currency = normalize_currency(currency)?;
+        let rate = rate
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|r| r.is_finite() && *r > 0.0)
+            .ok_or_else(|| format!("Invalid rate for {}: \"{}\"", currency, rate.trim()))?;
+        rates.insert(currency, rate);
+    }
+    Ok(rates)
+}
+
+/// Convert rates quoted against `base` into rates per euro
+fn rebase_rates(
+    base: &str,
+    rates: HashMap<String, f64>,
+) -> std::result::Result<HashMap<String, f64>, String> {
+    let base = normalize_currency(base)?;
+    if base == BASE_CURRENCY {
+        return Ok(rates);
+    }
+
+    let euro = rates
+        .get(BASE_CURRENCY)
+        .copied()
+        .filter(|r| *r > 0.0)
+        .ok_or_else(|| format!("No {} rate for base {}", BASE_CURRENCY, base))?;
+    let mut rebased: HashMap<String, f64> = rates
+        .into_iter()
+        .filter(|(currency, _)| currency != BASE_CURRENCY)
+        .map(|(currency, rate)| (currency, rate / euro))
+        .collect();
+    rebased.insert(base, 1.0 / euro);
+    Ok(rebased)
+}
+
+/// Exchange rates in effect, as units per euro
+#[derive(Debug, Clone, Default)]
+pub struct ExchangeRates {
+    rates: HashMap<String, f64>,
+}
+
+impl ExchangeRates {
+    pub fn new(rates: HashMap<String, f64>) -> Self {
+        Self { rates }
+    }
+
+    pub fn rate(&self, currency: &str) -> Option<f64> {
+        if currency == BASE_CURRENCY {
+            Some(1.0)
+        } else {
+            self.rates.get(currency).copied()
+        }
+    }
+
+    /// Convert between two ISO codes; `None` if a rate is missing
+    pub fn convert(&self, amount: f64, from: &str, to: &str) -> Option<f64> {
+        Some(amount / self.rate(from)? * self.rate(to)?)
+    }
+
+    /// Sum up the per-currency totals of a value report in `to`. Amounts
+    /// without currency are taken as `to`.
+    pub fn convert_report(&self, report: &ValueReport, to: &str) -> ConvertedTotals {
+        let mut converted = ConvertedTotals {
+            currency: to.to_string(),
+            ..Default::default()
+        };
+
+        for totals in &report.totals {
+            let Some(factor) = self.factor(totals.currency.as_deref(), to) else {
+                converted
+                    .unconverted
+                    .extend(totals.currency.iter().cloned());
+                continue;
+            };
+
+            converted.purchase_total += totals.purchase_total * factor;
+            converted.value_total += totals.value_total * factor;
+            converted.disposed_purchase_total += totals.disposed_purchase_total * factor;
+            converted.disposal_total += totals.disposal_total * factor;
+        }
+
+        converted
+    }
+
+    /// Sum up the per-currency totals of a valuation in `to`, like
+    /// [`ExchangeRates::convert_report`]
+    pub fn convert_valuation(&self, totals: &[ValuationTotals], to: &str) -> ConvertedValuation {
+        let mut converted = ConvertedValuation {
+            currency: to.to_string(),
+            ..Default::default()
+        };
+
+        for totals in totals {
+            let Some(factor) = self.factor(totals.currency.as_deref(), to) else {
+                converted
+                    .unconverted
+                    .extend(totals.currency.iter().cloned());
+                continue;
+            };
+
+            converted.purchase_total += totals.purchase_total * factor;
+            converted.value_total += totals.value_total * factor;
+        }
+
+        converted
+    }
+
+    /// Factor converting amounts in a stored currency (code, symbol or name)
+    /// into `to`; amounts without currency are taken as `to`
+    fn factor(&self, currency: Option<&str>, to: &str) -> Option<f64> {
+        match currency {
+            None => Some(1.0),
+            Some(currency) => normalize_currency(currency)
+                .ok()
+                .and_then(|from| self.convert(1.0, &from, to)),
+        }
+    }
+}
+
+/// Exchange rates for value reports: fetched rates, overridden by the
+/// manually maintained `exchange_rates` setting
+pub struct CurrencyService {
+    pool: SqlitePool,
+    client: reqwest::Client,
+}
+
+impl CurrencyService {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            pool,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Rates in effect, sorted by currency
+    pub async fn list(&self) -> Result<Vec<ExchangeRate>> {
+        let mut rates = sqlx::query_as::<_, ExchangeRate>(
+            "SELECT currency, rate, 'fetched' AS source, updated_at FROM exchange_rates",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        for (currency, rate) in self.manual_rates().await? {
+            rates.retain(|r| r.currency != currency);
+            rates.push(ExchangeRate {
+                currency,
+                rate,
+                source: RateSource::Manual,
+                updated_at: None,
+            });
+        }
+
+        rates.sort_by(|a, b| a.currency.cmp(&b.currency));
+        Ok(rates)
+    }
+
+    pub async fn rates(&self) -> Result<ExchangeRates> {
+        let rates = self.list().await?;
+        Ok(ExchangeRates::new(
+            rates.into_iter().map(|r| (r.currency, r.rate)).collect(),
+        ))
+    }
+
+    /// Invalid manual rates are ignored so reports keep working; the setting
+    /// is validated when it's changed via the API
+    async fn manual_rates(&self) -> Result<HashMap<String, f64>> {
+        let settings = SettingsService::new(self.pool.clone());
+        let Some(value) = settings.get(SettingKey::ExchangeRates).await? else {
+            return Ok(HashMap::new());
+        };
+        Ok(parse_manual_rates(&value).unwrap_or_else(|e| {
+            warn!("Ignoring invalid exchange_rates setting: {}", e);
+            HashMap::new()
+        }))
+    }
+
+    /// Store fetched rates, replacing earlier ones. Returns the number of rates.
+    pub async fn store_fetched(&self, base: &str, rates: HashMap<String, f64>) -> Result<usize> {
+        let rates = rebase_rates(base, rates).map_err(Error::ExternalApi)?;
+        let now = Utc::now().to_rfc3339();
+
+        let mut stored = 0;
+        let mut tx = self.pool.begin().await?;
+        for (currency, rate) in &rates {
+            let Ok(currency) = normalize_currency(currency) else {
+                continue;
+            };
+            if !rate.is_finite() || *rate <= 0.0 {
+                continue;
+            }
+            sqlx::query(
+                r#"
+                INSERT INTO exchange_rates (currency, rate, updated_at) VALUES (?, ?, ?)
+                ON CONFLICT(currency) DO UPDATE SET
+                    rate = excluded.rate,
+                    updated_at = excluded.updated_at
+                "#,
+            )
+            .bind(currency)
+            .bind(rate)
+            .bind(&now)
+            .execute(&mut *tx)
+            .await?;
+            stored += 1;
+        }
+        tx.commit().await?;
+
+        Ok(stored)
+    }
+
+    /// Fetch rates from `exchange_rate_url`. Returns `None` when no URL is set.
+    pub async fn refresh(&self) -> Result<Option<usize>> {
+        let settings = SettingsService::new(self.pool.clone());
+        let Some(url) = settings.get(SettingKey::ExchangeRateUrl).await? else {
+            return Ok(None);
+        };
+
+        let response = self
+            .client
+            .get(&url)
+            .header("User-Agent", "MyMovies/1.0")
+            .send()
+            .await
+            .map_err(|e| Error::ExternalApi(format!("Exchange rate request failed: {}", e)))?;
+        if !response.status().is_success() {
+            return Err(Error::ExternalApi(format!(
+                "Exchange rate endpoint returned {}",
+                response.status()
+            )));
+        }
+        let data: RatesResponse = response
+            .json()
+            .await
+            .map_err(|e| Error::ExternalApi(format!("Invalid exchange rate response: {}", e)))?;
+
+        self.store_fetched(&data.base, data.rates).await.map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{CurrencyTotals, SettingUpdate};
+    use crate::test_helpers::create_test_db;
+
+    fn rates(entries: &[(&str, f64)]) -> HashMap<String, f64> {
+        entries.iter().map(|(c, r)| (c.to_string(), *r)).collect()
+    }
+
+    #[test]
+    fn test_parse_manual_rates() {
+        let parsed = parse_manual_rates("USD=1.08; gbp = 0.85\n").unwrap();
+        assert_eq!(parsed, rates(&[("USD", 1.08), ("GBP", 0.85)]));
+        assert!(parse_manual_rates("").unwrap().is_empty());
+        assert!(parse_manual_rates("USD").is_err());
+        assert!(parse_manual_rates("USD=-1").is_err());
+        assert!(parse_manual_rates("Dollars=1").is_err());
+    }
+
+    #[test]
+    fn test_rebase_rates() {
+        let rebased = rebase_rates("USD", rates(&[("EUR", 0.5), ("GBP", 0.4)])).unwrap();
+        assert_eq!(rebased, rates(&[("USD", 2.0), ("GBP", 0.8)]));
+        assert!(rebase_rates("USD", rates(&[("GBP", 0.4)])).is_err());
+    }
+
+    #[test]
+    fn test_convert_report() {
+        let exchange = ExchangeRates::new(rates(&[("USD", 2.0)]));
+        assert_eq!(exchange.convert(10.0, "USD", "EUR"), Some(5.0));
+        assert_eq!(exchange.convert(10.0, "EUR", "GBP"), None);
+
+        let report = ValueReport {
+            totals: vec![
+                CurrencyTotals {
+                    currency: None,
+                    purchase_total: 1.0,
+                    ..Default::default()
+                },
+                CurrencyTotals {
+                    currency: Some("€".to_string()),
+                    purchase_total: 10.0,
+                    disposal_total: 4.0,
+                    ..Default::default()
+                },
+                CurrencyTotals {
+                    currency: Some("USD".to_string()),
+                    purchase_total: 20.0,
+                    ..Default::default()
+                },
+                CurrencyTotals {
+                    currency: Some("GBP".to_string()),
+                    purchase_total: 99.0,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let converted = exchange.convert_report(&report, "EUR");
+        assert_eq!(converted.purchase_total, 21.0);
+        assert_eq!(converted.disposal_total, 4.0);
+        assert_eq!(converted.unconverted, vec!["GBP".to_string()]);
+    }
+
+    #[test]
+    fn test_convert_valuation() {
+        let exchange = ExchangeRates::new(rates(&[("USD", 2.0)]));
+        let totals = [
+            ValuationTotals {
+                currency: Some("$".to_string()),
+                purchase_total: 20.0,
+                value_total: 30.0,
+                ..Default::default()
+            },
+            ValuationTotals {
+                currency: None,
+                value_total: 5.0,
+                ..Default::default()
+            },
+            ValuationTotals {
+                currency: Some("yen".to_string()),
+                purchase_total: 1000.0,
+                ..Default::default()
+            },
+        ];
+
+        let converted = exchange.convert_valuation(&totals, "EUR");
+        assert_eq!(converted.purchase_total, 10.0);
+        assert_eq!(converted.value_total, 20.0);
+        assert_eq!(converted.unconverted, vec!["yen".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_manual_rates_override_fetched() {
+        let pool = create_test_db().await;
+        let service = CurrencyService::new(pool.clone());
+
+        service
+            .store_fetched("EUR", rates(&[("USD", 1.1), ("GBP", 0.9)]))
+            .await
+            .unwrap();
+        SettingsService::new(pool)
+            .update(
+                SettingKey::ExchangeRates,
+                SettingUpdate {
+                    value: "USD=1.2".to_string(),
+                },
+            )
+            .await
+            .unwrap();
+
+        let list = service.list().await.unwrap();
+        assert_eq!(list.len(), 2);
+        assert_eq!(list[1].currency, "USD");
+        assert_eq!(list[1].source, RateSource::Manual);
+
+        let exchange = service.rates().await.unwrap();
+        assert_eq!(exchange.rate("USD"), Some(1.2));
+        assert_eq!(exchange.rate("GBP"), Some(0.9));
+    }
+}
@@ -0,0 +1,256 @@
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+use crate::db::DbPool;
+use crate::error::Result;
+use crate::models::{CreateMovie, UpdateMovie};
+use crate::services::{AuthService, MovieService};
+
+pub const DEMO_USERNAME: &str = "demo";
+pub const DEMO_PASSWORD: &str = "demo";
+const DEMO_EMAIL: &str = "demo@example.com";
+
+/// A movie in the demo library
+pub struct DemoMovie {
+    pub tmdb_id: i64,
+    pub title: &'static str,
+    pub original_title: &'static str,
+    pub year: i32,
+    pub disc_type: &'static str,
+    pub running_time: i32,
+    pub director: &'static str,
+    pub genres: &'static str,
+    pub description: &'static str,
+    pub watched: bool,
+    pub personal_rating: Option<f64>,
+}
+
+pub const DEMO_MOVIES: &[DemoMovie] = &[
+    DemoMovie {
+        tmdb_id: 603,
+        title: "Matrix",
+        original_title: "The Matrix",
+        year: 1999,
+        disc_type: "Blu-ray",
+        running_time: 136,
+        director: "Lana Wachowski, Lilly Wachowski",
+        genres: "Action, Science Fiction",
+        description: "Der Hacker Neo erfährt, dass seine Welt eine Simulation ist, und schließt sich dem Widerstand an.",
+        watched: true,
+        personal_rating: Some(9.0),
+    },
+    DemoMovie {
+        tmdb_id: 27205,
+        title: "Inception",
+        original_title: "Inception",
+        year: 2010,
+        disc_type: "4K UHD",
+        running_time: 148,
+        director: "Christopher Nolan",
+        genres: "Action, Science Fiction, Abenteuer",
+        description: "Ein Dieb, der Geheimnisse aus Träumen stiehlt, soll diesmal eine Idee einpflanzen.",
+        watched: true,
+        personal_rating: Some(8.5),
+    },
+    DemoMovie {
+        tmdb_id: 238,
+        title: "Der Pate",
+        original_title: "The Godfather",
+        year: 1972,
+        disc_type: "Blu-ray",
+        running_time: 175,
+        director: "Francis Ford Coppola",
+        genres: "Drama, Krimi",
+        description: "Die Geschichte der Mafiafamilie Corleone und des Aufstiegs von Michael Corleone.",
+        watched: false,
+        personal_rating: None,
+    },
+    DemoMovie {
+        tmdb_id: 129,
+        title: "Chihiros Reise ins Zauberland",
+        original_title: "千と千尋の神隠し",
+        year: 2001,
+        disc_type: "DVD",
+        running_time: 125,
+        director: "Hayao Miyazaki",
+        genres: "Animation, Familie, Fantasy",
+        description: "Die zehnjährige Chihiro gerät in eine Welt voller Geister und Götter.",
+        watched: true,
+        personal_rating: Some(9.5),
+    },
+    DemoMovie {
+        tmdb_id: 78,
+        title: "Blade Runner",
+        original_title: "Blade Runner",
+        year: 1982,
+        disc_type: "4K UHD",
+        running_time: 117,
+        director: "Ridley Scott",
+        genres: "Science Fiction, Drama, Thriller",
+        description: "Im Los Angeles des Jahres 2019 jagt Rick Deckard abtrünnige Replikanten.",
+        watched: false,
+        personal_rating: None,
+    },
+    DemoMovie {
+        tmdb_id: 105,
+        title: "Zurück in die Zukunft",
+        original_title: "Back to the Future",
+        year: 1985,
+        disc_type: "Blu-ray",
+        running_time: 116,
+        director: "Robert Zemeckis",
+        genres: "Abenteuer, Komödie, Science Fiction",
+        description: "Marty McFly landet mit einer Zeitmaschine im Jahr 1955.",
+        watched: true,
+        personal_rating: Some(8.0),
+    },
+    DemoMovie {
+        tmdb_id: 680,
+        title: "Pulp Fiction",
+        original_title: "Pulp Fiction",
+        year: 1994,
+        disc_type: "DVD",
+        running_time: 154,
+        director: "Quentin Tarantino",
+        genres: "Thriller, Krimi",
+        description: "Miteinander verwobene Geschichten aus der Unterwelt von Los Angeles.",
+        watched: false,
+        personal_rating: None,
+    },
+    DemoMovie {
+        tmdb_id: 496243,
+        title: "Parasite",
+        original_title: "기생충",
+        year: 2019,
+        disc_type: "Blu-ray",
+        running_time: 133,
+        director: "Bong Joon-ho",
+        genres: "Komödie, Thriller, Drama",
+        description: "Eine arme Familie schleicht sich nach und nach in den Haushalt einer reichen Familie ein.",
+        watched: true,
+        personal_rating: Some(9.0),
+    },
+];
+
+/// Seeds and resets the sample library used in demo mode
+pub struct DemoService {
+    pool: DbPool,
+}
+
+impl DemoService {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Delete all user data and recreate the demo user with the sample library.
+    ///
+    /// `posters` maps TMDB ids to already downloaded poster images so they
+    /// don't have to be fetched again on every reset.
+    pub async fn reset(&self, posters: &HashMap<i64, Vec<u8>>) -> Result<Uuid> {
+        // Delete explicitly instead of relying on ON DELETE CASCADE,
+        // which needs foreign keys enabled on the connection
+        for table in [
+            "collection_items",
+            "collections",
+            "series",
+            "movies",
+            "users",
+        ] {
+            sqlx::query(&format!("DELETE FROM {}", table))
+                .execute(&self.pool)
+                .await?;
+        }
+
+        // The JWT secret is not needed for creating users
+        let auth_service = AuthService::new(self.pool.clone(), String::new());
+        let (user, _) = auth_service
+            .admin_create_user(
+                DEMO_USERNAME.to_string(),
+                DEMO_EMAIL.to_string(),
+                Some(DEMO_PASSWORD.to_string()),
+            )
+            .await?;
+
+        let movie_service = MovieService::new(self.pool.clone());
+        for demo in DEMO_MOVIES {
+            let movie = movie_service
+                .create(
+                    user.id,
+                    CreateMovie {
+                        barcode: None,
+                        tmdb_id: Some(demo.tmdb_id),
+                        title: demo.title.to_string(),
+                        original_title: Some(demo.original_title.to_string()),
+                        disc_type: Some(demo.disc_type.to_string()),
+                        production_year: Some(demo.year),
+                    },
+                )
+                .await?;
+
+            movie_service
+                .update(
+                    user.id,
+                    movie.id,
+                    UpdateMovie {
+                        description: Some(demo.description.to_string()),
+                        running_time: Some(demo.running_time),
+                        director: Some(demo.director.to_string()),
+                        genres: Some(demo.genres.to_string()),
+                        watched: Some(demo.watched),
+                        personal_rating: demo.personal_rating,
+                        poster_data: posters.get(&demo.tmdb_id).cloned(),
+                        ..Default::default()
+                    },
+                )
+                .await?;
+        }
+
+        Ok(user.id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::MovieFilter;
+    use crate::test_helpers::create_test_db_with_users;
+
+    #[tokio::test]
+    async fn test_reset_seeds_demo_library() {
+        let pool = create_test_db_with_users().await;
+        let service = DemoService::new(pool.clone());
+
+        let user_id = service.reset(&HashMap::new()).await.unwrap();
+
+        let movie_service = MovieService::new(pool.clone());
+        let count = movie_service
+            .count(user_id, &MovieFilter::default())
+            .await
+            .unwrap();
+        assert_eq!(count, DEMO_MOVIES.len() as i64);
+
+        // Existing users are removed, only the demo user remains
+        let users: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(users, 1);
+    }
+
+    #[tokio::test]
+    async fn test_reset_is_repeatable() {
+        let pool = create_test_db_with_users().await;
+        let service = DemoService::new(pool.clone());
+
+        service.reset(&HashMap::new()).await.unwrap();
+        let user_id = service.reset(&HashMap::new()).await.unwrap();
+
+        let movie_service = MovieService::new(pool);
+        let count = movie_service
+            .count(user_id, &MovieFilter::default())
+            .await
+            .unwrap();
+        assert_eq!(count, DEMO_MOVIES.len() as i64);
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Instant;
+
+use serde::Serialize;
+
+use crate::db::DbPool;
+use crate::error::Result;
+
+/// Tables whose row counts are included in diagnostics
+const COUNTED_TABLES: &[&str] = &[
+    "users",
+    "movies",
+    "series",
+    "collections",
+    "collection_items",
+    "images",
+    "settings",
+];
+
+/// Database facts for support bundles. Contains counts only, no personal data.
+#[derive(Debug, Clone, Serialize)]
+pub struct DatabaseDiagnostics {
+    /// Latest applied migration version
+    pub schema_version: Option<i64>,
+    pub migrations_applied: i64,
+    pub sqlite_version: String,
+    pub size_bytes: i64,
+    pub row_counts: BTreeMap<String, i64>,
+    pub movies_with_poster: i64,
+    /// Round trip time of a trivial query
+    pub query_latency_ms: f64,
+}
+
+pub struct DiagnosticsService {
+    pool: DbPool,
+}
+
+impl DiagnosticsService {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Highest successfully applied migration
+    pub async fn schema_version(&self) -> Result<Option<i64>> {
+        let version = sqlx::query_scalar::<_, Option<i64>>(
+            "SELECT MAX(version) FROM _sqlx_migrations WHERE success = 1",
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(version)
+    }
+
+    /// Write a consistent copy of the database to `target` with `VACUUM INTO`.
+    /// `target` must not exist yet.
+    pub async fn snapshot(&self, target: &Path) -> Result<()> {
+        sqlx::query("VACUUM INTO ?")
+            .bind(target.to_string_lossy().into_owned())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn database(&self) -> Result<DatabaseDiagnostics> {
+        let started = Instant::now();
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        let query_latency_ms = started.elapsed().as_secs_f64() * 1000.0;
+
+        let (schema_version, migrations_applied) = sqlx::query_as::<_, (Option<i64>, i64)>(
+            "SELECT MAX(version), COUNT(*) FROM _sqlx_migrations WHERE success = 1",
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        let sqlite_version = sqlx::query_scalar::<_, String>("SELECT sqlite_version()")
+            .fetch_one(&self.pool)
+            .await?;
+
+        let size_bytes = sqlx::query_scalar::<_, i64>(
+            "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()",
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        let mut row_counts = BTreeMap::new();
+        for table in COUNTED_TABLES {
+            let count = sqlx::query_scalar::<_, i64>(&format!("SELECT COUNT(*) FROM {}", table))
+                .fetch_one(&self.pool)
+                .await?;
+            row_counts.insert(table.to_string(), count);
+        }
+
+        let movies_with_poster = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM movies WHERE poster_hash IS NOT NULL",
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(DatabaseDiagnostics {
+            schema_version,
+            migrations_applied,
+            sqlite_version,
+            size_bytes,
+            row_counts,
+            movies_with_poster,
+            query_latency_ms,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::create_test_db_with_users;
+
+    #[tokio::test]
+    async fn test_database_diagnostics() {
+        let pool = create_test_db_with_users().await;
+        let service = DiagnosticsService::new(pool);
+
+        let diagnostics = service.database().await.unwrap();
+
+        assert!(diagnostics.schema_version.is_some());
+        assert_eq!(
+            service.schema_version().await.unwrap(),
+            diagnostics.schema_version
+        );
+        assert!(diagnostics.migrations_applied > 0);
+        assert_eq!(diagnostics.row_counts.get("users"), Some(&2));
+        assert_eq!(diagnostics.row_counts.get("movies"), Some(&0));
+        assert!(diagnostics.size_bytes > 0);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot() {
+        // VACUUM INTO from an in-memory database writes to memory as well
+        let dir = tempfile::tempdir().unwrap();
+        let url = format!("sqlite:{}?mode=rwc", dir.path().join("movies.db").display());
+        let pool = crate::db::create_pool(&url).await.unwrap();
+        let service = DiagnosticsService::new(pool);
+        let target = dir.path().join("snapshot.db");
+
+        service.snapshot(&target).await.unwrap();
+        let header = std::fs::read(&target).unwrap();
+        assert!(header.starts_with(b"SQLite format 3\0"));
+    }
+}
@@ -0,0 +1,376 @@
+use chrono::{DateTime, Datelike, Duration, Timelike, Utc};
+use uuid::Uuid;
+
+use crate::db::DbPool;
+use crate::error::{Error, Result};
+use crate::models::{
+    Digest, DigestLoan, DigestMovie, DigestSettings, User, local_date, user_timezone,
+};
+use crate::services::EmailMessage;
+
+/// Additions listed in one digest; the rest is summarized as a count
+const MAX_ADDITIONS: usize = 20;
+const SUGGESTIONS: i64 = 3;
+
+/// Email text of the digest; `{{name}}` placeholders are filled by `render`
+const TEMPLATE: &str = "Hallo {{username}},
+
+hier ist dein Wochenrückblick vom {{since}} bis {{today}}.
+
+{{sections}}Viel Spaß beim Schauen!
+My Movies
+
+--
+Du bekommst diese E-Mail, weil du den Wochenrückblick aktiviert hast.
+Abbestellen kannst du ihn in deinen Einstellungen.
+";
+
+/// Replace each `{{key}}` in `template` by its value
+fn fill(template: &str, values: &[(&str, String)]) -> String {
+    values
+        .iter()
+        .fold(template.to_string(), |text, (key, value)| {
+            text.replace(&format!("{{{{{}}}}}", key), value)
+        })
+}
+
+fn movie_line(movie: &DigestMovie) -> String {
+    match movie.production_year {
+        Some(year) => format!("- {} ({})", movie.title, year),
+        None => format!("- {}", movie.title),
+    }
+}
+
+/// Whether the digest is due at `now`: on the chosen weekday from the chosen
+/// hour on (in the user's timezone), once per week
+pub fn is_due(
+    settings: DigestSettings,
+    timezone: Option<&str>,
+    sent_at: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> bool {
+    let local = now.with_timezone(&user_timezone(timezone));
+    settings.enabled
+        && local.weekday().num_days_from_monday() as i32 == settings.weekday
+        && local.hour() as i32 >= settings.hour
+        && sent_at.is_none_or(|sent| now - sent > Duration::days(1))
+}
+
+/// The weekly digest email: new additions, loans due this week and a few
+/// unwatched movies, for users who opted in
+pub struct DigestService {
+    pool: DbPool,
+}
+
+impl DigestService {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Digests due at `now`, marked as sent. Users with nothing to report
+    /// are marked too and get no email this week.
+    pub async fn take_due(&self, now: DateTime<Utc>) -> Result<Vec<Digest>> {
+        let users = sqlx::query_as::<_, User>(
+            "SELECT * FROM users WHERE digest_enabled = 1 AND email != '' ORDER BY username",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut digests = Vec::new();
+        for user in users {
+            if !is_due(
+                user.digest_settings(),
+                user.timezone.as_deref(),
+                user.digest_sent_at,
+                now,
+            ) {
+                continue;
+            }
+
+            sqlx::query("UPDATE users SET digest_sent_at = ? WHERE id = ?")
+                .bind(now.to_rfc3339())
+                .bind(user.id)
+                .execute(&self.pool)
+                .await?;
+
+            let digest = self.compose(&user, now).await?;
+            if !digest.is_empty() {
+                digests.push(digest);
+            }
+        }
+
+        Ok(digests)
+    }
+
+    /// The digest `user_id` would get at `now`, whether or not it is enabled
+    pub async fn preview(&self, user_id: Uuid, now: DateTime<Utc>) -> Result<Digest> {
+        let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = ?")
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or(Error::UserNotFound)?;
+        self.compose(&user, now).await
+    }
+
+    async fn compose(&self, user: &User, now: DateTime<Utc>) -> Result<Digest> {
+        let today = local_date(now, user.timezone.as_deref());
+
+        let additions = sqlx::query_as::<_, DigestMovie>(
+            r#"
+            SELECT id, title, production_year FROM movies
+            WHERE user_id = ? AND is_collection = 0 AND disposition IS NULL
+              AND availability IS NOT NULL
+              AND julianday(created_at) >= julianday(?)
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(user.id)
+        .bind((now - Duration::days(7)).to_rfc3339())
+        .fetch_all(&self.pool)
+        .await?;
+
+        let loans_due = sqlx::query_as::<_, DigestLoan>(
+            r#"
+            SELECT id, title, lent_to, lent_due FROM movies
+            WHERE user_id = ? AND disposition IS NULL
+              AND lent_to IS NOT NULL AND lent_to != ''
+              AND lent_due IS NOT NULL AND lent_due <= ?
+            ORDER BY lent_due
+            "#,
+        )
+        .bind(user.id)
+        .bind(today + Duration::days(6))
+        .fetch_all(&self.pool)
+        .await?;
+
+        let suggestions = sqlx::query_as::<_, DigestMovie>(
+            r#"
+            SELECT id, title, production_year FROM movies
+            WHERE user_id = ? AND watched = 0 AND is_collection = 0 AND disposition IS NULL
+              AND availability = 'available'
+            ORDER BY RANDOM()
+            LIMIT ?
+            "#,
+        )
+        .bind(user.id)
+        .bind(SUGGESTIONS)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(Digest {
+            user_id: user.id,
+            username: user.username.clone(),
+            email: user.email.clone(),
+            since: today - Duration::days(7),
+            today,
+            additions,
+            loans_due,
+            suggestions,
+        })
+    }
+
+    /// The email for a digest; empty sections are left out
+    pub fn render(digest: &Digest) -> EmailMessage {
+        let mut sections = String::new();
+
+        if !digest.additions.is_empty() {
+            sections.push_str(&format!(
+                "Neu in deiner Sammlung ({}):\n",
+                digest.additions.len()
+            ));
+            for movie in digest.additions.iter().take(MAX_ADDITIONS) {
+                sections.push_str(&movie_line(movie));
+                sections.push('\n');
+            }
+            if digest.additions.len() > MAX_ADDITIONS {
+                sections.push_str(&format!(
+                    "... und {} weitere\n",
+                    digest.additions.len() - MAX_ADDITIONS
+                ));
+            }
+            sections.push('\n');
+        }
+
+        if !digest.loans_due.is_empty() {
+            sections.push_str("Verliehen und bald fällig:\n");
+            for loan in &digest.loans_due {
+                let due = loan.lent_due.format("%d.%m.%Y");
+                if loan.lent_due < digest.today {
+                    sections.push_str(&format!(
+                        "- {} bei {}, seit {} überfällig\n",
+                        loan.title, loan.lent_to, due
+                    ));
+                } else {
+                    sections.push_str(&format!(
+                        "- {} bei {}, fällig am {}\n",
+                        loan.title, loan.lent_to, due
+                    ));
+                }
+            }
+            sections.push('\n');
+        }
+
+        if !digest.suggestions.is_empty() {
+            sections.push_str("Noch nicht gesehen:\n");
+            for movie in &digest.suggestions {
+                sections.push_str(&movie_line(movie));
+                sections.push('\n');
+            }
+            sections.push('\n');
+        }
+
+        let body = fill(
+            TEMPLATE,
+            &[
+                ("username", digest.username.clone()),
+                ("since", digest.since.format("%d.%m.%Y").to_string()),
+                ("today", digest.today.format("%d.%m.%Y").to_string()),
+                ("sections", sections),
+            ],
+        );
+
+        EmailMessage {
+            to: digest.email.clone(),
+            subject: "Dein Wochenrückblick".to_string(),
+            body,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{CreateMovie, UpdateMovie};
+    use crate::services::{AuthService, MovieService};
+    use crate::test_helpers::{create_test_db_with_users, fixtures};
+
+    #[test]
+    fn test_is_due() {
+        // Sunday, 2026-03-15
+        let sunday = |hour| {
+            chrono::NaiveDate::from_ymd_opt(2026, 3, 15)
+                .unwrap()
+                .and_hms_opt(hour, 0, 0)
+                .unwrap()
+                .and_utc()
+        };
+        let settings = DigestSettings {
+            enabled: true,
+            weekday: 6,
+            hour: 9,
+        };
+
+        assert!(is_due(settings, None, None, sunday(9)));
+        assert!(!is_due(settings, None, None, sunday(8)));
+        // Already 9 o'clock two hours east of Greenwich
+        assert!(is_due(settings, Some("Europe/Helsinki"), None, sunday(7)));
+        // Monday already east of the date line
+        assert!(!is_due(
+            settings,
+            Some("Pacific/Kiritimati"),
+            None,
+            sunday(12)
+        ));
+        // Berlin is one hour ahead in winter and two in summer
+        assert!(!is_due(settings, Some("Europe/Berlin"), None, sunday(7)));
+        let summer_sunday = sunday(7) + Duration::weeks(17);
+        assert!(is_due(settings, Some("Europe/Berlin"), None, summer_sunday));
+        // Once a week
+        assert!(!is_due(settings, None, Some(sunday(9)), sunday(20)));
+        assert!(is_due(
+            settings,
+            None,
+            Some(sunday(9) - Duration::days(7)),
+            sunday(9)
+        ));
+        let disabled = DigestSettings {
+            enabled: false,
+            ..settings
+        };
+        assert!(!is_due(disabled, None, None, sunday(9)));
+    }
+
+    #[tokio::test]
+    async fn test_take_due_and_render() {
+        let pool = create_test_db_with_users().await;
+        let movies = MovieService::new(pool.clone());
+        let auth = AuthService::new(pool.clone(), "secret".to_string());
+        let digests = DigestService::new(pool);
+        let user_id = fixtures::test_user_id();
+        let now = Utc::now();
+
+        for title in ["Heat", "Ronin"] {
+            let movie = movies
+                .create(
+                    user_id,
+                    CreateMovie {
+                        barcode: None,
+                        tmdb_id: None,
+                        title: title.to_string(),
+                        original_title: None,
+                        disc_type: None,
+                        production_year: Some(1995),
+                    },
+                )
+                .await
+                .unwrap();
+            if title == "Ronin" {
+                movies
+                    .update(
+                        user_id,
+                        movie.id,
+                        UpdateMovie {
+                            lent_to: Some("Anna".to_string()),
+                            lent_due: Some(local_date(now, None) + Duration::days(2)),
+                            ..Default::default()
+                        },
+                    )
+                    .await
+                    .unwrap();
+            }
+        }
+
+        // Opt-in only
+        assert!(digests.take_due(now).await.unwrap().is_empty());
+
+        let weekday = now.weekday().num_days_from_monday() as i32;
+        auth.update_user_digest(
+            user_id,
+            DigestSettings {
+                enabled: true,
+                weekday,
+                hour: 0,
+            },
+        )
+        .await
+        .unwrap();
+        let due = digests.take_due(now).await.unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].additions.len(), 2);
+        assert_eq!(due[0].loans_due.len(), 1);
+        assert_eq!(due[0].suggestions.len(), 1);
+        assert_eq!(due[0].suggestions[0].title, "Heat");
+
+        // Sent once a week
+        assert!(digests.take_due(now).await.unwrap().is_empty());
+
+        let email = DigestService::render(&due[0]);
+        assert_eq!(email.to, "test@test.com");
+        assert!(email.body.starts_with("Hallo testuser,"));
+        assert!(email.body.contains("Neu in deiner Sammlung (2):\n"));
+        assert!(email.body.contains("- Heat (1995)\n"));
+        assert!(email.body.contains("- Ronin bei Anna, fällig am "));
+        assert!(!email.body.contains("{{"));
+
+        // Out of range schedules are rejected
+        let invalid = DigestSettings {
+            enabled: true,
+            weekday: 7,
+            hour: 0,
+        };
+        assert!(matches!(
+            auth.update_user_digest(user_id, invalid).await,
+            Err(Error::Validation(_))
+        ));
+    }
+}
@@ -0,0 +1,550 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::RwLock;
+
+use crate::error::{Error, Result};
+use crate::models::{LoanReminder, LoanReminderKind, Movie, SettingKey};
+use crate::services::settings::SettingsWatch;
+
+/// Embed accent color (blue)
+const EMBED_COLOR: u32 = 0x3B82F6;
+/// Discord limits embed descriptions to 4096 characters; keep notifications short
+const MAX_DESCRIPTION_CHARS: usize = 300;
+const POSTER_FILENAME: &str = "poster.jpg";
+
+/// Events that can be forwarded to Discord
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiscordEvent {
+    MovieAdded,
+    MovieNightScheduled,
+    LoanReminder,
+}
+
+impl DiscordEvent {
+    pub const ALL: [DiscordEvent; 3] = [
+        DiscordEvent::MovieAdded,
+        DiscordEvent::MovieNightScheduled,
+        DiscordEvent::LoanReminder,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiscordEvent::MovieAdded => "movie_added",
+            DiscordEvent::MovieNightScheduled => "movie_night_scheduled",
+            DiscordEvent::LoanReminder => "loan_reminder",
+        }
+    }
+
+    /// Parse a comma-separated event filter. An empty filter enables all events.
+    pub fn parse_filter(filter: &str) -> Vec<DiscordEvent> {
+        if filter.trim().is_empty() {
+            return Self::ALL.to_vec();
+        }
+
+        filter
+            .split(',')
+            .map(|s| s.trim())
+            .filter_map(|s| Self::ALL.into_iter().find(|e| e.as_str() == s))
+            .collect()
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct DiscordMessage {
+    username: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content: Option<String>,
+    embeds: Vec<DiscordEmbed>,
+}
+
+#[derive(Debug, Serialize)]
+struct DiscordEmbed {
+    title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+    color: u32,
+    fields: Vec<DiscordEmbedField>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thumbnail: Option<DiscordEmbedImage>,
+    footer: DiscordEmbedFooter,
+    timestamp: String,
+}
+
+#[derive(Debug, Serialize)]
+struct DiscordEmbedField {
+    name: String,
+    value: String,
+    inline: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct DiscordEmbedImage {
+    url: String,
+}
+
+#[derive(Debug, Serialize)]
+struct DiscordEmbedFooter {
+    text: String,
+}
+
+/// Posts notifications to a Discord channel via an incoming webhook
+pub struct DiscordService {
+    client: reqwest::Client,
+    webhook_url: RwLock<String>,
+    events: RwLock<Vec<DiscordEvent>>,
+    settings: Option<SettingsWatch>,
+}
+
+impl DiscordService {
+    pub fn new(webhook_url: String, events: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            webhook_url: RwLock::new(webhook_url),
+            events: RwLock::new(DiscordEvent::parse_filter(events)),
+            settings: None,
+        }
+    }
+
+    /// Take webhook URL and event filter from the `discord_webhook_url` and
+    /// `discord_events` settings on every call; the values given to `new`
+    /// are used while a setting is empty
+    pub fn with_settings(mut self, settings: SettingsWatch) -> Self {
+        self.settings = Some(settings);
+        self
+    }
+
+    fn setting(&self, key: SettingKey) -> Option<String> {
+        let settings = self.settings.as_ref()?;
+        settings.borrow().get(key).map(String::from)
+    }
+
+    /// Update the fallback webhook URL at runtime
+    pub fn set_webhook_url(&self, webhook_url: String) {
+        if let Ok(mut url) = self.webhook_url.write() {
+            *url = webhook_url;
+        }
+    }
+
+    /// Update the fallback event filter at runtime
+    pub fn set_events(&self, events: &str) {
+        if let Ok(mut current) = self.events.write() {
+            *current = DiscordEvent::parse_filter(events);
+        }
+    }
+
+    /// Whether a webhook URL is set, regardless of the event filter
+    pub fn is_configured(&self) -> bool {
+        !self.current_webhook_url().is_empty()
+    }
+
+    /// Whether a webhook is configured and the event passes the filter
+    pub fn is_enabled(&self, event: DiscordEvent) -> bool {
+        if self.current_webhook_url().is_empty() {
+            return false;
+        }
+        match self.setting(SettingKey::DiscordEvents) {
+            Some(filter) => DiscordEvent::parse_filter(&filter).contains(&event),
+            None => self
+                .events
+                .read()
+                .map(|e| e.contains(&event))
+                .unwrap_or(false),
+        }
+    }
+
+    fn current_webhook_url(&self) -> String {
+        self.setting(SettingKey::DiscordWebhookUrl)
+            .unwrap_or_else(|| {
+                self.webhook_url
+                    .read()
+                    .map(|u| u.clone())
+                    .unwrap_or_default()
+            })
+    }
+
+    fn get_webhook_url(&self) -> Result<String> {
+        let url = self.current_webhook_url();
+        if url.is_empty() {
+            return Err(Error::ExternalApi(
+                "Discord webhook URL not configured. Please set it in Settings.".to_string(),
+            ));
+        }
+        Ok(url)
+    }
+
+    /// Announce a newly added movie. Uses the stored poster as thumbnail if present,
+    /// otherwise `poster_url` (e.g. a TMDB image URL).
+    pub async fn notify_movie_added(
+        &self,
+        movie: &Movie,
+        poster_url: Option<String>,
+    ) -> Result<()> {
+        if !self.is_enabled(DiscordEvent::MovieAdded) {
+            return Ok(());
+        }
+
+        let embed = movie_embed(movie, poster_url);
+        let message = DiscordMessage {
+            username: "My Movies",
+            content: Some(format!("🎬 Neu in der Sammlung: **{}**", movie.title)),
+            embeds: vec![embed],
+        };
+
+        self.send(message, movie.poster_data.clone()).await
+    }
+
+    /// Announce a scheduled movie night for the given movie
+    pub async fn notify_movie_night(
+        &self,
+        movie: &Movie,
+        scheduled_for: DateTime<Utc>,
+        poster_url: Option<String>,
+    ) -> Result<()> {
+        if !self.is_enabled(DiscordEvent::MovieNightScheduled) {
+            return Ok(());
+        }
+
+        let mut embed = movie_embed(movie, poster_url);
+        embed.fields.insert(
+            0,
+            DiscordEmbedField {
+                name: "Termin".to_string(),
+                // Discord renders <t:unix:F> in each viewer's local timezone
+                value: format!("<t:{}:F>", scheduled_for.timestamp()),
+                inline: false,
+            },
+        );
+
+        let message = DiscordMessage {
+            username: "My Movies",
+            content: Some(format!("🍿 Filmabend geplant: **{}**", movie.title)),
+            embeds: vec![embed],
+        };
+
+        self.send(message, movie.poster_data.clone()).await
+    }
+
+    /// Remind that a lent movie is due soon or overdue
+    pub async fn notify_loan_reminder(&self, reminder: &LoanReminder) -> Result<()> {
+        if !self.is_enabled(DiscordEvent::LoanReminder) {
+            return Ok(());
+        }
+
+        let content = match reminder.kind {
+            LoanReminderKind::DueSoon => format!(
+                "📅 **{}** ist bei {} und soll am {} zurückkommen.",
+                reminder.title,
+                reminder.lent_to,
+                reminder.lent_due.format("%d.%m.%Y")
+            ),
+            LoanReminderKind::Overdue => format!(
+                "⏰ **{}** ist bei {} seit {} überfällig.",
+                reminder.title,
+                reminder.lent_to,
+                reminder.lent_due.format("%d.%m.%Y")
+            ),
+        };
+        let message = DiscordMessage {
+            username: "My Movies",
+            content: Some(content),
+            embeds: Vec::new(),
+        };
+
+        self.send(message, None).await
+    }
+
+    /// Send a test message to verify the webhook URL
+    pub async fn send_test(&self) -> Result<()> {
+        let message = DiscordMessage {
+            username: "My Movies",
+            content: Some("✅ Discord-Benachrichtigungen sind eingerichtet.".to_string()),
+            embeds: Vec::new(),
+        };
+
+        self.send(message, None).await
+    }
+
+    async fn send(&self, mut message: DiscordMessage, poster: Option<Vec<u8>>) -> Result<()> {
+        let url = self.get_webhook_url()?;
+
+        let request = match poster {
+            Some(data) => {
+                // Attach the poster and reference it from the embed
+                for embed in &mut message.embeds {
+                    embed.thumbnail = Some(DiscordEmbedImage {
+                        url: format!("attachment://{}", POSTER_FILENAME),
+                    });
+                }
+
+                let payload = serde_json::to_string(&message)
+                    .map_err(|e| Error::Internal(format!("Failed to encode payload: {}", e)))?;
+                let part = reqwest::multipart::Part::bytes(data)
+                    .file_name(POSTER_FILENAME)
+                    .mime_str("image/jpeg")
+                    .map_err(|e| Error::Internal(e.to_string()))?;
+                let form = reqwest::multipart::Form::new()
+                    .text("payload_json", payload)
+                    .part("files[0]", part);
+
+                self.client.post(&url).multipart(form)
+            }
+            None => self.client.post(&url).json(&message),
+        };
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| Error::ExternalApi(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(Error::ExternalApi(format!(
+                "Discord webhook returned {}: {}",
+                status, body
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+fn movie_embed(movie: &Movie, poster_url: Option<String>) -> DiscordEmbed {
+    let mut fields = Vec::new();
+    if let Some(year) = movie.production_year {
+        fields.push(DiscordEmbedField {
+            name: "Jahr".to_string(),
+            value: year.to_string(),
+            inline: true,
+        });
+    }
+    if let Some(disc_type) = &movie.disc_type {
+        fields.push(DiscordEmbedField {
+            name: "Medium".to_string(),
+            value: disc_type.clone(),
+            inline: true,
+        });
+    }
+    if let Some(running_time) = movie.running_time {
+        fields.push(DiscordEmbedField {
+            name: "Laufzeit".to_string(),
+            value: format!("{} min", running_time),
+            inline: true,
+        });
+    }
+    if let Some(genres) = movie.genres.as_ref().filter(|g| !g.is_empty()) {
+        fields.push(DiscordEmbedField {
+            name: "Genres".to_string(),
+            value: genres.clone(),
+            inline: false,
+        });
+    }
+
+    DiscordEmbed {
+        title: movie.title.clone(),
+        description: movie
+            .description
+            .as_deref()
+            .map(|d| truncate(d, MAX_DESCRIPTION_CHARS)),
+        url: movie
+            .tmdb_id
+            .map(|id| format!("https://www.themoviedb.org/movie/{}", id)),
+        color: EMBED_COLOR,
+        fields,
+        thumbnail: poster_url.map(|url| DiscordEmbedImage { url }),
+        footer: DiscordEmbedFooter {
+            text: "My Movies".to_string(),
+        },
+        timestamp: Utc::now().to_rfc3339(),
+    }
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let truncated: String = text.chars().take(max_chars).collect();
+    format!("{}…", truncated.trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use chrono::TimeZone;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use super::*;
+    use crate::models::CreateMovie;
+    use crate::services::MovieService;
+    use crate::test_helpers::{create_test_db_with_users, fixtures};
+
+    /// Stand-in for a Discord webhook: answers every request with `status` and
+    /// `body` and collects the request bodies
+    async fn webhook(status: u16, body: &'static str) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!(
+            "http://{}/api/webhooks/1/token",
+            listener.local_addr().unwrap()
+        );
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let requests = received.clone();
+
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                // Headers, then as many bytes as Content-Length announces
+                let mut request = Vec::new();
+                let mut buf = [0u8; 4096];
+                loop {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    if n == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buf[..n]);
+                    let Some(end) = request.windows(4).position(|w| w == b"\r\n\r\n") else {
+                        continue;
+                    };
+                    let headers = String::from_utf8_lossy(&request[..end]).to_lowercase();
+                    let length = headers
+                        .lines()
+                        .find_map(|l| l.strip_prefix("content-length:"))
+                        .and_then(|v| v.trim().parse::<usize>().ok())
+                        .unwrap_or(0);
+                    if request.len() >= end + 4 + length {
+                        let body = String::from_utf8_lossy(&request[end + 4..]).into_owned();
+                        requests.lock().unwrap().push(body);
+                        break;
+                    }
+                }
+
+                let response = format!(
+                    "HTTP/1.1 {} Test\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        (url, received)
+    }
+
+    async fn movie() -> Movie {
+        let pool = create_test_db_with_users().await;
+        MovieService::new(pool)
+            .create(
+                fixtures::test_user_id(),
+                CreateMovie {
+                    barcode: None,
+                    tmdb_id: Some(105),
+                    title: "Zurück in die Zukunft".to_string(),
+                    original_title: None,
+                    disc_type: Some("Blu-ray".to_string()),
+                    production_year: Some(1985),
+                },
+            )
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_filter() {
+        assert_eq!(DiscordEvent::parse_filter(" "), DiscordEvent::ALL.to_vec());
+        assert_eq!(
+            DiscordEvent::parse_filter("movie_night_scheduled, unknown"),
+            vec![DiscordEvent::MovieNightScheduled]
+        );
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("Kurz", 10), "Kurz");
+        assert_eq!(truncate("Großer Film ", 6), "Großer…");
+        assert_eq!(truncate("Ein langer Text", 4), "Ein…");
+    }
+
+    #[tokio::test]
+    async fn test_movie_night_payload() {
+        let (url, received) = webhook(204, "").await;
+        let service = DiscordService::new(url, "");
+        let movie = movie().await;
+        let scheduled_for = Utc.with_ymd_and_hms(2026, 12, 24, 19, 30, 0).unwrap();
+
+        service
+            .notify_movie_night(
+                &movie,
+                scheduled_for,
+                Some("https://image.tmdb.org/t/p/w185/poster.jpg".to_string()),
+            )
+            .await
+            .unwrap();
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        let payload: serde_json::Value = serde_json::from_str(&received[0]).unwrap();
+        assert_eq!(payload["username"], "My Movies");
+        assert_eq!(
+            payload["content"],
+            "🍿 Filmabend geplant: **Zurück in die Zukunft**"
+        );
+        let embed = &payload["embeds"][0];
+        assert_eq!(embed["title"], "Zurück in die Zukunft");
+        assert_eq!(embed["url"], "https://www.themoviedb.org/movie/105");
+        assert_eq!(embed["color"], EMBED_COLOR);
+        assert_eq!(
+            embed["thumbnail"]["url"],
+            "https://image.tmdb.org/t/p/w185/poster.jpg"
+        );
+        // The date comes first, then the movie's details
+        assert_eq!(embed["fields"][0]["name"], "Termin");
+        assert_eq!(
+            embed["fields"][0]["value"],
+            format!("<t:{}:F>", scheduled_for.timestamp())
+        );
+        assert_eq!(embed["fields"][1]["value"], "1985");
+        assert_eq!(embed["fields"][2]["value"], "Blu-ray");
+    }
+
+    #[tokio::test]
+    async fn test_filtered_events_are_not_sent() {
+        let (url, received) = webhook(204, "").await;
+        let service = DiscordService::new(url, "loan_reminder");
+        let movie = movie().await;
+
+        assert!(!service.is_enabled(DiscordEvent::MovieNightScheduled));
+        service
+            .notify_movie_night(&movie, Utc::now(), None)
+            .await
+            .unwrap();
+        service.notify_movie_added(&movie, None).await.unwrap();
+        assert!(received.lock().unwrap().is_empty());
+
+        let unconfigured = DiscordService::new(String::new(), "");
+        assert!(!unconfigured.is_configured());
+        assert!(!unconfigured.is_enabled(DiscordEvent::MovieAdded));
+    }
+
+    #[tokio::test]
+    async fn test_webhook_errors() {
+        let (url, _) = webhook(404, r#"{"message": "Unknown Webhook", "code": 10015}"#).await;
+        let service = DiscordService::new(url, "");
+
+        match service.send_test().await {
+            Err(Error::ExternalApi(message)) => {
+                assert!(message.contains("404"), "{message}");
+                assert!(message.contains("Unknown Webhook"), "{message}");
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+
+        // No webhook at all
+        let service = DiscordService::new(String::new(), "");
+        assert!(matches!(
+            service.send_test().await,
+            Err(Error::ExternalApi(_))
+        ));
+    }
+}
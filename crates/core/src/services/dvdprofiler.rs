@@ -0,0 +1,522 @@
+//! Reader for the `collection.xml` export of DVD Profiler. The export is
+//! plain XML (usually windows-1252, sometimes UTF-8) without namespaces or a
+//! DTD, so a small element tree parser is enough.
+
+use crate::error::{Error, Result};
+use crate::services::import::CsvMovieRecord;
+
+/// Nesting deeper than this isn't a DVD Profiler export
+const MAX_DEPTH: usize = 64;
+
+/// An XML element with its attributes, text and child elements
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Element {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub text: String,
+    pub children: Vec<Element>,
+}
+
+impl Element {
+    pub fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    pub fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> + 'a {
+        self.children.iter().filter(move |c| c.name == name)
+    }
+
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Trimmed text of the child element `name`, `None` if missing or empty
+    pub fn text_of(&self, name: &str) -> Option<String> {
+        let text = self.child(name)?.text.trim();
+        (!text.is_empty()).then(|| text.to_string())
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.text_of(name)
+            .is_some_and(|v| v.eq_ignore_ascii_case("true"))
+    }
+}
+
+/// Windows-1252 characters of the bytes 0x80 to 0x9F; the other bytes are
+/// the Unicode code points of the same value
+const CP1252_HIGH: [char; 32] = [
+    '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8d}', 'Ž', '\u{8f}',
+    '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9d}', 'ž', 'Ÿ',
+];
+
+/// The export as text: UTF-8 if it is valid UTF-8, windows-1252 otherwise
+pub fn decode(data: &[u8]) -> String {
+    let data = data.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(data);
+    match std::str::from_utf8(data) {
+        Ok(text) => text.to_string(),
+        Err(_) => data
+            .iter()
+            .map(|&b| match b {
+                0x80..=0x9F => CP1252_HIGH[(b - 0x80) as usize],
+                _ => b as char,
+            })
+            .collect(),
+    }
+}
+
+/// Replace the predefined entities and character references
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find(';').filter(|&end| end <= 10) else {
+            out.push('&');
+            rest = &rest[1..];
+            continue;
+        };
+        let entity = &rest[1..end];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(|dec| dec.parse::<u32>()))
+                .and_then(|code| code.ok())
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            // Unknown entity: keep it as written
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn error(&self, message: &str) -> Error {
+        let line = self.input[..self.pos].matches('\n').count() + 1;
+        Error::Validation(format!("Invalid XML on line {}: {}", line, message))
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Move past the next `end`
+    fn skip_past(&mut self, end: &str) -> Result<&'a str> {
+        let rest = self.rest();
+        let Some(index) = rest.find(end) else {
+            return Err(self.error(&format!("missing '{}'", end)));
+        };
+        self.pos += index + end.len();
+        Ok(&rest[..index])
+    }
+
+    fn name(&mut self) -> Result<&'a str> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '/' | '>' | '='))
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(self.error("expected a name"));
+        }
+        self.pos += len;
+        Ok(&rest[..len])
+    }
+
+    /// Declarations, comments and processing instructions before the root
+    fn skip_prolog(&mut self) -> Result<()> {
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if rest.starts_with("<!") {
+                self.skip_past(">")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn element(&mut self, depth: usize) -> Result<Element> {
+        if depth > MAX_DEPTH {
+            return Err(self.error("elements nested too deeply"));
+        }
+        if !self.rest().starts_with('<') {
+            return Err(self.error("expected an element"));
+        }
+        self.pos += 1;
+        let mut element = Element {
+            name: self.name()?.to_string(),
+            ..Default::default()
+        };
+
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.starts_with("/>") {
+                self.pos += 2;
+                return Ok(element);
+            }
+            if rest.starts_with('>') {
+                self.pos += 1;
+                break;
+            }
+            let name = self.name()?.to_string();
+            self.skip_whitespace();
+            if !self.rest().starts_with('=') {
+                return Err(self.error(&format!("attribute {} without value", name)));
+            }
+            self.pos += 1;
+            self.skip_whitespace();
+            let quote = match self.rest().chars().next() {
+                Some(q @ ('"' | '\'')) => q,
+                _ => return Err(self.error("expected a quoted attribute value")),
+            };
+            self.pos += 1;
+            let value = self.skip_past(if quote == '"' { "\"" } else { "'" })?;
+            element.attributes.push((name, unescape(value)));
+        }
+
+        loop {
+            let rest = self.rest();
+            if rest.is_empty() {
+                return Err(self.error(&format!("element {} is not closed", element.name)));
+            } else if rest.starts_with("</") {
+                self.pos += 2;
+                let name = self.name()?;
+                if name != element.name {
+                    return Err(
+                        self.error(&format!("expected </{}>, found </{}>", element.name, name))
+                    );
+                }
+                self.skip_past(">")?;
+                return Ok(element);
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if rest.starts_with("<![CDATA[") {
+                self.pos += "<![CDATA[".len();
+                let data = self.skip_past("]]>")?;
+                element.text.push_str(data);
+            } else if rest.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if rest.starts_with('<') {
+                let child = self.element(depth + 1)?;
+                element.children.push(child);
+            } else {
+                let len = rest.find('<').unwrap_or(rest.len());
+                element.text.push_str(&unescape(&rest[..len]));
+                self.pos += len;
+            }
+        }
+    }
+}
+
+/// Parse a whole document into its root element
+pub fn parse_xml(input: &str) -> Result<Element> {
+    let mut parser = Parser { input, pos: 0 };
+    parser.skip_prolog()?;
+    parser.element(0)
+}
+
+/// One `<DVD>` of the export
+#[derive(Debug)]
+pub struct DvdProfilerEntry {
+    /// DVD Profiler's own ID (UPC plus a suffix), used by box sets
+    pub profile_id: Option<String>,
+    pub record: CsvMovieRecord,
+    /// Profile ID of the box set containing this disc
+    pub parent: Option<String>,
+    /// The entry is a box set with contents of its own
+    pub is_box_set: bool,
+}
+
+fn join<'a>(values: impl Iterator<Item = String> + 'a, separator: &str) -> Option<String> {
+    let values: Vec<String> = values.filter(|v| !v.is_empty()).collect();
+    (!values.is_empty()).then(|| values.join(separator))
+}
+
+fn person(element: &Element) -> String {
+    ["FirstName", "MiddleName", "LastName"]
+        .iter()
+        .filter_map(|a| element.attr(a))
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn disc_type(dvd: &Element) -> Option<String> {
+    let media = dvd.child("MediaTypes")?;
+    let disc_type = if media.flag("UltraHD") {
+        "4K UHD"
+    } else if media.flag("BluRay") {
+        "Blu-ray"
+    } else if media.flag("HDDVD") {
+        "HD DVD"
+    } else if media.flag("DVD") {
+        "DVD"
+    } else {
+        return None;
+    };
+    Some(disc_type.to_string())
+}
+
+fn entry(dvd: &Element) -> DvdProfilerEntry {
+    let list = |parent: &str, child: &'static str| {
+        dvd.child(parent)
+            .map(|p| join(p.children(child).map(|c| c.text.trim().to_string()), ", "))
+            .unwrap_or_default()
+    };
+    let format = dvd.child("Format");
+    let purchase = dvd.child("PurchaseInfo");
+    let loan = dvd.child("LoanInfo").filter(|loan| loan.flag("Loaned"));
+    let box_set = dvd.child("BoxSet");
+
+    let record = CsvMovieRecord {
+        collection_number: dvd.text_of("CollectionNumber").filter(|n| n != "0"),
+        title: dvd.text_of("Title"),
+        original_title: dvd.text_of("OriginalTitle"),
+        sort_title: dvd.text_of("SortTitle"),
+        barcode: dvd
+            .text_of("UPC")
+            .map(|upc| upc.chars().filter(char::is_ascii_digit).collect::<String>())
+            .filter(|upc| !upc.is_empty()),
+        disc_type: disc_type(dvd),
+        production_year: dvd.text_of("ProductionYear"),
+        release_date: dvd.text_of("Released"),
+        running_time: dvd.text_of("RunningTime").filter(|t| t != "0"),
+        rating: dvd.text_of("Rating"),
+        description: dvd.text_of("Overview"),
+        production_countries: dvd.text_of("CountryOfOrigin"),
+        genres: list("Genres", "Genre"),
+        studios: list("Studios", "Studio"),
+        subtitles: list("Subtitles", "Subtitle"),
+        region_codes: dvd
+            .child("Regions")
+            .and_then(|r| join(r.children("Region").map(|c| c.text.trim().to_string()), ",")),
+        aspect_ratio: format.and_then(|f| f.text_of("AspectRatio")),
+        video_standard: format.and_then(|f| f.text_of("VideoStandard")),
+        audio_tracks: dvd.child("Audio").and_then(|audio| {
+            join(
+                audio.children("AudioTrack").map(|track| {
+                    match (track.text_of("AudioContent"), track.text_of("AudioFormat")) {
+                        (Some(language), Some(format)) => format!("{} ({})", language, format),
+                        (language, format) => language.or(format).unwrap_or_default(),
+                    }
+                }),
+                ", ",
+            )
+        }),
+        actors: dvd
+            .child("Actors")
+            .and_then(|actors| join(actors.children("Actor").map(person), ", ")),
+        director: dvd.child("Credits").and_then(|credits| {
+            join(
+                credits
+                    .children("Credit")
+                    .filter(|c| c.attr("CreditSubtype") == Some("Director"))
+                    .map(person),
+                ", ",
+            )
+        }),
+        discs: dvd
+            .child("Discs")
+            .map(|discs| discs.children("Disc").count())
+            .filter(|&count| count > 0)
+            .map(|count| count.to_string()),
+        cover_type: dvd.text_of("CaseType"),
+        slip_cover: dvd.flag("CaseSlipCover").then(|| "true".to_string()),
+        edition: dvd.text_of("DistTrait"),
+        status: dvd.text_of("CollectionType"),
+        tags: dvd.child("Tags").and_then(|tags| {
+            join(
+                tags.children("Tag").map(|tag| {
+                    tag.attr("FullName")
+                        .or(tag.attr("Name"))
+                        .unwrap_or_default()
+                        .trim()
+                        .to_string()
+                }),
+                ", ",
+            )
+        }),
+        watched: dvd
+            .child("Events")
+            .is_some_and(|events| {
+                events
+                    .children("Event")
+                    .any(|e| e.text_of("EventType").as_deref() == Some("Watched"))
+            })
+            .then(|| "true".to_string()),
+        purchase_date: purchase.and_then(|p| p.text_of("PurchaseDate")),
+        purchase_place: purchase.and_then(|p| p.text_of("PurchasePlace")),
+        price: purchase
+            .and_then(|p| p.text_of("PurchasePrice"))
+            .filter(|p| !p.trim_start_matches(['0', '.', ',']).is_empty()),
+        currency: purchase
+            .and_then(|p| p.child("PurchasePrice"))
+            .and_then(|p| p.attr("DenominationType"))
+            .map(str::to_string),
+        lent_to: loan
+            .and_then(|l| l.child("User"))
+            .map(person)
+            .filter(|name| !name.is_empty()),
+        lent_due: loan.and_then(|l| l.text_of("Due")),
+        notes: dvd.text_of("Notes"),
+        ..Default::default()
+    };
+
+    DvdProfilerEntry {
+        profile_id: dvd.text_of("ID"),
+        record,
+        parent: box_set.and_then(|b| b.text_of("Parent")),
+        is_box_set: box_set
+            .and_then(|b| b.child("Contents"))
+            .is_some_and(|c| c.children("Content").next().is_some()),
+    }
+}
+
+/// The `<DVD>` entries of a `collection.xml` export
+pub fn parse_collection(data: &[u8]) -> Result<Vec<DvdProfilerEntry>> {
+    let root = parse_xml(&decode(data))?;
+    if root.name != "Collection" {
+        return Err(Error::Validation(format!(
+            "Not a DVD Profiler collection export (root element <{}>)",
+            root.name
+        )));
+    }
+    Ok(root.children("DVD").map(entry).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPORT: &str = r#"<?xml version="1.0" encoding="windows-1252"?>
+<!-- Exported by DVD Profiler -->
+<Collection>
+  <DVD>
+    <ID>0883929130925.5</ID>
+    <MediaTypes><DVD>False</DVD><HDDVD>False</HDDVD><BluRay>True</BluRay></MediaTypes>
+    <UPC>8-83929-13092-5</UPC>
+    <CollectionNumber>12</CollectionNumber>
+    <CollectionType IsPartOfOwnedCollection="True">Owned</CollectionType>
+    <Title>Heat</Title>
+    <DistTrait>Director's Definitive Edition</DistTrait>
+    <ProductionYear>1995</ProductionYear>
+    <RunningTime>171</RunningTime>
+    <Genres><Genre>Action</Genre><Genre>Crime</Genre></Genres>
+    <Regions><Region>B</Region></Regions>
+    <Format><AspectRatio>2.40</AspectRatio><VideoStandard>PAL</VideoStandard></Format>
+    <Audio>
+      <AudioTrack><AudioContent>English</AudioContent><AudioFormat>DTS-HD 5.1</AudioFormat></AudioTrack>
+    </Audio>
+    <Actors>
+      <Actor FirstName="Al" MiddleName="" LastName="Pacino" Role="Vincent Hanna"/>
+      <Actor FirstName="Robert" MiddleName="" LastName="De Niro"/>
+    </Actors>
+    <Credits><Credit FirstName="Michael" LastName="Mann" CreditSubtype="Director"/></Credits>
+    <Overview>Cops &amp; robbers in L.A. &#8211; <![CDATA[<the> original]]></Overview>
+    <PurchaseInfo>
+      <PurchasePrice DenominationType="EUR" FormattedValue="9,99 €">9.99</PurchasePrice>
+      <PurchasePlace>Müller</PurchasePlace>
+      <PurchaseDate>2020-05-04</PurchaseDate>
+    </PurchaseInfo>
+    <LoanInfo><Loaned>True</Loaned><Due>2026-03-01</Due><User FirstName="Anna" LastName="K."/></LoanInfo>
+    <Events><Event><EventType>Watched</EventType></Event></Events>
+  </DVD>
+  <DVD>
+    <ID>5051890011234.4</ID>
+    <CollectionType>Wish List</CollectionType>
+    <Title>Trilogy</Title>
+    <BoxSet><Parent/><Contents><Content>5051890011234.4a</Content></Contents></BoxSet>
+  </DVD>
+</Collection>
+"#;
+
+    #[test]
+    fn test_parse_collection() {
+        let entries = parse_collection(EXPORT.as_bytes()).unwrap();
+        assert_eq!(entries.len(), 2);
+
+        let heat = &entries[0];
+        assert_eq!(heat.profile_id.as_deref(), Some("0883929130925.5"));
+        assert!(!heat.is_box_set);
+        let record = &heat.record;
+        assert_eq!(record.title.as_deref(), Some("Heat"));
+        assert_eq!(record.barcode.as_deref(), Some("883929130925"));
+        assert_eq!(record.disc_type.as_deref(), Some("Blu-ray"));
+        assert_eq!(record.genres.as_deref(), Some("Action, Crime"));
+        assert_eq!(record.actors.as_deref(), Some("Al Pacino, Robert De Niro"));
+        assert_eq!(record.director.as_deref(), Some("Michael Mann"));
+        assert_eq!(record.audio_tracks.as_deref(), Some("English (DTS-HD 5.1)"));
+        assert_eq!(
+            record.description.as_deref(),
+            Some("Cops & robbers in L.A. – <the> original")
+        );
+        assert_eq!(record.price.as_deref(), Some("9.99"));
+        assert_eq!(record.currency.as_deref(), Some("EUR"));
+        assert_eq!(record.purchase_place.as_deref(), Some("Müller"));
+        assert_eq!(record.lent_to.as_deref(), Some("Anna K."));
+        assert_eq!(record.lent_due.as_deref(), Some("2026-03-01"));
+        assert_eq!(record.watched.as_deref(), Some("true"));
+
+        let box_set = &entries[1];
+        assert!(box_set.is_box_set);
+        assert_eq!(box_set.parent, None);
+        assert_eq!(box_set.record.status.as_deref(), Some("Wish List"));
+        assert_eq!(box_set.record.disc_type, None);
+    }
+
+    #[test]
+    fn test_decode_windows_1252() {
+        assert_eq!(decode(b"M\xfcller \x80 9"), "Müller € 9");
+        assert_eq!(decode("Müller".as_bytes()), "Müller");
+        assert_eq!(decode(b"\xEF\xBB\xBF<a/>"), "<a/>");
+    }
+
+    #[test]
+    fn test_invalid_xml() {
+        for input in ["<Collection><DVD></Collection>", "<Collection>", "no xml"] {
+            assert!(matches!(
+                parse_collection(input.as_bytes()),
+                Err(Error::Validation(_))
+            ));
+        }
+        assert!(parse_collection(b"<Other/>").is_err());
+        assert!(parse_collection("<a>".repeat(100).as_bytes()).is_err());
+        assert_eq!(unescape("a &unknown; &#x41;&#66;"), "a &unknown; AB");
+    }
+}
@@ -1,10 +1,32 @@
-use crate::error::{Error, Result};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, RwLock};
+
+use chrono::Utc;
 use serde::Deserialize;
+use serde_json::json;
+use sha2::{Digest, Sha256};
 use tracing::{debug, warn};
 
-/// Service for looking up product information from EAN/barcode
+use crate::error::{Error, Result};
+use crate::services::blob_store::{hex, hmac_sha256};
+
+pub type LookupFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<Option<EanLookupResult>>> + Send + 'a>>;
+
+/// A product database that can be asked for a barcode
+pub trait BarcodeProvider: Send + Sync {
+    /// Name used in the `barcode_providers` setting and reported as `source`
+    fn name(&self) -> &'static str;
+
+    /// Look up a cleaned (digits only) barcode; `None` if the product is unknown
+    fn lookup<'a>(&'a self, barcode: &'a str) -> LookupFuture<'a>;
+}
+
+/// Service for looking up product information from EAN/barcode.
+/// Asks the configured providers in order until one knows a movie.
 pub struct EanService {
-    client: reqwest::Client,
+    providers: RwLock<Vec<Arc<dyn BarcodeProvider>>>,
 }
 
 #[derive(Debug, Clone)]
@@ -13,6 +35,59 @@ pub struct EanLookupResult {
     pub original_barcode: String,
     pub vendor: Option<String>,
     pub category: Option<String>,
+    /// Provider the title comes from
+    pub source: &'static str,
+    /// DVD, Blu-ray or 4K UHD when the title or category says so
+    pub disc_type: Option<&'static str>,
+}
+
+impl EanLookupResult {
+    /// Whether the product category says this is a movie (and not e.g. a soundtrack)
+    fn is_media(&self) -> bool {
+        let category = self.category.as_deref().unwrap_or_default().to_lowercase();
+        ["movie", "film", "dvd", "blu-ray", "bluray", "video", "uhd"]
+            .iter()
+            .any(|word| category.contains(word))
+    }
+}
+
+/// Combine the results of several providers: the title comes from the first
+/// result that is a movie (or the first result), missing vendor and category
+/// are taken from the others
+fn merge_results(results: Vec<EanLookupResult>) -> Option<EanLookupResult> {
+    let best = results
+        .iter()
+        .position(EanLookupResult::is_media)
+        .unwrap_or(0);
+    let mut merged = results.get(best)?.clone();
+    for other in &results {
+        if merged.vendor.is_none() {
+            merged.vendor = other.vendor.clone();
+        }
+        if merged.category.is_none() {
+            merged.category = other.category.clone();
+        }
+    }
+    Some(merged)
+}
+
+/// Disc type named in a product title or category, before the title is cleaned
+pub fn detect_disc_type(title: &str, category: Option<&str>) -> Option<&'static str> {
+    let text = format!("{} {}", title, category.unwrap_or_default()).to_lowercase();
+    let words: Vec<&str> = text
+        .split(|c: char| !c.is_alphanumeric() && c != '-')
+        .collect();
+    let has = |word: &str| words.contains(&word);
+
+    if has("uhd") || has("4k") {
+        Some("4K UHD")
+    } else if text.contains("blu-ray") || text.contains("bluray") || has("bd") {
+        Some("Blu-ray")
+    } else if has("dvd") {
+        Some("DVD")
+    } else {
+        None
+    }
 }
 
 // UPCitemdb API response structures
@@ -29,43 +104,21 @@ struct UpcItem {
     category: Option<String>,
 }
 
-impl EanService {
+/// UPCitemdb (good for movies/media). Free tier: 100 requests/day
+pub struct UpcItemDbProvider {
+    client: reqwest::Client,
+}
+
+impl UpcItemDbProvider {
+    pub const NAME: &'static str = "upcitemdb";
+
     pub fn new() -> Self {
         Self {
             client: reqwest::Client::new(),
         }
     }
 
-    /// Look up a product by EAN/barcode
-    pub async fn lookup(&self, barcode: &str) -> Result<Option<EanLookupResult>> {
-        // Clean the barcode (remove spaces, dashes)
-        let clean_barcode: String = barcode.chars().filter(|c| c.is_ascii_digit()).collect();
-
-        if clean_barcode.is_empty() {
-            return Err(Error::Validation("Invalid barcode format".into()));
-        }
-
-        debug!("Looking up barcode: {}", clean_barcode);
-
-        // Try UPCitemdb first (better for movies/media)
-        if let Some(result) = self.lookup_upcitemdb(&clean_barcode).await? {
-            debug!("Found in UPCitemdb: {:?}", result.title);
-            return Ok(Some(result));
-        }
-
-        // Fallback to OpenGTINDB
-        if let Some(result) = self.lookup_opengtindb(&clean_barcode).await? {
-            debug!("Found in OpenGTINDB: {:?}", result.title);
-            return Ok(Some(result));
-        }
-
-        debug!("Barcode not found in any database: {}", clean_barcode);
-        Ok(None)
-    }
-
-    /// Look up using UPCitemdb API (good for movies/media)
-    /// Free tier: 100 requests/day
-    async fn lookup_upcitemdb(&self, barcode: &str) -> Result<Option<EanLookupResult>> {
+    async fn fetch(&self, barcode: &str) -> Result<Option<EanLookupResult>> {
         let url = format!(
             "https://api.upcitemdb.com/prod/trial/lookup?upc={}",
             barcode
@@ -108,15 +161,47 @@ impl EanService {
         };
 
         Ok(Some(EanLookupResult {
-            title: Self::clean_title(&title),
+            title,
             original_barcode: barcode.to_string(),
             vendor: item.brand,
             category: item.category,
+            source: Self::NAME,
+            disc_type: None,
         }))
     }
+}
+
+impl Default for UpcItemDbProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BarcodeProvider for UpcItemDbProvider {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn lookup<'a>(&'a self, barcode: &'a str) -> LookupFuture<'a> {
+        Box::pin(self.fetch(barcode))
+    }
+}
 
-    /// Fallback to OpenGTINDB
-    async fn lookup_opengtindb(&self, barcode: &str) -> Result<Option<EanLookupResult>> {
+/// OpenGTINDB, strong on German releases
+pub struct OpenGtinDbProvider {
+    client: reqwest::Client,
+}
+
+impl OpenGtinDbProvider {
+    pub const NAME: &'static str = "opengtindb";
+
+    pub fn new() -> Self {
+        Self {
+            client: reqwest::Client::new(),
+        }
+    }
+
+    async fn fetch(&self, barcode: &str) -> Result<Option<EanLookupResult>> {
         let url = format!(
             "https://opengtindb.org/api/v1/?ean={}&cmd=query&queryid=400000000",
             barcode
@@ -146,16 +231,14 @@ impl EanService {
 
         for line in text.lines() {
             if let Some((key, value)) = line.split_once('=') {
+                let value = value.trim();
                 match key.trim() {
-                    "error" => {
-                        if value != "0" {
-                            return Ok(None);
-                        }
-                    }
-                    "detailname" => title = Some(value.trim().to_string()),
-                    "mainname" if title.is_none() => title = Some(value.trim().to_string()),
-                    "vendor" => vendor = Some(value.trim().to_string()),
-                    "subcat" => category = Some(value.trim().to_string()),
+                    // `error=0` is a hit; anything else (e.g. 1 = not found) is a miss
+                    "error" if value != "0" => return Ok(None),
+                    "detailname" => title = Some(value.to_string()),
+                    "mainname" if title.is_none() => title = Some(value.to_string()),
+                    "vendor" => vendor = Some(value.to_string()),
+                    "subcat" => category = Some(value.to_string()),
                     _ => {}
                 }
             }
@@ -163,14 +246,395 @@ impl EanService {
 
         match title {
             Some(t) if !t.is_empty() => Ok(Some(EanLookupResult {
-                title: Self::clean_title(&t),
+                title: t,
                 original_barcode: barcode.to_string(),
                 vendor,
                 category,
+                source: Self::NAME,
+                disc_type: None,
             })),
             _ => Ok(None),
         }
     }
+}
+
+impl Default for OpenGtinDbProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BarcodeProvider for OpenGtinDbProvider {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn lookup<'a>(&'a self, barcode: &'a str) -> LookupFuture<'a> {
+        Box::pin(self.fetch(barcode))
+    }
+}
+
+/// Credentials for the Amazon Product Advertising API (PA-API 5)
+#[derive(Debug, Clone)]
+pub struct AmazonCredentials {
+    pub access_key: String,
+    pub secret_key: String,
+    pub partner_tag: String,
+    /// e.g. `www.amazon.de`
+    pub marketplace: String,
+}
+
+// PA-API SearchItems response structures
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct AmazonSearchResponse {
+    search_result: Option<AmazonSearchResult>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct AmazonSearchResult {
+    #[serde(default)]
+    items: Vec<AmazonItem>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct AmazonItem {
+    item_info: Option<AmazonItemInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct AmazonItemInfo {
+    title: Option<AmazonValue>,
+    by_line_info: Option<AmazonByLine>,
+    classifications: Option<AmazonClassifications>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct AmazonByLine {
+    brand: Option<AmazonValue>,
+    manufacturer: Option<AmazonValue>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct AmazonClassifications {
+    binding: Option<AmazonValue>,
+    product_group: Option<AmazonValue>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct AmazonValue {
+    display_value: String,
+}
+
+/// Amazon Product Advertising API, searched by barcode. Needs an associate
+/// account; only used when its keys are configured
+pub struct AmazonProvider {
+    client: reqwest::Client,
+    credentials: AmazonCredentials,
+    host: String,
+    region: &'static str,
+}
+
+impl AmazonProvider {
+    pub const NAME: &'static str = "amazon";
+    const TARGET: &'static str = "com.amazon.paapi5.v1.ProductAdvertisingAPIv1.SearchItems";
+
+    pub fn new(mut credentials: AmazonCredentials) -> Self {
+        let marketplace = credentials.marketplace.trim().to_lowercase();
+        let marketplace = if marketplace.is_empty() {
+            "www.amazon.de".to_string()
+        } else if marketplace.starts_with("www.") {
+            marketplace
+        } else {
+            format!("www.{}", marketplace)
+        };
+        let domain = marketplace.trim_start_matches("www.amazon.");
+        // PA-API hosts are grouped in three regions
+        let region = match domain {
+            "com" | "ca" | "com.mx" | "com.br" => "us-east-1",
+            "co.jp" | "com.au" | "sg" => "us-west-2",
+            _ => "eu-west-1",
+        };
+        let host = format!("webservices.amazon.{}", domain);
+        credentials.marketplace = marketplace;
+
+        Self {
+            client: reqwest::Client::new(),
+            credentials,
+            host,
+            region,
+        }
+    }
+
+    async fn fetch(&self, barcode: &str) -> Result<Option<EanLookupResult>> {
+        let body = json!({
+            "Keywords": barcode,
+            "SearchIndex": "All",
+            "ItemCount": 1,
+            "PartnerTag": self.credentials.partner_tag,
+            "PartnerType": "Associates",
+            "Marketplace": self.credentials.marketplace,
+            "Resources": ["ItemInfo.Title", "ItemInfo.ByLineInfo", "ItemInfo.Classifications"],
+        })
+        .to_string();
+        let path = "/paapi5/searchitems";
+        let amz_date = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+        let authorization = paapi_authorization(
+            path,
+            &self.host,
+            &body,
+            &amz_date,
+            self.region,
+            &self.credentials.access_key,
+            &self.credentials.secret_key,
+        );
+
+        let response = self
+            .client
+            .post(format!("https://{}{}", self.host, path))
+            .header("content-encoding", "amz-1.0")
+            .header("content-type", "application/json; charset=utf-8")
+            .header("host", &self.host)
+            .header("x-amz-date", amz_date)
+            .header("x-amz-target", Self::TARGET)
+            .header("authorization", authorization)
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| Error::ExternalApi(format!("Amazon request failed: {}", e)))?;
+
+        // No results is a 404 with an error body
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(Error::ExternalApi(format!(
+                "Amazon returned {}",
+                response.status()
+            )));
+        }
+
+        let data: AmazonSearchResponse = response
+            .json()
+            .await
+            .map_err(|e| Error::ExternalApi(e.to_string()))?;
+        let info = match data
+            .search_result
+            .and_then(|r| r.items.into_iter().next())
+            .and_then(|item| item.item_info)
+        {
+            Some(info) => info,
+            None => return Ok(None),
+        };
+        let title = match info.title {
+            Some(t) if !t.display_value.is_empty() => t.display_value,
+            _ => return Ok(None),
+        };
+
+        let vendor = info.by_line_info.and_then(|b| b.brand.or(b.manufacturer));
+        // Binding is the format, e.g. "Blu-ray"
+        let category = info
+            .classifications
+            .and_then(|c| c.binding.or(c.product_group));
+        Ok(Some(EanLookupResult {
+            title,
+            original_barcode: barcode.to_string(),
+            vendor: vendor.map(|v| v.display_value),
+            category: category.map(|c| c.display_value),
+            source: Self::NAME,
+            disc_type: None,
+        }))
+    }
+}
+
+impl BarcodeProvider for AmazonProvider {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn lookup<'a>(&'a self, barcode: &'a str) -> LookupFuture<'a> {
+        Box::pin(self.fetch(barcode))
+    }
+}
+
+/// SigV4 `Authorization` header for a PA-API request
+fn paapi_authorization(
+    path: &str,
+    host: &str,
+    body: &str,
+    amz_date: &str,
+    region: &str,
+    access_key: &str,
+    secret_key: &str,
+) -> String {
+    let date = &amz_date[..8];
+    let signed_headers = "content-encoding;host;x-amz-date;x-amz-target";
+    let canonical_request = format!(
+        "POST\n{}\n\ncontent-encoding:amz-1.0\nhost:{}\nx-amz-date:{}\nx-amz-target:{}\n\n{}\n{}",
+        path,
+        host,
+        amz_date,
+        AmazonProvider::TARGET,
+        signed_headers,
+        hex(&Sha256::digest(body.as_bytes()))
+    );
+    let scope = format!("{}/{}/ProductAdvertisingAPI/aws4_request", date, region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex(&Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let mut key = hmac_sha256(format!("AWS4{}", secret_key).as_bytes(), date.as_bytes());
+    for part in [region, "ProductAdvertisingAPI", "aws4_request"] {
+        key = hmac_sha256(&key, part.as_bytes());
+    }
+    let signature = hex(&hmac_sha256(&key, string_to_sign.as_bytes()));
+
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        access_key, scope, signed_headers, signature
+    )
+}
+
+/// Build the provider chain from the `barcode_providers` setting, a
+/// comma-separated list of provider names. Empty means all providers in the
+/// default order; Amazon is left out while it has no credentials.
+pub fn barcode_providers_from_settings(
+    chain: Option<&str>,
+    amazon: Option<AmazonCredentials>,
+) -> Result<Vec<Arc<dyn BarcodeProvider>>> {
+    let chain = chain.map(str::trim).unwrap_or_default();
+    let names: Vec<String> = if chain.is_empty() {
+        [
+            UpcItemDbProvider::NAME,
+            OpenGtinDbProvider::NAME,
+            AmazonProvider::NAME,
+        ]
+        .map(String::from)
+        .to_vec()
+    } else {
+        chain.split(',').map(|n| n.trim().to_lowercase()).collect()
+    };
+
+    let mut providers: Vec<Arc<dyn BarcodeProvider>> = Vec::new();
+    for name in names.iter().filter(|n| !n.is_empty()) {
+        if providers.iter().any(|p| p.name() == name) {
+            continue;
+        }
+        match name.as_str() {
+            UpcItemDbProvider::NAME => providers.push(Arc::new(UpcItemDbProvider::new())),
+            OpenGtinDbProvider::NAME => providers.push(Arc::new(OpenGtinDbProvider::new())),
+            AmazonProvider::NAME => match &amazon {
+                Some(credentials) => {
+                    providers.push(Arc::new(AmazonProvider::new(credentials.clone())))
+                }
+                None if !chain.is_empty() => {
+                    warn!(
+                        "Amazon barcode lookups need amazon_access_key, amazon_secret_key and amazon_partner_tag"
+                    );
+                }
+                None => {}
+            },
+            _ => {
+                return Err(Error::Validation(format!(
+                    "Unknown barcode provider: {} (known: upcitemdb, opengtindb, amazon)",
+                    name
+                )));
+            }
+        }
+    }
+    Ok(providers)
+}
+
+impl EanService {
+    /// UPCitemdb, then OpenGTINDB
+    pub fn new() -> Self {
+        Self::with_providers(vec![
+            Arc::new(UpcItemDbProvider::new()),
+            Arc::new(OpenGtinDbProvider::new()),
+        ])
+    }
+
+    pub fn with_providers(providers: Vec<Arc<dyn BarcodeProvider>>) -> Self {
+        Self {
+            providers: RwLock::new(providers),
+        }
+    }
+
+    /// Replace the provider chain at runtime (e.g., when settings are changed)
+    pub fn set_providers(&self, providers: Vec<Arc<dyn BarcodeProvider>>) {
+        if let Ok(mut current) = self.providers.write() {
+            *current = providers;
+        }
+    }
+
+    /// Names of the providers in the chain, in lookup order
+    pub fn provider_names(&self) -> Vec<&'static str> {
+        self.providers
+            .read()
+            .map(|p| p.iter().map(|p| p.name()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Look up a product by EAN/barcode. Providers are asked in order until
+    /// one returns a movie; a provider that fails is skipped. Fails only if
+    /// every provider failed.
+    pub async fn lookup(&self, barcode: &str) -> Result<Option<EanLookupResult>> {
+        // Clean the barcode (remove spaces, dashes)
+        let clean_barcode: String = barcode.chars().filter(|c| c.is_ascii_digit()).collect();
+
+        if clean_barcode.is_empty() {
+            return Err(Error::Validation("Invalid barcode format".into()));
+        }
+
+        debug!("Looking up barcode: {}", clean_barcode);
+
+        let providers = self.providers.read().map(|p| p.clone()).unwrap_or_default();
+        let mut results = Vec::new();
+        let mut failures = 0;
+        let mut last_error = None;
+        for provider in &providers {
+            match provider.lookup(&clean_barcode).await {
+                Ok(Some(result)) => {
+                    debug!("Found in {}: {:?}", provider.name(), result.title);
+                    let is_media = result.is_media();
+                    results.push(result);
+                    if is_media {
+                        break;
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    warn!("Barcode lookup via {} failed: {}", provider.name(), e);
+                    failures += 1;
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        match merge_results(results) {
+            Some(mut result) => {
+                result.disc_type = detect_disc_type(&result.title, result.category.as_deref());
+                result.title = Self::clean_title(&result.title);
+                Ok(Some(result))
+            }
+            None => match last_error {
+                Some(e) if failures == providers.len() => Err(e),
+                _ => {
+                    debug!("Barcode not found in any database: {}", clean_barcode);
+                    Ok(None)
+                }
+            },
+        }
+    }
 
     /// Clean up the title for TMDB search
     /// Removes common suffixes like [Blu-ray], (DVD), import info, actor names, etc.
@@ -389,6 +853,179 @@ mod tests {
         );
     }
 
+    fn result(source: &'static str, title: &str, category: Option<&str>) -> EanLookupResult {
+        EanLookupResult {
+            title: title.to_string(),
+            original_barcode: "5050582721478".to_string(),
+            vendor: None,
+            category: category.map(String::from),
+            source,
+            disc_type: None,
+        }
+    }
+
+    /// Answers every barcode the same way
+    struct FixedProvider {
+        name: &'static str,
+        result: Option<EanLookupResult>,
+        fails: bool,
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    impl FixedProvider {
+        fn new(name: &'static str, result: Option<EanLookupResult>, fails: bool) -> Arc<Self> {
+            Arc::new(Self {
+                name,
+                result,
+                fails,
+                calls: Default::default(),
+            })
+        }
+
+        fn calls(&self) -> usize {
+            self.calls.load(std::sync::atomic::Ordering::Relaxed)
+        }
+    }
+
+    impl BarcodeProvider for FixedProvider {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn lookup<'a>(&'a self, _barcode: &'a str) -> LookupFuture<'a> {
+            self.calls
+                .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            Box::pin(async move {
+                if self.fails {
+                    return Err(Error::ExternalApi("offline".to_string()));
+                }
+                Ok(self.result.clone())
+            })
+        }
+    }
+
+    #[test]
+    fn test_merge_results_prefers_movies() {
+        let mut soundtrack = result("upcitemdb", "Inception (Soundtrack)", Some("Music CDs"));
+        soundtrack.vendor = Some("Reprise".to_string());
+        let movie = result("amazon", "Inception [Blu-ray]", Some("Blu-ray"));
+
+        let merged = merge_results(vec![soundtrack, movie]).unwrap();
+        assert_eq!(merged.title, "Inception [Blu-ray]");
+        assert_eq!(merged.source, "amazon");
+        // Filled in from the other result
+        assert_eq!(merged.vendor.as_deref(), Some("Reprise"));
+
+        let unknown = result("opengtindb", "Inception", None);
+        assert_eq!(merge_results(vec![unknown]).unwrap().source, "opengtindb");
+        assert!(merge_results(Vec::new()).is_none());
+    }
+
+    #[test]
+    fn test_barcode_providers_from_settings() {
+        let names = |providers: Vec<Arc<dyn BarcodeProvider>>| {
+            providers.iter().map(|p| p.name()).collect::<Vec<_>>()
+        };
+
+        // Amazon is skipped without credentials
+        let providers = barcode_providers_from_settings(None, None).unwrap();
+        assert_eq!(names(providers), ["upcitemdb", "opengtindb"]);
+
+        let amazon = AmazonCredentials {
+            access_key: "AKIA".to_string(),
+            secret_key: "secret".to_string(),
+            partner_tag: "mymovies-21".to_string(),
+            marketplace: String::new(),
+        };
+        let providers =
+            barcode_providers_from_settings(Some(" Amazon, upcitemdb,amazon "), Some(amazon))
+                .unwrap();
+        assert_eq!(names(providers), ["amazon", "upcitemdb"]);
+
+        assert!(barcode_providers_from_settings(Some("upcitemdb, ebay"), None).is_err());
+    }
+
+    #[test]
+    fn test_amazon_marketplace_region() {
+        let provider = |marketplace: &str| {
+            AmazonProvider::new(AmazonCredentials {
+                access_key: String::new(),
+                secret_key: String::new(),
+                partner_tag: String::new(),
+                marketplace: marketplace.to_string(),
+            })
+        };
+
+        let de = provider("");
+        assert_eq!(de.host, "webservices.amazon.de");
+        assert_eq!(de.region, "eu-west-1");
+        assert_eq!(de.credentials.marketplace, "www.amazon.de");
+
+        let us = provider("amazon.com");
+        assert_eq!(us.host, "webservices.amazon.com");
+        assert_eq!(us.region, "us-east-1");
+        assert_eq!(us.credentials.marketplace, "www.amazon.com");
+
+        assert_eq!(provider("www.amazon.co.jp").region, "us-west-2");
+    }
+
+    #[test]
+    fn test_detect_disc_type() {
+        assert_eq!(detect_disc_type("Dune [Blu-ray]", None), Some("Blu-ray"));
+        assert_eq!(
+            detect_disc_type("Dune", Some("Movies > BluRay")),
+            Some("Blu-ray")
+        );
+        assert_eq!(
+            detect_disc_type("Dune 4K UHD + Blu-ray", None),
+            Some("4K UHD")
+        );
+        assert_eq!(detect_disc_type("Dune (DVD)", Some("Film")), Some("DVD"));
+        assert_eq!(detect_disc_type("Avatar BD", None), Some("Blu-ray"));
+        assert_eq!(detect_disc_type("Dune", Some("Books")), None);
+        assert_eq!(detect_disc_type("Bdsm Dvdr", None), None);
+    }
+
+    #[tokio::test]
+    async fn test_lookup_chain() {
+        let failing = FixedProvider::new("upcitemdb", None, true);
+        let other = FixedProvider::new(
+            "opengtindb",
+            Some(result("opengtindb", "Alien (Soundtrack)", Some("Musik"))),
+            false,
+        );
+        let movie = FixedProvider::new(
+            "amazon",
+            Some(result("amazon", "Alien [Blu-ray]", Some("Blu-ray"))),
+            false,
+        );
+        let last = FixedProvider::new("last", None, false);
+        let chain = |providers: &[&Arc<FixedProvider>]| -> Vec<Arc<dyn BarcodeProvider>> {
+            providers
+                .iter()
+                .map(|&p| p.clone() as Arc<dyn BarcodeProvider>)
+                .collect()
+        };
+        let service = EanService::with_providers(chain(&[&failing, &other, &movie, &last]));
+
+        let found = service.lookup("5050-5827 21478").await.unwrap().unwrap();
+        assert_eq!(found.title, "Alien");
+        assert_eq!(found.source, "amazon");
+        assert_eq!(found.category.as_deref(), Some("Blu-ray"));
+        assert_eq!(found.disc_type, Some("Blu-ray"));
+        // Stops at the first movie
+        assert_eq!(last.calls(), 0);
+        assert_eq!(failing.calls(), 1);
+
+        // Nothing found is not an error as long as one provider answered
+        service.set_providers(chain(&[&failing, &last]));
+        assert!(service.lookup("5050582721478").await.unwrap().is_none());
+
+        service.set_providers(chain(&[&failing]));
+        assert!(service.lookup("5050582721478").await.is_err());
+        assert!(service.lookup("no digits").await.is_err());
+    }
+
     #[test]
     fn test_default_creates_service() {
         let service = EanService::default();
@@ -0,0 +1,378 @@
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::db::DbPool;
+use crate::error::{Error, Result};
+use crate::models::{Household, HouseholdInvitation, HouseholdMember};
+
+const MAX_NAME: usize = 100;
+
+/// Users whose movies the user (bound once) sees with `include_household`,
+/// the user included only if in a household
+pub(crate) const HOUSEHOLD_USERS: &str = "SELECT m.user_id FROM household_members m \
+     JOIN household_members me ON me.household_id = m.household_id WHERE me.user_id = ?";
+
+/// Households: everyone sees the movies of the other members, while
+/// changing a movie stays up to its owner. Users join by accepting an
+/// invitation of the owner.
+pub struct HouseholdService {
+    pool: DbPool,
+}
+
+impl HouseholdService {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// The user's household with its members, if any
+    pub async fn get(&self, user_id: Uuid) -> Result<Option<Household>> {
+        let household = sqlx::query_as::<_, Household>(
+            r#"
+            SELECT h.* FROM households h
+            JOIN household_members m ON m.household_id = h.id
+            WHERE m.user_id = ?
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let Some(mut household) = household else {
+            return Ok(None);
+        };
+        household.members = sqlx::query_as::<_, HouseholdMember>(
+            r#"
+            SELECT m.user_id, u.username, m.joined_at
+            FROM household_members m JOIN users u ON u.id = m.user_id
+            WHERE m.household_id = ?
+            ORDER BY m.joined_at, u.username
+            "#,
+        )
+        .bind(household.id)
+        .fetch_all(&self.pool)
+        .await?;
+        if household.owner_id == user_id {
+            household.invitations = self
+                .invitations_where("i.household_id = ?", household.id)
+                .await?;
+        }
+        Ok(Some(household))
+    }
+
+    /// Invitations to join a household that the user hasn't answered
+    pub async fn invitations(&self, user_id: Uuid) -> Result<Vec<HouseholdInvitation>> {
+        self.invitations_where("i.user_id = ?", user_id).await
+    }
+
+    async fn invitations_where(
+        &self,
+        condition: &str,
+        id: Uuid,
+    ) -> Result<Vec<HouseholdInvitation>> {
+        let invitations = sqlx::query_as::<_, HouseholdInvitation>(&format!(
+            r#"
+            SELECT i.household_id, h.name AS household_name, i.user_id, u.username, i.invited_at
+            FROM household_invitations i
+            JOIN households h ON h.id = i.household_id
+            JOIN users u ON u.id = i.user_id
+            WHERE {}
+            ORDER BY i.invited_at, u.username
+            "#,
+            condition
+        ))
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(invitations)
+    }
+
+    /// Start a household with the user as owner and first member
+    pub async fn create(&self, user_id: Uuid, name: &str) -> Result<Household> {
+        let name = name.trim();
+        if name.is_empty() || name.chars().count() > MAX_NAME {
+            return Err(Error::Validation(format!(
+                "Name must be 1 to {} characters",
+                MAX_NAME
+            )));
+        }
+        if self.get(user_id).await?.is_some() {
+            return Err(Error::Duplicate("Already in a household".to_string()));
+        }
+
+        let id = Uuid::new_v4();
+        let now = Utc::now().to_rfc3339();
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("INSERT INTO households (id, name, owner_id, created_at) VALUES (?, ?, ?, ?)")
+            .bind(id)
+            .bind(name)
+            .bind(user_id)
+            .bind(&now)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            "INSERT INTO household_members (household_id, user_id, joined_at) VALUES (?, ?, ?)",
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(&now)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        self.get(user_id).await?.ok_or(Error::NotFound)
+    }
+
+    /// The owner's household; `Forbidden` for members, `NotFound` without one
+    async fn owned(&self, owner_id: Uuid) -> Result<Household> {
+        let household = self.get(owner_id).await?.ok_or(Error::NotFound)?;
+        if household.owner_id != owner_id {
+            return Err(Error::Forbidden);
+        }
+        Ok(household)
+    }
+
+    /// Invite a user by name. Unknown users and users who are in a household
+    /// already get the same error, so names can't be probed; inviting a user
+    /// again is a no-op. Returns the household and the invited user's id.
+    pub async fn invite(&self, owner_id: Uuid, username: &str) -> Result<(Household, Uuid)> {
+        let household = self.owned(owner_id).await?;
+        let username = username.trim();
+        let user_id: Option<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT id FROM users
+            WHERE username = ?
+              AND id NOT IN (SELECT user_id FROM household_members)
+            "#,
+        )
+        .bind(username)
+        .fetch_optional(&self.pool)
+        .await?;
+        let user_id = user_id.ok_or_else(|| {
+            Error::Validation(format!("{} can't be invited to a household", username))
+        })?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO household_invitations (household_id, user_id, invited_at)
+            VALUES (?, ?, ?)
+            ON CONFLICT (household_id, user_id) DO NOTHING
+            "#,
+        )
+        .bind(household.id)
+        .bind(user_id)
+        .bind(Utc::now().to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+
+        let household = self.get(owner_id).await?.ok_or(Error::NotFound)?;
+        Ok((household, user_id))
+    }
+
+    /// Join the household that invited the user; other invitations are
+    /// dropped, since users can be in one household only
+    pub async fn accept(&self, user_id: Uuid, household_id: Uuid) -> Result<Household> {
+        let mut tx = self.pool.begin().await?;
+        let invited =
+            sqlx::query("DELETE FROM household_invitations WHERE household_id = ? AND user_id = ?")
+                .bind(household_id)
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+        if invited == 0 {
+            return Err(Error::NotFound);
+        }
+        let member: Option<Uuid> =
+            sqlx::query_scalar("SELECT household_id FROM household_members WHERE user_id = ?")
+                .bind(user_id)
+                .fetch_optional(&mut *tx)
+                .await?;
+        if member.is_some() {
+            return Err(Error::Duplicate("Already in a household".to_string()));
+        }
+
+        sqlx::query(
+            "INSERT INTO household_members (household_id, user_id, joined_at) VALUES (?, ?, ?)",
+        )
+        .bind(household_id)
+        .bind(user_id)
+        .bind(Utc::now().to_rfc3339())
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("DELETE FROM household_invitations WHERE user_id = ?")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        self.get(user_id).await?.ok_or(Error::NotFound)
+    }
+
+    /// Turn an invitation down
+    pub async fn decline(&self, user_id: Uuid, household_id: Uuid) -> Result<()> {
+        let result =
+            sqlx::query("DELETE FROM household_invitations WHERE household_id = ? AND user_id = ?")
+                .bind(household_id)
+                .bind(user_id)
+                .execute(&self.pool)
+                .await?;
+        if result.rows_affected() == 0 {
+            return Err(Error::NotFound);
+        }
+        Ok(())
+    }
+
+    /// The owner removes a member or withdraws an invitation, or a member
+    /// leaves. The owner can't leave; they delete the household instead.
+    pub async fn remove_member(&self, user_id: Uuid, member_id: Uuid) -> Result<()> {
+        let household = self.get(user_id).await?.ok_or(Error::NotFound)?;
+        if member_id == household.owner_id {
+            return Err(Error::Validation(
+                "The owner can't leave; delete the household instead".to_string(),
+            ));
+        }
+        if user_id != member_id && user_id != household.owner_id {
+            return Err(Error::Forbidden);
+        }
+
+        let mut removed = 0;
+        for table in ["household_members", "household_invitations"] {
+            removed += sqlx::query(&format!(
+                "DELETE FROM {} WHERE household_id = ? AND user_id = ?",
+                table
+            ))
+            .bind(household.id)
+            .bind(member_id)
+            .execute(&self.pool)
+            .await?
+            .rows_affected();
+        }
+        if removed == 0 {
+            return Err(Error::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Dissolve the owner's household; everyone keeps their movies
+    pub async fn delete(&self, owner_id: Uuid) -> Result<()> {
+        let household = self.owned(owner_id).await?;
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM household_members WHERE household_id = ?")
+            .bind(household.id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM households WHERE id = ?")
+            .bind(household.id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// The user and the other members of their household
+    pub async fn member_ids(&self, user_id: Uuid) -> Result<Vec<Uuid>> {
+        let mut ids: Vec<Uuid> = sqlx::query_scalar(HOUSEHOLD_USERS)
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await?;
+        if !ids.contains(&user_id) {
+            ids.insert(0, user_id);
+        }
+        Ok(ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{create_test_db_with_users, fixtures};
+
+    #[tokio::test]
+    async fn test_household_membership() {
+        let pool = create_test_db_with_users().await;
+        let service = HouseholdService::new(pool);
+        let owner = fixtures::test_admin_id();
+        let member = fixtures::test_user_id();
+
+        assert!(service.get(owner).await.unwrap().is_none());
+        assert_eq!(service.member_ids(owner).await.unwrap(), vec![owner]);
+
+        let household = service.create(owner, " Home ").await.unwrap();
+        assert_eq!(household.name, "Home");
+        assert_eq!(household.members.len(), 1);
+        assert!(matches!(
+            service.create(owner, "Again").await,
+            Err(Error::Duplicate(_))
+        ));
+
+        // Only the owner invites, and invited users are no members yet
+        assert!(matches!(
+            service.invite(member, "testadmin").await,
+            Err(Error::NotFound)
+        ));
+        let (household, invited) = service.invite(owner, " testuser ").await.unwrap();
+        assert_eq!(invited, member);
+        assert_eq!(household.members.len(), 1);
+        assert_eq!(household.invitations.len(), 1);
+        assert_eq!(household.invitations[0].username, "testuser");
+        assert!(service.get(member).await.unwrap().is_none());
+        assert_eq!(service.member_ids(owner).await.unwrap(), vec![owner]);
+        assert_eq!(service.member_ids(member).await.unwrap(), vec![member]);
+
+        // Unknown users and members look the same
+        let unknown = service.invite(owner, "nobody").await.unwrap_err();
+        let in_household = service.invite(owner, "testadmin").await.unwrap_err();
+        assert!(matches!(unknown, Error::Validation(_)));
+        assert_eq!(
+            unknown.to_string().replace("nobody", "testadmin"),
+            in_household.to_string()
+        );
+
+        // Only the invited user accepts
+        assert!(matches!(
+            service.accept(owner, household.id).await,
+            Err(Error::NotFound)
+        ));
+        let invitations = service.invitations(member).await.unwrap();
+        assert_eq!(invitations.len(), 1);
+        assert_eq!(invitations[0].household_name, "Home");
+        let household = service.accept(member, household.id).await.unwrap();
+        assert_eq!(household.members.len(), 2);
+        assert!(household.invitations.is_empty());
+        assert!(service.invitations(member).await.unwrap().is_empty());
+
+        let mut ids = service.member_ids(member).await.unwrap();
+        ids.sort();
+        let mut expected = vec![owner, member];
+        expected.sort();
+        assert_eq!(ids, expected);
+
+        // Members can't remove others or the owner, but can leave
+        assert!(matches!(
+            service.remove_member(member, owner).await,
+            Err(Error::Validation(_))
+        ));
+        service.remove_member(member, member).await.unwrap();
+        assert!(service.get(member).await.unwrap().is_none());
+
+        // Declined and withdrawn invitations are gone
+        service.invite(owner, "testuser").await.unwrap();
+        service.decline(member, household.id).await.unwrap();
+        assert!(matches!(
+            service.accept(member, household.id).await,
+            Err(Error::NotFound)
+        ));
+        service.invite(owner, "testuser").await.unwrap();
+        service.remove_member(owner, member).await.unwrap();
+        assert!(service.invitations(member).await.unwrap().is_empty());
+
+        service.invite(owner, "testuser").await.unwrap();
+        service.accept(member, household.id).await.unwrap();
+        assert!(matches!(
+            service.delete(member).await,
+            Err(Error::Forbidden)
+        ));
+        service.delete(owner).await.unwrap();
+        assert!(service.get(member).await.unwrap().is_none());
+    }
+}
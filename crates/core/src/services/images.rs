@@ -0,0 +1,375 @@
+use std::sync::{Arc, RwLock};
+
+use chrono::Utc;
+use sha2::{Digest, Sha256};
+
+use crate::db::DbPool;
+use crate::error::{Error, Result};
+use crate::services::blob_store::{BlobStore, SqliteBlobStore};
+
+/// Store for new images configured via the `image_storage` setting (`None` = database)
+static CONFIGURED_STORE: RwLock<Option<Arc<dyn BlobStore>>> = RwLock::new(None);
+
+/// Select where new images are stored. Existing images stay where they are.
+pub fn set_blob_store(store: Option<Arc<dyn BlobStore>>) {
+    if let Ok(mut configured) = CONFIGURED_STORE.write() {
+        *configured = store;
+    }
+}
+
+fn configured_store() -> Option<Arc<dyn BlobStore>> {
+    CONFIGURED_STORE.read().ok().and_then(|s| s.clone())
+}
+
+/// Kind of the store new images go to (`db`, `fs` or `s3`)
+pub fn storage_kind() -> &'static str {
+    configured_store().map_or(SqliteBlobStore::KIND, |s| s.kind())
+}
+
+/// Key of an image: hex SHA-256 of its bytes
+pub fn image_hash(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// Content-addressed image storage shared by all posters and avatars.
+///
+/// Identical images are stored once. Rows are referenced by `movies.poster_hash`,
+/// `movies.backdrop_hash`, `series.poster_hash` and `users.avatar_hash`; triggers
+/// keep `ref_count` up to date and remove images nobody references. The bytes
+/// live in the database or in an external `BlobStore`, as recorded in
+/// `images.storage`.
+pub struct ImageService {
+    pool: DbPool,
+    store: Option<Arc<dyn BlobStore>>,
+}
+
+impl ImageService {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool, store: None }
+    }
+
+    /// Use `store` for new images instead of the configured one
+    pub fn with_store(pool: DbPool, store: Arc<dyn BlobStore>) -> Self {
+        Self {
+            pool,
+            store: Some(store),
+        }
+    }
+
+    /// Store for new images, following `set_blob_store` changes
+    fn store_for_new(&self) -> Arc<dyn BlobStore> {
+        self.store
+            .clone()
+            .or_else(configured_store)
+            .unwrap_or_else(|| Arc::new(SqliteBlobStore::new(self.pool.clone())))
+    }
+
+    /// Store image bytes and return their hash. Storing an existing image is a no-op.
+    pub async fn store(&self, data: &[u8]) -> Result<String> {
+        let hash = image_hash(data);
+        let store = self.store_for_new();
+        // External images keep an empty `data` column
+        let external = store.kind() != SqliteBlobStore::KIND;
+
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO images (hash, data, size, ref_count, created_at, storage)
+            VALUES (?, ?, ?, 0, ?, ?)
+            ON CONFLICT(hash) DO NOTHING
+            "#,
+        )
+        .bind(&hash)
+        .bind(if external { &[][..] } else { data })
+        .bind(data.len() as i64)
+        .bind(Utc::now().to_rfc3339())
+        .bind(store.kind())
+        .execute(&self.pool)
+        .await?
+        .rows_affected()
+            > 0;
+
+        if external
+            && inserted
+            && let Err(e) = store.put(&hash, data).await
+        {
+            sqlx::query("DELETE FROM images WHERE hash = ?")
+                .bind(&hash)
+                .execute(&self.pool)
+                .await?;
+            return Err(e);
+        }
+
+        Ok(hash)
+    }
+
+    pub async fn get(&self, hash: &str) -> Result<Option<Vec<u8>>> {
+        let storage: Option<String> =
+            sqlx::query_scalar("SELECT storage FROM images WHERE hash = ?")
+                .bind(hash)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        let store = self.store_for_new();
+        match storage.as_deref() {
+            None => Ok(None),
+            Some(SqliteBlobStore::KIND) => SqliteBlobStore::new(self.pool.clone()).get(hash).await,
+            Some(kind) if kind == store.kind() => store.get(hash).await,
+            Some(kind) => Err(Error::Configuration(format!(
+                "Image {} is in {} storage, which is not configured",
+                hash, kind
+            ))),
+        }
+    }
+
+    /// Image referenced by an optional hash column
+    pub async fn get_optional(&self, hash: Option<&str>) -> Result<Option<Vec<u8>>> {
+        match hash {
+            Some(hash) => self.get(hash).await,
+            None => Ok(None),
+        }
+    }
+
+    /// Remove bytes of deleted images from external storage. Returns the number removed.
+    pub async fn purge_deleted(&self) -> Result<usize> {
+        let deleted: Vec<(i64, String, String)> =
+            sqlx::query_as("SELECT id, hash, storage FROM image_deletions ORDER BY id")
+                .fetch_all(&self.pool)
+                .await?;
+
+        let store = self.store_for_new();
+        let mut purged = 0;
+        for (id, hash, storage) in deleted {
+            if storage != store.kind() {
+                // Kept until that storage is configured again
+                continue;
+            }
+            // Stored again since (same content, same key)
+            let reused: bool = sqlx::query_scalar(
+                "SELECT EXISTS(SELECT 1 FROM images WHERE hash = ? AND storage = ?)",
+            )
+            .bind(&hash)
+            .bind(&storage)
+            .fetch_one(&self.pool)
+            .await?;
+            if !reused {
+                store.delete(&hash).await?;
+                purged += 1;
+            }
+            sqlx::query("DELETE FROM image_deletions WHERE id = ?")
+                .bind(id)
+                .execute(&self.pool)
+                .await?;
+        }
+
+        Ok(purged)
+    }
+
+    /// Replace the temporary keys the images migration gives existing posters
+    /// with their SHA-256. Returns the number of images rehashed.
+    pub async fn rehash_legacy(&self) -> Result<usize> {
+        let legacy: Vec<String> =
+            sqlx::query_scalar("SELECT hash FROM images WHERE hash LIKE 'legacy:%'")
+                .fetch_all(&self.pool)
+                .await?;
+
+        for old_hash in &legacy {
+            let Some(data) = self.get(old_hash).await? else {
+                continue;
+            };
+            let new_hash = image_hash(&data);
+
+            let mut tx = self.pool.begin().await?;
+            sqlx::query(
+                r#"
+                INSERT INTO images (hash, data, size, ref_count, created_at)
+                SELECT ?, data, size, 0, created_at FROM images WHERE hash = ?
+                ON CONFLICT(hash) DO NOTHING
+                "#,
+            )
+            .bind(&new_hash)
+            .bind(old_hash)
+            .execute(&mut *tx)
+            .await?;
+
+            // Moving the references transfers the counts; the old row is dropped by trigger
+            sqlx::query("UPDATE movies SET poster_hash = ? WHERE poster_hash = ?")
+                .bind(&new_hash)
+                .bind(old_hash)
+                .execute(&mut *tx)
+                .await?;
+
+            // Unreferenced legacy rows are not covered by the trigger
+            sqlx::query("DELETE FROM images WHERE hash = ?")
+                .bind(old_hash)
+                .execute(&mut *tx)
+                .await?;
+            tx.commit().await?;
+        }
+
+        Ok(legacy.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::CreateMovie;
+    use crate::services::{FsBlobStore, MovieService};
+    use crate::test_helpers::{create_test_db_with_users, fixtures};
+    use uuid::Uuid;
+
+    async fn create_movie(service: &MovieService, title: &str) -> Uuid {
+        service
+            .create(
+                fixtures::test_user_id(),
+                CreateMovie {
+                    barcode: None,
+                    tmdb_id: None,
+                    title: title.to_string(),
+                    original_title: None,
+                    disc_type: None,
+                    production_year: None,
+                },
+            )
+            .await
+            .unwrap()
+            .id
+    }
+
+    async fn image_rows(pool: &DbPool) -> Vec<(String, i64)> {
+        sqlx::query_as("SELECT hash, ref_count FROM images ORDER BY hash")
+            .fetch_all(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_identical_posters_are_stored_once() {
+        let pool = create_test_db_with_users().await;
+        let movies = MovieService::new(pool.clone());
+        let user_id = fixtures::test_user_id();
+
+        let a = create_movie(&movies, "Teil 1").await;
+        let b = create_movie(&movies, "Teil 2").await;
+        let poster = vec![0xFF, 0xD8, 0xFF, 0xE0, 1, 2, 3];
+        movies
+            .update_movie_poster_data(user_id, a, Some(poster.clone()))
+            .await
+            .unwrap();
+        movies
+            .update_movie_poster_data(user_id, b, Some(poster.clone()))
+            .await
+            .unwrap();
+
+        assert_eq!(image_rows(&pool).await, vec![(image_hash(&poster), 2)]);
+        let loaded = movies.get_by_id(user_id, b).await.unwrap();
+        assert_eq!(loaded.poster_data, Some(poster));
+    }
+
+    #[tokio::test]
+    async fn test_unreferenced_images_are_removed() {
+        let pool = create_test_db_with_users().await;
+        let movies = MovieService::new(pool.clone());
+        let user_id = fixtures::test_user_id();
+
+        let a = create_movie(&movies, "Teil 1").await;
+        let b = create_movie(&movies, "Teil 2").await;
+        let poster = vec![1, 2, 3, 4];
+        movies
+            .update_movie_poster_data(user_id, a, Some(poster.clone()))
+            .await
+            .unwrap();
+        movies
+            .update_movie_poster_data(user_id, b, Some(poster.clone()))
+            .await
+            .unwrap();
+
+        movies.delete(user_id, a).await.unwrap();
+        assert_eq!(image_rows(&pool).await, vec![(image_hash(&poster), 1)]);
+
+        // Replacing the last reference drops the old image
+        let replacement = vec![5, 6, 7, 8];
+        movies
+            .update_movie_poster_data(user_id, b, Some(replacement.clone()))
+            .await
+            .unwrap();
+        assert_eq!(image_rows(&pool).await, vec![(image_hash(&replacement), 1)]);
+
+        movies
+            .update_movie_poster_data(user_id, b, None)
+            .await
+            .unwrap();
+        assert!(image_rows(&pool).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rehash_legacy_images() {
+        let pool = create_test_db_with_users().await;
+        let movies = MovieService::new(pool.clone());
+        let service = ImageService::new(pool.clone());
+        let user_id = fixtures::test_user_id();
+
+        // State right after the migration: temporary key with its reference count
+        let poster = vec![9, 8, 7, 6];
+        sqlx::query(
+            "INSERT INTO images (hash, data, size, ref_count, created_at) VALUES ('legacy:abc', ?, 4, 0, ?)",
+        )
+        .bind(&poster)
+        .bind(Utc::now().to_rfc3339())
+        .execute(&pool)
+        .await
+        .unwrap();
+        let a = create_movie(&movies, "Teil 1").await;
+        let b = create_movie(&movies, "Teil 2").await;
+        sqlx::query("UPDATE movies SET poster_hash = 'legacy:abc'")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert_eq!(service.rehash_legacy().await.unwrap(), 1);
+
+        assert_eq!(image_rows(&pool).await, vec![(image_hash(&poster), 2)]);
+        for id in [a, b] {
+            let movie = movies.get_by_id(user_id, id).await.unwrap();
+            assert_eq!(movie.poster_data.as_deref(), Some(poster.as_slice()));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_external_storage() {
+        let pool = create_test_db_with_users().await;
+        let dir = tempfile::tempdir().unwrap();
+        let service =
+            ImageService::with_store(pool.clone(), Arc::new(FsBlobStore::new(dir.path())));
+
+        let poster = vec![0x89, 0x50, 0x4E, 0x47, 1, 2];
+        let hash = service.store(&poster).await.unwrap();
+        let (storage, data): (String, Vec<u8>) =
+            sqlx::query_as("SELECT storage, data FROM images WHERE hash = ?")
+                .bind(&hash)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(storage, FsBlobStore::KIND);
+        assert!(data.is_empty());
+        assert_eq!(service.get(&hash).await.unwrap(), Some(poster.clone()));
+
+        // Without the filesystem store configured the image can't be read
+        assert!(matches!(
+            ImageService::new(pool.clone()).get(&hash).await,
+            Err(Error::Configuration(_))
+        ));
+
+        // Unreferenced rows are removed; their files go with the next purge
+        let blob = dir.path().join(&hash[..2]).join(&hash);
+        assert!(blob.exists());
+        sqlx::query("DELETE FROM images WHERE hash = ?")
+            .bind(&hash)
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert_eq!(service.purge_deleted().await.unwrap(), 1);
+        assert!(!blob.exists());
+        assert_eq!(service.purge_deleted().await.unwrap(), 0);
+    }
+}
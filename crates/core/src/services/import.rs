@@ -1,16 +1,24 @@
 use chrono::Utc;
 use csv::ReaderBuilder;
+use std::collections::HashMap;
 use std::io::Read;
 use uuid::Uuid;
 
 use crate::db::DbPool;
 use crate::error::{Error, Result};
+use crate::models::{
+    ActivityAction, EntityType, ImportPreset, MovieStatus, normalize_movie_status,
+    normalize_region_codes, normalize_video_standard,
+};
+use crate::services::activity::record_activity;
+use crate::services::dvdprofiler;
+use crate::services::import_presets::ColumnMapping;
 
 pub struct ImportService {
     pool: DbPool,
 }
 
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug, Default, serde::Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct CsvMovieRecord {
     #[serde(rename = "Collection Number")]
@@ -124,6 +132,8 @@ pub struct ImportResult {
     pub series_imported: u32,
     pub collections_imported: u32,
     pub errors: Vec<String>,
+    /// Ids of the imported movies (not series or collections)
+    pub movie_ids: Vec<Uuid>,
 }
 
 impl ImportService {
@@ -131,7 +141,14 @@ impl ImportService {
         Self { pool }
     }
 
-    pub async fn import_csv<R: Read>(&self, user_id: Uuid, reader: R) -> Result<ImportResult> {
+    /// Import a CSV file in our format, or, with a preset, the CSV export of
+    /// another collection manager read through the preset's column mapping
+    pub async fn import_csv<R: Read>(
+        &self,
+        user_id: Uuid,
+        reader: R,
+        preset: Option<&ImportPreset>,
+    ) -> Result<ImportResult> {
         let mut csv_reader = ReaderBuilder::new()
             .has_headers(true)
             .flexible(true)
@@ -142,22 +159,39 @@ impl ImportService {
             series_imported: 0,
             collections_imported: 0,
             errors: Vec::new(),
+            movie_ids: Vec::new(),
         };
 
-        for (index, record_result) in csv_reader.deserialize::<CsvMovieRecord>().enumerate() {
+        let headers = csv_reader
+            .headers()
+            .map_err(|e| Error::CsvImport(e.to_string()))?
+            .clone();
+        let mapping = preset
+            .map(|preset| ColumnMapping::new(&headers, preset))
+            .transpose()?;
+
+        for (index, row) in csv_reader.records().enumerate() {
             let row_num = index + 2; // +2 because of 0-indexing and header row
+            let record_result = row.and_then(|row| match &mapping {
+                Some(mapping) => mapping
+                    .apply(&row)
+                    .deserialize::<CsvMovieRecord>(Some(mapping.headers())),
+                None => row.deserialize::<CsvMovieRecord>(Some(&headers)),
+            });
 
             match record_result {
                 Ok(record) => {
-                    if let Err(e) = self.import_record(user_id, &record).await {
-                        result.errors.push(format!("Row {}: {}", row_num, e));
-                    } else {
+                    match self.import_record(user_id, &record).await {
+                        Err(e) => result.errors.push(format!("Row {}: {}", row_num, e)),
                         // Determine type and increment counter
-                        match record.item_type.as_deref() {
+                        Ok(id) => match record.item_type.as_deref() {
                             Some("Series") => result.series_imported += 1,
                             Some("Collection") => result.collections_imported += 1,
-                            _ => result.movies_imported += 1,
-                        }
+                            _ => {
+                                result.movies_imported += 1;
+                                result.movie_ids.push(id);
+                            }
+                        },
                     }
                 }
                 Err(e) => {
@@ -171,7 +205,84 @@ impl ImportService {
         Ok(result)
     }
 
-    async fn import_record(&self, user_id: Uuid, record: &CsvMovieRecord) -> Result<()> {
+    /// Import a DVD Profiler `collection.xml` export. Every `<DVD>` becomes a
+    /// movie; box sets become collection entries (`is_collection`) with their
+    /// contents linked as parts. Entries that fail are reported by position.
+    pub async fn import_dvdprofiler_xml(&self, user_id: Uuid, data: &[u8]) -> Result<ImportResult> {
+        let entries = dvdprofiler::parse_collection(data)?;
+
+        let mut result = ImportResult {
+            movies_imported: 0,
+            series_imported: 0,
+            collections_imported: 0,
+            errors: Vec::new(),
+            movie_ids: Vec::new(),
+        };
+        // DVD Profiler ID -> imported movie, to link box set contents
+        let mut imported: HashMap<String, Uuid> = HashMap::new();
+
+        for (index, entry) in entries.iter().enumerate() {
+            let label = match entry.record.title.as_deref() {
+                Some(title) => format!("DVD {} ({})", index + 1, title),
+                None => format!("DVD {}", index + 1),
+            };
+            let Some(title) = entry.record.title.as_deref() else {
+                result.errors.push(format!("{}: Missing title", label));
+                continue;
+            };
+
+            let id = Uuid::new_v4();
+            let now = Utc::now();
+            if let Err(e) = self
+                .import_movie(user_id, id, &now, &entry.record, title)
+                .await
+            {
+                result.errors.push(format!("{}: {}", label, e));
+                continue;
+            }
+
+            if entry.is_box_set {
+                sqlx::query("UPDATE movies SET is_collection = 1 WHERE id = ?")
+                    .bind(id)
+                    .execute(&self.pool)
+                    .await?;
+                result.collections_imported += 1;
+            } else {
+                result.movies_imported += 1;
+            }
+            result.movie_ids.push(id);
+            if let Some(profile_id) = &entry.profile_id {
+                imported.insert(profile_id.clone(), id);
+            }
+        }
+
+        for (index, entry) in entries.iter().enumerate() {
+            let (Some(parent), Some(profile_id)) = (&entry.parent, &entry.profile_id) else {
+                continue;
+            };
+            let Some(&id) = imported.get(profile_id) else {
+                continue;
+            };
+            match imported.get(parent) {
+                Some(&parent_id) => {
+                    sqlx::query("UPDATE movies SET parent_collection_id = ? WHERE id = ?")
+                        .bind(parent_id)
+                        .bind(id)
+                        .execute(&self.pool)
+                        .await?;
+                }
+                None => result.errors.push(format!(
+                    "DVD {}: Box set {} is not in the export, imported on its own",
+                    index + 1,
+                    parent
+                )),
+            }
+        }
+
+        Ok(result)
+    }
+
+    async fn import_record(&self, user_id: Uuid, record: &CsvMovieRecord) -> Result<Uuid> {
         let title = record
             .title
             .as_ref()
@@ -182,13 +293,15 @@ impl ImportService {
 
         // Determine if this is a movie, series, or collection based on item_type
         match record.item_type.as_deref() {
-            Some("Series") => self.import_series(user_id, id, &now, record, title).await,
+            Some("Series") => self.import_series(user_id, id, &now, record, title).await?,
             Some("Collection") => {
                 self.import_collection(user_id, id, &now, record, title)
-                    .await
+                    .await?
             }
-            _ => self.import_movie(user_id, id, &now, record, title).await,
+            _ => self.import_movie(user_id, id, &now, record, title).await?,
         }
+
+        Ok(id)
     }
 
     async fn import_movie(
@@ -213,10 +326,10 @@ impl ImportService {
                 edition, extra_features, purchase_date, price, currency, purchase_place,
                 value_date, value_price, value_currency, lent_to, lent_due, location,
                 notes, budget, revenue, spoken_languages, imdb_id, added_date,
-                created_at, updated_at
+                added_by, updated_by, created_at, updated_at
             )
             VALUES (
-                ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?
+                ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?
             )
             "#,
         )
@@ -244,8 +357,8 @@ impl ImportService {
         .bind(&record.disc_type)
         .bind(&record.media_type)
         .bind(Self::parse_int(&record.discs))
-        .bind(&record.region_codes)
-        .bind(&record.video_standard)
+        .bind(Self::parse_region_codes(&record.region_codes))
+        .bind(Self::parse_video_standard(&record.video_standard))
         .bind(&record.aspect_ratio)
         .bind(&record.audio_tracks)
         .bind(&record.subtitles)
@@ -257,7 +370,7 @@ impl ImportService {
         .bind(&record.group)
         .bind(Self::parse_bool(&record.watched))
         .bind(&record.digital_copies)
-        .bind(&record.status)
+        .bind(Self::parse_movie_status(&record.status))
         .bind(&record.condition)
         .bind(Self::parse_bool(&record.slip_cover))
         .bind(&record.cover_type)
@@ -279,12 +392,23 @@ impl ImportService {
         .bind(&record.spoken_languages)
         .bind(&record.imdb_id)
         .bind(&record.added_date)
+        .bind(user_id)
+        .bind(user_id)
         .bind(now.to_rfc3339())
         .bind(now.to_rfc3339())
         .execute(&self.pool)
         .await?;
 
-        Ok(())
+        record_activity(
+            &self.pool,
+            user_id,
+            ActivityAction::Import,
+            EntityType::Movie,
+            id,
+            Some(title),
+            None,
+        )
+        .await
     }
 
     async fn import_series(
@@ -334,8 +458,8 @@ impl ImportService {
         .bind(&record.disc_type)
         .bind(&record.media_type)
         .bind(Self::parse_int(&record.discs))
-        .bind(&record.region_codes)
-        .bind(&record.video_standard)
+        .bind(Self::parse_region_codes(&record.region_codes))
+        .bind(Self::parse_video_standard(&record.video_standard))
         .bind(&record.aspect_ratio)
         .bind(&record.audio_tracks)
         .bind(&record.subtitles)
@@ -370,7 +494,16 @@ impl ImportService {
         .execute(&self.pool)
         .await?;
 
-        Ok(())
+        record_activity(
+            &self.pool,
+            user_id,
+            ActivityAction::Import,
+            EntityType::Series,
+            id,
+            Some(title),
+            None,
+        )
+        .await
     }
 
     async fn import_collection(
@@ -405,7 +538,7 @@ impl ImportService {
         .bind(&record.description)
         .bind(&record.disc_type)
         .bind(Self::parse_int(&record.discs))
-        .bind(&record.region_codes)
+        .bind(Self::parse_region_codes(&record.region_codes))
         .bind(&record.genres)
         .bind(&record.categories)
         .bind(&record.tags)
@@ -430,7 +563,16 @@ impl ImportService {
         .execute(&self.pool)
         .await?;
 
-        Ok(())
+        record_activity(
+            &self.pool,
+            user_id,
+            ActivityAction::Import,
+            EntityType::Collection,
+            id,
+            Some(title),
+            None,
+        )
+        .await
     }
 
     fn parse_int(s: &Option<String>) -> Option<i64> {
@@ -441,9 +583,166 @@ impl ImportService {
         s.as_ref().and_then(|v| v.replace(',', ".").parse().ok())
     }
 
+    /// Stored form of the region codes; unreadable values are kept as-is and
+    /// show up in the region report
+    fn parse_region_codes(s: &Option<String>) -> Option<String> {
+        let value = s.as_ref()?;
+        normalize_region_codes(value).unwrap_or_else(|_| Some(value.clone()))
+    }
+
+    fn parse_video_standard(s: &Option<String>) -> Option<String> {
+        let value = s.as_ref()?;
+        normalize_video_standard(value).unwrap_or_else(|_| Some(value.clone()))
+    }
+
+    /// Unknown values count as owned, like an empty status
+    fn parse_movie_status(s: &Option<String>) -> Option<&'static str> {
+        let value = s.as_ref()?;
+        Some(
+            normalize_movie_status(value)
+                .unwrap_or(MovieStatus::Owned)
+                .as_str(),
+        )
+    }
+
     fn parse_bool(s: &Option<String>) -> bool {
         s.as_ref()
             .map(|v| matches!(v.to_lowercase().as_str(), "true" | "yes" | "1" | "ja"))
             .unwrap_or(false)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{create_test_db_with_users, fixtures};
+
+    #[tokio::test]
+    async fn test_import_csv_with_preset() {
+        let pool = create_test_db_with_users().await;
+        let import = ImportService::new(pool.clone());
+        let preset = crate::services::find_import_preset(Vec::new(), "Libib").unwrap();
+        let csv = "item_type,title,ean_isbn13,upc_isbn10,tags\n\
+                   movies,Alien,,0123456789012,Horror\n\
+                   movies,,4006680023456,,\n";
+
+        let result = import
+            .import_csv(fixtures::test_user_id(), csv.as_bytes(), Some(&preset))
+            .await
+            .unwrap();
+        assert_eq!(result.movies_imported, 1);
+        assert_eq!(result.errors.len(), 1);
+        assert!(result.errors[0].starts_with("Row 3: "));
+
+        let movie: (String, Option<String>, Option<String>) =
+            sqlx::query_as("SELECT title, barcode, tags FROM movies WHERE id = ?")
+                .bind(result.movie_ids[0])
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(
+            movie,
+            (
+                "Alien".to_string(),
+                Some("0123456789012".to_string()),
+                Some("Horror".to_string())
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_import_libib_export() {
+        let pool = create_test_db_with_users().await;
+        let import = ImportService::new(pool.clone());
+        let preset = crate::services::find_import_preset(Vec::new(), "Libib").unwrap();
+        let csv = include_str!("testdata/libib_movies.csv");
+
+        let result = import
+            .import_csv(fixtures::test_user_id(), csv.as_bytes(), Some(&preset))
+            .await
+            .unwrap();
+        assert_eq!(result.movies_imported, 2);
+        assert_eq!(result.errors.len(), 1);
+        assert!(result.errors[0].starts_with("Row 4: "));
+
+        type LibibRow = (
+            String,
+            Option<String>,
+            Option<String>,
+            Option<String>,
+            bool,
+            Option<f64>,
+            Option<i64>,
+            Option<f64>,
+        );
+        let rows: Vec<LibibRow> = sqlx::query_as(
+            "SELECT title, barcode, director, tags, watched, personal_rating, running_time, price \
+             FROM movies ORDER BY title",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            rows[0],
+            (
+                "Alien".to_string(),
+                Some("5039036081726".to_string()),
+                Some("Ridley Scott, Sigourney Weaver".to_string()),
+                Some("sci-fi, horror, classics".to_string()),
+                true,
+                Some(10.0),
+                Some(117),
+                Some(9.99),
+            )
+        );
+        assert_eq!(rows[1].1.as_deref(), Some("0786936229576"));
+        assert!(!rows[1].4);
+        assert_eq!(rows[1].5, Some(6.0));
+    }
+
+    #[tokio::test]
+    async fn test_import_dvdprofiler_box_set() {
+        let pool = create_test_db_with_users().await;
+        let import = ImportService::new(pool.clone());
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<Collection>
+  <DVD><ID>1.1</ID><Title>Trilogy</Title>
+    <BoxSet><Parent/><Contents><Content>1.2</Content><Content>1.3</Content></Contents></BoxSet>
+  </DVD>
+  <DVD><ID>1.2</ID><Title>Part One</Title><BoxSet><Parent>1.1</Parent></BoxSet></DVD>
+  <DVD><ID>1.3</ID><Title>Part Two</Title><BoxSet><Parent>1.1</Parent></BoxSet></DVD>
+  <DVD><ID>2.1</ID><UPC>123</UPC></DVD>
+  <DVD><ID>3.1</ID><Title>Orphan</Title><BoxSet><Parent>9.9</Parent></BoxSet></DVD>
+</Collection>"#;
+
+        let result = import
+            .import_dvdprofiler_xml(fixtures::test_user_id(), xml.as_bytes())
+            .await
+            .unwrap();
+        assert_eq!(result.collections_imported, 1);
+        assert_eq!(result.movies_imported, 3);
+        assert_eq!(result.movie_ids.len(), 4);
+        assert_eq!(result.errors.len(), 2);
+        assert!(result.errors[0].starts_with("DVD 4: Missing title"));
+        assert!(result.errors[1].starts_with("DVD 5: Box set 9.9"));
+
+        let parts: Vec<(String,)> = sqlx::query_as(
+            "SELECT m.title FROM movies m JOIN movies p ON p.id = m.parent_collection_id \
+             WHERE p.title = 'Trilogy' AND p.is_collection = 1 ORDER BY m.title",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            parts,
+            vec![("Part One".to_string(),), ("Part Two".to_string(),)]
+        );
+
+        assert!(matches!(
+            import
+                .import_dvdprofiler_xml(fixtures::test_user_id(), b"title,year\n")
+                .await,
+            Err(Error::Validation(_))
+        ));
+    }
+}
@@ -0,0 +1,537 @@
+//! Column mappings for CSV exports of other collection managers. A preset
+//! maps the columns of such a file onto the columns of our own CSV format
+//! (`CsvMovieRecord`); the rows are then imported like our own CSV. A few
+//! presets are built in, users can save their own next to them.
+
+use std::collections::{BTreeMap, HashMap};
+
+use csv::StringRecord;
+
+use crate::error::{Error, Result};
+use crate::models::ImportPreset;
+
+/// Columns of our CSV format, as read by `CsvMovieRecord`
+pub const CSV_COLUMNS: &[&str] = &[
+    "Collection Number",
+    "Collection Item Type",
+    "Title",
+    "Original Title",
+    "Sort Title",
+    "Barcode",
+    "Disc Type",
+    "Production Year",
+    "IMDB Id",
+    "Running Time",
+    "Rating",
+    "Description",
+    "Director",
+    "Actors",
+    "Audio Tracks",
+    "Subtitles",
+    "Categories",
+    "Digital Copies",
+    "Region Codes",
+    "Discs",
+    "Genres",
+    "Watched",
+    "Tagline",
+    "Budget",
+    "Revenue",
+    "Network",
+    "3D",
+    "Status",
+    "Production Companies",
+    "Production Countries",
+    "Spoken Languages",
+    "Studios",
+    "First Aired",
+    "Mastered in 4K",
+    "Media Type",
+    "Slip Cover",
+    "Aspect Ratio",
+    "Video Standard",
+    "Cover Type",
+    "Release Date",
+    "Par. Rating",
+    "Extra Features",
+    "Edition",
+    "Air Time",
+    "Group",
+    "Personal Title",
+    "Personal Sort Title",
+    "Notes",
+    "Tags",
+    "Personal Rating",
+    "Type",
+    "Condition",
+    "Added Date",
+    "Lent To",
+    "Lent Due",
+    "Location",
+    "Purchase Date",
+    "Price",
+    "Currency",
+    "Purchase Place",
+    "Value Date",
+    "Value Price",
+    "Value Currency",
+    "Episodes Count",
+];
+
+/// Our columns holding comma-separated lists
+const LIST_COLUMNS: &[&str] = &[
+    "Director",
+    "Actors",
+    "Audio Tracks",
+    "Subtitles",
+    "Categories",
+    "Genres",
+    "Production Companies",
+    "Production Countries",
+    "Spoken Languages",
+    "Studios",
+    "Tags",
+];
+
+const MAX_NAME_LEN: usize = 60;
+const MAX_PRESETS: usize = 50;
+
+fn preset(name: &str, columns: &[(&str, &str)]) -> ImportPreset {
+    ImportPreset {
+        name: name.to_string(),
+        columns: columns
+            .iter()
+            .map(|(source, target)| (source.to_string(), target.to_string()))
+            .collect(),
+        builtin: true,
+        ..Default::default()
+    }
+}
+
+fn values(column: &str, values: &[(&str, &str)]) -> (String, BTreeMap<String, String>) {
+    (
+        column.to_string(),
+        values
+            .iter()
+            .map(|(from, to)| (from.to_string(), to.to_string()))
+            .collect(),
+    )
+}
+
+/// Presets shipped with the app, for the CSV exports of DVD Profiler,
+/// Delicious Library, Libib and CLZ Movies
+pub fn builtin_import_presets() -> Vec<ImportPreset> {
+    vec![
+        preset(
+            "DVD Profiler",
+            &[
+                ("Collection Number", "Collection Number"),
+                ("Title", "Title"),
+                ("Original Title", "Original Title"),
+                ("Sort Title", "Sort Title"),
+                ("UPC", "Barcode"),
+                ("Media Type", "Disc Type"),
+                ("Production Year", "Production Year"),
+                ("Running Time", "Running Time"),
+                ("Rating", "Rating"),
+                ("Overview", "Description"),
+                ("Director", "Director"),
+                ("Genres", "Genres"),
+                ("Studios", "Studios"),
+                ("Regions", "Region Codes"),
+                ("Case Type", "Cover Type"),
+                ("Edition", "Edition"),
+                ("Location", "Location"),
+                ("Purchase Date", "Purchase Date"),
+                ("Purchase Price", "Price"),
+                ("Purchase Place", "Purchase Place"),
+                ("Notes", "Notes"),
+            ],
+        ),
+        preset(
+            "Delicious Library",
+            &[
+                ("title", "Title"),
+                ("ean", "Barcode"),
+                ("upc", "Barcode"),
+                ("format", "Disc Type"),
+                ("director", "Director"),
+                ("creator", "Director"),
+                ("actors", "Actors"),
+                ("publisher", "Studios"),
+                ("release date", "Release Date"),
+                ("length", "Running Time"),
+                ("audience rating", "Rating"),
+                ("genres", "Genres"),
+                ("aspect ratio", "Aspect Ratio"),
+                ("edition", "Edition"),
+                ("features", "Extra Features"),
+                ("purchase date", "Purchase Date"),
+                ("price", "Price"),
+                ("location", "Location"),
+                ("notes", "Notes"),
+            ],
+        ),
+        // Libib exports one file per library; lists are comma-separated,
+        // ratings are 0 - 5 stars and `status` is the reading/watching state
+        ImportPreset {
+            list_separator: Some(",".to_string()),
+            values: [
+                values("Watched", &[("completed", "true")]),
+                values(
+                    "Personal Rating",
+                    &[
+                        ("0", ""),
+                        ("1", "2"),
+                        ("2", "4"),
+                        ("3", "6"),
+                        ("4", "8"),
+                        ("5", "10"),
+                    ],
+                ),
+            ]
+            .into(),
+            ..preset(
+                "Libib",
+                &[
+                    ("title", "Title"),
+                    ("ean_isbn13", "Barcode"),
+                    ("upc_isbn10", "Barcode"),
+                    ("creators", "Director"),
+                    ("description", "Description"),
+                    ("publisher", "Studios"),
+                    ("publish_date", "Release Date"),
+                    ("length", "Running Time"),
+                    ("number_of_discs", "Discs"),
+                    ("aspect_ratio", "Aspect Ratio"),
+                    ("rating", "Personal Rating"),
+                    ("group", "Group"),
+                    ("tags", "Tags"),
+                    ("notes", "Notes"),
+                    ("price", "Price"),
+                    ("status", "Watched"),
+                    ("added", "Added Date"),
+                ],
+            )
+        },
+        preset(
+            "CLZ Movies",
+            &[
+                ("Index", "Collection Number"),
+                ("Title", "Title"),
+                ("Sort Title", "Sort Title"),
+                ("Original Title", "Original Title"),
+                ("Barcode", "Barcode"),
+                ("Format", "Disc Type"),
+                ("Edition", "Edition"),
+                ("Movie Release Year", "Production Year"),
+                ("Release Date", "Release Date"),
+                ("Runtime", "Running Time"),
+                ("Director", "Director"),
+                ("Cast", "Actors"),
+                ("Genre", "Genres"),
+                ("Studio", "Studios"),
+                ("Plot", "Description"),
+                ("IMDb Number", "IMDB Id"),
+                ("Audience Rating", "Rating"),
+                ("Region", "Region Codes"),
+                ("Screen Ratios", "Aspect Ratio"),
+                ("Audio Tracks", "Audio Tracks"),
+                ("Subtitles", "Subtitles"),
+                ("Seen It", "Watched"),
+                ("Location", "Location"),
+                ("Purchase Date", "Purchase Date"),
+                ("Purchase Price", "Price"),
+                ("Store", "Purchase Place"),
+                ("Loaned To", "Lent To"),
+                ("Due Date", "Lent Due"),
+                ("Added Date", "Added Date"),
+                ("Tags", "Tags"),
+                ("Notes", "Notes"),
+            ],
+        ),
+    ]
+}
+
+/// A user preset, falling back to the built-in ones; names are compared
+/// case-insensitively
+pub fn find_import_preset(user_presets: Vec<ImportPreset>, name: &str) -> Option<ImportPreset> {
+    let name = name.trim();
+    user_presets
+        .into_iter()
+        .chain(builtin_import_presets())
+        .find(|p| p.name.eq_ignore_ascii_case(name))
+}
+
+/// Checked and cleaned presets of a user: names are unique and don't shadow a
+/// built-in preset, every target is one of `CSV_COLUMNS` (in its spelling)
+pub fn validate_import_presets(presets: Vec<ImportPreset>) -> Result<Vec<ImportPreset>> {
+    if presets.len() > MAX_PRESETS {
+        return Err(Error::Validation(format!(
+            "At most {} import presets can be saved",
+            MAX_PRESETS
+        )));
+    }
+
+    let builtin = builtin_import_presets();
+    let mut cleaned: Vec<ImportPreset> = Vec::with_capacity(presets.len());
+    for preset in presets {
+        let name = preset.name.trim().to_string();
+        if name.is_empty() || name.chars().count() > MAX_NAME_LEN {
+            return Err(Error::Validation(format!(
+                "Preset names must have 1 to {} characters",
+                MAX_NAME_LEN
+            )));
+        }
+        if builtin.iter().any(|p| p.name.eq_ignore_ascii_case(&name)) {
+            return Err(Error::Validation(format!(
+                "'{}' is a built-in preset",
+                name
+            )));
+        }
+        if cleaned.iter().any(|p| p.name.eq_ignore_ascii_case(&name)) {
+            return Err(Error::Validation(format!("Preset '{}' exists twice", name)));
+        }
+
+        let mut columns = BTreeMap::new();
+        for (source, target) in preset.columns {
+            let source = source.trim();
+            if source.is_empty() {
+                continue;
+            }
+            let column = csv_column(&target, &name)?;
+            columns.insert(source.to_string(), column.to_string());
+        }
+        if columns.is_empty() {
+            return Err(Error::Validation(format!(
+                "Preset '{}' maps no columns",
+                name
+            )));
+        }
+
+        let mut values = BTreeMap::new();
+        for (target, replacements) in preset.values {
+            let column = csv_column(&target, &name)?;
+            let replacements: BTreeMap<String, String> = replacements
+                .into_iter()
+                .map(|(from, to)| (from.trim().to_lowercase(), to.trim().to_string()))
+                .collect();
+            values.insert(column.to_string(), replacements);
+        }
+
+        cleaned.push(ImportPreset {
+            name,
+            columns,
+            list_separator: preset.list_separator.filter(|s| !s.trim().is_empty()),
+            values,
+            builtin: false,
+        });
+    }
+
+    Ok(cleaned)
+}
+
+/// One of `CSV_COLUMNS`, in its spelling
+fn csv_column(column: &str, preset: &str) -> Result<&'static str> {
+    CSV_COLUMNS
+        .iter()
+        .find(|c| c.eq_ignore_ascii_case(column.trim()))
+        .copied()
+        .ok_or_else(|| {
+            Error::Validation(format!(
+                "Unknown column '{}' in preset '{}'",
+                column, preset
+            ))
+        })
+}
+
+/// The columns of a file resolved against a preset
+pub(crate) struct ColumnMapping {
+    /// Our columns the file provides
+    headers: StringRecord,
+    /// Per entry of `headers`, the file columns feeding it in file order
+    sources: Vec<Vec<usize>>,
+    /// Per entry of `headers`, replaced values by their lowercase original
+    values: Vec<HashMap<String, String>>,
+    /// Per entry of `headers`, the separator to split the value at
+    separators: Vec<Option<String>>,
+}
+
+impl ColumnMapping {
+    pub(crate) fn new(file_headers: &StringRecord, preset: &ImportPreset) -> Result<Self> {
+        let mut headers = StringRecord::new();
+        let mut sources: Vec<Vec<usize>> = Vec::new();
+
+        for (index, header) in file_headers.iter().enumerate() {
+            let header = header.trim_start_matches('\u{feff}').trim();
+            let Some(target) = preset
+                .columns
+                .iter()
+                .find(|(source, _)| source.eq_ignore_ascii_case(header))
+                .map(|(_, target)| target.as_str())
+            else {
+                continue;
+            };
+            match headers.iter().position(|h| h == target) {
+                Some(position) => sources[position].push(index),
+                None => {
+                    headers.push_field(target);
+                    sources.push(vec![index]);
+                }
+            }
+        }
+
+        if headers.is_empty() {
+            return Err(Error::CsvImport(format!(
+                "None of the columns of preset '{}' are in the file",
+                preset.name
+            )));
+        }
+        let values = headers
+            .iter()
+            .map(|column| {
+                preset
+                    .values
+                    .iter()
+                    .find(|(target, _)| target.eq_ignore_ascii_case(column))
+                    .map(|(_, replacements)| {
+                        replacements
+                            .iter()
+                            .map(|(from, to)| (from.trim().to_lowercase(), to.clone()))
+                            .collect()
+                    })
+                    .unwrap_or_default()
+            })
+            .collect();
+        let separators = headers
+            .iter()
+            .map(|column| {
+                preset
+                    .list_separator
+                    .clone()
+                    .filter(|_| LIST_COLUMNS.contains(&column))
+            })
+            .collect();
+
+        Ok(Self {
+            headers,
+            sources,
+            values,
+            separators,
+        })
+    }
+
+    pub(crate) fn headers(&self) -> &StringRecord {
+        &self.headers
+    }
+
+    /// The row with our columns; of several file columns for one of ours the
+    /// first non-empty one wins. Values are replaced as the preset says and
+    /// lists are joined with commas.
+    pub(crate) fn apply(&self, row: &StringRecord) -> StringRecord {
+        let mut record = StringRecord::new();
+        for (column, indexes) in self.sources.iter().enumerate() {
+            let value = indexes
+                .iter()
+                .filter_map(|&i| row.get(i))
+                .map(str::trim)
+                .find(|v| !v.is_empty())
+                .unwrap_or("");
+
+            if let Some(replacement) = self.values[column].get(&value.to_lowercase()) {
+                record.push_field(replacement);
+            } else if let Some(separator) = &self.separators[column] {
+                let items: Vec<&str> = value
+                    .split(separator.as_str())
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty())
+                    .collect();
+                record.push_field(&items.join(", "));
+            } else {
+                record.push_field(value);
+            }
+        }
+        record
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_presets_are_valid() {
+        for preset in builtin_import_presets() {
+            for target in preset.columns.values().chain(preset.values.keys()) {
+                assert!(CSV_COLUMNS.contains(&target.as_str()), "{}", target);
+            }
+        }
+    }
+
+    #[test]
+    fn test_validate_import_presets() {
+        let preset = |name: &str, target: &str| ImportPreset {
+            name: name.to_string(),
+            columns: [(" Name ".to_string(), target.to_string())].into(),
+            builtin: true,
+            ..Default::default()
+        };
+
+        let cleaned = validate_import_presets(vec![preset(" Mine ", "sort title")]).unwrap();
+        assert_eq!(cleaned[0].name, "Mine");
+        assert_eq!(cleaned[0].columns["Name"], "Sort Title");
+        assert!(!cleaned[0].builtin);
+
+        let with_values = ImportPreset {
+            list_separator: Some(" ".to_string()),
+            values: [(
+                "watched".to_string(),
+                [(" Seen ".to_string(), "true".to_string())].into(),
+            )]
+            .into(),
+            ..preset("Mine", "Title")
+        };
+        let cleaned = validate_import_presets(vec![with_values]).unwrap();
+        assert_eq!(cleaned[0].values["Watched"]["seen"], "true");
+        assert_eq!(cleaned[0].list_separator, None);
+
+        assert!(validate_import_presets(vec![preset("libib", "Title")]).is_err());
+        assert!(validate_import_presets(vec![preset("Mine", "Nonsense")]).is_err());
+        assert!(validate_import_presets(vec![preset("A", "Title"), preset("a", "Title")]).is_err());
+        assert!(validate_import_presets(vec![preset("", "Title")]).is_err());
+    }
+
+    #[test]
+    fn test_column_mapping() {
+        let preset = find_import_preset(Vec::new(), "libib").unwrap();
+        let headers = StringRecord::from(vec!["\u{feff}title", "ean_isbn13", "upc_isbn10", "esrb"]);
+        let mapping = ColumnMapping::new(&headers, &preset).unwrap();
+        assert_eq!(
+            mapping.headers(),
+            &StringRecord::from(vec!["Title", "Barcode"])
+        );
+
+        let row = StringRecord::from(vec!["Alien", "", "0123", "R"]);
+        assert_eq!(
+            mapping.apply(&row),
+            StringRecord::from(vec!["Alien", "0123"])
+        );
+
+        let headers = StringRecord::from(vec!["tags", "creators", "status", "rating", "notes"]);
+        let mapping = ColumnMapping::new(&headers, &preset).unwrap();
+        let row = StringRecord::from(vec![
+            "sci-fi,horror",
+            "Ridley Scott",
+            "Completed",
+            "4",
+            "a,b",
+        ]);
+        assert_eq!(
+            mapping.apply(&row),
+            StringRecord::from(vec!["sci-fi, horror", "Ridley Scott", "true", "8", "a,b"])
+        );
+
+        let unrelated = StringRecord::from(vec!["foo", "bar"]);
+        assert!(ColumnMapping::new(&unrelated, &preset).is_err());
+    }
+}
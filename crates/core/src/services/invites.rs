@@ -0,0 +1,217 @@
+use chrono::Utc;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::db::DbPool;
+use crate::error::{Error, Result};
+use crate::models::{CreateInvite, CreatedInvite, Invite};
+
+const MAX_NOTE: usize = 100;
+const MAX_USES: i64 = 1000;
+
+/// Invite codes for registering while open registration is disabled, e.g.
+/// for a family server. Each code creates up to `max_uses` accounts with the
+/// invite's role until it expires or is deleted.
+pub struct InviteService {
+    pool: DbPool,
+}
+
+impl InviteService {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    fn hash(code: &str) -> String {
+        format!("{:x}", Sha256::digest(code.trim().as_bytes()))
+    }
+
+    pub async fn create(&self, created_by: Uuid, input: CreateInvite) -> Result<CreatedInvite> {
+        let max_uses = input.max_uses.unwrap_or(1);
+        if !(1..=MAX_USES).contains(&max_uses) {
+            return Err(Error::Validation(format!(
+                "Uses must be between 1 and {}",
+                MAX_USES
+            )));
+        }
+        let note = input
+            .note
+            .as_deref()
+            .map(str::trim)
+            .filter(|n| !n.is_empty());
+        if note.is_some_and(|n| n.chars().count() > MAX_NOTE) {
+            return Err(Error::Validation(format!(
+                "Note must be at most {} characters",
+                MAX_NOTE
+            )));
+        }
+        if input.expires_at.is_some_and(|at| at <= Utc::now()) {
+            return Err(Error::Validation(
+                "Expiry must be in the future".to_string(),
+            ));
+        }
+
+        let code = Uuid::new_v4().simple().to_string();
+        let id = Uuid::new_v4();
+        sqlx::query(
+            r#"
+            INSERT INTO invites (id, code_hash, role, max_uses, note, created_by, created_at, expires_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id)
+        .bind(Self::hash(&code))
+        .bind(&input.role)
+        .bind(max_uses)
+        .bind(note)
+        .bind(created_by)
+        .bind(Utc::now().to_rfc3339())
+        .bind(input.expires_at.map(|at| at.to_rfc3339()))
+        .execute(&self.pool)
+        .await?;
+
+        let invite = sqlx::query_as::<_, Invite>("SELECT * FROM invites WHERE id = ?")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(CreatedInvite { code, invite })
+    }
+
+    /// All invites, newest first, including used up and expired ones
+    pub async fn list(&self) -> Result<Vec<Invite>> {
+        let invites = sqlx::query_as::<_, Invite>("SELECT * FROM invites ORDER BY created_at DESC")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(invites)
+    }
+
+    /// Revoke an invite; accounts created with it stay
+    pub async fn delete(&self, id: Uuid) -> Result<()> {
+        let result = sqlx::query("DELETE FROM invites WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(Error::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Use the invite of `code` once. Unknown, used up and expired codes are
+    /// all rejected the same way.
+    pub async fn redeem(&self, code: &str) -> Result<Invite> {
+        sqlx::query_as::<_, Invite>(
+            r#"
+            UPDATE invites SET uses = uses + 1
+            WHERE code_hash = ? AND uses < max_uses
+              AND (expires_at IS NULL OR julianday(expires_at) > julianday(?))
+            RETURNING *
+            "#,
+        )
+        .bind(Self::hash(code))
+        .bind(Utc::now().to_rfc3339())
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| Error::Validation("Invalid or expired invite".to_string()))
+    }
+
+    /// Give back a use when the registration it was redeemed for failed
+    pub async fn release(&self, id: Uuid) -> Result<()> {
+        sqlx::query("UPDATE invites SET uses = uses - 1 WHERE id = ? AND uses > 0")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::UserRole;
+    use crate::test_helpers::{create_test_db_with_users, fixtures};
+
+    #[tokio::test]
+    async fn test_invite_uses() {
+        let pool = create_test_db_with_users().await;
+        let invites = InviteService::new(pool);
+
+        let created = invites
+            .create(
+                fixtures::test_admin_id(),
+                CreateInvite {
+                    role: UserRole::Admin,
+                    max_uses: Some(2),
+                    note: Some("  Family  ".to_string()),
+                    expires_at: None,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(created.invite.note.as_deref(), Some("Family"));
+        assert_eq!(created.invite.uses, 0);
+
+        let invite = invites.redeem(&created.code).await.unwrap();
+        assert_eq!(invite.role, UserRole::Admin);
+        assert_eq!(invite.uses, 1);
+
+        // A failed registration gives its use back
+        invites.release(invite.id).await.unwrap();
+        invites.redeem(&created.code).await.unwrap();
+        invites.redeem(&created.code).await.unwrap();
+        assert!(matches!(
+            invites.redeem(&created.code).await,
+            Err(Error::Validation(_))
+        ));
+        assert!(invites.redeem("made-up").await.is_err());
+
+        assert_eq!(invites.list().await.unwrap()[0].uses, 2);
+        invites.delete(invite.id).await.unwrap();
+        assert!(invites.list().await.unwrap().is_empty());
+        assert!(matches!(
+            invites.delete(invite.id).await,
+            Err(Error::NotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_invite_validation_and_expiry() {
+        let pool = create_test_db_with_users().await;
+        let invites = InviteService::new(pool.clone());
+        let admin = fixtures::test_admin_id();
+
+        for input in [
+            CreateInvite {
+                max_uses: Some(0),
+                ..Default::default()
+            },
+            CreateInvite {
+                expires_at: Some(Utc::now() - chrono::Duration::hours(1)),
+                ..Default::default()
+            },
+            CreateInvite {
+                note: Some("x".repeat(MAX_NOTE + 1)),
+                ..Default::default()
+            },
+        ] {
+            assert!(matches!(
+                invites.create(admin, input).await,
+                Err(Error::Validation(_))
+            ));
+        }
+
+        let created = invites
+            .create(admin, CreateInvite::default())
+            .await
+            .unwrap();
+        assert_eq!(created.invite.role, UserRole::User);
+        assert_eq!(created.invite.max_uses, 1);
+
+        // Expired since it was created
+        sqlx::query("UPDATE invites SET expires_at = ?")
+            .bind((Utc::now() - chrono::Duration::minutes(1)).to_rfc3339())
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(invites.redeem(&created.code).await.is_err());
+    }
+}
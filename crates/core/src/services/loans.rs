@@ -0,0 +1,524 @@
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use uuid::Uuid;
+
+use crate::db::DbPool;
+use crate::error::{Error, Result};
+use crate::models::{
+    ApproveBorrowRequest, BorrowRequest, BorrowRequestStatus, CreateBorrowRequest, LoanReminder,
+    LoanReminderKind, local_date,
+};
+
+const SELECT_BORROW_REQUEST: &str = r#"
+    SELECT r.id, r.user_id, r.movie_id, m.title, r.requester_name, r.requester_contact,
+        r.message, r.status, r.created_at, r.decided_at
+    FROM borrow_requests r
+    JOIN movies m ON m.id = r.movie_id
+"#;
+
+const MAX_REQUEST_NAME: usize = 100;
+const MAX_REQUEST_CONTACT: usize = 200;
+const MAX_REQUEST_MESSAGE: usize = 1000;
+
+/// Loan reminders and borrow requests
+pub struct LoanService {
+    pool: DbPool,
+}
+
+impl LoanService {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Reminders due at `now` that haven't been sent yet; `mark_reminder_sent`
+    /// once one is delivered. Each loan gets one `DueSoon` reminder within the
+    /// owner's lead time and one `Overdue` reminder after the due date; changing
+    /// the due date starts over. Due dates are compared with the date in the
+    /// owner's timezone.
+    pub async fn due_reminders(&self, now: DateTime<Utc>) -> Result<Vec<LoanReminder>> {
+        type LoanRow = (
+            Uuid,
+            Uuid,
+            String,
+            String,
+            NaiveDate,
+            i32,
+            Option<String>,
+            String,
+            String,
+        );
+        let loans: Vec<LoanRow> = sqlx::query_as(
+            r#"
+            SELECT m.id, m.user_id, m.title, m.lent_to, m.lent_due, u.loan_reminder_days,
+                u.timezone, u.username, u.email
+            FROM movies m
+            JOIN users u ON u.id = m.user_id
+            WHERE u.loan_reminders = 1
+              AND m.lent_to IS NOT NULL AND m.lent_to != ''
+              AND m.lent_due IS NOT NULL
+              AND m.disposition IS NULL
+            ORDER BY m.lent_due
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut reminders = Vec::new();
+        for (movie_id, user_id, title, lent_to, lent_due, days_before, timezone, username, email) in
+            loans
+        {
+            let today = local_date(now, timezone.as_deref());
+            let kind = if lent_due < today {
+                LoanReminderKind::Overdue
+            } else if lent_due <= today + Duration::days(days_before.into()) {
+                LoanReminderKind::DueSoon
+            } else {
+                continue;
+            };
+
+            let sent: Option<i64> = sqlx::query_scalar(
+                "SELECT 1 FROM loan_reminders WHERE movie_id = ? AND kind = ? AND lent_due = ?",
+            )
+            .bind(movie_id)
+            .bind(kind.as_str())
+            .bind(lent_due)
+            .fetch_optional(&self.pool)
+            .await?;
+
+            if sent.is_none() {
+                reminders.push(LoanReminder {
+                    kind,
+                    user_id,
+                    movie_id,
+                    title,
+                    lent_to,
+                    lent_due,
+                    username,
+                    email,
+                });
+            }
+        }
+
+        Ok(reminders)
+    }
+
+    /// Record that `reminder` reached its owner, so it isn't sent again
+    pub async fn mark_reminder_sent(
+        &self,
+        reminder: &LoanReminder,
+        sent_at: DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO loan_reminders (movie_id, kind, lent_due, sent_at)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(reminder.movie_id)
+        .bind(reminder.kind.as_str())
+        .bind(reminder.lent_due)
+        .bind(sent_at.to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// A guest asks to borrow one of `owner_id`'s movies. Only owned movies in
+    /// the collection can be requested.
+    pub async fn request_borrow(
+        &self,
+        owner_id: Uuid,
+        movie_id: Uuid,
+        input: CreateBorrowRequest,
+    ) -> Result<BorrowRequest> {
+        let name = input.name.trim();
+        if name.is_empty() || name.chars().count() > MAX_REQUEST_NAME {
+            return Err(Error::Validation(format!(
+                "Name must be 1 to {} characters",
+                MAX_REQUEST_NAME
+            )));
+        }
+        let message = input
+            .message
+            .as_deref()
+            .map(str::trim)
+            .filter(|m| !m.is_empty());
+        if message.is_some_and(|m| m.chars().count() > MAX_REQUEST_MESSAGE) {
+            return Err(Error::Validation(format!(
+                "Message must be at most {} characters",
+                MAX_REQUEST_MESSAGE
+            )));
+        }
+        let contact = input
+            .contact
+            .as_deref()
+            .map(str::trim)
+            .filter(|c| !c.is_empty());
+        if contact.is_some_and(|c| c.chars().count() > MAX_REQUEST_CONTACT) {
+            return Err(Error::Validation(format!(
+                "Contact must be at most {} characters",
+                MAX_REQUEST_CONTACT
+            )));
+        }
+
+        let available: Option<i64> = sqlx::query_scalar(
+            r#"
+            SELECT 1 FROM movies
+            WHERE id = ? AND user_id = ? AND disposition IS NULL
+              AND COALESCE(status, 'owned') = 'owned'
+            "#,
+        )
+        .bind(movie_id)
+        .bind(owner_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        if available.is_none() {
+            return Err(Error::NotFound);
+        }
+
+        let pending: Option<i64> = sqlx::query_scalar(
+            r#"
+            SELECT 1 FROM borrow_requests
+            WHERE movie_id = ? AND requester_name = ? COLLATE NOCASE AND status = 'pending'
+            "#,
+        )
+        .bind(movie_id)
+        .bind(name)
+        .fetch_optional(&self.pool)
+        .await?;
+        if pending.is_some() {
+            return Err(Error::Duplicate(
+                "This movie has already been requested".into(),
+            ));
+        }
+
+        let id = Uuid::new_v4();
+        sqlx::query(
+            r#"
+            INSERT INTO borrow_requests
+                (id, user_id, movie_id, requester_name, requester_contact, message, status, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id)
+        .bind(owner_id)
+        .bind(movie_id)
+        .bind(name)
+        .bind(contact)
+        .bind(message)
+        .bind(BorrowRequestStatus::Pending)
+        .bind(Utc::now().to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+
+        self.get_borrow_request(owner_id, id).await
+    }
+
+    pub async fn get_borrow_request(&self, user_id: Uuid, id: Uuid) -> Result<BorrowRequest> {
+        let query = format!("{} WHERE r.id = ? AND r.user_id = ?", SELECT_BORROW_REQUEST);
+        sqlx::query_as::<_, BorrowRequest>(&query)
+            .bind(id)
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or(Error::NotFound)
+    }
+
+    /// Requests for the user's movies, newest first
+    pub async fn borrow_requests(
+        &self,
+        user_id: Uuid,
+        status: Option<BorrowRequestStatus>,
+    ) -> Result<Vec<BorrowRequest>> {
+        let mut query = format!("{} WHERE r.user_id = ?", SELECT_BORROW_REQUEST);
+        if status.is_some() {
+            query.push_str(" AND r.status = ?");
+        }
+        query.push_str(" ORDER BY r.created_at DESC");
+
+        let mut q = sqlx::query_as::<_, BorrowRequest>(&query).bind(user_id);
+        if let Some(status) = status {
+            q = q.bind(status);
+        }
+        Ok(q.fetch_all(&self.pool).await?)
+    }
+
+    /// Lend the movie to the requester. Fails while it is lent to someone else.
+    pub async fn approve_borrow_request(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+        input: ApproveBorrowRequest,
+    ) -> Result<BorrowRequest> {
+        let request = self.pending_borrow_request(user_id, id).await?;
+
+        let lent_to: Option<String> =
+            sqlx::query_scalar("SELECT lent_to FROM movies WHERE id = ? AND user_id = ?")
+                .bind(request.movie_id)
+                .bind(user_id)
+                .fetch_one(&self.pool)
+                .await?;
+        if let Some(lent_to) = lent_to.filter(|l| !l.trim().is_empty()) {
+            return Err(Error::Validation(format!(
+                "'{}' is currently lent to {}",
+                request.title, lent_to
+            )));
+        }
+
+        let now = Utc::now().to_rfc3339();
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            r#"
+            UPDATE movies SET lent_to = ?, lent_due = ?, updated_at = ?, updated_by = ?
+            WHERE id = ? AND user_id = ?
+            "#,
+        )
+        .bind(&request.requester_name)
+        .bind(input.due)
+        .bind(&now)
+        .bind(user_id)
+        .bind(request.movie_id)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("UPDATE borrow_requests SET status = ?, decided_at = ? WHERE id = ?")
+            .bind(BorrowRequestStatus::Approved)
+            .bind(&now)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        self.get_borrow_request(user_id, id).await
+    }
+
+    pub async fn decline_borrow_request(&self, user_id: Uuid, id: Uuid) -> Result<BorrowRequest> {
+        let _ = self.pending_borrow_request(user_id, id).await?;
+
+        sqlx::query("UPDATE borrow_requests SET status = ?, decided_at = ? WHERE id = ?")
+            .bind(BorrowRequestStatus::Declined)
+            .bind(Utc::now().to_rfc3339())
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        self.get_borrow_request(user_id, id).await
+    }
+
+    async fn pending_borrow_request(&self, user_id: Uuid, id: Uuid) -> Result<BorrowRequest> {
+        let request = self.get_borrow_request(user_id, id).await?;
+        if request.status != BorrowRequestStatus::Pending {
+            return Err(Error::Validation(
+                "This request has already been answered".into(),
+            ));
+        }
+        Ok(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{CreateMovie, LoanReminderSettings, UpdateMovie};
+    use crate::services::{AuthService, MovieService};
+    use crate::test_helpers::{create_test_db_with_users, fixtures};
+
+    async fn lend(movies: &MovieService, title: &str, due: NaiveDate) -> Uuid {
+        let user_id = fixtures::test_user_id();
+        let movie = movies
+            .create(
+                user_id,
+                CreateMovie {
+                    barcode: None,
+                    tmdb_id: None,
+                    title: title.to_string(),
+                    original_title: None,
+                    disc_type: None,
+                    production_year: None,
+                },
+            )
+            .await
+            .unwrap();
+        movies
+            .update(
+                user_id,
+                movie.id,
+                UpdateMovie {
+                    lent_to: Some("Anna".to_string()),
+                    lent_due: Some(due),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        movie.id
+    }
+
+    /// Due reminders, marked as sent as if delivered
+    async fn take_due_reminders(loans: &LoanService, now: DateTime<Utc>) -> Vec<LoanReminder> {
+        let reminders = loans.due_reminders(now).await.unwrap();
+        for reminder in &reminders {
+            loans.mark_reminder_sent(reminder, now).await.unwrap();
+        }
+        reminders
+    }
+
+    #[tokio::test]
+    async fn test_due_reminders() {
+        let pool = create_test_db_with_users().await;
+        let movies = MovieService::new(pool.clone());
+        let loans = LoanService::new(pool.clone());
+        let today = NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+        let noon = |date: NaiveDate| date.and_hms_opt(12, 0, 0).unwrap().and_utc();
+
+        let soon = lend(&movies, "Casablanca", today + Duration::days(2)).await;
+        let late = lend(&movies, "Vertigo", today - Duration::days(1)).await;
+        lend(&movies, "Psycho", today + Duration::days(20)).await;
+
+        // Reminders that weren't delivered come up again
+        assert_eq!(loans.due_reminders(noon(today)).await.unwrap().len(), 2);
+        let reminders = take_due_reminders(&loans, noon(today)).await;
+        assert_eq!(reminders.len(), 2);
+        assert_eq!(reminders[0].movie_id, late);
+        assert_eq!(reminders[0].kind, LoanReminderKind::Overdue);
+        assert_eq!(reminders[1].movie_id, soon);
+        assert_eq!(reminders[1].kind, LoanReminderKind::DueSoon);
+
+        // Each reminder is sent once
+        assert!(take_due_reminders(&loans, noon(today)).await.is_empty());
+
+        // ... until the loan becomes overdue
+        let later = today + Duration::days(3);
+        let reminders = take_due_reminders(&loans, noon(later)).await;
+        assert_eq!(reminders.len(), 1);
+        assert_eq!(reminders[0].movie_id, soon);
+        assert_eq!(reminders[0].kind, LoanReminderKind::Overdue);
+
+        // Late in the evening UTC it is already the next day east of Greenwich
+        let auth = AuthService::new(pool, "secret".to_string());
+        let rear_window = lend(&movies, "Rear Window", today + Duration::days(10)).await;
+        let evening = (today + Duration::days(6))
+            .and_hms_opt(23, 30, 0)
+            .unwrap()
+            .and_utc();
+        assert!(take_due_reminders(&loans, evening).await.is_empty());
+        auth.update_user_timezone(fixtures::test_user_id(), Some("Europe/Berlin".to_string()))
+            .await
+            .unwrap();
+        let reminders = take_due_reminders(&loans, evening).await;
+        assert_eq!(reminders.len(), 1);
+        assert_eq!(reminders[0].movie_id, rear_window);
+        assert_eq!(reminders[0].kind, LoanReminderKind::DueSoon);
+
+        // Users can turn reminders off
+        auth.update_user_loan_reminders(
+            fixtures::test_user_id(),
+            LoanReminderSettings {
+                enabled: false,
+                days_before: 30,
+            },
+        )
+        .await
+        .unwrap();
+        assert!(take_due_reminders(&loans, noon(later)).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_borrow_requests() {
+        let pool = create_test_db_with_users().await;
+        let movies = MovieService::new(pool.clone());
+        let loans = LoanService::new(pool);
+        let user_id = fixtures::test_user_id();
+        let due = NaiveDate::from_ymd_opt(2026, 4, 1).unwrap();
+
+        let movie = movies
+            .create(
+                user_id,
+                CreateMovie {
+                    barcode: None,
+                    tmdb_id: None,
+                    title: "Fargo".to_string(),
+                    original_title: None,
+                    disc_type: None,
+                    production_year: None,
+                },
+            )
+            .await
+            .unwrap();
+        let request = |name: &str| CreateBorrowRequest {
+            name: name.to_string(),
+            contact: Some("  ".to_string()),
+            message: Some("Bring it to the party?".to_string()),
+        };
+
+        let first = loans
+            .request_borrow(user_id, movie.id, request("Lena"))
+            .await
+            .unwrap();
+        assert_eq!(first.status, BorrowRequestStatus::Pending);
+        assert_eq!(first.title, "Fargo");
+        assert_eq!(first.requester_contact, None);
+        assert!(matches!(
+            loans
+                .request_borrow(user_id, movie.id, request("lena"))
+                .await,
+            Err(Error::Duplicate(_))
+        ));
+        assert!(matches!(
+            loans.request_borrow(user_id, movie.id, request(" ")).await,
+            Err(Error::Validation(_))
+        ));
+        let long_contact = CreateBorrowRequest {
+            contact: Some("x".repeat(201)),
+            ..request("Mia")
+        };
+        assert!(matches!(
+            loans.request_borrow(user_id, movie.id, long_contact).await,
+            Err(Error::Validation(_))
+        ));
+        // Only the owner's movies
+        assert!(matches!(
+            loans
+                .request_borrow(fixtures::test_admin_id(), movie.id, request("Lena"))
+                .await,
+            Err(Error::NotFound)
+        ));
+        let second = loans
+            .request_borrow(user_id, movie.id, request("Tom"))
+            .await
+            .unwrap();
+
+        let pending = loans
+            .borrow_requests(user_id, Some(BorrowRequestStatus::Pending))
+            .await
+            .unwrap();
+        assert_eq!(pending.len(), 2);
+
+        let approved = loans
+            .approve_borrow_request(user_id, first.id, ApproveBorrowRequest { due: Some(due) })
+            .await
+            .unwrap();
+        assert_eq!(approved.status, BorrowRequestStatus::Approved);
+        assert!(approved.decided_at.is_some());
+        let lent = movies.get_by_id(user_id, movie.id).await.unwrap();
+        assert_eq!(lent.lent_to.as_deref(), Some("Lena"));
+        assert_eq!(lent.lent_due, Some(due));
+
+        // Already lent to Lena
+        assert!(matches!(
+            loans
+                .approve_borrow_request(user_id, second.id, ApproveBorrowRequest::default())
+                .await,
+            Err(Error::Validation(_))
+        ));
+        let declined = loans
+            .decline_borrow_request(user_id, second.id)
+            .await
+            .unwrap();
+        assert_eq!(declined.status, BorrowRequestStatus::Declined);
+        assert!(matches!(
+            loans.decline_borrow_request(user_id, second.id).await,
+            Err(Error::Validation(_))
+        ));
+    }
+}
@@ -0,0 +1,115 @@
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::error::{Error, Result};
+use crate::parsing::filename::{self, VIDEO_EXTENSIONS};
+
+/// Upper bound for files returned by one scan
+pub const MAX_MEDIA_FILES: usize = 5000;
+
+/// A video file found in a media folder
+#[derive(Debug, Clone, Serialize)]
+pub struct MediaFile {
+    pub path: String,
+    pub file_name: String,
+    pub size: u64,
+    pub title: String,
+    pub year: Option<i32>,
+    pub resolution: Option<String>,
+    pub source: Option<String>,
+    pub edition: Option<String>,
+}
+
+fn is_video_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| VIDEO_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+}
+
+/// Collect the video files below `root`, sorted by path. Hidden files and
+/// directories are skipped. Blocking, call from a blocking task.
+pub fn scan_media_folder(root: &Path, recursive: bool) -> Result<Vec<MediaFile>> {
+    if !root.is_dir() {
+        return Err(Error::Validation(format!(
+            "Not a directory: {}",
+            root.display()
+        )));
+    }
+
+    let mut files = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let entries = std::fs::read_dir(&dir)
+            .map_err(|e| Error::Internal(format!("Failed to read {}: {}", dir.display(), e)))?;
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') {
+                continue;
+            }
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+
+            if file_type.is_dir() {
+                if recursive {
+                    pending.push(path);
+                }
+            } else if is_video_file(&path) {
+                let parsed = filename::parse(&name);
+                files.push(MediaFile {
+                    path: path.display().to_string(),
+                    file_name: name,
+                    size: entry.metadata().map(|m| m.len()).unwrap_or(0),
+                    title: parsed.title,
+                    year: parsed.year,
+                    resolution: parsed.resolution,
+                    source: parsed.source,
+                    edition: parsed.edition,
+                });
+
+                if files.len() >= MAX_MEDIA_FILES {
+                    return Err(Error::Validation(format!(
+                        "Folder contains more than {} video files, please pick a subfolder",
+                        MAX_MEDIA_FILES
+                    )));
+                }
+            }
+        }
+    }
+
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_media_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        let sub = dir.path().join("Filme");
+        std::fs::create_dir(&sub).unwrap();
+        std::fs::write(dir.path().join("Heat.1995.720p.mkv"), b"x").unwrap();
+        std::fs::write(sub.join("Alien (1979).MP4"), b"xy").unwrap();
+        std::fs::write(sub.join("Alien (1979).nfo"), b"").unwrap();
+        std::fs::write(dir.path().join(".hidden.mkv"), b"").unwrap();
+
+        let files = scan_media_folder(dir.path(), true).unwrap();
+        let titles: Vec<&str> = files.iter().map(|f| f.title.as_str()).collect();
+        assert_eq!(titles, vec!["Alien", "Heat"]);
+        assert_eq!(files[0].size, 2);
+
+        let top_level = scan_media_folder(dir.path(), false).unwrap();
+        assert_eq!(top_level.len(), 1);
+    }
+
+    #[test]
+    fn test_scan_rejects_missing_folder() {
+        assert!(scan_media_folder(Path::new("/does/not/exist"), true).is_err());
+    }
+}
@@ -1,17 +1,104 @@
+pub mod activity;
+pub mod api_keys;
 pub mod auth;
+pub mod barcode_cache;
+pub mod blob_store;
 pub mod collections;
+pub mod contacts;
+pub mod currency;
+pub mod demo;
+pub mod diagnostics;
+pub mod digest;
+pub mod discord;
+pub mod dvdprofiler;
 pub mod ean;
+pub mod households;
+pub mod images;
 pub mod import;
+pub mod import_presets;
+pub mod invites;
+pub mod jwt;
+pub mod loans;
+pub mod mail;
+pub mod media_folder;
 pub mod movies;
+pub mod notifications;
+pub mod ocr;
+pub mod omdb;
+pub mod planner;
+pub mod poster_retries;
+pub mod push;
+pub mod repair;
+pub mod search;
+pub mod seed;
 pub mod series;
 pub mod settings;
+pub mod shares;
+pub mod sync;
 pub mod tmdb;
+pub mod tmdb_cache;
+pub mod tombstones;
+pub mod valuation;
+pub mod watches;
 
+pub use activity::ActivityService;
+pub use api_keys::ApiKeyService;
 pub use auth::AuthService;
+pub use barcode_cache::BarcodeCacheService;
+pub use blob_store::{
+    BlobStore, FsBlobStore, S3BlobStore, SqliteBlobStore, blob_store_from_settings,
+};
 pub use collections::CollectionService;
-pub use ean::EanService;
+pub use contacts::ContactService;
+pub use currency::{CurrencyService, ExchangeRates};
+pub use demo::DemoService;
+pub use diagnostics::{DatabaseDiagnostics, DiagnosticsService};
+pub use digest::DigestService;
+pub use discord::{DiscordEvent, DiscordService};
+pub use ean::{
+    AmazonCredentials, AmazonProvider, BarcodeProvider, EanService, OpenGtinDbProvider,
+    UpcItemDbProvider, barcode_providers_from_settings,
+};
+pub use households::HouseholdService;
+pub use images::{ImageService, image_hash, set_blob_store, storage_kind};
 pub use import::ImportService;
-pub use movies::MovieService;
+pub use import_presets::{
+    CSV_COLUMNS, builtin_import_presets, find_import_preset, validate_import_presets,
+};
+pub use invites::InviteService;
+pub use jwt::{JwtKeys, jwt_algorithm};
+pub use loans::LoanService;
+pub use mail::{
+    EmailMessage, LogMailer, MailFuture, MailService, Mailer, SmtpMailer, mailer_from_settings,
+};
+pub use media_folder::{MediaFile, scan_media_folder};
+pub use movies::{MovieService, listing_text};
+pub use notifications::NotificationService;
+pub use ocr::{
+    CoverMatch, GoogleVisionProvider, OcrLine, OcrProvider, OcrService, TesseractProvider,
+    ocr_provider_from_settings, rank_cover_matches, title_candidates,
+};
+pub use omdb::{OmdbMovie, OmdbService};
+pub use planner::PlannerService;
+pub use poster_retries::PosterRetryService;
+pub use push::{
+    FcmProvider, GatewayProvider, PushMessage, PushOutcome, PushProvider,
+    push_provider_from_settings,
+};
+pub use repair::{RepairCheck, RepairIssue, RepairReport, RepairService};
+pub use search::SearchService;
+pub use seed::{SeedOptions, SeedReport, SeedService};
 pub use series::SeriesService;
-pub use settings::{SettingSource, SettingStatus, SettingsService};
-pub use tmdb::{TmdbCollectionOverview, TmdbMovie, TmdbService};
+pub use settings::{
+    SettingSource, SettingStatus, SettingsService, SettingsSnapshot, SettingsWatch,
+};
+pub use shares::ShareService;
+pub use sync::{SyncService, TokenCipher};
+pub use tmdb::{
+    TmdbCollection, TmdbCollectionOverview, TmdbImage, TmdbImages, TmdbMovie, TmdbOptions,
+    TmdbService,
+};
+pub use tmdb_cache::{TmdbCacheMode, TmdbCacheStats};
+pub use tombstones::TombstoneService;
+pub use valuation::ValuationService;
+pub use watches::WatchService;
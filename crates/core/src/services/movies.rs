@@ -1,9 +1,268 @@
+use std::collections::BTreeMap;
+use std::sync::LazyLock;
+
 use chrono::Utc;
+use regex::Regex;
 use uuid::Uuid;
 
 use crate::db::DbPool;
+use crate::db::query::Conditions;
 use crate::error::{Error, Result};
-use crate::models::{CreateMovie, Movie, MovieFilter, UpdateMovie};
+use crate::models::{
+    AcquireMovie, ActivityAction, BulkUpdateMovies, BulkUpdateResult, ConditionGrade, CreateMovie,
+    CurrencyTotals, DiscCondition, DisposeMovie, Disposition, EntityType, FixOrphans,
+    IncludedExtra, MOVIE_FIELDS, MOVIE_SUMMARY_FIELDS, MissingMetadataEntry, MissingMetadataReport,
+    Movie, MovieDefaults, MovieDetail, MovieFields, MovieFilter, MovieStats, MovieStatus,
+    MovieSuggestion, MovieSummary, OrphanFixAction, OrphanFixResult, OrphanGroup, RecentView,
+    RegionCode, RegionReport, RegionReportEntry, UpdateMovie, ValueReport, included_extras,
+    normalize_condition, normalize_currency, normalize_movie_status, normalize_region_codes,
+    normalize_video_standard, parse_region_codes, region_plays_on,
+};
+use crate::parsing::title::fold_title;
+use crate::services::ImageService;
+use crate::services::activity::{record_activity, record_changes};
+use crate::services::households::HOUSEHOLD_USERS;
+use crate::services::search::refresh_search_titles;
+use crate::services::tombstones::record_deletions;
+
+/// Columns of a [`MovieDetail`]: everything but the poster bytes
+static MOVIE_DETAIL_COLUMNS: LazyLock<String> =
+    LazyLock::new(|| select_columns(&MOVIE_FIELDS.iter().map(|(f, _, _)| *f).collect::<Vec<_>>()));
+
+/// Columns of a [`MovieSummary`]
+static MOVIE_SUMMARY_COLUMNS: LazyLock<String> =
+    LazyLock::new(|| select_columns(MOVIE_SUMMARY_FIELDS));
+
+/// SELECT list of the given `MOVIE_FIELDS`; computed fields are named after
+/// the field, columns keep their name (`group` is read from `movie_group`)
+fn select_columns(fields: &[&str]) -> String {
+    fields
+        .iter()
+        .map(|name| {
+            let (_, expr, _) = MOVIE_FIELDS
+                .iter()
+                .find(|(field, _, _)| field == name)
+                .unwrap_or_else(|| panic!("unknown movie field {}", name));
+            if expr.starts_with('(') {
+                format!("{} AS {}", expr, name)
+            } else {
+                expr.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Columns of a list, with `include_household` plus the owner's username
+fn list_columns(columns: &str, filter: &MovieFilter) -> String {
+    if filter.include_household == Some(true) {
+        format!(
+            "{}, (SELECT username FROM users u WHERE u.id = movies.user_id) AS owner_name",
+            columns
+        )
+    } else {
+        columns.to_string()
+    }
+}
+
+/// Conditions of `count` and `list`: the user's movies, with `include_household`
+/// also those of the other household members, matching the filter
+fn movie_conditions(user_id: Uuid, filter: &MovieFilter) -> Result<Conditions> {
+    let mut conditions = Conditions::new();
+    if filter.include_household == Some(true) {
+        conditions.push_with(
+            format!("(user_id = ? OR user_id IN ({}))", HOUSEHOLD_USERS),
+            [user_id, user_id],
+        );
+    } else {
+        conditions.push_bind("user_id = ?", user_id);
+    }
+
+    if let Some(ref search) = filter.search {
+        let pattern = format!("%{}%", search);
+        // Titles also match in folded form, so "Koenig" finds "König"
+        let folded = fold_title(search);
+        if folded.is_empty() {
+            conditions.push_with(
+                "(title LIKE ? OR original_title LIKE ? OR director LIKE ?)",
+                [pattern.clone(), pattern.clone(), pattern],
+            );
+        } else {
+            conditions.push_with(
+                "(title LIKE ? OR original_title LIKE ? OR director LIKE ? OR search_title LIKE ?)",
+                [
+                    pattern.clone(),
+                    pattern.clone(),
+                    pattern,
+                    format!("%{}%", folded),
+                ],
+            );
+        }
+    }
+    if let Some(ref genre) = filter.genre {
+        conditions.push_bind("genres LIKE ?", format!("%{}%", genre));
+    }
+    if let Some(ref disc_type) = filter.disc_type {
+        conditions.push_bind("disc_type = ?", disc_type.as_str());
+    }
+    if let Some(watched) = filter.watched {
+        conditions.push_bind("watched = ?", watched);
+    }
+    if let Some(favorites) = filter.favorites {
+        conditions.push_bind("favorite = ?", favorites);
+    }
+    if let Some(is_collection) = filter.is_collection {
+        conditions.push_bind("is_collection = ?", is_collection);
+    }
+    if filter.exclude_collection_children == Some(true) {
+        conditions.push("parent_collection_id IS NULL");
+    }
+    if let Some(year_from) = filter.year_from {
+        conditions.push_bind("production_year >= ?", year_from);
+    }
+    if let Some(year_to) = filter.year_to {
+        conditions.push_bind("production_year <= ?", year_to);
+    }
+    if let Some(ref region) = filter.region {
+        conditions.push_bind(
+            "(',' || region_codes || ',' LIKE ? OR ',' || region_codes || ',' LIKE '%,R0,%')",
+            region_filter_pattern(region)?,
+        );
+    }
+    if let Some(ref video_standard) = filter.video_standard {
+        conditions.push_bind(
+            "video_standard = ?",
+            normalize_video_standard(video_standard).map_err(Error::Validation)?,
+        );
+    }
+    if let Some(condition) = filter.condition {
+        conditions.push_bind("condition = ?", condition.as_str());
+    }
+    if let Some(min_condition) = filter.min_condition {
+        let grades = grades_at_least(min_condition);
+        let placeholders = vec!["?"; grades.len()].join(", ");
+        conditions.push_with(
+            format!("condition IN ({})", placeholders),
+            grades.iter().map(|grade| grade.as_str()),
+        );
+    }
+    if let Some(extra) = filter.extra {
+        push_extra_condition(&mut conditions, extra, false);
+    }
+    if let Some(extra) = filter.missing_extra {
+        push_extra_condition(&mut conditions, extra, true);
+    }
+    if let Some(added_by) = filter.added_by {
+        conditions.push_bind("added_by = ?", added_by);
+    }
+    if let Some(updated_by) = filter.updated_by {
+        conditions.push_bind("updated_by = ?", updated_by);
+    }
+    if let Some(updated_since) = filter.updated_since {
+        conditions.push_bind(
+            "julianday(updated_at) > julianday(?)",
+            updated_since.to_rfc3339(),
+        );
+    }
+    if let Some(ref tag) = filter.tag {
+        conditions.push_bind(
+            "',' || REPLACE(tags, ', ', ',') || ',' LIKE ?",
+            format!("%,{},%", tag.trim()),
+        );
+    }
+    if let Some(collection_id) = filter.collection_id {
+        conditions.push_bind(
+            "id IN (SELECT movie_id FROM collection_items WHERE collection_id = ?)",
+            collection_id,
+        );
+    }
+    match filter.disposed {
+        Some(true) => conditions.push("disposition IS NOT NULL"),
+        Some(false) => conditions.push("disposition IS NULL"),
+        None => {}
+    }
+    if let Some(status) = filter.status {
+        conditions.push_bind("COALESCE(status, 'owned') = ?", status.as_str());
+    }
+    if let Some(availability) = filter.availability {
+        conditions.push_bind("availability = ?", availability.as_str());
+    }
+
+    Ok(conditions)
+}
+
+/// Number of movies kept in a user's view history
+const VIEW_HISTORY_LIMIT: i64 = 100;
+
+/// Full movie row including the poster bytes from the shared image store
+const SELECT_MOVIE_WITH_POSTER: &str =
+    "SELECT m.*, i.data AS poster_data FROM movies m LEFT JOIN images i ON i.hash = m.poster_hash";
+
+/// Percentage of owned parts, `None` if the collection has no parts
+pub fn completeness_percent(owned: i32, total: i32) -> Option<f64> {
+    if total <= 0 {
+        return None;
+    }
+    Some((owned.min(total) as f64 / total as f64 * 1000.0).round() / 10.0)
+}
+
+/// Short description of a disc for marketplace listings (title, edition,
+/// condition). Labels are German when `german` is set.
+pub fn listing_text(movie: &Movie, german: bool) -> String {
+    let mut lines = Vec::new();
+
+    let mut heading = movie.title.clone();
+    if let Some(year) = movie.production_year {
+        heading.push_str(&format!(" ({})", year));
+    }
+    lines.push(heading);
+
+    if let Some(original) = movie
+        .original_title
+        .as_ref()
+        .filter(|o| !o.is_empty() && **o != movie.title)
+    {
+        let label = if german {
+            "Originaltitel"
+        } else {
+            "Original title"
+        };
+        lines.push(format!("{}: {}", label, original));
+    }
+
+    let fields = [
+        ("Format", "Format", &movie.disc_type),
+        ("Edition", "Edition", &movie.edition),
+        ("Zustand", "Condition", &movie.condition),
+        ("Region", "Region", &movie.region_codes),
+        ("Altersfreigabe", "Rating", &movie.rating),
+        ("Sprachen", "Audio", &movie.audio_tracks),
+    ];
+    for (de, en, value) in fields {
+        if let Some(value) = value.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+            lines.push(format!("{}: {}", if german { de } else { en }, value));
+        }
+    }
+
+    if movie.slip_cover {
+        lines.push(
+            if german {
+                "Mit Schuber"
+            } else {
+                "Includes slipcover"
+            }
+            .to_string(),
+        );
+    }
+
+    lines.join("\n")
+}
+
+/// Number of child titles used to name a recreated collection
+const REGROUP_TITLE_PARTS: usize = 3;
+
+/// Most movies a single bulk edit may change
+const BULK_UPDATE_LIMIT: usize = 1000;
 
 /// Check if a barcode is a placeholder/invalid value that shouldn't be used for duplicate detection
 fn is_placeholder_barcode(barcode: &str) -> bool {
@@ -30,17 +289,54 @@ impl MovieService {
         Self { pool }
     }
 
+    /// Create a movie. Disc type, location, media type and currency fall back
+    /// to the user's movie defaults.
     pub async fn create(&self, user_id: Uuid, input: CreateMovie) -> Result<Movie> {
+        let defaults = self.movie_defaults(user_id).await?;
+        self.insert(user_id, input, defaults).await
+    }
+
+    /// Create a movie exactly as given, e.g. when restoring a backup
+    pub async fn create_without_defaults(
+        &self,
+        user_id: Uuid,
+        input: CreateMovie,
+    ) -> Result<Movie> {
+        self.insert(user_id, input, MovieDefaults::default()).await
+    }
+
+    async fn movie_defaults(&self, user_id: Uuid) -> Result<MovieDefaults> {
+        let defaults = sqlx::query_as::<_, MovieDefaults>(
+            r#"
+            SELECT default_disc_type AS disc_type, default_location AS location,
+                default_media_type AS media_type, default_currency AS currency
+            FROM users WHERE id = ?
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(defaults.unwrap_or_default())
+    }
+
+    async fn insert(
+        &self,
+        user_id: Uuid,
+        input: CreateMovie,
+        defaults: MovieDefaults,
+    ) -> Result<Movie> {
         let id = Uuid::new_v4();
         let now = Utc::now();
 
         sqlx::query(
             r#"
             INSERT INTO movies (
-                id, user_id, barcode, tmdb_id, title, original_title, 
-                disc_type, production_year, created_at, updated_at
+                id, user_id, barcode, tmdb_id, title, search_title, original_title, 
+                disc_type, production_year, location, media_type, currency,
+                added_by, updated_by, created_at, updated_at
             )
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(id)
@@ -48,138 +344,154 @@ impl MovieService {
         .bind(&input.barcode)
         .bind(input.tmdb_id)
         .bind(&input.title)
+        .bind(fold_title(&input.title))
         .bind(&input.original_title)
-        .bind(&input.disc_type)
+        .bind(input.disc_type.or(defaults.disc_type))
         .bind(input.production_year)
+        .bind(defaults.location)
+        .bind(defaults.media_type)
+        .bind(defaults.currency)
+        .bind(user_id)
+        .bind(user_id)
         .bind(now.to_rfc3339())
         .bind(now.to_rfc3339())
         .execute(&self.pool)
         .await?;
 
-        self.get_by_id(user_id, id).await
+        let movie = self.get_by_id(user_id, id).await?;
+        record_activity(
+            &self.pool,
+            user_id,
+            ActivityAction::Create,
+            EntityType::Movie,
+            id,
+            Some(&movie.title),
+            None,
+        )
+        .await?;
+        Ok(movie)
     }
 
     pub async fn get_by_id(&self, user_id: Uuid, id: Uuid) -> Result<Movie> {
-        sqlx::query_as::<_, Movie>("SELECT * FROM movies WHERE id = ? AND user_id = ?")
-            .bind(id)
-            .bind(user_id)
-            .fetch_optional(&self.pool)
-            .await?
-            .ok_or(Error::NotFound)
+        let movie = sqlx::query_as::<_, Movie>(&format!(
+            "{} WHERE m.id = ? AND m.user_id = ?",
+            SELECT_MOVIE_WITH_POSTER
+        ))
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or(Error::NotFound)?;
+        self.load_external_poster(movie).await
     }
 
-    pub async fn count(&self, user_id: Uuid, filter: &MovieFilter) -> Result<i64> {
-        let mut query = String::from("SELECT COUNT(*) as count FROM movies WHERE user_id = ?");
-
-        if filter.search.is_some() {
-            query.push_str(" AND (title LIKE ? OR original_title LIKE ? OR director LIKE ?)");
-        }
-
-        if filter.genre.is_some() {
-            query.push_str(" AND genres LIKE ?");
-        }
-
-        if filter.disc_type.is_some() {
-            query.push_str(" AND disc_type = ?");
-        }
-
-        if filter.watched.is_some() {
-            query.push_str(" AND watched = ?");
-        }
-
-        if filter.year_from.is_some() {
-            query.push_str(" AND production_year >= ?");
-        }
-
-        if filter.year_to.is_some() {
-            query.push_str(" AND production_year <= ?");
-        }
-
-        let mut q = sqlx::query_scalar::<_, i64>(&query).bind(user_id);
-
-        if let Some(ref search) = filter.search {
-            let search_pattern = format!("%{}%", search);
-            q = q
-                .bind(search_pattern.clone())
-                .bind(search_pattern.clone())
-                .bind(search_pattern);
-        }
-
-        if let Some(ref genre) = filter.genre {
-            q = q.bind(format!("%{}%", genre));
-        }
-
-        if let Some(ref disc_type) = filter.disc_type {
-            q = q.bind(disc_type);
-        }
-
-        if let Some(watched) = filter.watched {
-            q = q.bind(watched);
-        }
-
-        if let Some(year_from) = filter.year_from {
-            q = q.bind(year_from);
-        }
-
-        if let Some(year_to) = filter.year_to {
-            q = q.bind(year_to);
+    /// Posters in an external blob store join in as empty data; fetch them from the store
+    async fn load_external_poster(&self, mut movie: Movie) -> Result<Movie> {
+        if movie
+            .poster_data
+            .as_ref()
+            .is_some_and(|data| data.is_empty())
+        {
+            movie.poster_data = self.get_poster_data(movie.user_id, movie.id).await?;
         }
+        Ok(movie)
+    }
 
-        let count = q.fetch_one(&self.pool).await?;
+    pub async fn count(&self, user_id: Uuid, filter: &MovieFilter) -> Result<i64> {
+        self.refresh_search_titles_for(user_id, filter).await?;
+        let conditions = movie_conditions(user_id, filter)?;
+        let query = format!("SELECT COUNT(*) FROM movies WHERE {}", conditions.sql());
+        let (count,): (i64,) = conditions
+            .bind(sqlx::query_as(&query))
+            .fetch_one(&self.pool)
+            .await?;
         Ok(count)
     }
 
-    pub async fn list(&self, user_id: Uuid, filter: MovieFilter) -> Result<Vec<Movie>> {
-        let limit = filter.limit; // None = no limit (return all)
-        let offset = filter.offset.unwrap_or(0);
-        let sort_by = filter.sort_by.unwrap_or_else(|| "title".to_string());
-        let sort_order = filter.sort_order.unwrap_or_else(|| "asc".to_string());
-
-        // Build dynamic query string first
-        // Exclude poster_data from list queries for performance (it's large BLOB data)
-        let mut query = String::from(
-            "SELECT id, user_id, collection_number, barcode, tmdb_id, imdb_id, title, original_title, \
-            sort_title, personal_title, personal_sort_title, description, tagline, production_year, \
-            release_date, running_time, director, actors, production_companies, production_countries, \
-            studios, rating, personal_rating, disc_type, media_type, discs, region_codes, video_standard, \
-            aspect_ratio, audio_tracks, subtitles, is_3d, mastered_in_4k, genres, categories, tags, \
-            movie_group, is_collection, parent_collection_id, watched, digital_copies, status, condition, slip_cover, cover_type, edition, \
-            extra_features, purchase_date, price, currency, purchase_place, value_date, value_price, \
-            value_currency, lent_to, lent_due, location, notes, budget, revenue, spoken_languages, \
-            added_date, created_at, updated_at FROM movies WHERE user_id = ?",
-        );
-
+    /// Folded titles of renamed movies are recomputed before a search uses them
+    async fn refresh_search_titles_for(&self, user_id: Uuid, filter: &MovieFilter) -> Result<()> {
         if filter.search.is_some() {
-            query.push_str(" AND (title LIKE ? OR original_title LIKE ? OR director LIKE ?)");
-        }
-
-        if filter.genre.is_some() {
-            query.push_str(" AND genres LIKE ?");
-        }
-
-        if filter.disc_type.is_some() {
-            query.push_str(" AND disc_type = ?");
+            // Household members' movies are searched too
+            let owner = (filter.include_household != Some(true)).then_some(user_id);
+            refresh_search_titles(&self.pool, owner).await?;
         }
+        Ok(())
+    }
 
-        if filter.watched.is_some() {
-            query.push_str(" AND watched = ?");
+    /// Full movies matching the filter (without the poster bytes)
+    pub async fn list(&self, user_id: Uuid, filter: MovieFilter) -> Result<Vec<MovieDetail>> {
+        let columns = list_columns(&MOVIE_DETAIL_COLUMNS, &filter);
+        let mut movies: Vec<MovieDetail> = self.fetch_filtered(&columns, user_id, filter).await?;
+        // Only other members' movies are marked
+        for movie in movies.iter_mut().filter(|m| m.user_id == user_id) {
+            movie.owner_name = None;
         }
+        Ok(movies)
+    }
 
-        if filter.is_collection.is_some() {
-            query.push_str(" AND is_collection = ?");
+    /// Like `list`, but only with the fields lists show
+    pub async fn list_summaries(
+        &self,
+        user_id: Uuid,
+        filter: MovieFilter,
+    ) -> Result<Vec<MovieSummary>> {
+        let columns = list_columns(&MOVIE_SUMMARY_COLUMNS, &filter);
+        let mut movies: Vec<MovieSummary> = self.fetch_filtered(&columns, user_id, filter).await?;
+        for movie in movies.iter_mut().filter(|m| m.user_id == user_id) {
+            movie.owner_name = None;
         }
+        Ok(movies)
+    }
 
-        if filter.exclude_collection_children == Some(true) {
-            query.push_str(" AND parent_collection_id IS NULL");
+    /// Like `list`, but only with the given fields (names from `MOVIE_FIELDS`)
+    pub async fn list_fields(
+        &self,
+        user_id: Uuid,
+        filter: MovieFilter,
+        fields: &[&str],
+    ) -> Result<Vec<MovieFields>> {
+        let columns = fields
+            .iter()
+            .map(|name| {
+                MOVIE_FIELDS
+                    .iter()
+                    .find(|(field, _, _)| field == name)
+                    .map(|(field, expr, _)| format!("{} AS \"{}\"", expr, field))
+                    .ok_or_else(|| Error::Validation(format!("Unknown field: {}", name)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if columns.is_empty() {
+            return Err(Error::Validation("No fields given".to_string()));
         }
 
-        if filter.year_from.is_some() {
-            query.push_str(" AND production_year >= ?");
-        }
+        self.fetch_filtered(&columns.join(", "), user_id, filter)
+            .await
+    }
 
-        if filter.year_to.is_some() {
-            query.push_str(" AND production_year <= ?");
-        }
+    /// Select `columns` of the user's movies matching the filter, sorted and paged
+    async fn fetch_filtered<T>(
+        &self,
+        columns: &str,
+        user_id: Uuid,
+        filter: MovieFilter,
+    ) -> Result<Vec<T>>
+    where
+        T: for<'r> sqlx::FromRow<'r, sqlx::sqlite::SqliteRow> + Send + Unpin,
+    {
+        let limit = filter.limit; // None = no limit (return all)
+        let offset = filter.offset.unwrap_or(0);
+        let sort_by = filter
+            .sort_by
+            .clone()
+            .unwrap_or_else(|| "title".to_string());
+        let sort_order = filter
+            .sort_order
+            .clone()
+            .unwrap_or_else(|| "asc".to_string());
+
+        self.refresh_search_titles_for(user_id, &filter).await?;
+        let conditions = movie_conditions(user_id, &filter)?;
+        let mut query = format!("SELECT {} FROM movies WHERE {}", columns, conditions.sql());
 
         // Whitelist allowed sort columns
         let allowed_sorts = [
@@ -188,6 +500,7 @@ impl MovieService {
             "production_year",
             "created_at",
             "personal_rating",
+            "collection_completeness",
         ];
         let sort_column = if allowed_sorts.contains(&sort_by.as_str()) {
             sort_by
@@ -216,42 +529,7 @@ impl MovieService {
             query.push_str(&format!(" ORDER BY {}", order_clause));
         }
 
-        // Now bind all parameters in the correct order
-        let mut q = sqlx::query_as::<_, Movie>(&query).bind(user_id);
-
-        if let Some(ref search) = filter.search {
-            let search_pattern = format!("%{}%", search);
-            q = q
-                .bind(search_pattern.clone())
-                .bind(search_pattern.clone())
-                .bind(search_pattern);
-        }
-
-        if let Some(ref genre) = filter.genre {
-            q = q.bind(format!("%{}%", genre));
-        }
-
-        if let Some(ref disc_type) = filter.disc_type {
-            q = q.bind(disc_type);
-        }
-
-        if let Some(watched) = filter.watched {
-            q = q.bind(watched);
-        }
-
-        if let Some(is_collection) = filter.is_collection {
-            q = q.bind(is_collection);
-        }
-
-        // Note: exclude_collection_children doesn't need a bind (IS NULL check)
-
-        if let Some(year_from) = filter.year_from {
-            q = q.bind(year_from);
-        }
-
-        if let Some(year_to) = filter.year_to {
-            q = q.bind(year_to);
-        }
+        let q = conditions.bind(sqlx::query_as::<_, T>(&query));
 
         // Only bind limit/offset if limit is specified
         let rows = if let Some(lim) = limit {
@@ -264,16 +542,19 @@ impl MovieService {
 
     pub async fn update(&self, user_id: Uuid, id: Uuid, input: UpdateMovie) -> Result<Movie> {
         // Verify ownership first
-        let _ = self.get_by_id(user_id, id).await?;
+        let before = self.get_by_id(user_id, id).await?;
 
         // Update each field individually if provided
         if let Some(ref title) = input.title {
-            sqlx::query("UPDATE movies SET title = ? WHERE id = ? AND user_id = ?")
-                .bind(title)
-                .bind(id)
-                .bind(user_id)
-                .execute(&self.pool)
-                .await?;
+            sqlx::query(
+                "UPDATE movies SET title = ?, search_title = ? WHERE id = ? AND user_id = ?",
+            )
+            .bind(title)
+            .bind(fold_title(title))
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
         }
 
         if let Some(ref description) = input.description {
@@ -294,6 +575,15 @@ impl MovieService {
                 .await?;
         }
 
+        if let Some(favorite) = input.favorite {
+            sqlx::query("UPDATE movies SET favorite = ? WHERE id = ? AND user_id = ?")
+                .bind(favorite)
+                .bind(id)
+                .bind(user_id)
+                .execute(&self.pool)
+                .await?;
+        }
+
         if let Some(rating) = input.personal_rating {
             sqlx::query("UPDATE movies SET personal_rating = ? WHERE id = ? AND user_id = ?")
                 .bind(rating)
@@ -312,6 +602,74 @@ impl MovieService {
                 .await?;
         }
 
+        if let Some(ref sort_title) = input.sort_title {
+            sqlx::query("UPDATE movies SET sort_title = ? WHERE id = ? AND user_id = ?")
+                .bind(sort_title)
+                .bind(id)
+                .bind(user_id)
+                .execute(&self.pool)
+                .await?;
+        }
+
+        if let Some(ref rating) = input.rating {
+            sqlx::query("UPDATE movies SET rating = ? WHERE id = ? AND user_id = ?")
+                .bind(rating)
+                .bind(id)
+                .bind(user_id)
+                .execute(&self.pool)
+                .await?;
+        }
+
+        if let Some(ref tags) = input.tags {
+            sqlx::query("UPDATE movies SET tags = ? WHERE id = ? AND user_id = ?")
+                .bind(tags)
+                .bind(id)
+                .bind(user_id)
+                .execute(&self.pool)
+                .await?;
+        }
+
+        if let Some(missing) = input.missing {
+            sqlx::query("UPDATE movies SET missing = ? WHERE id = ? AND user_id = ?")
+                .bind(missing)
+                .bind(id)
+                .bind(user_id)
+                .execute(&self.pool)
+                .await?;
+        }
+
+        // The loan history trigger links the loan to the contact by name
+        let lent_to = match input.lent_to_contact {
+            Some(contact_id) => Some(
+                sqlx::query_scalar::<_, String>(
+                    "SELECT name FROM contacts WHERE id = ? AND user_id = ?",
+                )
+                .bind(contact_id)
+                .bind(user_id)
+                .fetch_optional(&self.pool)
+                .await?
+                .ok_or(Error::NotFound)?,
+            ),
+            None => input.lent_to.clone(),
+        };
+        if let Some(ref lent_to) = lent_to {
+            sqlx::query("UPDATE movies SET lent_to = ? WHERE id = ? AND user_id = ?")
+                .bind(lent_to)
+                .bind(id)
+                .bind(user_id)
+                .execute(&self.pool)
+                .await?;
+        }
+
+        if let Some(lent_due) = input.lent_due {
+            sqlx::query("UPDATE movies SET lent_due = ? WHERE id = ? AND user_id = ?")
+                .bind(lent_due)
+                .bind(id)
+                .bind(user_id)
+                .execute(&self.pool)
+                .await?;
+        }
+
         if let Some(ref notes) = input.notes {
             sqlx::query("UPDATE movies SET notes = ? WHERE id = ? AND user_id = ?")
                 .bind(notes)
@@ -411,6 +769,24 @@ impl MovieService {
                 .await?;
         }
 
+        if let Some(imdb_rating) = input.imdb_rating {
+            sqlx::query("UPDATE movies SET imdb_rating = ? WHERE id = ? AND user_id = ?")
+                .bind(imdb_rating)
+                .bind(id)
+                .bind(user_id)
+                .execute(&self.pool)
+                .await?;
+        }
+
+        if let Some(rotten_tomatoes) = input.rotten_tomatoes {
+            sqlx::query("UPDATE movies SET rotten_tomatoes = ? WHERE id = ? AND user_id = ?")
+                .bind(rotten_tomatoes)
+                .bind(id)
+                .bind(user_id)
+                .execute(&self.pool)
+                .await?;
+        }
+
         if let Some(ref disc_type) = input.disc_type {
             sqlx::query("UPDATE movies SET disc_type = ? WHERE id = ? AND user_id = ?")
                 .bind(disc_type)
@@ -420,41 +796,330 @@ impl MovieService {
                 .await?;
         }
 
-        if let Some(is_collection) = input.is_collection {
-            sqlx::query("UPDATE movies SET is_collection = ? WHERE id = ? AND user_id = ?")
-                .bind(is_collection)
+        if let Some(ref region_codes) = input.region_codes {
+            let region_codes = normalize_region_codes(region_codes).map_err(Error::Validation)?;
+            sqlx::query("UPDATE movies SET region_codes = ? WHERE id = ? AND user_id = ?")
+                .bind(region_codes)
                 .bind(id)
                 .bind(user_id)
                 .execute(&self.pool)
                 .await?;
         }
 
-        if let Some(ref parent_collection_id) = input.parent_collection_id {
-            sqlx::query("UPDATE movies SET parent_collection_id = ? WHERE id = ? AND user_id = ?")
-                .bind(parent_collection_id)
+        if let Some(ref video_standard) = input.video_standard {
+            let video_standard =
+                normalize_video_standard(video_standard).map_err(Error::Validation)?;
+            sqlx::query("UPDATE movies SET video_standard = ? WHERE id = ? AND user_id = ?")
+                .bind(video_standard)
                 .bind(id)
                 .bind(user_id)
                 .execute(&self.pool)
                 .await?;
         }
 
-        if let Some(ref poster_data) = input.poster_data {
-            sqlx::query("UPDATE movies SET poster_data = ? WHERE id = ? AND user_id = ?")
-                .bind(poster_data)
+        if let Some(ref status) = input.status {
+            let status = normalize_movie_status(status).map_err(Error::Validation)?;
+            sqlx::query("UPDATE movies SET status = ? WHERE id = ? AND user_id = ?")
+                .bind(status.as_str())
                 .bind(id)
                 .bind(user_id)
                 .execute(&self.pool)
                 .await?;
         }
 
-        // Update timestamp
-        sqlx::query("UPDATE movies SET updated_at = ? WHERE id = ? AND user_id = ?")
-            .bind(Utc::now().to_rfc3339())
-            .bind(id)
-            .bind(user_id)
-            .execute(&self.pool)
-            .await?;
-
+        if let Some(ref condition) = input.condition {
+            let condition = normalize_condition(condition).map_err(Error::Validation)?;
+            sqlx::query("UPDATE movies SET condition = ? WHERE id = ? AND user_id = ?")
+                .bind(condition)
+                .bind(id)
+                .bind(user_id)
+                .execute(&self.pool)
+                .await?;
+        }
+
+        if let Some(slip_cover) = input.slip_cover {
+            sqlx::query("UPDATE movies SET slip_cover = ? WHERE id = ? AND user_id = ?")
+                .bind(slip_cover)
+                .bind(id)
+                .bind(user_id)
+                .execute(&self.pool)
+                .await?;
+        }
+
+        if let Some(is_collection) = input.is_collection {
+            sqlx::query("UPDATE movies SET is_collection = ? WHERE id = ? AND user_id = ?")
+                .bind(is_collection)
+                .bind(id)
+                .bind(user_id)
+                .execute(&self.pool)
+                .await?;
+        }
+
+        if let Some(ref parent_collection_id) = input.parent_collection_id {
+            sqlx::query("UPDATE movies SET parent_collection_id = ? WHERE id = ? AND user_id = ?")
+                .bind(parent_collection_id)
+                .bind(id)
+                .bind(user_id)
+                .execute(&self.pool)
+                .await?;
+        }
+
+        if let Some(disc_number) = input.disc_number {
+            sqlx::query("UPDATE movies SET disc_number = ? WHERE id = ? AND user_id = ?")
+                .bind(disc_number)
+                .bind(id)
+                .bind(user_id)
+                .execute(&self.pool)
+                .await?;
+        }
+
+        if let Some(ref poster_data) = input.poster_data {
+            let hash = ImageService::new(self.pool.clone())
+                .store(poster_data)
+                .await?;
+            sqlx::query("UPDATE movies SET poster_hash = ? WHERE id = ? AND user_id = ?")
+                .bind(hash)
+                .bind(id)
+                .bind(user_id)
+                .execute(&self.pool)
+                .await?;
+        }
+
+        // Update timestamp and editor
+        sqlx::query(
+            "UPDATE movies SET updated_at = ?, updated_by = ? WHERE id = ? AND user_id = ?",
+        )
+        .bind(Utc::now().to_rfc3339())
+        .bind(user_id)
+        .bind(id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        let movie = self.get_by_id(user_id, id).await?;
+        record_changes(
+            &self.pool,
+            user_id,
+            EntityType::Movie,
+            id,
+            &movie.title,
+            &before,
+            &movie,
+        )
+        .await?;
+        Ok(movie)
+    }
+
+    /// Flip the favorite flag of a movie
+    pub async fn toggle_favorite(&self, user_id: Uuid, id: Uuid) -> Result<Movie> {
+        let before = self.get_by_id(user_id, id).await?;
+        let result = sqlx::query(
+            "UPDATE movies SET favorite = NOT favorite, updated_at = ?, updated_by = ? \
+             WHERE id = ? AND user_id = ?",
+        )
+        .bind(Utc::now().to_rfc3339())
+        .bind(user_id)
+        .bind(id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(Error::NotFound);
+        }
+
+        let movie = self.get_by_id(user_id, id).await?;
+        record_changes(
+            &self.pool,
+            user_id,
+            EntityType::Movie,
+            id,
+            &movie.title,
+            &before,
+            &movie,
+        )
+        .await?;
+        Ok(movie)
+    }
+
+    /// Apply the same fields to many movies in one transaction. Returns the ids that
+    /// were changed; ids the user doesn't own are skipped.
+    pub async fn bulk_update(
+        &self,
+        user_id: Uuid,
+        input: BulkUpdateMovies,
+    ) -> Result<BulkUpdateResult> {
+        if input.ids.len() > BULK_UPDATE_LIMIT {
+            return Err(Error::Validation(format!(
+                "At most {} movies can be edited at once",
+                BULK_UPDATE_LIMIT
+            )));
+        }
+        let fields = input.fields;
+        let condition = match fields.condition {
+            Some(ref condition) => Some(normalize_condition(condition).map_err(Error::Validation)?),
+            None => None,
+        };
+        let status = match fields.status {
+            Some(ref status) => Some(normalize_movie_status(status).map_err(Error::Validation)?),
+            None => None,
+        };
+
+        // Bound below in the same order
+        let mut sets = vec!["updated_at = ?", "updated_by = ?"];
+        let columns = [
+            ("location = ?", fields.location.is_some()),
+            ("watched = ?", fields.watched.is_some()),
+            ("favorite = ?", fields.favorite.is_some()),
+            ("disc_type = ?", fields.disc_type.is_some()),
+            ("tags = ?", fields.tags.is_some()),
+            ("status = ?", status.is_some()),
+            ("condition = ?", condition.is_some()),
+            ("lent_to = ?", fields.lent_to.is_some()),
+        ];
+        sets.extend(
+            columns
+                .iter()
+                .filter(|(_, set)| *set)
+                .map(|(column, _)| *column),
+        );
+        if sets.len() == 2 {
+            return Err(Error::Validation("No fields to update".to_string()));
+        }
+        if input.ids.is_empty() {
+            return Ok(BulkUpdateResult {
+                updated: Vec::new(),
+            });
+        }
+
+        let placeholders = vec!["?"; input.ids.len()].join(", ");
+        let mut tx = self.pool.begin().await?;
+
+        let select = format!(
+            "SELECT {} FROM movies WHERE user_id = ? AND id IN ({})",
+            *MOVIE_DETAIL_COLUMNS, placeholders
+        );
+        let mut q = sqlx::query_as::<_, Movie>(&select).bind(user_id);
+        for id in &input.ids {
+            q = q.bind(id);
+        }
+        let before = q.fetch_all(&mut *tx).await?;
+        let updated: Vec<Uuid> = before.iter().map(|m| m.id).collect();
+
+        let update = format!(
+            "UPDATE movies SET {} WHERE user_id = ? AND id IN ({})",
+            sets.join(", "),
+            placeholders
+        );
+        let mut q = sqlx::query(&update)
+            .bind(Utc::now().to_rfc3339())
+            .bind(user_id);
+        if let Some(ref location) = fields.location {
+            q = q.bind(location);
+        }
+        if let Some(watched) = fields.watched {
+            q = q.bind(watched);
+        }
+        if let Some(favorite) = fields.favorite {
+            q = q.bind(favorite);
+        }
+        if let Some(ref disc_type) = fields.disc_type {
+            q = q.bind(disc_type);
+        }
+        if let Some(ref tags) = fields.tags {
+            q = q.bind(tags);
+        }
+        if let Some(status) = status {
+            q = q.bind(status.as_str());
+        }
+        if let Some(ref condition) = condition {
+            q = q.bind(condition);
+        }
+        if let Some(ref lent_to) = fields.lent_to {
+            q = q.bind(lent_to);
+        }
+        q = q.bind(user_id);
+        for id in &input.ids {
+            q = q.bind(id);
+        }
+        q.execute(&mut *tx).await?;
+
+        let select = format!(
+            "SELECT {} FROM movies WHERE id = ? AND user_id = ?",
+            *MOVIE_DETAIL_COLUMNS
+        );
+        for old in &before {
+            let new = sqlx::query_as::<_, Movie>(&select)
+                .bind(old.id)
+                .bind(user_id)
+                .fetch_one(&mut *tx)
+                .await?;
+            record_changes(
+                &mut *tx,
+                user_id,
+                EntityType::Movie,
+                old.id,
+                &new.title,
+                old,
+                &new,
+            )
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(BulkUpdateResult { updated })
+    }
+
+    /// Count how many of the given TMDB movie ids the user owns
+    pub async fn count_owned_tmdb_ids(&self, user_id: Uuid, tmdb_ids: &[i64]) -> Result<i32> {
+        if tmdb_ids.is_empty() {
+            return Ok(0);
+        }
+
+        let placeholders = vec!["?"; tmdb_ids.len()].join(", ");
+        let query = format!(
+            "SELECT COUNT(DISTINCT tmdb_id) FROM movies WHERE user_id = ? AND tmdb_id IN ({})",
+            placeholders
+        );
+
+        let mut q = sqlx::query_scalar::<_, i64>(&query).bind(user_id);
+        for tmdb_id in tmdb_ids {
+            q = q.bind(tmdb_id);
+        }
+
+        Ok(q.fetch_one(&self.pool).await? as i32)
+    }
+
+    /// Store the link to a TMDB collection and the resulting completeness of a box set
+    pub async fn update_collection_completeness(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+        tmdb_collection_id: i64,
+        owned_parts: i32,
+        total_parts: i32,
+    ) -> Result<Movie> {
+        sqlx::query(
+            r#"
+            UPDATE movies SET
+                tmdb_collection_id = ?,
+                collection_owned_parts = ?,
+                collection_total_parts = ?,
+                collection_completeness = ?,
+                updated_at = ?
+            WHERE id = ? AND user_id = ?
+            "#,
+        )
+        .bind(tmdb_collection_id)
+        .bind(owned_parts)
+        .bind(total_parts)
+        .bind(completeness_percent(owned_parts, total_parts))
+        .bind(Utc::now().to_rfc3339())
+        .bind(id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
         self.get_by_id(user_id, id).await
     }
 
@@ -464,65 +1129,271 @@ impl MovieService {
         id: Uuid,
         poster_data: Option<Vec<u8>>,
     ) -> Result<Movie> {
+        let poster_hash = match poster_data {
+            Some(ref data) => Some(ImageService::new(self.pool.clone()).store(data).await?),
+            None => None,
+        };
+
         sqlx::query(
-            "UPDATE movies SET poster_data = ?, updated_at = ? WHERE id = ? AND user_id = ?",
+            "UPDATE movies SET poster_hash = ?, updated_at = ? WHERE id = ? AND user_id = ?",
         )
-        .bind(&poster_data)
+        .bind(&poster_hash)
         .bind(Utc::now().to_rfc3339())
         .bind(id)
         .bind(user_id)
         .execute(&self.pool)
         .await?;
 
+        // A poster from anywhere ends pending download retries
+        if poster_hash.is_some() {
+            sqlx::query("DELETE FROM poster_retries WHERE movie_id = ? AND user_id = ?")
+                .bind(id)
+                .bind(user_id)
+                .execute(&self.pool)
+                .await?;
+        }
+
         self.get_by_id(user_id, id).await
     }
 
-    pub async fn get_movie_poster_data(&self, user_id: Uuid, id: Uuid) -> Result<Option<Vec<u8>>> {
-        // Verify movie belongs to user
-        let _movie = self.get_by_id(user_id, id).await?;
+    /// Store or clear the movie's backdrop; `None` removes it
+    pub async fn update_movie_backdrop_data(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+        backdrop_data: Option<Vec<u8>>,
+    ) -> Result<Movie> {
+        let backdrop_hash = match backdrop_data {
+            Some(ref data) => Some(ImageService::new(self.pool.clone()).store(data).await?),
+            None => None,
+        };
 
-        let data = sqlx::query_scalar::<_, Option<Vec<u8>>>(
-            "SELECT poster_data FROM movies WHERE id = ? AND user_id = ?",
+        let result = sqlx::query(
+            "UPDATE movies SET backdrop_hash = ?, updated_at = ? WHERE id = ? AND user_id = ?",
         )
+        .bind(&backdrop_hash)
+        .bind(Utc::now().to_rfc3339())
         .bind(id)
         .bind(user_id)
-        .fetch_optional(&self.pool)
+        .execute(&self.pool)
         .await?;
+        if result.rows_affected() == 0 {
+            return Err(Error::NotFound);
+        }
+
+        self.get_by_id(user_id, id).await
+    }
+
+    /// The backdrop bytes, `NotFound` if the movie isn't the user's
+    pub async fn get_backdrop_data(&self, user_id: Uuid, id: Uuid) -> Result<Option<Vec<u8>>> {
+        let hash = sqlx::query_scalar::<_, Option<String>>(
+            "SELECT backdrop_hash FROM movies WHERE id = ? AND user_id = ?",
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or(Error::NotFound)?;
 
-        Ok(data.flatten())
+        ImageService::new(self.pool.clone())
+            .get_optional(hash.as_deref())
+            .await
+    }
+
+    pub async fn get_movie_poster_data(&self, user_id: Uuid, id: Uuid) -> Result<Option<Vec<u8>>> {
+        // Verify movie belongs to user
+        let movie = self.get_by_id(user_id, id).await?;
+        Ok(movie.poster_data)
     }
 
     pub async fn get_movie_poster_data_public(&self, id: Uuid) -> Result<Option<Vec<u8>>> {
         // Public method to get poster without user verification
-        let data =
-            sqlx::query_scalar::<_, Option<Vec<u8>>>("SELECT poster_data FROM movies WHERE id = ?")
+        let hash =
+            sqlx::query_scalar::<_, Option<String>>("SELECT poster_hash FROM movies WHERE id = ?")
                 .bind(id)
                 .fetch_optional(&self.pool)
                 .await?;
 
-        Ok(data.flatten())
+        ImageService::new(self.pool.clone())
+            .get_optional(hash.flatten().as_deref())
+            .await
     }
 
     pub async fn delete(&self, user_id: Uuid, id: Uuid) -> Result<()> {
-        let result = sqlx::query("DELETE FROM movies WHERE id = ? AND user_id = ?")
+        let mut tx = self.pool.begin().await?;
+        let title: String =
+            sqlx::query_scalar("SELECT title FROM movies WHERE id = ? AND user_id = ?")
+                .bind(id)
+                .bind(user_id)
+                .fetch_optional(&mut *tx)
+                .await?
+                .ok_or(Error::NotFound)?;
+
+        sqlx::query("DELETE FROM movies WHERE id = ? AND user_id = ?")
             .bind(id)
             .bind(user_id)
-            .execute(&self.pool)
+            .execute(&mut *tx)
             .await?;
 
-        if result.rows_affected() == 0 {
-            return Err(Error::NotFound);
-        }
+        record_deletions(&mut tx, user_id, EntityType::Movie, &[id]).await?;
+        record_activity(
+            &mut *tx,
+            user_id,
+            ActivityAction::Delete,
+            EntityType::Movie,
+            id,
+            Some(&title),
+            None,
+        )
+        .await?;
+        tx.commit().await?;
 
         Ok(())
     }
 
+    /// Find movies linked to a parent collection that doesn't exist, grouped by the missing parent
+    pub async fn find_orphans(&self, user_id: Uuid) -> Result<Vec<OrphanGroup>> {
+        let movies = sqlx::query_as::<_, Movie>(
+            r#"
+            SELECT m.* FROM movies m
+            WHERE m.user_id = ?
+              AND m.parent_collection_id IS NOT NULL
+              AND NOT EXISTS (
+                  SELECT 1 FROM movies p
+                  WHERE p.id = m.parent_collection_id AND p.user_id = m.user_id
+              )
+            ORDER BY m.title COLLATE NOCASE
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut groups: Vec<OrphanGroup> = Vec::new();
+        for movie in movies {
+            let Some(parent_id) = movie.parent_collection_id else {
+                continue;
+            };
+            match groups
+                .iter_mut()
+                .find(|g| g.parent_collection_id == parent_id)
+            {
+                Some(group) => group.movies.push(movie),
+                None => groups.push(OrphanGroup {
+                    parent_collection_id: parent_id,
+                    movies: vec![movie],
+                }),
+            }
+        }
+
+        Ok(groups)
+    }
+
+    /// Fix orphaned collection children by clearing the link or regrouping them
+    /// into a newly created collection
+    pub async fn fix_orphans(&self, user_id: Uuid, input: FixOrphans) -> Result<OrphanFixResult> {
+        let mut result = OrphanFixResult::default();
+
+        let groups = self.find_orphans(user_id).await?.into_iter().filter(|g| {
+            input
+                .parent_ids
+                .as_ref()
+                .is_none_or(|ids| ids.contains(&g.parent_collection_id))
+        });
+
+        for group in groups {
+            let new_parent = match input.action {
+                OrphanFixAction::Unlink => None,
+                OrphanFixAction::Regroup => {
+                    let mut title = group
+                        .movies
+                        .iter()
+                        .take(REGROUP_TITLE_PARTS)
+                        .map(|m| m.title.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    if group.movies.len() > REGROUP_TITLE_PARTS {
+                        title.push_str(", …");
+                    }
+
+                    let collection = self
+                        .create(
+                            user_id,
+                            CreateMovie {
+                                barcode: None,
+                                tmdb_id: None,
+                                title: format!("Sammlung: {}", title),
+                                original_title: None,
+                                disc_type: group.movies[0].disc_type.clone(),
+                                production_year: None,
+                            },
+                        )
+                        .await?;
+                    let collection = self
+                        .update(
+                            user_id,
+                            collection.id,
+                            UpdateMovie {
+                                is_collection: Some(true),
+                                ..Default::default()
+                            },
+                        )
+                        .await?;
+                    let id = collection.id;
+                    result.collections_created.push(collection);
+                    Some(id)
+                }
+            };
+
+            for movie in &group.movies {
+                sqlx::query(
+                    "UPDATE movies SET parent_collection_id = ?, updated_at = ? WHERE id = ? AND user_id = ?",
+                )
+                .bind(new_parent)
+                .bind(Utc::now().to_rfc3339())
+                .bind(movie.id)
+                .bind(user_id)
+                .execute(&self.pool)
+                .await?;
+            }
+
+            if new_parent.is_none() {
+                result.unlinked += group.movies.len() as u64;
+            }
+        }
+
+        Ok(result)
+    }
+
     /// Delete all movies for a user
     pub async fn delete_all(&self, user_id: Uuid) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+        let movies: Vec<(Uuid, String)> =
+            sqlx::query_as("SELECT id, title FROM movies WHERE user_id = ?")
+                .bind(user_id)
+                .fetch_all(&mut *tx)
+                .await?;
+        let ids: Vec<Uuid> = movies.iter().map(|(id, _)| *id).collect();
+
         let result = sqlx::query("DELETE FROM movies WHERE user_id = ?")
             .bind(user_id)
-            .execute(&self.pool)
+            .execute(&mut *tx)
+            .await?;
+
+        record_deletions(&mut tx, user_id, EntityType::Movie, &ids).await?;
+        for (id, title) in &movies {
+            record_activity(
+                &mut *tx,
+                user_id,
+                ActivityAction::Delete,
+                EntityType::Movie,
+                *id,
+                Some(title),
+                None,
+            )
             .await?;
+        }
+        tx.commit().await?;
 
         Ok(result.rows_affected())
     }
@@ -532,7 +1403,7 @@ impl MovieService {
     pub async fn get_movie_ids_with_poster(&self, user_id: Uuid) -> Result<Vec<Uuid>> {
         // Query returns (id,) tuples where id is a Uuid blob
         let rows: Vec<(Uuid,)> =
-            sqlx::query_as("SELECT id FROM movies WHERE user_id = ? AND poster_data IS NOT NULL")
+            sqlx::query_as("SELECT id FROM movies WHERE user_id = ? AND poster_hash IS NOT NULL")
                 .bind(user_id)
                 .fetch_all(&self.pool)
                 .await?;
@@ -542,51 +1413,184 @@ impl MovieService {
 
     /// Get only the poster data for a movie (for export without loading full Movie struct)
     pub async fn get_poster_data(&self, user_id: Uuid, movie_id: Uuid) -> Result<Option<Vec<u8>>> {
-        let row: Option<(Vec<u8>,)> =
-            sqlx::query_as("SELECT poster_data FROM movies WHERE id = ? AND user_id = ? AND poster_data IS NOT NULL")
-                .bind(movie_id)
-                .bind(user_id)
-                .fetch_optional(&self.pool)
-                .await?;
+        let hash = sqlx::query_scalar::<_, Option<String>>(
+            "SELECT poster_hash FROM movies WHERE id = ? AND user_id = ?",
+        )
+        .bind(movie_id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
 
-        Ok(row.map(|(data,)| data))
+        ImageService::new(self.pool.clone())
+            .get_optional(hash.flatten().as_deref())
+            .await
     }
 
     pub async fn find_by_barcode(&self, user_id: Uuid, barcode: &str) -> Result<Option<Movie>> {
-        sqlx::query_as::<_, Movie>("SELECT * FROM movies WHERE barcode = ? AND user_id = ?")
-            .bind(barcode)
-            .bind(user_id)
-            .fetch_optional(&self.pool)
-            .await
-            .map_err(Into::into)
+        let movie = sqlx::query_as::<_, Movie>(&format!(
+            "{} WHERE m.barcode = ? AND m.user_id = ?",
+            SELECT_MOVIE_WITH_POSTER
+        ))
+        .bind(barcode)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        match movie {
+            Some(movie) => Ok(Some(self.load_external_poster(movie).await?)),
+            None => Ok(None),
+        }
     }
 
     pub async fn find_by_tmdb_id(&self, user_id: Uuid, tmdb_id: i64) -> Result<Option<Movie>> {
-        sqlx::query_as::<_, Movie>("SELECT * FROM movies WHERE tmdb_id = ? AND user_id = ?")
-            .bind(tmdb_id)
-            .bind(user_id)
-            .fetch_optional(&self.pool)
-            .await
-            .map_err(Into::into)
+        let movie = sqlx::query_as::<_, Movie>(&format!(
+            "{} WHERE m.tmdb_id = ? AND m.user_id = ?",
+            SELECT_MOVIE_WITH_POSTER
+        ))
+        .bind(tmdb_id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        match movie {
+            Some(movie) => Ok(Some(self.load_external_poster(movie).await?)),
+            None => Ok(None),
+        }
     }
 
-    pub async fn find_by_title(&self, user_id: Uuid, title: &str) -> Result<Vec<Movie>> {
-        sqlx::query_as::<_, Movie>(
-            "SELECT * FROM movies WHERE (title = ? OR original_title = ?) AND user_id = ?",
+    /// Title suggestions for search-as-you-type, using the title FTS index with prefix matching
+    pub async fn suggest(
+        &self,
+        user_id: Uuid,
+        query: &str,
+        limit: i64,
+    ) -> Result<Vec<MovieSuggestion>> {
+        let Some(match_query) = fts_prefix_query(query) else {
+            return Ok(Vec::new());
+        };
+        refresh_search_titles(&self.pool, Some(user_id)).await?;
+
+        let suggestions = sqlx::query_as::<_, MovieSuggestion>(
+            r#"
+            SELECT m.id, m.title, m.production_year AS year, m.poster_hash IS NOT NULL AS has_poster
+            FROM movies_title_fts f
+            JOIN movies m ON m.id = f.movie_id
+            WHERE movies_title_fts MATCH ? AND f.user_id = ?
+            ORDER BY f.rank, m.title
+            LIMIT ?
+            "#,
         )
-        .bind(title)
-        .bind(title)
+        .bind(match_query)
         .bind(user_id)
+        .bind(limit)
         .fetch_all(&self.pool)
-        .await
-        .map_err(Into::into)
+        .await?;
+
+        Ok(suggestions)
     }
 
-    /// Check for potential duplicates before adding
-    pub async fn find_duplicates(
-        &self,
-        user_id: Uuid,
-        title: &str,
+    /// Record that the user opened a movie. Only the most recent
+    /// `VIEW_HISTORY_LIMIT` movies are kept per user.
+    pub async fn record_view(&self, user_id: Uuid, movie_id: Uuid) -> Result<()> {
+        // Ensures the movie exists and belongs to the user
+        self.get_by_id(user_id, movie_id).await?;
+
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO movie_views (user_id, movie_id, view_count, last_viewed_at)
+            VALUES (?, ?, 1, ?)
+            ON CONFLICT(user_id, movie_id) DO UPDATE SET
+                view_count = view_count + 1,
+                last_viewed_at = excluded.last_viewed_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(movie_id)
+        .bind(Utc::now().to_rfc3339())
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM movie_views
+            WHERE user_id = ? AND movie_id NOT IN (
+                SELECT movie_id FROM movie_views
+                WHERE user_id = ?
+                ORDER BY last_viewed_at DESC
+                LIMIT ?
+            )
+            "#,
+        )
+        .bind(user_id)
+        .bind(user_id)
+        .bind(VIEW_HISTORY_LIMIT)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// The user's view history, newest first, or by view count when `most_viewed` is set
+    pub async fn recent_views(
+        &self,
+        user_id: Uuid,
+        limit: i64,
+        most_viewed: bool,
+    ) -> Result<Vec<RecentView>> {
+        let order = if most_viewed {
+            "v.view_count DESC, v.last_viewed_at DESC"
+        } else {
+            "v.last_viewed_at DESC"
+        };
+
+        let views = sqlx::query_as::<_, RecentView>(&format!(
+            r#"
+            SELECT v.movie_id, m.title, m.production_year, m.disc_type, v.view_count, v.last_viewed_at
+            FROM movie_views v
+            JOIN movies m ON m.id = v.movie_id
+            WHERE v.user_id = ?
+            ORDER BY {}
+            LIMIT ?
+            "#,
+            order
+        ))
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(views)
+    }
+
+    /// Movies with this title or original title; differently written titles
+    /// ("Koenig" for "König") match as well
+    pub async fn find_by_title(&self, user_id: Uuid, title: &str) -> Result<Vec<Movie>> {
+        refresh_search_titles(&self.pool, Some(user_id)).await?;
+
+        let found = sqlx::query_as::<_, Movie>(&format!(
+            "{} WHERE (m.title = ? OR m.original_title = ? OR m.search_title = ?) AND m.user_id = ?",
+            SELECT_MOVIE_WITH_POSTER
+        ))
+        .bind(title)
+        .bind(title)
+        .bind(fold_title(title))
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut movies = Vec::with_capacity(found.len());
+        for movie in found {
+            movies.push(self.load_external_poster(movie).await?);
+        }
+        Ok(movies)
+    }
+
+    /// Check for potential duplicates before adding
+    pub async fn find_duplicates(
+        &self,
+        user_id: Uuid,
+        title: &str,
         barcode: Option<&str>,
         tmdb_id: Option<i64>,
     ) -> Result<Vec<Movie>> {
@@ -617,6 +1621,34 @@ impl MovieService {
         Ok(duplicates)
     }
 
+    /// `find_duplicates` over the movies of several users, e.g. a household.
+    /// Movies of users other than `user_id` get `owner_name` set.
+    pub async fn find_duplicates_across(
+        &self,
+        user_id: Uuid,
+        user_ids: &[Uuid],
+        title: &str,
+        barcode: Option<&str>,
+        tmdb_id: Option<i64>,
+    ) -> Result<Vec<Movie>> {
+        let mut duplicates = Vec::new();
+        for &owner in user_ids {
+            let mut found = self.find_duplicates(owner, title, barcode, tmdb_id).await?;
+            if owner != user_id && !found.is_empty() {
+                let name: Option<String> =
+                    sqlx::query_scalar("SELECT username FROM users WHERE id = ?")
+                        .bind(owner)
+                        .fetch_optional(&self.pool)
+                        .await?;
+                for movie in &mut found {
+                    movie.owner_name = name.clone();
+                }
+            }
+            duplicates.extend(found);
+        }
+        Ok(duplicates)
+    }
+
     /// Find all duplicate movies in the collection
     pub async fn find_all_duplicates(&self, user_id: Uuid) -> Result<Vec<Vec<Movie>>> {
         // Get all movies
@@ -658,7 +1690,8 @@ impl MovieService {
 
             // Find duplicates by TMDB ID (exclude 0 as it's a placeholder)
             // IMPORTANT: Only consider duplicates if they have the SAME disc_type
-            // (DVD vs Blu-Ray of the same movie are NOT duplicates)
+            // (DVD vs Blu-Ray of the same movie are NOT duplicates).
+            // Seasons of a series share the TMDB ID, so different seasons never match.
             if let Some(tmdb_id) = movie.tmdb_id
                 && tmdb_id > 0
             {
@@ -666,6 +1699,7 @@ impl MovieService {
                     if other.id != movie.id
                         && other.tmdb_id == Some(tmdb_id)
                         && same_disc_type(&movie.disc_type, &other.disc_type)
+                        && same_season(&movie.title, &other.title) != Some(false)
                         && !group.iter().any(|m| m.id == other.id)
                     {
                         group.push(other.clone());
@@ -673,11 +1707,12 @@ impl MovieService {
                 }
             }
 
-            // Find duplicates by exact title match
+            // Find duplicates by title match, ignoring case, accents and umlaut spelling
             // IMPORTANT: Must have same disc_type AND for TV series, must be same season
+            let folded = fold_title(&movie.title);
             for other in &movies {
                 if other.id != movie.id
-                    && other.title.to_lowercase() == movie.title.to_lowercase()
+                    && fold_title(&other.title) == folded
                     && same_disc_type(&movie.disc_type, &other.disc_type)
                     && !group.iter().any(|m| m.id == other.id)
                 {
@@ -686,10 +1721,18 @@ impl MovieService {
                 }
             }
 
-            // Also check for similar titles that might be different seasons of same show
-            // e.g., "Die Nanny - Staffel 1" vs "Die Nanny - Staffel 2" should NOT be duplicates
-            // But "Die Nanny Season One" and "Die Nanny: Staffel 1" SHOULD be duplicates
-            // Only exact title matches are considered duplicates (already handled above)
+            // Same season of the same show with differently written titles
+            // e.g., "Die Nanny Season One" and "Die Nanny: Staffel 1" SHOULD be duplicates,
+            // but "Die Nanny - Staffel 1" vs "Die Nanny - Staffel 2" should NOT be
+            for other in &movies {
+                if other.id != movie.id
+                    && same_season(&movie.title, &other.title) == Some(true)
+                    && same_disc_type(&movie.disc_type, &other.disc_type)
+                    && !group.iter().any(|m| m.id == other.id)
+                {
+                    group.push(other.clone());
+                }
+            }
 
             if group.len() > 1 {
                 for m in &group {
@@ -701,6 +1744,610 @@ impl MovieService {
 
         Ok(duplicate_groups)
     }
+
+    /// Condition grade and included extras. A stored condition that isn't a
+    /// known grade is returned as `None`.
+    pub async fn get_condition(&self, user_id: Uuid, id: Uuid) -> Result<DiscCondition> {
+        let (condition, slip_cover, extras) =
+            sqlx::query_as::<_, (Option<String>, bool, Option<String>)>(
+                "SELECT condition, slip_cover, included_extras FROM movies WHERE id = ? AND user_id = ?",
+            )
+            .bind(id)
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or(Error::NotFound)?;
+
+        Ok(DiscCondition {
+            grade: condition.and_then(|c| c.parse().ok()),
+            extras: included_extras(slip_cover, extras.as_deref()),
+        })
+    }
+
+    /// Replace condition grade and included extras
+    pub async fn set_condition(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+        input: DiscCondition,
+    ) -> Result<Movie> {
+        let before = self.get_by_id(user_id, id).await?;
+
+        let mut extras = input.extras;
+        extras.sort();
+        extras.dedup();
+        let slip_cover = extras.contains(&IncludedExtra::Slipcover);
+        let stored_extras = extras
+            .iter()
+            .filter(|e| **e != IncludedExtra::Slipcover)
+            .map(|e| e.as_str())
+            .collect::<Vec<_>>()
+            .join(",");
+
+        sqlx::query(
+            r#"
+            UPDATE movies SET condition = ?, slip_cover = ?, included_extras = ?, updated_at = ?,
+                updated_by = ?
+            WHERE id = ? AND user_id = ?
+            "#,
+        )
+        .bind(input.grade.map(ConditionGrade::as_str))
+        .bind(slip_cover)
+        .bind((!stored_extras.is_empty()).then_some(stored_extras))
+        .bind(Utc::now().to_rfc3339())
+        .bind(user_id)
+        .bind(id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        let movie = self.get_by_id(user_id, id).await?;
+        record_changes(
+            &self.pool,
+            user_id,
+            EntityType::Movie,
+            id,
+            &movie.title,
+            &before,
+            &movie,
+        )
+        .await?;
+        Ok(movie)
+    }
+
+    /// Record that a movie left the collection. It stays available for history
+    /// (`disposed: true` filter) and value reports.
+    pub async fn dispose(&self, user_id: Uuid, id: Uuid, input: DisposeMovie) -> Result<Movie> {
+        let before = self.get_by_id(user_id, id).await?;
+
+        if input.price.is_some_and(|p| p < 0.0) {
+            return Err(Error::Validation("Price must not be negative".into()));
+        }
+        let date = input.date.unwrap_or_else(|| Utc::now().date_naive());
+
+        sqlx::query(
+            r#"
+            UPDATE movies SET disposition = ?, disposed_at = ?, disposal_price = ?,
+                disposal_currency = ?, disposed_to = ?, updated_at = ?, updated_by = ?,
+                status = CASE WHEN ? THEN 'sold' ELSE status END
+            WHERE id = ? AND user_id = ?
+            "#,
+        )
+        .bind(input.disposition)
+        .bind(date)
+        .bind(input.price)
+        .bind(&input.currency)
+        .bind(&input.recipient)
+        .bind(Utc::now().to_rfc3339())
+        .bind(user_id)
+        .bind(input.disposition == Disposition::Sold)
+        .bind(id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        let movie = self.get_by_id(user_id, id).await?;
+        record_changes(
+            &self.pool,
+            user_id,
+            EntityType::Movie,
+            id,
+            &movie.title,
+            &before,
+            &movie,
+        )
+        .await?;
+        Ok(movie)
+    }
+
+    /// Undo a disposition, the movie is back in the collection
+    pub async fn restore_disposed(&self, user_id: Uuid, id: Uuid) -> Result<Movie> {
+        let before = self.get_by_id(user_id, id).await?;
+
+        sqlx::query(
+            r#"
+            UPDATE movies SET disposition = NULL, disposed_at = NULL, disposal_price = NULL,
+                disposal_currency = NULL, disposed_to = NULL, updated_at = ?, updated_by = ?,
+                status = CASE WHEN status = 'sold' THEN 'owned' ELSE status END
+            WHERE id = ? AND user_id = ?
+            "#,
+        )
+        .bind(Utc::now().to_rfc3339())
+        .bind(user_id)
+        .bind(id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        let movie = self.get_by_id(user_id, id).await?;
+        record_changes(
+            &self.pool,
+            user_id,
+            EntityType::Movie,
+            id,
+            &movie.title,
+            &before,
+            &movie,
+        )
+        .await?;
+        Ok(movie)
+    }
+
+    /// Move a wishlist or ordered movie into the collection. Purchase date
+    /// defaults to today; price, currency and place are only overwritten when given.
+    pub async fn move_to_collection(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+        input: AcquireMovie,
+    ) -> Result<Movie> {
+        let before = self.get_by_id(user_id, id).await?;
+        let status = normalize_movie_status(before.status.as_deref().unwrap_or(""))
+            .unwrap_or(MovieStatus::Owned);
+        if !matches!(status, MovieStatus::Wishlist | MovieStatus::Ordered) {
+            return Err(Error::Validation(
+                "Only wishlist or ordered movies can be moved to the collection".into(),
+            ));
+        }
+        if input.price.is_some_and(|p| p < 0.0) {
+            return Err(Error::Validation("Price must not be negative".into()));
+        }
+        let currency = match input.currency {
+            Some(ref currency) => Some(normalize_currency(currency).map_err(Error::Validation)?),
+            None => None,
+        };
+        let date = input
+            .purchase_date
+            .unwrap_or_else(|| Utc::now().date_naive());
+
+        sqlx::query(
+            r#"
+            UPDATE movies SET status = 'owned', purchase_date = ?,
+                price = COALESCE(?, price), currency = COALESCE(?, currency),
+                purchase_place = COALESCE(?, purchase_place), updated_at = ?, updated_by = ?
+            WHERE id = ? AND user_id = ?
+            "#,
+        )
+        .bind(date)
+        .bind(input.price)
+        .bind(currency)
+        .bind(&input.purchase_place)
+        .bind(Utc::now().to_rfc3339())
+        .bind(user_id)
+        .bind(id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        let movie = self.get_by_id(user_id, id).await?;
+        record_changes(
+            &self.pool,
+            user_id,
+            EntityType::Movie,
+            id,
+            &movie.title,
+            &before,
+            &movie,
+        )
+        .await?;
+        Ok(movie)
+    }
+
+    /// Movie counts per availability and viewings logged in `year`; collection
+    /// parents aren't counted
+    pub async fn stats(&self, user_id: Uuid, year: i32) -> Result<MovieStats> {
+        let year = format!("{:04}", year);
+        let stats = sqlx::query_as::<_, MovieStats>(
+            r#"
+            SELECT COUNT(*) AS total,
+                COALESCE(SUM(availability = 'available'), 0) AS available,
+                COALESCE(SUM(availability = 'lent'), 0) AS lent,
+                COALESCE(SUM(availability = 'missing'), 0) AS missing,
+                COALESCE(SUM(availability = 'sold'), 0) AS sold,
+                COALESCE(SUM(availability = 'ordered'), 0) AS ordered,
+                COALESCE(SUM(availability IS NULL), 0) AS wishlist,
+                (SELECT COUNT(DISTINCT movie_id) FROM watch_events
+                    WHERE user_id = ? AND strftime('%Y', watched_on) = ?) AS watched_this_year,
+                (SELECT COUNT(*) FROM watch_events
+                    WHERE user_id = ? AND strftime('%Y', watched_on) = ?) AS watches_this_year
+            FROM movies
+            WHERE user_id = ? AND is_collection = 0
+            "#,
+        )
+        .bind(user_id)
+        .bind(&year)
+        .bind(user_id)
+        .bind(&year)
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(stats)
+    }
+
+    /// Purchase prices, values and sale proceeds per currency, for movies in the
+    /// collection and movies that left it
+    pub async fn value_report(&self, user_id: Uuid) -> Result<ValueReport> {
+        type ValueRow = (
+            Option<f64>,
+            Option<String>,
+            Option<f64>,
+            Option<String>,
+            Option<Disposition>,
+            Option<f64>,
+            Option<String>,
+        );
+        let rows = sqlx::query_as::<_, ValueRow>(
+            r#"
+            SELECT price, currency, value_price, value_currency, disposition,
+                disposal_price, disposal_currency
+            FROM movies WHERE user_id = ? AND is_collection = 0
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut report = ValueReport::default();
+        let mut totals: BTreeMap<Option<String>, CurrencyTotals> = BTreeMap::new();
+        fn entry(
+            totals: &mut BTreeMap<Option<String>, CurrencyTotals>,
+            currency: Option<String>,
+        ) -> &mut CurrencyTotals {
+            totals
+                .entry(currency.clone())
+                .or_insert_with(|| CurrencyTotals {
+                    currency,
+                    ..Default::default()
+                })
+        }
+
+        for (price, currency, value, value_currency, disposition, proceeds, proceeds_currency) in
+            rows
+        {
+            if disposition.is_some() {
+                report.disposed_count += 1;
+                if let Some(price) = price {
+                    entry(&mut totals, currency).disposed_purchase_total += price;
+                }
+                if let Some(proceeds) = proceeds {
+                    entry(&mut totals, proceeds_currency).disposal_total += proceeds;
+                }
+            } else {
+                report.owned_count += 1;
+                if let Some(price) = price {
+                    entry(&mut totals, currency).purchase_total += price;
+                }
+                if let Some(value) = value {
+                    entry(&mut totals, value_currency).value_total += value;
+                }
+            }
+        }
+
+        report.totals = totals.into_values().collect();
+        Ok(report)
+    }
+
+    /// Discs that won't play on the given player regions, and discs whose region
+    /// codes or video standard can't be read
+    pub async fn region_report(
+        &self,
+        user_id: Uuid,
+        player_regions: &[RegionCode],
+    ) -> Result<RegionReport> {
+        let rows =
+            sqlx::query_as::<_, (Uuid, String, Option<String>, Option<String>, Option<String>)>(
+                r#"
+            SELECT id, title, disc_type, region_codes, video_standard FROM movies
+            WHERE user_id = ? AND is_collection = 0
+              AND (region_codes IS NOT NULL OR video_standard IS NOT NULL)
+            ORDER BY title COLLATE NOCASE
+            "#,
+            )
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut report = RegionReport {
+            player_regions: player_regions.iter().map(ToString::to_string).collect(),
+            mismatched: Vec::new(),
+            invalid: Vec::new(),
+        };
+
+        for (id, title, disc_type, region_codes, video_standard) in rows {
+            let regions = parse_region_codes(region_codes.as_deref().unwrap_or(""));
+            let standard_valid = video_standard
+                .as_deref()
+                .is_none_or(|v| normalize_video_standard(v).is_ok());
+            let entry = RegionReportEntry {
+                id,
+                title,
+                disc_type,
+                region_codes,
+                video_standard,
+            };
+
+            match regions {
+                Ok(_) if !standard_valid => report.invalid.push(entry),
+                Ok(codes) if !region_plays_on(&codes, player_regions) => {
+                    report.mismatched.push(entry)
+                }
+                Ok(_) => {}
+                Err(_) => report.invalid.push(entry),
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Movies still missing TMDB data, a poster or a description. Sold and
+    /// given-away movies and box sets are left out; `failing_posters` is up
+    /// to the caller.
+    pub async fn missing_metadata(&self, user_id: Uuid) -> Result<MissingMetadataReport> {
+        Ok(MissingMetadataReport {
+            without_tmdb: self.missing(user_id, "tmdb_id IS NULL").await?,
+            without_poster: self.missing(user_id, "poster_hash IS NULL").await?,
+            without_description: self
+                .missing(user_id, "COALESCE(TRIM(description), '') = ''")
+                .await?,
+            failing_posters: Vec::new(),
+        })
+    }
+
+    async fn missing(&self, user_id: Uuid, condition: &str) -> Result<Vec<MissingMetadataEntry>> {
+        let entries = sqlx::query_as::<_, MissingMetadataEntry>(&format!(
+            r#"
+            SELECT id, title, production_year FROM movies
+            WHERE user_id = ? AND is_collection = 0 AND disposition IS NULL AND {}
+            ORDER BY title COLLATE NOCASE
+            "#,
+            condition
+        ))
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(entries)
+    }
+
+    /// Bring region codes, video standards and conditions written before
+    /// validation existed into their stored form. Values that can't be parsed
+    /// are left alone. Returns the number of movies changed.
+    pub async fn normalize_disc_fields(&self) -> Result<u64> {
+        type DiscRow = (Uuid, Option<String>, Option<String>, Option<String>);
+        let rows = sqlx::query_as::<_, DiscRow>(
+            "SELECT id, region_codes, video_standard, condition FROM movies \
+             WHERE region_codes IS NOT NULL OR video_standard IS NOT NULL OR condition IS NOT NULL",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut changed = 0;
+        for (id, region_codes, video_standard, condition) in rows {
+            let normalized_regions = match region_codes.as_deref().map(normalize_region_codes) {
+                Some(Ok(normalized)) => normalized,
+                _ => region_codes.clone(),
+            };
+            let normalized_standard = match video_standard.as_deref().map(normalize_video_standard)
+            {
+                Some(Ok(normalized)) => normalized,
+                _ => video_standard.clone(),
+            };
+            let normalized_condition = match condition.as_deref().map(normalize_condition) {
+                Some(Ok(normalized)) => normalized,
+                _ => condition.clone(),
+            };
+
+            if normalized_regions != region_codes
+                || normalized_standard != video_standard
+                || normalized_condition != condition
+            {
+                sqlx::query(
+                    "UPDATE movies SET region_codes = ?, video_standard = ?, condition = ? WHERE id = ?",
+                )
+                .bind(normalized_regions)
+                .bind(normalized_standard)
+                .bind(normalized_condition)
+                .bind(id)
+                .execute(&self.pool)
+                .await?;
+                changed += 1;
+            }
+        }
+
+        Ok(changed)
+    }
+}
+
+/// Condition grades equal to or better than `min`
+fn grades_at_least(min: ConditionGrade) -> Vec<ConditionGrade> {
+    ConditionGrade::ALL
+        .into_iter()
+        .filter(|g| *g >= min)
+        .collect()
+}
+
+/// Condition of the `extra`/`missing_extra` filters. Everything but the
+/// slipcover is a LIKE pattern ("%,booklet,%").
+fn push_extra_condition(conditions: &mut Conditions, extra: IncludedExtra, missing: bool) {
+    let pattern = format!("%,{},%", extra.as_str());
+    match (extra, missing) {
+        (IncludedExtra::Slipcover, false) => conditions.push("slip_cover = 1"),
+        (IncludedExtra::Slipcover, true) => conditions.push("slip_cover = 0"),
+        (_, false) => conditions.push_bind(
+            "',' || COALESCE(included_extras, '') || ',' LIKE ?",
+            pattern,
+        ),
+        (_, true) => conditions.push_bind(
+            "',' || COALESCE(included_extras, '') || ',' NOT LIKE ?",
+            pattern,
+        ),
+    }
+}
+
+/// LIKE pattern matching one region in a stored region list ("R2,RB")
+fn region_filter_pattern(region: &str) -> Result<String> {
+    let code = region.parse::<RegionCode>().map_err(Error::Validation)?;
+    Ok(format!("%,{},%", code))
+}
+
+/// Build an FTS5 prefix query: every word must match the start of a title word.
+/// Words are quoted so FTS syntax in user input is treated as text. A word
+/// with a different folded form ("König" -> "koenig") matches either.
+pub(crate) fn fts_prefix_query(input: &str) -> Option<String> {
+    let terms: Vec<String> = input
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| {
+            let folded = fold_title(w);
+            if folded == w.to_lowercase() {
+                format!("\"{}\"*", w)
+            } else {
+                format!("(\"{}\"* OR \"{}\"*)", w, folded)
+            }
+        })
+        .collect();
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+/// Number words used in season titles ("Season One", "Erste Staffel")
+const SEASON_WORDS: &[(&str, u32)] = &[
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+    ("ten", 10),
+    ("first", 1),
+    ("second", 2),
+    ("third", 3),
+    ("fourth", 4),
+    ("fifth", 5),
+    ("sixth", 6),
+    ("seventh", 7),
+    ("eighth", 8),
+    ("ninth", 9),
+    ("tenth", 10),
+    ("eins", 1),
+    ("zwei", 2),
+    ("drei", 3),
+    ("vier", 4),
+    ("fünf", 5),
+    ("sechs", 6),
+    ("sieben", 7),
+    ("acht", 8),
+    ("neun", 9),
+    ("zehn", 10),
+    ("erste", 1),
+    ("zweite", 2),
+    ("dritte", 3),
+    ("vierte", 4),
+    ("fünfte", 5),
+    ("sechste", 6),
+    ("siebte", 7),
+    ("achte", 8),
+    ("neunte", 9),
+    ("zehnte", 10),
+];
+
+/// Season patterns, each with the season number (digits or word) in group 1:
+/// "Staffel 1", "Season 2", "Season One", "3. Staffel", "Erste Staffel", "S04"
+static SEASON_PATTERNS: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    [
+        r"(?i)\b(?:staffel|season)\s*(\d{1,2}|[a-zäöüß]+)\b",
+        r"(?i)\b(\d{1,2})\.\s*staffel\b",
+        r"(?i)\b([a-zäöüß]+)\s+(?:staffel|season)\b",
+        r"(?i)\bs(\d{1,2})\b",
+    ]
+    .iter()
+    .filter_map(|p| Regex::new(p).ok())
+    .collect()
+});
+
+/// Words left over after removing the season part, e.g. "Die komplette 1. Staffel"
+const SEASON_FILLER_WORDS: &[&str] = &["die", "the", "komplette", "complete", "vollständige"];
+
+fn parse_season_number(value: &str) -> Option<u32> {
+    if let Ok(number) = value.parse::<u32>() {
+        return Some(number);
+    }
+    let value = value.to_lowercase();
+    SEASON_WORDS
+        .iter()
+        .find(|(word, _)| *word == value)
+        .map(|(_, number)| *number)
+}
+
+/// Split a TV season title into the normalized show name and season number.
+///
+/// "Die Nanny – Staffel 1", "Die Nanny: Season One" and "Die Nanny - Die komplette
+/// erste Staffel" all give `("die nanny", 1)`. Returns `None` for titles without a season.
+pub fn extract_season(title: &str) -> Option<(String, u32)> {
+    for pattern in SEASON_PATTERNS.iter() {
+        for captures in pattern.captures_iter(title) {
+            let (Some(full), Some(number)) = (captures.get(0), captures.get(1)) else {
+                continue;
+            };
+            let Some(season) = parse_season_number(number.as_str()) else {
+                continue;
+            };
+
+            let rest = format!("{} {}", &title[..full.start()], &title[full.end()..]);
+            let mut words: Vec<String> = rest
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|w| !w.is_empty())
+                .map(|w| w.to_lowercase())
+                .collect();
+            while words
+                .last()
+                .is_some_and(|w| SEASON_FILLER_WORDS.contains(&w.as_str()))
+            {
+                words.pop();
+            }
+
+            if words.is_empty() {
+                continue;
+            }
+            return Some((words.join(" "), season));
+        }
+    }
+    None
+}
+
+/// Whether two titles are seasons of the same show.
+/// `Some(true)`: same show and season, `Some(false)`: same show, different season,
+/// `None`: not comparable as seasons.
+fn same_season(a: &str, b: &str) -> Option<bool> {
+    let (show_a, season_a) = extract_season(a)?;
+    let (show_b, season_b) = extract_season(b)?;
+    (show_a == show_b).then_some(season_a == season_b)
 }
 
 /// Helper function to check if two disc types are the same
@@ -720,7 +2367,10 @@ fn same_disc_type(a: &Option<String>, b: &Option<String>) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::{BulkMovieFields, MovieAvailability, parse_movie_fields};
     use crate::test_helpers::{create_test_db_with_users, fixtures};
+    use proptest::prelude::*;
+    use proptest::test_runner::{Config as ProptestConfig, TestRunner};
 
     async fn setup() -> MovieService {
         let pool = create_test_db_with_users().await;
@@ -754,11 +2404,132 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_get_movie_by_id() {
+    async fn test_summary_and_detail_columns_match_their_structs() {
         let service = setup().await;
         let user_id = fixtures::test_user_id();
+        create_filter_movies(&service, user_id).await;
 
-        let created = service
+        let keys = |value: serde_json::Value| -> Vec<String> {
+            let mut keys: Vec<String> = value.as_object().unwrap().keys().cloned().collect();
+            keys.sort();
+            keys
+        };
+
+        let summaries = service
+            .list_summaries(user_id, MovieFilter::default())
+            .await
+            .unwrap();
+        let mut summary_fields: Vec<String> =
+            MOVIE_SUMMARY_FIELDS.iter().map(|f| f.to_string()).collect();
+        summary_fields.sort();
+        assert_eq!(
+            keys(serde_json::to_value(&summaries[0]).unwrap()),
+            summary_fields
+        );
+
+        // Every field of a movie is read; the backdrop is only loaded with the poster
+        let movies = service.list(user_id, MovieFilter::default()).await.unwrap();
+        let mut detail_fields: Vec<String> =
+            MOVIE_FIELDS.iter().map(|(f, _, _)| f.to_string()).collect();
+        detail_fields.sort();
+        assert_eq!(
+            keys(serde_json::to_value(&movies[0]).unwrap()),
+            detail_fields
+        );
+
+        let ids: Vec<Uuid> = summaries.iter().map(|m| m.id).collect();
+        assert_eq!(ids, movies.iter().map(|m| m.id).collect::<Vec<_>>());
+        assert_eq!(summaries[0].times_watched, movies[0].times_watched);
+        assert_eq!(summaries[0].availability, movies[0].availability);
+    }
+
+    #[tokio::test]
+    async fn test_added_by_and_updated_by() {
+        let service = setup().await;
+        let user_id = fixtures::test_user_id();
+
+        let movie = service
+            .create(
+                user_id,
+                CreateMovie {
+                    barcode: None,
+                    tmdb_id: None,
+                    title: "Heat".to_string(),
+                    original_title: None,
+                    disc_type: None,
+                    production_year: None,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(movie.added_by, Some(user_id));
+        assert_eq!(movie.updated_by, Some(user_id));
+
+        sqlx::query("UPDATE movies SET updated_by = NULL")
+            .execute(&service.pool)
+            .await
+            .unwrap();
+        let movie = service.toggle_favorite(user_id, movie.id).await.unwrap();
+        assert_eq!(movie.updated_by, Some(user_id));
+
+        let mine = MovieFilter {
+            added_by: Some(user_id),
+            ..Default::default()
+        };
+        assert_eq!(service.list(user_id, mine).await.unwrap().len(), 1);
+        let others = MovieFilter {
+            updated_by: Some(fixtures::test_admin_id()),
+            ..Default::default()
+        };
+        assert_eq!(service.count(user_id, &others).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_create_applies_movie_defaults() {
+        let service = setup().await;
+        let user_id = fixtures::test_user_id();
+        sqlx::query(
+            "UPDATE users SET default_disc_type = 'Blu-ray', default_location = 'Shelf 3', \
+             default_currency = 'EUR' WHERE id = ?",
+        )
+        .bind(user_id)
+        .execute(&service.pool)
+        .await
+        .unwrap();
+
+        let input = |disc_type: Option<&str>| CreateMovie {
+            barcode: None,
+            tmdb_id: None,
+            title: "Heat".to_string(),
+            original_title: None,
+            disc_type: disc_type.map(str::to_string),
+            production_year: None,
+        };
+
+        let movie = service.create(user_id, input(None)).await.unwrap();
+        assert_eq!(movie.disc_type.as_deref(), Some("Blu-ray"));
+        assert_eq!(movie.location.as_deref(), Some("Shelf 3"));
+        assert_eq!(movie.currency.as_deref(), Some("EUR"));
+        assert_eq!(movie.media_type, None);
+
+        // Given values win
+        let movie = service.create(user_id, input(Some("DVD"))).await.unwrap();
+        assert_eq!(movie.disc_type.as_deref(), Some("DVD"));
+
+        let movie = service
+            .create_without_defaults(user_id, input(None))
+            .await
+            .unwrap();
+        assert_eq!(movie.disc_type, None);
+        assert_eq!(movie.location, None);
+    }
+
+    #[tokio::test]
+    async fn test_get_movie_by_id() {
+        let service = setup().await;
+        let user_id = fixtures::test_user_id();
+
+        let created = service
             .create(
                 user_id,
                 CreateMovie {
@@ -850,11 +2621,1352 @@ mod tests {
             )
             .await
             .unwrap();
-        assert_eq!(movies.len(), 5);
+        assert_eq!(movies.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_list_movies_with_search() {
+        let service = setup().await;
+        let user_id = fixtures::test_user_id();
+
+        service
+            .create(
+                user_id,
+                CreateMovie {
+                    barcode: None,
+                    tmdb_id: Some(1),
+                    title: "The Matrix".to_string(),
+                    original_title: None,
+                    disc_type: None,
+                    production_year: None,
+                },
+            )
+            .await
+            .unwrap();
+
+        service
+            .create(
+                user_id,
+                CreateMovie {
+                    barcode: None,
+                    tmdb_id: Some(2),
+                    title: "Inception".to_string(),
+                    original_title: None,
+                    disc_type: None,
+                    production_year: None,
+                },
+            )
+            .await
+            .unwrap();
+
+        let movies = service
+            .list(
+                user_id,
+                MovieFilter {
+                    search: Some("Matrix".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(movies.len(), 1);
+        assert_eq!(movies[0].title, "The Matrix");
+    }
+
+    #[tokio::test]
+    async fn test_list_movies_search_folds_titles() {
+        let service = setup().await;
+        let user_id = fixtures::test_user_id();
+
+        let mut ids = Vec::new();
+        for title in ["Der König der Löwen", "Amélie", "Heat"] {
+            let movie = service
+                .create(
+                    user_id,
+                    CreateMovie {
+                        barcode: None,
+                        tmdb_id: None,
+                        title: title.to_string(),
+                        original_title: None,
+                        disc_type: None,
+                        production_year: None,
+                    },
+                )
+                .await
+                .unwrap();
+            ids.push(movie.id);
+        }
+        // Renamed titles are folded again before searching
+        service
+            .update(
+                user_id,
+                ids[2],
+                UpdateMovie {
+                    title: Some("Schöne Grüße".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        for (search, expected) in [
+            ("Koenig", "Der König der Löwen"),
+            ("koenig der loewen", "Der König der Löwen"),
+            ("König", "Der König der Löwen"),
+            ("amelie", "Amélie"),
+            ("gruesse", "Schöne Grüße"),
+        ] {
+            let filter = MovieFilter {
+                search: Some(search.to_string()),
+                ..Default::default()
+            };
+            let movies = service.list(user_id, filter.clone()).await.unwrap();
+            let titles: Vec<&str> = movies.iter().map(|m| m.title.as_str()).collect();
+            assert_eq!(titles, [expected], "{}", search);
+            assert_eq!(service.count(user_id, &filter).await.unwrap(), 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_fields() {
+        let service = setup().await;
+        let user_id = fixtures::test_user_id();
+
+        for (title, year) in [("Heat", 1995), ("Alien", 1979)] {
+            service
+                .create(
+                    user_id,
+                    CreateMovie {
+                        barcode: None,
+                        tmdb_id: None,
+                        title: title.to_string(),
+                        original_title: None,
+                        disc_type: None,
+                        production_year: Some(year),
+                    },
+                )
+                .await
+                .unwrap();
+        }
+
+        let fields = parse_movie_fields("id, title,production_year,watched,group,title").unwrap();
+        assert_eq!(
+            fields,
+            ["id", "title", "production_year", "watched", "group"]
+        );
+        assert!(parse_movie_fields("title,poster_data").is_err());
+        assert!(parse_movie_fields(" , ").is_err());
+
+        let movies = service
+            .list_fields(
+                user_id,
+                MovieFilter {
+                    year_from: Some(1990),
+                    ..Default::default()
+                },
+                &fields,
+            )
+            .await
+            .unwrap();
+        assert_eq!(movies.len(), 1);
+        let json = serde_json::to_value(&movies[0]).unwrap();
+        let object = json.as_object().unwrap();
+        assert_eq!(object.len(), 5);
+        assert_eq!(json["title"], "Heat");
+        assert_eq!(json["production_year"], 1995);
+        assert_eq!(json["watched"], false);
+        assert!(json["group"].is_null());
+        assert!(json["id"].as_str().unwrap().parse::<Uuid>().is_ok());
+
+        let sorted = service
+            .list_fields(
+                user_id,
+                MovieFilter {
+                    sort_by: Some("production_year".to_string()),
+                    ..Default::default()
+                },
+                &["title"],
+            )
+            .await
+            .unwrap();
+        assert_eq!(serde_json::to_value(&sorted[0]).unwrap()["title"], "Alien");
+    }
+
+    #[tokio::test]
+    async fn test_list_by_tag_and_collection() {
+        use crate::models::{AddCollectionItem, CollectionItemType, CreateCollection};
+        use crate::services::CollectionService;
+
+        let pool = create_test_db_with_users().await;
+        let service = MovieService::new(pool.clone());
+        let collections = CollectionService::new(pool);
+        let user_id = fixtures::test_user_id();
+
+        let mut ids = Vec::new();
+        for (title, tags) in [
+            ("Ronja Räubertochter", "Kinderfilme, Klassiker"),
+            ("Heat", "Klassiker"),
+            ("Alien", "Horror,Kinderfilme-Nein"),
+        ] {
+            let movie = service
+                .create(
+                    user_id,
+                    CreateMovie {
+                        barcode: None,
+                        tmdb_id: None,
+                        title: title.to_string(),
+                        original_title: None,
+                        disc_type: None,
+                        production_year: None,
+                    },
+                )
+                .await
+                .unwrap();
+            service
+                .update(
+                    user_id,
+                    movie.id,
+                    UpdateMovie {
+                        tags: Some(tags.to_string()),
+                        ..Default::default()
+                    },
+                )
+                .await
+                .unwrap();
+            ids.push(movie.id);
+        }
+
+        let filter = MovieFilter {
+            tag: Some("kinderfilme".to_string()),
+            ..Default::default()
+        };
+        let tagged = service.list(user_id, filter).await.unwrap();
+        assert_eq!(tagged.len(), 1);
+        assert_eq!(tagged[0].id, ids[0]);
+
+        let collection = collections
+            .create(
+                user_id,
+                CreateCollection {
+                    barcode: None,
+                    title: "Mann-Box".to_string(),
+                    description: None,
+                    disc_type: None,
+                },
+            )
+            .await
+            .unwrap();
+        collections
+            .add_item(
+                user_id,
+                collection.id,
+                AddCollectionItem {
+                    item_type: CollectionItemType::Movie,
+                    movie_id: Some(ids[1]),
+                    series_id: None,
+                    position: None,
+                },
+            )
+            .await
+            .unwrap();
+
+        let filter = MovieFilter {
+            collection_id: Some(collection.id),
+            ..Default::default()
+        };
+        assert_eq!(service.count(user_id, &filter).await.unwrap(), 1);
+        let boxed = service.list(user_id, filter).await.unwrap();
+        assert_eq!(boxed.len(), 1);
+        assert_eq!(boxed[0].title, "Heat");
+    }
+
+    #[tokio::test]
+    async fn test_list_movies_with_disc_type_filter() {
+        let service = setup().await;
+        let user_id = fixtures::test_user_id();
+
+        service
+            .create(
+                user_id,
+                CreateMovie {
+                    barcode: None,
+                    tmdb_id: Some(1),
+                    title: "DVD Movie".to_string(),
+                    original_title: None,
+                    disc_type: Some("DVD".to_string()),
+                    production_year: None,
+                },
+            )
+            .await
+            .unwrap();
+
+        service
+            .create(
+                user_id,
+                CreateMovie {
+                    barcode: None,
+                    tmdb_id: Some(2),
+                    title: "Blu-ray Movie".to_string(),
+                    original_title: None,
+                    disc_type: Some("Blu-ray".to_string()),
+                    production_year: None,
+                },
+            )
+            .await
+            .unwrap();
+
+        let movies = service
+            .list(
+                user_id,
+                MovieFilter {
+                    disc_type: Some("DVD".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(movies.len(), 1);
+        assert_eq!(movies[0].title, "DVD Movie");
+    }
+
+    #[tokio::test]
+    async fn test_update_movie() {
+        let service = setup().await;
+        let user_id = fixtures::test_user_id();
+
+        let movie = service
+            .create(
+                user_id,
+                CreateMovie {
+                    barcode: None,
+                    tmdb_id: Some(550),
+                    title: "Fight Club".to_string(),
+                    original_title: None,
+                    disc_type: None,
+                    production_year: None,
+                },
+            )
+            .await
+            .unwrap();
+
+        let updated = service
+            .update(
+                user_id,
+                movie.id,
+                UpdateMovie {
+                    title: Some("Fight Club (Updated)".to_string()),
+                    personal_rating: Some(9.0),
+                    watched: Some(true),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(updated.title, "Fight Club (Updated)");
+        assert_eq!(updated.personal_rating, Some(9.0));
+        assert!(updated.watched);
+    }
+
+    #[tokio::test]
+    async fn test_update_movie_tags_and_loan() {
+        let service = setup().await;
+        let user_id = fixtures::test_user_id();
+
+        let movie = service
+            .create(
+                user_id,
+                CreateMovie {
+                    barcode: None,
+                    tmdb_id: None,
+                    title: "Alien".to_string(),
+                    original_title: None,
+                    disc_type: None,
+                    production_year: None,
+                },
+            )
+            .await
+            .unwrap();
+
+        let due = chrono::NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let updated = service
+            .update(
+                user_id,
+                movie.id,
+                UpdateMovie {
+                    sort_title: Some("Alien 1".to_string()),
+                    rating: Some("FSK 16".to_string()),
+                    tags: Some("Horror, Klassiker".to_string()),
+                    lent_to: Some("Peter".to_string()),
+                    lent_due: Some(due),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(updated.sort_title.as_deref(), Some("Alien 1"));
+        assert_eq!(updated.rating.as_deref(), Some("FSK 16"));
+        assert_eq!(updated.tags.as_deref(), Some("Horror, Klassiker"));
+        assert_eq!(updated.lent_to.as_deref(), Some("Peter"));
+        assert_eq!(updated.lent_due, Some(due));
+    }
+
+    #[tokio::test]
+    async fn test_region_codes_validation_filter_and_report() {
+        let service = setup().await;
+        let user_id = fixtures::test_user_id();
+
+        let mut ids = Vec::new();
+        for (title, regions) in [("Heat", "2"), ("Alien", "Region A"), ("Brazil", "free")] {
+            let movie = service
+                .create(
+                    user_id,
+                    CreateMovie {
+                        barcode: None,
+                        tmdb_id: None,
+                        title: title.to_string(),
+                        original_title: None,
+                        disc_type: None,
+                        production_year: None,
+                    },
+                )
+                .await
+                .unwrap();
+            let updated = service
+                .update(
+                    user_id,
+                    movie.id,
+                    UpdateMovie {
+                        region_codes: Some(regions.to_string()),
+                        video_standard: Some("pal".to_string()),
+                        ..Default::default()
+                    },
+                )
+                .await
+                .unwrap();
+            assert_eq!(updated.video_standard.as_deref(), Some("PAL"));
+            ids.push(updated.id);
+        }
+
+        let heat = service.get_by_id(user_id, ids[0]).await.unwrap();
+        assert_eq!(heat.region_codes.as_deref(), Some("R2"));
+
+        let invalid = service
+            .update(
+                user_id,
+                ids[0],
+                UpdateMovie {
+                    region_codes: Some("Europe".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await;
+        assert!(matches!(invalid, Err(Error::Validation(_))));
+
+        // Region-free discs match every region
+        let filter = MovieFilter {
+            region: Some("R2".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(service.count(user_id, &filter).await.unwrap(), 2);
+        let filter = MovieFilter {
+            region: Some("a".to_string()),
+            video_standard: Some("PAL".to_string()),
+            ..Default::default()
+        };
+        let titles: Vec<String> = service
+            .list(user_id, filter)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|m| m.title)
+            .collect();
+        assert_eq!(titles, vec!["Alien", "Brazil"]);
+
+        // Written by an older version or import, bypassing validation
+        sqlx::query("UPDATE movies SET video_standard = 'SECAM' WHERE id = ?")
+            .bind(ids[2])
+            .execute(&service.pool)
+            .await
+            .unwrap();
+
+        let player = parse_region_codes("R2,RB").unwrap();
+        let report = service.region_report(user_id, &player).await.unwrap();
+        assert_eq!(report.player_regions, vec!["R2", "RB"]);
+        assert_eq!(report.mismatched.len(), 1);
+        assert_eq!(report.mismatched[0].id, ids[1]);
+        assert_eq!(report.invalid.len(), 1);
+        assert_eq!(report.invalid[0].id, ids[2]);
+    }
+
+    #[tokio::test]
+    async fn test_normalize_disc_fields() {
+        let service = setup().await;
+        let user_id = fixtures::test_user_id();
+
+        let movie = service
+            .create(
+                user_id,
+                CreateMovie {
+                    barcode: None,
+                    tmdb_id: None,
+                    title: "Heat".to_string(),
+                    original_title: None,
+                    disc_type: None,
+                    production_year: None,
+                },
+            )
+            .await
+            .unwrap();
+        sqlx::query(
+            "UPDATE movies SET region_codes = 'B, 2', video_standard = 'ntsc', condition = 'Sehr gut'",
+        )
+        .execute(&service.pool)
+        .await
+        .unwrap();
+
+        assert_eq!(service.normalize_disc_fields().await.unwrap(), 1);
+        assert_eq!(service.normalize_disc_fields().await.unwrap(), 0);
+
+        let movie = service.get_by_id(user_id, movie.id).await.unwrap();
+        assert_eq!(movie.region_codes.as_deref(), Some("R2,RB"));
+        assert_eq!(movie.video_standard.as_deref(), Some("NTSC"));
+        assert_eq!(movie.condition.as_deref(), Some("very_good"));
+    }
+
+    #[tokio::test]
+    async fn test_condition_and_extras() {
+        let service = setup().await;
+        let user_id = fixtures::test_user_id();
+
+        let mut ids = Vec::new();
+        for title in ["Heat", "Alien", "Up"] {
+            let movie = service
+                .create(
+                    user_id,
+                    CreateMovie {
+                        barcode: None,
+                        tmdb_id: None,
+                        title: title.to_string(),
+                        original_title: None,
+                        disc_type: None,
+                        production_year: None,
+                    },
+                )
+                .await
+                .unwrap();
+            ids.push(movie.id);
+        }
+
+        let movie = service
+            .set_condition(
+                user_id,
+                ids[0],
+                DiscCondition {
+                    grade: Some(ConditionGrade::Mint),
+                    extras: vec![
+                        IncludedExtra::DigitalCode,
+                        IncludedExtra::Slipcover,
+                        IncludedExtra::DigitalCode,
+                    ],
+                },
+            )
+            .await
+            .unwrap();
+        assert!(movie.slip_cover);
+        assert_eq!(movie.included_extras.as_deref(), Some("digital_code"));
+        service
+            .set_condition(
+                user_id,
+                ids[1],
+                DiscCondition {
+                    grade: Some(ConditionGrade::Good),
+                    extras: vec![IncludedExtra::Booklet],
+                },
+            )
+            .await
+            .unwrap();
+
+        let condition = service.get_condition(user_id, ids[0]).await.unwrap();
+        assert_eq!(condition.grade, Some(ConditionGrade::Mint));
+        assert_eq!(
+            condition.extras,
+            vec![IncludedExtra::Slipcover, IncludedExtra::DigitalCode]
+        );
+
+        async fn titles(service: &MovieService, user_id: Uuid, filter: MovieFilter) -> Vec<String> {
+            let movies = service.list(user_id, filter).await.unwrap();
+            movies.into_iter().map(|m| m.title).collect()
+        }
+        let at_least_very_good = MovieFilter {
+            min_condition: Some(ConditionGrade::VeryGood),
+            ..Default::default()
+        };
+        assert_eq!(
+            titles(&service, user_id, at_least_very_good).await,
+            vec!["Heat"]
+        );
+        let good = MovieFilter {
+            condition: Some(ConditionGrade::Good),
+            ..Default::default()
+        };
+        assert_eq!(titles(&service, user_id, good).await, vec!["Alien"]);
+        let with_slipcover = MovieFilter {
+            extra: Some(IncludedExtra::Slipcover),
+            ..Default::default()
+        };
+        assert_eq!(
+            titles(&service, user_id, with_slipcover).await,
+            vec!["Heat"]
+        );
+        let without_code = MovieFilter {
+            missing_extra: Some(IncludedExtra::DigitalCode),
+            ..Default::default()
+        };
+        assert_eq!(
+            titles(&service, user_id, without_code).await,
+            vec!["Alien", "Up"]
+        );
+        let booklet_count = MovieFilter {
+            extra: Some(IncludedExtra::Booklet),
+            ..Default::default()
+        };
+        assert_eq!(service.count(user_id, &booklet_count).await.unwrap(), 1);
+
+        // Free text conditions are validated on update
+        let invalid = UpdateMovie {
+            condition: Some("zerkratzt".to_string()),
+            ..Default::default()
+        };
+        assert!(service.update(user_id, ids[2], invalid).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_dispose_and_restore_movie() {
+        let service = setup().await;
+        let user_id = fixtures::test_user_id();
+
+        let mut ids = Vec::new();
+        for title in ["Heat", "Alien"] {
+            let movie = service
+                .create(
+                    user_id,
+                    CreateMovie {
+                        barcode: None,
+                        tmdb_id: None,
+                        title: title.to_string(),
+                        original_title: None,
+                        disc_type: Some("Blu-ray".to_string()),
+                        production_year: None,
+                    },
+                )
+                .await
+                .unwrap();
+            ids.push(movie.id);
+        }
+        sqlx::query("UPDATE movies SET price = 20.0, currency = 'EUR'")
+            .execute(&service.pool)
+            .await
+            .unwrap();
+
+        let date = chrono::NaiveDate::from_ymd_opt(2025, 5, 1).unwrap();
+        let sold = service
+            .dispose(
+                user_id,
+                ids[0],
+                DisposeMovie {
+                    disposition: Disposition::Sold,
+                    date: Some(date),
+                    price: Some(12.5),
+                    currency: Some("EUR".to_string()),
+                    recipient: Some("Flohmarkt".to_string()),
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(sold.disposition, Some(Disposition::Sold));
+        assert_eq!(sold.disposed_at, Some(date));
+        assert_eq!(sold.disposed_to.as_deref(), Some("Flohmarkt"));
+
+        let active = MovieFilter {
+            disposed: Some(false),
+            ..Default::default()
+        };
+        assert_eq!(service.count(user_id, &active).await.unwrap(), 1);
+        let history = MovieFilter {
+            disposed: Some(true),
+            ..Default::default()
+        };
+        let history = service.list(user_id, history).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].disposal_price, Some(12.5));
+
+        let report = service.value_report(user_id).await.unwrap();
+        assert_eq!(report.owned_count, 1);
+        assert_eq!(report.disposed_count, 1);
+        assert_eq!(report.totals.len(), 1);
+        assert_eq!(report.totals[0].currency.as_deref(), Some("EUR"));
+        assert_eq!(report.totals[0].purchase_total, 20.0);
+        assert_eq!(report.totals[0].disposed_purchase_total, 20.0);
+        assert_eq!(report.totals[0].disposal_total, 12.5);
+
+        let restored = service.restore_disposed(user_id, ids[0]).await.unwrap();
+        assert_eq!(restored.disposition, None);
+        assert_eq!(restored.disposal_price, None);
+        assert_eq!(service.count(user_id, &active).await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_wishlist_move_to_collection() {
+        let service = setup().await;
+        let user_id = fixtures::test_user_id();
+
+        let mut ids = Vec::new();
+        for title in ["Heat", "Ronin"] {
+            let movie = service
+                .create(
+                    user_id,
+                    CreateMovie {
+                        barcode: None,
+                        tmdb_id: None,
+                        title: title.to_string(),
+                        original_title: None,
+                        disc_type: None,
+                        production_year: None,
+                    },
+                )
+                .await
+                .unwrap();
+            ids.push(movie.id);
+        }
+        let update = UpdateMovie {
+            status: Some("Wish List".to_string()),
+            ..Default::default()
+        };
+        let wished = service.update(user_id, ids[0], update).await.unwrap();
+        assert_eq!(wished.status.as_deref(), Some("wishlist"));
+
+        let wishlist = MovieFilter {
+            status: Some(MovieStatus::Wishlist),
+            ..Default::default()
+        };
+        let owned = MovieFilter {
+            status: Some(MovieStatus::Owned),
+            ..Default::default()
+        };
+        let listed = service.list(user_id, wishlist).await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, ids[0]);
+        assert_eq!(service.count(user_id, &owned).await.unwrap(), 1);
+
+        let date = chrono::NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+        let acquired = service
+            .move_to_collection(
+                user_id,
+                ids[0],
+                AcquireMovie {
+                    purchase_date: Some(date),
+                    price: Some(9.99),
+                    currency: Some("eur".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(acquired.status.as_deref(), Some("owned"));
+        assert_eq!(acquired.purchase_date, Some(date));
+        assert_eq!(acquired.price, Some(9.99));
+        assert_eq!(acquired.currency.as_deref(), Some("EUR"));
+        assert_eq!(service.count(user_id, &owned).await.unwrap(), 2);
+
+        // Already owned
+        let again = service
+            .move_to_collection(user_id, ids[1], AcquireMovie::default())
+            .await;
+        assert!(matches!(again, Err(Error::Validation(_))));
+
+        let update = UpdateMovie {
+            status: Some("lost".to_string()),
+            ..Default::default()
+        };
+        let invalid = service.update(user_id, ids[1], update).await;
+        assert!(matches!(invalid, Err(Error::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn test_availability_and_stats() {
+        let service = setup().await;
+        let user_id = fixtures::test_user_id();
+
+        let mut ids = Vec::new();
+        for title in [
+            "Alien",
+            "Aliens",
+            "Alien 3",
+            "Prometheus",
+            "Covenant",
+            "Romulus",
+        ] {
+            let movie = service
+                .create(
+                    user_id,
+                    CreateMovie {
+                        barcode: None,
+                        tmdb_id: None,
+                        title: title.to_string(),
+                        original_title: None,
+                        disc_type: None,
+                        production_year: None,
+                    },
+                )
+                .await
+                .unwrap();
+            assert_eq!(movie.availability, Some(MovieAvailability::Available));
+            ids.push(movie.id);
+        }
+
+        let lent = UpdateMovie {
+            lent_to: Some("Anna".to_string()),
+            ..Default::default()
+        };
+        let lent = service.update(user_id, ids[1], lent).await.unwrap();
+        assert_eq!(lent.availability, Some(MovieAvailability::Lent));
+        let missing = UpdateMovie {
+            missing: Some(true),
+            ..Default::default()
+        };
+        let missing = service.update(user_id, ids[2], missing).await.unwrap();
+        assert_eq!(missing.availability, Some(MovieAvailability::Missing));
+        let sold = DisposeMovie {
+            disposition: Disposition::Sold,
+            date: None,
+            price: None,
+            currency: None,
+            recipient: None,
+        };
+        let sold = service.dispose(user_id, ids[3], sold).await.unwrap();
+        assert_eq!(sold.availability, Some(MovieAvailability::Sold));
+        for (id, status) in [(ids[4], "ordered"), (ids[5], "wishlist")] {
+            let update = UpdateMovie {
+                status: Some(status.to_string()),
+                ..Default::default()
+            };
+            service.update(user_id, id, update).await.unwrap();
+        }
+
+        let filter = MovieFilter {
+            availability: Some(MovieAvailability::Lent),
+            ..Default::default()
+        };
+        let listed = service.list(user_id, filter).await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, ids[1]);
+        assert_eq!(listed[0].availability, Some(MovieAvailability::Lent));
+
+        let stats = service.stats(user_id, 2026).await.unwrap();
+        assert_eq!(stats.total, 6);
+        assert_eq!(stats.available, 1);
+        assert_eq!(stats.lent, 1);
+        assert_eq!(stats.missing, 1);
+        assert_eq!(stats.sold, 1);
+        assert_eq!(stats.ordered, 1);
+        assert_eq!(stats.wishlist, 1);
+    }
+
+    #[tokio::test]
+    async fn test_listing_text() {
+        let service = setup().await;
+        let user_id = fixtures::test_user_id();
+
+        let movie = service
+            .create(
+                user_id,
+                CreateMovie {
+                    barcode: None,
+                    tmdb_id: None,
+                    title: "Das Boot".to_string(),
+                    original_title: Some("Das Boot".to_string()),
+                    disc_type: Some("Blu-ray".to_string()),
+                    production_year: Some(1981),
+                },
+            )
+            .await
+            .unwrap();
+        sqlx::query(
+            "UPDATE movies SET edition = 'Director''s Cut', condition = 'Sehr gut', slip_cover = 1",
+        )
+        .execute(&service.pool)
+        .await
+        .unwrap();
+        let movie = service.get_by_id(user_id, movie.id).await.unwrap();
+
+        assert_eq!(
+            listing_text(&movie, true),
+            "Das Boot (1981)\nFormat: Blu-ray\nEdition: Director's Cut\nZustand: Sehr gut\nMit Schuber"
+        );
+        assert!(listing_text(&movie, false).contains("Condition: Sehr gut"));
+    }
+
+    #[tokio::test]
+    async fn test_delete_movie() {
+        let service = setup().await;
+        let user_id = fixtures::test_user_id();
+
+        let movie = service
+            .create(
+                user_id,
+                CreateMovie {
+                    barcode: None,
+                    tmdb_id: Some(550),
+                    title: "Fight Club".to_string(),
+                    original_title: None,
+                    disc_type: None,
+                    production_year: None,
+                },
+            )
+            .await
+            .unwrap();
+
+        service.delete(user_id, movie.id).await.unwrap();
+
+        let result = service.get_by_id(user_id, movie.id).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_updated_since_and_tombstones() {
+        let service = setup().await;
+        let tombstones = crate::services::TombstoneService::new(service.pool.clone());
+        let user_id = fixtures::test_user_id();
+
+        let mut ids = Vec::new();
+        for title in ["Alien", "Aliens", "Alien 3"] {
+            let movie = service
+                .create(
+                    user_id,
+                    CreateMovie {
+                        barcode: None,
+                        tmdb_id: None,
+                        title: title.to_string(),
+                        original_title: None,
+                        disc_type: None,
+                        production_year: None,
+                    },
+                )
+                .await
+                .unwrap();
+            ids.push(movie.id);
+        }
+
+        let since = Utc::now();
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+
+        service
+            .update(
+                user_id,
+                ids[0],
+                UpdateMovie {
+                    watched: Some(true),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        service.delete(user_id, ids[1]).await.unwrap();
+
+        let filter = MovieFilter {
+            updated_since: Some(since),
+            ..Default::default()
+        };
+        assert_eq!(service.count(user_id, &filter).await.unwrap(), 1);
+        let changed = service.list(user_id, filter).await.unwrap();
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].id, ids[0]);
+
+        let deleted = tombstones
+            .since(user_id, since, Some(EntityType::Movie))
+            .await
+            .unwrap();
+        assert_eq!(deleted.len(), 1);
+        assert_eq!(deleted[0].entity_id, ids[1]);
+
+        // delete_all leaves a tombstone for every movie
+        service.delete_all(user_id).await.unwrap();
+        let deleted = tombstones.since(user_id, since, None).await.unwrap();
+        assert_eq!(deleted.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_delete_all_movies() {
+        let service = setup().await;
+        let user_id = fixtures::test_user_id();
+
+        for i in 1..=5i64 {
+            service
+                .create(
+                    user_id,
+                    CreateMovie {
+                        barcode: None,
+                        tmdb_id: Some(i),
+                        title: format!("Movie {}", i),
+                        original_title: None,
+                        disc_type: None,
+                        production_year: None,
+                    },
+                )
+                .await
+                .unwrap();
+        }
+
+        let deleted = service.delete_all(user_id).await.unwrap();
+        assert_eq!(deleted, 5);
+
+        let movies = service.list(user_id, MovieFilter::default()).await.unwrap();
+        assert!(movies.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_count_movies() {
+        let service = setup().await;
+        let user_id = fixtures::test_user_id();
+
+        for i in 1..=10i64 {
+            service
+                .create(
+                    user_id,
+                    CreateMovie {
+                        barcode: None,
+                        tmdb_id: Some(i),
+                        title: format!("Movie {}", i),
+                        original_title: None,
+                        disc_type: None,
+                        production_year: None,
+                    },
+                )
+                .await
+                .unwrap();
+        }
+
+        let count = service
+            .count(user_id, &MovieFilter::default())
+            .await
+            .unwrap();
+        assert_eq!(count, 10);
+    }
+
+    #[tokio::test]
+    async fn test_find_by_barcode() {
+        let service = setup().await;
+        let user_id = fixtures::test_user_id();
+
+        service
+            .create(
+                user_id,
+                CreateMovie {
+                    barcode: Some("1234567890123".to_string()),
+                    tmdb_id: Some(550),
+                    title: "Fight Club".to_string(),
+                    original_title: None,
+                    disc_type: None,
+                    production_year: None,
+                },
+            )
+            .await
+            .unwrap();
+
+        let movie = service
+            .find_by_barcode(user_id, "1234567890123")
+            .await
+            .unwrap();
+        assert!(movie.is_some());
+        assert_eq!(movie.unwrap().title, "Fight Club");
+
+        let movie = service
+            .find_by_barcode(user_id, "0000000000000")
+            .await
+            .unwrap();
+        assert!(movie.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_update_movie_poster_data() {
+        let service = setup().await;
+        let user_id = fixtures::test_user_id();
+
+        let movie = service
+            .create(
+                user_id,
+                CreateMovie {
+                    barcode: None,
+                    tmdb_id: Some(550),
+                    title: "Fight Club".to_string(),
+                    original_title: None,
+                    disc_type: None,
+                    production_year: None,
+                },
+            )
+            .await
+            .unwrap();
+
+        let poster_data = vec![1, 2, 3, 4, 5]; // Fake image data
+        service
+            .update_movie_poster_data(user_id, movie.id, Some(poster_data.clone()))
+            .await
+            .unwrap();
+
+        let retrieved_poster = service.get_poster_data(user_id, movie.id).await.unwrap();
+        assert!(retrieved_poster.is_some());
+        assert_eq!(retrieved_poster.unwrap(), poster_data);
+    }
+
+    #[tokio::test]
+    async fn test_missing_metadata() {
+        let service = setup().await;
+        let user_id = fixtures::test_user_id();
+        let create = |title: &str, tmdb_id: Option<i64>| CreateMovie {
+            barcode: None,
+            tmdb_id,
+            title: title.to_string(),
+            original_title: None,
+            disc_type: None,
+            production_year: None,
+        };
+
+        let matched = service
+            .create(user_id, create("Alien", Some(348)))
+            .await
+            .unwrap();
+        service
+            .create(user_id, create("Unbekannt", None))
+            .await
+            .unwrap();
+        service
+            .update_movie_poster_data(user_id, matched.id, Some(vec![1, 2, 3]))
+            .await
+            .unwrap();
+
+        let report = service.missing_metadata(user_id).await.unwrap();
+        let titles = |entries: &[MissingMetadataEntry]| {
+            entries.iter().map(|e| e.title.clone()).collect::<Vec<_>>()
+        };
+        assert_eq!(titles(&report.without_tmdb), vec!["Unbekannt"]);
+        assert_eq!(titles(&report.without_poster), vec!["Unbekannt"]);
+        assert_eq!(
+            titles(&report.without_description),
+            vec!["Alien", "Unbekannt"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_movie_backdrop_data() {
+        let service = setup().await;
+        let user_id = fixtures::test_user_id();
+
+        let movie = service
+            .create(
+                user_id,
+                CreateMovie {
+                    barcode: None,
+                    tmdb_id: Some(550),
+                    title: "Fight Club".to_string(),
+                    original_title: None,
+                    disc_type: None,
+                    production_year: None,
+                },
+            )
+            .await
+            .unwrap();
+        assert!(movie.backdrop_hash.is_none());
+        assert!(
+            service
+                .get_backdrop_data(user_id, movie.id)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        let backdrop = vec![9, 8, 7, 6, 5];
+        let updated = service
+            .update_movie_backdrop_data(user_id, movie.id, Some(backdrop.clone()))
+            .await
+            .unwrap();
+        assert!(updated.backdrop_hash.is_some());
+        assert_eq!(
+            service.get_backdrop_data(user_id, movie.id).await.unwrap(),
+            Some(backdrop)
+        );
+        // The poster is untouched
+        assert!(
+            service
+                .get_poster_data(user_id, movie.id)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        // Other users neither read nor change it
+        let other = fixtures::test_admin_id();
+        assert!(matches!(
+            service.get_backdrop_data(other, movie.id).await,
+            Err(Error::NotFound)
+        ));
+        assert!(matches!(
+            service
+                .update_movie_backdrop_data(other, movie.id, None)
+                .await,
+            Err(Error::NotFound)
+        ));
+
+        service
+            .update_movie_backdrop_data(user_id, movie.id, None)
+            .await
+            .unwrap();
+        assert!(
+            service
+                .get_backdrop_data(user_id, movie.id)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bulk_update() {
+        let service = setup().await;
+        let user_id = fixtures::test_user_id();
+        let admin_id = fixtures::test_admin_id();
+
+        let mut ids = Vec::new();
+        for (owner, title) in [
+            (user_id, "Alien"),
+            (user_id, "Aliens"),
+            (admin_id, "Alien 3"),
+        ] {
+            let movie = service
+                .create(
+                    owner,
+                    CreateMovie {
+                        barcode: None,
+                        tmdb_id: None,
+                        title: title.to_string(),
+                        original_title: None,
+                        disc_type: None,
+                        production_year: None,
+                    },
+                )
+                .await
+                .unwrap();
+            ids.push(movie.id);
+        }
+
+        let result = service
+            .bulk_update(
+                user_id,
+                BulkUpdateMovies {
+                    ids: ids.clone(),
+                    fields: BulkMovieFields {
+                        location: Some("Regal B".to_string()),
+                        watched: Some(true),
+                        ..Default::default()
+                    },
+                },
+            )
+            .await
+            .unwrap();
+
+        // The admin's movie is skipped
+        assert_eq!(result.updated.len(), 2);
+        for id in &ids[..2] {
+            let movie = service.get_by_id(user_id, *id).await.unwrap();
+            assert_eq!(movie.location.as_deref(), Some("Regal B"));
+            assert!(movie.watched);
+        }
+        let other = service.get_by_id(admin_id, ids[2]).await.unwrap();
+        assert_eq!(other.location, None);
+
+        // Nothing to change
+        let empty = service
+            .bulk_update(
+                user_id,
+                BulkUpdateMovies {
+                    ids,
+                    fields: BulkMovieFields::default(),
+                },
+            )
+            .await;
+        assert!(matches!(empty, Err(Error::Validation(_))));
+    }
+
+    #[test]
+    fn test_is_placeholder_barcode() {
+        assert!(is_placeholder_barcode("000000000000"));
+        assert!(is_placeholder_barcode("0000000000000"));
+        assert!(is_placeholder_barcode("111111111111"));
+        assert!(is_placeholder_barcode("999999999999"));
+        assert!(!is_placeholder_barcode("1234567890123"));
+        assert!(!is_placeholder_barcode("5050582721478"));
+    }
+
+    #[test]
+    fn test_same_disc_type() {
+        // Same types
+        assert!(same_disc_type(
+            &Some("DVD".to_string()),
+            &Some("DVD".to_string())
+        ));
+        assert!(same_disc_type(
+            &Some("dvd".to_string()),
+            &Some("DVD".to_string())
+        ));
+        assert!(same_disc_type(
+            &Some("Blu-ray".to_string()),
+            &Some("blu-ray".to_string())
+        ));
+
+        // Different types
+        assert!(!same_disc_type(
+            &Some("DVD".to_string()),
+            &Some("Blu-ray".to_string())
+        ));
+
+        // None and empty
+        assert!(same_disc_type(&None, &None));
+        assert!(same_disc_type(&None, &Some("".to_string())));
+        assert!(same_disc_type(&Some("".to_string()), &None));
+        assert!(same_disc_type(&Some("  ".to_string()), &None));
+    }
+
+    #[tokio::test]
+    async fn test_find_by_tmdb_id() {
+        let service = setup().await;
+        let user_id = fixtures::test_user_id();
+
+        service
+            .create(
+                user_id,
+                CreateMovie {
+                    barcode: None,
+                    tmdb_id: Some(550),
+                    title: "Fight Club".to_string(),
+                    original_title: None,
+                    disc_type: None,
+                    production_year: None,
+                },
+            )
+            .await
+            .unwrap();
+
+        let movie = service.find_by_tmdb_id(user_id, 550).await.unwrap();
+        assert!(movie.is_some());
+        assert_eq!(movie.unwrap().title, "Fight Club");
+
+        let movie = service.find_by_tmdb_id(user_id, 999999).await.unwrap();
+        assert!(movie.is_none());
     }
 
     #[tokio::test]
-    async fn test_list_movies_with_search() {
+    async fn test_find_by_title() {
         let service = setup().await;
         let user_id = fixtures::test_user_id();
 
@@ -863,8 +3975,8 @@ mod tests {
                 user_id,
                 CreateMovie {
                     barcode: None,
-                    tmdb_id: Some(1),
-                    title: "The Matrix".to_string(),
+                    tmdb_id: Some(550),
+                    title: "Fight Club".to_string(),
                     original_title: None,
                     disc_type: None,
                     production_year: None,
@@ -878,8 +3990,8 @@ mod tests {
                 user_id,
                 CreateMovie {
                     barcode: None,
-                    tmdb_id: Some(2),
-                    title: "Inception".to_string(),
+                    tmdb_id: Some(551),
+                    title: "Fight Club 2".to_string(),
                     original_title: None,
                     disc_type: None,
                     production_year: None,
@@ -888,149 +4000,222 @@ mod tests {
             .await
             .unwrap();
 
-        let movies = service
-            .list(
-                user_id,
-                MovieFilter {
-                    search: Some("Matrix".to_string()),
-                    ..Default::default()
-                },
-            )
+        let movies = service.find_by_title(user_id, "Fight Club").await.unwrap();
+        assert_eq!(movies.len(), 1);
+        assert_eq!(movies[0].title, "Fight Club");
+
+        let movies = service.find_by_title(user_id, "NonExistent").await.unwrap();
+        assert!(movies.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_delete_all() {
+        let service = setup().await;
+        let user_id = fixtures::test_user_id();
+
+        for i in 1..=5i64 {
+            service
+                .create(
+                    user_id,
+                    CreateMovie {
+                        barcode: None,
+                        tmdb_id: Some(i),
+                        title: format!("Movie {}", i),
+                        original_title: None,
+                        disc_type: None,
+                        production_year: None,
+                    },
+                )
+                .await
+                .unwrap();
+        }
+
+        let count = service
+            .count(user_id, &MovieFilter::default())
             .await
             .unwrap();
-        assert_eq!(movies.len(), 1);
-        assert_eq!(movies[0].title, "The Matrix");
+        assert_eq!(count, 5);
+
+        let deleted = service.delete_all(user_id).await.unwrap();
+        assert_eq!(deleted, 5);
+
+        let count = service
+            .count(user_id, &MovieFilter::default())
+            .await
+            .unwrap();
+        assert_eq!(count, 0);
     }
 
     #[tokio::test]
-    async fn test_list_movies_with_disc_type_filter() {
+    async fn test_get_movie_ids_with_poster() {
         let service = setup().await;
         let user_id = fixtures::test_user_id();
 
-        service
+        // Create movies without posters
+        let movie1 = service
             .create(
                 user_id,
                 CreateMovie {
                     barcode: None,
                     tmdb_id: Some(1),
-                    title: "DVD Movie".to_string(),
+                    title: "Movie 1".to_string(),
                     original_title: None,
-                    disc_type: Some("DVD".to_string()),
+                    disc_type: None,
                     production_year: None,
                 },
             )
             .await
             .unwrap();
 
-        service
+        let _movie2 = service
             .create(
                 user_id,
                 CreateMovie {
                     barcode: None,
                     tmdb_id: Some(2),
-                    title: "Blu-ray Movie".to_string(),
+                    title: "Movie 2".to_string(),
                     original_title: None,
-                    disc_type: Some("Blu-ray".to_string()),
+                    disc_type: None,
                     production_year: None,
                 },
             )
             .await
             .unwrap();
 
-        let movies = service
-            .list(
-                user_id,
-                MovieFilter {
-                    disc_type: Some("DVD".to_string()),
-                    ..Default::default()
-                },
-            )
+        // Add poster to movie1 only
+        service
+            .update_movie_poster_data(user_id, movie1.id, Some(vec![1, 2, 3]))
             .await
             .unwrap();
-        assert_eq!(movies.len(), 1);
-        assert_eq!(movies[0].title, "DVD Movie");
+
+        let ids_with_poster = service.get_movie_ids_with_poster(user_id).await.unwrap();
+        assert_eq!(ids_with_poster.len(), 1);
+        assert_eq!(ids_with_poster[0], movie1.id);
     }
 
     #[tokio::test]
-    async fn test_update_movie() {
+    async fn test_household_list() {
         let service = setup().await;
         let user_id = fixtures::test_user_id();
+        let admin_id = fixtures::test_admin_id();
+        for (owner, title) in [(user_id, "Alien"), (admin_id, "Aliens")] {
+            service
+                .create(
+                    owner,
+                    CreateMovie {
+                        barcode: None,
+                        tmdb_id: None,
+                        title: title.to_string(),
+                        original_title: None,
+                        disc_type: None,
+                        production_year: None,
+                    },
+                )
+                .await
+                .unwrap();
+        }
+        let household = MovieFilter {
+            include_household: Some(true),
+            ..Default::default()
+        };
 
-        let movie = service
-            .create(
-                user_id,
-                CreateMovie {
-                    barcode: None,
-                    tmdb_id: Some(550),
-                    title: "Fight Club".to_string(),
-                    original_title: None,
-                    disc_type: None,
-                    production_year: None,
-                },
-            )
-            .await
-            .unwrap();
+        // Without a household only the own movies show up
+        assert_eq!(service.count(user_id, &household).await.unwrap(), 1);
 
-        let updated = service
-            .update(
-                user_id,
-                movie.id,
-                UpdateMovie {
-                    title: Some("Fight Club (Updated)".to_string()),
-                    personal_rating: Some(9.0),
-                    watched: Some(true),
-                    ..Default::default()
-                },
-            )
+        let households = crate::services::HouseholdService::new(service.pool.clone());
+        let home = households.create(admin_id, "Home").await.unwrap();
+        households.invite(admin_id, "testuser").await.unwrap();
+
+        // Invited users don't see the household's movies, nor it theirs
+        assert_eq!(service.count(user_id, &household).await.unwrap(), 1);
+        assert_eq!(service.count(admin_id, &household).await.unwrap(), 1);
+
+        households.accept(user_id, home.id).await.unwrap();
+
+        assert_eq!(service.count(user_id, &household).await.unwrap(), 2);
+        assert_eq!(
+            service
+                .count(user_id, &MovieFilter::default())
+                .await
+                .unwrap(),
+            1
+        );
+        let movies = service.list(user_id, household).await.unwrap();
+        assert_eq!(movies.len(), 2);
+        assert_eq!(movies[0].title, "Alien");
+        assert_eq!(movies[0].owner_name, None);
+        assert_eq!(movies[1].owner_name.as_deref(), Some("testadmin"));
+
+        let members = households.member_ids(user_id).await.unwrap();
+        let duplicates = service
+            .find_duplicates_across(user_id, &members, "Aliens", None, None)
             .await
             .unwrap();
-
-        assert_eq!(updated.title, "Fight Club (Updated)");
-        assert_eq!(updated.personal_rating, Some(9.0));
-        assert!(updated.watched);
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].user_id, admin_id);
+        assert_eq!(duplicates[0].owner_name.as_deref(), Some("testadmin"));
     }
 
     #[tokio::test]
-    async fn test_delete_movie() {
+    async fn test_find_duplicates() {
         let service = setup().await;
         let user_id = fixtures::test_user_id();
 
-        let movie = service
+        // Create one movie
+        service
             .create(
                 user_id,
                 CreateMovie {
-                    barcode: None,
+                    barcode: Some("1234567890123".to_string()),
                     tmdb_id: Some(550),
                     title: "Fight Club".to_string(),
                     original_title: None,
-                    disc_type: None,
+                    disc_type: Some("DVD".to_string()),
                     production_year: None,
                 },
             )
             .await
             .unwrap();
 
-        service.delete(user_id, movie.id).await.unwrap();
+        // Check for duplicates - should find the existing movie by barcode
+        let duplicates = service
+            .find_duplicates(user_id, "Fight Club", Some("1234567890123"), Some(550))
+            .await
+            .unwrap();
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].title, "Fight Club");
 
-        let result = service.get_by_id(user_id, movie.id).await;
-        assert!(result.is_err());
+        // Check with unknown barcode - should find by title
+        let duplicates = service
+            .find_duplicates(user_id, "Fight Club", None, None)
+            .await
+            .unwrap();
+        assert_eq!(duplicates.len(), 1);
+
+        // Check with non-matching data - should find nothing
+        let duplicates = service
+            .find_duplicates(user_id, "NonExistent", None, None)
+            .await
+            .unwrap();
+        assert!(duplicates.is_empty());
     }
 
     #[tokio::test]
-    async fn test_delete_all_movies() {
+    async fn test_find_all_duplicates() {
         let service = setup().await;
         let user_id = fixtures::test_user_id();
 
-        for i in 1..=5i64 {
+        // Create duplicates for barcode A
+        for _ in 0..2 {
             service
                 .create(
                     user_id,
                     CreateMovie {
-                        barcode: None,
-                        tmdb_id: Some(i),
-                        title: format!("Movie {}", i),
+                        barcode: Some("1111111111111".to_string()),
+                        tmdb_id: Some(1),
+                        title: "Movie A".to_string(),
                         original_title: None,
-                        disc_type: None,
+                        disc_type: Some("DVD".to_string()),
                         production_year: None,
                     },
                 )
@@ -1038,28 +4223,59 @@ mod tests {
                 .unwrap();
         }
 
-        let deleted = service.delete_all(user_id).await.unwrap();
-        assert_eq!(deleted, 5);
+        // Create duplicates for barcode B
+        for _ in 0..3 {
+            service
+                .create(
+                    user_id,
+                    CreateMovie {
+                        barcode: Some("2222222222222".to_string()),
+                        tmdb_id: Some(2),
+                        title: "Movie B".to_string(),
+                        original_title: None,
+                        disc_type: Some("DVD".to_string()),
+                        production_year: None,
+                    },
+                )
+                .await
+                .unwrap();
+        }
 
-        let movies = service.list(user_id, MovieFilter::default()).await.unwrap();
-        assert!(movies.is_empty());
+        // Create unique movie (no duplicate)
+        service
+            .create(
+                user_id,
+                CreateMovie {
+                    barcode: Some("3333333333333".to_string()),
+                    tmdb_id: Some(3),
+                    title: "Movie C".to_string(),
+                    original_title: None,
+                    disc_type: None,
+                    production_year: None,
+                },
+            )
+            .await
+            .unwrap();
+
+        let duplicate_groups = service.find_all_duplicates(user_id).await.unwrap();
+        assert_eq!(duplicate_groups.len(), 2); // Two groups of duplicates
     }
 
     #[tokio::test]
-    async fn test_count_movies() {
+    async fn test_find_all_duplicates_numerals() {
         let service = setup().await;
         let user_id = fixtures::test_user_id();
 
-        for i in 1..=10i64 {
+        for title in ["Rocky II", "Rocky 2", "Rocky III", "Sieben", "Se7en"] {
             service
                 .create(
                     user_id,
                     CreateMovie {
                         barcode: None,
-                        tmdb_id: Some(i),
-                        title: format!("Movie {}", i),
+                        tmdb_id: None,
+                        title: title.to_string(),
                         original_title: None,
-                        disc_type: None,
+                        disc_type: Some("DVD".to_string()),
                         production_year: None,
                     },
                 )
@@ -1067,49 +4283,94 @@ mod tests {
                 .unwrap();
         }
 
-        let count = service
-            .count(user_id, &MovieFilter::default())
+        let mut groups: Vec<Vec<String>> = service
+            .find_all_duplicates(user_id)
             .await
-            .unwrap();
-        assert_eq!(count, 10);
+            .unwrap()
+            .into_iter()
+            .map(|group| {
+                let mut titles: Vec<String> = group.into_iter().map(|m| m.title).collect();
+                titles.sort();
+                titles
+            })
+            .collect();
+        groups.sort();
+        assert_eq!(
+            groups,
+            vec![vec!["Rocky 2", "Rocky II"], vec!["Se7en", "Sieben"]]
+        );
+
+        let found = service.find_by_title(user_id, "Rocky Two").await.unwrap();
+        assert_eq!(found.len(), 2);
     }
 
     #[tokio::test]
-    async fn test_find_by_barcode() {
+    async fn test_list_with_filters() {
         let service = setup().await;
         let user_id = fixtures::test_user_id();
 
+        // Create movies with different attributes
         service
             .create(
                 user_id,
                 CreateMovie {
-                    barcode: Some("1234567890123".to_string()),
-                    tmdb_id: Some(550),
-                    title: "Fight Club".to_string(),
+                    barcode: None,
+                    tmdb_id: Some(1),
+                    title: "Action Movie".to_string(),
                     original_title: None,
-                    disc_type: None,
-                    production_year: None,
+                    disc_type: Some("DVD".to_string()),
+                    production_year: Some(2020),
                 },
             )
             .await
             .unwrap();
 
-        let movie = service
-            .find_by_barcode(user_id, "1234567890123")
+        service
+            .create(
+                user_id,
+                CreateMovie {
+                    barcode: None,
+                    tmdb_id: Some(2),
+                    title: "Comedy Movie".to_string(),
+                    original_title: None,
+                    disc_type: Some("Blu-ray".to_string()),
+                    production_year: Some(2021),
+                },
+            )
             .await
             .unwrap();
-        assert!(movie.is_some());
-        assert_eq!(movie.unwrap().title, "Fight Club");
 
-        let movie = service
-            .find_by_barcode(user_id, "0000000000000")
+        // Test search filter
+        let movies = service
+            .list(
+                user_id,
+                MovieFilter {
+                    search: Some("Action".to_string()),
+                    ..Default::default()
+                },
+            )
             .await
             .unwrap();
-        assert!(movie.is_none());
+        assert_eq!(movies.len(), 1);
+        assert_eq!(movies[0].title, "Action Movie");
+
+        // Test disc_type filter
+        let movies = service
+            .list(
+                user_id,
+                MovieFilter {
+                    disc_type: Some("DVD".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(movies.len(), 1);
+        assert_eq!(movies[0].disc_type, Some("DVD".to_string()));
     }
 
     #[tokio::test]
-    async fn test_update_movie_poster_data() {
+    async fn test_update_movie_various_fields() {
         let service = setup().await;
         let user_id = fixtures::test_user_id();
 
@@ -1128,96 +4389,146 @@ mod tests {
             .await
             .unwrap();
 
-        let poster_data = vec![1, 2, 3, 4, 5]; // Fake image data
-        service
-            .update_movie_poster_data(user_id, movie.id, Some(poster_data.clone()))
+        // Update multiple fields
+        let updated = service
+            .update(
+                user_id,
+                movie.id,
+                UpdateMovie {
+                    title: Some("Fight Club (Special Edition)".to_string()),
+                    watched: Some(true),
+                    personal_rating: Some(9.5),
+                    location: Some("Shelf A".to_string()),
+                    notes: Some("Great movie!".to_string()),
+                    ..Default::default()
+                },
+            )
             .await
             .unwrap();
 
-        let retrieved_poster = service.get_poster_data(user_id, movie.id).await.unwrap();
-        assert!(retrieved_poster.is_some());
-        assert_eq!(retrieved_poster.unwrap(), poster_data);
+        assert_eq!(updated.title, "Fight Club (Special Edition)");
+        assert!(updated.watched);
+        assert_eq!(updated.personal_rating, Some(9.5));
+        assert_eq!(updated.location, Some("Shelf A".to_string()));
+        assert_eq!(updated.notes, Some("Great movie!".to_string()));
     }
 
-    #[test]
-    fn test_is_placeholder_barcode() {
-        assert!(is_placeholder_barcode("000000000000"));
-        assert!(is_placeholder_barcode("0000000000000"));
-        assert!(is_placeholder_barcode("111111111111"));
-        assert!(is_placeholder_barcode("999999999999"));
-        assert!(!is_placeholder_barcode("1234567890123"));
-        assert!(!is_placeholder_barcode("5050582721478"));
-    }
+    #[tokio::test]
+    async fn test_collection_child_disc_number() {
+        let service = setup().await;
+        let user_id = fixtures::test_user_id();
 
-    #[test]
-    fn test_same_disc_type() {
-        // Same types
-        assert!(same_disc_type(
-            &Some("DVD".to_string()),
-            &Some("DVD".to_string())
-        ));
-        assert!(same_disc_type(
-            &Some("dvd".to_string()),
-            &Some("DVD".to_string())
-        ));
-        assert!(same_disc_type(
-            &Some("Blu-ray".to_string()),
-            &Some("blu-ray".to_string())
-        ));
+        let mut ids = Vec::new();
+        for title in ["Bourne Trilogy", "The Bourne Supremacy"] {
+            let movie = service
+                .create(
+                    user_id,
+                    CreateMovie {
+                        barcode: None,
+                        tmdb_id: None,
+                        title: title.to_string(),
+                        original_title: None,
+                        disc_type: None,
+                        production_year: None,
+                    },
+                )
+                .await
+                .unwrap();
+            ids.push(movie.id);
+        }
 
-        // Different types
-        assert!(!same_disc_type(
-            &Some("DVD".to_string()),
-            &Some("Blu-ray".to_string())
-        ));
+        let child = service
+            .update(
+                user_id,
+                ids[1],
+                UpdateMovie {
+                    parent_collection_id: Some(ids[0]),
+                    disc_number: Some(2),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(child.disc_number, Some(2));
 
-        // None and empty
-        assert!(same_disc_type(&None, &None));
-        assert!(same_disc_type(&None, &Some("".to_string())));
-        assert!(same_disc_type(&Some("".to_string()), &None));
-        assert!(same_disc_type(&Some("  ".to_string()), &None));
+        let movies = service
+            .list(
+                user_id,
+                MovieFilter {
+                    exclude_collection_children: Some(false),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        let listed = movies.iter().find(|m| m.id == ids[1]).unwrap();
+        assert_eq!(listed.disc_number, Some(2));
+        assert_eq!(listed.parent_collection_id, Some(ids[0]));
     }
 
     #[tokio::test]
-    async fn test_find_by_tmdb_id() {
+    async fn test_suggest_prefix_matching() {
         let service = setup().await;
         let user_id = fixtures::test_user_id();
 
-        service
-            .create(
-                user_id,
-                CreateMovie {
-                    barcode: None,
-                    tmdb_id: Some(550),
-                    title: "Fight Club".to_string(),
-                    original_title: None,
-                    disc_type: None,
-                    production_year: None,
-                },
-            )
+        for (title, original_title) in [
+            ("Der Herr der Ringe", Some("The Lord of the Rings")),
+            ("Herr der Diebe", None),
+            ("Matrix", Some("The Matrix")),
+        ] {
+            service
+                .create(
+                    user_id,
+                    CreateMovie {
+                        barcode: None,
+                        tmdb_id: None,
+                        title: title.to_string(),
+                        original_title: original_title.map(String::from),
+                        disc_type: None,
+                        production_year: Some(2001),
+                    },
+                )
+                .await
+                .unwrap();
+        }
+
+        let results = service.suggest(user_id, "her", 10).await.unwrap();
+        assert_eq!(results.len(), 2);
+
+        let results = service.suggest(user_id, "herr ring", 10).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].title, "Der Herr der Ringe");
+        assert_eq!(results[0].year, Some(2001));
+
+        // Original title is indexed too
+        let results = service.suggest(user_id, "lord", 10).await.unwrap();
+        assert_eq!(results.len(), 1);
+
+        // Other users' movies are not suggested
+        let results = service
+            .suggest(fixtures::test_admin_id(), "matrix", 10)
             .await
             .unwrap();
+        assert!(results.is_empty());
 
-        let movie = service.find_by_tmdb_id(user_id, 550).await.unwrap();
-        assert!(movie.is_some());
-        assert_eq!(movie.unwrap().title, "Fight Club");
-
-        let movie = service.find_by_tmdb_id(user_id, 999999).await.unwrap();
-        assert!(movie.is_none());
+        // FTS syntax is treated as text
+        let results = service.suggest(user_id, "\"mat* OR", 10).await.unwrap();
+        assert!(results.is_empty());
+        assert!(service.suggest(user_id, "  ", 10).await.unwrap().is_empty());
     }
 
     #[tokio::test]
-    async fn test_find_by_title() {
+    async fn test_suggest_follows_updates_and_deletes() {
         let service = setup().await;
         let user_id = fixtures::test_user_id();
 
-        service
+        let movie = service
             .create(
                 user_id,
                 CreateMovie {
                     barcode: None,
-                    tmdb_id: Some(550),
-                    title: "Fight Club".to_string(),
+                    tmdb_id: None,
+                    title: "Alien".to_string(),
                     original_title: None,
                     disc_type: None,
                     production_year: None,
@@ -1227,41 +4538,44 @@ mod tests {
             .unwrap();
 
         service
-            .create(
+            .update(
                 user_id,
-                CreateMovie {
-                    barcode: None,
-                    tmdb_id: Some(551),
-                    title: "Fight Club 2".to_string(),
-                    original_title: None,
-                    disc_type: None,
-                    production_year: None,
+                movie.id,
+                UpdateMovie {
+                    title: Some("Aliens".to_string()),
+                    ..Default::default()
                 },
             )
             .await
             .unwrap();
 
-        let movies = service.find_by_title(user_id, "Fight Club").await.unwrap();
-        assert_eq!(movies.len(), 1);
-        assert_eq!(movies[0].title, "Fight Club");
+        let results = service.suggest(user_id, "aliens", 10).await.unwrap();
+        assert_eq!(results.len(), 1);
 
-        let movies = service.find_by_title(user_id, "NonExistent").await.unwrap();
-        assert!(movies.is_empty());
+        service.delete(user_id, movie.id).await.unwrap();
+        assert!(
+            service
+                .suggest(user_id, "ali", 10)
+                .await
+                .unwrap()
+                .is_empty()
+        );
     }
 
     #[tokio::test]
-    async fn test_delete_all() {
+    async fn test_record_and_list_views() {
         let service = setup().await;
         let user_id = fixtures::test_user_id();
 
-        for i in 1..=5i64 {
-            service
+        let mut ids = Vec::new();
+        for title in ["Alien", "Heat", "Ronin"] {
+            let movie = service
                 .create(
                     user_id,
                     CreateMovie {
                         barcode: None,
-                        tmdb_id: Some(i),
-                        title: format!("Movie {}", i),
+                        tmdb_id: None,
+                        title: title.to_string(),
                         original_title: None,
                         disc_type: None,
                         production_year: None,
@@ -1269,129 +4583,204 @@ mod tests {
                 )
                 .await
                 .unwrap();
+            ids.push(movie.id);
         }
 
-        let count = service
-            .count(user_id, &MovieFilter::default())
-            .await
-            .unwrap();
-        assert_eq!(count, 5);
+        service.record_view(user_id, ids[0]).await.unwrap();
+        service.record_view(user_id, ids[0]).await.unwrap();
+        service.record_view(user_id, ids[0]).await.unwrap();
+        service.record_view(user_id, ids[1]).await.unwrap();
+        service.record_view(user_id, ids[2]).await.unwrap();
 
-        let deleted = service.delete_all(user_id).await.unwrap();
-        assert_eq!(deleted, 5);
+        let recent = service.recent_views(user_id, 10, false).await.unwrap();
+        assert_eq!(recent.len(), 3);
+        assert_eq!(recent[0].title, "Ronin");
 
-        let count = service
-            .count(user_id, &MovieFilter::default())
-            .await
-            .unwrap();
-        assert_eq!(count, 0);
+        let most_viewed = service.recent_views(user_id, 10, true).await.unwrap();
+        assert_eq!(most_viewed[0].title, "Alien");
+        assert_eq!(most_viewed[0].view_count, 3);
+
+        // Views of other users' movies are rejected
+        let result = service.record_view(fixtures::test_admin_id(), ids[0]).await;
+        assert!(matches!(result, Err(Error::NotFound)));
+
+        // Deleted movies disappear from the history
+        service.delete(user_id, ids[0]).await.unwrap();
+        let recent = service.recent_views(user_id, 10, false).await.unwrap();
+        assert_eq!(recent.len(), 2);
     }
 
     #[tokio::test]
-    async fn test_get_movie_ids_with_poster() {
+    async fn test_toggle_favorite_and_filter() {
         let service = setup().await;
         let user_id = fixtures::test_user_id();
 
-        // Create movies without posters
-        let movie1 = service
-            .create(
-                user_id,
-                CreateMovie {
-                    barcode: None,
-                    tmdb_id: Some(1),
-                    title: "Movie 1".to_string(),
-                    original_title: None,
-                    disc_type: None,
-                    production_year: None,
-                },
-            )
-            .await
-            .unwrap();
+        let mut ids = Vec::new();
+        for title in ["Alien", "Heat"] {
+            let movie = service
+                .create(
+                    user_id,
+                    CreateMovie {
+                        barcode: None,
+                        tmdb_id: None,
+                        title: title.to_string(),
+                        original_title: None,
+                        disc_type: None,
+                        production_year: None,
+                    },
+                )
+                .await
+                .unwrap();
+            assert!(!movie.favorite);
+            ids.push(movie.id);
+        }
 
-        let _movie2 = service
-            .create(
-                user_id,
-                CreateMovie {
-                    barcode: None,
-                    tmdb_id: Some(2),
-                    title: "Movie 2".to_string(),
-                    original_title: None,
-                    disc_type: None,
-                    production_year: None,
-                },
-            )
-            .await
-            .unwrap();
+        let movie = service.toggle_favorite(user_id, ids[0]).await.unwrap();
+        assert!(movie.favorite);
 
-        // Add poster to movie1 only
-        service
-            .update_movie_poster_data(user_id, movie1.id, Some(vec![1, 2, 3]))
-            .await
-            .unwrap();
+        let filter = MovieFilter {
+            favorites: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(service.count(user_id, &filter).await.unwrap(), 1);
+        let favorites = service.list(user_id, filter).await.unwrap();
+        assert_eq!(favorites.len(), 1);
+        assert_eq!(favorites[0].title, "Alien");
+        assert!(favorites[0].favorite);
+
+        let movie = service.toggle_favorite(user_id, ids[0]).await.unwrap();
+        assert!(!movie.favorite);
+
+        let result = service
+            .toggle_favorite(fixtures::test_admin_id(), ids[1])
+            .await;
+        assert!(matches!(result, Err(Error::NotFound)));
+    }
 
-        let ids_with_poster = service.get_movie_ids_with_poster(user_id).await.unwrap();
-        assert_eq!(ids_with_poster.len(), 1);
-        assert_eq!(ids_with_poster[0], movie1.id);
+    #[test]
+    fn test_completeness_percent() {
+        assert_eq!(completeness_percent(2, 3), Some(66.7));
+        assert_eq!(completeness_percent(3, 3), Some(100.0));
+        assert_eq!(completeness_percent(5, 3), Some(100.0));
+        assert_eq!(completeness_percent(0, 0), None);
     }
 
     #[tokio::test]
-    async fn test_find_duplicates() {
+    async fn test_update_collection_completeness() {
         let service = setup().await;
         let user_id = fixtures::test_user_id();
 
-        // Create one movie
-        service
-            .create(
+        let mut collection_id = None;
+        for (title, tmdb_id) in [
+            ("Alien Collection", None),
+            ("Alien", Some(348)),
+            ("Aliens", Some(679)),
+        ] {
+            let movie = service
+                .create(
+                    user_id,
+                    CreateMovie {
+                        barcode: None,
+                        tmdb_id,
+                        title: title.to_string(),
+                        original_title: None,
+                        disc_type: None,
+                        production_year: None,
+                    },
+                )
+                .await
+                .unwrap();
+            collection_id.get_or_insert(movie.id);
+        }
+        let collection_id = collection_id.unwrap();
+
+        let parts = [348, 679, 8077, 8078];
+        let owned = service.count_owned_tmdb_ids(user_id, &parts).await.unwrap();
+        assert_eq!(owned, 2);
+
+        let movie = service
+            .update_collection_completeness(user_id, collection_id, 8091, owned, parts.len() as i32)
+            .await
+            .unwrap();
+        assert_eq!(movie.tmdb_collection_id, Some(8091));
+        assert_eq!(movie.collection_completeness, Some(50.0));
+
+        // Exposed in list responses and sortable
+        let movies = service
+            .list(
                 user_id,
-                CreateMovie {
-                    barcode: Some("1234567890123".to_string()),
-                    tmdb_id: Some(550),
-                    title: "Fight Club".to_string(),
-                    original_title: None,
-                    disc_type: Some("DVD".to_string()),
-                    production_year: None,
+                MovieFilter {
+                    sort_by: Some("collection_completeness".to_string()),
+                    sort_order: Some("desc".to_string()),
+                    ..Default::default()
                 },
             )
             .await
             .unwrap();
+        assert_eq!(movies[0].id, collection_id);
+        assert_eq!(movies[0].collection_owned_parts, Some(2));
+    }
 
-        // Check for duplicates - should find the existing movie by barcode
-        let duplicates = service
-            .find_duplicates(user_id, "Fight Club", Some("1234567890123"), Some(550))
-            .await
-            .unwrap();
-        assert_eq!(duplicates.len(), 1);
-        assert_eq!(duplicates[0].title, "Fight Club");
+    #[test]
+    fn test_extract_season_patterns() {
+        let cases = [
+            ("Die Nanny - Staffel 1", Some(("die nanny", 1))),
+            ("Die Nanny – Staffel 2", Some(("die nanny", 2))),
+            ("Die Nanny: Season One", Some(("die nanny", 1))),
+            ("Die Nanny Season 3", Some(("die nanny", 3))),
+            (
+                "Die Nanny - Die komplette erste Staffel",
+                Some(("die nanny", 1)),
+            ),
+            ("Die Nanny - 4. Staffel", Some(("die nanny", 4))),
+            ("Friends - The Complete Second Season", Some(("friends", 2))),
+            ("Friends S05", Some(("friends", 5))),
+            ("Stargate SG-1 Staffel 10", Some(("stargate sg 1", 10))),
+            ("Staffel 1", None),
+            ("Matrix", None),
+            ("Sieben", None),
+        ];
 
-        // Check with unknown barcode - should find by title
-        let duplicates = service
-            .find_duplicates(user_id, "Fight Club", None, None)
-            .await
-            .unwrap();
-        assert_eq!(duplicates.len(), 1);
+        for (title, expected) in cases {
+            let expected = expected.map(|(show, season)| (show.to_string(), season));
+            assert_eq!(extract_season(title), expected, "{}", title);
+        }
+    }
 
-        // Check with non-matching data - should find nothing
-        let duplicates = service
-            .find_duplicates(user_id, "NonExistent", None, None)
-            .await
-            .unwrap();
-        assert!(duplicates.is_empty());
+    #[test]
+    fn test_same_season() {
+        assert_eq!(
+            same_season("Die Nanny Season One", "Die Nanny: Staffel 1"),
+            Some(true)
+        );
+        assert_eq!(
+            same_season("Die Nanny - Staffel 1", "Die Nanny - Staffel 2"),
+            Some(false)
+        );
+        assert_eq!(
+            same_season("Die Nanny - Staffel 1", "Friends - Staffel 1"),
+            None
+        );
+        assert_eq!(same_season("Matrix", "Matrix"), None);
     }
 
     #[tokio::test]
-    async fn test_find_all_duplicates() {
+    async fn test_find_all_duplicates_season_aware() {
         let service = setup().await;
         let user_id = fixtures::test_user_id();
 
-        // Create duplicates for barcode A
-        for _ in 0..2 {
+        for (title, tmdb_id) in [
+            ("Die Nanny - Staffel 1", Some(2437)),
+            ("Die Nanny: Season One", Some(2437)),
+            ("Die Nanny - Staffel 2", Some(2437)),
+        ] {
             service
                 .create(
                     user_id,
                     CreateMovie {
-                        barcode: Some("1111111111111".to_string()),
-                        tmdb_id: Some(1),
-                        title: "Movie A".to_string(),
+                        barcode: None,
+                        tmdb_id,
+                        title: title.to_string(),
                         original_title: None,
                         disc_type: Some("DVD".to_string()),
                         production_year: None,
@@ -1401,15 +4790,22 @@ mod tests {
                 .unwrap();
         }
 
-        // Create duplicates for barcode B
-        for _ in 0..3 {
-            service
+        let groups = service.find_all_duplicates(user_id).await.unwrap();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].len(), 2);
+        assert!(groups[0].iter().all(|m| m.title != "Die Nanny - Staffel 2"));
+    }
+
+    async fn create_orphans(service: &MovieService, user_id: Uuid, titles: &[&str]) -> Uuid {
+        let missing_parent = Uuid::new_v4();
+        for title in titles {
+            let movie = service
                 .create(
                     user_id,
                     CreateMovie {
-                        barcode: Some("2222222222222".to_string()),
-                        tmdb_id: Some(2),
-                        title: "Movie B".to_string(),
+                        barcode: None,
+                        tmdb_id: None,
+                        title: title.to_string(),
                         original_title: None,
                         disc_type: Some("DVD".to_string()),
                         production_year: None,
@@ -1417,134 +4813,439 @@ mod tests {
                 )
                 .await
                 .unwrap();
+            // Bypass foreign keys to simulate a link left behind by older versions
+            sqlx::query("PRAGMA foreign_keys = OFF")
+                .execute(&service.pool)
+                .await
+                .unwrap();
+            sqlx::query("UPDATE movies SET parent_collection_id = ? WHERE id = ?")
+                .bind(missing_parent)
+                .bind(movie.id)
+                .execute(&service.pool)
+                .await
+                .unwrap();
+            sqlx::query("PRAGMA foreign_keys = ON")
+                .execute(&service.pool)
+                .await
+                .unwrap();
         }
+        missing_parent
+    }
 
-        // Create unique movie (no duplicate)
-        service
-            .create(
+    #[tokio::test]
+    async fn test_find_and_unlink_orphans() {
+        let service = setup().await;
+        let user_id = fixtures::test_user_id();
+
+        let parent = create_orphans(&service, user_id, &["Alien", "Aliens"]).await;
+        create_orphans(&service, user_id, &["Heat"]).await;
+
+        let groups = service.find_orphans(user_id).await.unwrap();
+        assert_eq!(groups.len(), 2);
+        let group = groups
+            .iter()
+            .find(|g| g.parent_collection_id == parent)
+            .unwrap();
+        assert_eq!(group.movies.len(), 2);
+
+        let result = service
+            .fix_orphans(
                 user_id,
-                CreateMovie {
-                    barcode: Some("3333333333333".to_string()),
-                    tmdb_id: Some(3),
-                    title: "Movie C".to_string(),
-                    original_title: None,
-                    disc_type: None,
-                    production_year: None,
+                FixOrphans {
+                    action: OrphanFixAction::Unlink,
+                    parent_ids: Some(vec![parent]),
                 },
             )
             .await
             .unwrap();
-
-        let duplicate_groups = service.find_all_duplicates(user_id).await.unwrap();
-        assert_eq!(duplicate_groups.len(), 2); // Two groups of duplicates
+        assert_eq!(result.unlinked, 2);
+        assert_eq!(service.find_orphans(user_id).await.unwrap().len(), 1);
     }
 
     #[tokio::test]
-    async fn test_list_with_filters() {
+    async fn test_regroup_orphans() {
         let service = setup().await;
         let user_id = fixtures::test_user_id();
 
-        // Create movies with different attributes
-        service
-            .create(
-                user_id,
-                CreateMovie {
-                    barcode: None,
-                    tmdb_id: Some(1),
-                    title: "Action Movie".to_string(),
-                    original_title: None,
-                    disc_type: Some("DVD".to_string()),
-                    production_year: Some(2020),
-                },
-            )
-            .await
-            .unwrap();
+        create_orphans(&service, user_id, &["Alien", "Aliens"]).await;
 
-        service
-            .create(
+        let result = service
+            .fix_orphans(
                 user_id,
-                CreateMovie {
-                    barcode: None,
-                    tmdb_id: Some(2),
-                    title: "Comedy Movie".to_string(),
-                    original_title: None,
-                    disc_type: Some("Blu-ray".to_string()),
-                    production_year: Some(2021),
+                FixOrphans {
+                    action: OrphanFixAction::Regroup,
+                    parent_ids: None,
                 },
             )
             .await
             .unwrap();
+        assert_eq!(result.collections_created.len(), 1);
+        let collection = &result.collections_created[0];
+        assert!(collection.is_collection);
+        assert_eq!(collection.title, "Sammlung: Alien, Aliens");
+
+        assert!(service.find_orphans(user_id).await.unwrap().is_empty());
+        let children = service
+            .list(user_id, MovieFilter::default())
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|m| m.parent_collection_id == Some(collection.id))
+            .count();
+        assert_eq!(children, 2);
+    }
 
-        // Test search filter
-        let movies = service
-            .list(
-                user_id,
-                MovieFilter {
-                    search: Some("Action".to_string()),
-                    ..Default::default()
-                },
+    /// Movies with varied attributes for the filter combination test; returns
+    /// the id of a collection holding two of them
+    async fn create_filter_movies(service: &MovieService, user_id: Uuid) -> Uuid {
+        let titles = [
+            "The Matrix",
+            "Alien",
+            "Aliens",
+            "Heat",
+            "Matrix Reloaded",
+            "Dark City",
+            "Box Set",
+            "Se7en",
+        ];
+        let mut ids = Vec::new();
+        for (i, title) in titles.into_iter().enumerate() {
+            let owner = if i == 3 {
+                fixtures::test_admin_id()
+            } else {
+                user_id
+            };
+            let movie = service
+                .create(
+                    owner,
+                    CreateMovie {
+                        barcode: None,
+                        tmdb_id: None,
+                        title: title.to_string(),
+                        original_title: (i == 4).then(|| "The Matrix Reloaded".to_string()),
+                        disc_type: [Some("DVD"), Some("Blu-ray"), None][i % 3].map(String::from),
+                        production_year: Some(1979 + i as i32 * 5),
+                    },
+                )
+                .await
+                .unwrap();
+            sqlx::query(
+                "UPDATE movies SET director = ?, genres = ?, watched = ?, favorite = ?, \
+                 region_codes = ?, video_standard = ?, condition = ?, included_extras = ?, \
+                 slip_cover = ?, disposition = ?, status = ?, lent_to = ?, tags = ?, \
+                 is_collection = ?, updated_by = ?, updated_at = ? WHERE id = ?",
+            )
+            .bind(["Lana Wachowski", "Ridley Scott", "James Cameron"][i % 3])
+            .bind([Some("Action"), Some("Drama"), Some("Action, Drama"), None][i % 4])
+            .bind(i % 2 == 0)
+            .bind(i % 3 == 0)
+            .bind([Some("R2"), Some("RB"), Some("R0"), Some("R1,R2"), None][i % 5])
+            .bind([Some("PAL"), Some("NTSC"), None][i % 3])
+            .bind(ConditionGrade::ALL.get(i).map(|g| g.as_str()))
+            .bind(
+                [
+                    Some("booklet"),
+                    Some("booklet,poster"),
+                    None,
+                    Some("digital_code"),
+                ][i % 4],
             )
+            .bind(i % 3 == 1)
+            .bind((i == 5).then_some("sold"))
+            .bind([Some("owned"), None, Some("wishlist"), Some("ordered")][i % 4])
+            .bind((i == 1).then_some("Bob"))
+            .bind([Some("cult"), Some("cult, 4k"), None][i % 3])
+            .bind(i == 6)
+            .bind(if i % 2 == 0 {
+                user_id
+            } else {
+                fixtures::test_admin_id()
+            })
+            .bind((Utc::now() - chrono::Duration::days(i as i64 * 10)).to_rfc3339())
+            .bind(movie.id)
+            .execute(&service.pool)
             .await
             .unwrap();
-        assert_eq!(movies.len(), 1);
-        assert_eq!(movies[0].title, "Action Movie");
-
-        // Test disc_type filter
-        let movies = service
-            .list(
-                user_id,
-                MovieFilter {
-                    disc_type: Some("DVD".to_string()),
-                    ..Default::default()
-                },
-            )
+            ids.push(movie.id);
+        }
+        sqlx::query("UPDATE movies SET parent_collection_id = ? WHERE id = ?")
+            .bind(ids[6])
+            .bind(ids[7])
+            .execute(&service.pool)
             .await
             .unwrap();
-        assert_eq!(movies.len(), 1);
-        assert_eq!(movies[0].disc_type, Some("DVD".to_string()));
-    }
-
-    #[tokio::test]
-    async fn test_update_movie_various_fields() {
-        let service = setup().await;
-        let user_id = fixtures::test_user_id();
 
-        let movie = service
+        let collections = crate::services::CollectionService::new(service.pool.clone());
+        let collection = collections
             .create(
                 user_id,
-                CreateMovie {
+                crate::models::CreateCollection {
                     barcode: None,
-                    tmdb_id: Some(550),
-                    title: "Fight Club".to_string(),
-                    original_title: None,
+                    title: "Favorites".to_string(),
+                    description: None,
                     disc_type: None,
-                    production_year: None,
                 },
             )
             .await
             .unwrap();
+        for movie_id in [ids[0], ids[2]] {
+            collections
+                .add_item(
+                    user_id,
+                    collection.id,
+                    crate::models::AddCollectionItem {
+                        item_type: crate::models::CollectionItemType::Movie,
+                        movie_id: Some(movie_id),
+                        series_id: None,
+                        position: None,
+                    },
+                )
+                .await
+                .unwrap();
+        }
+        collection.id
+    }
 
-        // Update multiple fields
-        let updated = service
-            .update(
-                user_id,
-                movie.id,
-                UpdateMovie {
-                    title: Some("Fight Club (Special Edition)".to_string()),
-                    watched: Some(true),
-                    personal_rating: Some(9.5),
-                    location: Some("Shelf A".to_string()),
-                    notes: Some("Great movie!".to_string()),
-                    ..Default::default()
-                },
-            )
-            .await
+    fn movie_filter_strategy(
+        user_id: Uuid,
+        collection_id: Uuid,
+    ) -> impl Strategy<Value = MovieFilter> {
+        // Few filters at a time, so that combinations still match some movies
+        fn maybe<T: Clone + std::fmt::Debug + 'static>(
+            values: Vec<T>,
+        ) -> impl Strategy<Value = Option<T>> {
+            proptest::option::weighted(0.12, proptest::sample::select(values))
+        }
+        let text = (
+            maybe(vec!["matrix", "Scott", "Reloaded", "zzz"]),
+            maybe(vec!["Action", "Drama"]),
+            maybe(vec!["DVD", "Blu-ray"]),
+            maybe(vec!["R2", "RB", "R1"]),
+            maybe(vec!["PAL", "ntsc"]),
+            maybe(vec!["cult", "4k"]),
+        );
+        let flags = (
+            maybe(vec![true, false]),
+            maybe(vec![true, false]),
+            maybe(vec![true, false]),
+            maybe(vec![true, false]),
+            maybe(vec![true, false]),
+            maybe(vec![true, false]),
+        );
+        let grades = (
+            maybe((1975..2020).collect()),
+            maybe((1975..2020).collect()),
+            maybe(ConditionGrade::ALL.to_vec()),
+            maybe(ConditionGrade::ALL.to_vec()),
+            maybe(vec![
+                IncludedExtra::Slipcover,
+                IncludedExtra::Booklet,
+                IncludedExtra::Poster,
+            ]),
+            maybe(vec![IncludedExtra::Slipcover, IncludedExtra::DigitalCode]),
+        );
+        let misc = (
+            maybe(vec![
+                MovieStatus::Owned,
+                MovieStatus::Wishlist,
+                MovieStatus::Ordered,
+            ]),
+            maybe(vec![
+                MovieAvailability::Available,
+                MovieAvailability::Lent,
+                MovieAvailability::Sold,
+            ]),
+            maybe(vec![user_id, fixtures::test_admin_id()]),
+            maybe(vec![user_id, fixtures::test_admin_id()]),
+            maybe(vec![5i64, 25, 45]),
+            maybe(vec![collection_id, Uuid::nil()]),
+        );
+
+        (text, flags, grades, misc).prop_map(
+            move |(
+                (search, genre, disc_type, region, video_standard, tag),
+                (watched, favorites, is_collection, exclude_children, disposed, household),
+                (year_from, year_to, condition, min_condition, extra, missing_extra),
+                (status, availability, added_by, updated_by, updated_days_ago, collection_id),
+            )| MovieFilter {
+                search: search.map(String::from),
+                genre: genre.map(String::from),
+                disc_type: disc_type.map(String::from),
+                watched,
+                favorites,
+                is_collection,
+                exclude_collection_children: exclude_children,
+                year_from,
+                year_to,
+                region: region.map(String::from),
+                video_standard: video_standard.map(String::from),
+                condition,
+                min_condition,
+                extra,
+                missing_extra,
+                disposed,
+                status,
+                availability,
+                added_by,
+                updated_by,
+                updated_since: updated_days_ago
+                    .map(|days| Utc::now() - chrono::Duration::days(days)),
+                tag: tag.map(String::from),
+                collection_id,
+                include_household: household,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// One filter per set field of `filter`
+    fn single_field_filters(filter: &MovieFilter) -> Vec<MovieFilter> {
+        let base = MovieFilter {
+            include_household: filter.include_household,
+            ..Default::default()
+        };
+        let mut filters = Vec::new();
+        macro_rules! single {
+            ($($field:ident),*) => {
+                $(
+                    if filter.$field.is_some() {
+                        filters.push(MovieFilter {
+                            $field: filter.$field.clone(),
+                            ..base.clone()
+                        });
+                    }
+                )*
+            };
+        }
+        single!(
+            search,
+            genre,
+            disc_type,
+            watched,
+            favorites,
+            is_collection,
+            exclude_collection_children,
+            year_from,
+            year_to,
+            region,
+            video_standard,
+            condition,
+            min_condition,
+            extra,
+            missing_extra,
+            disposed,
+            status,
+            availability,
+            added_by,
+            updated_by,
+            updated_since,
+            tag,
+            collection_id
+        );
+        filters
+    }
+
+    /// Every combination of filters binds its parameters to the right
+    /// placeholders: it matches the movies matched by each of its filters
+    /// alone, and `count` agrees with `list`
+    #[test]
+    fn test_filter_combinations_bind_correctly() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
             .unwrap();
+        let user_id = fixtures::test_user_id();
+        let (service, collection_id) = runtime.block_on(async {
+            let service = setup().await;
+            let collection_id = create_filter_movies(&service, user_id).await;
+            (service, collection_id)
+        });
+
+        let ids = |filter: MovieFilter| {
+            let service = &service;
+            async move {
+                let mut ids: Vec<Uuid> = service
+                    .list(user_id, filter)
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .map(|m| m.id)
+                    .collect();
+                ids.sort();
+                ids
+            }
+        };
 
-        assert_eq!(updated.title, "Fight Club (Special Edition)");
-        assert!(updated.watched);
-        assert_eq!(updated.personal_rating, Some(9.5));
-        assert_eq!(updated.location, Some("Shelf A".to_string()));
-        assert_eq!(updated.notes, Some("Great movie!".to_string()));
+        let mut runner = TestRunner::new(ProptestConfig {
+            cases: 512,
+            ..ProptestConfig::default()
+        });
+        runner
+            .run(&movie_filter_strategy(user_id, collection_id), |filter| {
+                runtime.block_on(async {
+                    let conditions = movie_conditions(user_id, &filter).unwrap();
+                    prop_assert_eq!(
+                        crate::db::query::placeholders(&conditions.sql()),
+                        conditions.params().len()
+                    );
+
+                    let matched = ids(filter.clone()).await;
+                    let count = service.count(user_id, &filter).await.unwrap();
+                    prop_assert_eq!(count, matched.len() as i64);
+
+                    let mut expected = ids(MovieFilter {
+                        include_household: filter.include_household,
+                        ..Default::default()
+                    })
+                    .await;
+                    for single in single_field_filters(&filter) {
+                        let single_ids = ids(single).await;
+                        expected.retain(|id| single_ids.contains(id));
+                    }
+                    prop_assert_eq!(&matched, &expected);
+
+                    // Paging binds after the conditions
+                    let page = service
+                        .list(
+                            user_id,
+                            MovieFilter {
+                                limit: Some(2),
+                                offset: Some(1),
+                                ..filter.clone()
+                            },
+                        )
+                        .await
+                        .unwrap();
+                    let all = service.list(user_id, filter).await.unwrap();
+                    let expected_page: Vec<Uuid> =
+                        all.iter().skip(1).take(2).map(|m| m.id).collect();
+                    let page: Vec<Uuid> = page.iter().map(|m| m.id).collect();
+                    prop_assert_eq!(page, expected_page);
+                    Ok(())
+                })
+            })
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_count_matches_collection_filters() {
+        let service = setup().await;
+        let user_id = fixtures::test_user_id();
+        create_filter_movies(&service, user_id).await;
+
+        for filter in [
+            MovieFilter {
+                is_collection: Some(true),
+                ..Default::default()
+            },
+            MovieFilter {
+                exclude_collection_children: Some(true),
+                ..Default::default()
+            },
+        ] {
+            let count = service.count(user_id, &filter).await.unwrap();
+            let listed = service.list(user_id, filter).await.unwrap().len();
+            assert_eq!(count, listed as i64);
+        }
     }
 }
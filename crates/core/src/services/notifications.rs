@@ -0,0 +1,449 @@
+use std::sync::{Arc, RwLock};
+
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::db::DbPool;
+use crate::error::{Error, Result};
+use crate::models::{Device, MessageLevel, Notification, RegisterDevice};
+use crate::services::push::{PushMessage, PushOutcome, PushProvider};
+
+const MAX_MESSAGE: usize = 1000;
+const MAX_TOKEN: usize = 4096;
+const MAX_DEVICE_NAME: usize = 100;
+
+/// Number of notifications returned per list, newest first
+const LIST_LIMIT: i64 = 100;
+
+/// Notification center: messages kept per user until read or deleted, so
+/// users who were offline still see them. Also keeps the users' devices and
+/// sends them push notifications through the configured provider.
+pub struct NotificationService {
+    pool: DbPool,
+    push_provider: RwLock<Option<Arc<dyn PushProvider>>>,
+}
+
+impl NotificationService {
+    pub fn new(pool: DbPool) -> Self {
+        Self {
+            pool,
+            push_provider: RwLock::new(None),
+        }
+    }
+
+    /// Replace the push provider at runtime (e.g., when settings are changed);
+    /// `None` disables push
+    pub fn set_push_provider(&self, provider: Option<Arc<dyn PushProvider>>) {
+        if let Ok(mut current) = self.push_provider.write() {
+            *current = provider;
+        }
+    }
+
+    /// Name of the push provider, if one is configured
+    pub fn push_provider_name(&self) -> Option<&'static str> {
+        self.current_push_provider().map(|p| p.name())
+    }
+
+    fn current_push_provider(&self) -> Option<Arc<dyn PushProvider>> {
+        self.push_provider.read().ok().and_then(|p| p.clone())
+    }
+
+    /// Store a message for the given users, or for every user with `None`.
+    /// Returns the number of recipients.
+    pub async fn notify(
+        &self,
+        user_ids: Option<&[Uuid]>,
+        kind: &str,
+        level: MessageLevel,
+        message: &str,
+    ) -> Result<u64> {
+        let message = message.trim();
+        if message.is_empty() {
+            return Err(Error::Validation("Message must not be empty".to_string()));
+        }
+        if message.chars().count() > MAX_MESSAGE {
+            return Err(Error::Validation(format!(
+                "Message must be at most {} characters",
+                MAX_MESSAGE
+            )));
+        }
+
+        let insert = r#"
+            INSERT INTO notifications (user_id, kind, level, message, created_at)
+            SELECT id, ?, ?, ?, ? FROM users
+        "#;
+        let now = Utc::now().to_rfc3339();
+        let mut tx = self.pool.begin().await?;
+        let recipients = match user_ids {
+            None => sqlx::query(insert)
+                .bind(kind)
+                .bind(level)
+                .bind(message)
+                .bind(&now)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected(),
+            Some(user_ids) => {
+                let mut recipients = 0;
+                for user_id in user_ids {
+                    let inserted = sqlx::query(&format!("{} WHERE id = ?", insert))
+                        .bind(kind)
+                        .bind(level)
+                        .bind(message)
+                        .bind(&now)
+                        .bind(user_id)
+                        .execute(&mut *tx)
+                        .await?
+                        .rows_affected();
+                    if inserted == 0 {
+                        return Err(Error::Validation(format!("Unknown user: {}", user_id)));
+                    }
+                    recipients += inserted;
+                }
+                recipients
+            }
+        };
+        tx.commit().await?;
+
+        Ok(recipients)
+    }
+
+    /// The user's notifications, newest first
+    pub async fn list(&self, user_id: Uuid, unread_only: bool) -> Result<Vec<Notification>> {
+        let notifications = sqlx::query_as::<_, Notification>(&format!(
+            r#"
+            SELECT id, kind, level, message, created_at, read_at FROM notifications
+            WHERE user_id = ?{}
+            ORDER BY id DESC
+            LIMIT ?
+            "#,
+            if unread_only {
+                " AND read_at IS NULL"
+            } else {
+                ""
+            }
+        ))
+        .bind(user_id)
+        .bind(LIST_LIMIT)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(notifications)
+    }
+
+    /// Mark one notification (or with `None` all of them) as read. Returns
+    /// the number of notifications that were unread.
+    pub async fn mark_read(&self, user_id: Uuid, id: Option<i64>) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE notifications SET read_at = ?
+            WHERE user_id = ? AND read_at IS NULL AND (? IS NULL OR id = ?)
+            "#,
+        )
+        .bind(Utc::now().to_rfc3339())
+        .bind(user_id)
+        .bind(id)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0
+            && let Some(id) = id
+        {
+            self.ensure_exists(user_id, id).await?;
+        }
+        Ok(result.rows_affected())
+    }
+
+    pub async fn delete(&self, user_id: Uuid, id: i64) -> Result<()> {
+        let result = sqlx::query("DELETE FROM notifications WHERE id = ? AND user_id = ?")
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(Error::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Register a device's push token. A token that is already registered
+    /// (possibly by another user on the same phone) is moved to this user.
+    pub async fn register_device(&self, user_id: Uuid, input: RegisterDevice) -> Result<Device> {
+        let token = input.token.trim();
+        if token.is_empty() || token.len() > MAX_TOKEN {
+            return Err(Error::Validation(format!(
+                "Token must be 1 to {} characters",
+                MAX_TOKEN
+            )));
+        }
+        let name = input
+            .name
+            .as_deref()
+            .map(str::trim)
+            .filter(|n| !n.is_empty());
+        if name.is_some_and(|n| n.chars().count() > MAX_DEVICE_NAME) {
+            return Err(Error::Validation(format!(
+                "Name must be at most {} characters",
+                MAX_DEVICE_NAME
+            )));
+        }
+
+        let now = Utc::now().to_rfc3339();
+        let device = sqlx::query_as::<_, Device>(
+            r#"
+            INSERT INTO devices (id, user_id, platform, token, name, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(token) DO UPDATE SET
+                user_id = excluded.user_id,
+                platform = excluded.platform,
+                name = excluded.name,
+                updated_at = excluded.updated_at
+            RETURNING id, platform, token, name, created_at, updated_at
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .bind(input.platform)
+        .bind(token)
+        .bind(name)
+        .bind(&now)
+        .bind(&now)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(device)
+    }
+
+    /// The user's devices, most recently registered first
+    pub async fn devices(&self, user_id: Uuid) -> Result<Vec<Device>> {
+        let devices = sqlx::query_as::<_, Device>(
+            r#"
+            SELECT id, platform, token, name, created_at, updated_at FROM devices
+            WHERE user_id = ?
+            ORDER BY updated_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(devices)
+    }
+
+    pub async fn remove_device(&self, user_id: Uuid, id: Uuid) -> Result<()> {
+        let result = sqlx::query("DELETE FROM devices WHERE id = ? AND user_id = ?")
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(Error::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Send a push notification to all of the user's devices. Devices whose
+    /// token is no longer valid are removed; other failures are logged.
+    /// Returns the number of devices reached (0 without a push provider).
+    pub async fn push(&self, user_id: Uuid, message: &PushMessage) -> Result<usize> {
+        let Some(provider) = self.current_push_provider() else {
+            return Ok(0);
+        };
+
+        let mut delivered = 0;
+        for device in self.devices(user_id).await? {
+            match provider.send(&device, message).await {
+                Ok(PushOutcome::Delivered) => delivered += 1,
+                Ok(PushOutcome::Unregistered) => {
+                    tracing::info!("Removing device {} with an expired push token", device.id);
+                    sqlx::query("DELETE FROM devices WHERE id = ?")
+                        .bind(device.id)
+                        .execute(&self.pool)
+                        .await?;
+                }
+                Err(e) => tracing::warn!("Failed to push to device {}: {}", device.id, e),
+            }
+        }
+        Ok(delivered)
+    }
+
+    async fn ensure_exists(&self, user_id: Uuid, id: i64) -> Result<()> {
+        let exists: Option<i64> =
+            sqlx::query_scalar("SELECT 1 FROM notifications WHERE id = ? AND user_id = ?")
+                .bind(id)
+                .bind(user_id)
+                .fetch_optional(&self.pool)
+                .await?;
+        exists.map(|_| ()).ok_or(Error::NotFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::PushPlatform;
+    use crate::services::push::PushFuture;
+    use crate::test_helpers::{create_test_db_with_users, fixtures};
+
+    /// Delivers to every token except `expired`
+    struct FakeProvider {
+        sent: std::sync::Mutex<Vec<String>>,
+    }
+
+    impl PushProvider for FakeProvider {
+        fn name(&self) -> &'static str {
+            "fake"
+        }
+
+        fn send<'a>(&'a self, device: &'a Device, _message: &'a PushMessage) -> PushFuture<'a> {
+            Box::pin(async move {
+                if device.token == "expired" {
+                    return Ok(PushOutcome::Unregistered);
+                }
+                self.sent.lock().unwrap().push(device.token.clone());
+                Ok(PushOutcome::Delivered)
+            })
+        }
+    }
+
+    fn register(token: &str) -> RegisterDevice {
+        RegisterDevice {
+            token: token.to_string(),
+            platform: PushPlatform::Fcm,
+            name: Some(" Pixel ".to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_notify_and_read() {
+        let pool = create_test_db_with_users().await;
+        let service = NotificationService::new(pool);
+        let user_id = fixtures::test_user_id();
+        let admin_id = fixtures::test_admin_id();
+
+        let recipients = service
+            .notify(
+                None,
+                "server_message",
+                MessageLevel::Warning,
+                " Wartung um 22 Uhr ",
+            )
+            .await
+            .unwrap();
+        assert_eq!(recipients, 2);
+        service
+            .notify(
+                Some(&[admin_id]),
+                "server_message",
+                MessageLevel::Info,
+                "Nur für Admins",
+            )
+            .await
+            .unwrap();
+
+        let notifications = service.list(user_id, false).await.unwrap();
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].message, "Wartung um 22 Uhr");
+        assert_eq!(notifications[0].level, MessageLevel::Warning);
+        assert!(notifications[0].read_at.is_none());
+
+        let newest = service.list(admin_id, false).await.unwrap();
+        assert_eq!(newest.len(), 2);
+        assert_eq!(newest[0].message, "Nur für Admins");
+
+        assert_eq!(
+            service
+                .mark_read(user_id, Some(notifications[0].id))
+                .await
+                .unwrap(),
+            1
+        );
+        assert!(service.list(user_id, true).await.unwrap().is_empty());
+        // Someone else's notification
+        assert!(
+            service
+                .mark_read(admin_id, Some(notifications[0].id))
+                .await
+                .is_err()
+        );
+        assert_eq!(service.mark_read(admin_id, None).await.unwrap(), 2);
+
+        service.delete(admin_id, newest[0].id).await.unwrap();
+        assert_eq!(service.list(admin_id, false).await.unwrap().len(), 1);
+
+        assert!(
+            service
+                .notify(
+                    Some(&[Uuid::new_v4()]),
+                    "server_message",
+                    MessageLevel::Info,
+                    "Hallo"
+                )
+                .await
+                .is_err()
+        );
+        assert!(
+            service
+                .notify(None, "server_message", MessageLevel::Info, "  ")
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_devices_and_push() {
+        let pool = create_test_db_with_users().await;
+        let service = NotificationService::new(pool);
+        let user_id = fixtures::test_user_id();
+        let admin_id = fixtures::test_admin_id();
+        let message = PushMessage {
+            kind: "loan_reminder".to_string(),
+            title: "Ausgeliehen".to_string(),
+            body: "Alien ist überfällig".to_string(),
+        };
+
+        let device = service
+            .register_device(user_id, register("abc"))
+            .await
+            .unwrap();
+        assert_eq!(device.name.as_deref(), Some("Pixel"));
+        service
+            .register_device(user_id, register("expired"))
+            .await
+            .unwrap();
+        assert!(
+            service
+                .register_device(user_id, register(" "))
+                .await
+                .is_err()
+        );
+
+        // Without a provider nothing is sent
+        assert!(service.push_provider_name().is_none());
+        assert_eq!(service.push(user_id, &message).await.unwrap(), 0);
+
+        let provider = Arc::new(FakeProvider {
+            sent: std::sync::Mutex::new(Vec::new()),
+        });
+        service.set_push_provider(Some(provider.clone()));
+        assert_eq!(service.push_provider_name(), Some("fake"));
+        assert_eq!(service.push(user_id, &message).await.unwrap(), 1);
+        assert_eq!(*provider.sent.lock().unwrap(), vec!["abc".to_string()]);
+        // The expired token is gone
+        assert_eq!(service.devices(user_id).await.unwrap().len(), 1);
+
+        // Registering the token again moves it to the other user
+        let moved = service
+            .register_device(admin_id, register("abc"))
+            .await
+            .unwrap();
+        assert_eq!(moved.id, device.id);
+        assert!(service.devices(user_id).await.unwrap().is_empty());
+        assert_eq!(service.push(user_id, &message).await.unwrap(), 0);
+
+        assert!(matches!(
+            service.remove_device(user_id, device.id).await,
+            Err(Error::NotFound)
+        ));
+        service.remove_device(admin_id, device.id).await.unwrap();
+        assert!(service.devices(admin_id).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,648 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::process::Stdio;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::io::AsyncWriteExt;
+
+use crate::error::{Error, Result};
+use crate::parsing::title::{fold_title, titles_match};
+use crate::services::tmdb::TmdbMovie;
+
+pub type OcrFuture<'a> = Pin<Box<dyn Future<Output = Result<Vec<OcrLine>>> + Send + 'a>>;
+
+/// Words tesseract is less sure about than this (0 - 100) are left out
+const MIN_CONFIDENCE: f64 = 40.0;
+const TESSERACT_TIMEOUT: Duration = Duration::from_secs(30);
+/// Number of recognized lines searched on TMDB
+const MAX_CANDIDATES: usize = 3;
+/// Number of ranked TMDB matches returned
+const MAX_MATCHES: usize = 10;
+
+/// Words printed on covers that are never part of the title
+const COVER_NOISE: &[&str] = &[
+    "dvd",
+    "blu",
+    "ray",
+    "bluray",
+    "disc",
+    "uhd",
+    "4k",
+    "hd",
+    "fsk",
+    "pal",
+    "ntsc",
+    "dolby",
+    "dts",
+    "widescreen",
+];
+
+/// A line of text recognized on a cover
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OcrLine {
+    pub text: String,
+    /// Height of the text in pixels; the title is usually the largest text
+    pub height: u32,
+}
+
+/// Text recognition engine for photos of disc covers
+pub trait OcrProvider: Send + Sync {
+    /// Name used in the `ocr_provider` setting and reported in `/capabilities`
+    fn name(&self) -> &'static str;
+
+    /// Lines of text on the image, in reading order
+    fn recognize<'a>(&'a self, image: &'a [u8]) -> OcrFuture<'a>;
+}
+
+/// The `tesseract` command line program, which has to be installed on the server
+pub struct TesseractProvider {
+    /// Tesseract languages, e.g. `deu+eng`; `None` uses tesseract's default
+    languages: Option<String>,
+}
+
+impl TesseractProvider {
+    pub const NAME: &'static str = "tesseract";
+
+    pub fn new(languages: Option<&str>) -> Self {
+        Self {
+            languages: languages.map(String::from),
+        }
+    }
+
+    async fn run(&self, image: &[u8]) -> Result<Vec<OcrLine>> {
+        let mut command = tokio::process::Command::new("tesseract");
+        command.arg("stdin").arg("stdout");
+        if let Some(ref languages) = self.languages {
+            command.arg("-l").arg(languages);
+        }
+        command
+            .arg("tsv")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+
+        let mut child = command.spawn().map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => Error::Configuration(
+                "tesseract is not installed; install it or choose another ocr_provider".to_string(),
+            ),
+            _ => Error::Internal(format!("Failed to start tesseract: {}", e)),
+        })?;
+        let mut stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| Error::Internal("tesseract has no stdin".to_string()))?;
+        let write = async move {
+            stdin.write_all(image).await?;
+            // Closing stdin tells tesseract the image is complete
+            drop(stdin);
+            Ok::<_, std::io::Error>(())
+        };
+
+        let (written, output) = tokio::time::timeout(TESSERACT_TIMEOUT, async {
+            tokio::join!(write, child.wait_with_output())
+        })
+        .await
+        .map_err(|_| Error::Internal("tesseract took too long".to_string()))?;
+        let output = output.map_err(|e| Error::Internal(format!("tesseract failed: {}", e)))?;
+        if !output.status.success() {
+            // Mostly images tesseract can't read
+            return Err(Error::Validation(format!(
+                "Could not read the image: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        written.map_err(|e| Error::Internal(format!("tesseract failed: {}", e)))?;
+
+        Ok(parse_tesseract_tsv(&String::from_utf8_lossy(
+            &output.stdout,
+        )))
+    }
+}
+
+/// Lines from tesseract's TSV output: words (level 5) are joined per line,
+/// the line is as high as its highest word
+fn parse_tesseract_tsv(tsv: &str) -> Vec<OcrLine> {
+    let mut lines: Vec<([&str; 4], OcrLine)> = Vec::new();
+    for row in tsv.lines().skip(1) {
+        let columns: Vec<&str> = row.split('\t').collect();
+        if columns.len() < 12 || columns[0] != "5" {
+            continue;
+        }
+        let text = columns[11].trim();
+        let confidence = columns[10].parse::<f64>().unwrap_or(-1.0);
+        if text.is_empty() || confidence < MIN_CONFIDENCE {
+            continue;
+        }
+        // page, block, paragraph and line number
+        let key = [columns[1], columns[2], columns[3], columns[4]];
+        let height = columns[9].parse::<u32>().unwrap_or(0);
+
+        match lines.last_mut() {
+            Some((last, line)) if *last == key => {
+                line.text.push(' ');
+                line.text.push_str(text);
+                line.height = line.height.max(height);
+            }
+            _ => lines.push((
+                key,
+                OcrLine {
+                    text: text.to_string(),
+                    height,
+                },
+            )),
+        }
+    }
+    lines.into_iter().map(|(_, line)| line).collect()
+}
+
+impl OcrProvider for TesseractProvider {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn recognize<'a>(&'a self, image: &'a [u8]) -> OcrFuture<'a> {
+        Box::pin(self.run(image))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct VisionResponse {
+    #[serde(default)]
+    responses: Vec<VisionResult>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VisionResult {
+    #[serde(default)]
+    text_annotations: Vec<TextAnnotation>,
+    error: Option<VisionError>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TextAnnotation {
+    description: String,
+    bounding_poly: Option<BoundingPoly>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BoundingPoly {
+    #[serde(default)]
+    vertices: Vec<Vertex>,
+}
+
+/// Vision leaves out coordinates that are 0
+#[derive(Debug, Deserialize)]
+struct Vertex {
+    #[serde(default)]
+    y: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct VisionError {
+    message: String,
+}
+
+impl TextAnnotation {
+    fn height(&self) -> u32 {
+        let ys = || {
+            self.bounding_poly
+                .iter()
+                .flat_map(|poly| poly.vertices.iter().map(|v| v.y))
+        };
+        match (ys().min(), ys().max()) {
+            (Some(min), Some(max)) => u32::try_from(max - min).unwrap_or(0),
+            _ => 0,
+        }
+    }
+}
+
+/// Lines of a Vision text detection. The first annotation is the whole text,
+/// the others are its words in the same order; each line takes as many words
+/// as it has characters and is as high as the highest of them.
+fn vision_lines(annotations: &[TextAnnotation]) -> Vec<OcrLine> {
+    let Some((full, words)) = annotations.split_first() else {
+        return Vec::new();
+    };
+    let length = |s: &str| s.chars().filter(|c| !c.is_whitespace()).count();
+    let mut words = words.iter();
+
+    full.description
+        .lines()
+        .filter_map(|line| {
+            let mut remaining = length(line);
+            let mut height = 0;
+            while remaining > 0 {
+                let Some(word) = words.next() else {
+                    break;
+                };
+                remaining = remaining.saturating_sub(length(&word.description));
+                height = height.max(word.height());
+            }
+            let text = line.trim();
+            (!text.is_empty()).then(|| OcrLine {
+                text: text.to_string(),
+                height,
+            })
+        })
+        .collect()
+}
+
+/// Google Cloud Vision text detection, authorized with an API key
+pub struct GoogleVisionProvider {
+    client: reqwest::Client,
+    api_key: String,
+}
+
+impl GoogleVisionProvider {
+    pub const NAME: &'static str = "google";
+
+    pub fn new(api_key: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_key: api_key.to_string(),
+        }
+    }
+
+    async fn annotate(&self, image: &[u8]) -> Result<Vec<OcrLine>> {
+        let body = json!({
+            "requests": [{
+                "image": { "content": base64::engine::general_purpose::STANDARD.encode(image) },
+                "features": [{ "type": "TEXT_DETECTION" }],
+            }]
+        });
+        let response = self
+            .client
+            .post(format!(
+                "https://vision.googleapis.com/v1/images:annotate?key={}",
+                urlencoding::encode(&self.api_key)
+            ))
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| Error::ExternalApi(format!("Vision request failed: {}", e)))?;
+        if !response.status().is_success() {
+            return Err(Error::ExternalApi(format!(
+                "Vision request failed: HTTP {}",
+                response.status()
+            )));
+        }
+        let response: VisionResponse = response
+            .json()
+            .await
+            .map_err(|e| Error::ExternalApi(format!("Invalid Vision response: {}", e)))?;
+
+        let Some(result) = response.responses.into_iter().next() else {
+            return Ok(Vec::new());
+        };
+        if let Some(error) = result.error {
+            return Err(Error::Validation(format!(
+                "Could not read the image: {}",
+                error.message
+            )));
+        }
+        Ok(vision_lines(&result.text_annotations))
+    }
+}
+
+impl OcrProvider for GoogleVisionProvider {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn recognize<'a>(&'a self, image: &'a [u8]) -> OcrFuture<'a> {
+        Box::pin(self.annotate(image))
+    }
+}
+
+/// Provider selected by the `ocr_provider` setting: `tesseract` (the default,
+/// optionally with languages as in `tesseract:deu+eng`), `google` (with the
+/// Vision API key as `ocr_api_key`) or `off`
+pub fn ocr_provider_from_settings(
+    provider: Option<&str>,
+    api_key: Option<&str>,
+) -> Result<Option<Arc<dyn OcrProvider>>> {
+    let provider = provider.map(str::trim).unwrap_or_default();
+    let api_key = api_key.map(str::trim).filter(|k| !k.is_empty());
+    let (name, options) = provider.split_once(':').unwrap_or((provider, ""));
+
+    match name.trim().to_lowercase().as_str() {
+        "" | TesseractProvider::NAME => {
+            let languages = Some(options.trim()).filter(|l| !l.is_empty());
+            Ok(Some(Arc::new(TesseractProvider::new(languages))))
+        }
+        GoogleVisionProvider::NAME => {
+            let api_key = api_key.ok_or_else(|| {
+                Error::Validation("Google Vision needs an API key as ocr_api_key".to_string())
+            })?;
+            Ok(Some(Arc::new(GoogleVisionProvider::new(api_key))))
+        }
+        "off" => Ok(None),
+        other => Err(Error::Validation(format!(
+            "Unknown OCR provider: {} (use tesseract, google or off)",
+            other
+        ))),
+    }
+}
+
+/// Text recognition on photos of disc covers, for adding discs whose barcode
+/// is damaged or missing
+pub struct OcrService {
+    provider: RwLock<Option<Arc<dyn OcrProvider>>>,
+}
+
+impl OcrService {
+    pub fn new() -> Self {
+        Self {
+            provider: RwLock::new(None),
+        }
+    }
+
+    /// Replace the provider at runtime (e.g., when settings are changed);
+    /// `None` disables cover recognition
+    pub fn set_provider(&self, provider: Option<Arc<dyn OcrProvider>>) {
+        if let Ok(mut current) = self.provider.write() {
+            *current = provider;
+        }
+    }
+
+    /// Name of the provider, if one is configured
+    pub fn provider_name(&self) -> Option<&'static str> {
+        self.current_provider().map(|p| p.name())
+    }
+
+    fn current_provider(&self) -> Option<Arc<dyn OcrProvider>> {
+        self.provider.read().ok().and_then(|p| p.clone())
+    }
+
+    /// Lines of text on a cover photo
+    pub async fn recognize(&self, image: &[u8]) -> Result<Vec<OcrLine>> {
+        let provider = self.current_provider().ok_or_else(|| {
+            Error::Configuration("Cover recognition is disabled (ocr_provider is off)".to_string())
+        })?;
+        provider.recognize(image).await
+    }
+}
+
+impl Default for OcrService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A line with cover words ("DVD", "FSK 12") removed; `None` if nothing
+/// title-like is left
+fn clean_line(text: &str) -> Option<String> {
+    let words: Vec<&str> = text
+        .split_whitespace()
+        .filter(|word| {
+            let folded = fold_title(word);
+            folded.is_empty() || !folded.split(' ').all(|w| COVER_NOISE.contains(&w))
+        })
+        .collect();
+    let line = words
+        .join(" ")
+        .trim_matches(|c: char| !c.is_alphanumeric())
+        .to_string();
+
+    // Mostly letters, so OCR garbage from artwork is left out
+    let letters = line.chars().filter(|c| c.is_alphabetic()).count();
+    let visible = line.chars().filter(|c| !c.is_whitespace()).count();
+    (letters >= 2 && letters * 2 >= visible).then_some(line)
+}
+
+/// Likely titles among the recognized lines, largest text first. Two
+/// neighbouring lines of about the same size are also tried together, since
+/// long titles often wrap.
+pub fn title_candidates(lines: &[OcrLine]) -> Vec<String> {
+    let cleaned: Vec<(String, u32)> = lines
+        .iter()
+        .filter_map(|line| Some((clean_line(&line.text)?, line.height)))
+        .collect();
+
+    let mut candidates = cleaned.clone();
+    for pair in cleaned.windows(2) {
+        let (low, high) = (pair[0].1.min(pair[1].1), pair[0].1.max(pair[1].1));
+        if low * 5 >= high * 4 {
+            candidates.push((format!("{} {}", pair[0].0, pair[1].0), low));
+        }
+    }
+    candidates.sort_by_key(|(_, height)| std::cmp::Reverse(*height));
+
+    let mut seen = Vec::new();
+    candidates
+        .into_iter()
+        .filter(|(text, _)| {
+            let folded = fold_title(text);
+            let new = !seen.contains(&folded);
+            seen.push(folded);
+            new
+        })
+        .map(|(text, _)| text)
+        .take(MAX_CANDIDATES)
+        .collect()
+}
+
+/// A TMDB movie that may be on the cover
+#[derive(Debug, Clone, Serialize)]
+pub struct CoverMatch {
+    pub movie: TmdbMovie,
+    /// Title candidate the movie was found with
+    pub query: String,
+    /// 0 - 1, higher is more likely
+    pub score: f64,
+}
+
+/// How well a search result fits the candidate it was found with: 1 for the
+/// same title, 0.7 when one contains the other, 0.4 otherwise
+fn title_fit(movie: &TmdbMovie, query: &str) -> f64 {
+    let titles = std::iter::once(movie.title.as_str()).chain(movie.original_title.as_deref());
+    let folded_query = fold_title(query);
+    let mut fit: f64 = 0.4;
+    for title in titles {
+        if titles_match(title, query) {
+            return 1.0;
+        }
+        let folded = fold_title(title);
+        if folded.len() >= 3
+            && folded_query.len() >= 3
+            && (folded.contains(&folded_query) || folded_query.contains(&folded))
+        {
+            fit = 0.7;
+        }
+    }
+    fit
+}
+
+/// Rank the TMDB results of all candidates (in candidate order): a good title
+/// fit counts most, then the candidate's rank, then TMDB's order. Movies
+/// found with several candidates keep their best score.
+pub fn rank_cover_matches(searches: Vec<(String, Vec<TmdbMovie>)>) -> Vec<CoverMatch> {
+    let mut matches: Vec<CoverMatch> = Vec::new();
+    for (rank, (query, results)) in searches.into_iter().enumerate() {
+        for (position, movie) in results.into_iter().enumerate() {
+            let score =
+                title_fit(&movie, &query) * 0.85f64.powi(rank as i32) - 0.03 * position as f64;
+            let score = (score.max(0.0) * 100.0).round() / 100.0;
+
+            match matches.iter_mut().find(|m| m.movie.id == movie.id) {
+                Some(existing) if existing.score >= score => {}
+                Some(existing) => {
+                    existing.score = score;
+                    existing.query = query.clone();
+                }
+                None => matches.push(CoverMatch {
+                    movie,
+                    query: query.clone(),
+                    score,
+                }),
+            }
+        }
+    }
+    matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+    matches.truncate(MAX_MATCHES);
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(text: &str, height: u32) -> OcrLine {
+        OcrLine {
+            text: text.to_string(),
+            height,
+        }
+    }
+
+    fn movie(id: i64, title: &str) -> TmdbMovie {
+        TmdbMovie {
+            id,
+            title: title.to_string(),
+            original_title: None,
+            overview: None,
+            poster_path: None,
+            backdrop_path: None,
+            release_date: None,
+            vote_average: None,
+            vote_count: None,
+            popularity: None,
+            genre_ids: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_tesseract_tsv() {
+        let tsv = "level\tpage_num\tblock_num\tpar_num\tline_num\tword_num\tleft\ttop\twidth\theight\tconf\ttext\n\
+            1\t1\t0\t0\t0\t0\t0\t0\t800\t1200\t-1\t\n\
+            5\t1\t1\t1\t1\t1\t10\t10\t200\t80\t95.1\tZURÜCK\n\
+            5\t1\t1\t1\t1\t2\t220\t10\t100\t84\t91.0\tIN\n\
+            5\t1\t1\t1\t2\t1\t10\t100\t300\t60\t93.4\tDIE ZUKUNFT\n\
+            5\t1\t2\t1\t1\t1\t10\t900\t40\t12\t12.0\t~#\n\
+            5\t1\t2\t1\t1\t2\t60\t900\t40\t12\t88.0\tFSK\n";
+        assert_eq!(
+            parse_tesseract_tsv(tsv),
+            vec![
+                line("ZURÜCK IN", 84),
+                line("DIE ZUKUNFT", 60),
+                line("FSK", 12)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_vision_lines() {
+        let response: VisionResult = serde_json::from_value(json!({
+            "textAnnotations": [
+                { "description": "ALIEN\nDirector's Cut\n" },
+                { "description": "ALIEN", "boundingPoly": { "vertices": [{"x": 5}, {"x": 90}, {"x": 90, "y": 70}, {"x": 5, "y": 70}] } },
+                { "description": "Director", "boundingPoly": { "vertices": [{"y": 80}, {"y": 80}, {"y": 95}, {"y": 95}] } },
+                { "description": "'s", "boundingPoly": { "vertices": [{"y": 80}, {"y": 96}] } },
+                { "description": "Cut", "boundingPoly": { "vertices": [{"y": 80}, {"y": 95}] } }
+            ]
+        }))
+        .unwrap();
+        assert_eq!(
+            vision_lines(&response.text_annotations),
+            vec![line("ALIEN", 70), line("Director's Cut", 16)]
+        );
+    }
+
+    #[test]
+    fn test_ocr_provider_from_settings() {
+        let tesseract = ocr_provider_from_settings(None, None).unwrap().unwrap();
+        assert_eq!(tesseract.name(), TesseractProvider::NAME);
+        let tesseract = ocr_provider_from_settings(Some("tesseract:deu+eng"), None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(tesseract.name(), TesseractProvider::NAME);
+
+        let google = ocr_provider_from_settings(Some("Google"), Some("key"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(google.name(), GoogleVisionProvider::NAME);
+        assert!(ocr_provider_from_settings(Some("google"), Some(" ")).is_err());
+
+        assert!(
+            ocr_provider_from_settings(Some("off"), None)
+                .unwrap()
+                .is_none()
+        );
+        assert!(ocr_provider_from_settings(Some("abbyy"), None).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_recognize_disabled() {
+        let service = OcrService::new();
+        assert!(service.provider_name().is_none());
+        assert!(matches!(
+            service.recognize(b"image").await,
+            Err(Error::Configuration(_))
+        ));
+    }
+
+    #[test]
+    fn test_title_candidates() {
+        let lines = [
+            line("Blu-ray Disc", 40),
+            line("DER HERR DER RINGE", 90),
+            line("Die Gefährten", 80),
+            line("~|| =_ .", 95),
+            line("FSK 12", 30),
+            line("Extended Edition DVD", 20),
+        ];
+        assert_eq!(
+            title_candidates(&lines),
+            vec![
+                "DER HERR DER RINGE",
+                "Die Gefährten",
+                "DER HERR DER RINGE Die Gefährten"
+            ]
+        );
+        assert!(title_candidates(&[line("DVD", 50)]).is_empty());
+    }
+
+    #[test]
+    fn test_rank_cover_matches() {
+        let matches = rank_cover_matches(vec![
+            (
+                "Alien".to_string(),
+                vec![movie(1, "Alien Covenant"), movie(2, "Alien")],
+            ),
+            (
+                "Ridley Scott".to_string(),
+                vec![movie(3, "Blade Runner"), movie(2, "Alien")],
+            ),
+        ]);
+        let ids: Vec<i64> = matches.iter().map(|m| m.movie.id).collect();
+        assert_eq!(ids, vec![2, 1, 3]);
+        assert_eq!(matches[0].score, 0.97);
+        assert_eq!(matches[0].query, "Alien");
+    }
+}
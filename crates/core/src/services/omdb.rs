@@ -0,0 +1,268 @@
+use crate::error::{Error, Result};
+use crate::models::SettingKey;
+use crate::services::settings::SettingsWatch;
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+
+const OMDB_BASE_URL: &str = "https://www.omdbapi.com/";
+
+/// Open Movie Database (omdbapi.com), looked up by IMDb ID or title when TMDB
+/// has nothing. Also the source of IMDb and Rotten Tomatoes ratings.
+pub struct OmdbService {
+    client: reqwest::Client,
+    api_key: RwLock<String>,
+    settings: Option<SettingsWatch>,
+}
+
+/// OMDb answers with "N/A" for missing values; see the accessors
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct OmdbMovie {
+    pub title: String,
+    pub year: Option<String>,
+    pub runtime: Option<String>,
+    pub genre: Option<String>,
+    pub director: Option<String>,
+    pub actors: Option<String>,
+    pub plot: Option<String>,
+    pub country: Option<String>,
+    pub language: Option<String>,
+    pub poster: Option<String>,
+    #[serde(rename = "imdbRating")]
+    pub imdb_rating: Option<String>,
+    #[serde(rename = "imdbID")]
+    pub imdb_id: Option<String>,
+    #[serde(rename = "Type")]
+    pub kind: Option<String>,
+    #[serde(default)]
+    pub ratings: Vec<OmdbRating>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct OmdbRating {
+    pub source: String,
+    pub value: String,
+}
+
+fn available(value: &Option<String>) -> Option<String> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty() && *v != "N/A")
+        .map(str::to_string)
+}
+
+impl OmdbMovie {
+    pub fn genre(&self) -> Option<String> {
+        available(&self.genre)
+    }
+
+    pub fn director(&self) -> Option<String> {
+        available(&self.director)
+    }
+
+    pub fn actors(&self) -> Option<String> {
+        available(&self.actors)
+    }
+
+    pub fn plot(&self) -> Option<String> {
+        available(&self.plot)
+    }
+
+    pub fn poster_url(&self) -> Option<String> {
+        available(&self.poster).filter(|url| url.starts_with("http"))
+    }
+
+    pub fn imdb_id(&self) -> Option<String> {
+        available(&self.imdb_id)
+    }
+
+    /// "136 min" -> 136
+    pub fn runtime_minutes(&self) -> Option<i32> {
+        available(&self.runtime)?
+            .split_whitespace()
+            .next()?
+            .parse()
+            .ok()
+    }
+
+    /// First year of "1999" or "2005–2013"
+    pub fn production_year(&self) -> Option<i32> {
+        available(&self.year)?.get(..4)?.parse().ok()
+    }
+
+    /// IMDb user rating, 0 - 10
+    pub fn imdb_score(&self) -> Option<f64> {
+        available(&self.imdb_rating)?.parse().ok()
+    }
+
+    /// Rotten Tomatoes Tomatometer in percent
+    pub fn rotten_tomatoes(&self) -> Option<i32> {
+        self.ratings
+            .iter()
+            .find(|r| r.source == "Rotten Tomatoes")?
+            .value
+            .trim()
+            .trim_end_matches('%')
+            .parse()
+            .ok()
+    }
+}
+
+/// OMDb reports "not found" with status 200 and `Response: "False"`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct OmdbResponse {
+    response: String,
+    error: Option<String>,
+    #[serde(flatten)]
+    movie: Option<OmdbMovie>,
+}
+
+impl OmdbService {
+    pub fn new(api_key: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_key: RwLock::new(api_key),
+            settings: None,
+        }
+    }
+
+    /// Take the API key from the `omdb_api_key` setting on every call; the
+    /// key given to `new` is used while the setting is empty
+    pub fn with_settings(mut self, settings: SettingsWatch) -> Self {
+        self.settings = Some(settings);
+        self
+    }
+
+    /// Update the fallback API key at runtime
+    pub fn set_api_key(&self, api_key: String) {
+        if let Ok(mut key) = self.api_key.write() {
+            *key = api_key;
+        }
+    }
+
+    fn current_api_key(&self) -> String {
+        if let Some(settings) = &self.settings
+            && let Some(key) = settings.borrow().get(SettingKey::OmdbApiKey)
+        {
+            return key.to_string();
+        }
+        self.api_key.read().map(|k| k.clone()).unwrap_or_default()
+    }
+
+    pub fn is_configured(&self) -> bool {
+        !self.current_api_key().is_empty()
+    }
+
+    fn get_api_key(&self) -> Result<String> {
+        let key = self.current_api_key();
+        if key.is_empty() {
+            return Err(Error::ExternalApi(
+                "OMDb API key not configured. Please set it in Settings.".to_string(),
+            ));
+        }
+        Ok(key)
+    }
+
+    /// Look up a movie or series by IMDb ID ("tt0133093")
+    #[tracing::instrument(skip(self))]
+    pub async fn get_by_imdb_id(&self, imdb_id: &str) -> Result<Option<OmdbMovie>> {
+        self.fetch(&[("i", imdb_id.to_string())]).await
+    }
+
+    /// Best match for a title, optionally restricted to a year
+    #[tracing::instrument(skip(self))]
+    pub async fn find_by_title(&self, title: &str, year: Option<i32>) -> Result<Option<OmdbMovie>> {
+        let mut params = vec![("t", title.to_string())];
+        if let Some(year) = year {
+            params.push(("y", year.to_string()));
+        }
+        self.fetch(&params).await
+    }
+
+    async fn fetch(&self, params: &[(&str, String)]) -> Result<Option<OmdbMovie>> {
+        let api_key = self.get_api_key()?;
+        let mut url = format!("{}?apikey={}&plot=full", OMDB_BASE_URL, api_key);
+        for (name, value) in params {
+            url.push_str(&format!("&{}={}", name, urlencoding::encode(value)));
+        }
+
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| Error::ExternalApi(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(Error::ExternalApi(format!(
+                "OMDb API error: {}",
+                response.status()
+            )));
+        }
+
+        let result: OmdbResponse = response
+            .json()
+            .await
+            .map_err(|e| Error::ExternalApi(e.to_string()))?;
+        parse_response(result)
+    }
+}
+
+fn parse_response(result: OmdbResponse) -> Result<Option<OmdbMovie>> {
+    if result.response.eq_ignore_ascii_case("true") {
+        return Ok(result.movie);
+    }
+    match result.error {
+        Some(error) if error.ends_with("not found!") => Ok(None),
+        Some(error) => Err(Error::ExternalApi(format!("OMDb API error: {}", error))),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_omdb_movie() {
+        let json = r#"{
+            "Title": "The Matrix", "Year": "1999", "Runtime": "136 min",
+            "Genre": "Action, Sci-Fi", "Director": "Lana Wachowski, Lilly Wachowski",
+            "Actors": "Keanu Reeves, Laurence Fishburne", "Plot": "N/A",
+            "Poster": "https://m.media-amazon.com/images/M/matrix.jpg",
+            "Ratings": [
+                {"Source": "Internet Movie Database", "Value": "8.7/10"},
+                {"Source": "Rotten Tomatoes", "Value": "83%"}
+            ],
+            "imdbRating": "8.7", "imdbID": "tt0133093", "Type": "movie", "Response": "True"
+        }"#;
+        let movie = parse_response(serde_json::from_str(json).unwrap())
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(movie.title, "The Matrix");
+        assert_eq!(movie.production_year(), Some(1999));
+        assert_eq!(movie.runtime_minutes(), Some(136));
+        assert_eq!(movie.imdb_score(), Some(8.7));
+        assert_eq!(movie.rotten_tomatoes(), Some(83));
+        assert_eq!(movie.imdb_id().as_deref(), Some("tt0133093"));
+        assert_eq!(movie.plot(), None);
+        assert!(movie.poster_url().is_some());
+    }
+
+    #[test]
+    fn test_parse_omdb_errors() {
+        let not_found = r#"{"Response": "False", "Error": "Movie not found!"}"#;
+        assert!(
+            parse_response(serde_json::from_str(not_found).unwrap())
+                .unwrap()
+                .is_none()
+        );
+
+        let limit = r#"{"Response": "False", "Error": "Request limit reached!"}"#;
+        assert!(parse_response(serde_json::from_str(limit).unwrap()).is_err());
+    }
+}
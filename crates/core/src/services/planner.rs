@@ -0,0 +1,411 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::db::DbPool;
+use crate::error::{Error, Result};
+
+/// Proposals returned when no limit is given
+pub const DEFAULT_PROPOSALS: usize = 10;
+pub const MAX_PROPOSALS: usize = 50;
+
+const SELECT_MOVIE_NIGHT: &str = "SELECT n.id, n.movie_id, m.title, n.scheduled_for, n.created_at \
+     FROM movie_nights n JOIN movies m ON m.id = n.movie_id";
+
+#[derive(Debug, Deserialize)]
+pub struct PlannerQuery {
+    pub available_minutes: i32,
+    /// Only movies with this genre
+    pub genre: Option<String>,
+    /// Comma separated allowed certifications, e.g. "FSK 0,FSK 6,FSK 12"
+    pub certification: Option<String>,
+    pub limit: Option<usize>,
+}
+
+/// A movie night picked from the proposals, announced as `movie_night_scheduled`
+#[derive(Debug, Deserialize)]
+pub struct ScheduleMovieNight {
+    pub movie_id: Uuid,
+    pub scheduled_for: DateTime<Utc>,
+}
+
+/// A scheduled movie night
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct MovieNight {
+    pub id: Uuid,
+    pub movie_id: Uuid,
+    pub title: String,
+    pub scheduled_for: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct PlannerMovie {
+    pub id: Uuid,
+    pub title: String,
+    pub production_year: Option<i32>,
+    /// Minutes
+    pub running_time: i32,
+    pub genres: Option<String>,
+    /// Certification (FSK/MPAA)
+    pub rating: Option<String>,
+}
+
+/// One film or a double feature
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannerProposal {
+    pub movies: Vec<PlannerMovie>,
+    pub total_minutes: i32,
+    pub remaining_minutes: i32,
+}
+
+/// Single films and double features fitting `available_minutes`, the ones
+/// filling the time best first. Each film is paired with the longest of the
+/// longer films that still fits.
+pub fn plan_movie_night(
+    mut movies: Vec<PlannerMovie>,
+    available_minutes: i32,
+    limit: usize,
+) -> Vec<PlannerProposal> {
+    movies.retain(|m| m.running_time > 0 && m.running_time <= available_minutes);
+    movies.sort_by_key(|m| m.running_time);
+
+    // (total minutes, first index, second index)
+    let mut candidates: Vec<(i32, usize, Option<usize>)> = movies
+        .iter()
+        .enumerate()
+        .map(|(i, m)| (m.running_time, i, None))
+        .collect();
+
+    for (i, movie) in movies.iter().enumerate() {
+        let budget = available_minutes - movie.running_time;
+        // Longest partner after `i` that fits; earlier ones were paired already
+        let fitting = movies[i + 1..].partition_point(|m| m.running_time <= budget);
+        if fitting > 0 {
+            let j = i + fitting;
+            candidates.push((movie.running_time + movies[j].running_time, i, Some(j)));
+        }
+    }
+
+    // Longest total first; singles before double features of the same length
+    candidates.sort_by(|a, b| b.0.cmp(&a.0).then(a.2.is_some().cmp(&b.2.is_some())));
+    candidates.truncate(limit);
+
+    candidates
+        .into_iter()
+        .map(|(total, i, j)| PlannerProposal {
+            movies: std::iter::once(i)
+                .chain(j)
+                .map(|k| movies[k].clone())
+                .collect(),
+            total_minutes: total,
+            remaining_minutes: available_minutes - total,
+        })
+        .collect()
+}
+
+/// Movie night proposals from unwatched movies with a known running time, and
+/// the movie nights scheduled from them
+pub struct PlannerService {
+    pool: DbPool,
+}
+
+impl PlannerService {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn plan(&self, user_id: Uuid, query: &PlannerQuery) -> Result<Vec<PlannerProposal>> {
+        if query.available_minutes <= 0 {
+            return Err(Error::Validation(
+                "available_minutes must be positive".into(),
+            ));
+        }
+
+        let mut sql = String::from(
+            "SELECT id, title, production_year, running_time, genres, rating FROM movies \
+             WHERE user_id = ? AND watched = 0 AND is_collection = 0 AND disposition IS NULL \
+             AND running_time > 0 AND running_time <= ?",
+        );
+
+        if query.genre.is_some() {
+            sql.push_str(" AND genres LIKE ?");
+        }
+
+        let certifications: Vec<&str> = query
+            .certification
+            .as_deref()
+            .unwrap_or("")
+            .split(',')
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .collect();
+        if !certifications.is_empty() {
+            let placeholders = vec!["?"; certifications.len()].join(", ");
+            sql.push_str(&format!(" AND rating COLLATE NOCASE IN ({})", placeholders));
+        }
+
+        let mut q = sqlx::query_as::<_, PlannerMovie>(&sql)
+            .bind(user_id)
+            .bind(query.available_minutes);
+
+        if let Some(ref genre) = query.genre {
+            q = q.bind(format!("%{}%", genre));
+        }
+
+        for certification in certifications {
+            q = q.bind(certification);
+        }
+
+        let movies = q.fetch_all(&self.pool).await?;
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_PROPOSALS)
+            .clamp(1, MAX_PROPOSALS);
+
+        Ok(plan_movie_night(movies, query.available_minutes, limit))
+    }
+
+    /// Save a movie night for one of the user's movies
+    pub async fn schedule(&self, user_id: Uuid, input: &ScheduleMovieNight) -> Result<MovieNight> {
+        if input.scheduled_for <= Utc::now() {
+            return Err(Error::Validation(
+                "scheduled_for must be in the future".into(),
+            ));
+        }
+        let exists: Option<i64> =
+            sqlx::query_scalar("SELECT 1 FROM movies WHERE id = ? AND user_id = ?")
+                .bind(input.movie_id)
+                .bind(user_id)
+                .fetch_optional(&self.pool)
+                .await?;
+        if exists.is_none() {
+            return Err(Error::NotFound);
+        }
+
+        let id = Uuid::new_v4();
+        sqlx::query(
+            r#"
+            INSERT INTO movie_nights (id, user_id, movie_id, scheduled_for, created_at)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(input.movie_id)
+        .bind(input.scheduled_for.to_rfc3339())
+        .bind(Utc::now().to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+
+        let night =
+            sqlx::query_as::<_, MovieNight>(&format!("{} WHERE n.id = ?", SELECT_MOVIE_NIGHT))
+                .bind(id)
+                .fetch_one(&self.pool)
+                .await?;
+        Ok(night)
+    }
+
+    /// Movie nights that haven't started yet, soonest first
+    pub async fn upcoming(&self, user_id: Uuid) -> Result<Vec<MovieNight>> {
+        let nights = sqlx::query_as::<_, MovieNight>(&format!(
+            "{} WHERE n.user_id = ? AND julianday(n.scheduled_for) > julianday(?) \
+             ORDER BY julianday(n.scheduled_for)",
+            SELECT_MOVIE_NIGHT
+        ))
+        .bind(user_id)
+        .bind(Utc::now().to_rfc3339())
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(nights)
+    }
+
+    /// Call off a movie night
+    pub async fn cancel(&self, user_id: Uuid, id: Uuid) -> Result<()> {
+        let result = sqlx::query("DELETE FROM movie_nights WHERE id = ? AND user_id = ?")
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(Error::NotFound);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{CreateMovie, UpdateMovie};
+    use crate::services::MovieService;
+    use crate::test_helpers::{create_test_db_with_users, fixtures};
+
+    fn movie(title: &str, running_time: i32) -> PlannerMovie {
+        PlannerMovie {
+            id: Uuid::new_v4(),
+            title: title.to_string(),
+            production_year: None,
+            running_time,
+            genres: None,
+            rating: None,
+        }
+    }
+
+    fn titles(proposal: &PlannerProposal) -> Vec<&str> {
+        proposal.movies.iter().map(|m| m.title.as_str()).collect()
+    }
+
+    #[test]
+    fn test_plan_movie_night() {
+        let movies = vec![
+            movie("Heat", 170),
+            movie("Alien", 117),
+            movie("Up", 96),
+            movie("Coco", 80),
+            movie("Titanic", 195),
+        ];
+
+        let proposals = plan_movie_night(movies, 180, 10);
+        assert_eq!(titles(&proposals[0]), vec!["Coco", "Up"]);
+        assert_eq!(proposals[0].remaining_minutes, 4);
+        assert_eq!(titles(&proposals[1]), vec!["Heat"]);
+        // Titanic doesn't fit
+        assert!(
+            proposals
+                .iter()
+                .all(|p| p.movies.iter().all(|m| m.title != "Titanic"))
+        );
+        assert!(proposals.iter().all(|p| p.total_minutes <= 180));
+
+        assert_eq!(plan_movie_night(vec![movie("Up", 96)], 90, 10).len(), 0);
+        assert_eq!(
+            plan_movie_night(vec![movie("Up", 96), movie("Coco", 80)], 200, 1).len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_plan_uses_unwatched_movies() {
+        let pool = create_test_db_with_users().await;
+        let movie_service = MovieService::new(pool.clone());
+        let service = PlannerService::new(pool);
+        let user_id = fixtures::test_user_id();
+
+        for (title, running_time, watched, rating) in [
+            ("Heat", 170, false, "FSK 16"),
+            ("Up", 96, false, "FSK 0"),
+            ("Coco", 80, true, "FSK 0"),
+        ] {
+            let created = movie_service
+                .create(
+                    user_id,
+                    CreateMovie {
+                        barcode: None,
+                        tmdb_id: None,
+                        title: title.to_string(),
+                        original_title: None,
+                        disc_type: None,
+                        production_year: None,
+                    },
+                )
+                .await
+                .unwrap();
+            movie_service
+                .update(
+                    user_id,
+                    created.id,
+                    UpdateMovie {
+                        running_time: Some(running_time),
+                        watched: Some(watched),
+                        rating: Some(rating.to_string()),
+                        ..Default::default()
+                    },
+                )
+                .await
+                .unwrap();
+        }
+
+        let query = PlannerQuery {
+            available_minutes: 180,
+            genre: None,
+            certification: None,
+            limit: None,
+        };
+        let proposals = service.plan(user_id, &query).await.unwrap();
+        assert_eq!(proposals.len(), 2);
+        assert_eq!(titles(&proposals[0]), vec!["Heat"]);
+
+        let family = PlannerQuery {
+            certification: Some("fsk 0, FSK 6".to_string()),
+            ..query
+        };
+        let proposals = service.plan(user_id, &family).await.unwrap();
+        assert_eq!(proposals.len(), 1);
+        assert_eq!(titles(&proposals[0]), vec!["Up"]);
+    }
+
+    #[tokio::test]
+    async fn test_schedule_movie_night() {
+        let pool = create_test_db_with_users().await;
+        let movie_service = MovieService::new(pool.clone());
+        let service = PlannerService::new(pool);
+        let user_id = fixtures::test_user_id();
+        let movie = movie_service
+            .create(
+                user_id,
+                CreateMovie {
+                    barcode: None,
+                    tmdb_id: None,
+                    title: "Heat".to_string(),
+                    original_title: None,
+                    disc_type: None,
+                    production_year: None,
+                },
+            )
+            .await
+            .unwrap();
+        let tomorrow = Utc::now() + chrono::Duration::days(1);
+
+        let night = service
+            .schedule(
+                user_id,
+                &ScheduleMovieNight {
+                    movie_id: movie.id,
+                    scheduled_for: tomorrow,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(night.title, "Heat");
+        assert_eq!(night.scheduled_for.timestamp(), tomorrow.timestamp());
+
+        let past = ScheduleMovieNight {
+            movie_id: movie.id,
+            scheduled_for: Utc::now() - chrono::Duration::hours(1),
+        };
+        assert!(matches!(
+            service.schedule(user_id, &past).await,
+            Err(Error::Validation(_))
+        ));
+        let other_user = ScheduleMovieNight {
+            movie_id: movie.id,
+            scheduled_for: tomorrow,
+        };
+        assert!(matches!(
+            service.schedule(Uuid::new_v4(), &other_user).await,
+            Err(Error::NotFound)
+        ));
+
+        let upcoming = service.upcoming(user_id).await.unwrap();
+        assert_eq!(upcoming.len(), 1);
+        assert_eq!(upcoming[0].id, night.id);
+
+        service.cancel(user_id, night.id).await.unwrap();
+        assert!(service.upcoming(user_id).await.unwrap().is_empty());
+        assert!(matches!(
+            service.cancel(user_id, night.id).await,
+            Err(Error::NotFound)
+        ));
+    }
+}
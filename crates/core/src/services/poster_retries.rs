@@ -0,0 +1,189 @@
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+use crate::db::DbPool;
+use crate::error::Result;
+use crate::models::PosterRetry;
+
+/// Failed downloads are given up after this many attempts
+pub const MAX_ATTEMPTS: i64 = 6;
+/// Wait before the first retry; every further retry waits four times as long
+const FIRST_RETRY_MINUTES: i64 = 15;
+const MAX_ERROR: usize = 500;
+
+const SELECT_RETRY: &str = "SELECT r.movie_id, r.user_id, m.title, r.source, r.attempts, \
+     r.last_error, r.next_attempt_at, r.updated_at \
+     FROM poster_retries r JOIN movies m ON m.id = r.movie_id";
+
+/// Time until the next try after `attempts` failed downloads: 15 minutes,
+/// 1 hour, 4 hours, 16 hours, 2.7 days; `None` after `MAX_ATTEMPTS`
+pub fn retry_delay(attempts: i64) -> Option<Duration> {
+    if attempts >= MAX_ATTEMPTS {
+        return None;
+    }
+    let exponent = attempts.saturating_sub(1).clamp(0, MAX_ATTEMPTS) as u32;
+    Some(Duration::minutes(FIRST_RETRY_MINUTES * 4i64.pow(exponent)))
+}
+
+/// Queue of poster downloads to retry. Entries go away when the movie gets
+/// a poster by any means (`MovieService::update_movie_poster_data`).
+pub struct PosterRetryService {
+    pool: DbPool,
+}
+
+impl PosterRetryService {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Record a failed download of the movie's poster from `source`, counting
+    /// the attempt and scheduling the next one
+    pub async fn record_failure(
+        &self,
+        user_id: Uuid,
+        movie_id: Uuid,
+        source: &str,
+        error: &str,
+    ) -> Result<()> {
+        let attempts: i64 = sqlx::query_scalar(
+            "SELECT attempts FROM poster_retries WHERE movie_id = ? AND source = ?",
+        )
+        .bind(movie_id)
+        .bind(source)
+        .fetch_optional(&self.pool)
+        .await?
+        .map_or(1, |attempts: i64| attempts + 1);
+
+        let now = Utc::now();
+        let next_attempt_at = retry_delay(attempts).map(|delay| (now + delay).to_rfc3339());
+        let error: String = error.chars().take(MAX_ERROR).collect();
+
+        // A new source starts counting again
+        sqlx::query(
+            r#"
+            INSERT INTO poster_retries
+                (movie_id, user_id, source, attempts, last_error, next_attempt_at, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(movie_id) DO UPDATE SET
+                source = excluded.source,
+                attempts = excluded.attempts,
+                last_error = excluded.last_error,
+                next_attempt_at = excluded.next_attempt_at,
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(movie_id)
+        .bind(user_id)
+        .bind(source)
+        .bind(attempts)
+        .bind(&error)
+        .bind(next_attempt_at)
+        .bind(now.to_rfc3339())
+        .bind(now.to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Downloads whose next attempt is due, oldest first
+    pub async fn due(&self, now: DateTime<Utc>, limit: i64) -> Result<Vec<PosterRetry>> {
+        let retries = sqlx::query_as::<_, PosterRetry>(&format!(
+            "{} WHERE r.next_attempt_at <= ? ORDER BY r.next_attempt_at LIMIT ?",
+            SELECT_RETRY
+        ))
+        .bind(now.to_rfc3339())
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(retries)
+    }
+
+    /// The user's downloads that are no longer retried
+    pub async fn given_up(&self, user_id: Uuid) -> Result<Vec<PosterRetry>> {
+        let retries = sqlx::query_as::<_, PosterRetry>(&format!(
+            "{} WHERE r.user_id = ? AND r.next_attempt_at IS NULL ORDER BY m.title COLLATE NOCASE",
+            SELECT_RETRY
+        ))
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(retries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::CreateMovie;
+    use crate::services::MovieService;
+    use crate::test_helpers::{create_test_db_with_users, fixtures};
+
+    #[test]
+    fn test_retry_delay() {
+        assert_eq!(retry_delay(1), Some(Duration::minutes(15)));
+        assert_eq!(retry_delay(2), Some(Duration::hours(1)));
+        assert_eq!(retry_delay(5), Some(Duration::minutes(15 * 256)));
+        assert_eq!(retry_delay(MAX_ATTEMPTS), None);
+    }
+
+    #[tokio::test]
+    async fn test_retry_queue() {
+        let pool = create_test_db_with_users().await;
+        let movies = MovieService::new(pool.clone());
+        let service = PosterRetryService::new(pool);
+        let user_id = fixtures::test_user_id();
+
+        let movie = movies
+            .create(
+                user_id,
+                CreateMovie {
+                    barcode: None,
+                    tmdb_id: Some(550),
+                    title: "Fight Club".to_string(),
+                    original_title: None,
+                    disc_type: None,
+                    production_year: None,
+                },
+            )
+            .await
+            .unwrap();
+
+        service
+            .record_failure(user_id, movie.id, "/abc.jpg", "HTTP 503")
+            .await
+            .unwrap();
+        let now = Utc::now();
+        assert!(service.due(now, 10).await.unwrap().is_empty());
+        let due = service.due(now + Duration::minutes(16), 10).await.unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].title, "Fight Club");
+        assert_eq!(due[0].attempts, 1);
+
+        for _ in 1..MAX_ATTEMPTS {
+            service
+                .record_failure(user_id, movie.id, "/abc.jpg", "HTTP 503")
+                .await
+                .unwrap();
+        }
+        let far = now + Duration::days(30);
+        assert!(service.due(far, 10).await.unwrap().is_empty());
+        let given_up = service.given_up(user_id).await.unwrap();
+        assert_eq!(given_up.len(), 1);
+        assert_eq!(given_up[0].attempts, MAX_ATTEMPTS);
+
+        // A new source is tried again
+        service
+            .record_failure(user_id, movie.id, "/other.jpg", "timeout")
+            .await
+            .unwrap();
+        assert_eq!(service.due(far, 10).await.unwrap()[0].attempts, 1);
+
+        // Getting a poster resolves the entry
+        movies
+            .update_movie_poster_data(user_id, movie.id, Some(vec![1, 2, 3]))
+            .await
+            .unwrap();
+        assert!(service.due(far, 10).await.unwrap().is_empty());
+        assert!(service.given_up(user_id).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,332 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::sync::Mutex;
+
+use crate::error::{Error, Result};
+use crate::models::{Device, PushPlatform};
+
+pub type PushFuture<'a> = Pin<Box<dyn Future<Output = Result<PushOutcome>> + Send + 'a>>;
+
+const FCM_SCOPE: &str = "https://www.googleapis.com/auth/firebase.messaging";
+const GOOGLE_TOKEN_URI: &str = "https://oauth2.googleapis.com/token";
+/// Access tokens live an hour; get a new one a bit earlier
+const ACCESS_TOKEN_LIFETIME: Duration = Duration::from_secs(50 * 60);
+
+/// Notification sent to a user's devices
+#[derive(Debug, Clone, Serialize)]
+pub struct PushMessage {
+    /// What it is about, the same as the WebSocket event type, e.g. `loan_reminder`
+    pub kind: String,
+    pub title: String,
+    pub body: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PushOutcome {
+    Delivered,
+    /// The token is no longer valid (app removed); the device should be dropped
+    Unregistered,
+}
+
+/// Service delivering push notifications to device tokens
+pub trait PushProvider: Send + Sync {
+    /// Name reported in `/capabilities`
+    fn name(&self) -> &'static str;
+
+    fn send<'a>(&'a self, device: &'a Device, message: &'a PushMessage) -> PushFuture<'a>;
+}
+
+/// Key of a Firebase service account, as downloaded from the Firebase console
+#[derive(Debug, Clone, Deserialize)]
+struct ServiceAccount {
+    project_id: String,
+    client_email: String,
+    private_key: String,
+    #[serde(default)]
+    token_uri: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ServiceAccountClaims<'a> {
+    iss: &'a str,
+    scope: &'a str,
+    aud: &'a str,
+    iat: i64,
+    exp: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct AccessToken {
+    access_token: String,
+}
+
+/// Firebase Cloud Messaging (HTTP v1 API), authorized with a service account
+pub struct FcmProvider {
+    client: reqwest::Client,
+    account: ServiceAccount,
+    key: jsonwebtoken::EncodingKey,
+    access_token: Mutex<Option<(String, Instant)>>,
+}
+
+impl FcmProvider {
+    pub const NAME: &'static str = "fcm";
+
+    /// `credentials` is the service account JSON
+    pub fn new(credentials: &str) -> Result<Self> {
+        let account: ServiceAccount = serde_json::from_str(credentials).map_err(|e| {
+            Error::Validation(format!(
+                "push_credentials is not a service account key: {}",
+                e
+            ))
+        })?;
+        let key = jsonwebtoken::EncodingKey::from_rsa_pem(account.private_key.as_bytes()).map_err(
+            |e| Error::Validation(format!("Invalid service account private key: {}", e)),
+        )?;
+        Ok(Self {
+            client: reqwest::Client::new(),
+            account,
+            key,
+            access_token: Mutex::new(None),
+        })
+    }
+
+    /// OAuth access token for the service account, cached until shortly before it expires
+    async fn access_token(&self) -> Result<String> {
+        let mut cached = self.access_token.lock().await;
+        if let Some((token, fetched_at)) = cached.as_ref()
+            && fetched_at.elapsed() < ACCESS_TOKEN_LIFETIME
+        {
+            return Ok(token.clone());
+        }
+
+        let token_uri = self
+            .account
+            .token_uri
+            .as_deref()
+            .unwrap_or(GOOGLE_TOKEN_URI);
+        let now = chrono::Utc::now().timestamp();
+        let claims = ServiceAccountClaims {
+            iss: &self.account.client_email,
+            scope: FCM_SCOPE,
+            aud: token_uri,
+            iat: now,
+            exp: now + 3600,
+        };
+        let assertion = jsonwebtoken::encode(
+            &jsonwebtoken::Header::new(jsonwebtoken::Algorithm::RS256),
+            &claims,
+            &self.key,
+        )
+        .map_err(|e| Error::ExternalApi(format!("Failed to sign FCM token request: {}", e)))?;
+
+        let response = self
+            .client
+            .post(token_uri)
+            .header(
+                reqwest::header::CONTENT_TYPE,
+                "application/x-www-form-urlencoded",
+            )
+            .body(format!(
+                "grant_type={}&assertion={}",
+                urlencoding::encode("urn:ietf:params:oauth:grant-type:jwt-bearer"),
+                assertion
+            ))
+            .send()
+            .await
+            .map_err(|e| Error::ExternalApi(format!("FCM token request failed: {}", e)))?;
+        if !response.status().is_success() {
+            return Err(Error::ExternalApi(format!(
+                "FCM token request failed: HTTP {}",
+                response.status()
+            )));
+        }
+        let token: AccessToken = response
+            .json()
+            .await
+            .map_err(|e| Error::ExternalApi(format!("Invalid FCM token response: {}", e)))?;
+
+        *cached = Some((token.access_token.clone(), Instant::now()));
+        Ok(token.access_token)
+    }
+}
+
+/// Request body of `messages:send`
+fn fcm_message(token: &str, message: &PushMessage) -> serde_json::Value {
+    json!({
+        "message": {
+            "token": token,
+            "notification": { "title": message.title, "body": message.body },
+            "data": { "kind": message.kind },
+        }
+    })
+}
+
+impl PushProvider for FcmProvider {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn send<'a>(&'a self, device: &'a Device, message: &'a PushMessage) -> PushFuture<'a> {
+        Box::pin(async move {
+            if device.platform != PushPlatform::Fcm {
+                return Err(Error::Validation(
+                    "FCM can't deliver to APNs tokens; use a push gateway".to_string(),
+                ));
+            }
+
+            let access_token = self.access_token().await?;
+            let response = self
+                .client
+                .post(format!(
+                    "https://fcm.googleapis.com/v1/projects/{}/messages:send",
+                    self.account.project_id
+                ))
+                .bearer_auth(access_token)
+                .json(&fcm_message(&device.token, message))
+                .send()
+                .await
+                .map_err(|e| Error::ExternalApi(format!("FCM request failed: {}", e)))?;
+
+            match response.status() {
+                status if status.is_success() => Ok(PushOutcome::Delivered),
+                // UNREGISTERED: the app was removed or the token expired
+                reqwest::StatusCode::NOT_FOUND => Ok(PushOutcome::Unregistered),
+                status => Err(Error::ExternalApi(format!(
+                    "FCM request failed: HTTP {}",
+                    status
+                ))),
+            }
+        })
+    }
+}
+
+/// Relay that forwards notifications to FCM or APNs, e.g. a small service
+/// holding the APNs key. Receives `{token, platform, kind, title, body}` as
+/// JSON and answers 404 or 410 for tokens that are no longer valid.
+pub struct GatewayProvider {
+    client: reqwest::Client,
+    url: String,
+    token: Option<String>,
+}
+
+impl GatewayProvider {
+    pub const NAME: &'static str = "gateway";
+
+    /// `token` is sent as bearer token if given
+    pub fn new(url: &str, token: Option<&str>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.to_string(),
+            token: token.map(String::from),
+        }
+    }
+}
+
+impl PushProvider for GatewayProvider {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn send<'a>(&'a self, device: &'a Device, message: &'a PushMessage) -> PushFuture<'a> {
+        Box::pin(async move {
+            let mut request = self.client.post(&self.url).json(&json!({
+                "token": device.token,
+                "platform": device.platform,
+                "kind": message.kind,
+                "title": message.title,
+                "body": message.body,
+            }));
+            if let Some(ref token) = self.token {
+                request = request.bearer_auth(token);
+            }
+            let response = request
+                .send()
+                .await
+                .map_err(|e| Error::ExternalApi(format!("Push gateway request failed: {}", e)))?;
+
+            match response.status() {
+                status if status.is_success() => Ok(PushOutcome::Delivered),
+                reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::GONE => {
+                    Ok(PushOutcome::Unregistered)
+                }
+                status => Err(Error::ExternalApi(format!(
+                    "Push gateway request failed: HTTP {}",
+                    status
+                ))),
+            }
+        })
+    }
+}
+
+/// Provider selected by the `push_provider` setting: `fcm` (with the service
+/// account JSON as `push_credentials`) or the URL of a push gateway (with an
+/// optional bearer token as `push_credentials`). Empty disables push.
+pub fn push_provider_from_settings(
+    provider: Option<&str>,
+    credentials: Option<&str>,
+) -> Result<Option<Arc<dyn PushProvider>>> {
+    let provider = provider.map(str::trim).unwrap_or_default();
+    let credentials = credentials.map(str::trim).filter(|c| !c.is_empty());
+    if provider.is_empty() {
+        return Ok(None);
+    }
+
+    if provider.eq_ignore_ascii_case(FcmProvider::NAME) {
+        let credentials = credentials.ok_or_else(|| {
+            Error::Validation("FCM needs the service account JSON as push_credentials".to_string())
+        })?;
+        return Ok(Some(Arc::new(FcmProvider::new(credentials)?)));
+    }
+
+    if provider.starts_with("https://") || provider.starts_with("http://") {
+        return Ok(Some(Arc::new(GatewayProvider::new(provider, credentials))));
+    }
+
+    Err(Error::Validation(format!(
+        "Unknown push provider: {} (use fcm or a gateway URL)",
+        provider
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_provider_from_settings() {
+        assert!(push_provider_from_settings(None, None).unwrap().is_none());
+        assert!(
+            push_provider_from_settings(Some("  "), Some("x"))
+                .unwrap()
+                .is_none()
+        );
+
+        let gateway = push_provider_from_settings(Some("https://push.example.com/send"), None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(gateway.name(), GatewayProvider::NAME);
+
+        // FCM needs a service account key
+        assert!(push_provider_from_settings(Some("fcm"), None).is_err());
+        assert!(push_provider_from_settings(Some("fcm"), Some("{}")).is_err());
+        assert!(push_provider_from_settings(Some("apns"), None).is_err());
+    }
+
+    #[test]
+    fn test_fcm_message() {
+        let message = PushMessage {
+            kind: "loan_reminder".to_string(),
+            title: "Überfällig".to_string(),
+            body: "Alien ist bei Max".to_string(),
+        };
+        let body = fcm_message("abc", &message);
+        assert_eq!(body["message"]["token"], "abc");
+        assert_eq!(body["message"]["notification"]["title"], "Überfällig");
+        assert_eq!(body["message"]["data"]["kind"], "loan_reminder");
+    }
+}
@@ -0,0 +1,442 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::db::DbPool;
+use crate::error::Result;
+use crate::models::EntityType;
+
+/// Date columns checked per table
+const DATE_COLUMNS: &[(EntityType, &[&str])] = &[
+    (
+        EntityType::Movie,
+        &[
+            "release_date",
+            "purchase_date",
+            "value_date",
+            "lent_due",
+            "disposed_at",
+            "added_date",
+        ],
+    ),
+    (
+        EntityType::Series,
+        &[
+            "first_aired",
+            "purchase_date",
+            "value_date",
+            "lent_due",
+            "added_date",
+        ],
+    ),
+    (
+        EntityType::Collection,
+        &["purchase_date", "value_date", "lent_due", "added_date"],
+    ),
+];
+
+/// Personal ratings are 0 - 10
+const MAX_RATING: f64 = 10.0;
+
+/// (id, title, personal_title, original_title) of a row with a suspect title
+type TitleRow = (Uuid, Option<String>, Option<String>, Option<String>);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RepairCheck {
+    /// Not a `YYYY-MM-DD` date; reformatted when it can be parsed, cleared otherwise
+    InvalidDate,
+    NegativeRuntime,
+    RatingOutOfRange,
+    /// Part of a box set but itself marked as a box set
+    ChildCollection,
+    /// Empty or padded with whitespace
+    EmptyTitle,
+}
+
+/// New value for a field
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum RepairValue {
+    Text(String),
+    Real(f64),
+    Int(i64),
+    Null,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RepairIssue {
+    pub check: RepairCheck,
+    pub entity_type: EntityType,
+    pub id: Uuid,
+    pub title: String,
+    pub field: &'static str,
+    /// The stored value, as text
+    pub value: Option<String>,
+    /// What the field is (or would be) set to; `None` when it needs a manual fix
+    pub fix: Option<RepairValue>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RepairReport {
+    pub dry_run: bool,
+    pub found: usize,
+    pub fixed: usize,
+    pub issues: Vec<RepairIssue>,
+}
+
+/// Finds and fixes junk left by imports that breaks sorting and filtering
+pub struct RepairService {
+    pool: DbPool,
+}
+
+fn table_name(entity_type: EntityType) -> &'static str {
+    match entity_type {
+        EntityType::Movie => "movies",
+        EntityType::Series => "series",
+        EntityType::Collection => "collections",
+    }
+}
+
+/// Read dates in the formats imports have left behind
+fn parse_loose_date(value: &str) -> Option<NaiveDate> {
+    let value = value.trim();
+    const FORMATS: [&str; 5] = ["%Y-%m-%d", "%d.%m.%Y", "%m/%d/%Y", "%Y/%m/%d", "%Y%m%d"];
+    FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+        .or_else(|| {
+            DateTime::parse_from_rfc3339(value)
+                .ok()
+                .map(|d| d.date_naive())
+        })
+        // "2024-01-31 20:15:00" and similar
+        .or_else(|| {
+            let date = value.get(..10)?;
+            NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()
+        })
+}
+
+/// Ratings above 10 up to 100 are percentages (TMDB style 85 → 8.5)
+fn repair_rating(rating: f64) -> RepairValue {
+    if rating > MAX_RATING && rating <= 100.0 {
+        RepairValue::Real(rating / 10.0)
+    } else {
+        RepairValue::Null
+    }
+}
+
+impl RepairService {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Scan all libraries. With `dry_run` nothing is changed; otherwise every
+    /// issue with a `fix` is applied in one transaction and the rows get a new
+    /// `updated_at` so clients pick up the change.
+    pub async fn repair(&self, dry_run: bool) -> Result<RepairReport> {
+        let mut issues = Vec::new();
+        self.check_dates(&mut issues).await?;
+        self.check_runtimes(&mut issues).await?;
+        self.check_ratings(&mut issues).await?;
+        self.check_child_collections(&mut issues).await?;
+        self.check_titles(&mut issues).await?;
+
+        let fixable: Vec<&RepairIssue> = issues.iter().filter(|i| i.fix.is_some()).collect();
+        let mut fixed = 0;
+        if !dry_run && !fixable.is_empty() {
+            let now = Utc::now().to_rfc3339();
+            let mut tx = self.pool.begin().await?;
+            for issue in &fixable {
+                // Table and column names come from the checks, not from input
+                let query = format!(
+                    "UPDATE {} SET {} = ?, updated_at = ? WHERE id = ?",
+                    table_name(issue.entity_type),
+                    issue.field
+                );
+                let q = sqlx::query(&query);
+                let q = match issue.fix.clone() {
+                    Some(RepairValue::Text(value)) => q.bind(value),
+                    Some(RepairValue::Real(value)) => q.bind(value),
+                    Some(RepairValue::Int(value)) => q.bind(value),
+                    Some(RepairValue::Null) | None => q.bind(None::<String>),
+                };
+                q.bind(&now).bind(issue.id).execute(&mut *tx).await?;
+                fixed += 1;
+            }
+            tx.commit().await?;
+        }
+
+        Ok(RepairReport {
+            dry_run,
+            found: issues.len(),
+            fixed,
+            issues,
+        })
+    }
+
+    async fn check_dates(&self, issues: &mut Vec<RepairIssue>) -> Result<()> {
+        for (entity_type, columns) in DATE_COLUMNS {
+            for column in *columns {
+                let query = format!(
+                    r#"
+                    SELECT id, title, CAST({column} AS TEXT) FROM {table}
+                    WHERE {column} IS NOT NULL
+                      AND (typeof({column}) != 'text' OR date({column}) IS NULL
+                           OR date({column}) != {column})
+                    "#,
+                    column = column,
+                    table = table_name(*entity_type)
+                );
+                let rows: Vec<(Uuid, String, Option<String>)> =
+                    sqlx::query_as(&query).fetch_all(&self.pool).await?;
+
+                for (id, title, value) in rows {
+                    let parsed = value.as_deref().and_then(parse_loose_date);
+                    issues.push(RepairIssue {
+                        check: RepairCheck::InvalidDate,
+                        entity_type: *entity_type,
+                        id,
+                        title,
+                        field: column,
+                        value,
+                        fix: Some(match parsed {
+                            Some(date) => RepairValue::Text(date.format("%Y-%m-%d").to_string()),
+                            None => RepairValue::Null,
+                        }),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn check_runtimes(&self, issues: &mut Vec<RepairIssue>) -> Result<()> {
+        for entity_type in [EntityType::Movie, EntityType::Series] {
+            let query = format!(
+                "SELECT id, title, running_time FROM {} WHERE running_time < 0",
+                table_name(entity_type)
+            );
+            let rows: Vec<(Uuid, String, i64)> =
+                sqlx::query_as(&query).fetch_all(&self.pool).await?;
+
+            for (id, title, runtime) in rows {
+                issues.push(RepairIssue {
+                    check: RepairCheck::NegativeRuntime,
+                    entity_type,
+                    id,
+                    title,
+                    field: "running_time",
+                    value: Some(runtime.to_string()),
+                    fix: Some(RepairValue::Null),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    async fn check_ratings(&self, issues: &mut Vec<RepairIssue>) -> Result<()> {
+        for entity_type in [EntityType::Movie, EntityType::Series] {
+            let query = format!(
+                r#"
+                SELECT id, title, personal_rating FROM {}
+                WHERE personal_rating < 0 OR personal_rating > ?
+                "#,
+                table_name(entity_type)
+            );
+            let rows: Vec<(Uuid, String, f64)> = sqlx::query_as(&query)
+                .bind(MAX_RATING)
+                .fetch_all(&self.pool)
+                .await?;
+
+            for (id, title, rating) in rows {
+                issues.push(RepairIssue {
+                    check: RepairCheck::RatingOutOfRange,
+                    entity_type,
+                    id,
+                    title,
+                    field: "personal_rating",
+                    value: Some(rating.to_string()),
+                    fix: Some(repair_rating(rating)),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Box set entries that are themselves marked as box sets. Only fixed when
+    /// nothing points at them as a parent.
+    async fn check_child_collections(&self, issues: &mut Vec<RepairIssue>) -> Result<()> {
+        let rows: Vec<(Uuid, String, bool)> = sqlx::query_as(
+            r#"
+            SELECT m.id, m.title,
+                EXISTS (SELECT 1 FROM movies c WHERE c.parent_collection_id = m.id)
+            FROM movies m
+            WHERE m.parent_collection_id IS NOT NULL AND m.is_collection = 1
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        for (id, title, has_children) in rows {
+            issues.push(RepairIssue {
+                check: RepairCheck::ChildCollection,
+                entity_type: EntityType::Movie,
+                id,
+                title,
+                field: "is_collection",
+                value: Some("1".to_string()),
+                fix: (!has_children).then_some(RepairValue::Int(0)),
+            });
+        }
+        Ok(())
+    }
+
+    /// Empty titles take the personal or original title if there is one;
+    /// padded titles are trimmed
+    async fn check_titles(&self, issues: &mut Vec<RepairIssue>) -> Result<()> {
+        for entity_type in [
+            EntityType::Movie,
+            EntityType::Series,
+            EntityType::Collection,
+        ] {
+            let original = match entity_type {
+                EntityType::Collection => "NULL",
+                _ => "original_title",
+            };
+            let query = format!(
+                r#"
+                SELECT id, title, personal_title, {} FROM {}
+                WHERE title IS NULL OR title != trim(title) OR trim(title) = ''
+                "#,
+                original,
+                table_name(entity_type)
+            );
+            let rows: Vec<TitleRow> = sqlx::query_as(&query).fetch_all(&self.pool).await?;
+
+            for (id, title, personal_title, original_title) in rows {
+                let trimmed = title.as_deref().map(str::trim).unwrap_or_default();
+                let fix = if trimmed.is_empty() {
+                    [personal_title, original_title]
+                        .into_iter()
+                        .flatten()
+                        .map(|t| t.trim().to_string())
+                        .find(|t| !t.is_empty())
+                } else {
+                    Some(trimmed.to_string())
+                };
+                issues.push(RepairIssue {
+                    check: RepairCheck::EmptyTitle,
+                    entity_type,
+                    id,
+                    title: fix.clone().unwrap_or_default(),
+                    field: "title",
+                    value: title,
+                    fix: fix.map(RepairValue::Text),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::CreateMovie;
+    use crate::services::MovieService;
+    use crate::test_helpers::{create_test_db_with_users, fixtures};
+
+    #[test]
+    fn test_parse_loose_date() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 9);
+        assert_eq!(parse_loose_date("2024-03-09"), date);
+        assert_eq!(parse_loose_date("09.03.2024"), date);
+        assert_eq!(parse_loose_date("03/09/2024"), date);
+        assert_eq!(parse_loose_date("2024-03-09T20:15:00Z"), date);
+        assert_eq!(parse_loose_date("2024-03-09 20:15:00"), date);
+        assert_eq!(parse_loose_date("soon"), None);
+    }
+
+    #[tokio::test]
+    async fn test_repair() {
+        let pool = create_test_db_with_users().await;
+        let movies = MovieService::new(pool.clone());
+        let service = RepairService::new(pool.clone());
+        let user_id = fixtures::test_user_id();
+
+        let mut ids = Vec::new();
+        for title in ["Alien", "Heat", "Ronin", "Box"] {
+            let movie = movies
+                .create(
+                    user_id,
+                    CreateMovie {
+                        barcode: None,
+                        tmdb_id: None,
+                        title: title.to_string(),
+                        original_title: None,
+                        disc_type: None,
+                        production_year: None,
+                    },
+                )
+                .await
+                .unwrap();
+            ids.push(movie.id);
+        }
+        let junk = [
+            "UPDATE movies SET purchase_date = '24.12.2019', running_time = -5 WHERE id = ?",
+            "UPDATE movies SET lent_due = 'next week', personal_rating = 85 WHERE id = ?",
+            "UPDATE movies SET title = '  ', original_title = 'Ronin', is_collection = 1, \
+             parent_collection_id = ? WHERE id = ?",
+        ];
+        sqlx::query(junk[0])
+            .bind(ids[0])
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(junk[1])
+            .bind(ids[1])
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(junk[2])
+            .bind(ids[3])
+            .bind(ids[2])
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let report = service.repair(true).await.unwrap();
+        assert_eq!(report.found, 6);
+        assert_eq!(report.fixed, 0);
+        let date = report
+            .issues
+            .iter()
+            .find(|i| i.field == "purchase_date")
+            .unwrap();
+        assert_eq!(date.check, RepairCheck::InvalidDate);
+        assert_eq!(date.fix, Some(RepairValue::Text("2019-12-24".to_string())));
+        // Nothing changed yet
+        let stored: String = sqlx::query_scalar("SELECT purchase_date FROM movies WHERE id = ?")
+            .bind(ids[0])
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(stored, "24.12.2019");
+
+        let report = service.repair(false).await.unwrap();
+        assert_eq!(report.fixed, 6);
+
+        let alien = movies.get_by_id(user_id, ids[0]).await.unwrap();
+        assert_eq!(alien.purchase_date, NaiveDate::from_ymd_opt(2019, 12, 24));
+        assert_eq!(alien.running_time, None);
+        let heat = movies.get_by_id(user_id, ids[1]).await.unwrap();
+        assert_eq!(heat.lent_due, None);
+        assert_eq!(heat.personal_rating, Some(8.5));
+        let ronin = movies.get_by_id(user_id, ids[2]).await.unwrap();
+        assert_eq!(ronin.title, "Ronin");
+        assert!(!ronin.is_collection);
+
+        assert_eq!(service.repair(true).await.unwrap().found, 0);
+    }
+}
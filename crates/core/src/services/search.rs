@@ -0,0 +1,252 @@
+use uuid::Uuid;
+
+use crate::db::DbPool;
+use crate::error::Result;
+use crate::models::SearchResult;
+use crate::parsing::title::fold_title;
+use crate::services::movies::fts_prefix_query;
+
+/// Ranking weights per `library_fts` column (the three unindexed ones first):
+/// title matches count most, descriptions and notes least
+const BM25_WEIGHTS: &str = "0, 0, 0, 10.0, 5.0, 5.0, 1.0, 2.0, 1.0, 2.0, 10.0";
+
+/// Fill in `search_title` of movies and series where it is missing (new
+/// entries, changed titles), for one user or everyone. Returns the number of
+/// updated rows.
+pub(crate) async fn refresh_search_titles(pool: &DbPool, user_id: Option<Uuid>) -> Result<u64> {
+    let mut updated = 0;
+    let mut tx = pool.begin().await?;
+    for table in ["movies", "series"] {
+        let rows = sqlx::query_as::<_, (Uuid, String)>(&format!(
+            "SELECT id, title FROM {} WHERE search_title IS NULL AND (? IS NULL OR user_id = ?)",
+            table
+        ))
+        .bind(user_id)
+        .bind(user_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        for (id, title) in rows {
+            sqlx::query(&format!(
+                "UPDATE {} SET search_title = ? WHERE id = ?",
+                table
+            ))
+            .bind(fold_title(&title))
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+            updated += 1;
+        }
+    }
+    tx.commit().await?;
+    Ok(updated)
+}
+
+/// Full-text search over a user's movies and series. The `library_fts`
+/// index is kept up to date by triggers on both tables.
+pub struct SearchService {
+    pool: DbPool,
+}
+
+impl SearchService {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Compute missing search titles of all users, e.g. after an upgrade
+    pub async fn refresh_search_titles(&self) -> Result<u64> {
+        refresh_search_titles(&self.pool, None).await
+    }
+
+    /// Movies and series whose titles, description, actors, notes or genres
+    /// contain words starting with every word of `query`. Titles also match
+    /// in folded form, so "Koenig" finds "König".
+    pub async fn search(
+        &self,
+        user_id: Uuid,
+        query: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<SearchResult>> {
+        let Some(match_query) = fts_prefix_query(query) else {
+            return Ok(Vec::new());
+        };
+        refresh_search_titles(&self.pool, Some(user_id)).await?;
+
+        let results = sqlx::query_as::<_, SearchResult>(&format!(
+            r#"
+            SELECT f.entity_type, f.entity_id AS id,
+                   COALESCE(m.title, s.title) AS title,
+                   COALESCE(m.production_year, s.production_year) AS year,
+                   COALESCE(m.poster_hash, s.poster_hash) IS NOT NULL AS has_poster
+            FROM library_fts f
+            LEFT JOIN movies m ON f.entity_type = 'movie' AND m.id = f.entity_id
+            LEFT JOIN series s ON f.entity_type = 'series' AND s.id = f.entity_id
+            WHERE library_fts MATCH ? AND f.user_id = ?
+            ORDER BY bm25(library_fts, {}), title
+            LIMIT ? OFFSET ?
+            "#,
+            BM25_WEIGHTS
+        ))
+        .bind(match_query)
+        .bind(user_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{CreateMovie, CreateSeries, EntityType, UpdateMovie};
+    use crate::services::{MovieService, SeriesService};
+    use crate::test_helpers::{create_test_db_with_users, fixtures};
+
+    #[tokio::test]
+    async fn test_search_movies_and_series() {
+        let pool = create_test_db_with_users().await;
+        let movies = MovieService::new(pool.clone());
+        let series = SeriesService::new(pool.clone());
+        let search = SearchService::new(pool);
+        let user_id = fixtures::test_user_id();
+
+        let heat = movies
+            .create(
+                user_id,
+                CreateMovie {
+                    barcode: None,
+                    tmdb_id: None,
+                    title: "Heat".to_string(),
+                    original_title: None,
+                    disc_type: None,
+                    production_year: Some(1995),
+                },
+            )
+            .await
+            .unwrap();
+        movies
+            .update(
+                user_id,
+                heat.id,
+                UpdateMovie {
+                    actors: Some("Al Pacino, Robert De Niro".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        let godfather = movies
+            .create(
+                user_id,
+                CreateMovie {
+                    barcode: None,
+                    tmdb_id: None,
+                    title: "Der Pate".to_string(),
+                    original_title: Some("The Godfather".to_string()),
+                    disc_type: None,
+                    production_year: Some(1972),
+                },
+            )
+            .await
+            .unwrap();
+        movies
+            .update(
+                user_id,
+                godfather.id,
+                UpdateMovie {
+                    notes: Some("Pacino-Box, Regal oben".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        series
+            .create(
+                user_id,
+                CreateSeries {
+                    barcode: None,
+                    tmdb_id: None,
+                    title: "Pacino Stories".to_string(),
+                    disc_type: None,
+                },
+            )
+            .await
+            .unwrap();
+
+        let results = search.search(user_id, "pacino", 50, 0).await.unwrap();
+        assert_eq!(results.len(), 3);
+        // Title matches rank first
+        assert_eq!(results[0].entity_type, EntityType::Series);
+        assert_eq!(results[0].title, "Pacino Stories");
+
+        // Updates and deletes are reflected
+        movies.delete(user_id, heat.id).await.unwrap();
+        let results = search.search(user_id, "pacino", 50, 0).await.unwrap();
+        assert_eq!(results.len(), 2);
+
+        // Other users' libraries are not searched
+        let results = search
+            .search(fixtures::test_admin_id(), "pacino", 50, 0)
+            .await
+            .unwrap();
+        assert!(results.is_empty());
+
+        assert!(
+            search
+                .search(user_id, "  ", 50, 0)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_ignores_accents_and_umlaut_spelling() {
+        let pool = create_test_db_with_users().await;
+        let movies = MovieService::new(pool.clone());
+        let search = SearchService::new(pool);
+        let user_id = fixtures::test_user_id();
+
+        for title in ["Der König der Löwen", "Die fabelhafte Welt der Amélie"] {
+            movies
+                .create(
+                    user_id,
+                    CreateMovie {
+                        barcode: None,
+                        tmdb_id: None,
+                        title: title.to_string(),
+                        original_title: None,
+                        disc_type: None,
+                        production_year: None,
+                    },
+                )
+                .await
+                .unwrap();
+        }
+
+        for (query, title) in [
+            ("koenig", "Der König der Löwen"),
+            ("König", "Der König der Löwen"),
+            ("konig loew", "Der König der Löwen"),
+            ("Amelie", "Die fabelhafte Welt der Amélie"),
+        ] {
+            let results = search.search(user_id, query, 50, 0).await.unwrap();
+            assert_eq!(results.len(), 1, "{}", query);
+            assert_eq!(results[0].title, title);
+        }
+
+        let suggestions = movies.suggest(user_id, "Koenig", 10).await.unwrap();
+        assert_eq!(suggestions.len(), 1);
+
+        // Duplicate check before adding
+        let found = movies
+            .find_by_title(user_id, "Der Koenig der Loewen")
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 1);
+    }
+}
@@ -0,0 +1,427 @@
+use argon2::{
+    Argon2, PasswordHasher,
+    password_hash::{SaltString, rand_core::OsRng},
+};
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+use crate::db::DbPool;
+use crate::error::{Error, Result};
+use crate::services::image_hash;
+
+/// Password of all generated users
+pub const SEED_PASSWORD: &str = "password";
+
+const ADJECTIVES: &[&str] = &[
+    "Silent",
+    "Dark",
+    "Last",
+    "Golden",
+    "Broken",
+    "Hidden",
+    "Eternal",
+    "Lost",
+    "Red",
+    "Frozen",
+    "Wild",
+    "Crimson",
+    "Secret",
+    "Midnight",
+    "Endless",
+    "Burning",
+    "Forgotten",
+    "Savage",
+];
+const NOUNS: &[&str] = &[
+    "River", "Empire", "Horizon", "Witness", "Kingdom", "Shadow", "Garden", "Voyage", "Storm",
+    "City", "Machine", "Legacy", "Island", "Promise", "Frontier", "Mirror", "Dragon", "Signal",
+];
+const SUFFIXES: &[&str] = &[
+    "",
+    "",
+    "",
+    "",
+    " II",
+    " III",
+    ": Reloaded",
+    ": The Return",
+    " - Director's Cut",
+];
+const GENRES: &[&str] = &[
+    "Action",
+    "Abenteuer",
+    "Animation",
+    "Komödie",
+    "Krimi",
+    "Dokumentarfilm",
+    "Drama",
+    "Familie",
+    "Fantasy",
+    "Horror",
+    "Science Fiction",
+    "Thriller",
+    "Western",
+];
+const FIRST_NAMES: &[&str] = &[
+    "Anna", "Peter", "Maria", "Thomas", "Julia", "Michael", "Laura", "Stefan", "Sofia", "David",
+];
+const LAST_NAMES: &[&str] = &[
+    "Berger",
+    "Hoffmann",
+    "Novak",
+    "Carter",
+    "Lindqvist",
+    "Moreau",
+    "Tanaka",
+    "Rossi",
+    "Klein",
+];
+const DISC_TYPES: &[&str] = &["DVD", "Blu-ray", "4K UHD"];
+const LOCATIONS: &[&str] = &["Wohnzimmer", "Regal A", "Regal B", "Keller", "Box 1"];
+
+/// What to generate
+#[derive(Debug, Clone)]
+pub struct SeedOptions {
+    pub users: usize,
+    /// Movies per user
+    pub movies: usize,
+    /// Series per user
+    pub series: usize,
+    /// Collections per user, each referencing some of the user's movies
+    pub collections: usize,
+    /// Placeholder poster images assigned round-robin to movies (empty = no posters)
+    pub posters: Vec<Vec<u8>>,
+    /// Seed for the random generator, for reproducible data
+    pub seed: u64,
+}
+
+impl Default for SeedOptions {
+    fn default() -> Self {
+        Self {
+            users: 1,
+            movies: 100,
+            series: 10,
+            collections: 5,
+            posters: Vec::new(),
+            seed: 42,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, serde::Serialize)]
+pub struct SeedReport {
+    pub users_created: usize,
+    pub movies_created: usize,
+    pub series_created: usize,
+    pub collections_created: usize,
+}
+
+/// Small xorshift generator; good enough for fake data and keeps runs reproducible
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed.max(1))
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+
+    fn range(&mut self, min: i64, max: i64) -> i64 {
+        min + (self.next_u64() % ((max - min + 1) as u64)) as i64
+    }
+
+    fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+        items[(self.next_u64() % items.len() as u64) as usize]
+    }
+
+    fn chance(&mut self, percent: u64) -> bool {
+        self.next_u64() % 100 < percent
+    }
+}
+
+/// Generates large amounts of realistic fake data for development and load testing
+pub struct SeedService {
+    pool: DbPool,
+}
+
+impl SeedService {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn seed(&self, options: &SeedOptions) -> Result<SeedReport> {
+        let mut rng = Rng::new(options.seed);
+        let mut report = SeedReport::default();
+
+        // Hashing is slow, all users share the same password
+        let salt = SaltString::generate(&mut OsRng);
+        let password_hash = Argon2::default()
+            .hash_password(SEED_PASSWORD.as_bytes(), &salt)
+            .map_err(|e| Error::Internal(e.to_string()))?
+            .to_string();
+
+        let existing_users = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM users")
+            .fetch_one(&self.pool)
+            .await?;
+
+        let mut tx = self.pool.begin().await?;
+
+        // Posters go into the shared image store once; movies reference them by hash
+        let mut poster_hashes = Vec::with_capacity(options.posters.len());
+        for poster in &options.posters {
+            let hash = image_hash(poster);
+            sqlx::query(
+                r#"
+                INSERT INTO images (hash, data, size, ref_count, created_at)
+                VALUES (?, ?, ?, 0, ?)
+                ON CONFLICT(hash) DO NOTHING
+                "#,
+            )
+            .bind(&hash)
+            .bind(poster)
+            .bind(poster.len() as i64)
+            .bind(Utc::now().to_rfc3339())
+            .execute(&mut *tx)
+            .await?;
+            poster_hashes.push(hash);
+        }
+
+        // Unique per run so seeding can be repeated without username conflicts
+        let run_id = Uuid::new_v4().simple().to_string();
+        let run_id = &run_id[..8];
+
+        for u in 0..options.users {
+            let user_id = Uuid::new_v4();
+            let now = Utc::now().to_rfc3339();
+            // Same rule as registration: the first user becomes admin
+            let role = if existing_users == 0 && u == 0 {
+                "admin"
+            } else {
+                "user"
+            };
+
+            sqlx::query(
+                r#"
+                INSERT INTO users (id, username, email, password_hash, role, created_at, updated_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(user_id)
+            .bind(format!("seed_{}_{}", run_id, u + 1))
+            .bind(format!("seed_{}_{}@example.com", run_id, u + 1))
+            .bind(&password_hash)
+            .bind(role)
+            .bind(&now)
+            .bind(&now)
+            .execute(&mut *tx)
+            .await?;
+            report.users_created += 1;
+
+            let mut movie_ids = Vec::with_capacity(options.movies);
+            for m in 0..options.movies {
+                let id = Uuid::new_v4();
+                let created = (Utc::now() - Duration::minutes(rng.range(0, 525_600))).to_rfc3339();
+                let title = format!(
+                    "{} {}{}",
+                    rng.pick(ADJECTIVES),
+                    rng.pick(NOUNS),
+                    rng.pick(SUFFIXES)
+                );
+                let genres = format!("{}, {}", rng.pick(GENRES), rng.pick(GENRES));
+                let director = format!("{} {}", rng.pick(FIRST_NAMES), rng.pick(LAST_NAMES));
+                let actors = (0..4)
+                    .map(|_| format!("{} {}", rng.pick(FIRST_NAMES), rng.pick(LAST_NAMES)))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let personal_rating = if rng.chance(40) {
+                    Some(rng.range(2, 20) as f64 / 2.0)
+                } else {
+                    None
+                };
+                let poster_hash = if poster_hashes.is_empty() {
+                    None
+                } else {
+                    Some(poster_hashes[m % poster_hashes.len()].as_str())
+                };
+
+                sqlx::query(
+                    r#"
+                    INSERT INTO movies (
+                        id, user_id, barcode, title, description, production_year, running_time,
+                        director, actors, personal_rating, disc_type, genres, watched, location,
+                        price, currency, poster_hash, added_by, updated_by, created_at, updated_at
+                    )
+                    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                    "#,
+                )
+                .bind(id)
+                .bind(user_id)
+                .bind(format!("40{:011}", rng.range(0, 99_999_999_999)))
+                .bind(&title)
+                .bind(format!(
+                    "{} muss sich in {} einer letzten Herausforderung stellen.",
+                    director,
+                    rng.pick(NOUNS)
+                ))
+                .bind(rng.range(1950, 2025) as i32)
+                .bind(rng.range(80, 190) as i32)
+                .bind(&director)
+                .bind(&actors)
+                .bind(personal_rating)
+                .bind(rng.pick(DISC_TYPES))
+                .bind(&genres)
+                .bind(rng.chance(50))
+                .bind(rng.pick(LOCATIONS))
+                .bind(rng.range(499, 2999) as f64 / 100.0)
+                .bind("EUR")
+                .bind(poster_hash)
+                .bind(user_id)
+                .bind(user_id)
+                .bind(&created)
+                .bind(&created)
+                .execute(&mut *tx)
+                .await?;
+
+                movie_ids.push(id);
+                report.movies_created += 1;
+            }
+
+            for _ in 0..options.series {
+                let now = Utc::now().to_rfc3339();
+                sqlx::query(
+                    r#"
+                    INSERT INTO series (id, user_id, title, production_year, disc_type, created_at, updated_at)
+                    VALUES (?, ?, ?, ?, ?, ?, ?)
+                    "#,
+                )
+                .bind(Uuid::new_v4())
+                .bind(user_id)
+                .bind(format!(
+                    "{} {} - Staffel {}",
+                    rng.pick(ADJECTIVES),
+                    rng.pick(NOUNS),
+                    rng.range(1, 8)
+                ))
+                .bind(rng.range(1990, 2025) as i32)
+                .bind(rng.pick(DISC_TYPES))
+                .bind(&now)
+                .bind(&now)
+                .execute(&mut *tx)
+                .await?;
+                report.series_created += 1;
+            }
+
+            for _ in 0..options.collections {
+                let collection_id = Uuid::new_v4();
+                let now = Utc::now().to_rfc3339();
+                sqlx::query(
+                    r#"
+                    INSERT INTO collections (id, user_id, title, description, disc_type, created_at, updated_at)
+                    VALUES (?, ?, ?, ?, ?, ?, ?)
+                    "#,
+                )
+                .bind(collection_id)
+                .bind(user_id)
+                .bind(format!("{} Collection", rng.pick(NOUNS)))
+                .bind("Generated collection")
+                .bind(rng.pick(DISC_TYPES))
+                .bind(&now)
+                .bind(&now)
+                .execute(&mut *tx)
+                .await?;
+
+                // Consecutive movies from a random offset, so no movie appears twice
+                let items = rng.range(2, 6) as usize;
+                let offset = rng.next_u64() as usize;
+                for position in 0..items.min(movie_ids.len()) {
+                    let movie_id =
+                        movie_ids[(offset % movie_ids.len() + position) % movie_ids.len()];
+                    sqlx::query(
+                        r#"
+                        INSERT INTO collection_items (id, collection_id, item_type, movie_id, position, created_at)
+                        VALUES (?, ?, 'movie', ?, ?, ?)
+                        "#,
+                    )
+                    .bind(Uuid::new_v4())
+                    .bind(collection_id)
+                    .bind(movie_id)
+                    .bind(position as i32)
+                    .bind(&now)
+                    .execute(&mut *tx)
+                    .await?;
+                }
+                report.collections_created += 1;
+            }
+        }
+
+        tx.commit().await?;
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::create_test_db;
+
+    #[tokio::test]
+    async fn test_seed_creates_requested_amounts() {
+        let pool = create_test_db().await;
+        let service = SeedService::new(pool.clone());
+
+        let options = SeedOptions {
+            users: 2,
+            movies: 25,
+            series: 3,
+            collections: 2,
+            posters: vec![vec![0xFF, 0xD8, 0xFF, 0xE0]],
+            seed: 7,
+        };
+        let report = service.seed(&options).await.unwrap();
+
+        assert_eq!(report.users_created, 2);
+        assert_eq!(report.movies_created, 50);
+        assert_eq!(report.series_created, 6);
+        assert_eq!(report.collections_created, 4);
+
+        let movies: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM movies")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(movies, 50);
+
+        let admins: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users WHERE role = 'admin'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(admins, 1);
+
+        let images: i64 = sqlx::query_scalar("SELECT ref_count FROM images")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(images, 50);
+    }
+
+    #[tokio::test]
+    async fn test_seed_can_run_twice() {
+        let pool = create_test_db().await;
+        let service = SeedService::new(pool);
+
+        let options = SeedOptions {
+            movies: 5,
+            ..Default::default()
+        };
+        service.seed(&options).await.unwrap();
+        let report = service.seed(&options).await.unwrap();
+        assert_eq!(report.users_created, 1);
+    }
+}
@@ -3,7 +3,14 @@ use uuid::Uuid;
 
 use crate::db::DbPool;
 use crate::error::{Error, Result};
-use crate::models::{CreateSeries, Series, SeriesFilter, UpdateSeries};
+use crate::models::{
+    ActivityAction, CreateEpisode, CreateSeries, EntityType, Episode, EpisodeFilter, EpisodeList,
+    SeasonSummary, Series, SeriesFilter, UpdateEpisode, UpdateSeries,
+};
+use crate::parsing::title::fold_title;
+use crate::services::ImageService;
+use crate::services::activity::{record_activity, record_changes};
+use crate::services::tombstones::record_deletions;
 
 pub struct SeriesService {
     pool: DbPool,
@@ -20,8 +27,9 @@ impl SeriesService {
 
         sqlx::query(
             r#"
-            INSERT INTO series (id, user_id, barcode, tmdb_id, title, disc_type, created_at, updated_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            INSERT INTO series (id, user_id, barcode, tmdb_id, title, search_title, disc_type,
+                created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(id)
@@ -29,13 +37,25 @@ impl SeriesService {
         .bind(&input.barcode)
         .bind(input.tmdb_id)
         .bind(&input.title)
+        .bind(fold_title(&input.title))
         .bind(&input.disc_type)
         .bind(now.to_rfc3339())
         .bind(now.to_rfc3339())
         .execute(&self.pool)
         .await?;
 
-        self.get_by_id(user_id, id).await
+        let series = self.get_by_id(user_id, id).await?;
+        record_activity(
+            &self.pool,
+            user_id,
+            ActivityAction::Create,
+            EntityType::Series,
+            id,
+            Some(&series.title),
+            None,
+        )
+        .await?;
+        Ok(series)
     }
 
     pub async fn get_by_id(&self, user_id: Uuid, id: Uuid) -> Result<Series> {
@@ -72,25 +92,359 @@ impl SeriesService {
 
     pub async fn update(&self, user_id: Uuid, id: Uuid, input: UpdateSeries) -> Result<Series> {
         // Verify ownership
-        let _ = self.get_by_id(user_id, id).await?;
+        let before = self.get_by_id(user_id, id).await?;
 
-        // Update fields (simplified - add more as needed)
-        if let Some(ref title) = input.title {
-            sqlx::query("UPDATE series SET title = ? WHERE id = ? AND user_id = ?")
-                .bind(title)
-                .bind(id)
-                .bind(user_id)
-                .execute(&self.pool)
-                .await?;
+        // Fields that are not given keep their value
+        sqlx::query(
+            r#"
+            UPDATE series SET
+                title = COALESCE(?, title),
+                search_title = COALESCE(?, search_title),
+                original_title = COALESCE(?, original_title),
+                imdb_id = COALESCE(?, imdb_id),
+                description = COALESCE(?, description),
+                tagline = COALESCE(?, tagline),
+                production_year = COALESCE(?, production_year),
+                first_aired = COALESCE(?, first_aired),
+                network = COALESCE(?, network),
+                status = COALESCE(?, status),
+                episodes_count = COALESCE(?, episodes_count),
+                running_time = COALESCE(?, running_time),
+                actors = COALESCE(?, actors),
+                genres = COALESCE(?, genres),
+                watched = COALESCE(?, watched),
+                personal_rating = COALESCE(?, personal_rating),
+                location = COALESCE(?, location),
+                parent_collection_id = COALESCE(?, parent_collection_id),
+                notes = COALESCE(?, notes),
+                updated_at = ?
+            WHERE id = ? AND user_id = ?
+            "#,
+        )
+        .bind(&input.title)
+        .bind(input.title.as_deref().map(fold_title))
+        .bind(&input.original_title)
+        .bind(&input.imdb_id)
+        .bind(&input.description)
+        .bind(&input.tagline)
+        .bind(input.production_year)
+        .bind(input.first_aired)
+        .bind(&input.network)
+        .bind(&input.status)
+        .bind(input.episodes_count)
+        .bind(input.running_time)
+        .bind(&input.actors)
+        .bind(&input.genres)
+        .bind(input.watched)
+        .bind(input.personal_rating)
+        .bind(&input.location)
+        .bind(input.parent_collection_id)
+        .bind(&input.notes)
+        .bind(Utc::now().to_rfc3339())
+        .bind(id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        let series = self.get_by_id(user_id, id).await?;
+        record_changes(
+            &self.pool,
+            user_id,
+            EntityType::Series,
+            id,
+            &series.title,
+            &before,
+            &series,
+        )
+        .await?;
+        Ok(series)
+    }
+
+    /// Store (or with `None` remove) the poster of a series
+    pub async fn update_poster_data(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+        poster_data: Option<Vec<u8>>,
+    ) -> Result<Series> {
+        let poster_hash = match poster_data {
+            Some(ref data) => Some(ImageService::new(self.pool.clone()).store(data).await?),
+            None => None,
+        };
+
+        let result = sqlx::query(
+            "UPDATE series SET poster_hash = ?, updated_at = ? WHERE id = ? AND user_id = ?",
+        )
+        .bind(&poster_hash)
+        .bind(Utc::now().to_rfc3339())
+        .bind(id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(Error::NotFound);
         }
 
         self.get_by_id(user_id, id).await
     }
 
+    pub async fn get_poster_data(&self, user_id: Uuid, id: Uuid) -> Result<Option<Vec<u8>>> {
+        let hash = sqlx::query_scalar::<_, Option<String>>(
+            "SELECT poster_hash FROM series WHERE id = ? AND user_id = ?",
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        ImageService::new(self.pool.clone())
+            .get_optional(hash.flatten().as_deref())
+            .await
+    }
+
     pub async fn delete(&self, user_id: Uuid, id: Uuid) -> Result<()> {
-        let result = sqlx::query("DELETE FROM series WHERE id = ? AND user_id = ?")
+        let mut tx = self.pool.begin().await?;
+        let title: String =
+            sqlx::query_scalar("SELECT title FROM series WHERE id = ? AND user_id = ?")
+                .bind(id)
+                .bind(user_id)
+                .fetch_optional(&mut *tx)
+                .await?
+                .ok_or(Error::NotFound)?;
+
+        sqlx::query("DELETE FROM series WHERE id = ? AND user_id = ?")
             .bind(id)
             .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        record_deletions(&mut tx, user_id, EntityType::Series, &[id]).await?;
+        record_activity(
+            &mut *tx,
+            user_id,
+            ActivityAction::Delete,
+            EntityType::Series,
+            id,
+            Some(&title),
+            None,
+        )
+        .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Episodes of a series, filtered and sorted, with per-season counts over
+    /// all episodes
+    pub async fn list_episodes(
+        &self,
+        user_id: Uuid,
+        series_id: Uuid,
+        filter: EpisodeFilter,
+    ) -> Result<EpisodeList> {
+        let _ = self.get_by_id(user_id, series_id).await?;
+        let today = Utc::now().date_naive();
+
+        let seasons = sqlx::query_as::<_, SeasonSummary>(
+            r#"
+            SELECT season_number,
+                COUNT(*) AS episode_count,
+                COALESCE(SUM(watched), 0) AS watched_count,
+                COALESCE(SUM(owned), 0) AS owned_count,
+                COALESCE(SUM(air_date IS NULL OR air_date > ?), 0) AS unaired_count,
+                COALESCE(SUM(runtime), 0) AS runtime_total
+            FROM series_episodes WHERE series_id = ?
+            GROUP BY season_number ORDER BY season_number
+            "#,
+        )
+        .bind(today)
+        .bind(series_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        if filter.seasons_only == Some(true) {
+            return Ok(EpisodeList {
+                seasons,
+                episodes: Vec::new(),
+            });
+        }
+
+        let mut query = String::from("SELECT * FROM series_episodes WHERE series_id = ?");
+
+        if filter.season.is_some() {
+            query.push_str(" AND season_number = ?");
+        }
+
+        match filter.unaired {
+            Some(true) => query.push_str(" AND (air_date IS NULL OR air_date > ?)"),
+            Some(false) => query.push_str(" AND air_date <= ?"),
+            None => {}
+        }
+
+        if filter.watched.is_some() {
+            query.push_str(" AND watched = ?");
+        }
+
+        if filter.owned.is_some() {
+            query.push_str(" AND owned = ?");
+        }
+
+        let order = if filter
+            .sort_order
+            .as_deref()
+            .is_some_and(|o| o.eq_ignore_ascii_case("desc"))
+        {
+            "DESC"
+        } else {
+            "ASC"
+        };
+        // Whitelisted sort columns; season/episode number breaks ties
+        let order_clause = match filter.sort_by.as_deref() {
+            Some("air_date") => format!("air_date {order}, season_number, episode_number"),
+            Some("title") => format!("title COLLATE NOCASE {order}, season_number, episode_number"),
+            Some("runtime") => format!("runtime {order}, season_number, episode_number"),
+            _ => format!("season_number {order}, episode_number {order}"),
+        };
+        query.push_str(&format!(" ORDER BY {}", order_clause));
+
+        let mut q = sqlx::query_as::<_, Episode>(&query).bind(series_id);
+
+        if let Some(season) = filter.season {
+            q = q.bind(season);
+        }
+
+        if filter.unaired.is_some() {
+            q = q.bind(today);
+        }
+
+        if let Some(watched) = filter.watched {
+            q = q.bind(watched);
+        }
+
+        if let Some(owned) = filter.owned {
+            q = q.bind(owned);
+        }
+
+        let episodes = q.fetch_all(&self.pool).await?;
+        Ok(EpisodeList { seasons, episodes })
+    }
+
+    pub async fn get_episode(
+        &self,
+        user_id: Uuid,
+        series_id: Uuid,
+        episode_id: Uuid,
+    ) -> Result<Episode> {
+        let _ = self.get_by_id(user_id, series_id).await?;
+
+        sqlx::query_as::<_, Episode>("SELECT * FROM series_episodes WHERE id = ? AND series_id = ?")
+            .bind(episode_id)
+            .bind(series_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or(Error::NotFound)
+    }
+
+    pub async fn create_episode(
+        &self,
+        user_id: Uuid,
+        series_id: Uuid,
+        input: CreateEpisode,
+    ) -> Result<Episode> {
+        let _ = self.get_by_id(user_id, series_id).await?;
+
+        if input.season_number < 0 || input.episode_number < 0 {
+            return Err(Error::Validation(
+                "Season and episode numbers must not be negative".into(),
+            ));
+        }
+
+        let existing = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM series_episodes \
+             WHERE series_id = ? AND season_number = ? AND episode_number = ?",
+        )
+        .bind(series_id)
+        .bind(input.season_number)
+        .bind(input.episode_number)
+        .fetch_one(&self.pool)
+        .await?;
+        if existing > 0 {
+            return Err(Error::Duplicate(format!(
+                "Episode S{:02}E{:02} already exists",
+                input.season_number, input.episode_number
+            )));
+        }
+
+        let id = Uuid::new_v4();
+        let now = Utc::now().to_rfc3339();
+
+        sqlx::query(
+            r#"
+            INSERT INTO series_episodes (
+                id, series_id, season_number, episode_number, title, air_date, runtime,
+                watched, owned, created_at, updated_at
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id)
+        .bind(series_id)
+        .bind(input.season_number)
+        .bind(input.episode_number)
+        .bind(&input.title)
+        .bind(input.air_date)
+        .bind(input.runtime)
+        .bind(input.watched)
+        .bind(input.owned)
+        .bind(&now)
+        .bind(&now)
+        .execute(&self.pool)
+        .await?;
+
+        self.get_episode(user_id, series_id, id).await
+    }
+
+    pub async fn update_episode(
+        &self,
+        user_id: Uuid,
+        series_id: Uuid,
+        episode_id: Uuid,
+        input: UpdateEpisode,
+    ) -> Result<Episode> {
+        let episode = self.get_episode(user_id, series_id, episode_id).await?;
+
+        sqlx::query(
+            r#"
+            UPDATE series_episodes SET title = ?, air_date = ?, runtime = ?, watched = ?,
+                owned = ?, updated_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(input.title.or(episode.title))
+        .bind(input.air_date.or(episode.air_date))
+        .bind(input.runtime.or(episode.runtime))
+        .bind(input.watched.unwrap_or(episode.watched))
+        .bind(input.owned.unwrap_or(episode.owned))
+        .bind(Utc::now().to_rfc3339())
+        .bind(episode_id)
+        .execute(&self.pool)
+        .await?;
+
+        self.get_episode(user_id, series_id, episode_id).await
+    }
+
+    pub async fn delete_episode(
+        &self,
+        user_id: Uuid,
+        series_id: Uuid,
+        episode_id: Uuid,
+    ) -> Result<()> {
+        let _ = self.get_by_id(user_id, series_id).await?;
+
+        let result = sqlx::query("DELETE FROM series_episodes WHERE id = ? AND series_id = ?")
+            .bind(episode_id)
+            .bind(series_id)
             .execute(&self.pool)
             .await?;
 
@@ -115,6 +469,7 @@ impl SeriesService {
 mod tests {
     use super::*;
     use crate::test_helpers::{create_test_db_with_users, fixtures};
+    use chrono::NaiveDate;
 
     async fn setup() -> SeriesService {
         let pool = create_test_db_with_users().await;
@@ -269,14 +624,7 @@ mod tests {
                 series.id,
                 UpdateSeries {
                     title: Some("Breaking Bad (Complete Series)".to_string()),
-                    original_title: None,
-                    description: None,
-                    network: None,
-                    episodes_count: None,
-                    watched: None,
-                    personal_rating: None,
-                    location: None,
-                    notes: None,
+                    ..Default::default()
                 },
             )
             .await
@@ -285,6 +633,67 @@ mod tests {
         assert_eq!(updated.title, "Breaking Bad (Complete Series)");
     }
 
+    #[tokio::test]
+    async fn test_update_metadata_poster_and_parent() {
+        let pool = create_test_db_with_users().await;
+        let service = SeriesService::new(pool.clone());
+        let user_id = fixtures::test_user_id();
+        let collection_id = Uuid::new_v4();
+
+        let series = service
+            .create(
+                user_id,
+                CreateSeries {
+                    barcode: None,
+                    tmdb_id: Some(1396),
+                    title: "Breaking Bad".to_string(),
+                    disc_type: None,
+                },
+            )
+            .await
+            .unwrap();
+
+        let updated = service
+            .update(
+                user_id,
+                series.id,
+                UpdateSeries {
+                    network: Some("AMC".to_string()),
+                    episodes_count: Some(62),
+                    production_year: Some(2008),
+                    first_aired: NaiveDate::from_ymd_opt(2008, 1, 20),
+                    parent_collection_id: Some(collection_id),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(updated.title, "Breaking Bad");
+        assert_eq!(updated.network.as_deref(), Some("AMC"));
+        assert_eq!(updated.episodes_count, Some(62));
+        assert_eq!(updated.first_aired, NaiveDate::from_ymd_opt(2008, 1, 20));
+        assert_eq!(updated.parent_collection_id, Some(collection_id));
+
+        let poster = vec![0xFF, 0xD8, 0xFF, 0xE0, 1, 2, 3];
+        let updated = service
+            .update_poster_data(user_id, series.id, Some(poster.clone()))
+            .await
+            .unwrap();
+        assert!(updated.poster_hash.is_some());
+        assert_eq!(
+            service.get_poster_data(user_id, series.id).await.unwrap(),
+            Some(poster)
+        );
+
+        // Deleting the series releases the image
+        service.delete(user_id, series.id).await.unwrap();
+        let images: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM images")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(images, 0);
+    }
+
     #[tokio::test]
     async fn test_delete_series() {
         let service = setup().await;
@@ -341,4 +750,160 @@ mod tests {
         let count = service.count(user_id).await.unwrap();
         assert_eq!(count, 5);
     }
+
+    #[tokio::test]
+    async fn test_episodes_filter_sort_and_seasons() {
+        let service = setup().await;
+        let user_id = fixtures::test_user_id();
+
+        let series = service
+            .create(
+                user_id,
+                CreateSeries {
+                    barcode: None,
+                    tmdb_id: None,
+                    title: "Dark".to_string(),
+                    disc_type: None,
+                },
+            )
+            .await
+            .unwrap();
+
+        let aired = chrono::NaiveDate::from_ymd_opt(2017, 12, 1);
+        let upcoming = Some(Utc::now().date_naive() + chrono::Duration::days(30));
+        for (season, episode, air_date, runtime, owned) in [
+            (1, 2, aired, 45, true),
+            (1, 1, aired, 50, true),
+            (2, 1, aired, 55, false),
+            (2, 2, upcoming, 60, false),
+        ] {
+            service
+                .create_episode(
+                    user_id,
+                    series.id,
+                    CreateEpisode {
+                        season_number: season,
+                        episode_number: episode,
+                        title: Some(format!("Episode {}x{}", season, episode)),
+                        air_date,
+                        runtime: Some(runtime),
+                        watched: false,
+                        owned,
+                    },
+                )
+                .await
+                .unwrap();
+        }
+
+        let duplicate = CreateEpisode {
+            season_number: 1,
+            episode_number: 1,
+            title: None,
+            air_date: None,
+            runtime: None,
+            watched: false,
+            owned: false,
+        };
+        assert!(matches!(
+            service.create_episode(user_id, series.id, duplicate).await,
+            Err(Error::Duplicate(_))
+        ));
+
+        let all = service
+            .list_episodes(user_id, series.id, EpisodeFilter::default())
+            .await
+            .unwrap();
+        let numbers: Vec<(i32, i32)> = all
+            .episodes
+            .iter()
+            .map(|e| (e.season_number, e.episode_number))
+            .collect();
+        assert_eq!(numbers, vec![(1, 1), (1, 2), (2, 1), (2, 2)]);
+        assert_eq!(all.seasons.len(), 2);
+        assert_eq!(all.seasons[0].owned_count, 2);
+        assert_eq!(all.seasons[0].runtime_total, 95);
+        assert_eq!(all.seasons[1].unaired_count, 1);
+
+        service
+            .update_episode(
+                user_id,
+                series.id,
+                all.episodes[0].id,
+                UpdateEpisode {
+                    watched: Some(true),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        let unwatched_owned = service
+            .list_episodes(
+                user_id,
+                series.id,
+                EpisodeFilter {
+                    watched: Some(false),
+                    owned: Some(true),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(unwatched_owned.episodes.len(), 1);
+        assert_eq!(unwatched_owned.episodes[0].episode_number, 2);
+
+        let unaired = service
+            .list_episodes(
+                user_id,
+                series.id,
+                EpisodeFilter {
+                    unaired: Some(true),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(unaired.episodes.len(), 1);
+        assert_eq!(unaired.episodes[0].air_date, upcoming);
+
+        let by_runtime = service
+            .list_episodes(
+                user_id,
+                series.id,
+                EpisodeFilter {
+                    sort_by: Some("runtime".to_string()),
+                    sort_order: Some("desc".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(by_runtime.episodes[0].runtime, Some(60));
+
+        let summary = service
+            .list_episodes(
+                user_id,
+                series.id,
+                EpisodeFilter {
+                    seasons_only: Some(true),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert!(summary.episodes.is_empty());
+        assert_eq!(summary.seasons[0].watched_count, 1);
+
+        // Other users can't see them
+        assert!(
+            service
+                .list_episodes(
+                    fixtures::test_admin_id(),
+                    series.id,
+                    EpisodeFilter::default()
+                )
+                .await
+                .is_err()
+        );
+    }
 }
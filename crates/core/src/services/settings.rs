@@ -1,15 +1,90 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
 use sqlx::SqlitePool;
+use tokio::sync::watch;
 
 use crate::models::{Setting, SettingKey, SettingUpdate};
+use crate::services::blob_store::{BlobStore, blob_store_from_settings};
+use crate::services::ean::{AmazonCredentials, BarcodeProvider, barcode_providers_from_settings};
+use crate::services::mail::{SmtpMailer, mailer_from_settings};
+use crate::services::ocr::{OcrProvider, ocr_provider_from_settings};
+use crate::services::push::{PushProvider, push_provider_from_settings};
 use crate::{Error, Result};
 
+/// Effective setting values (environment over database) at the last reload.
+/// Services holding a [`SettingsWatch`] read it on every call, so saved
+/// settings apply without a restart.
+#[derive(Debug, Clone, Default)]
+pub struct SettingsSnapshot {
+    values: HashMap<SettingKey, String>,
+}
+
+impl SettingsSnapshot {
+    /// The value of a setting; `None` if unset or empty
+    pub fn get(&self, key: SettingKey) -> Option<&str> {
+        self.values.get(&key).map(String::as_str)
+    }
+
+    /// Boolean setting; unset or unrecognized values fall back to `default`
+    pub fn get_bool(&self, key: SettingKey, default: bool) -> bool {
+        parse_bool(self.get(key)).unwrap_or(default)
+    }
+
+    #[cfg(test)]
+    pub(crate) fn from_values<const N: usize>(values: [(SettingKey, &str); N]) -> Self {
+        Self {
+            values: values
+                .into_iter()
+                .map(|(key, value)| (key, value.to_string()))
+                .collect(),
+        }
+    }
+}
+
+/// Receiver of the current settings snapshot
+pub type SettingsWatch = watch::Receiver<Arc<SettingsSnapshot>>;
+
+fn parse_bool(value: Option<&str>) -> Option<bool> {
+    match value.map(|v| v.trim().to_lowercase()).as_deref() {
+        Some("true" | "1" | "yes" | "on") => Some(true),
+        Some("false" | "0" | "no" | "off") => Some(false),
+        _ => None,
+    }
+}
+
 pub struct SettingsService {
     pool: SqlitePool,
+    snapshot: watch::Sender<Arc<SettingsSnapshot>>,
 }
 
 impl SettingsService {
+    /// The snapshot is empty until the first [`reload`](Self::reload)
     pub fn new(pool: SqlitePool) -> Self {
-        Self { pool }
+        let (snapshot, _) = watch::channel(Arc::new(SettingsSnapshot::default()));
+        Self { pool, snapshot }
+    }
+
+    /// Read all settings again and publish them to subscribed services
+    pub async fn reload(&self) -> Result<Arc<SettingsSnapshot>> {
+        let mut values = HashMap::new();
+        for key in SettingKey::ALL {
+            if let Some(value) = self.get(key).await? {
+                values.insert(key, value);
+            }
+        }
+        let snapshot = Arc::new(SettingsSnapshot { values });
+        self.snapshot.send_replace(snapshot.clone());
+        Ok(snapshot)
+    }
+
+    /// The current snapshot, updated on every reload
+    pub fn subscribe(&self) -> SettingsWatch {
+        self.snapshot.subscribe()
+    }
+
+    pub fn snapshot(&self) -> Arc<SettingsSnapshot> {
+        self.snapshot.borrow().clone()
     }
 
     /// Get a setting value, checking environment variable first, then database
@@ -56,7 +131,7 @@ impl SettingsService {
         Ok(settings)
     }
 
-    /// Update a setting in the database
+    /// Update a setting in the database and reload the snapshot
     pub async fn update(&self, key: SettingKey, update: SettingUpdate) -> Result<Setting> {
         sqlx::query(
             r#"
@@ -78,9 +153,144 @@ impl SettingsService {
             .fetch_one(&self.pool)
             .await?;
 
+        self.reload().await?;
         Ok(setting)
     }
 
+    /// Get a boolean setting; unset or unrecognized values fall back to `default`
+    pub async fn get_bool(&self, key: SettingKey, default: bool) -> Result<bool> {
+        let value = self.get(key).await?;
+        Ok(parse_bool(value.as_deref()).unwrap_or(default))
+    }
+
+    /// Image store selected by the image storage settings (`None` = database).
+    /// `pending` is a value about to be saved, so it can be checked first.
+    pub async fn blob_store(
+        &self,
+        pending: Option<(SettingKey, &str)>,
+    ) -> Result<Option<Arc<dyn BlobStore>>> {
+        let mut values = Vec::new();
+        for key in [
+            SettingKey::ImageStorage,
+            SettingKey::S3AccessKeyId,
+            SettingKey::S3SecretAccessKey,
+        ] {
+            let value = match pending {
+                // The environment still wins over the new value
+                Some((k, v)) if k == key && std::env::var(key.env_var()).is_err() => {
+                    Some(v.to_string()).filter(|v| !v.is_empty())
+                }
+                _ => self.get(key).await?,
+            };
+            values.push(value);
+        }
+
+        blob_store_from_settings(
+            values[0].as_deref(),
+            values[1].as_deref(),
+            values[2].as_deref(),
+        )
+    }
+
+    /// Barcode provider chain selected by the barcode settings. `pending` is
+    /// a value about to be saved, so it can be checked first.
+    pub async fn barcode_providers(
+        &self,
+        pending: Option<(SettingKey, &str)>,
+    ) -> Result<Vec<Arc<dyn BarcodeProvider>>> {
+        let mut values = Vec::new();
+        for key in [
+            SettingKey::BarcodeProviders,
+            SettingKey::AmazonAccessKey,
+            SettingKey::AmazonSecretKey,
+            SettingKey::AmazonPartnerTag,
+            SettingKey::AmazonMarketplace,
+        ] {
+            let value = match pending {
+                // The environment still wins over the new value
+                Some((k, v)) if k == key && std::env::var(key.env_var()).is_err() => {
+                    Some(v.trim().to_string()).filter(|v| !v.is_empty())
+                }
+                _ => self.get(key).await?,
+            };
+            values.push(value);
+        }
+
+        let amazon = match (&values[1], &values[2], &values[3]) {
+            (Some(access_key), Some(secret_key), Some(partner_tag)) => Some(AmazonCredentials {
+                access_key: access_key.clone(),
+                secret_key: secret_key.clone(),
+                partner_tag: partner_tag.clone(),
+                marketplace: values[4].clone().unwrap_or_default(),
+            }),
+            _ => None,
+        };
+        barcode_providers_from_settings(values[0].as_deref(), amazon)
+    }
+
+    /// Push provider selected by the push settings (`None` = push disabled).
+    /// `pending` is a value about to be saved, so it can be checked first.
+    pub async fn push_provider(
+        &self,
+        pending: Option<(SettingKey, &str)>,
+    ) -> Result<Option<Arc<dyn PushProvider>>> {
+        let mut values = Vec::new();
+        for key in [SettingKey::PushProvider, SettingKey::PushCredentials] {
+            let value = match pending {
+                // The environment still wins over the new value
+                Some((k, v)) if k == key && std::env::var(key.env_var()).is_err() => {
+                    Some(v.trim().to_string()).filter(|v| !v.is_empty())
+                }
+                _ => self.get(key).await?,
+            };
+            values.push(value);
+        }
+
+        push_provider_from_settings(values[0].as_deref(), values[1].as_deref())
+    }
+
+    /// OCR provider selected by the OCR settings (`None` = cover recognition
+    /// disabled). `pending` is a value about to be saved, so it can be checked first.
+    pub async fn ocr_provider(
+        &self,
+        pending: Option<(SettingKey, &str)>,
+    ) -> Result<Option<Arc<dyn OcrProvider>>> {
+        let mut values = Vec::new();
+        for key in [SettingKey::OcrProvider, SettingKey::OcrApiKey] {
+            let value = match pending {
+                // The environment still wins over the new value
+                Some((k, v)) if k == key && std::env::var(key.env_var()).is_err() => {
+                    Some(v.trim().to_string()).filter(|v| !v.is_empty())
+                }
+                _ => self.get(key).await?,
+            };
+            values.push(value);
+        }
+
+        ocr_provider_from_settings(values[0].as_deref(), values[1].as_deref())
+    }
+
+    /// SMTP server selected by the mail settings (`None` = emails go to the
+    /// log). `pending` is a value about to be saved, so it can be checked first.
+    pub async fn smtp_mailer(
+        &self,
+        pending: Option<(SettingKey, &str)>,
+    ) -> Result<Option<Arc<SmtpMailer>>> {
+        let mut values = Vec::new();
+        for key in [SettingKey::SmtpUrl, SettingKey::MailFrom] {
+            let value = match pending {
+                // The environment still wins over the new value
+                Some((k, v)) if k == key && std::env::var(key.env_var()).is_err() => {
+                    Some(v.trim().to_string()).filter(|v| !v.is_empty())
+                }
+                _ => self.get(key).await?,
+            };
+            values.push(value);
+        }
+
+        mailer_from_settings(values[0].as_deref(), values[1].as_deref())
+    }
+
     /// Check if a setting is configured (either via env or database)
     pub async fn is_configured(&self, key: SettingKey) -> bool {
         self.get(key).await.ok().flatten().is_some()
@@ -90,7 +300,7 @@ impl SettingsService {
     pub async fn get_status(&self) -> Result<Vec<SettingStatus>> {
         let mut statuses = Vec::new();
 
-        for key in [SettingKey::TmdbApiKey] {
+        for key in SettingKey::ALL {
             let env_value = std::env::var(key.env_var()).ok();
             let db_setting = sqlx::query_as::<_, Setting>("SELECT * FROM settings WHERE key = ?")
                 .bind(key.as_str())
@@ -269,6 +479,39 @@ mod tests {
         assert!(is_configured);
     }
 
+    #[tokio::test]
+    async fn test_get_bool() {
+        let service = setup().await;
+        // SAFETY: We're in a single-threaded test context
+        unsafe { std::env::remove_var("POSTER_PREFETCH_AFTER_IMPORT") };
+        let key = SettingKey::PosterPrefetchAfterImport;
+
+        assert!(service.get_bool(key, true).await.unwrap());
+
+        for (value, expected) in [
+            ("false", false),
+            ("On", true),
+            ("0", false),
+            ("maybe", true),
+        ] {
+            service
+                .update(
+                    key,
+                    SettingUpdate {
+                        value: value.to_string(),
+                    },
+                )
+                .await
+                .unwrap();
+            assert_eq!(
+                service.get_bool(key, true).await.unwrap(),
+                expected,
+                "{}",
+                value
+            );
+        }
+    }
+
     #[tokio::test]
     async fn test_get_status() {
         let service = setup().await;
@@ -310,4 +553,42 @@ mod tests {
         let value = service.get(SettingKey::TmdbApiKey).await.unwrap();
         assert_eq!(value, Some("second-key".to_string()));
     }
+
+    #[tokio::test]
+    async fn test_reload_snapshot() {
+        let service = setup().await;
+        // SAFETY: We're in a single-threaded test context
+        unsafe { std::env::remove_var("OMDB_API_KEY") };
+
+        let omdb =
+            crate::services::OmdbService::new(String::new()).with_settings(service.subscribe());
+        assert!(!omdb.is_configured());
+        assert!(service.snapshot().get(SettingKey::OmdbApiKey).is_none());
+
+        // Saving a setting publishes it right away
+        service
+            .update(
+                SettingKey::OmdbApiKey,
+                SettingUpdate {
+                    value: "omdb-key".to_string(),
+                },
+            )
+            .await
+            .unwrap();
+        assert!(omdb.is_configured());
+        assert_eq!(
+            service.snapshot().get(SettingKey::OmdbApiKey),
+            Some("omdb-key")
+        );
+
+        // Changes made elsewhere show up after a reload
+        sqlx::query("UPDATE settings SET value = '' WHERE key = 'omdb_api_key'")
+            .execute(&service.pool)
+            .await
+            .unwrap();
+        assert!(omdb.is_configured());
+        service.reload().await.unwrap();
+        assert!(!omdb.is_configured());
+        assert!(service.snapshot().get_bool(SettingKey::ReadOnly, true));
+    }
 }
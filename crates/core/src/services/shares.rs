@@ -0,0 +1,406 @@
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::db::DbPool;
+use crate::error::{Error, Result};
+use crate::models::{CreateShareLink, CreatedShareLink, ShareLink, SharedLibrary, SharedMovie};
+use crate::services::blob_store::{hex, hmac_sha256};
+
+const MAX_NAME: usize = 100;
+/// Row of the `settings` table holding the signing key; not a [`SettingKey`],
+/// so it is never listed or editable
+///
+/// [`SettingKey`]: crate::models::SettingKey
+const SECRET_SETTING: &str = "share_secret";
+
+/// Movies of the owner that guests see: owned and not sold or given away
+const SHARED_MOVIES: &str = r#"
+    SELECT m.id, m.title, m.original_title, m.production_year, m.disc_type, m.running_time,
+           m.genres, m.director, m.description, m.poster_hash IS NOT NULL AS has_poster,
+           m.availability = 'available' AS available
+    FROM movies m
+    WHERE m.user_id = ? AND m.availability IN ('available', 'lent', 'missing')
+"#;
+
+/// Read-only share links. A token is the link id with an HMAC signature, so
+/// made up or altered tokens are rejected before touching the database, and
+/// deleting the link revokes it. The signing key is separate from the session
+/// keys, so rotating `JWT_SECRET` leaves the links valid.
+pub struct ShareService {
+    pool: DbPool,
+    secret: Vec<u8>,
+}
+
+impl ShareService {
+    pub fn new(pool: DbPool, secret: String) -> Self {
+        Self {
+            pool,
+            secret: secret.into_bytes(),
+        }
+    }
+
+    /// The signing key of this instance, generated on first use and kept in
+    /// the database so links survive restarts
+    pub async fn load_secret(pool: &DbPool) -> Result<String> {
+        // Two v4 UUIDs give 244 random bits
+        let generated = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+        sqlx::query("INSERT OR IGNORE INTO settings (key, value, description) VALUES (?, ?, ?)")
+            .bind(SECRET_SETTING)
+            .bind(generated)
+            .bind("Signing key of share links")
+            .execute(pool)
+            .await?;
+
+        let secret = sqlx::query_scalar("SELECT value FROM settings WHERE key = ?")
+            .bind(SECRET_SETTING)
+            .fetch_one(pool)
+            .await?;
+        Ok(secret)
+    }
+
+    fn sign(&self, id: Uuid) -> String {
+        hex(&hmac_sha256(&self.secret, id.as_bytes()))
+    }
+
+    fn token(&self, id: Uuid) -> String {
+        format!("{}.{}", id.simple(), self.sign(id))
+    }
+
+    pub async fn create(&self, user_id: Uuid, input: CreateShareLink) -> Result<CreatedShareLink> {
+        let name = input
+            .name
+            .as_deref()
+            .map(str::trim)
+            .filter(|n| !n.is_empty());
+        if name.is_some_and(|n| n.chars().count() > MAX_NAME) {
+            return Err(Error::Validation(format!(
+                "Name must be at most {} characters",
+                MAX_NAME
+            )));
+        }
+        if input.expires_at.is_some_and(|at| at <= Utc::now()) {
+            return Err(Error::Validation(
+                "Expiry must be in the future".to_string(),
+            ));
+        }
+        if let Some(collection_id) = input.collection_id {
+            let owned: Option<i64> =
+                sqlx::query_scalar("SELECT 1 FROM collections WHERE id = ? AND user_id = ?")
+                    .bind(collection_id)
+                    .bind(user_id)
+                    .fetch_optional(&self.pool)
+                    .await?;
+            owned.ok_or(Error::NotFound)?;
+        }
+
+        let id = Uuid::new_v4();
+        sqlx::query(
+            r#"
+            INSERT INTO share_links (id, user_id, collection_id, name, created_at, expires_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(input.collection_id)
+        .bind(name)
+        .bind(Utc::now().to_rfc3339())
+        .bind(input.expires_at.map(|at| at.to_rfc3339()))
+        .execute(&self.pool)
+        .await?;
+
+        let link = sqlx::query_as::<_, ShareLink>("SELECT * FROM share_links WHERE id = ?")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(CreatedShareLink {
+            token: self.token(id),
+            link,
+        })
+    }
+
+    pub async fn list(&self, user_id: Uuid) -> Result<Vec<ShareLink>> {
+        let links = sqlx::query_as::<_, ShareLink>(
+            "SELECT * FROM share_links WHERE user_id = ? ORDER BY created_at DESC",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(links)
+    }
+
+    /// Revoke a link
+    pub async fn delete(&self, user_id: Uuid, id: Uuid) -> Result<()> {
+        let result = sqlx::query("DELETE FROM share_links WHERE id = ? AND user_id = ?")
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(Error::NotFound);
+        }
+        Ok(())
+    }
+
+    /// The link a token stands for. Invalid, revoked and expired tokens are
+    /// all `NotFound`.
+    pub async fn resolve(&self, token: &str) -> Result<ShareLink> {
+        let (id, signature) = token.split_once('.').ok_or(Error::NotFound)?;
+        let id = Uuid::parse_str(id).map_err(|_| Error::NotFound)?;
+        let expected = self.sign(id);
+        // Compared in full so the time taken doesn't tell how much matched
+        let matches = signature.len() == expected.len()
+            && signature
+                .bytes()
+                .zip(expected.bytes())
+                .fold(0, |diff, (a, b)| diff | (a ^ b))
+                == 0;
+        if !matches {
+            return Err(Error::NotFound);
+        }
+
+        sqlx::query_as::<_, ShareLink>(
+            r#"
+            SELECT * FROM share_links
+            WHERE id = ? AND (expires_at IS NULL OR julianday(expires_at) > julianday(?))
+            "#,
+        )
+        .bind(id)
+        .bind(Utc::now().to_rfc3339())
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or(Error::NotFound)
+    }
+
+    /// The shared movies, by title
+    pub async fn library(&self, link: &ShareLink) -> Result<SharedLibrary> {
+        let owner: String = sqlx::query_scalar("SELECT username FROM users WHERE id = ?")
+            .bind(link.user_id)
+            .fetch_one(&self.pool)
+            .await?;
+
+        let (collection, movies) = match link.collection_id {
+            Some(collection_id) => {
+                let title: String =
+                    sqlx::query_scalar("SELECT title FROM collections WHERE id = ?")
+                        .bind(collection_id)
+                        .fetch_one(&self.pool)
+                        .await?;
+                let movies = sqlx::query_as::<_, SharedMovie>(&format!(
+                    "{} AND m.id IN (SELECT movie_id FROM collection_items WHERE collection_id = ?) \
+                     ORDER BY m.title COLLATE NOCASE",
+                    SHARED_MOVIES
+                ))
+                .bind(link.user_id)
+                .bind(collection_id)
+                .fetch_all(&self.pool)
+                .await?;
+                (Some(title), movies)
+            }
+            None => {
+                let movies = sqlx::query_as::<_, SharedMovie>(&format!(
+                    "{} ORDER BY m.title COLLATE NOCASE",
+                    SHARED_MOVIES
+                ))
+                .bind(link.user_id)
+                .fetch_all(&self.pool)
+                .await?;
+                (None, movies)
+            }
+        };
+
+        Ok(SharedLibrary {
+            owner,
+            collection,
+            movies,
+        })
+    }
+
+    /// Whether the link shares this movie (for posters and borrow requests)
+    pub async fn shares_movie(&self, link: &ShareLink, movie_id: Uuid) -> Result<bool> {
+        let found: Option<i64> = sqlx::query_scalar(&format!(
+            "SELECT 1 FROM ({}) WHERE id = ? AND (? IS NULL OR id IN \
+             (SELECT movie_id FROM collection_items WHERE collection_id = ?))",
+            SHARED_MOVIES
+        ))
+        .bind(link.user_id)
+        .bind(movie_id)
+        .bind(link.collection_id)
+        .bind(link.collection_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(found.is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        AddCollectionItem, CollectionItemType, CreateCollection, CreateMovie, UpdateMovie,
+    };
+    use crate::services::{CollectionService, MovieService};
+    use crate::test_helpers::{create_test_db_with_users, fixtures};
+
+    #[tokio::test]
+    async fn test_share_links() {
+        let pool = create_test_db_with_users().await;
+        let movies = MovieService::new(pool.clone());
+        let collections = CollectionService::new(pool.clone());
+        let shares = ShareService::new(pool.clone(), "test-secret".to_string());
+        let user_id = fixtures::test_user_id();
+
+        let mut ids = Vec::new();
+        for title in ["Heat", "Alien", "Dune"] {
+            let movie = movies
+                .create(
+                    user_id,
+                    CreateMovie {
+                        barcode: None,
+                        tmdb_id: None,
+                        title: title.to_string(),
+                        original_title: None,
+                        disc_type: None,
+                        production_year: None,
+                    },
+                )
+                .await
+                .unwrap();
+            ids.push(movie.id);
+        }
+        // Wishlist entries aren't shared, lent movies are
+        movies
+            .update(
+                user_id,
+                ids[2],
+                UpdateMovie {
+                    status: Some("wishlist".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        movies
+            .update(
+                user_id,
+                ids[1],
+                UpdateMovie {
+                    lent_to: Some("Lena".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        let created = shares
+            .create(user_id, CreateShareLink::default())
+            .await
+            .unwrap();
+        let link = shares.resolve(&created.token).await.unwrap();
+        let library = shares.library(&link).await.unwrap();
+        assert_eq!(library.collection, None);
+        let titles: Vec<&str> = library.movies.iter().map(|m| m.title.as_str()).collect();
+        assert_eq!(titles, ["Alien", "Heat"]);
+        assert!(!library.movies[0].available);
+        assert!(shares.shares_movie(&link, ids[0]).await.unwrap());
+        assert!(!shares.shares_movie(&link, ids[2]).await.unwrap());
+
+        // Altered or made up tokens
+        let mut tampered = created.token.clone();
+        tampered.replace_range(..1, if tampered.starts_with('0') { "1" } else { "0" });
+        assert!(matches!(
+            shares.resolve(&tampered).await,
+            Err(Error::NotFound)
+        ));
+        assert!(shares.resolve("nonsense").await.is_err());
+
+        // A single collection
+        let collection = collections
+            .create(
+                user_id,
+                CreateCollection {
+                    barcode: None,
+                    title: "Kinderfilme".to_string(),
+                    description: None,
+                    disc_type: None,
+                },
+            )
+            .await
+            .unwrap();
+        collections
+            .add_item(
+                user_id,
+                collection.id,
+                AddCollectionItem {
+                    item_type: CollectionItemType::Movie,
+                    movie_id: Some(ids[0]),
+                    series_id: None,
+                    position: None,
+                },
+            )
+            .await
+            .unwrap();
+        let input = CreateShareLink {
+            collection_id: Some(collection.id),
+            name: Some(" Tom ".to_string()),
+            ..Default::default()
+        };
+        let boxed = shares.create(user_id, input).await.unwrap();
+        assert_eq!(boxed.link.name.as_deref(), Some("Tom"));
+        let link = shares.resolve(&boxed.token).await.unwrap();
+        let library = shares.library(&link).await.unwrap();
+        assert_eq!(library.collection.as_deref(), Some("Kinderfilme"));
+        assert_eq!(library.movies.len(), 1);
+        assert!(!shares.shares_movie(&link, ids[1]).await.unwrap());
+
+        // Only own collections
+        let input = CreateShareLink {
+            collection_id: Some(collection.id),
+            ..Default::default()
+        };
+        assert!(
+            shares
+                .create(fixtures::test_admin_id(), input)
+                .await
+                .is_err()
+        );
+
+        // Expired and revoked links
+        sqlx::query("UPDATE share_links SET expires_at = '2020-01-01T00:00:00+00:00' WHERE id = ?")
+            .bind(boxed.link.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(shares.resolve(&boxed.token).await.is_err());
+        assert_eq!(shares.list(user_id).await.unwrap().len(), 2);
+        shares.delete(user_id, created.link.id).await.unwrap();
+        assert!(shares.resolve(&created.token).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_load_secret() {
+        let pool = create_test_db_with_users().await;
+
+        let secret = ShareService::load_secret(&pool).await.unwrap();
+        assert_eq!(secret.len(), 64);
+        // Kept across restarts
+        assert_eq!(ShareService::load_secret(&pool).await.unwrap(), secret);
+
+        let shares = ShareService::new(pool.clone(), secret);
+        let created = shares
+            .create(fixtures::test_user_id(), CreateShareLink::default())
+            .await
+            .unwrap();
+        let restarted = ShareService::new(
+            pool.clone(),
+            ShareService::load_secret(&pool).await.unwrap(),
+        );
+        assert!(restarted.resolve(&created.token).await.is_ok());
+        // Not shown with the configurable settings
+        let statuses = crate::services::SettingsService::new(pool)
+            .get_status()
+            .await
+            .unwrap();
+        assert!(statuses.iter().all(|s| s.key != SECRET_SETTING));
+    }
+}
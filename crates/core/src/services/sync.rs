@@ -0,0 +1,967 @@
+use std::collections::HashMap;
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chrono::{DateTime, Utc};
+use jsonwebtoken::Algorithm;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::db::DbPool;
+use crate::error::{Error, Result};
+use crate::models::{
+    Collection, CreateSyncPeer, EntityType, Movie, Series, SyncChanges, SyncConflict, SyncOutcome,
+    SyncPeer, SyncPeerPublic, SyncWinner, Tombstone,
+};
+use crate::services::TombstoneService;
+use crate::services::blob_store::hmac_sha256;
+use crate::services::jwt::{jwt_algorithm, read_key};
+use crate::services::tombstones::record_deletions;
+
+/// Columns that are never copied from a peer: images live in the local image store
+const SKIPPED_COLUMNS: [&str; 3] = ["poster_hash", "poster_data", "backdrop_hash"];
+
+/// Columns that point at a user and are set to the local user instead
+const USER_COLUMNS: [&str; 3] = ["user_id", "added_by", "updated_by"];
+
+fn table_name(entity_type: EntityType) -> &'static str {
+    match entity_type {
+        EntityType::Movie => "movies",
+        EntityType::Series => "series",
+        EntityType::Collection => "collections",
+    }
+}
+
+/// A movie, series or collection from a peer as column values
+struct Record {
+    entity_type: EntityType,
+    id: Uuid,
+    updated_at: DateTime<Utc>,
+    fields: Map<String, Value>,
+}
+
+impl Record {
+    fn new<T: Serialize>(
+        entity_type: EntityType,
+        id: Uuid,
+        updated_at: DateTime<Utc>,
+        entity: &T,
+    ) -> Result<Self> {
+        let fields = match serde_json::to_value(entity) {
+            Ok(Value::Object(fields)) => fields,
+            _ => return Err(Error::Internal("Failed to serialize entity".to_string())),
+        };
+        Ok(Self {
+            entity_type,
+            id,
+            updated_at,
+            fields,
+        })
+    }
+
+    fn title(&self) -> Option<String> {
+        self.fields
+            .get("title")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    }
+
+    /// Value for a database column (`movie_group`/`series_group` are `group` in the API)
+    fn column(&self, column: &str) -> Option<&Value> {
+        match column {
+            "movie_group" | "series_group" => self.fields.get("group"),
+            _ => self.fields.get(column),
+        }
+    }
+}
+
+/// A value bound into the generated upsert
+enum Bind {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Real(f64),
+    Text(String),
+    Uuid(Uuid),
+}
+
+impl Bind {
+    fn from_json(value: &Value, declared_type: &str) -> Result<Self> {
+        Ok(match value {
+            Value::Null => Bind::Null,
+            Value::Bool(b) => Bind::Bool(*b),
+            Value::Number(n) => match n.as_i64() {
+                Some(i) => Bind::Int(i),
+                None => Bind::Real(n.as_f64().unwrap_or_default()),
+            },
+            // IDs are stored as blobs
+            Value::String(s) if declared_type.eq_ignore_ascii_case("BLOB") => {
+                Bind::Uuid(Uuid::parse_str(s).map_err(|e| Error::Validation(e.to_string()))?)
+            }
+            Value::String(s) => Bind::Text(s.clone()),
+            Value::Array(_) | Value::Object(_) => Bind::Text(value.to_string()),
+        })
+    }
+}
+
+/// Marks an encrypted pairing token in `sync_peers.token`
+const ENCRYPTED_PREFIX: &str = "enc:";
+const NONCE_LEN: usize = 12;
+
+/// Encrypts the pairing tokens of peers (AES-256-GCM). They have to be sent
+/// to the peer, so unlike API keys they can't be hashed. The key is derived
+/// from the session signing secret, which is not in the database, so a copy
+/// of the database alone doesn't reveal the tokens.
+pub struct TokenCipher {
+    /// Current key first, then the one of the previous secret
+    keys: Vec<Aes256Gcm>,
+}
+
+impl TokenCipher {
+    pub fn new(secret: &[u8]) -> Self {
+        Self {
+            keys: vec![Self::derive(secret)],
+        }
+    }
+
+    /// Keep decrypting tokens encrypted with a previous secret
+    pub fn with_previous_secret(mut self, secret: &[u8]) -> Self {
+        self.keys.push(Self::derive(secret));
+        self
+    }
+
+    /// Keys from `JWT_SECRET` (and `JWT_PREVIOUS_SECRET`) or, with RS256 or
+    /// EdDSA, the private key file
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let mut cipher = if jwt_algorithm(config.jwt_algorithm.as_deref())? == Algorithm::HS256 {
+            Self::new(config.jwt_secret.as_bytes())
+        } else {
+            let path = config.jwt_private_key_file.as_deref().ok_or_else(|| {
+                Error::Configuration("JWT_PRIVATE_KEY_FILE is not set".to_string())
+            })?;
+            Self::new(&read_key(path)?)
+        };
+        if let Some(secret) = config
+            .jwt_previous_secret
+            .as_deref()
+            .filter(|s| !s.is_empty())
+        {
+            cipher = cipher.with_previous_secret(secret.as_bytes());
+        }
+        Ok(cipher)
+    }
+
+    fn derive(secret: &[u8]) -> Aes256Gcm {
+        let key = hmac_sha256(secret, b"my-movies sync peer tokens");
+        Aes256Gcm::new(&key.into())
+    }
+
+    fn encrypt(&self, token: &str) -> Result<String> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let mut sealed = nonce.to_vec();
+        sealed.extend(
+            self.keys[0]
+                .encrypt(&nonce, token.as_bytes())
+                .map_err(|e| Error::Internal(format!("Failed to encrypt token: {}", e)))?,
+        );
+        Ok(format!("{}{}", ENCRYPTED_PREFIX, BASE64.encode(sealed)))
+    }
+
+    /// The token and whether it was encrypted with the current key; tokens
+    /// stored before encryption are returned as they are
+    fn decrypt(&self, stored: &str) -> Result<(String, bool)> {
+        let Some(encoded) = stored.strip_prefix(ENCRYPTED_PREFIX) else {
+            return Ok((stored.to_string(), false));
+        };
+        let sealed = BASE64.decode(encoded).unwrap_or_default();
+        if sealed.len() > NONCE_LEN {
+            let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+            for (i, key) in self.keys.iter().enumerate() {
+                if let Ok(token) = key.decrypt(Nonce::from_slice(nonce), ciphertext) {
+                    let token = String::from_utf8(token)
+                        .map_err(|_| Error::Internal("Invalid pairing token".to_string()))?;
+                    return Ok((token, i == 0));
+                }
+            }
+        }
+        Err(Error::Configuration(
+            "The pairing token can't be decrypted with the current JWT secret or key; add the peer again"
+                .to_string(),
+        ))
+    }
+}
+
+/// Sync between two my-movies servers: exchanges movies, series and collections
+/// changed since the last run, plus deletions from the tombstone table
+pub struct SyncService {
+    pool: DbPool,
+    tokens: TokenCipher,
+}
+
+impl SyncService {
+    pub fn new(pool: DbPool, tokens: TokenCipher) -> Self {
+        Self { pool, tokens }
+    }
+
+    /// Encrypt tokens stored in plain text or with a previous secret with the
+    /// current key; run at startup
+    pub async fn encrypt_stored_tokens(&self) -> Result<u64> {
+        let stored: Vec<(Uuid, String)> = sqlx::query_as("SELECT id, token FROM sync_peers")
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut updated = 0;
+        for (id, token) in stored {
+            let token = match self.tokens.decrypt(&token) {
+                Ok((_, true)) => continue,
+                Ok((token, false)) => token,
+                Err(e) => {
+                    tracing::warn!("Sync peer {}: {}", id, e);
+                    continue;
+                }
+            };
+            sqlx::query("UPDATE sync_peers SET token = ? WHERE id = ?")
+                .bind(self.tokens.encrypt(&token)?)
+                .bind(id)
+                .execute(&self.pool)
+                .await?;
+            updated += 1;
+        }
+        Ok(updated)
+    }
+
+    /// The pairing token to authenticate with at `peer`
+    pub fn peer_token(&self, peer: &SyncPeer) -> Result<String> {
+        self.tokens.decrypt(&peer.token).map(|(token, _)| token)
+    }
+
+    /// Entities changed or deleted after `since` (everything if `None`).
+    /// Box sets come before the movies linked to them.
+    pub async fn changes_since(
+        &self,
+        user_id: Uuid,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<SyncChanges> {
+        let since_str = since.map(|s| s.to_rfc3339());
+        let filter = "WHERE user_id = ? AND (? IS NULL OR julianday(updated_at) > julianday(?))";
+
+        let movies = sqlx::query_as::<_, Movie>(&format!(
+            "SELECT * FROM movies {} ORDER BY is_collection DESC, updated_at",
+            filter
+        ))
+        .bind(user_id)
+        .bind(&since_str)
+        .bind(&since_str)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let series = sqlx::query_as::<_, Series>(&format!(
+            "SELECT * FROM series {} ORDER BY updated_at",
+            filter
+        ))
+        .bind(user_id)
+        .bind(&since_str)
+        .bind(&since_str)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let collections = sqlx::query_as::<_, Collection>(&format!(
+            "SELECT * FROM collections {} ORDER BY updated_at",
+            filter
+        ))
+        .bind(user_id)
+        .bind(&since_str)
+        .bind(&since_str)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let deletions = match since {
+            Some(since) => {
+                TombstoneService::new(self.pool.clone())
+                    .since(user_id, since, None)
+                    .await?
+            }
+            None => Vec::new(),
+        };
+
+        Ok(SyncChanges {
+            movies,
+            series,
+            collections,
+            deletions,
+        })
+    }
+
+    /// Apply a peer's changes. The newer version of an entity wins; entities
+    /// changed on both sides since `since` are reported as conflicts.
+    pub async fn apply(
+        &self,
+        user_id: Uuid,
+        since: Option<DateTime<Utc>>,
+        changes: SyncChanges,
+    ) -> Result<SyncOutcome> {
+        let mut outcome = SyncOutcome::default();
+
+        for tombstone in &changes.deletions {
+            if let Err(e) = self
+                .apply_deletion(user_id, since, tombstone, &mut outcome)
+                .await
+            {
+                outcome
+                    .errors
+                    .push(format!("{}: {}", tombstone.entity_id, e));
+            }
+        }
+
+        let mut records = Vec::new();
+        let mut movies = changes.movies;
+        // Parents first so child links can be kept
+        movies.sort_by_key(|m| !m.is_collection);
+        for movie in &movies {
+            records.push(Record::new(
+                EntityType::Movie,
+                movie.id,
+                movie.updated_at,
+                movie,
+            )?);
+        }
+        for series in &changes.series {
+            records.push(Record::new(
+                EntityType::Series,
+                series.id,
+                series.updated_at,
+                series,
+            )?);
+        }
+        for collection in &changes.collections {
+            records.push(Record::new(
+                EntityType::Collection,
+                collection.id,
+                collection.updated_at,
+                collection,
+            )?);
+        }
+
+        let mut columns = HashMap::new();
+        for entity_type in [
+            EntityType::Movie,
+            EntityType::Series,
+            EntityType::Collection,
+        ] {
+            let table = table_name(entity_type);
+            let table_columns: Vec<(String, String)> =
+                sqlx::query_as("SELECT name, type FROM pragma_table_info(?)")
+                    .bind(table)
+                    .fetch_all(&self.pool)
+                    .await?;
+            columns.insert(table, table_columns);
+        }
+
+        for record in &records {
+            let table_columns = &columns[table_name(record.entity_type)];
+            if let Err(e) = self
+                .apply_record(user_id, since, record, table_columns, &mut outcome)
+                .await
+            {
+                let title = record.title().unwrap_or_else(|| record.id.to_string());
+                outcome.errors.push(format!("{}: {}", title, e));
+            }
+        }
+
+        Ok(outcome)
+    }
+
+    async fn local_updated_at(
+        &self,
+        user_id: Uuid,
+        entity_type: EntityType,
+        id: Uuid,
+    ) -> Result<Option<DateTime<Utc>>> {
+        let query = format!(
+            "SELECT updated_at FROM {} WHERE id = ? AND user_id = ?",
+            table_name(entity_type)
+        );
+        Ok(sqlx::query_scalar(&query)
+            .bind(id)
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?)
+    }
+
+    async fn apply_deletion(
+        &self,
+        user_id: Uuid,
+        since: Option<DateTime<Utc>>,
+        tombstone: &Tombstone,
+        outcome: &mut SyncOutcome,
+    ) -> Result<()> {
+        let entity_type = tombstone.entity_type;
+        let Some(local) = self
+            .local_updated_at(user_id, entity_type, tombstone.entity_id)
+            .await?
+        else {
+            return Ok(());
+        };
+
+        let table = table_name(entity_type);
+        let title: Option<String> =
+            sqlx::query_scalar(&format!("SELECT title FROM {} WHERE id = ?", table))
+                .bind(tombstone.entity_id)
+                .fetch_optional(&self.pool)
+                .await?;
+        let conflict = |winner| SyncConflict {
+            entity_type,
+            id: tombstone.entity_id,
+            title: title.clone(),
+            local_updated_at: Some(local),
+            remote_updated_at: None,
+            deleted: true,
+            winner,
+        };
+
+        // Edited here after it was deleted there: keep it
+        if local > tombstone.deleted_at {
+            outcome.conflicts.push(conflict(SyncWinner::Local));
+            return Ok(());
+        }
+        if since.is_some_and(|since| local > since) {
+            outcome.conflicts.push(conflict(SyncWinner::Remote));
+        }
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(&format!(
+            "DELETE FROM {} WHERE id = ? AND user_id = ?",
+            table
+        ))
+        .bind(tombstone.entity_id)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+        record_deletions(&mut tx, user_id, entity_type, &[tombstone.entity_id]).await?;
+        tx.commit().await?;
+
+        outcome.deleted += 1;
+        Ok(())
+    }
+
+    async fn apply_record(
+        &self,
+        user_id: Uuid,
+        since: Option<DateTime<Utc>>,
+        record: &Record,
+        columns: &[(String, String)],
+        outcome: &mut SyncOutcome,
+    ) -> Result<()> {
+        let conflict = |local_updated_at, deleted, winner| SyncConflict {
+            entity_type: record.entity_type,
+            id: record.id,
+            title: record.title(),
+            local_updated_at,
+            remote_updated_at: Some(record.updated_at),
+            deleted,
+            winner,
+        };
+
+        match self
+            .local_updated_at(user_id, record.entity_type, record.id)
+            .await?
+        {
+            Some(local) => {
+                if local == record.updated_at {
+                    return Ok(());
+                }
+                let winner = if record.updated_at > local {
+                    SyncWinner::Remote
+                } else {
+                    SyncWinner::Local
+                };
+                if since.is_some_and(|since| local > since) {
+                    outcome.conflicts.push(conflict(Some(local), false, winner));
+                }
+                if winner == SyncWinner::Remote {
+                    self.upsert(user_id, record, columns).await?;
+                    outcome.updated += 1;
+                }
+            }
+            None => {
+                let deleted_at: Option<DateTime<Utc>> = sqlx::query_scalar(
+                    "SELECT MAX(deleted_at) FROM deleted_entities WHERE user_id = ? AND entity_id = ?",
+                )
+                .bind(user_id)
+                .bind(record.id)
+                .fetch_one(&self.pool)
+                .await?;
+
+                if let Some(deleted_at) = deleted_at {
+                    // Deleted here after it was last edited there: stays deleted
+                    if deleted_at >= record.updated_at {
+                        outcome
+                            .conflicts
+                            .push(conflict(None, true, SyncWinner::Local));
+                        return Ok(());
+                    }
+                    if since.is_some_and(|since| deleted_at > since) {
+                        outcome
+                            .conflicts
+                            .push(conflict(None, true, SyncWinner::Remote));
+                    }
+                }
+
+                self.upsert(user_id, record, columns).await?;
+                outcome.created += 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Insert or update a row from the columns both servers know, keeping the
+    /// peer's `updated_at`
+    async fn upsert(
+        &self,
+        user_id: Uuid,
+        record: &Record,
+        columns: &[(String, String)],
+    ) -> Result<()> {
+        let table = table_name(record.entity_type);
+        let mut names = Vec::new();
+        let mut binds = Vec::new();
+
+        for (name, declared_type) in columns {
+            if SKIPPED_COLUMNS.contains(&name.as_str()) {
+                continue;
+            }
+            let bind = if USER_COLUMNS.contains(&name.as_str()) {
+                Bind::Uuid(user_id)
+            } else {
+                match record.column(name) {
+                    Some(value) => Bind::from_json(value, declared_type)?,
+                    None => continue,
+                }
+            };
+            names.push(name.as_str());
+            binds.push(bind);
+        }
+
+        // Drop links to box sets that don't exist here
+        if let Some(pos) = names.iter().position(|n| *n == "parent_collection_id")
+            && let Bind::Uuid(parent_id) = binds[pos]
+        {
+            let exists: bool = sqlx::query_scalar(
+                "SELECT EXISTS(SELECT 1 FROM movies WHERE id = ? AND user_id = ?)",
+            )
+            .bind(parent_id)
+            .bind(user_id)
+            .fetch_one(&self.pool)
+            .await?;
+            if !exists {
+                binds[pos] = Bind::Null;
+            }
+        }
+
+        let updates = names
+            .iter()
+            .filter(|n| !matches!(**n, "id" | "user_id"))
+            .map(|n| format!("{0} = excluded.{0}", n))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let query = format!(
+            "INSERT INTO {table} ({}) VALUES ({}) \
+             ON CONFLICT(id) DO UPDATE SET {updates} WHERE {table}.user_id = excluded.user_id",
+            names.join(", "),
+            vec!["?"; names.len()].join(", "),
+        );
+
+        let mut q = sqlx::query(&query);
+        for bind in binds {
+            q = match bind {
+                Bind::Null => q.bind(None::<String>),
+                Bind::Bool(b) => q.bind(b),
+                Bind::Int(i) => q.bind(i),
+                Bind::Real(f) => q.bind(f),
+                Bind::Text(s) => q.bind(s),
+                Bind::Uuid(id) => q.bind(id),
+            };
+        }
+
+        let result = q.execute(&self.pool).await?;
+        if result.rows_affected() == 0 {
+            // The ID belongs to another user on this server
+            return Err(Error::Duplicate(record.id.to_string()));
+        }
+        Ok(())
+    }
+
+    pub async fn list_peers(&self, user_id: Uuid) -> Result<Vec<SyncPeerPublic>> {
+        let peers = sqlx::query_as::<_, SyncPeer>(
+            "SELECT * FROM sync_peers WHERE user_id = ? ORDER BY created_at",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(peers.into_iter().map(Into::into).collect())
+    }
+
+    /// Peers of all admins, for the periodic sync. Only admins manage peers;
+    /// those of users who aren't (any more) are left alone.
+    pub async fn all_peers(&self) -> Result<Vec<SyncPeer>> {
+        Ok(sqlx::query_as::<_, SyncPeer>(
+            r#"
+            SELECT p.* FROM sync_peers p JOIN users u ON u.id = p.user_id
+            WHERE u.role = 'admin'
+            ORDER BY p.created_at
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    pub async fn get_peer(&self, user_id: Uuid, id: Uuid) -> Result<SyncPeer> {
+        sqlx::query_as::<_, SyncPeer>("SELECT * FROM sync_peers WHERE id = ? AND user_id = ?")
+            .bind(id)
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or(Error::NotFound)
+    }
+
+    pub async fn create_peer(
+        &self,
+        user_id: Uuid,
+        input: CreateSyncPeer,
+    ) -> Result<SyncPeerPublic> {
+        let name = input.name.trim();
+        let url = input.url.trim().trim_end_matches('/');
+        let token = input.token.trim();
+        if name.is_empty() {
+            return Err(Error::Validation("Name is required".to_string()));
+        }
+        if !(url.starts_with("http://") || url.starts_with("https://")) {
+            return Err(Error::Validation(
+                "URL must start with http:// or https://".to_string(),
+            ));
+        }
+        if token.is_empty() {
+            return Err(Error::Validation("Pairing token is required".to_string()));
+        }
+
+        let id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO sync_peers (id, user_id, name, url, token, created_at) VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(name)
+        .bind(url)
+        .bind(self.tokens.encrypt(token)?)
+        .bind(Utc::now().to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(self.get_peer(user_id, id).await?.into())
+    }
+
+    pub async fn delete_peer(&self, user_id: Uuid, id: Uuid) -> Result<()> {
+        let result = sqlx::query("DELETE FROM sync_peers WHERE id = ? AND user_id = ?")
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(Error::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Store the result of a run. On success `synced_at` becomes the new
+    /// starting point; on failure the next run retries from the old one.
+    pub async fn record_run(
+        &self,
+        id: Uuid,
+        synced_at: DateTime<Utc>,
+        result: std::result::Result<&SyncOutcome, String>,
+    ) -> Result<()> {
+        match result {
+            Ok(outcome) => {
+                let json =
+                    serde_json::to_string(outcome).map_err(|e| Error::Internal(e.to_string()))?;
+                sqlx::query(
+                    "UPDATE sync_peers SET last_synced_at = ?, last_result = ?, last_error = NULL WHERE id = ?",
+                )
+                .bind(synced_at.to_rfc3339())
+                .bind(json)
+                .bind(id)
+                .execute(&self.pool)
+                .await?;
+            }
+            Err(error) => {
+                sqlx::query("UPDATE sync_peers SET last_error = ? WHERE id = ?")
+                    .bind(error)
+                    .bind(id)
+                    .execute(&self.pool)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{CreateMovie, UpdateMovie};
+    use crate::services::MovieService;
+    use crate::test_helpers::{create_test_db_with_users, fixtures};
+    use chrono::Duration;
+
+    fn cipher() -> TokenCipher {
+        TokenCipher::new(b"test-secret")
+    }
+
+    async fn create_movie(service: &MovieService, user_id: Uuid, title: &str) -> Movie {
+        service
+            .create(
+                user_id,
+                CreateMovie {
+                    barcode: None,
+                    tmdb_id: None,
+                    title: title.to_string(),
+                    original_title: None,
+                    disc_type: None,
+                    production_year: None,
+                },
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_two_instances_converge() {
+        // Two separate databases stand in for the two servers
+        let home = SyncService::new(create_test_db_with_users().await, cipher());
+        let vps = SyncService::new(create_test_db_with_users().await, cipher());
+        let home_movies = MovieService::new(home.pool.clone());
+        let vps_movies = MovieService::new(vps.pool.clone());
+        let user_id = fixtures::test_user_id();
+
+        let alien = create_movie(&home_movies, user_id, "Alien").await;
+        let heat = create_movie(&home_movies, user_id, "Heat").await;
+
+        // First sync copies everything, keeping IDs and timestamps
+        let outcome = vps
+            .apply(
+                user_id,
+                None,
+                home.changes_since(user_id, None).await.unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(outcome.created, 2);
+        assert!(outcome.errors.is_empty(), "{:?}", outcome.errors);
+        let copy = vps_movies.get_by_id(user_id, alien.id).await.unwrap();
+        assert_eq!(copy.title, "Alien");
+        assert_eq!(copy.updated_at, alien.updated_at);
+
+        // Applying the same changes again does nothing
+        let again = vps
+            .apply(
+                user_id,
+                None,
+                home.changes_since(user_id, None).await.unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!((again.created, again.updated), (0, 0));
+
+        let since = Utc::now();
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+
+        // Both sides edit Alien, home deletes Heat
+        vps_movies
+            .update(
+                user_id,
+                alien.id,
+                UpdateMovie {
+                    watched: Some(true),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        home_movies
+            .update(
+                user_id,
+                alien.id,
+                UpdateMovie {
+                    location: Some("Shelf 2".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        home_movies.delete(user_id, heat.id).await.unwrap();
+
+        let outcome = vps
+            .apply(
+                user_id,
+                Some(since),
+                home.changes_since(user_id, Some(since)).await.unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!((outcome.updated, outcome.deleted), (1, 1));
+        assert_eq!(outcome.conflicts.len(), 1);
+        assert_eq!(outcome.conflicts[0].id, alien.id);
+        // Home edited last and wins
+        assert_eq!(outcome.conflicts[0].winner, SyncWinner::Remote);
+
+        let copy = vps_movies.get_by_id(user_id, alien.id).await.unwrap();
+        assert_eq!(copy.location.as_deref(), Some("Shelf 2"));
+        assert!(vps_movies.get_by_id(user_id, heat.id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_edit_after_remote_delete_is_kept() {
+        let pool = create_test_db_with_users().await;
+        let service = SyncService::new(pool.clone(), cipher());
+        let movies = MovieService::new(pool);
+        let user_id = fixtures::test_user_id();
+
+        let movie = create_movie(&movies, user_id, "Dune").await;
+        let changes = SyncChanges {
+            deletions: vec![Tombstone {
+                entity_type: EntityType::Movie,
+                entity_id: movie.id,
+                deleted_at: movie.updated_at - Duration::hours(1),
+            }],
+            ..Default::default()
+        };
+
+        let outcome = service
+            .apply(user_id, Some(movie.updated_at - Duration::days(1)), changes)
+            .await
+            .unwrap();
+        assert_eq!(outcome.deleted, 0);
+        assert_eq!(outcome.conflicts.len(), 1);
+        assert!(outcome.conflicts[0].deleted);
+        assert_eq!(outcome.conflicts[0].winner, SyncWinner::Local);
+        assert!(movies.get_by_id(user_id, movie.id).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_peer_crud() {
+        let service = SyncService::new(create_test_db_with_users().await, cipher());
+        let user_id = fixtures::test_user_id();
+
+        let invalid = service
+            .create_peer(
+                user_id,
+                CreateSyncPeer {
+                    name: "VPS".to_string(),
+                    url: "movies.example.com".to_string(),
+                    token: "mm_abc".to_string(),
+                },
+            )
+            .await;
+        assert!(matches!(invalid, Err(Error::Validation(_))));
+
+        let peer = service
+            .create_peer(
+                user_id,
+                CreateSyncPeer {
+                    name: "VPS".to_string(),
+                    url: "https://movies.example.com/".to_string(),
+                    token: "mm_abc".to_string(),
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(peer.url, "https://movies.example.com");
+
+        let now = Utc::now();
+        service
+            .record_run(peer.id, now, Ok(&SyncOutcome::default()))
+            .await
+            .unwrap();
+        let peers = service.list_peers(user_id).await.unwrap();
+        assert_eq!(peers.len(), 1);
+        assert!(peers[0].last_result.is_some());
+        // Peers of users who aren't admins don't sync periodically
+        assert!(service.all_peers().await.unwrap().is_empty());
+        sqlx::query("UPDATE users SET role = 'admin' WHERE id = ?")
+            .bind(user_id)
+            .execute(&service.pool)
+            .await
+            .unwrap();
+        assert_eq!(service.all_peers().await.unwrap().len(), 1);
+        assert!(
+            service
+                .list_peers(fixtures::test_admin_id())
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        assert!(matches!(
+            service
+                .delete_peer(fixtures::test_admin_id(), peer.id)
+                .await,
+            Err(Error::NotFound)
+        ));
+        service.delete_peer(user_id, peer.id).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_peer_tokens_are_encrypted() {
+        let pool = create_test_db_with_users().await;
+        let service = SyncService::new(pool.clone(), cipher());
+        let user_id = fixtures::test_user_id();
+        let created = service
+            .create_peer(
+                user_id,
+                CreateSyncPeer {
+                    name: "VPS".to_string(),
+                    url: "https://movies.example.com".to_string(),
+                    token: "mm_abc".to_string(),
+                },
+            )
+            .await
+            .unwrap();
+
+        let peer = service.get_peer(user_id, created.id).await.unwrap();
+        assert!(!peer.token.contains("mm_abc"));
+        assert_eq!(service.peer_token(&peer).unwrap(), "mm_abc");
+
+        // Tokens from before encryption and of a previous secret are
+        // encrypted with the current key at startup
+        sqlx::query("UPDATE sync_peers SET token = 'mm_plain' WHERE id = ?")
+            .bind(peer.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert_eq!(service.encrypt_stored_tokens().await.unwrap(), 1);
+        let peer = service.get_peer(user_id, peer.id).await.unwrap();
+        assert!(peer.token.starts_with(ENCRYPTED_PREFIX));
+        assert_eq!(service.encrypt_stored_tokens().await.unwrap(), 0);
+
+        let rotated = SyncService::new(
+            pool.clone(),
+            TokenCipher::new(b"new-secret").with_previous_secret(b"test-secret"),
+        );
+        assert_eq!(rotated.peer_token(&peer).unwrap(), "mm_plain");
+        assert_eq!(rotated.encrypt_stored_tokens().await.unwrap(), 1);
+
+        // Without the secret the token can't be read
+        let other = SyncService::new(pool, TokenCipher::new(b"other-secret"));
+        assert!(matches!(
+            other.peer_token(&peer),
+            Err(Error::Configuration(_))
+        ));
+    }
+}
@@ -1,13 +1,164 @@
+use crate::db::DbPool;
 use crate::error::{Error, Result};
+use crate::models::{SettingKey, TmdbPreferences};
+use crate::parsing::title::titles_match;
+use crate::services::settings::{SettingsSnapshot, SettingsWatch};
+use crate::services::tmdb_cache::{
+    DEFAULT_TTL_HOURS, TmdbCache, TmdbCacheMode, TmdbCacheStats, cache_mode, cache_ttl,
+};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
 use std::sync::RwLock;
 use urlencoding;
 
 const TMDB_BASE_URL: &str = "https://api.themoviedb.org/3";
 
+/// Poster sizes TMDB serves
+pub const POSTER_SIZES: &[&str] = &["w92", "w154", "w185", "w342", "w500", "w780", "original"];
+/// Backdrop sizes TMDB serves
+pub const BACKDROP_SIZES: &[&str] = &["w300", "w780", "w1280", "original"];
+
+const DEFAULT_POSTER_SIZE: &str = "w500";
+const DEFAULT_BACKDROP_SIZE: &str = "w1280";
+const DEFAULT_LANGUAGE: &str = "de-DE";
+
+/// Image sizes and metadata languages for TMDB lookups of one user, see
+/// `TmdbService::options`
+#[derive(Debug, Clone, PartialEq)]
+pub struct TmdbOptions {
+    pub poster_size: String,
+    pub backdrop_size: String,
+    /// Asked in order until one has an overview; never empty
+    pub languages: Vec<String>,
+}
+
+impl Default for TmdbOptions {
+    fn default() -> Self {
+        Self {
+            poster_size: DEFAULT_POSTER_SIZE.to_string(),
+            backdrop_size: DEFAULT_BACKDROP_SIZE.to_string(),
+            languages: vec![DEFAULT_LANGUAGE.to_string()],
+        }
+    }
+}
+
+impl TmdbOptions {
+    /// Language of the first request
+    pub fn language(&self) -> &str {
+        self.languages
+            .first()
+            .map(String::as_str)
+            .unwrap_or(DEFAULT_LANGUAGE)
+    }
+
+    /// Resolve the user's preferences against the server settings: the
+    /// user's language chain replaces the server one, which otherwise follows
+    /// the user's interface language
+    pub fn resolve(
+        preferences: Option<&TmdbPreferences>,
+        user_language: Option<&str>,
+        settings: &SettingsSnapshot,
+    ) -> Self {
+        let size = |own: Option<&String>, key: SettingKey, sizes: &[&str], default: &str| {
+            own.map(String::as_str)
+                .into_iter()
+                .chain(settings.get(key))
+                .find(|size| sizes.contains(size))
+                .unwrap_or(default)
+                .to_string()
+        };
+
+        let chains = match preferences.and_then(|p| p.languages.as_deref()) {
+            Some(own) => vec![own],
+            None => vec![
+                user_language.unwrap_or_default(),
+                settings.get(SettingKey::TmdbLanguages).unwrap_or_default(),
+            ],
+        };
+        let mut languages: Vec<String> = Vec::new();
+        for language in chains
+            .into_iter()
+            .flat_map(|chain| chain.split(','))
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+        {
+            if !languages.iter().any(|l| l.eq_ignore_ascii_case(language)) {
+                languages.push(language.to_string());
+            }
+        }
+        if languages.is_empty() {
+            languages.push(DEFAULT_LANGUAGE.to_string());
+        }
+
+        Self {
+            poster_size: size(
+                preferences.and_then(|p| p.poster_size.as_ref()),
+                SettingKey::TmdbPosterSize,
+                POSTER_SIZES,
+                DEFAULT_POSTER_SIZE,
+            ),
+            backdrop_size: size(
+                preferences.and_then(|p| p.backdrop_size.as_ref()),
+                SettingKey::TmdbBackdropSize,
+                BACKDROP_SIZES,
+                DEFAULT_BACKDROP_SIZE,
+            ),
+            languages,
+        }
+    }
+}
+
+/// One of `sizes`; empty means unset
+pub fn normalize_image_size(
+    value: &str,
+    sizes: &[&str],
+) -> std::result::Result<Option<String>, String> {
+    let value = value.trim().to_lowercase();
+    if value.is_empty() {
+        return Ok(None);
+    }
+    if sizes.contains(&value.as_str()) {
+        Ok(Some(value))
+    } else {
+        Err(format!(
+            "Unknown image size '{}', expected one of {}",
+            value,
+            sizes.join(", ")
+        ))
+    }
+}
+
+/// Canonical form of a language chain such as "de-de, en" ("de-DE,en");
+/// empty means unset
+pub fn normalize_tmdb_languages(value: &str) -> std::result::Result<Option<String>, String> {
+    let mut languages = Vec::new();
+    for language in value.split(',').map(str::trim).filter(|l| !l.is_empty()) {
+        let (code, region) = match language.split_once('-') {
+            Some((code, region)) => (code, Some(region)),
+            None => (language, None),
+        };
+        let valid = |part: &str| part.len() == 2 && part.chars().all(|c| c.is_ascii_alphabetic());
+        if !valid(code) || !region.is_none_or(valid) {
+            return Err(format!("'{}' is not a language like de or de-DE", language));
+        }
+        languages.push(match region {
+            Some(region) => format!("{}-{}", code.to_lowercase(), region.to_uppercase()),
+            None => code.to_lowercase(),
+        });
+    }
+    Ok((!languages.is_empty()).then(|| languages.join(",")))
+}
+
+fn is_blank(text: &Option<String>) -> bool {
+    text.as_deref().is_none_or(|t| t.trim().is_empty())
+}
+
 pub struct TmdbService {
     client: reqwest::Client,
     api_key: RwLock<String>,
+    settings: Option<SettingsWatch>,
+    cache: TmdbCache,
 }
 
 #[derive(Debug, Deserialize)]
@@ -32,6 +183,39 @@ pub struct TmdbMovie {
     pub genre_ids: Option<Vec<i32>>,
 }
 
+impl TmdbMovie {
+    /// How well this result fits a title and year: 2 for the same title or
+    /// original title (ignoring accents and how numbers are written), 1 for
+    /// the same release year
+    pub fn match_score(&self, title: &str, year: Option<i32>) -> u8 {
+        let same_title = titles_match(&self.title, title)
+            || self
+                .original_title
+                .as_deref()
+                .is_some_and(|original| titles_match(original, title));
+        let release_year = self
+            .release_date
+            .as_deref()
+            .and_then(|date| date.get(..4)?.parse::<i32>().ok());
+        let same_year = year.is_some() && release_year == year;
+        2 * u8::from(same_title) + u8::from(same_year)
+    }
+}
+
+/// The search result fitting a title and year best; TMDB's order decides
+/// between equally good ones
+pub fn best_match<'a>(
+    results: &'a [TmdbMovie],
+    title: &str,
+    year: Option<i32>,
+) -> Option<&'a TmdbMovie> {
+    results
+        .iter()
+        .enumerate()
+        .max_by_key(|(i, result)| (result.match_score(title, year), Reverse(*i)))
+        .map(|(_, result)| result)
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct TmdbMovieDetails {
     pub id: i64,
@@ -100,6 +284,39 @@ pub struct TmdbCrew {
     pub department: String,
 }
 
+/// Posters and backdrops of a movie, best rated first
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TmdbImages {
+    #[serde(default)]
+    pub posters: Vec<TmdbImage>,
+    #[serde(default)]
+    pub backdrops: Vec<TmdbImage>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TmdbImage {
+    /// Path for `TmdbService::poster_url`
+    pub file_path: String,
+    pub width: i32,
+    pub height: i32,
+    /// Language of the text on the image; `None` for images without text
+    pub iso_639_1: Option<String>,
+    pub vote_average: Option<f64>,
+}
+
+/// Whether `path` looks like an image path TMDB hands out ("/abc123.jpg"),
+/// so it can't point anywhere else when appended to the image host
+pub fn is_image_path(path: &str) -> bool {
+    let Some((name, extension)) = path.strip_prefix('/').and_then(|p| p.rsplit_once('.')) else {
+        return false;
+    };
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        && matches!(extension, "jpg" | "png")
+}
+
 // Collection types
 #[derive(Debug, Deserialize, Serialize)]
 pub struct TmdbCollectionSearchResult {
@@ -151,6 +368,7 @@ pub struct TmdbTvDetails {
     pub tagline: Option<String>,
     pub overview: Option<String>,
     pub poster_path: Option<String>,
+    pub backdrop_path: Option<String>,
     pub first_air_date: Option<String>,
     pub last_air_date: Option<String>,
     pub number_of_episodes: Option<i32>,
@@ -179,27 +397,157 @@ impl TmdbService {
         Self {
             client: reqwest::Client::new(),
             api_key: RwLock::new(api_key),
+            settings: None,
+            cache: TmdbCache::new(None),
         }
     }
 
-    /// Update the API key at runtime (e.g., when settings are changed)
+    /// Take the API key from the `tmdb_api_key` setting on every call; the
+    /// key given to `new` is used while the setting is empty
+    pub fn with_settings(mut self, settings: SettingsWatch) -> Self {
+        self.settings = Some(settings);
+        self
+    }
+
+    /// Keep responses in the `tmdb_cache` table when the setting is `sqlite`
+    pub fn with_cache_pool(mut self, pool: DbPool) -> Self {
+        self.cache = TmdbCache::new(Some(pool));
+        self
+    }
+
+    /// Update the fallback API key at runtime
     pub fn set_api_key(&self, api_key: String) {
         if let Ok(mut key) = self.api_key.write() {
             *key = api_key;
         }
     }
 
+    fn current_api_key(&self) -> String {
+        if let Some(settings) = &self.settings
+            && let Some(key) = settings.borrow().get(SettingKey::TmdbApiKey)
+        {
+            return key.to_string();
+        }
+        self.api_key.read().map(|k| k.clone()).unwrap_or_default()
+    }
+
+    pub fn is_configured(&self) -> bool {
+        !self.current_api_key().is_empty()
+    }
+
     /// Get current API key, returns error if not configured
     fn get_api_key(&self) -> Result<String> {
-        let key = self.api_key.read().map(|k| k.clone()).unwrap_or_default();
+        let key = self.current_api_key();
         if key.is_empty() {
-            return Err(Error::ExternalApi(
-                "TMDB API key not configured. Please set it in Settings.".to_string(),
-            ));
+            return Err(Error::TmdbNotConfigured);
         }
         Ok(key)
     }
 
+    /// Cache mode and TTL from the settings; invalid values fall back to the defaults
+    fn cache_settings(&self) -> (TmdbCacheMode, chrono::Duration) {
+        let snapshot = self
+            .settings
+            .as_ref()
+            .map(|s| s.borrow().clone())
+            .unwrap_or_default();
+        (
+            cache_mode(snapshot.get(SettingKey::TmdbCache)).unwrap_or(TmdbCacheMode::Memory),
+            cache_ttl(snapshot.get(SettingKey::TmdbCacheTtlHours))
+                .unwrap_or(chrono::Duration::hours(DEFAULT_TTL_HOURS)),
+        )
+    }
+
+    pub async fn cache_stats(&self) -> Result<TmdbCacheStats> {
+        let (mode, ttl) = self.cache_settings();
+        self.cache.stats(mode, ttl).await
+    }
+
+    /// Forget all cached responses; returns how many were removed
+    pub async fn clear_cache(&self) -> Result<u64> {
+        self.cache.clear().await
+    }
+
+    /// Remove responses older than the configured TTL
+    pub async fn purge_expired_cache(&self) -> Result<u64> {
+        let (_, ttl) = self.cache_settings();
+        self.cache.purge_expired(ttl).await
+    }
+
+    /// GET `path` with `query` (without API key) and return the raw JSON.
+    /// Successful responses are cached by endpoint, query and language.
+    async fn fetch(&self, path: &str, query: &str, language: Option<&str>) -> Result<String> {
+        let api_key = self.get_api_key()?;
+        let (mode, ttl) = self.cache_settings();
+        let key = format!("{}?{}|{}", path, query, language.unwrap_or_default());
+        if let Some(body) = self.cache.get(mode, ttl, &key).await {
+            return Ok(body);
+        }
+
+        let mut url = format!("{}/{}?api_key={}", TMDB_BASE_URL, path, api_key);
+        if !query.is_empty() {
+            url.push('&');
+            url.push_str(query);
+        }
+        if let Some(language) = language {
+            url.push_str(&format!("&language={}", language));
+        }
+
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| Error::ExternalApi(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(Error::ExternalApi(format!(
+                "TMDB API error: {}",
+                response.status()
+            )));
+        }
+
+        let body = response
+            .text()
+            .await
+            .map_err(|e| Error::ExternalApi(e.to_string()))?;
+        self.cache.put(mode, &key, &body).await;
+        Ok(body)
+    }
+
+    async fn fetch_json<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &str,
+        language: Option<&str>,
+    ) -> Result<T> {
+        let body = self.fetch(path, query, language).await?;
+        serde_json::from_str(&body).map_err(|e| Error::ExternalApi(e.to_string()))
+    }
+
+    /// Check the API key against TMDB, bypassing the cache
+    pub async fn check_api_key(&self) -> Result<()> {
+        let api_key = self.get_api_key()?;
+        let response = self
+            .client
+            .get(format!(
+                "{}/configuration?api_key={}",
+                TMDB_BASE_URL, api_key
+            ))
+            .send()
+            .await
+            .map_err(|e| Error::ExternalApi(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(Error::ExternalApi(format!(
+                "TMDB API error: {}",
+                response.status()
+            )));
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
     pub async fn search_movies(
         &self,
         query: &str,
@@ -213,6 +561,7 @@ impl TmdbService {
 
     /// Search for movies with pagination support
     /// max_pages limits how many pages to fetch (each page has ~20 results)
+    #[tracing::instrument(skip(self))]
     pub async fn search_movies_paginated(
         &self,
         query: &str,
@@ -222,42 +571,22 @@ impl TmdbService {
         max_pages: u32,
     ) -> Result<Vec<TmdbMovie>> {
         let lang = language.unwrap_or("de-DE");
-        let api_key = self.get_api_key()?;
         let mut all_results = Vec::new();
 
         for page in 1..=max_pages {
-            let mut url = format!(
-                "{}/search/movie?api_key={}&query={}&language={}&include_adult={}&page={}",
-                TMDB_BASE_URL,
-                api_key,
+            let mut params = format!(
+                "query={}&include_adult={}&page={}",
                 urlencoding::encode(query),
-                lang,
                 include_adult,
                 page
             );
 
             if let Some(y) = year {
-                url.push_str(&format!("&year={}", y));
-            }
-
-            let response = self
-                .client
-                .get(&url)
-                .send()
-                .await
-                .map_err(|e| Error::ExternalApi(e.to_string()))?;
-
-            if !response.status().is_success() {
-                return Err(Error::ExternalApi(format!(
-                    "TMDB API error: {}",
-                    response.status()
-                )));
+                params.push_str(&format!("&year={}", y));
             }
 
-            let result: TmdbSearchResult = response
-                .json()
-                .await
-                .map_err(|e| Error::ExternalApi(e.to_string()))?;
+            let result: TmdbSearchResult =
+                self.fetch_json("search/movie", &params, Some(lang)).await?;
 
             let results_count = result.results.len();
             all_results.extend(result.results);
@@ -272,243 +601,203 @@ impl TmdbService {
     }
 
     /// Find a movie by external ID (e.g., IMDB ID)
+    #[tracing::instrument(skip(self))]
     pub async fn find_by_imdb_id(&self, imdb_id: &str) -> Result<Option<TmdbMovie>> {
-        let api_key = self.get_api_key()?;
-        let url = format!(
-            "{}/find/{}?api_key={}&external_source=imdb_id",
-            TMDB_BASE_URL, imdb_id, api_key
-        );
-
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| Error::ExternalApi(e.to_string()))?;
-
-        if !response.status().is_success() {
-            return Err(Error::ExternalApi(format!(
-                "TMDB API error: {}",
-                response.status()
-            )));
-        }
-
         #[derive(Debug, Deserialize)]
         struct FindResult {
             movie_results: Vec<TmdbMovie>,
         }
 
-        let result: FindResult = response
-            .json()
-            .await
-            .map_err(|e| Error::ExternalApi(e.to_string()))?;
+        let result: FindResult = self
+            .fetch_json(
+                &format!("find/{}", urlencoding::encode(imdb_id)),
+                "external_source=imdb_id",
+                None,
+            )
+            .await?;
 
         Ok(result.movie_results.into_iter().next())
     }
 
+    #[tracing::instrument(skip(self))]
     pub async fn get_movie_details(
         &self,
         tmdb_id: i64,
         language: Option<&str>,
     ) -> Result<TmdbMovieDetails> {
         let lang = language.unwrap_or("de-DE");
-        let api_key = self.get_api_key()?;
-        let url = format!(
-            "{}/movie/{}?api_key={}&language={}",
-            TMDB_BASE_URL, tmdb_id, api_key, lang
-        );
-
-        let response = self
-            .client
-            .get(&url)
-            .send()
+        self.fetch_json(&format!("movie/{}", tmdb_id), "", Some(lang))
             .await
-            .map_err(|e| Error::ExternalApi(e.to_string()))?;
+    }
 
-        if !response.status().is_success() {
-            return Err(Error::ExternalApi(format!(
-                "TMDB API error: {}",
-                response.status()
-            )));
+    /// All posters and backdrops of a movie in the given language, English
+    /// and without text
+    #[tracing::instrument(skip(self))]
+    pub async fn get_movie_images(
+        &self,
+        tmdb_id: i64,
+        language: Option<&str>,
+    ) -> Result<TmdbImages> {
+        let lang = language
+            .and_then(|l| l.split('-').next())
+            .filter(|l| !l.is_empty())
+            .unwrap_or("de");
+
+        let mut images: TmdbImages = self
+            .fetch_json(
+                &format!("movie/{}/images", tmdb_id),
+                &format!("include_image_language={},en,null", lang),
+                None,
+            )
+            .await?;
+        for list in [&mut images.posters, &mut images.backdrops] {
+            list.sort_by(|a, b| {
+                b.vote_average
+                    .unwrap_or(0.0)
+                    .total_cmp(&a.vote_average.unwrap_or(0.0))
+            });
         }
-
-        response
-            .json()
-            .await
-            .map_err(|e| Error::ExternalApi(e.to_string()))
+        Ok(images)
     }
 
+    #[tracing::instrument(skip(self))]
     pub async fn get_movie_credits(
         &self,
         tmdb_id: i64,
         language: Option<&str>,
     ) -> Result<TmdbCredits> {
         let lang = language.unwrap_or("de-DE");
-        let api_key = self.get_api_key()?;
-        let url = format!(
-            "{}/movie/{}/credits?api_key={}&language={}",
-            TMDB_BASE_URL, tmdb_id, api_key, lang
-        );
-
-        let response = self
-            .client
-            .get(&url)
-            .send()
+        self.fetch_json(&format!("movie/{}/credits", tmdb_id), "", Some(lang))
             .await
-            .map_err(|e| Error::ExternalApi(e.to_string()))?;
-
-        response
-            .json()
-            .await
-            .map_err(|e| Error::ExternalApi(e.to_string()))
     }
 
+    #[tracing::instrument(skip(self))]
     pub async fn search_tv(&self, query: &str, language: Option<&str>) -> Result<Vec<TmdbTvShow>> {
         let lang = language.unwrap_or("de-DE");
-        let api_key = self.get_api_key()?;
-        let url = format!(
-            "{}/search/tv?api_key={}&query={}&language={}",
-            TMDB_BASE_URL,
-            api_key,
-            urlencoding::encode(query),
-            lang
-        );
-
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| Error::ExternalApi(e.to_string()))?;
+        let params = format!("query={}", urlencoding::encode(query));
 
-        let result: TmdbTvSearchResult = response
-            .json()
-            .await
-            .map_err(|e| Error::ExternalApi(e.to_string()))?;
+        let result: TmdbTvSearchResult = self.fetch_json("search/tv", &params, Some(lang)).await?;
 
         Ok(result.results)
     }
 
+    #[tracing::instrument(skip(self))]
     pub async fn get_tv_details(
         &self,
         tmdb_id: i64,
         language: Option<&str>,
     ) -> Result<TmdbTvDetails> {
         let lang = language.unwrap_or("de-DE");
-        let api_key = self.get_api_key()?;
-        let url = format!(
-            "{}/tv/{}?api_key={}&language={}",
-            TMDB_BASE_URL, tmdb_id, api_key, lang
-        );
-
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| Error::ExternalApi(e.to_string()))?;
-
-        response
-            .json()
+        self.fetch_json(&format!("tv/{}", tmdb_id), "", Some(lang))
             .await
-            .map_err(|e| Error::ExternalApi(e.to_string()))
     }
 
     /// Get TV series credits (cast and crew)
+    #[tracing::instrument(skip(self))]
     pub async fn get_tv_credits(
         &self,
         tmdb_id: i64,
         language: Option<&str>,
     ) -> Result<TmdbCredits> {
         let lang = language.unwrap_or("de-DE");
-        let api_key = self.get_api_key()?;
-        let url = format!(
-            "{}/tv/{}/credits?api_key={}&language={}",
-            TMDB_BASE_URL, tmdb_id, api_key, lang
-        );
-
-        let response = self
-            .client
-            .get(&url)
-            .send()
+        self.fetch_json(&format!("tv/{}/credits", tmdb_id), "", Some(lang))
             .await
-            .map_err(|e| Error::ExternalApi(e.to_string()))?;
-
-        response
-            .json()
-            .await
-            .map_err(|e| Error::ExternalApi(e.to_string()))
     }
 
     /// Search for collections (e.g., "Alien Collection")
+    #[tracing::instrument(skip(self))]
     pub async fn search_collections(
         &self,
         query: &str,
         language: Option<&str>,
     ) -> Result<Vec<TmdbCollectionOverview>> {
         let lang = language.unwrap_or("de-DE");
-        let api_key = self.get_api_key()?;
-        let url = format!(
-            "{}/search/collection?api_key={}&query={}&language={}",
-            TMDB_BASE_URL,
-            api_key,
-            urlencoding::encode(query),
-            lang
-        );
-
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| Error::ExternalApi(e.to_string()))?;
+        let params = format!("query={}", urlencoding::encode(query));
 
-        if !response.status().is_success() {
-            return Err(Error::ExternalApi(format!(
-                "TMDB API error: {}",
-                response.status()
-            )));
-        }
-
-        let result: TmdbCollectionSearchResult = response
-            .json()
-            .await
-            .map_err(|e| Error::ExternalApi(e.to_string()))?;
+        let result: TmdbCollectionSearchResult = self
+            .fetch_json("search/collection", &params, Some(lang))
+            .await?;
 
         Ok(result.results)
     }
 
     /// Get collection details with all movies in the collection
+    #[tracing::instrument(skip(self))]
     pub async fn get_collection_details(
         &self,
         collection_id: i64,
         language: Option<&str>,
     ) -> Result<TmdbCollection> {
         let lang = language.unwrap_or("de-DE");
-        let api_key = self.get_api_key()?;
-        let url = format!(
-            "{}/collection/{}?api_key={}&language={}",
-            TMDB_BASE_URL, collection_id, api_key, lang
-        );
-
-        let response = self
-            .client
-            .get(&url)
-            .send()
+        self.fetch_json(&format!("collection/{}", collection_id), "", Some(lang))
             .await
-            .map_err(|e| Error::ExternalApi(e.to_string()))?;
+    }
 
-        if !response.status().is_success() {
-            return Err(Error::ExternalApi(format!(
-                "TMDB API error: {}",
-                response.status()
-            )));
+    /// Image sizes and languages for a user, see `TmdbOptions::resolve`;
+    /// without preferences the server settings alone
+    pub fn options(
+        &self,
+        preferences: Option<&TmdbPreferences>,
+        user_language: Option<&str>,
+    ) -> TmdbOptions {
+        let snapshot = self
+            .settings
+            .as_ref()
+            .map(|s| s.borrow().clone())
+            .unwrap_or_default();
+        TmdbOptions::resolve(preferences, user_language, &snapshot)
+    }
+
+    /// Movie details in the first language of `options`; a missing overview
+    /// (and tagline) is taken from the next language that has one
+    pub async fn get_movie_details_localized(
+        &self,
+        tmdb_id: i64,
+        options: &TmdbOptions,
+    ) -> Result<TmdbMovieDetails> {
+        let mut details = self
+            .get_movie_details(tmdb_id, Some(options.language()))
+            .await?;
+        for language in options.languages.iter().skip(1) {
+            if !is_blank(&details.overview) {
+                break;
+            }
+            if let Ok(fallback) = self.get_movie_details(tmdb_id, Some(language)).await
+                && !is_blank(&fallback.overview)
+            {
+                details.overview = fallback.overview;
+                if is_blank(&details.tagline) {
+                    details.tagline = fallback.tagline;
+                }
+            }
         }
+        Ok(details)
+    }
 
-        response
-            .json()
-            .await
-            .map_err(|e| Error::ExternalApi(e.to_string()))
+    /// TV details with the same overview fallback as
+    /// `get_movie_details_localized`
+    pub async fn get_tv_details_localized(
+        &self,
+        tmdb_id: i64,
+        options: &TmdbOptions,
+    ) -> Result<TmdbTvDetails> {
+        let mut details = self
+            .get_tv_details(tmdb_id, Some(options.language()))
+            .await?;
+        for language in options.languages.iter().skip(1) {
+            if !is_blank(&details.overview) {
+                break;
+            }
+            if let Ok(fallback) = self.get_tv_details(tmdb_id, Some(language)).await
+                && !is_blank(&fallback.overview)
+            {
+                details.overview = fallback.overview;
+                if is_blank(&details.tagline) {
+                    details.tagline = fallback.tagline;
+                }
+            }
+        }
+        Ok(details)
     }
 
     /// Get full poster URL
@@ -516,3 +805,66 @@ impl TmdbService {
         format!("https://image.tmdb.org/t/p/{}{}", size, path)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tmdb_options() {
+        let settings = SettingsSnapshot::default();
+        assert_eq!(
+            TmdbOptions::resolve(None, None, &settings),
+            TmdbOptions::default()
+        );
+
+        let settings = SettingsSnapshot::from_values([
+            (SettingKey::TmdbPosterSize, "w780"),
+            (SettingKey::TmdbBackdropSize, "huge"),
+            (SettingKey::TmdbLanguages, "de-DE,en-US"),
+        ]);
+        let options = TmdbOptions::resolve(None, Some("en-US"), &settings);
+        assert_eq!(options.poster_size, "w780");
+        assert_eq!(options.backdrop_size, "w1280");
+        assert_eq!(options.languages, vec!["en-US", "de-DE"]);
+
+        let preferences = TmdbPreferences {
+            poster_size: Some("original".to_string()),
+            backdrop_size: None,
+            languages: Some("fr-FR".to_string()),
+        };
+        let options = TmdbOptions::resolve(Some(&preferences), Some("en-US"), &settings);
+        assert_eq!(options.poster_size, "original");
+        assert_eq!(options.languages, vec!["fr-FR"]);
+        assert_eq!(options.language(), "fr-FR");
+    }
+
+    #[test]
+    fn test_normalize_tmdb_languages() {
+        assert_eq!(
+            normalize_tmdb_languages(" de-de, EN ,"),
+            Ok(Some("de-DE,en".to_string()))
+        );
+        assert_eq!(normalize_tmdb_languages(""), Ok(None));
+        assert!(normalize_tmdb_languages("german").is_err());
+        assert!(normalize_tmdb_languages("de-DEU").is_err());
+
+        assert_eq!(
+            normalize_image_size("W780", POSTER_SIZES),
+            Ok(Some("w780".to_string()))
+        );
+        assert!(normalize_image_size("w1280", POSTER_SIZES).is_err());
+    }
+
+    #[test]
+    fn test_is_image_path() {
+        assert!(is_image_path("/kqjL17yufvn9OVLyXYpvtyrFfak.jpg"));
+        assert!(is_image_path("/a-b_c.png"));
+        assert!(!is_image_path("kqjL17yufvn9OVLyXYpvtyrFfak.jpg"));
+        assert!(!is_image_path("/.jpg"));
+        assert!(!is_image_path("/../../evil.jpg"));
+        assert!(!is_image_path("/x.jpg?redirect=http://example.com"));
+        assert!(!is_image_path("/poster.gif"));
+        assert!(!is_image_path("@evil.com/x.jpg"));
+    }
+}
@@ -0,0 +1,356 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+use crate::db::DbPool;
+use crate::error::{Error, Result};
+
+/// Responses kept in memory; the least recently used one is dropped beyond this
+pub const MEMORY_CAPACITY: usize = 1000;
+/// How long responses are reused while `tmdb_cache_ttl_hours` is empty
+pub const DEFAULT_TTL_HOURS: i64 = 24;
+
+/// Where TMDB responses are cached, from the `tmdb_cache` setting
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TmdbCacheMode {
+    Off,
+    /// In-process only, lost on restart
+    Memory,
+    /// In memory and in the `tmdb_cache` table, kept across restarts
+    Sqlite,
+}
+
+/// Parse the `tmdb_cache` setting; empty = memory
+pub fn cache_mode(value: Option<&str>) -> Result<TmdbCacheMode> {
+    match value.map(|v| v.trim().to_lowercase()).as_deref() {
+        None | Some("") | Some("memory") | Some("true") | Some("on") => Ok(TmdbCacheMode::Memory),
+        Some("sqlite") | Some("database") => Ok(TmdbCacheMode::Sqlite),
+        Some("off") | Some("false") | Some("none") => Ok(TmdbCacheMode::Off),
+        Some(other) => Err(Error::Validation(format!(
+            "Unknown TMDB cache mode: {} (use memory, sqlite or off)",
+            other
+        ))),
+    }
+}
+
+/// Parse the `tmdb_cache_ttl_hours` setting; empty = 24 hours
+pub fn cache_ttl(value: Option<&str>) -> Result<Duration> {
+    let Some(value) = value.map(str::trim).filter(|v| !v.is_empty()) else {
+        return Ok(Duration::hours(DEFAULT_TTL_HOURS));
+    };
+    match value.parse::<i64>() {
+        Ok(hours) if (1..=24 * 365).contains(&hours) => Ok(Duration::hours(hours)),
+        _ => Err(Error::Validation(format!(
+            "TMDB cache TTL must be a number of hours between 1 and 8760, got {}",
+            value
+        ))),
+    }
+}
+
+/// Counters since startup (or the last clear) and current size
+#[derive(Debug, Clone, Serialize)]
+pub struct TmdbCacheStats {
+    pub mode: TmdbCacheMode,
+    pub ttl_hours: i64,
+    pub memory_entries: usize,
+    pub database_entries: i64,
+    /// Requests answered from memory
+    pub hits: u64,
+    /// Requests answered from the database
+    pub database_hits: u64,
+    /// Requests that went to TMDB
+    pub misses: u64,
+}
+
+struct MemoryEntry {
+    body: String,
+    fetched_at: DateTime<Utc>,
+    /// Value of `Lru::clock` at the last use
+    used: u64,
+}
+
+#[derive(Default)]
+struct Lru {
+    entries: HashMap<String, MemoryEntry>,
+    clock: u64,
+}
+
+/// Successful TMDB responses (raw JSON) keyed by endpoint, query and
+/// language. The mode and TTL are passed on every call, so changed settings
+/// apply right away; without a pool, `sqlite` behaves like `memory`.
+pub struct TmdbCache {
+    pool: Option<DbPool>,
+    memory: Mutex<Lru>,
+    hits: AtomicU64,
+    database_hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl TmdbCache {
+    pub fn new(pool: Option<DbPool>) -> Self {
+        Self {
+            pool,
+            memory: Mutex::new(Lru::default()),
+            hits: AtomicU64::new(0),
+            database_hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    fn database(&self, mode: TmdbCacheMode) -> Option<&DbPool> {
+        self.pool.as_ref().filter(|_| mode == TmdbCacheMode::Sqlite)
+    }
+
+    /// A cached response younger than `ttl`; counts a hit or a miss
+    pub async fn get(&self, mode: TmdbCacheMode, ttl: Duration, key: &str) -> Option<String> {
+        if mode == TmdbCacheMode::Off {
+            return None;
+        }
+        let oldest = Utc::now() - ttl;
+
+        if let Ok(mut lru) = self.memory.lock() {
+            lru.clock += 1;
+            let clock = lru.clock;
+            if let Some(entry) = lru.entries.get_mut(key)
+                && entry.fetched_at > oldest
+            {
+                entry.used = clock;
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                return Some(entry.body.clone());
+            }
+        }
+
+        if let Some(pool) = self.database(mode) {
+            let row = sqlx::query_as::<_, (String, DateTime<Utc>)>(
+                "SELECT body, fetched_at FROM tmdb_cache \
+                 WHERE key = ? AND julianday(fetched_at) > julianday(?)",
+            )
+            .bind(key)
+            .bind(oldest.to_rfc3339())
+            .fetch_optional(pool)
+            .await;
+            match row {
+                Ok(Some((body, fetched_at))) => {
+                    self.remember(key, &body, fetched_at);
+                    self.database_hits.fetch_add(1, Ordering::Relaxed);
+                    return Some(body);
+                }
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Failed to read TMDB cache: {}", e),
+            }
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        None
+    }
+
+    /// Store a response just fetched from TMDB
+    pub async fn put(&self, mode: TmdbCacheMode, key: &str, body: &str) {
+        if mode == TmdbCacheMode::Off {
+            return;
+        }
+        let now = Utc::now();
+        self.remember(key, body, now);
+
+        if let Some(pool) = self.database(mode)
+            && let Err(e) = sqlx::query(
+                "INSERT INTO tmdb_cache (key, body, fetched_at) VALUES (?, ?, ?)
+                 ON CONFLICT(key) DO UPDATE SET body = excluded.body, fetched_at = excluded.fetched_at",
+            )
+            .bind(key)
+            .bind(body)
+            .bind(now.to_rfc3339())
+            .execute(pool)
+            .await
+        {
+            tracing::warn!("Failed to write TMDB cache: {}", e);
+        }
+    }
+
+    fn remember(&self, key: &str, body: &str, fetched_at: DateTime<Utc>) {
+        let Ok(mut lru) = self.memory.lock() else {
+            return;
+        };
+        lru.clock += 1;
+        let used = lru.clock;
+        if !lru.entries.contains_key(key)
+            && lru.entries.len() >= MEMORY_CAPACITY
+            && let Some(oldest) = lru
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.used)
+                .map(|(key, _)| key.clone())
+        {
+            lru.entries.remove(&oldest);
+        }
+        lru.entries.insert(
+            key.to_string(),
+            MemoryEntry {
+                body: body.to_string(),
+                fetched_at,
+                used,
+            },
+        );
+    }
+
+    pub async fn stats(&self, mode: TmdbCacheMode, ttl: Duration) -> Result<TmdbCacheStats> {
+        let database_entries = match &self.pool {
+            Some(pool) => {
+                sqlx::query_scalar("SELECT COUNT(*) FROM tmdb_cache")
+                    .fetch_one(pool)
+                    .await?
+            }
+            None => 0,
+        };
+        Ok(TmdbCacheStats {
+            mode,
+            ttl_hours: ttl.num_hours(),
+            memory_entries: self.memory.lock().map(|lru| lru.entries.len()).unwrap_or(0),
+            database_entries,
+            hits: self.hits.load(Ordering::Relaxed),
+            database_hits: self.database_hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        })
+    }
+
+    /// Forget all responses and reset the counters; returns the number of
+    /// entries removed (memory and database)
+    pub async fn clear(&self) -> Result<u64> {
+        let mut removed = match self.memory.lock() {
+            Ok(mut lru) => {
+                let count = lru.entries.len() as u64;
+                *lru = Lru::default();
+                count
+            }
+            Err(_) => 0,
+        };
+        if let Some(pool) = &self.pool {
+            removed += sqlx::query("DELETE FROM tmdb_cache")
+                .execute(pool)
+                .await?
+                .rows_affected();
+        }
+        self.hits.store(0, Ordering::Relaxed);
+        self.database_hits.store(0, Ordering::Relaxed);
+        self.misses.store(0, Ordering::Relaxed);
+        Ok(removed)
+    }
+
+    /// Drop responses older than `ttl`
+    pub async fn purge_expired(&self, ttl: Duration) -> Result<u64> {
+        let oldest = Utc::now() - ttl;
+        if let Ok(mut lru) = self.memory.lock() {
+            lru.entries.retain(|_, entry| entry.fetched_at > oldest);
+        }
+        let Some(pool) = &self.pool else {
+            return Ok(0);
+        };
+        let result =
+            sqlx::query("DELETE FROM tmdb_cache WHERE julianday(fetched_at) <= julianday(?)")
+                .bind(oldest.to_rfc3339())
+                .execute(pool)
+                .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::create_test_db;
+
+    #[test]
+    fn test_settings() {
+        assert_eq!(cache_mode(None).unwrap(), TmdbCacheMode::Memory);
+        assert_eq!(cache_mode(Some(" SQLite ")).unwrap(), TmdbCacheMode::Sqlite);
+        assert_eq!(cache_mode(Some("off")).unwrap(), TmdbCacheMode::Off);
+        assert!(cache_mode(Some("redis")).is_err());
+
+        assert_eq!(cache_ttl(None).unwrap(), Duration::hours(24));
+        assert_eq!(cache_ttl(Some("168")).unwrap(), Duration::hours(168));
+        assert!(cache_ttl(Some("0")).is_err());
+        assert!(cache_ttl(Some("a day")).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_memory_cache() {
+        let cache = TmdbCache::new(None);
+        let ttl = Duration::hours(1);
+        let mode = TmdbCacheMode::Memory;
+
+        assert!(cache.get(mode, ttl, "movie/550|de-DE").await.is_none());
+        cache.put(mode, "movie/550|de-DE", "{}").await;
+        assert_eq!(
+            cache.get(mode, ttl, "movie/550|de-DE").await.as_deref(),
+            Some("{}")
+        );
+        // Expired entries are not returned
+        assert!(
+            cache
+                .get(mode, Duration::zero(), "movie/550|de-DE")
+                .await
+                .is_none()
+        );
+        // Nothing is stored or returned while off
+        cache.put(TmdbCacheMode::Off, "movie/1|de-DE", "{}").await;
+        assert!(
+            cache
+                .get(TmdbCacheMode::Off, ttl, "movie/550|de-DE")
+                .await
+                .is_none()
+        );
+
+        let stats = cache.stats(mode, ttl).await.unwrap();
+        assert_eq!((stats.hits, stats.misses), (1, 2));
+        assert_eq!(stats.memory_entries, 1);
+
+        // The least recently used entry goes first
+        for i in 1..MEMORY_CAPACITY {
+            cache.put(mode, &format!("movie/{}", i), "{}").await;
+        }
+        assert!(cache.get(mode, ttl, "movie/550|de-DE").await.is_some());
+        cache.put(mode, "movie/new", "{}").await;
+        assert!(cache.get(mode, ttl, "movie/550|de-DE").await.is_some());
+        assert!(cache.get(mode, ttl, "movie/1").await.is_none());
+
+        assert_eq!(cache.clear().await.unwrap(), MEMORY_CAPACITY as u64);
+        assert_eq!(cache.stats(mode, ttl).await.unwrap().hits, 0);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_cache() {
+        let pool = create_test_db().await;
+        let ttl = Duration::hours(1);
+        let mode = TmdbCacheMode::Sqlite;
+
+        TmdbCache::new(Some(pool.clone()))
+            .put(mode, "collection/8091|de-DE", r#"{"id":8091}"#)
+            .await;
+
+        // A new process still has the response
+        let cache = TmdbCache::new(Some(pool));
+        assert!(
+            cache
+                .get(TmdbCacheMode::Memory, ttl, "collection/8091|de-DE")
+                .await
+                .is_none()
+        );
+        assert_eq!(
+            cache
+                .get(mode, ttl, "collection/8091|de-DE")
+                .await
+                .as_deref(),
+            Some(r#"{"id":8091}"#)
+        );
+        let stats = cache.stats(mode, ttl).await.unwrap();
+        assert_eq!((stats.database_hits, stats.database_entries), (1, 1));
+
+        assert_eq!(cache.purge_expired(ttl).await.unwrap(), 0);
+        assert_eq!(cache.purge_expired(Duration::zero()).await.unwrap(), 1);
+        assert_eq!(cache.stats(mode, ttl).await.unwrap().memory_entries, 0);
+    }
+}
@@ -0,0 +1,176 @@
+use chrono::{DateTime, Utc};
+use sqlx::SqliteConnection;
+use uuid::Uuid;
+
+use crate::db::DbPool;
+use crate::error::Result;
+use crate::models::{EntityType, Tombstone};
+
+/// Record deleted entities. Takes a connection so it can run in the
+/// same transaction as the delete.
+pub(crate) async fn record_deletions(
+    conn: &mut SqliteConnection,
+    user_id: Uuid,
+    entity_type: EntityType,
+    ids: &[Uuid],
+) -> Result<()> {
+    let now = Utc::now().to_rfc3339();
+    for id in ids {
+        sqlx::query(
+            "INSERT INTO deleted_entities (user_id, entity_type, entity_id, deleted_at) VALUES (?, ?, ?, ?)",
+        )
+        .bind(user_id)
+        .bind(entity_type)
+        .bind(id)
+        .bind(&now)
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
+
+/// Deletion records for delta sync
+pub struct TombstoneService {
+    pool: DbPool,
+}
+
+impl TombstoneService {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Entities deleted after `since`, oldest first
+    pub async fn since(
+        &self,
+        user_id: Uuid,
+        since: DateTime<Utc>,
+        entity_type: Option<EntityType>,
+    ) -> Result<Vec<Tombstone>> {
+        let mut query = String::from(
+            "SELECT entity_type, entity_id, deleted_at FROM deleted_entities \
+             WHERE user_id = ? AND julianday(deleted_at) > julianday(?)",
+        );
+        if entity_type.is_some() {
+            query.push_str(" AND entity_type = ?");
+        }
+        query.push_str(" ORDER BY deleted_at, id");
+
+        let mut q = sqlx::query_as::<_, Tombstone>(&query)
+            .bind(user_id)
+            .bind(since.to_rfc3339());
+        if let Some(entity_type) = entity_type {
+            q = q.bind(entity_type);
+        }
+
+        Ok(q.fetch_all(&self.pool).await?)
+    }
+
+    /// Remove tombstones older than `before`. Clients that haven't synced since
+    /// then have to do a full reload.
+    pub async fn prune(&self, before: DateTime<Utc>) -> Result<u64> {
+        let result =
+            sqlx::query("DELETE FROM deleted_entities WHERE julianday(deleted_at) < julianday(?)")
+                .bind(before.to_rfc3339())
+                .execute(&self.pool)
+                .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{CreateCollection, CreateSeries};
+    use crate::services::{CollectionService, SeriesService};
+    use crate::test_helpers::{create_test_db_with_users, fixtures};
+    use chrono::Duration;
+
+    #[tokio::test]
+    async fn test_series_and_collection_deletions_are_recorded() {
+        let pool = create_test_db_with_users().await;
+        let service = TombstoneService::new(pool.clone());
+        let series_service = SeriesService::new(pool.clone());
+        let collection_service = CollectionService::new(pool.clone());
+        let user_id = fixtures::test_user_id();
+        let since = Utc::now() - Duration::seconds(1);
+
+        let series = series_service
+            .create(
+                user_id,
+                CreateSeries {
+                    barcode: None,
+                    tmdb_id: None,
+                    title: "Dark".to_string(),
+                    disc_type: None,
+                },
+            )
+            .await
+            .unwrap();
+        let collection = collection_service
+            .create(
+                user_id,
+                CreateCollection {
+                    barcode: None,
+                    title: "Nolan".to_string(),
+                    description: None,
+                    disc_type: None,
+                },
+            )
+            .await
+            .unwrap();
+
+        series_service.delete(user_id, series.id).await.unwrap();
+        collection_service
+            .delete(user_id, collection.id)
+            .await
+            .unwrap();
+
+        let all = service.since(user_id, since, None).await.unwrap();
+        assert_eq!(all.len(), 2);
+
+        let series_only = service
+            .since(user_id, since, Some(EntityType::Series))
+            .await
+            .unwrap();
+        assert_eq!(series_only.len(), 1);
+        assert_eq!(series_only[0].entity_id, series.id);
+
+        // Other users don't see them
+        let other = service
+            .since(fixtures::test_admin_id(), since, None)
+            .await
+            .unwrap();
+        assert!(other.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_prune_removes_old_tombstones() {
+        let pool = create_test_db_with_users().await;
+        let service = TombstoneService::new(pool.clone());
+        let user_id = fixtures::test_user_id();
+
+        let mut conn = pool.acquire().await.unwrap();
+        record_deletions(&mut conn, user_id, EntityType::Movie, &[Uuid::new_v4()])
+            .await
+            .unwrap();
+        drop(conn);
+
+        let epoch = DateTime::<Utc>::UNIX_EPOCH;
+        assert_eq!(service.prune(epoch).await.unwrap(), 0);
+        assert_eq!(
+            service
+                .prune(Utc::now() + Duration::seconds(1))
+                .await
+                .unwrap(),
+            1
+        );
+        assert!(
+            service
+                .since(user_id, epoch, None)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,674 @@
+use chrono::{NaiveDate, Utc};
+use uuid::Uuid;
+
+use crate::db::DbPool;
+use crate::db::query::Conditions;
+use crate::error::{Error, Result};
+use crate::models::{
+    BulkUpdateCurrency, BulkUpdateCurrencyResult, EntityType, ItemValue, SetItemValue,
+    ValuationPoint, ValuationTotals, normalize_currency,
+};
+use crate::services::ExchangeRates;
+use crate::services::activity::record_changes;
+
+/// Tables with purchase prices and values
+const TABLES: [&str; 3] = ["movies", "series", "collections"];
+
+/// Amount and currency columns of each table
+const AMOUNT_COLUMNS: [(&str, &str); 2] =
+    [("price", "currency"), ("value_price", "value_currency")];
+
+/// Purchase prices and values of the items in a collection, one row per amount.
+/// Box sets, sold and wishlist movies are left out, like in the value report.
+const AMOUNTS: &str = r#"
+    SELECT user_id, currency, price AS purchase, NULL AS value FROM movies
+        WHERE price IS NOT NULL AND is_collection = 0 AND disposition IS NULL
+            AND COALESCE(status, 'owned') != 'wishlist'
+    UNION ALL
+    SELECT user_id, value_currency, NULL, value_price FROM movies
+        WHERE value_price IS NOT NULL AND is_collection = 0 AND disposition IS NULL
+            AND COALESCE(status, 'owned') != 'wishlist'
+    UNION ALL
+    SELECT user_id, currency, price, NULL FROM series WHERE price IS NOT NULL
+    UNION ALL
+    SELECT user_id, value_currency, NULL, value_price FROM series WHERE value_price IS NOT NULL
+    UNION ALL
+    SELECT user_id, currency, price, NULL FROM collections WHERE price IS NOT NULL
+    UNION ALL
+    SELECT user_id, value_currency, NULL, value_price FROM collections WHERE value_price IS NOT NULL
+"#;
+
+/// Totals per user and currency of [`AMOUNTS`]
+const TOTALS_COLUMNS: &str = "COALESCE(trim(currency), ''), \
+     TOTAL(purchase), TOTAL(value), COUNT(purchase), COUNT(value)";
+
+fn table(entity_type: EntityType) -> &'static str {
+    match entity_type {
+        EntityType::Movie => "movies",
+        EntityType::Series => "series",
+        EntityType::Collection => "collections",
+    }
+}
+
+type TotalsRow = (String, f64, f64, i64, i64);
+
+fn totals(
+    (currency, purchase_total, value_total, priced_count, valued_count): TotalsRow,
+) -> ValuationTotals {
+    ValuationTotals {
+        currency: (!currency.is_empty()).then_some(currency),
+        purchase_total,
+        value_total,
+        priced_count,
+        valued_count,
+    }
+}
+
+/// Current values of movies, series and collections, and daily snapshots of
+/// what a collection is worth
+pub struct ValuationService {
+    pool: DbPool,
+}
+
+impl ValuationService {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Purchase price and current value of an item
+    pub async fn get(&self, user_id: Uuid, entity_type: EntityType, id: Uuid) -> Result<ItemValue> {
+        type ValueRow = (
+            Uuid,
+            String,
+            Option<f64>,
+            Option<String>,
+            Option<f64>,
+            Option<String>,
+            Option<NaiveDate>,
+        );
+        let query = format!(
+            "SELECT id, title, price, currency, value_price, value_currency, value_date \
+             FROM {} WHERE id = ? AND user_id = ?",
+            table(entity_type)
+        );
+        let (id, title, price, currency, value_price, value_currency, value_date) =
+            sqlx::query_as::<_, ValueRow>(&query)
+                .bind(id)
+                .bind(user_id)
+                .fetch_optional(&self.pool)
+                .await?
+                .ok_or(Error::NotFound)?;
+
+        Ok(ItemValue {
+            entity_type,
+            id,
+            title,
+            price,
+            currency,
+            value_price,
+            value_currency,
+            value_date,
+        })
+    }
+
+    /// Record what an item is worth now
+    pub async fn set_value(
+        &self,
+        user_id: Uuid,
+        entity_type: EntityType,
+        id: Uuid,
+        input: SetItemValue,
+    ) -> Result<ItemValue> {
+        let before = self.get(user_id, entity_type, id).await?;
+
+        let (value_price, value_currency, value_date) = match input.value_price {
+            None => (None, None, None),
+            Some(value) if !value.is_finite() || value < 0.0 => {
+                return Err(Error::Validation(
+                    "The value must be a positive amount".into(),
+                ));
+            }
+            Some(value) => {
+                let currency = match input
+                    .value_currency
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|c| !c.is_empty())
+                {
+                    Some(currency) => {
+                        Some(normalize_currency(currency).map_err(Error::Validation)?)
+                    }
+                    None => before.currency.clone(),
+                };
+                let date = input.value_date.unwrap_or_else(|| Utc::now().date_naive());
+                (Some(value), currency, Some(date))
+            }
+        };
+
+        let editor = if entity_type == EntityType::Movie {
+            ", updated_by = ?"
+        } else {
+            ""
+        };
+        let query = format!(
+            "UPDATE {} SET value_price = ?, value_currency = ?, value_date = ?, updated_at = ?{} \
+             WHERE id = ? AND user_id = ?",
+            table(entity_type),
+            editor
+        );
+        let mut q = sqlx::query(&query)
+            .bind(value_price)
+            .bind(value_currency)
+            .bind(value_date)
+            .bind(Utc::now().to_rfc3339());
+        if entity_type == EntityType::Movie {
+            q = q.bind(user_id);
+        }
+        q.bind(id).bind(user_id).execute(&self.pool).await?;
+
+        let after = self.get(user_id, entity_type, id).await?;
+        record_changes(
+            &self.pool,
+            user_id,
+            entity_type,
+            id,
+            &after.title,
+            &before,
+            &after,
+        )
+        .await?;
+        Ok(after)
+    }
+
+    /// Set the currency of purchase prices and values, optionally converting
+    /// the amounts
+    pub async fn bulk_update_currency(
+        &self,
+        user_id: Uuid,
+        input: &BulkUpdateCurrency,
+        rates: &ExchangeRates,
+    ) -> Result<BulkUpdateCurrencyResult> {
+        let to = normalize_currency(&input.currency).map_err(Error::Validation)?;
+        let from = input
+            .from
+            .as_deref()
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .map(normalize_currency)
+            .transpose()
+            .map_err(Error::Validation)?;
+        let factor = match (&from, input.convert) {
+            (_, false) => None,
+            (None, true) => {
+                return Err(Error::Validation(
+                    "Amounts without currency can't be converted; set `from`".into(),
+                ));
+            }
+            (Some(from), true) => Some(rates.convert(1.0, from, &to).ok_or_else(|| {
+                Error::Validation(format!("No exchange rate from {} to {}", from, to))
+            })?),
+        };
+
+        let now = Utc::now().to_rfc3339();
+        let mut tx = self.pool.begin().await?;
+        let mut updated = 0;
+        for table in TABLES {
+            for (amount, currency) in AMOUNT_COLUMNS {
+                let convert = match factor {
+                    Some(_) => format!(", {amount} = round({amount} * ?, 2)"),
+                    None => String::new(),
+                };
+                let set = format!(
+                    "UPDATE {table} SET {currency} = ?{convert}, updated_at = ? \
+                     WHERE user_id = ? AND {amount} IS NOT NULL"
+                );
+
+                let Some(from) = &from else {
+                    let result = sqlx::query(&format!(
+                        "{set} AND ({currency} IS NULL OR trim({currency}) = '')"
+                    ))
+                    .bind(&to)
+                    .bind(&now)
+                    .bind(user_id)
+                    .execute(&mut *tx)
+                    .await?;
+                    updated += result.rows_affected();
+                    continue;
+                };
+
+                // Stored as code, symbol or name ("USD", "$", "dollar")
+                let stored: Vec<(String,)> = sqlx::query_as(&format!(
+                    "SELECT DISTINCT {currency} FROM {table} \
+                     WHERE user_id = ? AND {amount} IS NOT NULL AND {currency} IS NOT NULL"
+                ))
+                .bind(user_id)
+                .fetch_all(&mut *tx)
+                .await?;
+                for (stored,) in stored {
+                    if normalize_currency(&stored).as_ref() != Ok(from) {
+                        continue;
+                    }
+                    let query = format!("{set} AND {currency} = ?");
+                    let mut q = sqlx::query(&query).bind(&to);
+                    if let Some(factor) = factor {
+                        q = q.bind(factor);
+                    }
+                    let result = q
+                        .bind(&now)
+                        .bind(user_id)
+                        .bind(&stored)
+                        .execute(&mut *tx)
+                        .await?;
+                    updated += result.rows_affected();
+                }
+            }
+        }
+        tx.commit().await?;
+
+        Ok(BulkUpdateCurrencyResult { updated })
+    }
+
+    /// Store the totals of all users for `date`, replacing an earlier
+    /// snapshot of that day
+    pub async fn snapshot(&self, date: NaiveDate) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM valuation_snapshots WHERE date = ?")
+            .bind(date)
+            .execute(&mut *tx)
+            .await?;
+        let result = sqlx::query(&format!(
+            "INSERT INTO valuation_snapshots \
+             (user_id, date, currency, purchase_total, value_total, priced_count, valued_count) \
+             SELECT user_id, ?, {TOTALS_COLUMNS} FROM ({AMOUNTS}) \
+             GROUP BY user_id, COALESCE(trim(currency), '')"
+        ))
+        .bind(date)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Totals of a user's collection right now, one entry per currency
+    pub async fn current(&self, user_id: Uuid) -> Result<Vec<ValuationTotals>> {
+        let rows = sqlx::query_as::<_, TotalsRow>(&format!(
+            "SELECT {TOTALS_COLUMNS} FROM ({AMOUNTS}) WHERE user_id = ? \
+             GROUP BY COALESCE(trim(currency), '') ORDER BY 1"
+        ))
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(totals).collect())
+    }
+
+    /// Daily totals between `from` and `to`, oldest first. Today is always
+    /// the current totals, so recorded values show up before the next snapshot.
+    pub async fn history(
+        &self,
+        user_id: Uuid,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<Vec<ValuationPoint>> {
+        if let (Some(from), Some(to)) = (from, to)
+            && from > to
+        {
+            return Err(Error::Validation("`from` must not be after `to`".into()));
+        }
+        let today = Utc::now().date_naive();
+
+        let mut conditions = Conditions::new();
+        conditions.push_bind("user_id = ?", user_id);
+        // Today comes from the current totals
+        conditions.push_bind("date < ?", today.to_string());
+        if let Some(from) = from {
+            conditions.push_bind("date >= ?", from.to_string());
+        }
+        if let Some(to) = to {
+            conditions.push_bind("date <= ?", to.to_string());
+        }
+        let query = format!(
+            "SELECT date, currency, purchase_total, value_total, priced_count, valued_count \
+             FROM valuation_snapshots WHERE {} ORDER BY date, currency",
+            conditions.sql()
+        );
+        let rows = conditions
+            .bind(sqlx::query_as::<_, (NaiveDate, String, f64, f64, i64, i64)>(&query))
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut points: Vec<ValuationPoint> = Vec::new();
+        for (date, currency, purchase_total, value_total, priced_count, valued_count) in rows {
+            let totals = totals((
+                currency,
+                purchase_total,
+                value_total,
+                priced_count,
+                valued_count,
+            ));
+            match points.last_mut() {
+                Some(point) if point.date == date => point.totals.push(totals),
+                _ => points.push(ValuationPoint {
+                    date,
+                    totals: vec![totals],
+                    converted: None,
+                }),
+            }
+        }
+
+        if from.is_none_or(|from| from <= today) && to.is_none_or(|to| to >= today) {
+            let current = self.current(user_id).await?;
+            if !current.is_empty() {
+                points.push(ValuationPoint {
+                    date: today,
+                    totals: current,
+                    converted: None,
+                });
+            }
+        }
+
+        Ok(points)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::models::{ActivityAction, ActivityFilter, CreateMovie};
+    use crate::services::{ActivityService, MovieService};
+    use crate::test_helpers::{create_test_db_with_users, fixtures};
+
+    async fn create_priced_movie(
+        pool: &DbPool,
+        title: &str,
+        price: Option<f64>,
+        currency: Option<&str>,
+    ) -> Uuid {
+        let movie = MovieService::new(pool.clone())
+            .create(
+                fixtures::test_user_id(),
+                CreateMovie {
+                    barcode: None,
+                    tmdb_id: None,
+                    title: title.to_string(),
+                    original_title: None,
+                    disc_type: None,
+                    production_year: None,
+                },
+            )
+            .await
+            .unwrap();
+        sqlx::query("UPDATE movies SET price = ?, currency = ? WHERE id = ?")
+            .bind(price)
+            .bind(currency)
+            .bind(movie.id)
+            .execute(pool)
+            .await
+            .unwrap();
+        movie.id
+    }
+
+    #[tokio::test]
+    async fn test_set_value() {
+        let pool = create_test_db_with_users().await;
+        let service = ValuationService::new(pool.clone());
+        let user_id = fixtures::test_user_id();
+        let id = create_priced_movie(&pool, "Heat", Some(20.0), Some("EUR")).await;
+
+        // Currency defaults to the purchase currency, the date to today
+        let value = service
+            .set_value(
+                user_id,
+                EntityType::Movie,
+                id,
+                SetItemValue {
+                    value_price: Some(35.0),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(value.value_price, Some(35.0));
+        assert_eq!(value.value_currency.as_deref(), Some("EUR"));
+        assert_eq!(value.value_date, Some(Utc::now().date_naive()));
+
+        let value = service
+            .set_value(
+                user_id,
+                EntityType::Movie,
+                id,
+                SetItemValue {
+                    value_price: Some(40.0),
+                    value_currency: Some("$".to_string()),
+                    value_date: NaiveDate::from_ymd_opt(2024, 5, 1),
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(value.value_currency.as_deref(), Some("USD"));
+        assert_eq!(value.value_date, NaiveDate::from_ymd_opt(2024, 5, 1));
+
+        let cleared = service
+            .set_value(user_id, EntityType::Movie, id, SetItemValue::default())
+            .await
+            .unwrap();
+        assert_eq!(cleared.value_price, None);
+        assert_eq!(cleared.value_currency, None);
+        assert_eq!(cleared.price, Some(20.0));
+
+        let updates = ActivityService::new(pool.clone())
+            .list(
+                user_id,
+                &ActivityFilter {
+                    entity_id: Some(id),
+                    action: Some(ActivityAction::Update),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            updates[0].changes.as_ref().unwrap()["value_price"]["old"],
+            40.0
+        );
+
+        let negative = SetItemValue {
+            value_price: Some(-1.0),
+            ..Default::default()
+        };
+        assert!(matches!(
+            service
+                .set_value(user_id, EntityType::Movie, id, negative)
+                .await,
+            Err(Error::Validation(_))
+        ));
+        assert!(matches!(
+            service
+                .set_value(
+                    fixtures::test_admin_id(),
+                    EntityType::Movie,
+                    id,
+                    SetItemValue::default()
+                )
+                .await,
+            Err(Error::NotFound)
+        ));
+        assert!(matches!(
+            service
+                .set_value(
+                    user_id,
+                    EntityType::Collection,
+                    fixtures::test_collection_id(),
+                    SetItemValue::default()
+                )
+                .await,
+            Err(Error::NotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_bulk_update_currency() {
+        let pool = create_test_db_with_users().await;
+        let service = ValuationService::new(pool.clone());
+        let user_id = fixtures::test_user_id();
+        let unlabeled = create_priced_movie(&pool, "Alien", Some(10.0), None).await;
+        let dollars = create_priced_movie(&pool, "Heat", Some(15.0), Some("$")).await;
+        let unpriced = create_priced_movie(&pool, "Se7en", None, None).await;
+        let rates = ExchangeRates::new(HashMap::from([("USD".to_string(), 1.5)]));
+
+        let result = service
+            .bulk_update_currency(
+                user_id,
+                &BulkUpdateCurrency {
+                    currency: "euro".to_string(),
+                    from: None,
+                    convert: false,
+                },
+                &rates,
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.updated, 1);
+        let movie = service
+            .get(user_id, EntityType::Movie, unlabeled)
+            .await
+            .unwrap();
+        assert_eq!(movie.currency.as_deref(), Some("EUR"));
+        assert_eq!(movie.price, Some(10.0));
+        let movie = service
+            .get(user_id, EntityType::Movie, unpriced)
+            .await
+            .unwrap();
+        assert_eq!(movie.currency, None);
+
+        let result = service
+            .bulk_update_currency(
+                user_id,
+                &BulkUpdateCurrency {
+                    currency: "EUR".to_string(),
+                    from: Some("usd".to_string()),
+                    convert: true,
+                },
+                &rates,
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.updated, 1);
+        let movie = service
+            .get(user_id, EntityType::Movie, dollars)
+            .await
+            .unwrap();
+        assert_eq!(movie.currency.as_deref(), Some("EUR"));
+        assert_eq!(movie.price, Some(10.0));
+
+        for input in [
+            BulkUpdateCurrency {
+                currency: "EUR".to_string(),
+                from: None,
+                convert: true,
+            },
+            BulkUpdateCurrency {
+                currency: "GBP".to_string(),
+                from: Some("EUR".to_string()),
+                convert: true,
+            },
+        ] {
+            assert!(matches!(
+                service.bulk_update_currency(user_id, &input, &rates).await,
+                Err(Error::Validation(_))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_and_history() {
+        let pool = create_test_db_with_users().await;
+        let service = ValuationService::new(pool.clone());
+        let user_id = fixtures::test_user_id();
+        let today = Utc::now().date_naive();
+        let yesterday = today.pred_opt().unwrap();
+
+        let heat = create_priced_movie(&pool, "Heat", Some(20.0), Some("EUR")).await;
+        create_priced_movie(&pool, "Alien", Some(5.0), None).await;
+        let sold = create_priced_movie(&pool, "Se7en", Some(100.0), Some("EUR")).await;
+        sqlx::query("UPDATE movies SET disposition = 'sold' WHERE id = ?")
+            .bind(sold)
+            .execute(&pool)
+            .await
+            .unwrap();
+        service
+            .set_value(
+                user_id,
+                EntityType::Movie,
+                heat,
+                SetItemValue {
+                    value_price: Some(30.0),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(service.snapshot(yesterday).await.unwrap(), 2);
+        // Running again the same day replaces the snapshot
+        assert_eq!(service.snapshot(yesterday).await.unwrap(), 2);
+
+        service
+            .set_value(
+                user_id,
+                EntityType::Movie,
+                heat,
+                SetItemValue {
+                    value_price: Some(45.0),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        let history = service.history(user_id, None, None).await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].date, yesterday);
+        assert_eq!(
+            history[0].totals,
+            vec![
+                ValuationTotals {
+                    currency: None,
+                    purchase_total: 5.0,
+                    value_total: 0.0,
+                    priced_count: 1,
+                    valued_count: 0,
+                },
+                ValuationTotals {
+                    currency: Some("EUR".to_string()),
+                    purchase_total: 20.0,
+                    value_total: 30.0,
+                    priced_count: 1,
+                    valued_count: 1,
+                },
+            ]
+        );
+        assert_eq!(history[1].date, today);
+        assert_eq!(history[1].totals[1].value_total, 45.0);
+
+        let until_yesterday = service
+            .history(user_id, None, Some(yesterday))
+            .await
+            .unwrap();
+        assert_eq!(until_yesterday.len(), 1);
+        assert!(
+            service
+                .history(fixtures::test_admin_id(), None, None)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert!(matches!(
+            service.history(user_id, Some(today), Some(yesterday)).await,
+            Err(Error::Validation(_))
+        ));
+    }
+}
@@ -0,0 +1,199 @@
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::db::DbPool;
+use crate::error::{Error, Result};
+use crate::models::{LogWatch, WatchEvent};
+
+const MAX_NOTES: usize = 2000;
+
+/// Watch history: every viewing of a movie, with date and notes
+pub struct WatchService {
+    pool: DbPool,
+}
+
+impl WatchService {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Log a viewing (today unless a date is given) and mark the movie as watched
+    pub async fn log(&self, user_id: Uuid, movie_id: Uuid, input: LogWatch) -> Result<WatchEvent> {
+        self.ensure_movie(user_id, movie_id).await?;
+
+        let notes = input
+            .notes
+            .as_deref()
+            .map(str::trim)
+            .filter(|n| !n.is_empty());
+        if notes.is_some_and(|n| n.chars().count() > MAX_NOTES) {
+            return Err(Error::Validation(format!(
+                "Notes must be at most {} characters",
+                MAX_NOTES
+            )));
+        }
+        let watched_on = input.date.unwrap_or_else(|| Utc::now().date_naive());
+
+        let mut tx = self.pool.begin().await?;
+        let id: i64 = sqlx::query_scalar(
+            r#"
+            INSERT INTO watch_events (user_id, movie_id, watched_on, notes, created_at)
+            VALUES (?, ?, ?, ?, ?)
+            RETURNING id
+            "#,
+        )
+        .bind(user_id)
+        .bind(movie_id)
+        .bind(watched_on)
+        .bind(notes)
+        .bind(Utc::now().to_rfc3339())
+        .fetch_one(&mut *tx)
+        .await?;
+        sqlx::query("UPDATE movies SET watched = 1 WHERE id = ? AND user_id = ? AND watched = 0")
+            .bind(movie_id)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        self.get(user_id, id).await
+    }
+
+    /// Viewings of a movie, most recent first; undated ones last
+    pub async fn list(&self, user_id: Uuid, movie_id: Uuid) -> Result<Vec<WatchEvent>> {
+        self.ensure_movie(user_id, movie_id).await?;
+
+        let events = sqlx::query_as::<_, WatchEvent>(
+            r#"
+            SELECT id, movie_id, watched_on, notes, created_at FROM watch_events
+            WHERE user_id = ? AND movie_id = ?
+            ORDER BY watched_on IS NULL, watched_on DESC, id DESC
+            "#,
+        )
+        .bind(user_id)
+        .bind(movie_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(events)
+    }
+
+    /// Remove a viewing; the movie stays marked as watched
+    pub async fn delete(&self, user_id: Uuid, movie_id: Uuid, id: i64) -> Result<()> {
+        let result =
+            sqlx::query("DELETE FROM watch_events WHERE id = ? AND movie_id = ? AND user_id = ?")
+                .bind(id)
+                .bind(movie_id)
+                .bind(user_id)
+                .execute(&self.pool)
+                .await?;
+        if result.rows_affected() == 0 {
+            return Err(Error::NotFound);
+        }
+        Ok(())
+    }
+
+    async fn get(&self, user_id: Uuid, id: i64) -> Result<WatchEvent> {
+        sqlx::query_as::<_, WatchEvent>(
+            r#"
+            SELECT id, movie_id, watched_on, notes, created_at FROM watch_events
+            WHERE id = ? AND user_id = ?
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or(Error::NotFound)
+    }
+
+    async fn ensure_movie(&self, user_id: Uuid, movie_id: Uuid) -> Result<()> {
+        let exists: Option<i64> =
+            sqlx::query_scalar("SELECT 1 FROM movies WHERE id = ? AND user_id = ?")
+                .bind(movie_id)
+                .bind(user_id)
+                .fetch_optional(&self.pool)
+                .await?;
+        exists.map(|_| ()).ok_or(Error::NotFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{CreateMovie, MovieFilter};
+    use crate::services::MovieService;
+    use crate::test_helpers::{create_test_db_with_users, fixtures};
+    use chrono::NaiveDate;
+
+    #[tokio::test]
+    async fn test_watch_history() {
+        let pool = create_test_db_with_users().await;
+        let movies = MovieService::new(pool.clone());
+        let watches = WatchService::new(pool.clone());
+        let user_id = fixtures::test_user_id();
+
+        let movie = movies
+            .create(
+                user_id,
+                CreateMovie {
+                    barcode: None,
+                    tmdb_id: None,
+                    title: "Heat".to_string(),
+                    original_title: None,
+                    disc_type: None,
+                    production_year: None,
+                },
+            )
+            .await
+            .unwrap();
+
+        let first = NaiveDate::from_ymd_opt(2025, 12, 24).unwrap();
+        let second = NaiveDate::from_ymd_opt(2026, 2, 1).unwrap();
+        watches
+            .log(
+                user_id,
+                movie.id,
+                LogWatch {
+                    date: Some(first),
+                    notes: None,
+                },
+            )
+            .await
+            .unwrap();
+        let event = watches
+            .log(
+                user_id,
+                movie.id,
+                LogWatch {
+                    date: Some(second),
+                    notes: Some(" Director's cut ".to_string()),
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(event.notes.as_deref(), Some("Director's cut"));
+
+        let history = watches.list(user_id, movie.id).await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].watched_on, Some(second));
+
+        let listed = movies.list(user_id, MovieFilter::default()).await.unwrap();
+        assert!(listed[0].watched);
+        assert_eq!(listed[0].times_watched, 2);
+        assert_eq!(listed[0].last_watched_at, Some(second));
+
+        let stats = movies.stats(user_id, 2026).await.unwrap();
+        assert_eq!(stats.watched_this_year, 1);
+        assert_eq!(stats.watches_this_year, 1);
+
+        watches.delete(user_id, movie.id, event.id).await.unwrap();
+        assert_eq!(watches.list(user_id, movie.id).await.unwrap().len(), 1);
+        assert!(watches.delete(user_id, movie.id, event.id).await.is_err());
+        assert!(
+            watches
+                .list(fixtures::test_admin_id(), movie.id)
+                .await
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,329 @@
+//! Integrity manifest for ZIP backups, so copies kept off-site can be checked
+//! for silent corruption before they are needed
+
+use std::collections::BTreeMap;
+use std::io::{Cursor, Read};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::routes::movies::{BACKUP_SCHEMA_VERSION, ExportData};
+
+pub const MANIFEST_FILE: &str = "manifest.json";
+pub const MOVIES_FILE: &str = "movies.json";
+/// Avatar of the exporting user, see `ExportAccount`
+pub const AVATAR_FILE: &str = "avatar.jpg";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub sha256: String,
+    pub size: u64,
+}
+
+/// Written as `manifest.json` into every ZIP backup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub schema_version: u32,
+    pub generated_at: String,
+    pub movie_count: usize,
+    pub poster_count: usize,
+    /// Every other file in the archive, keyed by path
+    pub files: BTreeMap<String, ManifestEntry>,
+}
+
+impl BackupManifest {
+    pub fn new() -> Self {
+        Self {
+            schema_version: BACKUP_SCHEMA_VERSION,
+            generated_at: chrono::Utc::now().to_rfc3339(),
+            movie_count: 0,
+            poster_count: 0,
+            files: BTreeMap::new(),
+        }
+    }
+
+    /// Record a file written to the archive
+    pub fn add_file(&mut self, path: &str, data: &[u8]) {
+        self.files.insert(
+            path.to_string(),
+            ManifestEntry {
+                sha256: sha256_hex(data),
+                size: data.len() as u64,
+            },
+        );
+    }
+}
+
+impl Default for BackupManifest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn sha256_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// Result of checking a backup archive without importing it
+#[derive(Debug, Default, Serialize)]
+pub struct VerifyReport {
+    pub valid: bool,
+    pub has_manifest: bool,
+    pub schema_version: Option<u32>,
+    pub generated_at: Option<String>,
+    pub movie_count: usize,
+    pub poster_count: usize,
+    pub files_checked: usize,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+/// Check archive structure, checksums and counts against the manifest.
+/// Backups created before manifests existed are checked for readability only.
+pub fn verify_archive(data: &[u8]) -> VerifyReport {
+    let mut report = VerifyReport::default();
+
+    let mut archive = match zip::ZipArchive::new(Cursor::new(data)) {
+        Ok(a) => a,
+        Err(e) => {
+            report.errors.push(format!("Not a valid ZIP file: {}", e));
+            return report;
+        }
+    };
+
+    // Read every file once; a failing CRC check surfaces here
+    let mut contents: BTreeMap<String, Vec<u8>> = BTreeMap::new();
+    for i in 0..archive.len() {
+        let mut file = match archive.by_index(i) {
+            Ok(f) => f,
+            Err(e) => {
+                report
+                    .errors
+                    .push(format!("Unreadable entry #{}: {}", i, e));
+                continue;
+            }
+        };
+        if file.is_dir() {
+            continue;
+        }
+        let name = file.name().to_string();
+        let mut buf = Vec::new();
+        if let Err(e) = file.read_to_end(&mut buf) {
+            report
+                .errors
+                .push(format!("Failed to read '{}': {}", name, e));
+            continue;
+        }
+        contents.insert(name, buf);
+    }
+
+    let manifest = match contents.get(MANIFEST_FILE) {
+        Some(bytes) => match serde_json::from_slice::<BackupManifest>(bytes) {
+            Ok(m) => Some(m),
+            Err(e) => {
+                report
+                    .errors
+                    .push(format!("Invalid {}: {}", MANIFEST_FILE, e));
+                None
+            }
+        },
+        None => {
+            report
+                .warnings
+                .push("Backup has no manifest, checksums cannot be verified".to_string());
+            None
+        }
+    };
+    report.has_manifest = manifest.is_some();
+
+    match contents.get(MOVIES_FILE) {
+        Some(bytes) => match serde_json::from_slice::<ExportData>(bytes) {
+            Ok(export) => {
+                report.schema_version = Some(export.schema_version);
+                report.movie_count = export.movies.len();
+                report.warnings.extend(export.compatibility_warnings());
+            }
+            Err(e) => report
+                .errors
+                .push(format!("Invalid {}: {}", MOVIES_FILE, e)),
+        },
+        None => report
+            .errors
+            .push(format!("Backup does not contain {}", MOVIES_FILE)),
+    }
+
+    report.poster_count = contents
+        .keys()
+        .filter(|name| name.starts_with("posters/"))
+        .count();
+
+    if let Some(manifest) = manifest {
+        report.generated_at = Some(manifest.generated_at.clone());
+        if report.schema_version.is_none() {
+            report.schema_version = Some(manifest.schema_version);
+        }
+
+        for (path, entry) in &manifest.files {
+            match contents.get(path) {
+                Some(bytes) => {
+                    report.files_checked += 1;
+                    if bytes.len() as u64 != entry.size || sha256_hex(bytes) != entry.sha256 {
+                        report.errors.push(format!("Checksum mismatch: {}", path));
+                    }
+                }
+                None => report.errors.push(format!("Missing file: {}", path)),
+            }
+        }
+
+        for path in contents.keys() {
+            if path != MANIFEST_FILE && !manifest.files.contains_key(path) {
+                report
+                    .warnings
+                    .push(format!("File not listed in manifest: {}", path));
+            }
+        }
+
+        if manifest.movie_count != report.movie_count {
+            report.errors.push(format!(
+                "Manifest lists {} movies, {} contains {}",
+                manifest.movie_count, MOVIES_FILE, report.movie_count
+            ));
+        }
+        if manifest.poster_count != report.poster_count {
+            report.errors.push(format!(
+                "Manifest lists {} posters, archive contains {}",
+                manifest.poster_count, report.poster_count
+            ));
+        }
+    }
+
+    report.valid = report.errors.is_empty();
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    fn movies_json(count: usize) -> Vec<u8> {
+        let movies: Vec<serde_json::Value> = (0..count)
+            .map(|i| {
+                serde_json::json!({
+                    "id": format!("id-{}", i),
+                    "barcode": null,
+                    "tmdb_id": null,
+                    "title": format!("Movie {}", i),
+                    "original_title": null,
+                    "sort_title": null,
+                    "description": null,
+                    "production_year": null,
+                    "disc_type": null,
+                    "running_time": null,
+                    "genres": null,
+                    "director": null,
+                    "actors": null,
+                    "watched": false,
+                    "location": null,
+                    "rating": null,
+                    "personal_rating": null,
+                    "notes": null,
+                    "is_collection": false,
+                    "parent_collection_id": null,
+                    "created_at": "2024-01-01T00:00:00Z",
+                    "updated_at": "2024-01-01T00:00:00Z"
+                })
+            })
+            .collect();
+        serde_json::to_vec(&serde_json::json!({
+            "version": "1.0",
+            "schema_version": BACKUP_SCHEMA_VERSION,
+            "exported_at": "2024-01-01T00:00:00Z",
+            "total_movies": count,
+            "movies": movies
+        }))
+        .unwrap()
+    }
+
+    fn build_zip(files: &[(&str, Vec<u8>)]) -> Vec<u8> {
+        let mut buffer = Cursor::new(Vec::new());
+        {
+            let mut zip = zip::ZipWriter::new(&mut buffer);
+            for (name, data) in files {
+                zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+                zip.write_all(data).unwrap();
+            }
+            zip.finish().unwrap();
+        }
+        buffer.into_inner()
+    }
+
+    fn backup_with_manifest(poster: &[u8]) -> (Vec<(&'static str, Vec<u8>)>, BackupManifest) {
+        let movies = movies_json(2);
+        let mut manifest = BackupManifest::new();
+        manifest.add_file("posters/id-0.jpg", poster);
+        manifest.add_file(MOVIES_FILE, &movies);
+        manifest.movie_count = 2;
+        manifest.poster_count = 1;
+        (
+            vec![("posters/id-0.jpg", poster.to_vec()), (MOVIES_FILE, movies)],
+            manifest,
+        )
+    }
+
+    #[test]
+    fn test_verify_valid_backup() {
+        let (mut files, manifest) = backup_with_manifest(&[0xFF, 0xD8, 0xFF, 0xE0]);
+        files.push((MANIFEST_FILE, serde_json::to_vec(&manifest).unwrap()));
+
+        let report = verify_archive(&build_zip(&files));
+
+        assert!(report.valid, "{:?}", report.errors);
+        assert!(report.has_manifest);
+        assert_eq!(report.movie_count, 2);
+        assert_eq!(report.poster_count, 1);
+        assert_eq!(report.files_checked, 2);
+    }
+
+    #[test]
+    fn test_verify_detects_corrupted_file() {
+        let (mut files, manifest) = backup_with_manifest(&[0xFF, 0xD8, 0xFF, 0xE0]);
+        // Same size, different content
+        files[0].1 = vec![0x00, 0xD8, 0xFF, 0xE0];
+        files.push((MANIFEST_FILE, serde_json::to_vec(&manifest).unwrap()));
+
+        let report = verify_archive(&build_zip(&files));
+
+        assert!(!report.valid);
+        assert!(report.errors.iter().any(|e| e.contains("posters/id-0.jpg")));
+    }
+
+    #[test]
+    fn test_verify_detects_missing_file() {
+        let (mut files, manifest) = backup_with_manifest(&[1, 2, 3]);
+        files.remove(0);
+        files.push((MANIFEST_FILE, serde_json::to_vec(&manifest).unwrap()));
+
+        let report = verify_archive(&build_zip(&files));
+
+        assert!(!report.valid);
+        assert!(report.errors.iter().any(|e| e.starts_with("Missing file")));
+    }
+
+    #[test]
+    fn test_verify_legacy_backup_without_manifest() {
+        let report = verify_archive(&build_zip(&[(MOVIES_FILE, movies_json(3))]));
+
+        assert!(report.valid);
+        assert!(!report.has_manifest);
+        assert_eq!(report.movie_count, 3);
+        assert_eq!(report.warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_verify_rejects_non_zip() {
+        let report = verify_archive(b"definitely not a zip");
+        assert!(!report.valid);
+    }
+}
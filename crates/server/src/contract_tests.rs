@@ -0,0 +1,336 @@
+//! Contract tests: the client SDK against the full router over HTTP, so a
+//! change to a route or payload shape that breaks clients fails here
+
+use my_movies_client::{
+    AddCollectionItem, Client, CollectionFilter, CollectionItemType, CreateCollection, CreateMovie,
+    Error, MovieFilter, RegisterRequest, UpdateCollection, UpdateMovie,
+};
+use my_movies_core::models::BarcodeCacheEntry;
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use crate::test_helpers::{TestApp, create_test_app};
+
+/// Serve the app and sign in as the regular user through the client
+async fn signed_in(app: &TestApp) -> Client {
+    let client = Client::new(&app.serve().await).unwrap();
+    client.login("user", "password").await.unwrap();
+    client
+}
+
+/// Raw JSON of a GET route, as the client's token sees it
+async fn raw(client: &Client, path: &str) -> Value {
+    reqwest::Client::new()
+        .get(client.url(path).unwrap())
+        .bearer_auth(client.token().unwrap())
+        .send()
+        .await
+        .unwrap()
+        .error_for_status()
+        .unwrap()
+        .json()
+        .await
+        .unwrap()
+}
+
+/// Every field of the client type is in the server's response; a renamed or
+/// dropped field would otherwise decode silently as `None`
+fn assert_fields_sent<T: Serialize>(typed: &T, raw: &Value) {
+    let typed = serde_json::to_value(typed).unwrap();
+    for key in typed.as_object().unwrap().keys() {
+        assert!(
+            raw.get(key).is_some(),
+            "field `{key}` missing from response {raw}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_auth() {
+    let app = create_test_app().await;
+    let client = Client::new(&app.serve().await).unwrap();
+
+    let err = client.login("user", "wrong").await.unwrap_err();
+    assert!(err.is_unauthorized());
+
+    let auth = client.login("user", "password").await.unwrap();
+    assert_eq!(auth.user.username, "user");
+    assert!(!auth.user.is_admin());
+    assert!(auth.expires_in > 0);
+    assert_eq!(client.token().as_deref(), Some(auth.token.as_str()));
+
+    let me = client.me().await.unwrap();
+    assert_eq!(me.id, auth.user.id);
+    assert_eq!(me.email, "user@test.com");
+    assert_fields_sent(&me, &raw(&client, "/auth/me").await);
+
+    let refreshed = client.refresh().await.unwrap();
+    assert_ne!(refreshed.refresh_token, auth.refresh_token);
+    assert_eq!(client.me().await.unwrap().id, me.id);
+
+    client.logout().await.unwrap();
+    assert_eq!(client.token(), None);
+    assert!(client.me().await.unwrap_err().is_unauthorized());
+
+    let registered = client
+        .register(&RegisterRequest {
+            username: "newbie".to_string(),
+            email: "newbie@test.com".to_string(),
+            password: "password123".to_string(),
+            invite_code: None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(registered.user.role, "user");
+    assert_eq!(client.me().await.unwrap().username, "newbie");
+}
+
+#[tokio::test]
+async fn test_movies() {
+    let app = create_test_app().await;
+    let client = signed_in(&app).await;
+
+    let movie = client
+        .create_movie(&CreateMovie {
+            title: "The Matrix".to_string(),
+            barcode: Some("5051890001234".to_string()),
+            disc_type: Some("Blu-ray".to_string()),
+            production_year: Some(1999),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(movie.title, "The Matrix");
+    assert_eq!(movie.production_year, Some(1999));
+    assert!(!movie.watched);
+    client
+        .create_movie(&CreateMovie {
+            title: "Heat".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    let fetched = client.get_movie(movie.id).await.unwrap();
+    assert_eq!(fetched.barcode.as_deref(), Some("5051890001234"));
+    assert_fields_sent(
+        &fetched,
+        &raw(&client, &format!("/movies/{}", movie.id)).await,
+    );
+
+    let list = client
+        .list_movies(&MovieFilter {
+            search: Some("matrix".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(list.total, 1);
+    assert_eq!(list.items[0].id, movie.id);
+    let raw_list = raw(&client, "/movies?search=matrix").await;
+    assert_fields_sent(&list, &raw_list);
+    assert_fields_sent(&list.items[0], &raw_list["items"][0]);
+
+    // Umlauts and accents: "Koenig" finds "König"
+    let konig = client
+        .create_movie(&CreateMovie {
+            title: "Der König der Löwen".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let raw_list = raw(&client, "/movies?search=Koenig").await;
+    assert_eq!(raw_list["total"], 1);
+    assert_eq!(raw_list["items"][0]["id"], json!(konig.id));
+
+    let updated = client
+        .update_movie(
+            movie.id,
+            &UpdateMovie {
+                watched: Some(true),
+                personal_rating: Some(4.5),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert!(updated.watched);
+    assert_eq!(updated.personal_rating, Some(4.5));
+    assert_eq!(updated.title, "The Matrix");
+
+    let watched = client
+        .list_movies(&MovieFilter {
+            watched: Some(true),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(watched.total, 1);
+
+    // Without a TMDB key the server says so with a code the client can match
+    match client
+        .refresh_movie_tmdb(movie.id, false)
+        .await
+        .unwrap_err()
+    {
+        Error::Api { status, code, .. } => {
+            assert_eq!(status, 503);
+            assert_eq!(code.as_deref(), Some("tmdb_unconfigured"));
+        }
+        other => panic!("unexpected {other:?}"),
+    }
+
+    client.delete_movie(movie.id).await.unwrap();
+    assert!(client.get_movie(movie.id).await.unwrap_err().is_not_found());
+}
+
+#[tokio::test]
+async fn test_collections() {
+    let app = create_test_app().await;
+    let client = signed_in(&app).await;
+
+    let collection = client
+        .create_collection(&CreateCollection {
+            title: "Trilogy Box".to_string(),
+            disc_type: Some("Blu-ray".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(collection.title, "Trilogy Box");
+
+    let fetched = client.get_collection(collection.id).await.unwrap();
+    assert_eq!(fetched.disc_type.as_deref(), Some("Blu-ray"));
+    assert_fields_sent(
+        &fetched,
+        &raw(&client, &format!("/collections/{}", collection.id)).await,
+    );
+
+    let updated = client
+        .update_collection(
+            collection.id,
+            &UpdateCollection {
+                title: Some("Trilogy Box Set".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(updated.title, "Trilogy Box Set");
+
+    let movie = client
+        .create_movie(&CreateMovie {
+            title: "Alien".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let item = client
+        .add_collection_item(collection.id, &AddCollectionItem::movie(movie.id))
+        .await
+        .unwrap();
+    assert_eq!(item.item_type, CollectionItemType::Movie);
+    assert_eq!(item.movie_id, Some(movie.id));
+
+    let items = client.collection_items(collection.id).await.unwrap();
+    assert_eq!(items.len(), 1);
+    let raw_items = raw(&client, &format!("/collections/{}/items", collection.id)).await;
+    assert_fields_sent(&items[0], &raw_items[0]);
+
+    let containing = client
+        .list_collections(&CollectionFilter {
+            contains_movie: Some(movie.id),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(containing.len(), 1);
+    assert_eq!(containing[0].id, collection.id);
+
+    client
+        .remove_collection_item(collection.id, item.id)
+        .await
+        .unwrap();
+    assert!(
+        client
+            .collection_items(collection.id)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    client.delete_collection(collection.id).await.unwrap();
+    assert!(
+        client
+            .get_collection(collection.id)
+            .await
+            .unwrap_err()
+            .is_not_found()
+    );
+}
+
+#[tokio::test]
+async fn test_scan_and_search() {
+    let app = create_test_app().await;
+    let client = signed_in(&app).await;
+
+    // A cached lookup needs neither the barcode providers nor TMDB
+    app.state
+        .barcode_cache_service
+        .put(&BarcodeCacheEntry {
+            barcode: "5050582721478".to_string(),
+            title: Some("The Matrix".to_string()),
+            source: Some("upcitemdb".to_string()),
+            disc_type: Some("Blu-ray".to_string()),
+            tmdb_id: Some(603),
+            tmdb_results: Some(
+                json!([{
+                    "id": 603,
+                    "title": "The Matrix",
+                    "year": "1999",
+                    "poster_url": null,
+                    "poster_path": null
+                }])
+                .to_string(),
+            ),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    let lookup = client.scan_barcode("5050582721478", false).await.unwrap();
+    assert!(lookup.cached);
+    assert_eq!(lookup.title.as_deref(), Some("The Matrix"));
+    assert_eq!(lookup.disc_type.as_deref(), Some("Blu-ray"));
+    assert_eq!(lookup.tmdb_results.len(), 1);
+    assert_eq!(lookup.tmdb_results[0].id, 603);
+    assert_eq!(lookup.tmdb_results[0].year.as_deref(), Some("1999"));
+
+    let err = client
+        .search_tmdb_movies("matrix", Some(1999))
+        .await
+        .unwrap_err();
+    assert_eq!(err.status(), Some(503));
+}
+
+#[tokio::test]
+async fn test_import_csv() {
+    let app = create_test_app().await;
+    let client = signed_in(&app).await;
+
+    let result = client
+        .import_csv(b"Title,Year\nAlien,1979\nHeat,1995\n".to_vec(), None)
+        .await
+        .unwrap();
+    assert_eq!(result.movies_imported, 2);
+    assert!(result.errors.is_empty());
+
+    let movies = client.list_movies(&MovieFilter::default()).await.unwrap();
+    assert_eq!(movies.total, 2);
+
+    let err = client
+        .import_csv(b"Title\nAlien\n".to_vec(), Some("No such preset"))
+        .await
+        .unwrap_err();
+    assert!(err.is_not_found());
+}
@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use my_movies_core::services::demo::DEMO_MOVIES;
+use serde_json::json;
+
+use crate::AppState;
+use crate::routes::movies::download_poster_image;
+use crate::routes::ws::WsEvent;
+
+/// Seed the demo library now and reset it every `reset_minutes` minutes
+pub fn spawn_demo_reset(state: Arc<AppState>, reset_minutes: u64) {
+    tokio::spawn(async move {
+        // Posters are fetched once and reused for every reset
+        let mut posters: HashMap<i64, Vec<u8>> = HashMap::new();
+        let mut interval = tokio::time::interval(Duration::from_secs(reset_minutes.max(1) * 60));
+
+        loop {
+            interval.tick().await;
+
+            fetch_missing_posters(&state, &mut posters).await;
+
+            match state.demo_service.reset(&posters).await {
+                Ok(_) => {
+                    state.thumbnail_cache.write().await.clear();
+                    let msg = json!({ "type": "demo_reset", "payload": {} });
+                    let _ = state.ws_broadcast.send(WsEvent::everyone(&msg));
+                    tracing::info!("Demo library reset ({} movies)", DEMO_MOVIES.len());
+                }
+                Err(e) => tracing::error!("Failed to reset demo library: {}", e),
+            }
+        }
+    });
+}
+
+async fn fetch_missing_posters(state: &AppState, posters: &mut HashMap<i64, Vec<u8>>) {
+    let size = state.tmdb_service.options(None, None).poster_size;
+    for demo in DEMO_MOVIES {
+        if posters.contains_key(&demo.tmdb_id) {
+            continue;
+        }
+
+        let poster_path = match state
+            .tmdb_service
+            .get_movie_details(demo.tmdb_id, None)
+            .await
+        {
+            Ok(details) => details.poster_path,
+            Err(e) => {
+                tracing::debug!("No demo poster for {}: {}", demo.title, e);
+                continue;
+            }
+        };
+
+        if let Some(path) = poster_path
+            && let Some(data) = download_poster_image(&path, &size).await
+        {
+            posters.insert(demo.tmdb_id, data);
+        }
+    }
+}
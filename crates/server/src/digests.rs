@@ -0,0 +1,50 @@
+//! Sends the weekly digest emails. Users choose the weekday and hour, so the
+//! job checks every hour who is due.
+
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use my_movies_core::services::{DigestService, Mailer};
+
+use crate::AppState;
+
+/// How often due digests are looked for
+const DIGEST_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Send due digests periodically in the background, except in read-only mode
+pub fn spawn_digests(state: Arc<AppState>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(DIGEST_INTERVAL);
+        loop {
+            interval.tick().await;
+            // Taking due digests stores when they were sent
+            if state.read_only.load(Ordering::Relaxed) {
+                continue;
+            }
+            send_due(&state).await;
+        }
+    });
+}
+
+async fn send_due(state: &AppState) {
+    let digests = match state.digest_service.take_due(chrono::Utc::now()).await {
+        Ok(digests) => digests,
+        Err(e) => {
+            tracing::error!("Failed to check weekly digests: {}", e);
+            return;
+        }
+    };
+
+    for digest in digests {
+        let email = DigestService::render(&digest);
+        match state.mail_service.send(&email).await {
+            Ok(()) => tracing::debug!(
+                "Sent weekly digest to {} via {}",
+                digest.username,
+                state.mail_service.name()
+            ),
+            Err(e) => tracing::warn!("Failed to send weekly digest to {}: {}", digest.username, e),
+        }
+    }
+}
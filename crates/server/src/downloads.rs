@@ -0,0 +1,287 @@
+//! Large downloads (backups, database snapshots) served from temporary files,
+//! so clients can ask for the size with `HEAD` and resume with byte ranges.
+//! A file is kept for a while after it was written; range requests within
+//! that time get the same bytes, identified by the `ETag`.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use axum::body::Body;
+use axum::http::{HeaderMap, HeaderValue, Method, StatusCode, header};
+use axum::response::Response;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use uuid::Uuid;
+
+use crate::ApiError;
+
+/// How long a written download can be resumed
+pub const DOWNLOAD_TTL: Duration = Duration::from_secs(60 * 60);
+/// Bytes read from the file per body chunk
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// A finished download on disk
+#[derive(Debug, Clone)]
+pub struct Download {
+    /// Quoted entity tag, e.g. `"0b6f…"`
+    pub etag: String,
+    pub path: PathBuf,
+    pub filename: String,
+    created_at: Instant,
+}
+
+impl Download {
+    /// A new download to be written to a fresh temporary file
+    pub fn new(filename: String) -> Self {
+        let id = Uuid::new_v4().simple().to_string();
+        Self {
+            path: std::env::temp_dir().join(format!("my-movies-download-{}", id)),
+            etag: format!("\"{}\"", id),
+            filename,
+            created_at: Instant::now(),
+        }
+    }
+
+    fn is_fresh(&self) -> bool {
+        self.created_at.elapsed() < DOWNLOAD_TTL
+    }
+
+    /// Remove the file; a missing file is not an error
+    pub fn remove(&self) {
+        if let Err(e) = std::fs::remove_file(&self.path)
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            tracing::warn!("Failed to remove {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+/// Finished downloads by key (e.g. user and export filter)
+#[derive(Default)]
+pub struct DownloadCache {
+    downloads: Mutex<HashMap<String, Download>>,
+}
+
+impl DownloadCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The download stored under `key`, unless it expired
+    pub fn get(&self, key: &str) -> Option<Download> {
+        let downloads = self.downloads.lock().ok()?;
+        downloads
+            .get(key)
+            .filter(|d| d.is_fresh() && d.path.exists())
+            .cloned()
+    }
+
+    /// Store a finished download, removing the one it replaces and expired ones
+    pub fn insert(&self, key: String, download: Download) {
+        let Ok(mut downloads) = self.downloads.lock() else {
+            download.remove();
+            return;
+        };
+        if let Some(replaced) = downloads.insert(key, download) {
+            replaced.remove();
+        }
+        downloads.retain(|_, d| {
+            if d.is_fresh() {
+                return true;
+            }
+            d.remove();
+            false
+        });
+    }
+}
+
+/// What a `Range` header asks for, given the size of the file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeRequest {
+    Full,
+    /// First and last byte, inclusive
+    Partial(u64, u64),
+    Unsatisfiable,
+}
+
+/// Parse a `Range` header. Only single `bytes` ranges are supported; other
+/// units and multiple ranges get the whole file, as RFC 9110 allows.
+pub fn parse_range(header: Option<&str>, len: u64) -> RangeRequest {
+    let Some(spec) = header.and_then(|h| h.trim().strip_prefix("bytes=")) else {
+        return RangeRequest::Full;
+    };
+    if spec.contains(',') {
+        return RangeRequest::Full;
+    }
+    let Some((start, end)) = spec.trim().split_once('-') else {
+        return RangeRequest::Full;
+    };
+
+    let range = match (start.trim(), end.trim()) {
+        // Last n bytes
+        ("", suffix) => match suffix.parse::<u64>() {
+            Ok(0) | Err(_) => return RangeRequest::Unsatisfiable,
+            Ok(n) => (len.saturating_sub(n), len.saturating_sub(1)),
+        },
+        (start, "") => match start.parse::<u64>() {
+            Ok(start) => (start, len.saturating_sub(1)),
+            Err(_) => return RangeRequest::Full,
+        },
+        (start, end) => match (start.parse::<u64>(), end.parse::<u64>()) {
+            (Ok(start), Ok(end)) if start <= end => (start, end.min(len.saturating_sub(1))),
+            _ => return RangeRequest::Full,
+        },
+    };
+
+    if len == 0 || range.0 >= len {
+        return RangeRequest::Unsatisfiable;
+    }
+    if range == (0, len - 1) {
+        return RangeRequest::Full;
+    }
+    RangeRequest::Partial(range.0, range.1)
+}
+
+/// Answer a `GET` or `HEAD` for a finished download, honoring `Range` and
+/// `If-Range`
+pub async fn serve(
+    download: &Download,
+    method: &Method,
+    headers: &HeaderMap,
+    content_type: &str,
+) -> Result<Response, ApiError> {
+    let len = tokio::fs::metadata(&download.path)
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to read download: {}", e)))?
+        .len();
+
+    // A range of another version of the file would corrupt the result
+    let if_range_matches = headers
+        .get(header::IF_RANGE)
+        .is_none_or(|v| v.as_bytes() == download.etag.as_bytes());
+    let range = match headers.get(header::RANGE) {
+        Some(range) if if_range_matches => parse_range(range.to_str().ok(), len),
+        _ => RangeRequest::Full,
+    };
+
+    let builder = Response::builder()
+        .header(header::CONTENT_TYPE, content_type)
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", download.filename),
+        )
+        .header(header::ACCEPT_RANGES, "bytes")
+        .header(header::ETAG, download.etag.as_str());
+
+    let (builder, start, count) = match range {
+        RangeRequest::Full => (builder.status(StatusCode::OK), 0, len),
+        RangeRequest::Partial(start, end) => (
+            builder.status(StatusCode::PARTIAL_CONTENT).header(
+                header::CONTENT_RANGE,
+                format!("bytes {}-{}/{}", start, end, len),
+            ),
+            start,
+            end - start + 1,
+        ),
+        RangeRequest::Unsatisfiable => {
+            return Response::builder()
+                .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                .header(header::CONTENT_RANGE, format!("bytes */{}", len))
+                .body(Body::empty())
+                .map_err(|e| ApiError::internal(format!("Failed to build response: {}", e)));
+        }
+    };
+    let builder = builder.header(header::CONTENT_LENGTH, HeaderValue::from(count));
+
+    if method == Method::HEAD {
+        return builder
+            .body(Body::empty())
+            .map_err(|e| ApiError::internal(format!("Failed to build response: {}", e)));
+    }
+
+    let mut file = tokio::fs::File::open(&download.path)
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to open download: {}", e)))?;
+    file.seek(std::io::SeekFrom::Start(start))
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to read download: {}", e)))?;
+
+    let chunks = futures::stream::unfold((file, count), |(mut file, remaining)| async move {
+        if remaining == 0 {
+            return None;
+        }
+        let mut buf = vec![0; CHUNK_SIZE.min(remaining as usize)];
+        match file.read(&mut buf).await {
+            Ok(0) => None,
+            Ok(n) => {
+                buf.truncate(n);
+                Some((Ok(buf), (file, remaining - n as u64)))
+            }
+            Err(e) => Some((Err(e), (file, 0))),
+        }
+    });
+
+    builder
+        .body(Body::from_stream(chunks))
+        .map_err(|e| ApiError::internal(format!("Failed to build response: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range(None, 100), RangeRequest::Full);
+        assert_eq!(
+            parse_range(Some("bytes=10-19"), 100),
+            RangeRequest::Partial(10, 19)
+        );
+        assert_eq!(
+            parse_range(Some("bytes=90-"), 100),
+            RangeRequest::Partial(90, 99)
+        );
+        assert_eq!(
+            parse_range(Some("bytes=-10"), 100),
+            RangeRequest::Partial(90, 99)
+        );
+        // The end is capped at the file size
+        assert_eq!(
+            parse_range(Some("bytes=50-500"), 100),
+            RangeRequest::Partial(50, 99)
+        );
+        assert_eq!(parse_range(Some("bytes=0-"), 100), RangeRequest::Full);
+        assert_eq!(
+            parse_range(Some("bytes=100-"), 100),
+            RangeRequest::Unsatisfiable
+        );
+        assert_eq!(
+            parse_range(Some("bytes=-0"), 100),
+            RangeRequest::Unsatisfiable
+        );
+        // Unsupported forms get the whole file
+        assert_eq!(parse_range(Some("bytes=0-1,5-6"), 100), RangeRequest::Full);
+        assert_eq!(parse_range(Some("items=0-1"), 100), RangeRequest::Full);
+        assert_eq!(parse_range(Some("bytes=9-2"), 100), RangeRequest::Full);
+    }
+
+    #[test]
+    fn test_download_cache() {
+        let cache = DownloadCache::new();
+        let first = Download::new("a.zip".to_string());
+        std::fs::write(&first.path, b"first").unwrap();
+        cache.insert("key".to_string(), first.clone());
+        assert_eq!(cache.get("key").unwrap().etag, first.etag);
+        assert!(cache.get("other").is_none());
+
+        // Replacing a download removes its file
+        let second = Download::new("b.zip".to_string());
+        std::fs::write(&second.path, b"second").unwrap();
+        cache.insert("key".to_string(), second.clone());
+        assert!(!first.path.exists());
+        assert_eq!(cache.get("key").unwrap().etag, second.etag);
+        second.remove();
+        assert!(cache.get("key").is_none());
+    }
+}
@@ -9,6 +9,8 @@ use serde_json::json;
 pub struct ApiError {
     pub status: StatusCode,
     pub message: String,
+    /// Sent as `code` next to `error`
+    pub code: Option<&'static str>,
 }
 
 impl ApiError {
@@ -16,6 +18,7 @@ impl ApiError {
         Self {
             status,
             message: message.into(),
+            code: None,
         }
     }
 
@@ -38,11 +41,26 @@ impl ApiError {
     pub fn conflict(message: impl Into<String>) -> Self {
         Self::new(StatusCode::CONFLICT, message)
     }
+
+    /// 503 for routes that need TMDB while no API key is set
+    pub fn tmdb_unconfigured() -> Self {
+        my_movies_core::error::Error::TmdbNotConfigured.into()
+    }
 }
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
-        (self.status, Json(json!({ "error": self.message }))).into_response()
+        let body = match self.code {
+            Some(code) => json!({ "error": self.message, "code": code }),
+            None => json!({ "error": self.message }),
+        };
+        let mut response = (self.status, Json(body)).into_response();
+        if self.status.is_server_error() {
+            response
+                .extensions_mut()
+                .insert(crate::error_reporting::ErrorMessage(self.message));
+        }
+        response
     }
 }
 
@@ -51,7 +69,10 @@ impl From<my_movies_core::error::Error> for ApiError {
     fn from(err: my_movies_core::error::Error) -> Self {
         let status =
             StatusCode::from_u16(err.status_code()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
-        Self::new(status, err.to_string())
+        Self {
+            code: err.code(),
+            ..Self::new(status, err.to_string())
+        }
     }
 }
 
@@ -0,0 +1,132 @@
+use std::sync::{Arc, RwLock};
+
+use serde::Serialize;
+
+/// Release version included in every report
+const RELEASE: &str = env!("CARGO_PKG_VERSION");
+
+/// Error message attached to 5xx responses so the error report middleware
+/// can include it without buffering the response body
+#[derive(Debug, Clone)]
+pub struct ErrorMessage(pub String);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorReportKind {
+    Panic,
+    ServerError,
+}
+
+/// Payload posted to the configured error report endpoint
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorReport {
+    pub kind: ErrorReportKind,
+    pub message: String,
+    pub release: &'static str,
+    pub os: &'static str,
+    pub timestamp: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request: Option<RequestContext>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RequestContext {
+    pub method: String,
+    /// Path without query string (queries may contain tokens)
+    pub path: String,
+    pub status: u16,
+}
+
+impl ErrorReport {
+    pub fn new(kind: ErrorReportKind, message: String) -> Self {
+        Self {
+            kind,
+            message,
+            release: RELEASE,
+            os: std::env::consts::OS,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            location: None,
+            request: None,
+        }
+    }
+}
+
+/// Sends panics and 5xx errors as JSON to an external endpoint
+/// (e.g. a Sentry-compatible relay or any webhook). Disabled while no URL is set.
+pub struct ErrorReporter {
+    client: reqwest::Client,
+    url: RwLock<String>,
+}
+
+impl ErrorReporter {
+    pub fn new(url: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: RwLock::new(url),
+        }
+    }
+
+    /// Update the report endpoint at runtime (empty disables reporting)
+    pub fn set_url(&self, url: String) {
+        if let Ok(mut current) = self.url.write() {
+            *current = url;
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.url.read().map(|u| !u.is_empty()).unwrap_or(false)
+    }
+
+    /// Send a report in the background. Never fails; delivery errors are logged.
+    pub fn report(&self, report: ErrorReport) {
+        let url = self.url.read().map(|u| u.clone()).unwrap_or_default();
+        if url.is_empty() {
+            return;
+        }
+
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+
+        let client = self.client.clone();
+        handle.spawn(async move {
+            match client.post(&url).json(&report).send().await {
+                Ok(response) if !response.status().is_success() => {
+                    tracing::warn!("Error report rejected: {}", response.status());
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Failed to send error report: {}", e),
+            }
+        });
+    }
+}
+
+/// Install a panic hook that forwards panics to the reporter.
+/// The previous hook (default: print to stderr) still runs, so each call adds
+/// another hook; call it once per process (`start_server`).
+pub fn install_panic_hook(reporter: Arc<ErrorReporter>) {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        previous(info);
+
+        if !reporter.is_enabled() {
+            return;
+        }
+
+        let message = if let Some(s) = info.payload().downcast_ref::<&str>() {
+            s.to_string()
+        } else if let Some(s) = info.payload().downcast_ref::<String>() {
+            s.clone()
+        } else {
+            "unknown panic".to_string()
+        };
+
+        let mut report = ErrorReport::new(ErrorReportKind::Panic, message);
+        report.location = info
+            .location()
+            .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()));
+        reporter.report(report);
+    }));
+}
@@ -0,0 +1,155 @@
+//! Long-running work started by a request (e.g. splitting a box set). The request
+//! returns a job ID right away; progress is reported via WebSocket events and the
+//! job can be queried and cancelled by its owner.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use uuid::Uuid;
+
+/// Finished jobs stay queryable this long
+const FINISHED_TTL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Running,
+    Completed,
+    Cancelled,
+}
+
+/// Snapshot of a job for the API
+#[derive(Debug, Clone, Serialize)]
+pub struct JobInfo {
+    pub id: Uuid,
+    pub kind: &'static str,
+    pub status: JobStatus,
+    pub current: usize,
+    pub total: usize,
+}
+
+pub struct Job {
+    pub id: Uuid,
+    user_id: Uuid,
+    kind: &'static str,
+    total: usize,
+    current: AtomicUsize,
+    cancelled: AtomicBool,
+    finished_at: Mutex<Option<Instant>>,
+}
+
+impl Job {
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    pub fn set_progress(&self, current: usize) {
+        self.current.store(current, Ordering::SeqCst);
+    }
+
+    pub fn finish(&self) {
+        if let Ok(mut finished_at) = self.finished_at.lock() {
+            *finished_at = Some(Instant::now());
+        }
+    }
+
+    fn finished_at(&self) -> Option<Instant> {
+        self.finished_at.lock().ok().and_then(|f| *f)
+    }
+
+    pub fn info(&self) -> JobInfo {
+        let status = match (self.finished_at().is_some(), self.is_cancelled()) {
+            (false, _) => JobStatus::Running,
+            (true, false) => JobStatus::Completed,
+            (true, true) => JobStatus::Cancelled,
+        };
+        JobInfo {
+            id: self.id,
+            kind: self.kind,
+            status,
+            current: self.current.load(Ordering::SeqCst),
+            total: self.total,
+        }
+    }
+}
+
+/// Jobs of all users, in memory
+#[derive(Default)]
+pub struct JobRegistry {
+    jobs: Mutex<HashMap<Uuid, Arc<Job>>>,
+}
+
+impl JobRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a new running job with `total` steps
+    pub fn start(&self, user_id: Uuid, kind: &'static str, total: usize) -> Arc<Job> {
+        let job = Arc::new(Job {
+            id: Uuid::new_v4(),
+            user_id,
+            kind,
+            total,
+            current: AtomicUsize::new(0),
+            cancelled: AtomicBool::new(false),
+            finished_at: Mutex::new(None),
+        });
+
+        if let Ok(mut jobs) = self.jobs.lock() {
+            jobs.retain(|_, j| j.finished_at().is_none_or(|f| f.elapsed() < FINISHED_TTL));
+            jobs.insert(job.id, job.clone());
+        }
+        job
+    }
+
+    /// A job of `user_id`; other users' jobs are not visible
+    pub fn get(&self, user_id: Uuid, id: Uuid) -> Option<Arc<Job>> {
+        let jobs = self.jobs.lock().ok()?;
+        jobs.get(&id).filter(|j| j.user_id == user_id).cloned()
+    }
+
+    /// Ask a running job to stop. Returns `None` if there is no such job.
+    pub fn cancel(&self, user_id: Uuid, id: Uuid) -> Option<JobInfo> {
+        let job = self.get(user_id, id)?;
+        if job.finished_at().is_none() {
+            job.cancelled.store(true, Ordering::SeqCst);
+        }
+        Some(job.info())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_job_lifecycle() {
+        let registry = JobRegistry::new();
+        let user_id = Uuid::new_v4();
+
+        let job = registry.start(user_id, "collection_split", 3);
+        job.set_progress(1);
+        let info = registry.get(user_id, job.id).unwrap().info();
+        assert_eq!(info.status, JobStatus::Running);
+        assert_eq!((info.current, info.total), (1, 3));
+
+        // Other users can't see or cancel the job
+        assert!(registry.get(Uuid::new_v4(), job.id).is_none());
+        assert!(registry.cancel(Uuid::new_v4(), job.id).is_none());
+
+        registry.cancel(user_id, job.id).unwrap();
+        assert!(job.is_cancelled());
+        job.finish();
+        assert_eq!(job.info().status, JobStatus::Cancelled);
+
+        let done = registry.start(user_id, "collection_split", 1);
+        done.finish();
+        // Cancelling a finished job has no effect
+        let info = registry.cancel(user_id, done.id).unwrap();
+        assert_eq!(info.status, JobStatus::Completed);
+    }
+}
@@ -1,12 +1,8 @@
 use std::sync::Arc;
 
-use axum::{
-    Router,
-    extract::DefaultBodyLimit,
-    routing::{delete, get, post},
-};
+use axum::{Router, extract::DefaultBodyLimit, http::HeaderValue, routing::get};
 use tower_http::{
-    cors::CorsLayer,
+    cors::{AllowOrigin, Any, CorsLayer},
     limit::RequestBodyLimitLayer,
     services::{ServeDir, ServeFile},
     trace::TraceLayer,
@@ -14,33 +10,127 @@ use tower_http::{
 
 use my_movies_core::{
     Config,
-    db::create_pool,
+    db::create_pool_with,
+    models::{ApiScope, UserRole},
     services::{
-        AuthService, CollectionService, EanService, ImportService, MovieService, SeriesService,
-        SettingsService, TmdbService,
+        ActivityService, ApiKeyService, AuthService, BarcodeCacheService, CollectionService,
+        ContactService, CurrencyService, DemoService, DiagnosticsService, DigestService,
+        DiscordService, EanService, HouseholdService, ImageService, ImportService, InviteService,
+        JwtKeys, LoanService, MailService, MovieService, NotificationService, OcrService,
+        OmdbService, PlannerService, PosterRetryService, RepairService, SearchService,
+        SeriesService, SettingsService, ShareService, SyncService, TmdbService, TokenCipher,
+        TombstoneService, ValuationService, WatchService,
     },
 };
 
+pub mod backup;
+pub mod demo;
+pub mod digests;
+pub mod downloads;
 pub mod error;
+pub mod error_reporting;
+pub mod jobs;
+pub mod logging;
+pub mod maintenance;
 pub mod middleware;
+pub mod migrate_db;
+pub mod openapi;
+pub mod peer_sync;
+pub mod poster_retries;
+pub mod poster_transcode;
+pub mod rate_limit;
+pub mod reconcile;
+pub mod request_metrics;
+pub mod route_table;
 pub mod routes;
+pub mod seed;
+pub mod spine_index;
+pub mod tmdb_proxy;
+
+#[cfg(test)]
+mod contract_tests;
+#[cfg(test)]
+pub mod test_helpers;
 
 pub use error::{ApiError, ApiResult};
+use error_reporting::ErrorReporter;
+use route_table::RouteTable;
 
-use routes::{auth, collections, import, movies, scan, series, settings, users, ws};
+use routes::{
+    activity, admin, api_keys, auth, capabilities, collections, contacts, households, import,
+    invites, loans, movies, notifications, planner, scan, search, series, settings, shares, sync,
+    users, valuation, ws,
+};
 
 pub struct AppState {
     pub auth_service: AuthService,
+    pub api_key_service: ApiKeyService,
     pub movie_service: MovieService,
     pub series_service: SeriesService,
+    pub planner_service: PlannerService,
     pub collection_service: CollectionService,
+    pub contact_service: ContactService,
     pub tmdb_service: TmdbService,
+    pub omdb_service: OmdbService,
     pub ean_service: EanService,
+    pub barcode_cache_service: BarcodeCacheService,
+    /// Reads titles from cover photos for `/movies/identify-cover`
+    pub ocr_service: OcrService,
     pub import_service: ImportService,
+    pub tombstone_service: TombstoneService,
+    pub activity_service: ActivityService,
+    pub sync_service: SyncService,
+    pub loan_service: LoanService,
+    pub digest_service: DigestService,
+    /// Delivers emails; writes them to the log until a mail server is configured
+    pub mail_service: MailService,
+    pub search_service: SearchService,
+    pub image_service: ImageService,
+    pub currency_service: CurrencyService,
+    pub valuation_service: ValuationService,
     pub settings_service: SettingsService,
-    pub ws_broadcast: tokio::sync::broadcast::Sender<String>,
+    pub discord_service: DiscordService,
+    pub error_reporter: Arc<ErrorReporter>,
+    pub demo_service: DemoService,
+    pub diagnostics_service: DiagnosticsService,
+    pub repair_service: RepairService,
+    pub watch_service: WatchService,
+    pub notification_service: NotificationService,
+    pub share_service: ShareService,
+    pub household_service: HouseholdService,
+    pub invite_service: InviteService,
+    pub poster_retry_service: PosterRetryService,
+    pub ws_broadcast: tokio::sync::broadcast::Sender<routes::ws::WsEvent>,
+    /// Background jobs (collection splits), by job ID
+    pub jobs: jobs::JobRegistry,
+    /// Per-user budget for the TMDB search proxy
+    pub tmdb_rate_limiter: rate_limit::RateLimiter,
+    /// Per-link budget for borrow requests through public share links
+    pub borrow_rate_limiter: rate_limit::RateLimiter,
+    /// Password reset emails per email address
+    pub password_reset_rate_limiter: rate_limit::RateLimiter<String>,
+    /// Results of recent TMDB searches, shared by all users
+    pub tmdb_search_cache: tmdb_proxy::SearchCache,
+    /// Latency and request counts of the last 24 hours for `/admin/metrics`
+    pub request_metrics: request_metrics::RequestMetrics,
     /// Cache for generated thumbnails (movie_id -> thumbnail bytes)
     pub thumbnail_cache: tokio::sync::RwLock<std::collections::HashMap<uuid::Uuid, Vec<u8>>>,
+    /// WebP/AVIF versions of posters, keyed by image hash
+    pub poster_transcode_cache: poster_transcode::TranscodeCache,
+    /// Recent backups and database snapshots, kept for HEAD and range requests
+    pub downloads: downloads::DownloadCache,
+    /// Directory containing rotated log files (None if file logging is disabled)
+    pub log_dir: Option<String>,
+    /// Allowed CORS origins (empty = allow any origin)
+    pub cors_allowed_origins: Vec<String>,
+    /// Public demo mode: destructive endpoints are disabled
+    pub demo_mode: bool,
+    /// Serve Swagger UI at `/api/docs`
+    pub swagger_ui: bool,
+    /// Read-only mode: all changes are rejected (READ_ONLY env var or setting)
+    pub read_only: std::sync::atomic::AtomicBool,
+    /// When the server was started (for uptime in diagnostics)
+    pub started_at: std::time::Instant,
 }
 
 /// Configuration for starting the server
@@ -50,6 +140,10 @@ pub struct ServerConfig {
     /// Directory containing the frontend static files (index.html, assets, etc.)
     /// If None, no static files are served (API-only mode)
     pub static_dir: Option<String>,
+    /// Path to a config.toml file (falls back to $MY_MOVIES_CONFIG, then ./config.toml)
+    pub config_path: Option<std::path::PathBuf>,
+    /// Start without applying pending database migrations
+    pub skip_migrations: bool,
 }
 
 impl Default for ServerConfig {
@@ -58,56 +152,223 @@ impl Default for ServerConfig {
             host: "127.0.0.1".to_string(),
             port: 3000,
             static_dir: None,
+            config_path: None,
+            skip_migrations: false,
         }
     }
 }
 
 /// Creates the application state with all services initialized
 pub async fn create_app_state(config: &Config) -> anyhow::Result<Arc<AppState>> {
+    // Fail before touching the database if the signing keys are unusable
+    let jwt_keys = JwtKeys::from_config(config)?;
+    tracing::info!("Signing session tokens with {:?}", jwt_keys.algorithm());
+
     // Create database pool
-    let pool = create_pool(&config.database_url).await?;
+    let pool = create_pool_with(&config.database_url, config.database_options()).await?;
     tracing::info!("Database connected");
 
+    // Posters stored before content-addressed images get their final keys
+    let rehashed = ImageService::new(pool.clone()).rehash_legacy().await?;
+    if rehashed > 0 {
+        tracing::info!("Moved {} existing posters into the image store", rehashed);
+    }
+
+    // Region codes and video standards entered before they were validated
+    let normalized = MovieService::new(pool.clone())
+        .normalize_disc_fields()
+        .await?;
+    if normalized > 0 {
+        tracing::info!("Normalized disc fields of {} movies", normalized);
+    }
+
+    // Folded titles for entries stored before they existed
+    let folded = SearchService::new(pool.clone())
+        .refresh_search_titles()
+        .await?;
+    if folded > 0 {
+        tracing::info!("Computed search titles of {} movies and series", folded);
+    }
+
     // Create broadcast channel for WebSocket
-    let (ws_tx, _) = tokio::sync::broadcast::channel::<String>(100);
+    let (ws_tx, _) = tokio::sync::broadcast::channel::<routes::ws::WsEvent>(100);
 
-    // Create settings service first to get TMDB API key
+    // Create settings service first: TMDB, OMDb and Discord read their keys
+    // from its snapshot on every call (env var has priority, then database,
+    // then the config file)
     let settings_service = SettingsService::new(pool.clone());
+    settings_service.reload().await?;
+
+    let tmdb_service = TmdbService::new(config.tmdb_api_key.clone())
+        .with_settings(settings_service.subscribe())
+        .with_cache_pool(pool.clone());
+    // OMDb is optional, only used as fallback and for ratings
+    let omdb_service = OmdbService::new(String::new()).with_settings(settings_service.subscribe());
+    // Discord notifications are disabled until a webhook URL is configured
+    let discord_service = DiscordService::new(
+        config.discord_webhook_url.clone().unwrap_or_default(),
+        config.discord_events.as_deref().unwrap_or_default(),
+    )
+    .with_settings(settings_service.subscribe());
+
+    // Mirror or maintenance window: reads only
+    let read_only = settings_service
+        .get_bool(my_movies_core::models::SettingKey::ReadOnly, false)
+        .await
+        .unwrap_or(false);
+
+    // New images go to the database until another store is configured
+    match settings_service.blob_store(None).await {
+        Ok(store) => my_movies_core::services::set_blob_store(store),
+        Err(e) => tracing::error!("Invalid image storage settings, using the database: {}", e),
+    }
+
+    // Barcode lookups: UPCitemdb and OpenGTINDB unless configured otherwise
+    let ean_service = EanService::new();
+    match settings_service.barcode_providers(None).await {
+        Ok(providers) => ean_service.set_providers(providers),
+        Err(e) => tracing::error!("Invalid barcode provider settings, using defaults: {}", e),
+    }
+
+    // Cover recognition uses a local tesseract unless configured otherwise
+    let ocr_service = OcrService::new();
+    match settings_service.ocr_provider(None).await {
+        Ok(provider) => ocr_service.set_provider(provider),
+        Err(e) => tracing::error!("Invalid OCR settings, cover recognition is disabled: {}", e),
+    }
+
+    // Push notifications are disabled until a provider is configured
+    let notification_service = NotificationService::new(pool.clone());
+    match settings_service.push_provider(None).await {
+        Ok(provider) => notification_service.set_push_provider(provider),
+        Err(e) => tracing::error!("Invalid push settings, push is disabled: {}", e),
+    }
+
+    // Emails are written to the log until an SMTP server is configured
+    let mail_service = MailService::new();
+    match settings_service.smtp_mailer(None).await {
+        Ok(smtp) => mail_service.set_smtp(smtp),
+        Err(e) => tracing::error!("Invalid mail settings, emails go to the log: {}", e),
+    }
 
-    // Get TMDB API key from settings (env var has priority, then database)
-    let tmdb_api_key = settings_service
-        .get(my_movies_core::models::SettingKey::TmdbApiKey)
+    // Error reporting is disabled until a report URL is configured
+    let error_report_url = settings_service
+        .get(my_movies_core::models::SettingKey::ErrorReportUrl)
         .await
         .ok()
         .flatten()
-        .unwrap_or_else(|| config.tmdb_api_key.clone());
+        .or_else(|| config.error_report_url.clone())
+        .unwrap_or_default();
+    let error_reporter = Arc::new(ErrorReporter::new(error_report_url));
+
+    // Share links are signed with their own key, not the session keys
+    let share_secret = ShareService::load_secret(&pool).await?;
+
+    // Encrypts pairing tokens with a key derived from the session secret
+    let sync_service = SyncService::new(pool.clone(), TokenCipher::from_config(config)?);
+    match sync_service.encrypt_stored_tokens().await {
+        Ok(0) => {}
+        Ok(count) => tracing::info!("Encrypted {} sync peer tokens", count),
+        Err(e) => tracing::warn!("Failed to encrypt sync peer tokens: {}", e),
+    }
 
     // Create services
     let state = Arc::new(AppState {
-        auth_service: AuthService::new(pool.clone(), config.jwt_secret.clone()),
+        auth_service: AuthService::with_keys(pool.clone(), jwt_keys),
+        api_key_service: ApiKeyService::new(pool.clone()),
         movie_service: MovieService::new(pool.clone()),
         series_service: SeriesService::new(pool.clone()),
+        planner_service: PlannerService::new(pool.clone()),
         collection_service: CollectionService::new(pool.clone()),
-        tmdb_service: TmdbService::new(tmdb_api_key),
-        ean_service: EanService::new(),
+        contact_service: ContactService::new(pool.clone()),
+        tmdb_service,
+        omdb_service,
+        ean_service,
+        barcode_cache_service: BarcodeCacheService::new(pool.clone()),
+        ocr_service,
         import_service: ImportService::new(pool.clone()),
+        tombstone_service: TombstoneService::new(pool.clone()),
+        activity_service: ActivityService::new(pool.clone()),
+        sync_service,
+        loan_service: LoanService::new(pool.clone()),
+        digest_service: DigestService::new(pool.clone()),
+        mail_service,
+        search_service: SearchService::new(pool.clone()),
+        image_service: ImageService::new(pool.clone()),
+        currency_service: CurrencyService::new(pool.clone()),
+        valuation_service: ValuationService::new(pool.clone()),
         settings_service,
+        discord_service,
+        error_reporter,
+        demo_service: DemoService::new(pool.clone()),
+        diagnostics_service: DiagnosticsService::new(pool.clone()),
+        repair_service: RepairService::new(pool.clone()),
+        watch_service: WatchService::new(pool.clone()),
+        notification_service,
+        share_service: ShareService::new(pool.clone(), share_secret),
+        household_service: HouseholdService::new(pool.clone()),
+        invite_service: InviteService::new(pool.clone()),
+        poster_retry_service: PosterRetryService::new(pool.clone()),
         ws_broadcast: ws_tx,
+        jobs: jobs::JobRegistry::new(),
+        tmdb_rate_limiter: rate_limit::RateLimiter::new(
+            tmdb_proxy::BUCKET_CAPACITY,
+            tmdb_proxy::REFILL_PER_SECOND,
+        ),
+        borrow_rate_limiter: rate_limit::RateLimiter::new(
+            routes::shares::BORROW_REQUEST_BURST,
+            routes::shares::BORROW_REQUESTS_PER_SECOND,
+        ),
+        password_reset_rate_limiter: rate_limit::RateLimiter::new(
+            routes::auth::PASSWORD_RESET_BURST,
+            routes::auth::PASSWORD_RESETS_PER_SECOND,
+        ),
+        tmdb_search_cache: tmdb_proxy::SearchCache::new(),
+        request_metrics: request_metrics::RequestMetrics::new(),
         thumbnail_cache: tokio::sync::RwLock::new(std::collections::HashMap::new()),
+        poster_transcode_cache: Default::default(),
+        downloads: downloads::DownloadCache::new(),
+        log_dir: config.log_dir.clone(),
+        cors_allowed_origins: config.cors_allowed_origins.clone(),
+        demo_mode: config.demo_mode,
+        swagger_ui: config.swagger_ui,
+        read_only: std::sync::atomic::AtomicBool::new(read_only),
+        started_at: std::time::Instant::now(),
     });
 
+    maintenance::spawn_maintenance(state.clone());
+    peer_sync::spawn_peer_sync(state.clone());
+    poster_retries::spawn_poster_retries(state.clone());
+    digests::spawn_digests(state.clone());
+
+    if read_only {
+        tracing::warn!("Read-only mode enabled: all changes are rejected");
+    }
+
+    if config.demo_mode {
+        tracing::warn!(
+            "Demo mode enabled: library resets every {} minutes (login: {}/{})",
+            config.demo_reset_minutes,
+            my_movies_core::services::demo::DEMO_USERNAME,
+            my_movies_core::services::demo::DEMO_PASSWORD
+        );
+        demo::spawn_demo_reset(state.clone(), config.demo_reset_minutes);
+    }
+
     Ok(state)
 }
 
 /// Creates the router with all routes configured
 pub fn create_router(state: Arc<AppState>, static_dir: Option<&str>) -> Router {
-    let mut router = Router::new()
-        // Public routes
-        .route("/api/v1/auth/register", post(auth::register))
-        .route("/api/v1/auth/login", post(auth::login))
-        .route("/api/v1/auth/forgot-password", post(auth::forgot_password))
-        .route("/api/v1/auth/reset-password", post(auth::reset_password))
-        .route("/health", get(health_check))
+    let cors = cors_layer(&state.cors_allowed_origins);
+    let swagger_ui = state.swagger_ui;
+
+    let mut router = public_routes()
+        .into_router()
+        .route_layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::metrics::metrics_middleware,
+        ))
         // Protected routes with increased body limit for file uploads (500MB for ZIP backup)
         .nest(
             "/api/v1",
@@ -117,10 +378,26 @@ pub fn create_router(state: Arc<AppState>, static_dir: Option<&str>) -> Router {
         )
         // WebSocket
         .route("/ws", get(ws::websocket_handler))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::demo::demo_guard,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::read_only::read_only_guard,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::error_report::error_report_middleware,
+        ))
         .layer(TraceLayer::new_for_http())
-        .layer(CorsLayer::permissive())
+        .layer(cors)
         .with_state(state);
 
+    if swagger_ui {
+        router = router.route("/api/docs", get(openapi::swagger_ui));
+    }
+
     // Serve static frontend files if directory is configured
     if let Some(dir) = static_dir {
         let index_path = format!("{}/index.html", dir);
@@ -141,127 +418,331 @@ pub fn create_router(state: Arc<AppState>, static_dir: Option<&str>) -> Router {
     router
 }
 
+/// Routes that need no authentication
+pub fn public_routes() -> RouteTable {
+    RouteTable::new()
+        .post("/api/v1/auth/register", auth::register)
+        .post("/api/v1/auth/login", auth::login)
+        .post("/api/v1/auth/refresh", auth::refresh)
+        .post("/api/v1/auth/forgot-password", auth::forgot_password)
+        .post("/api/v1/auth/reset-password", auth::reset_password)
+        .get("/api/v1/capabilities", capabilities::get)
+        .get("/api/v1/openapi.json", openapi::openapi_json)
+        // Share links: read-only, the token is the credential
+        .get("/api/v1/shared/{token}", shares::shared_library)
+        .get(
+            "/api/v1/shared/{token}/movies/{id}/poster",
+            shares::shared_poster,
+        )
+        .post(
+            "/api/v1/shared/{token}/movies/{id}/borrow",
+            shares::request_borrow,
+        )
+        .get("/health", health_check)
+}
+
+/// Build the CORS layer: permissive unless specific origins are configured
+fn cors_layer(allowed_origins: &[String]) -> CorsLayer {
+    let origins: Vec<HeaderValue> = allowed_origins
+        .iter()
+        .filter(|o| !o.is_empty())
+        .filter_map(|o| match o.parse() {
+            Ok(origin) => Some(origin),
+            Err(_) => {
+                tracing::warn!("Ignoring invalid CORS origin: {}", o);
+                None
+            }
+        })
+        .collect();
+
+    if origins.is_empty() {
+        return CorsLayer::permissive();
+    }
+
+    CorsLayer::new()
+        .allow_origin(AllowOrigin::list(origins))
+        .allow_methods(Any)
+        .allow_headers(Any)
+        .expose_headers(Any)
+}
+
 fn protected_routes(state: Arc<AppState>) -> Router<Arc<AppState>> {
     Router::new()
-        // Auth
-        .route("/auth/me", get(auth::me))
-        .route("/auth/language", axum::routing::put(auth::update_language))
-        .route(
-            "/auth/include-adult",
-            axum::routing::put(auth::update_include_adult),
+        .merge(
+            account_routes()
+                .into_router()
+                .layer(axum::middleware::from_fn(
+                    middleware::scope::require_session,
+                )),
+        )
+        .merge(
+            library_routes()
+                .into_router()
+                .layer(axum::middleware::from_fn(
+                    middleware::scope::require_library_scope,
+                )),
         )
-        .route("/auth/theme", axum::routing::put(auth::update_theme))
-        .route(
-            "/auth/card-size",
-            axum::routing::put(auth::update_card_size),
+        .merge(
+            admin_routes()
+                .into_router()
+                .layer(axum::middleware::from_fn_with_state(
+                    UserRole::Admin,
+                    middleware::role::require_role,
+                ))
+                .layer(axum::middleware::from_fn_with_state(
+                    ApiScope::Admin,
+                    middleware::scope::require_scope,
+                )),
         )
+        // Inside the auth middleware to see the signed-in user
+        .route_layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::metrics::metrics_middleware,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            state,
+            middleware::auth::auth_middleware,
+        ))
+}
+
+/// Profile and API key management; not available to API keys
+pub fn account_routes() -> RouteTable {
+    RouteTable::new()
+        // Auth
+        .get("/auth/me", auth::me)
+        .post("/auth/logout", auth::logout)
+        .get("/auth/sessions", auth::list_sessions)
+        .delete("/auth/sessions/{id}", auth::revoke_session)
+        .put("/auth/language", auth::update_language)
+        .put("/auth/include-adult", auth::update_include_adult)
+        .put("/auth/theme", auth::update_theme)
+        .put("/auth/loan-reminders", auth::update_loan_reminders)
+        .put("/auth/digest", auth::update_digest)
+        .put("/auth/tmdb", auth::update_tmdb)
+        .get("/auth/digest/preview", auth::preview_digest)
+        .put("/auth/player-regions", auth::update_player_regions)
+        .put("/auth/currency", auth::update_preferred_currency)
+        .put("/auth/timezone", auth::update_timezone)
+        .put("/auth/movie-defaults", auth::update_movie_defaults)
+        .put("/auth/card-size", auth::update_card_size)
         // Avatar upload
-        .route("/auth/avatar", axum::routing::post(auth::upload_avatar))
-        .route("/auth/avatar", axum::routing::delete(auth::delete_avatar))
-        .route("/auth/avatar/{id}", axum::routing::get(auth::get_avatar))
+        .post("/auth/avatar", auth::upload_avatar)
+        .delete("/auth/avatar", auth::delete_avatar)
+        .get("/auth/avatar/{id}", auth::get_avatar)
+        // API keys
+        .get("/auth/api-keys", api_keys::list)
+        .post("/auth/api-keys", api_keys::create)
+        .delete("/auth/api-keys/{id}", api_keys::delete)
+        // Instance sync
+        .post("/sync/pairing-token", sync::create_pairing_token)
+        // Share links
+        .get("/shares", shares::list)
+        .post("/shares", shares::create)
+        .delete("/shares/{id}", shares::delete)
+        // Household
+        .get("/household", households::get)
+        .post("/household", households::create)
+        .delete("/household", households::delete)
+        .get("/household/invitations", households::invitations)
+        .post("/household/invitations", households::invite)
+        .post("/household/invitations/{household_id}", households::accept)
+        .delete("/household/invitations/{household_id}", households::decline)
+        .delete("/household/members/{user_id}", households::remove_member)
+        // Notification center
+        .get("/notifications", notifications::list)
+        .post("/notifications/read", notifications::mark_all_read)
+        .delete("/notifications/{id}", notifications::delete)
+        .post("/notifications/{id}/read", notifications::mark_read)
+        // Push notification devices
+        .get("/devices", notifications::list_devices)
+        .post("/devices", notifications::register_device)
+        .delete("/devices/{id}", notifications::remove_device)
+}
+
+/// Movies, series, collections, lookups and imports.
+/// API keys need `movies:read` for reads and `movies:write` for changes.
+pub fn library_routes() -> RouteTable {
+    RouteTable::new()
         // Movies
-        .route("/movies", get(movies::list).post(movies::create))
-        .route("/movies/all", delete(movies::delete_all))
-        .route("/movies/export", get(movies::export))
-        .route("/movies/import-json", post(movies::import_json))
-        .route("/movies/import-zip", post(movies::import_zip))
-        .route("/movies/check-duplicates", get(movies::check_duplicates))
-        .route("/movies/duplicates", get(movies::find_all_duplicates))
-        .route(
-            "/movies/{id}",
-            get(movies::get).put(movies::update).delete(movies::delete),
-        )
-        .route("/movies/{id}/refresh-tmdb", post(movies::refresh_tmdb))
-        .route("/movies/{id}/upload-poster", post(movies::upload_poster))
-        .route(
-            "/movies/{id}/set-poster-url",
-            post(movies::set_poster_from_url),
+        .get("/movies", movies::list)
+        .post("/movies", movies::create)
+        .delete("/movies/all", movies::delete_all)
+        .patch("/movies/bulk", movies::bulk_update)
+        .post("/movies/refresh-tmdb/batch", movies::refresh_tmdb_batch)
+        .get("/wishlist", movies::wishlist)
+        .get("/movies/suggest", movies::suggest)
+        .get("/search", search::search)
+        .get("/movies/recent-views", movies::recent_views)
+        .get("/movies/region-report", movies::region_report)
+        .get("/movies/missing-metadata", movies::missing_metadata)
+        .get("/movies/value-report", movies::value_report)
+        .get("/movies/stats", movies::stats)
+        .get("/exchange-rates", settings::get_exchange_rates)
+        .get("/movies/orphans", movies::find_orphans)
+        .post("/movies/orphans/fix", movies::fix_orphans)
+        .get("/movies/export", movies::export)
+        .get("/export/spine-index", movies::spine_index)
+        .post("/movies/import-json", movies::import_json)
+        .post("/movies/import-zip", movies::import_zip)
+        .get("/movies/check-duplicates", movies::check_duplicates)
+        .get("/movies/duplicates", movies::find_all_duplicates)
+        .get("/movies/{id}", movies::get)
+        .put("/movies/{id}", movies::update)
+        .delete("/movies/{id}", movies::delete)
+        .post("/movies/{id}/view", movies::record_view)
+        .post("/movies/{id}/favorite", movies::toggle_favorite)
+        .post("/movies/{id}/watch", movies::log_watch)
+        .get("/movies/{id}/watches", movies::list_watches)
+        .delete("/movies/{id}/watches/{watch_id}", movies::delete_watch)
+        .post("/movies/{id}/dispose", movies::dispose)
+        .delete("/movies/{id}/dispose", movies::restore_disposed)
+        .post(
+            "/movies/{id}/move-to-collection",
+            movies::move_to_collection,
         )
-        .route(
-            "/movies/{id}/poster",
-            axum::routing::get(movies::get_poster),
-        )
-        .route(
-            "/movies/{id}/thumbnail",
-            axum::routing::get(movies::get_thumbnail),
+        .get("/movies/{id}/condition", movies::get_condition)
+        .put("/movies/{id}/condition", movies::set_condition)
+        .get("/movies/{id}/listing-text", movies::listing_text)
+        .post("/movies/{id}/refresh-tmdb", movies::refresh_tmdb)
+        .post("/movies/{id}/upload-poster", movies::upload_poster)
+        .post("/movies/{id}/set-poster-url", movies::set_poster_from_url)
+        .get("/movies/{id}/poster", movies::get_poster)
+        .get("/movies/{id}/backdrop", movies::get_backdrop)
+        .get("/movies/{id}/tmdb-posters", movies::tmdb_posters)
+        .post(
+            "/movies/{id}/poster/from-tmdb",
+            movies::set_poster_from_tmdb,
         )
+        .get("/movies/{id}/thumbnail", movies::get_thumbnail)
         // Collection analysis (for box sets)
-        .route(
+        .get(
             "/movies/{id}/analyze-collection",
-            get(movies::analyze_collection),
-        )
-        .route(
-            "/movies/{id}/split-collection",
-            post(movies::split_collection),
+            movies::analyze_collection,
         )
-        .route(
+        .post("/movies/{id}/split-collection", movies::split_collection)
+        .get(
             "/movies/{id}/collection-movies",
-            get(movies::get_collection_movies),
+            movies::get_collection_movies,
         )
+        // Background jobs (collection splits)
+        .get("/jobs/{id}", routes::jobs::get)
+        .post("/jobs/{id}/cancel", routes::jobs::cancel)
+        // Movie night planner
+        .get("/planner", planner::plan)
+        .post("/planner/schedule", planner::schedule)
+        .get("/planner/nights", planner::list_nights)
+        .delete("/planner/nights/{id}", planner::cancel_night)
         // Series
-        .route("/series", get(series::list).post(series::create))
-        .route(
-            "/series/{id}",
-            get(series::get).put(series::update).delete(series::delete),
-        )
+        .get("/series", series::list)
+        .post("/series", series::create)
+        .get("/series/{id}", series::get)
+        .put("/series/{id}", series::update)
+        .delete("/series/{id}", series::delete)
+        .get("/series/{id}/poster", series::get_poster)
+        .get("/series/{id}/episodes", series::list_episodes)
+        .post("/series/{id}/episodes", series::create_episode)
+        .put("/series/{id}/episodes/{episode_id}", series::update_episode)
+        .delete("/series/{id}/episodes/{episode_id}", series::delete_episode)
         // Collections
-        .route(
-            "/collections",
-            get(collections::list).post(collections::create),
+        .get("/collections", collections::list)
+        .post("/collections", collections::create)
+        .get("/collections/{id}", collections::get)
+        .put("/collections/{id}", collections::update)
+        .delete("/collections/{id}", collections::delete)
+        .get("/collections/{id}/items", collections::get_items)
+        .post("/collections/{id}/items", collections::add_item)
+        .delete(
+            "/collections/{id}/items/{item_id}",
+            collections::remove_item,
         )
-        .route(
-            "/collections/{id}",
-            get(collections::get)
-                .put(collections::update)
-                .delete(collections::delete),
+        // Contacts (borrowers)
+        .get("/contacts", contacts::list)
+        .post("/contacts", contacts::create)
+        .get("/contacts/{id}", contacts::get)
+        .put("/contacts/{id}", contacts::update)
+        .delete("/contacts/{id}", contacts::delete)
+        .get("/contacts/{id}/loans", contacts::loans)
+        .get("/borrow-requests", loans::list_borrow_requests)
+        .post(
+            "/borrow-requests/{id}/approve",
+            loans::approve_borrow_request,
         )
-        .route(
-            "/collections/{id}/items",
-            get(collections::get_items).post(collections::add_item),
-        )
-        .route(
-            "/collections/{id}/items/{item_id}",
-            delete(collections::remove_item),
+        .post(
+            "/borrow-requests/{id}/decline",
+            loans::decline_borrow_request,
         )
         // Scanning & Lookup
-        .route("/scan", post(scan::lookup_barcode))
-        .route("/tmdb/search/movies", get(scan::search_tmdb_movies))
-        .route("/tmdb/search/tv", get(scan::search_tmdb_tv))
-        .route("/tmdb/movies/{id}", get(scan::get_tmdb_movie))
-        .route("/tmdb/tv/{id}", get(scan::get_tmdb_tv))
+        .post("/scan", scan::lookup_barcode)
+        .post("/movies/identify-cover", scan::identify_cover)
+        .get("/tmdb/search/movies", scan::search_tmdb_movies)
+        .get("/tmdb/search/tv", scan::search_tmdb_tv)
+        .get("/tmdb/movies/{id}", scan::get_tmdb_movie)
+        .get("/tmdb/tv/{id}", scan::get_tmdb_tv)
+        // Delta sync and instance sync
+        .get("/sync/tombstones", sync::tombstones)
+        .post("/sync/exchange", sync::exchange)
+        // Activity log
+        .get("/activity", activity::list)
+        // Valuation
+        .get("/valuation/history", valuation::history)
+        .post("/valuation/currency", valuation::bulk_update_currency)
+        .get("/valuation/{entity_type}/{id}", valuation::get)
+        .put("/valuation/{entity_type}/{id}", valuation::set_value)
         // Import/Export
-        .route("/import/csv", post(import::import_csv))
-        .route("/import/enrich-tmdb", post(import::enrich_movies_tmdb))
-        .route(
-            "/import/enrich-tmdb/cancel",
-            post(import::cancel_enrich_tmdb),
-        )
-        .route("/import/enrich-tmdb/status", get(import::get_enrich_status))
-        // Settings (admin only)
-        .route("/settings", get(settings::get_settings))
-        .route(
-            "/settings/{key}",
-            axum::routing::put(settings::update_setting),
-        )
-        .route("/settings/test/tmdb", post(settings::test_tmdb))
-        // User management (admin only)
-        .route(
-            "/users",
-            get(users::list_users).post(users::admin_create_user),
-        )
-        .route(
-            "/users/{id}/role",
-            axum::routing::put(users::update_user_role),
-        )
-        .route("/users/{id}", delete(users::delete_user))
-        .route(
-            "/users/{id}/password",
-            axum::routing::put(users::admin_set_password),
+        .post("/import/csv", import::import_csv)
+        .get("/import/presets", import::list_presets)
+        .put("/import/presets/{name}", import::save_preset)
+        .delete("/import/presets/{name}", import::delete_preset)
+        .post("/import/dvdprofiler", import::import_dvdprofiler)
+        .post("/import/verify", import::verify_backup)
+        .post("/import/reconcile", import::reconcile_backup)
+        .post("/import/enrich-tmdb", import::enrich_movies_tmdb)
+        .get("/import/enrich-tmdb/status", import::get_enrich_status)
+}
+
+/// Routes that require the admin role
+pub fn admin_routes() -> RouteTable {
+    RouteTable::new()
+        .post("/import/enrich-tmdb/cancel", import::cancel_enrich_tmdb)
+        // Reads the server's file system
+        .post("/import/media-folder", import::scan_media_folder)
+        // Settings
+        .get("/settings", settings::get_settings)
+        .put("/settings/{key}", settings::update_setting)
+        .post("/settings/reload", settings::reload_settings)
+        .post("/settings/test/tmdb", settings::test_tmdb)
+        .post("/settings/test/discord", settings::test_discord)
+        .post("/settings/test/smtp", settings::test_smtp)
+        .post(
+            "/settings/exchange-rates/refresh",
+            settings::refresh_exchange_rates,
         )
-        .layer(axum::middleware::from_fn_with_state(
-            state,
-            middleware::auth::auth_middleware,
-        ))
+        // User management
+        .get("/users", users::list_users)
+        .post("/users", users::admin_create_user)
+        .put("/users/{id}/role", users::update_user_role)
+        .delete("/users/{id}", users::delete_user)
+        .put("/users/{id}/password", users::admin_set_password)
+        // Invites for registering while open registration is disabled
+        .get("/invites", invites::list)
+        .post("/invites", invites::create)
+        .delete("/invites/{id}", invites::delete)
+        // Admin tools
+        .get("/admin/logs", admin::get_logs)
+        .get("/admin/diagnostics", admin::get_diagnostics)
+        .get("/admin/database", admin::database_snapshot)
+        .post("/admin/repair", admin::repair)
+        .post("/admin/broadcast", admin::broadcast)
+        .get("/admin/barcode-cache", admin::barcode_cache)
+        .delete("/admin/barcode-cache", admin::clear_barcode_cache)
+        .get("/admin/tmdb-cache", admin::tmdb_cache)
+        .delete("/admin/tmdb-cache", admin::clear_tmdb_cache)
+        .get("/admin/metrics", admin::request_metrics)
+        // Instance sync peers; the server sends requests to their URLs
+        .get("/sync/peers", sync::list_peers)
+        .post("/sync/peers", sync::create_peer)
+        .delete("/sync/peers/{id}", sync::delete_peer)
+        .post("/sync/peers/{id}/run", sync::run_peer)
 }
 
 async fn health_check() -> &'static str {
@@ -276,13 +757,17 @@ pub async fn start_server(server_config: ServerConfig) -> anyhow::Result<()> {
     dotenvy::dotenv().ok();
 
     // Load app config
-    let config = Config::from_env().map_err(|e| anyhow::anyhow!("Failed to load config: {}", e))?;
+    let mut config = Config::load(server_config.config_path.as_deref())?;
+    config.skip_migrations |= server_config.skip_migrations;
 
     tracing::info!("Starting My Movies server...");
 
     // Create app state
     let state = create_app_state(&config).await?;
 
+    // Once per process: the hook chains the previous one
+    error_reporting::install_panic_hook(state.error_reporter.clone());
+
     // Build router with optional static file serving
     let app = create_router(state, server_config.static_dir.as_deref());
 
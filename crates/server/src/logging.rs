@@ -0,0 +1,234 @@
+use std::path::{Path, PathBuf};
+
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::{FilterFn, filter_fn};
+use tracing_subscriber::{Layer, layer::SubscriberExt, util::SubscriberInitExt};
+
+/// Prefix of the rotated log files (e.g. `my-movies.2024-01-31.log`)
+const LOG_FILE_PREFIX: &str = "my-movies";
+const LOG_FILE_SUFFIX: &str = "log";
+
+/// Maximum number of bytes read from the end of the log file when tailing
+const TAIL_MAX_BYTES: u64 = 512 * 1024;
+
+/// Keeps background log writers alive; flushes pending logs and traces on drop
+pub struct TracingGuard {
+    _file_guard: Option<WorkerGuard>,
+    tracer_provider: Option<SdkTracerProvider>,
+}
+
+impl Drop for TracingGuard {
+    fn drop(&mut self) {
+        if let Some(provider) = self.tracer_provider.take()
+            && let Err(e) = provider.shutdown()
+        {
+            eprintln!("Failed to flush OpenTelemetry traces: {}", e);
+        }
+    }
+}
+
+/// Initialize tracing: human-readable output on stdout and, if `log_dir` is set,
+/// JSON lines in a daily rotated file keeping at most `max_files` files.
+///
+/// If `OTEL_EXPORTER_OTLP_ENDPOINT` (or `OTEL_EXPORTER_OTLP_TRACES_ENDPOINT`) is set,
+/// spans are additionally exported via OTLP/HTTP. The exporter reads the standard
+/// `OTEL_*` environment variables (headers, timeout, service name, ...).
+///
+/// The returned guard must be kept alive for the lifetime of the program,
+/// otherwise buffered log lines and spans are lost on shutdown.
+pub fn init_tracing(log_dir: Option<&str>, max_files: usize) -> TracingGuard {
+    let env_filter = tracing_subscriber::EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| "info,my_movies_server=debug,my_movies_core=debug".into());
+
+    let (file_layer, file_guard) = match log_dir.map(|dir| build_file_appender(dir, max_files)) {
+        Some(Ok(appender)) => {
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            let layer = tracing_subscriber::fmt::layer()
+                .json()
+                .with_ansi(false)
+                .with_writer(writer)
+                .with_filter(output_filter());
+            (Some(layer), Some(guard))
+        }
+        Some(Err(e)) => {
+            eprintln!("Failed to set up file logging: {}", e);
+            (None, None)
+        }
+        None => (None, None),
+    };
+
+    let tracer_provider = if otel_enabled() {
+        match build_tracer_provider() {
+            Ok(provider) => Some(provider),
+            Err(e) => {
+                eprintln!("Failed to set up OpenTelemetry export: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let otel_layer = tracer_provider.as_ref().map(|provider| {
+        tracing_opentelemetry::layer()
+            .with_tracer(provider.tracer("my-movies-server"))
+            .with_filter(output_filter())
+    });
+
+    tracing_subscriber::registry()
+        .with(env_filter)
+        .with(tracing_subscriber::fmt::layer().with_filter(output_filter()))
+        .with(file_layer)
+        .with(otel_layer)
+        .with(my_movies_core::db::slow_queries::SlowQueryLayer)
+        .init();
+
+    if tracer_provider.is_some() {
+        tracing::info!("OpenTelemetry trace export enabled");
+    }
+
+    TracingGuard {
+        _file_guard: file_guard,
+        tracer_provider,
+    }
+}
+
+/// Filter of the layers writing logs out: SQLx's slow statement reports
+/// contain the unredacted statement, `SlowQueryLayer` logs a redacted copy
+fn output_filter() -> FilterFn<fn(&tracing::Metadata<'_>) -> bool> {
+    filter_fn(|metadata| !my_movies_core::db::slow_queries::is_sqlx_slow_statement(metadata))
+}
+
+fn otel_enabled() -> bool {
+    [
+        "OTEL_EXPORTER_OTLP_ENDPOINT",
+        "OTEL_EXPORTER_OTLP_TRACES_ENDPOINT",
+    ]
+    .iter()
+    .any(|var| std::env::var(var).is_ok_and(|v| !v.is_empty()))
+}
+
+fn build_tracer_provider() -> Result<SdkTracerProvider, opentelemetry_otlp::ExporterBuildError> {
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .build()?;
+
+    // OTEL_SERVICE_NAME / OTEL_RESOURCE_ATTRIBUTES are picked up by the default detectors
+    let resource = if std::env::var("OTEL_SERVICE_NAME").is_ok() {
+        Resource::builder().build()
+    } else {
+        Resource::builder().with_service_name("my-movies").build()
+    };
+
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(resource)
+        .build();
+    opentelemetry::global::set_tracer_provider(provider.clone());
+
+    Ok(provider)
+}
+
+fn build_file_appender(
+    dir: &str,
+    max_files: usize,
+) -> Result<RollingFileAppender, tracing_appender::rolling::InitError> {
+    RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix(LOG_FILE_SUFFIX)
+        .max_log_files(max_files.max(1))
+        .build(dir)
+}
+
+/// Find the most recently written log file in `log_dir`
+pub async fn current_log_file(log_dir: &Path) -> std::io::Result<Option<PathBuf>> {
+    let mut entries = tokio::fs::read_dir(log_dir).await?;
+    let mut newest: Option<(std::time::SystemTime, PathBuf)> = None;
+
+    while let Some(entry) = entries.next_entry().await? {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if !name.starts_with(LOG_FILE_PREFIX) || !name.ends_with(LOG_FILE_SUFFIX) {
+            continue;
+        }
+
+        let modified = entry.metadata().await?.modified()?;
+        if newest.as_ref().is_none_or(|(t, _)| modified > *t) {
+            newest = Some((modified, entry.path()));
+        }
+    }
+
+    Ok(newest.map(|(_, path)| path))
+}
+
+/// Read the last `lines` lines of a log file
+pub async fn tail_log_file(path: &Path, lines: usize) -> std::io::Result<Vec<String>> {
+    let mut file = tokio::fs::File::open(path).await?;
+    let len = file.metadata().await?.len();
+    let start = len.saturating_sub(TAIL_MAX_BYTES);
+    file.seek(std::io::SeekFrom::Start(start)).await?;
+
+    let mut buf = Vec::new();
+    file.read_to_end(&mut buf).await?;
+    let content = String::from_utf8_lossy(&buf);
+
+    let mut all: Vec<&str> = content.lines().collect();
+    // The first line is likely cut off if we didn't start at the beginning
+    if start > 0 && !all.is_empty() {
+        all.remove(0);
+    }
+
+    let skip = all.len().saturating_sub(lines);
+    Ok(all[skip..].iter().map(|l| l.to_string()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_output_filter_drops_sqlx_slow_statements() {
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::registry().with(
+            tracing_subscriber::fmt::layer()
+                .with_ansi(false)
+                .with_writer(move || writer.clone())
+                .with_filter(output_filter()),
+        );
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::warn!(
+                target: "sqlx::query",
+                summary = "SELECT * FROM users WHERE email = 'a@b.c'",
+                "slow statement: execution time exceeded alert threshold"
+            );
+            tracing::warn!("Slow query: SELECT * FROM users WHERE email = '?'");
+        });
+
+        let logged = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert!(!logged.contains("a@b.c"), "{logged}");
+        assert!(logged.contains("Slow query"), "{logged}");
+    }
+}
@@ -1,30 +1,62 @@
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use std::path::PathBuf;
 
+use my_movies_core::Config;
 use my_movies_server::ServerConfig;
 
+/// Remove `--config <path>` / `--config=<path>` from the arguments and return the path
+fn take_config_path(args: &mut Vec<String>) -> Option<PathBuf> {
+    let pos = args
+        .iter()
+        .position(|a| a == "--config" || a.starts_with("--config="))?;
+    let arg = args.remove(pos);
+
+    match arg.strip_prefix("--config=") {
+        Some(path) => Some(PathBuf::from(path)),
+        None if pos < args.len() => Some(PathBuf::from(args.remove(pos))),
+        None => None,
+    }
+}
+
+/// Remove a boolean `flag` from the arguments and return whether it was given
+fn take_flag(args: &mut Vec<String>, flag: &str) -> bool {
+    let len = args.len();
+    args.retain(|a| a != flag);
+    args.len() != len
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     // Load .env file early for environment variables
     dotenvy::dotenv().ok();
 
-    // Initialize tracing
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "info,my_movies_server=debug,my_movies_core=debug".into()),
-        )
-        .with(tracing_subscriber::fmt::layer())
-        .init();
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+
+    // Load config file (if any) with environment variable overrides
+    let config_path = take_config_path(&mut args);
+    let mut config = Config::load(config_path.as_deref())?;
+    // Run on the current schema, e.g. to export data after a failed upgrade
+    config.skip_migrations |= take_flag(&mut args, "--skip-migrations");
+
+    // Initialize tracing (stdout, plus rotating log files if a log dir is configured)
+    let log_dir = config.log_dir.as_deref().filter(|d| !d.is_empty());
+    let _tracing_guard = my_movies_server::logging::init_tracing(log_dir, config.log_max_files);
+
+    // Subcommands
+    if args.first().map(String::as_str) == Some("seed") {
+        return my_movies_server::seed::run(&config, &args[1..]).await;
+    }
+    if args.first().map(String::as_str) == Some("migrate-db") {
+        return my_movies_server::migrate_db::run(&config, &args[1..]).await;
+    }
 
     // Configure server
     let server_config = ServerConfig {
-        host: std::env::var("HOST").unwrap_or_else(|_| "0.0.0.0".to_string()),
-        port: std::env::var("PORT")
-            .ok()
-            .and_then(|p| p.parse().ok())
-            .unwrap_or(3000),
-        // STATIC_DIR environment variable to serve frontend files
-        static_dir: std::env::var("STATIC_DIR").ok(),
+        host: config.host.clone(),
+        port: config.port,
+        // STATIC_DIR / [server] static_dir to serve frontend files
+        static_dir: config.static_dir.clone(),
+        config_path,
+        skip_migrations: config.skip_migrations,
     };
 
     my_movies_server::start_server(server_config).await
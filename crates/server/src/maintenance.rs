@@ -0,0 +1,228 @@
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use my_movies_core::models::{LoanReminderKind, MessageLevel};
+use my_movies_core::services::{EmailMessage, Mailer, PushMessage};
+use serde_json::json;
+
+use crate::AppState;
+use crate::routes::ws::WsEvent;
+
+/// How often maintenance runs
+const MAINTENANCE_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+/// Deletion records are kept this long; clients syncing less often do a full reload
+const TOMBSTONE_RETENTION_DAYS: i64 = 90;
+/// Activity log entries are kept this long
+const ACTIVITY_RETENTION_DAYS: i64 = 365;
+
+/// Run periodic cleanup, exchange rate updates and valuation snapshots in the
+/// background (first run right after startup); skipped in read-only mode
+pub fn spawn_maintenance(state: Arc<AppState>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(MAINTENANCE_INTERVAL);
+        loop {
+            interval.tick().await;
+            // Purges, reminder flags and snapshots all write to the database
+            if state.read_only.load(Ordering::Relaxed) {
+                continue;
+            }
+            run_maintenance(&state).await;
+        }
+    });
+}
+
+async fn run_maintenance(state: &AppState) {
+    let cutoff = chrono::Utc::now() - chrono::Duration::days(TOMBSTONE_RETENTION_DAYS);
+    match state.tombstone_service.prune(cutoff).await {
+        Ok(0) => {}
+        Ok(pruned) => tracing::info!("Pruned {} old deletion records", pruned),
+        Err(e) => tracing::error!("Failed to prune deletion records: {}", e),
+    }
+
+    let cutoff = chrono::Utc::now() - chrono::Duration::days(ACTIVITY_RETENTION_DAYS);
+    match state.activity_service.prune(cutoff).await {
+        Ok(0) => {}
+        Ok(pruned) => tracing::info!("Pruned {} old activity log entries", pruned),
+        Err(e) => tracing::warn!("Failed to prune the activity log: {}", e),
+    }
+
+    match state.image_service.purge_deleted().await {
+        Ok(0) => {}
+        Ok(purged) => tracing::info!("Removed {} deleted images from image storage", purged),
+        Err(e) => tracing::warn!("Failed to remove deleted images from image storage: {}", e),
+    }
+
+    match state.auth_service.purge_sessions().await {
+        Ok(0) => {}
+        Ok(purged) => tracing::info!("Removed {} expired or revoked sessions", purged),
+        Err(e) => tracing::warn!("Failed to remove expired sessions: {}", e),
+    }
+
+    match state.barcode_cache_service.purge_expired().await {
+        Ok(0) => {}
+        Ok(purged) => tracing::info!("Removed {} expired barcode lookups", purged),
+        Err(e) => tracing::warn!("Failed to remove expired barcode lookups: {}", e),
+    }
+
+    match state.tmdb_service.purge_expired_cache().await {
+        Ok(0) => {}
+        Ok(purged) => tracing::info!("Removed {} expired TMDB responses", purged),
+        Err(e) => tracing::warn!("Failed to remove expired TMDB responses: {}", e),
+    }
+
+    send_loan_reminders(state).await;
+
+    match state.currency_service.refresh().await {
+        Ok(Some(count)) => tracing::info!("Fetched {} exchange rates", count),
+        Ok(None) => {}
+        Err(e) => tracing::warn!("Failed to fetch exchange rates: {}", e),
+    }
+
+    let today = chrono::Utc::now().date_naive();
+    match state.valuation_service.snapshot(today).await {
+        Ok(count) => tracing::debug!("Stored {} valuation totals", count),
+        Err(e) => tracing::warn!("Failed to store the collection valuation: {}", e),
+    }
+}
+
+/// Notify owners of lent movies that are due soon or overdue (once per loan
+/// and kind). A reminder counts as sent once it was pushed to a device, stored
+/// in the notification center or emailed; otherwise the next run tries again.
+async fn send_loan_reminders(state: &AppState) {
+    let now = chrono::Utc::now();
+    let reminders = match state.loan_service.due_reminders(now).await {
+        Ok(reminders) => reminders,
+        Err(e) => {
+            tracing::error!("Failed to check loan due dates: {}", e);
+            return;
+        }
+    };
+
+    for reminder in reminders {
+        let msg = json!({ "type": "loan_reminder", "payload": reminder });
+        let _ = state
+            .ws_broadcast
+            .send(WsEvent::user(reminder.user_id, &msg));
+
+        if let Err(e) = state.discord_service.notify_loan_reminder(&reminder).await {
+            tracing::warn!("Failed to send loan reminder to Discord: {}", e);
+        }
+
+        let mut delivered = false;
+        let message = PushMessage {
+            kind: "loan_reminder".to_string(),
+            title: reminder.title.clone(),
+            body: reminder.text(),
+        };
+        match state
+            .notification_service
+            .push(reminder.user_id, &message)
+            .await
+        {
+            Ok(devices) => delivered |= devices > 0,
+            Err(e) => tracing::warn!("Failed to push loan reminder: {}", e),
+        }
+
+        // Kept in the notification center for when no device was reached
+        let level = match reminder.kind {
+            LoanReminderKind::DueSoon => MessageLevel::Info,
+            LoanReminderKind::Overdue => MessageLevel::Warning,
+        };
+        let text = format!("{}: {}", reminder.title, reminder.text());
+        match state
+            .notification_service
+            .notify(Some(&[reminder.user_id]), "loan_reminder", level, &text)
+            .await
+        {
+            Ok(_) => delivered = true,
+            Err(e) => tracing::warn!("Failed to store loan reminder: {}", e),
+        }
+
+        // Without SMTP the email would only end up in the server log
+        if state.mail_service.smtp().is_some() && !reminder.email.is_empty() {
+            let email = EmailMessage::loan_reminder(&reminder);
+            match state.mail_service.send(&email).await {
+                Ok(()) => delivered = true,
+                Err(e) => tracing::warn!("Failed to email loan reminder: {}", e),
+            }
+        }
+
+        if !delivered {
+            continue;
+        }
+        if let Err(e) = state.loan_service.mark_reminder_sent(&reminder, now).await {
+            tracing::warn!("Failed to record loan reminder: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use my_movies_core::models::{CreateMovie, UpdateMovie};
+
+    use super::*;
+    use crate::test_helpers::create_test_app;
+
+    #[tokio::test]
+    async fn test_loan_reminders_are_stored_before_marked_sent() {
+        let app = create_test_app().await;
+        let state = &app.state;
+        let user = state
+            .auth_service
+            .get_user_by_username("user")
+            .await
+            .unwrap();
+        let movie = state
+            .movie_service
+            .create(
+                user.id,
+                CreateMovie {
+                    barcode: None,
+                    tmdb_id: None,
+                    title: "Vertigo".to_string(),
+                    original_title: None,
+                    disc_type: None,
+                    production_year: None,
+                },
+            )
+            .await
+            .unwrap();
+        state
+            .movie_service
+            .update(
+                user.id,
+                movie.id,
+                UpdateMovie {
+                    lent_to: Some("Anna".to_string()),
+                    lent_due: Some(chrono::Utc::now().date_naive() - chrono::Duration::days(2)),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        send_loan_reminders(state).await;
+
+        let notifications = state
+            .notification_service
+            .list(user.id, true)
+            .await
+            .unwrap();
+        assert_eq!(notifications.len(), 1);
+        assert!(
+            notifications[0]
+                .message
+                .starts_with("Vertigo: Ist bei Anna")
+        );
+        let now = chrono::Utc::now();
+        assert!(
+            state
+                .loan_service
+                .due_reminders(now)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -10,6 +10,8 @@ use axum::{
 };
 use serde_json::json;
 
+use my_movies_core::services::ApiKeyService;
+
 use crate::AppState;
 
 pub async fn auth_middleware(
@@ -34,8 +36,15 @@ pub async fn auth_middleware(
         }
     };
 
-    // Verify token
-    match state.auth_service.verify_token(token) {
+    // API keys (for integrations) carry scopes; everything else is a session JWT,
+    // checked against the session so revoked devices are locked out
+    let claims = if ApiKeyService::is_api_key(token) {
+        state.api_key_service.authenticate(token).await
+    } else {
+        state.auth_service.authenticate(token).await
+    };
+
+    match claims {
         Ok(claims) => {
             // Insert claims into request extensions
             request.extensions_mut().insert(claims);
@@ -0,0 +1,54 @@
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    body::Body,
+    extract::State,
+    http::{Method, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+
+use crate::AppState;
+
+/// Path prefixes that may not be modified in demo mode
+const BLOCKED_PREFIXES: &[&str] = &[
+    "/api/v1/auth/register",
+    "/api/v1/auth/forgot-password",
+    "/api/v1/auth/reset-password",
+    "/api/v1/auth/avatar",
+    "/api/v1/settings",
+    "/api/v1/users",
+    "/api/v1/admin",
+    "/api/v1/import",
+    "/api/v1/movies/import-json",
+    "/api/v1/movies/import-zip",
+    // Makes the server send requests to other servers
+    "/api/v1/sync",
+];
+
+/// Reject destructive requests while running as public demo
+pub async fn demo_guard(
+    State(state): State<Arc<AppState>>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    if !state.demo_mode || !is_blocked(request.method(), request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    (
+        StatusCode::FORBIDDEN,
+        Json(json!({ "error": "Diese Aktion ist im Demo-Modus deaktiviert" })),
+    )
+        .into_response()
+}
+
+fn is_blocked(method: &Method, path: &str) -> bool {
+    if *method == Method::GET || *method == Method::HEAD || *method == Method::OPTIONS {
+        return false;
+    }
+
+    *method == Method::DELETE || BLOCKED_PREFIXES.iter().any(|p| path.starts_with(p))
+}
@@ -0,0 +1,36 @@
+use std::sync::Arc;
+
+use axum::{body::Body, extract::State, http::Request, middleware::Next, response::Response};
+
+use crate::AppState;
+use crate::error_reporting::{ErrorMessage, ErrorReport, ErrorReportKind, RequestContext};
+
+/// Report responses with a 5xx status to the configured error report sink
+pub async fn error_report_middleware(
+    State(state): State<Arc<AppState>>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let method = request.method().to_string();
+    let path = request.uri().path().to_string();
+
+    let response = next.run(request).await;
+
+    if response.status().is_server_error() && state.error_reporter.is_enabled() {
+        let message = response
+            .extensions()
+            .get::<ErrorMessage>()
+            .map(|m| m.0.clone())
+            .unwrap_or_else(|| response.status().to_string());
+
+        let mut report = ErrorReport::new(ErrorReportKind::ServerError, message);
+        report.request = Some(RequestContext {
+            method,
+            path,
+            status: response.status().as_u16(),
+        });
+        state.error_reporter.report(report);
+    }
+
+    response
+}
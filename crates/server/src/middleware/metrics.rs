@@ -0,0 +1,87 @@
+use std::sync::Arc;
+use std::time::Instant;
+
+use axum::{
+    body::Body,
+    extract::{MatchedPath, State},
+    http::Request,
+    middleware::Next,
+    response::Response,
+};
+
+use my_movies_core::models::Claims;
+
+use crate::AppState;
+
+/// Record method, route, user, status and duration of every routed request
+pub async fn metrics_middleware(
+    State(state): State<Arc<AppState>>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let Some(route) = request.extensions().get::<MatchedPath>() else {
+        return next.run(request).await;
+    };
+    let endpoint = format!("{} {}", request.method(), route.as_str());
+    let user_id = request.extensions().get::<Claims>().map(|c| c.id);
+
+    let started = Instant::now();
+    let response = next.run(request).await;
+
+    state.request_metrics.record(
+        &endpoint,
+        user_id,
+        response.status().as_u16(),
+        started.elapsed(),
+    );
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{Method, StatusCode};
+    use serde_json::json;
+
+    use crate::request_metrics::WINDOW;
+    use crate::test_helpers::create_test_app;
+
+    #[tokio::test]
+    async fn test_requests_are_recorded_per_route_and_user() {
+        let app = create_test_app().await;
+
+        for path in [
+            "/api/v1/movies/00000000-0000-0000-0000-000000000001",
+            "/health",
+        ] {
+            app.request(Method::GET, path, Some(&app.user_token), json!({}))
+                .await;
+        }
+        let status = app
+            .request(
+                Method::GET,
+                "/api/v1/movies",
+                Some(&app.user_token),
+                json!({}),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let report = app.state.request_metrics.report(WINDOW);
+        let endpoints: Vec<&str> = report
+            .endpoints
+            .iter()
+            .map(|e| e.endpoint.as_str())
+            .collect();
+        assert!(
+            endpoints.contains(&"GET /api/v1/movies/{id}"),
+            "{endpoints:?}"
+        );
+        assert!(endpoints.contains(&"GET /api/v1/movies"), "{endpoints:?}");
+        assert!(endpoints.contains(&"GET /health"), "{endpoints:?}");
+        assert_eq!(report.requests, 3);
+        assert_eq!(report.anonymous_requests, 1);
+        assert_eq!(report.users.len(), 1);
+        assert_eq!(report.users[0].requests, 2);
+        assert_eq!(report.users[0].errors, 1);
+    }
+}
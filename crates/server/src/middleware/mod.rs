@@ -1 +1,7 @@
 pub mod auth;
+pub mod demo;
+pub mod error_report;
+pub mod metrics;
+pub mod read_only;
+pub mod role;
+pub mod scope;
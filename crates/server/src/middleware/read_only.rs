@@ -0,0 +1,163 @@
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+
+use axum::{
+    Json,
+    body::Body,
+    extract::State,
+    http::{Method, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+
+use crate::AppState;
+
+/// Requests still possible in read-only mode: signing in and out, refreshing
+/// the access token, checking a backup without importing it, and settings so
+/// an admin can switch the mode off again
+const ALLOWED_PREFIXES: &[&str] = &[
+    "/api/v1/auth/login",
+    "/api/v1/auth/refresh",
+    "/api/v1/auth/logout",
+    "/api/v1/import/verify",
+    "/api/v1/settings",
+];
+
+/// Reject all mutating requests while the server is read-only
+pub async fn read_only_guard(
+    State(state): State<Arc<AppState>>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    if !state.read_only.load(Ordering::Relaxed)
+        || !is_blocked(request.method(), request.uri().path())
+    {
+        return next.run(request).await;
+    }
+
+    (
+        StatusCode::FORBIDDEN,
+        Json(json!({ "error": "The server is in read-only mode" })),
+    )
+        .into_response()
+}
+
+fn is_blocked(method: &Method, path: &str) -> bool {
+    if *method == Method::GET || *method == Method::HEAD || *method == Method::OPTIONS {
+        return false;
+    }
+
+    !ALLOWED_PREFIXES.iter().any(|p| path.starts_with(p))
+}
+
+#[cfg(test)]
+mod tests {
+    use my_movies_core::models::LoginRequest;
+
+    use super::*;
+    use crate::test_helpers::create_test_app;
+
+    #[tokio::test]
+    async fn test_read_only_rejects_changes() {
+        let app = create_test_app().await;
+        app.state.read_only.store(true, Ordering::Relaxed);
+
+        let status = app
+            .request(
+                Method::POST,
+                "/api/v1/movies",
+                Some(&app.user_token),
+                json!({ "title": "Matrix" }),
+            )
+            .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let status = app
+            .request(
+                Method::DELETE,
+                "/api/v1/movies/all",
+                Some(&app.user_token),
+                json!({}),
+            )
+            .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        // Reads and exports keep working
+        let status = app
+            .request(
+                Method::GET,
+                "/api/v1/movies",
+                Some(&app.user_token),
+                json!({}),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+        let status = app
+            .request(
+                Method::GET,
+                "/api/v1/movies/export",
+                Some(&app.user_token),
+                json!({}),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+
+        app.state.read_only.store(false, Ordering::Relaxed);
+        let status = app
+            .request(
+                Method::POST,
+                "/api/v1/movies",
+                Some(&app.user_token),
+                json!({ "title": "Matrix" }),
+            )
+            .await;
+        assert_eq!(status, StatusCode::CREATED);
+    }
+
+    #[tokio::test]
+    async fn test_read_only_keeps_sessions() {
+        let app = create_test_app().await;
+        app.state.read_only.store(true, Ordering::Relaxed);
+
+        let session = app
+            .state
+            .auth_service
+            .login(LoginRequest {
+                username: "user".to_string(),
+                password: "password".to_string(),
+                device: None,
+            })
+            .await
+            .unwrap();
+        let status = app
+            .request(
+                Method::POST,
+                "/api/v1/auth/refresh",
+                None,
+                json!({ "refresh_token": session.refresh_token }),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let status = app
+            .request(
+                Method::POST,
+                "/api/v1/auth/logout",
+                Some(&session.token),
+                json!({}),
+            )
+            .await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+    }
+
+    #[test]
+    fn test_settings_stay_writable() {
+        assert!(!is_blocked(&Method::PUT, "/api/v1/settings/read_only"));
+        assert!(!is_blocked(&Method::POST, "/api/v1/auth/login"));
+        assert!(!is_blocked(&Method::POST, "/api/v1/import/verify"));
+        assert!(is_blocked(&Method::POST, "/api/v1/import/reconcile"));
+        assert!(is_blocked(&Method::POST, "/api/v1/auth/register"));
+        assert!(is_blocked(&Method::PUT, "/api/v1/movies/123"));
+    }
+}
@@ -0,0 +1,279 @@
+use axum::{
+    Json,
+    body::Body,
+    extract::State,
+    http::{Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+
+use my_movies_core::models::{Claims, UserRole};
+
+/// Require the authenticated user to have `required` role (admins pass every check).
+///
+/// Must run inside `auth_middleware`, which inserts the `Claims`. Use with
+/// `axum::middleware::from_fn_with_state(UserRole::Admin, require_role)`.
+pub async fn require_role(
+    State(required): State<UserRole>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let Some(claims) = request.extensions().get::<Claims>() else {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({ "error": "Missing or invalid Authorization header" })),
+        )
+            .into_response();
+    };
+
+    if claims.role != required && claims.role != UserRole::Admin {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(json!({ "error": my_movies_core::Error::Forbidden.to_string() })),
+        )
+            .into_response();
+    }
+
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::Method;
+    use my_movies_core::models::LoginRequest;
+
+    use super::*;
+    use crate::test_helpers::{create_test_app, route_matches};
+
+    /// Every route behind `require_role(UserRole::Admin)`, with a body each
+    /// handler accepts; `test_admin_routes_are_listed` keeps it complete
+    fn admin_routes() -> Vec<(Method, String, serde_json::Value)> {
+        let id = uuid::Uuid::new_v4();
+        vec![
+            (
+                Method::POST,
+                "/api/v1/import/enrich-tmdb/cancel".into(),
+                json!({}),
+            ),
+            (
+                Method::POST,
+                "/api/v1/import/media-folder".into(),
+                json!({ "path": "/nonexistent" }),
+            ),
+            (Method::GET, "/api/v1/settings".into(), json!({})),
+            (Method::POST, "/api/v1/settings/reload".into(), json!({})),
+            (
+                Method::PUT,
+                "/api/v1/settings/discord_events".into(),
+                json!({ "value": "movie_added" }),
+            ),
+            (Method::POST, "/api/v1/settings/test/tmdb".into(), json!({})),
+            (
+                Method::POST,
+                "/api/v1/settings/test/discord".into(),
+                json!({}),
+            ),
+            (Method::POST, "/api/v1/settings/test/smtp".into(), json!({})),
+            (
+                Method::POST,
+                "/api/v1/settings/exchange-rates/refresh".into(),
+                json!({}),
+            ),
+            (Method::GET, "/api/v1/users".into(), json!({})),
+            (
+                Method::POST,
+                "/api/v1/users".into(),
+                json!({ "username": "new", "email": "new@test.com" }),
+            ),
+            (
+                Method::PUT,
+                format!("/api/v1/users/{}/role", id),
+                json!({ "role": "user" }),
+            ),
+            (Method::DELETE, format!("/api/v1/users/{}", id), json!({})),
+            (
+                Method::PUT,
+                format!("/api/v1/users/{}/password", id),
+                json!({ "password": "secret" }),
+            ),
+            (Method::GET, "/api/v1/invites".into(), json!({})),
+            (
+                Method::POST,
+                "/api/v1/invites".into(),
+                json!({ "max_uses": 2 }),
+            ),
+            (Method::DELETE, format!("/api/v1/invites/{}", id), json!({})),
+            (Method::GET, "/api/v1/admin/logs".into(), json!({})),
+            (Method::GET, "/api/v1/admin/diagnostics".into(), json!({})),
+            // Snapshot of the whole database, the server backup
+            (Method::GET, "/api/v1/admin/database".into(), json!({})),
+            (Method::HEAD, "/api/v1/admin/database".into(), json!({})),
+            (
+                Method::POST,
+                "/api/v1/admin/repair?dry_run=true".into(),
+                json!({}),
+            ),
+            (
+                Method::POST,
+                "/api/v1/admin/broadcast".into(),
+                json!({ "message": "Maintenance tonight" }),
+            ),
+            (Method::GET, "/api/v1/admin/barcode-cache".into(), json!({})),
+            (
+                Method::DELETE,
+                "/api/v1/admin/barcode-cache".into(),
+                json!({}),
+            ),
+            (Method::GET, "/api/v1/admin/tmdb-cache".into(), json!({})),
+            (Method::DELETE, "/api/v1/admin/tmdb-cache".into(), json!({})),
+            (Method::GET, "/api/v1/admin/metrics".into(), json!({})),
+            (Method::GET, "/api/v1/sync/peers".into(), json!({})),
+            (
+                Method::POST,
+                "/api/v1/sync/peers".into(),
+                json!({ "name": "VPS", "url": "http://127.0.0.1:9", "token": "t" }),
+            ),
+            (
+                Method::DELETE,
+                format!("/api/v1/sync/peers/{}", id),
+                json!({}),
+            ),
+            (
+                Method::POST,
+                format!("/api/v1/sync/peers/{}/run", id),
+                json!({}),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_admin_routes_are_listed() {
+        let listed = admin_routes();
+        for (method, path) in crate::admin_routes().routes() {
+            let template = format!("/api/v1{}", path);
+            assert!(
+                listed
+                    .iter()
+                    .any(|(m, p, _)| m == method && route_matches(&template, p)),
+                "{} {} is missing from admin_routes()",
+                method,
+                template
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_admin_routes_forbidden_for_users() {
+        let app = create_test_app().await;
+
+        for (method, path, body) in admin_routes() {
+            let status = app
+                .request(method.clone(), &path, Some(&app.user_token), body)
+                .await;
+            assert_eq!(status, StatusCode::FORBIDDEN, "{} {}", method, path);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_admin_routes_allowed_for_admins() {
+        let app = create_test_app().await;
+
+        for (method, path, body) in admin_routes() {
+            let status = app
+                .request(method.clone(), &path, Some(&app.admin_token), body)
+                .await;
+            assert_ne!(status, StatusCode::FORBIDDEN, "{} {}", method, path);
+            assert_ne!(status, StatusCode::UNAUTHORIZED, "{} {}", method, path);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_admin_routes_require_authentication() {
+        let app = create_test_app().await;
+
+        for (method, path, body) in admin_routes() {
+            let status = app.request(method.clone(), &path, None, body).await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED, "{} {}", method, path);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_demoted_user_loses_admin_routes() {
+        let app = create_test_app().await;
+        let user = app
+            .state
+            .auth_service
+            .get_user_by_username("user")
+            .await
+            .unwrap();
+        let role_path = format!("/api/v1/users/{}/role", user.id);
+
+        let status = app
+            .request(
+                Method::PUT,
+                &role_path,
+                Some(&app.admin_token),
+                json!({ "role": "admin" }),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+        let admin_token = app
+            .state
+            .auth_service
+            .login(LoginRequest {
+                username: "user".to_string(),
+                password: "password".to_string(),
+                device: None,
+            })
+            .await
+            .unwrap()
+            .token;
+        let status = app
+            .request(Method::GET, "/api/v1/users", Some(&admin_token), json!({}))
+            .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let status = app
+            .request(
+                Method::PUT,
+                &role_path,
+                Some(&app.admin_token),
+                json!({ "role": "user" }),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+
+        // The token issued while they were admin stays valid, without the role
+        let status = app
+            .request(Method::GET, "/api/v1/users", Some(&admin_token), json!({}))
+            .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_user_routes_allowed_for_users() {
+        let app = create_test_app().await;
+
+        let status = app
+            .request(
+                Method::GET,
+                "/api/v1/movies",
+                Some(&app.user_token),
+                json!({}),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+
+        // Users back up their own library; only the database snapshot is admin only
+        let status = app
+            .request(
+                Method::GET,
+                "/api/v1/movies/export",
+                Some(&app.user_token),
+                json!({}),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+}
@@ -0,0 +1,198 @@
+use axum::{
+    Json,
+    body::Body,
+    extract::State,
+    http::{Method, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+
+use my_movies_core::models::{ApiScope, Claims};
+
+/// Check the scopes of an API key. Regular sessions (no scopes) pass every check.
+#[allow(clippy::result_large_err)]
+fn check_scope(request: &Request<Body>, required: ApiScope) -> Result<(), Response> {
+    let Some(claims) = request.extensions().get::<Claims>() else {
+        return Err((
+            StatusCode::UNAUTHORIZED,
+            Json(json!({ "error": "Missing or invalid Authorization header" })),
+        )
+            .into_response());
+    };
+
+    match &claims.scopes {
+        Some(scopes) if !scopes.iter().any(|s| s.grants(required)) => Err((
+            StatusCode::FORBIDDEN,
+            Json(json!({
+                "error": format!("API key is missing the '{}' scope", required.as_str())
+            })),
+        )
+            .into_response()),
+        _ => Ok(()),
+    }
+}
+
+/// Require an API key scope for a route group.
+///
+/// Must run inside `auth_middleware`. Use with
+/// `axum::middleware::from_fn_with_state(ApiScope::Admin, require_scope)`.
+pub async fn require_scope(
+    State(required): State<ApiScope>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    if let Err(response) = check_scope(&request, required) {
+        return response;
+    }
+    next.run(request).await
+}
+
+/// Library routes: reads need `movies:read`, everything else `movies:write`
+pub async fn require_library_scope(request: Request<Body>, next: Next) -> Response {
+    let required = match *request.method() {
+        Method::GET | Method::HEAD => ApiScope::MoviesRead,
+        _ => ApiScope::MoviesWrite,
+    };
+    if let Err(response) = check_scope(&request, required) {
+        return response;
+    }
+    next.run(request).await
+}
+
+/// Account routes (profile, API key management) are not available to API keys
+pub async fn require_session(request: Request<Body>, next: Next) -> Response {
+    if request
+        .extensions()
+        .get::<Claims>()
+        .is_some_and(|c| c.scopes.is_some())
+    {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(json!({ "error": "Not available for API keys" })),
+        )
+            .into_response();
+    }
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use my_movies_core::models::CreateApiKey;
+
+    use super::*;
+    use crate::test_helpers::{TestApp, create_test_app};
+
+    async fn create_key(app: &TestApp, session_token: &str, scopes: Vec<ApiScope>) -> String {
+        let claims = app.state.auth_service.verify_token(session_token).unwrap();
+        app.state
+            .api_key_service
+            .create(
+                claims.id,
+                &claims.role,
+                CreateApiKey {
+                    name: "Home Assistant".to_string(),
+                    scopes,
+                },
+            )
+            .await
+            .unwrap()
+            .token
+    }
+
+    #[tokio::test]
+    async fn test_read_key_cannot_write() {
+        let app = create_test_app().await;
+        let key = create_key(&app, &app.user_token, vec![ApiScope::MoviesRead]).await;
+
+        let status = app
+            .request(Method::GET, "/api/v1/movies", Some(&key), json!({}))
+            .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let status = app
+            .request(
+                Method::POST,
+                "/api/v1/movies",
+                Some(&key),
+                json!({ "title": "Matrix" }),
+            )
+            .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let status = app
+            .request(Method::DELETE, "/api/v1/movies/all", Some(&key), json!({}))
+            .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_write_key_can_write() {
+        let app = create_test_app().await;
+        let key = create_key(&app, &app.user_token, vec![ApiScope::MoviesWrite]).await;
+
+        let status = app
+            .request(
+                Method::POST,
+                "/api/v1/movies",
+                Some(&key),
+                json!({ "title": "Matrix" }),
+            )
+            .await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        let status = app
+            .request(Method::GET, "/api/v1/movies", Some(&key), json!({}))
+            .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_admin_routes_require_admin_scope() {
+        let app = create_test_app().await;
+        let write_key = create_key(&app, &app.admin_token, vec![ApiScope::MoviesWrite]).await;
+        let admin_key = create_key(&app, &app.admin_token, vec![ApiScope::Admin]).await;
+
+        let status = app
+            .request(Method::GET, "/api/v1/settings", Some(&write_key), json!({}))
+            .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let status = app
+            .request(Method::GET, "/api/v1/settings", Some(&admin_key), json!({}))
+            .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_account_routes_reject_api_keys() {
+        let app = create_test_app().await;
+        let key = create_key(&app, &app.user_token, vec![ApiScope::MoviesWrite]).await;
+
+        let status = app
+            .request(Method::GET, "/api/v1/auth/me", Some(&key), json!({}))
+            .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        // Keys can't be used to create further keys
+        let status = app
+            .request(
+                Method::POST,
+                "/api/v1/auth/api-keys",
+                Some(&key),
+                json!({ "name": "x", "scopes": ["movies:write"] }),
+            )
+            .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_key_is_unauthorized() {
+        let app = create_test_app().await;
+
+        let status = app
+            .request(Method::GET, "/api/v1/movies", Some("mm_invalid"), json!({}))
+            .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+}
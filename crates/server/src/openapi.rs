@@ -0,0 +1,2511 @@
+//! OpenAPI 3.1 description of the REST API, served at `/api/v1/openapi.json`
+//! (and as Swagger UI at `/api/docs` with `SWAGGER_UI=true`). The document is
+//! built from `OPERATIONS`, which lists every route of the router with its
+//! request and response schema, so a new route needs an entry here too
+//! (`test_operations_match_router` compares the two).
+
+use std::sync::OnceLock;
+
+use axum::{
+    Json,
+    http::header,
+    response::{Html, IntoResponse},
+};
+use serde_json::{Map, Value, json};
+
+use my_movies_core::models::MOVIE_SUMMARY_FIELDS;
+
+/// Who may call an operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    Public,
+    User,
+    Admin,
+}
+
+/// One method on one path, relative to `/api/v1`
+#[derive(Debug, Clone, Copy)]
+pub struct Operation {
+    pub method: &'static str,
+    pub path: &'static str,
+    /// Handler name, used as `operationId`
+    pub id: &'static str,
+    pub tag: &'static str,
+    pub summary: &'static str,
+    pub access: Access,
+    /// Request body schema, `multipart` for file uploads
+    pub body: Option<&'static str>,
+    /// Response schema; `image`, `zip` and `sqlite` are files, `None` is no body
+    pub response: Option<&'static str>,
+    pub status: u16,
+    /// Path parameters named `id` are integers instead of UUIDs
+    pub integer_id: bool,
+}
+
+const fn op(
+    method: &'static str,
+    path: &'static str,
+    id: &'static str,
+    tag: &'static str,
+    summary: &'static str,
+) -> Operation {
+    Operation {
+        method,
+        path,
+        id,
+        tag,
+        summary,
+        access: Access::User,
+        body: None,
+        response: Some("object"),
+        status: 200,
+        integer_id: false,
+    }
+}
+
+impl Operation {
+    const fn body(self, schema: &'static str) -> Self {
+        Self {
+            body: Some(schema),
+            ..self
+        }
+    }
+
+    const fn returns(self, schema: &'static str) -> Self {
+        Self {
+            response: Some(schema),
+            ..self
+        }
+    }
+
+    const fn created(self, schema: &'static str) -> Self {
+        Self {
+            response: Some(schema),
+            status: 201,
+            ..self
+        }
+    }
+
+    const fn no_content(self) -> Self {
+        Self {
+            response: None,
+            status: 204,
+            ..self
+        }
+    }
+
+    const fn status(self, status: u16) -> Self {
+        Self { status, ..self }
+    }
+
+    const fn public(self) -> Self {
+        Self {
+            access: Access::Public,
+            ..self
+        }
+    }
+
+    const fn admin(self) -> Self {
+        Self {
+            access: Access::Admin,
+            ..self
+        }
+    }
+
+    const fn integer_id(self) -> Self {
+        Self {
+            integer_id: true,
+            ..self
+        }
+    }
+}
+
+const AUTH: &str = "Auth";
+const ACCOUNT: &str = "Account";
+const MOVIES: &str = "Movies";
+const SERIES: &str = "Series";
+const COLLECTIONS: &str = "Collections";
+const LOANS: &str = "Contacts and loans";
+const SHARING: &str = "Sharing";
+const IMPORT: &str = "Import and export";
+const LOOKUP: &str = "Lookup";
+const SYNC: &str = "Sync";
+const NOTIFICATIONS: &str = "Notifications";
+const ADMIN: &str = "Admin";
+
+/// Every route of the API
+pub const OPERATIONS: &[Operation] = &[
+    // Auth
+    op(
+        "POST",
+        "/auth/register",
+        "auth_register",
+        AUTH,
+        "Create an account",
+    )
+    .public()
+    .body("RegisterRequest")
+    .created("AuthResponse"),
+    op("POST", "/auth/login", "auth_login", AUTH, "Log in")
+        .public()
+        .body("LoginRequest")
+        .returns("AuthResponse"),
+    op(
+        "POST",
+        "/auth/refresh",
+        "auth_refresh",
+        AUTH,
+        "New access token for a refresh token",
+    )
+    .public()
+    .body("RefreshRequest")
+    .returns("AuthResponse"),
+    op(
+        "POST",
+        "/auth/forgot-password",
+        "auth_forgot_password",
+        AUTH,
+        "Request a password reset",
+    )
+    .public()
+    .body("ForgotPasswordRequest"),
+    op(
+        "POST",
+        "/auth/reset-password",
+        "auth_reset_password",
+        AUTH,
+        "Set a new password with a reset token",
+    )
+    .public()
+    .body("ResetPasswordRequest"),
+    op(
+        "GET",
+        "/capabilities",
+        "capabilities_get",
+        AUTH,
+        "What this instance supports",
+    )
+    .public()
+    .returns("Capabilities"),
+    op(
+        "GET",
+        "/openapi.json",
+        "openapi_json",
+        AUTH,
+        "This OpenAPI document",
+    )
+    .public(),
+    op("GET", "/auth/me", "auth_me", AUTH, "The current user").returns("User"),
+    op(
+        "POST",
+        "/auth/logout",
+        "auth_logout",
+        AUTH,
+        "Revoke the current session",
+    )
+    .no_content(),
+    op(
+        "GET",
+        "/auth/sessions",
+        "auth_list_sessions",
+        AUTH,
+        "Signed-in devices",
+    )
+    .returns("[Session]"),
+    op(
+        "DELETE",
+        "/auth/sessions/{id}",
+        "auth_revoke_session",
+        AUTH,
+        "Sign out a device",
+    )
+    .no_content(),
+    // Account
+    op(
+        "PUT",
+        "/auth/language",
+        "auth_update_language",
+        ACCOUNT,
+        "Set the UI and TMDB language",
+    )
+    .body("object")
+    .returns("User"),
+    op(
+        "PUT",
+        "/auth/include-adult",
+        "auth_update_include_adult",
+        ACCOUNT,
+        "Include adult titles in TMDB searches",
+    )
+    .body("object")
+    .returns("User"),
+    op(
+        "PUT",
+        "/auth/theme",
+        "auth_update_theme",
+        ACCOUNT,
+        "Set the theme",
+    )
+    .body("object")
+    .returns("User"),
+    op(
+        "PUT",
+        "/auth/loan-reminders",
+        "auth_update_loan_reminders",
+        ACCOUNT,
+        "Reminders for lent movies",
+    )
+    .body("LoanReminderSettings")
+    .returns("User"),
+    op(
+        "PUT",
+        "/auth/digest",
+        "auth_update_digest",
+        ACCOUNT,
+        "When to get the weekly digest email",
+    )
+    .body("DigestSettings")
+    .returns("User"),
+    op(
+        "PUT",
+        "/auth/tmdb",
+        "auth_update_tmdb",
+        ACCOUNT,
+        "TMDB image sizes and metadata languages",
+    )
+    .body("TmdbPreferences")
+    .returns("User"),
+    op(
+        "GET",
+        "/auth/digest/preview",
+        "auth_preview_digest",
+        ACCOUNT,
+        "The digest email as it would be sent now",
+    )
+    .returns("EmailMessage"),
+    op(
+        "PUT",
+        "/auth/player-regions",
+        "auth_update_player_regions",
+        ACCOUNT,
+        "Regions of your players",
+    )
+    .body("object")
+    .returns("User"),
+    op(
+        "PUT",
+        "/auth/currency",
+        "auth_update_preferred_currency",
+        ACCOUNT,
+        "Currency for value reports",
+    )
+    .body("object")
+    .returns("User"),
+    op(
+        "PUT",
+        "/auth/timezone",
+        "auth_update_timezone",
+        ACCOUNT,
+        "Timezone as IANA name",
+    )
+    .body("object")
+    .returns("User"),
+    op(
+        "PUT",
+        "/auth/movie-defaults",
+        "auth_update_movie_defaults",
+        ACCOUNT,
+        "Defaults for new movies",
+    )
+    .body("MovieDefaults")
+    .returns("User"),
+    op(
+        "PUT",
+        "/auth/card-size",
+        "auth_update_card_size",
+        ACCOUNT,
+        "Set the card size",
+    )
+    .body("object")
+    .returns("User"),
+    op(
+        "POST",
+        "/auth/avatar",
+        "auth_upload_avatar",
+        ACCOUNT,
+        "Upload an avatar",
+    )
+    .body("multipart")
+    .returns("User"),
+    op(
+        "DELETE",
+        "/auth/avatar",
+        "auth_delete_avatar",
+        ACCOUNT,
+        "Remove the avatar",
+    )
+    .returns("User"),
+    op(
+        "GET",
+        "/auth/avatar/{id}",
+        "auth_get_avatar",
+        ACCOUNT,
+        "Avatar of a user",
+    )
+    .returns("image"),
+    op(
+        "GET",
+        "/auth/api-keys",
+        "api_keys_list",
+        ACCOUNT,
+        "Your API keys",
+    )
+    .returns("[ApiKey]"),
+    op(
+        "POST",
+        "/auth/api-keys",
+        "api_keys_create",
+        ACCOUNT,
+        "Create an API key; the key is only returned here",
+    )
+    .body("CreateApiKey")
+    .created("CreatedApiKey"),
+    op(
+        "DELETE",
+        "/auth/api-keys/{id}",
+        "api_keys_delete",
+        ACCOUNT,
+        "Revoke an API key",
+    )
+    .no_content(),
+    op(
+        "GET",
+        "/household",
+        "households_get",
+        ACCOUNT,
+        "Your household and its members",
+    )
+    .returns("Household"),
+    op(
+        "POST",
+        "/household",
+        "households_create",
+        ACCOUNT,
+        "Start a household",
+    )
+    .body("object")
+    .created("Household"),
+    op(
+        "DELETE",
+        "/household",
+        "households_delete",
+        ACCOUNT,
+        "Dissolve your household",
+    )
+    .no_content(),
+    op(
+        "GET",
+        "/household/invitations",
+        "households_invitations",
+        ACCOUNT,
+        "Invitations to join a household",
+    )
+    .returns("[HouseholdInvitation]"),
+    op(
+        "POST",
+        "/household/invitations",
+        "households_invite",
+        ACCOUNT,
+        "Invite a user to your household",
+    )
+    .body("object")
+    .returns("Household"),
+    op(
+        "POST",
+        "/household/invitations/{household_id}",
+        "households_accept",
+        ACCOUNT,
+        "Accept an invitation and join the household",
+    )
+    .returns("Household"),
+    op(
+        "DELETE",
+        "/household/invitations/{household_id}",
+        "households_decline",
+        ACCOUNT,
+        "Decline an invitation",
+    )
+    .no_content(),
+    op(
+        "DELETE",
+        "/household/members/{user_id}",
+        "households_remove_member",
+        ACCOUNT,
+        "Remove a member, withdraw an invitation or leave",
+    )
+    .no_content(),
+    // Notifications
+    op(
+        "GET",
+        "/notifications",
+        "notifications_list",
+        NOTIFICATIONS,
+        "Your notification center",
+    )
+    .returns("[Notification]"),
+    op(
+        "POST",
+        "/notifications/read",
+        "notifications_mark_all_read",
+        NOTIFICATIONS,
+        "Mark all as read",
+    ),
+    op(
+        "DELETE",
+        "/notifications/{id}",
+        "notifications_delete",
+        NOTIFICATIONS,
+        "Delete a notification",
+    )
+    .integer_id()
+    .no_content(),
+    op(
+        "POST",
+        "/notifications/{id}/read",
+        "notifications_mark_read",
+        NOTIFICATIONS,
+        "Mark as read",
+    )
+    .integer_id()
+    .no_content(),
+    op(
+        "GET",
+        "/devices",
+        "notifications_list_devices",
+        NOTIFICATIONS,
+        "Devices registered for push",
+    )
+    .returns("[object]"),
+    op(
+        "POST",
+        "/devices",
+        "notifications_register_device",
+        NOTIFICATIONS,
+        "Register a push token",
+    )
+    .body("object")
+    .created("object"),
+    op(
+        "DELETE",
+        "/devices/{id}",
+        "notifications_remove_device",
+        NOTIFICATIONS,
+        "Stop pushing to a device",
+    )
+    .no_content(),
+    // Sharing
+    op("GET", "/shares", "shares_list", SHARING, "Your share links").returns("[ShareLink]"),
+    op(
+        "POST",
+        "/shares",
+        "shares_create",
+        SHARING,
+        "Create a share link; the token is only returned here",
+    )
+    .body("CreateShareLink")
+    .created("CreatedShareLink"),
+    op(
+        "DELETE",
+        "/shares/{id}",
+        "shares_delete",
+        SHARING,
+        "Revoke a share link",
+    )
+    .no_content(),
+    op(
+        "GET",
+        "/shared/{token}",
+        "shares_shared_library",
+        SHARING,
+        "Movies behind a share link",
+    )
+    .public()
+    .returns("SharedLibrary"),
+    op(
+        "GET",
+        "/shared/{token}/movies/{id}/poster",
+        "shares_shared_poster",
+        SHARING,
+        "Poster of a shared movie",
+    )
+    .public()
+    .returns("image"),
+    op(
+        "POST",
+        "/shared/{token}/movies/{id}/borrow",
+        "shares_request_borrow",
+        SHARING,
+        "Ask to borrow a shared movie",
+    )
+    .public()
+    .body("object")
+    .created("object"),
+    // Sync
+    op(
+        "POST",
+        "/sync/pairing-token",
+        "sync_create_pairing_token",
+        SYNC,
+        "Token for pairing another instance",
+    )
+    .created("object"),
+    op(
+        "GET",
+        "/sync/peers",
+        "sync_list_peers",
+        SYNC,
+        "Paired instances",
+    )
+    .returns("[object]")
+    .admin(),
+    op(
+        "POST",
+        "/sync/peers",
+        "sync_create_peer",
+        SYNC,
+        "Pair with another instance",
+    )
+    .body("object")
+    .created("object")
+    .admin(),
+    op(
+        "DELETE",
+        "/sync/peers/{id}",
+        "sync_delete_peer",
+        SYNC,
+        "Unpair an instance",
+    )
+    .no_content()
+    .admin(),
+    op(
+        "POST",
+        "/sync/peers/{id}/run",
+        "sync_run_peer",
+        SYNC,
+        "Sync with a paired instance now",
+    )
+    .admin(),
+    op(
+        "GET",
+        "/sync/tombstones",
+        "sync_tombstones",
+        SYNC,
+        "Deletions since a point in time",
+    )
+    .returns("[object]"),
+    op(
+        "GET",
+        "/activity",
+        "activity_list",
+        SYNC,
+        "Created, changed, deleted and imported entries",
+    )
+    .returns("[ActivityEntry]"),
+    op(
+        "POST",
+        "/sync/exchange",
+        "sync_exchange",
+        SYNC,
+        "Exchange changes with a paired instance",
+    )
+    .body("object"),
+    // Movies
+    op(
+        "GET",
+        "/movies",
+        "movies_list",
+        MOVIES,
+        "List movies as summaries; filters and paging as query parameters, `full=true` for full movies",
+    )
+    .returns("MovieSummaryList"),
+    op("POST", "/movies", "movies_create", MOVIES, "Add a movie")
+        .body("CreateMovie")
+        .created("Movie"),
+    op(
+        "DELETE",
+        "/movies/all",
+        "movies_delete_all",
+        MOVIES,
+        "Delete all your movies",
+    ),
+    op(
+        "PATCH",
+        "/movies/bulk",
+        "movies_bulk_update",
+        MOVIES,
+        "Set fields on many movies",
+    )
+    .body("object"),
+    op(
+        "GET",
+        "/wishlist",
+        "movies_wishlist",
+        MOVIES,
+        "Movies on the wishlist",
+    )
+    .returns("MovieList"),
+    op(
+        "GET",
+        "/movies/suggest",
+        "movies_suggest",
+        MOVIES,
+        "Title suggestions (`?q=`)",
+    )
+    .returns("[object]"),
+    op(
+        "GET",
+        "/search",
+        "search_search",
+        MOVIES,
+        "Full-text search over movies and series (`?q=`)",
+    ),
+    op(
+        "GET",
+        "/movies/recent-views",
+        "movies_recent_views",
+        MOVIES,
+        "Recently viewed movies",
+    )
+    .returns("[Movie]"),
+    op(
+        "GET",
+        "/movies/region-report",
+        "movies_region_report",
+        MOVIES,
+        "Discs your players can't play",
+    ),
+    op(
+        "GET",
+        "/movies/missing-metadata",
+        "movies_missing_metadata",
+        MOVIES,
+        "Movies without TMDB match, poster or description",
+    ),
+    op(
+        "GET",
+        "/movies/value-report",
+        "movies_value_report",
+        MOVIES,
+        "Prices, values and sale proceeds",
+    ),
+    op(
+        "GET",
+        "/valuation/history",
+        "valuation_history",
+        MOVIES,
+        "Purchase cost and current value of the collection per day",
+    )
+    .returns("[ValuationPoint]"),
+    op(
+        "POST",
+        "/valuation/currency",
+        "valuation_bulk_update_currency",
+        MOVIES,
+        "Set or convert the currency of prices and values",
+    )
+    .body("BulkUpdateCurrency"),
+    op(
+        "GET",
+        "/valuation/{entity_type}/{id}",
+        "valuation_get",
+        MOVIES,
+        "Purchase price and current value of a movie, series or collection",
+    )
+    .returns("ItemValue"),
+    op(
+        "PUT",
+        "/valuation/{entity_type}/{id}",
+        "valuation_set_value",
+        MOVIES,
+        "Record the current value of a movie, series or collection",
+    )
+    .body("SetItemValue")
+    .returns("ItemValue"),
+    op(
+        "GET",
+        "/movies/stats",
+        "movies_stats",
+        MOVIES,
+        "Movie counts per availability",
+    ),
+    op(
+        "GET",
+        "/exchange-rates",
+        "settings_get_exchange_rates",
+        MOVIES,
+        "Exchange rates in effect",
+    )
+    .returns("[object]"),
+    op(
+        "GET",
+        "/movies/orphans",
+        "movies_find_orphans",
+        MOVIES,
+        "Movies linked to a missing collection",
+    )
+    .returns("[object]"),
+    op(
+        "POST",
+        "/movies/orphans/fix",
+        "movies_fix_orphans",
+        MOVIES,
+        "Unlink or regroup orphans",
+    )
+    .body("object"),
+    op(
+        "GET",
+        "/movies/check-duplicates",
+        "movies_check_duplicates",
+        MOVIES,
+        "Movies matching a title or barcode",
+    ),
+    op(
+        "GET",
+        "/movies/duplicates",
+        "movies_find_all_duplicates",
+        MOVIES,
+        "Possible duplicates in the library",
+    ),
+    op("GET", "/movies/{id}", "movies_get", MOVIES, "A movie").returns("Movie"),
+    op(
+        "PUT",
+        "/movies/{id}",
+        "movies_update",
+        MOVIES,
+        "Update a movie",
+    )
+    .body("UpdateMovie")
+    .returns("Movie"),
+    op(
+        "DELETE",
+        "/movies/{id}",
+        "movies_delete",
+        MOVIES,
+        "Delete a movie",
+    )
+    .no_content(),
+    op(
+        "POST",
+        "/movies/{id}/view",
+        "movies_record_view",
+        MOVIES,
+        "Record a view",
+    )
+    .no_content(),
+    op(
+        "POST",
+        "/movies/{id}/favorite",
+        "movies_toggle_favorite",
+        MOVIES,
+        "Toggle favorite",
+    )
+    .returns("Movie"),
+    op(
+        "POST",
+        "/movies/{id}/watch",
+        "movies_log_watch",
+        MOVIES,
+        "Log a viewing",
+    )
+    .body("object")
+    .created("object"),
+    op(
+        "GET",
+        "/movies/{id}/watches",
+        "movies_list_watches",
+        MOVIES,
+        "Watch history",
+    )
+    .returns("[object]"),
+    op(
+        "DELETE",
+        "/movies/{id}/watches/{watch_id}",
+        "movies_delete_watch",
+        MOVIES,
+        "Remove a logged viewing",
+    )
+    .no_content(),
+    op(
+        "POST",
+        "/movies/{id}/dispose",
+        "movies_dispose",
+        MOVIES,
+        "Mark as sold, given away or traded",
+    )
+    .body("object")
+    .returns("Movie"),
+    op(
+        "DELETE",
+        "/movies/{id}/dispose",
+        "movies_restore_disposed",
+        MOVIES,
+        "Undo a disposition",
+    )
+    .returns("Movie"),
+    op(
+        "POST",
+        "/movies/{id}/move-to-collection",
+        "movies_move_to_collection",
+        MOVIES,
+        "Turn a wishlist movie into an owned one",
+    )
+    .body("object")
+    .returns("Movie"),
+    op(
+        "GET",
+        "/movies/{id}/condition",
+        "movies_get_condition",
+        MOVIES,
+        "Condition and included extras",
+    ),
+    op(
+        "PUT",
+        "/movies/{id}/condition",
+        "movies_set_condition",
+        MOVIES,
+        "Set condition and extras",
+    )
+    .body("object"),
+    op(
+        "GET",
+        "/movies/{id}/listing-text",
+        "movies_listing_text",
+        MOVIES,
+        "Listing text for marketplaces",
+    ),
+    op(
+        "GET",
+        "/export/spine-index",
+        "movies_spine_index",
+        MOVIES,
+        "Printable index of the shelf (`?format=html|pdf&location=&tag=`)",
+    )
+    .returns("document"),
+    op(
+        "POST",
+        "/movies/{id}/refresh-tmdb",
+        "movies_refresh_tmdb",
+        MOVIES,
+        "Refresh from TMDB",
+    ),
+    op(
+        "POST",
+        "/movies/refresh-tmdb/batch",
+        "movies_refresh_tmdb_batch",
+        MOVIES,
+        "Refresh several movies from TMDB in the background",
+    )
+    .body("RefreshTmdbBatch")
+    .status(202),
+    op(
+        "POST",
+        "/movies/{id}/upload-poster",
+        "movies_upload_poster",
+        MOVIES,
+        "Upload a poster",
+    )
+    .body("multipart"),
+    op(
+        "POST",
+        "/movies/{id}/set-poster-url",
+        "movies_set_poster_from_url",
+        MOVIES,
+        "Download a poster from a URL",
+    )
+    .body("object"),
+    op(
+        "GET",
+        "/movies/{id}/poster",
+        "movies_get_poster",
+        MOVIES,
+        "Poster image",
+    )
+    .returns("image"),
+    op(
+        "GET",
+        "/movies/{id}/backdrop",
+        "movies_get_backdrop",
+        MOVIES,
+        "Backdrop image",
+    )
+    .returns("image"),
+    op(
+        "GET",
+        "/movies/{id}/tmdb-posters",
+        "movies_tmdb_posters",
+        MOVIES,
+        "Posters TMDB has for the movie",
+    )
+    .returns("[object]"),
+    op(
+        "POST",
+        "/movies/{id}/poster/from-tmdb",
+        "movies_set_poster_from_tmdb",
+        MOVIES,
+        "Use a TMDB poster",
+    )
+    .body("object"),
+    op(
+        "GET",
+        "/movies/{id}/thumbnail",
+        "movies_get_thumbnail",
+        MOVIES,
+        "Poster thumbnail",
+    )
+    .returns("image"),
+    op(
+        "GET",
+        "/movies/{id}/analyze-collection",
+        "movies_analyze_collection",
+        MOVIES,
+        "Films contained in a box set",
+    ),
+    op(
+        "POST",
+        "/movies/{id}/split-collection",
+        "movies_split_collection",
+        MOVIES,
+        "Split a box set into its films",
+    )
+    .body("object")
+    .status(202),
+    op(
+        "GET",
+        "/movies/{id}/collection-movies",
+        "movies_get_collection_movies",
+        MOVIES,
+        "Films of a box set",
+    )
+    .returns("[Movie]"),
+    op(
+        "GET",
+        "/planner",
+        "planner_plan",
+        MOVIES,
+        "Movie night proposals",
+    ),
+    op(
+        "POST",
+        "/planner/schedule",
+        "planner_schedule",
+        MOVIES,
+        "Schedule a movie night and announce it on Discord",
+    )
+    .body("object")
+    .created("MovieNight"),
+    op(
+        "GET",
+        "/planner/nights",
+        "planner_list_nights",
+        MOVIES,
+        "Upcoming movie nights",
+    )
+    .returns("[MovieNight]"),
+    op(
+        "DELETE",
+        "/planner/nights/{id}",
+        "planner_cancel_night",
+        MOVIES,
+        "Cancel a movie night",
+    )
+    .no_content(),
+    op(
+        "GET",
+        "/jobs/{id}",
+        "jobs_get",
+        MOVIES,
+        "Progress of a background job",
+    ),
+    op(
+        "POST",
+        "/jobs/{id}/cancel",
+        "jobs_cancel",
+        MOVIES,
+        "Cancel a background job",
+    ),
+    // Series
+    op("GET", "/series", "series_list", SERIES, "List series").returns("[Series]"),
+    op("POST", "/series", "series_create", SERIES, "Add a series")
+        .body("CreateSeries")
+        .created("Series"),
+    op("GET", "/series/{id}", "series_get", SERIES, "A series").returns("Series"),
+    op(
+        "PUT",
+        "/series/{id}",
+        "series_update",
+        SERIES,
+        "Update a series",
+    )
+    .body("object")
+    .returns("Series"),
+    op(
+        "DELETE",
+        "/series/{id}",
+        "series_delete",
+        SERIES,
+        "Delete a series",
+    )
+    .no_content(),
+    op(
+        "GET",
+        "/series/{id}/poster",
+        "series_get_poster",
+        SERIES,
+        "Poster image",
+    )
+    .returns("image"),
+    op(
+        "GET",
+        "/series/{id}/episodes",
+        "series_list_episodes",
+        SERIES,
+        "Episodes with per-season counts",
+    ),
+    op(
+        "POST",
+        "/series/{id}/episodes",
+        "series_create_episode",
+        SERIES,
+        "Add an episode",
+    )
+    .body("object")
+    .created("Episode"),
+    op(
+        "PUT",
+        "/series/{id}/episodes/{episode_id}",
+        "series_update_episode",
+        SERIES,
+        "Update an episode",
+    )
+    .body("object")
+    .returns("Episode"),
+    op(
+        "DELETE",
+        "/series/{id}/episodes/{episode_id}",
+        "series_delete_episode",
+        SERIES,
+        "Delete an episode",
+    )
+    .no_content(),
+    // Collections
+    op(
+        "GET",
+        "/collections",
+        "collections_list",
+        COLLECTIONS,
+        "List collections",
+    )
+    .returns("[Collection]"),
+    op(
+        "POST",
+        "/collections",
+        "collections_create",
+        COLLECTIONS,
+        "Create a collection",
+    )
+    .body("CreateCollection")
+    .created("Collection"),
+    op(
+        "GET",
+        "/collections/{id}",
+        "collections_get",
+        COLLECTIONS,
+        "A collection",
+    )
+    .returns("Collection"),
+    op(
+        "PUT",
+        "/collections/{id}",
+        "collections_update",
+        COLLECTIONS,
+        "Update a collection",
+    )
+    .body("object")
+    .returns("Collection"),
+    op(
+        "DELETE",
+        "/collections/{id}",
+        "collections_delete",
+        COLLECTIONS,
+        "Delete a collection",
+    )
+    .no_content(),
+    op(
+        "GET",
+        "/collections/{id}/items",
+        "collections_get_items",
+        COLLECTIONS,
+        "Items of a collection",
+    )
+    .returns("[CollectionItem]"),
+    op(
+        "POST",
+        "/collections/{id}/items",
+        "collections_add_item",
+        COLLECTIONS,
+        "Add a movie or series",
+    )
+    .body("object")
+    .created("CollectionItem"),
+    op(
+        "DELETE",
+        "/collections/{id}/items/{item_id}",
+        "collections_remove_item",
+        COLLECTIONS,
+        "Remove an item",
+    )
+    .no_content(),
+    // Contacts and loans
+    op(
+        "GET",
+        "/contacts",
+        "contacts_list",
+        LOANS,
+        "People you lend movies to",
+    )
+    .returns("[Contact]"),
+    op(
+        "POST",
+        "/contacts",
+        "contacts_create",
+        LOANS,
+        "Create a contact",
+    )
+    .body("CreateContact")
+    .created("Contact"),
+    op("GET", "/contacts/{id}", "contacts_get", LOANS, "A contact").returns("Contact"),
+    op(
+        "PUT",
+        "/contacts/{id}",
+        "contacts_update",
+        LOANS,
+        "Update a contact",
+    )
+    .body("CreateContact")
+    .returns("Contact"),
+    op(
+        "DELETE",
+        "/contacts/{id}",
+        "contacts_delete",
+        LOANS,
+        "Delete a contact",
+    )
+    .no_content(),
+    op(
+        "GET",
+        "/contacts/{id}/loans",
+        "contacts_loans",
+        LOANS,
+        "Loan history of a contact",
+    )
+    .returns("[object]"),
+    op(
+        "GET",
+        "/borrow-requests",
+        "loans_list_borrow_requests",
+        LOANS,
+        "Requests to borrow your movies",
+    )
+    .returns("[object]"),
+    op(
+        "POST",
+        "/borrow-requests/{id}/approve",
+        "loans_approve_borrow_request",
+        LOANS,
+        "Lend the movie to the requester",
+    )
+    .body("object"),
+    op(
+        "POST",
+        "/borrow-requests/{id}/decline",
+        "loans_decline_borrow_request",
+        LOANS,
+        "Decline a request",
+    ),
+    // Lookup
+    op(
+        "POST",
+        "/scan",
+        "scan_lookup_barcode",
+        LOOKUP,
+        "Look up a barcode",
+    )
+    .body("object"),
+    op(
+        "POST",
+        "/movies/identify-cover",
+        "scan_identify_cover",
+        LOOKUP,
+        "Find TMDB movies from a cover photo",
+    )
+    .body("multipart"),
+    op(
+        "GET",
+        "/tmdb/search/movies",
+        "scan_search_tmdb_movies",
+        LOOKUP,
+        "Search TMDB movies (`?query=`)",
+    ),
+    op(
+        "GET",
+        "/tmdb/search/tv",
+        "scan_search_tmdb_tv",
+        LOOKUP,
+        "Search TMDB series (`?query=`)",
+    ),
+    op(
+        "GET",
+        "/tmdb/movies/{id}",
+        "scan_get_tmdb_movie",
+        LOOKUP,
+        "A TMDB movie",
+    )
+    .integer_id(),
+    op(
+        "GET",
+        "/tmdb/tv/{id}",
+        "scan_get_tmdb_tv",
+        LOOKUP,
+        "A TMDB series",
+    )
+    .integer_id(),
+    // Import and export
+    op(
+        "GET",
+        "/movies/export",
+        "movies_export",
+        IMPORT,
+        "ZIP backup; resumable with range requests",
+    )
+    .returns("zip"),
+    op(
+        "POST",
+        "/movies/import-json",
+        "movies_import_json",
+        IMPORT,
+        "Restore from movies.json",
+    )
+    .body("multipart"),
+    op(
+        "POST",
+        "/movies/import-zip",
+        "movies_import_zip",
+        IMPORT,
+        "Restore from a ZIP backup",
+    )
+    .body("multipart"),
+    op(
+        "POST",
+        "/import/csv",
+        "import_import_csv",
+        IMPORT,
+        "Import a CSV file, optionally read with an import preset (`?preset=Libib`)",
+    )
+    .body("multipart"),
+    op(
+        "GET",
+        "/import/presets",
+        "import_list_presets",
+        IMPORT,
+        "Built-in and saved CSV import presets",
+    )
+    .returns("[ImportPreset]"),
+    op(
+        "PUT",
+        "/import/presets/{name}",
+        "import_save_preset",
+        IMPORT,
+        "Save a CSV import preset",
+    )
+    .body("object")
+    .returns("ImportPreset"),
+    op(
+        "DELETE",
+        "/import/presets/{name}",
+        "import_delete_preset",
+        IMPORT,
+        "Delete a saved CSV import preset",
+    )
+    .no_content(),
+    op(
+        "POST",
+        "/import/dvdprofiler",
+        "import_import_dvdprofiler",
+        IMPORT,
+        "Import a DVD Profiler collection.xml export",
+    )
+    .body("multipart"),
+    op(
+        "POST",
+        "/import/verify",
+        "import_verify_backup",
+        IMPORT,
+        "Check a ZIP backup against its manifest",
+    )
+    .body("multipart"),
+    op(
+        "POST",
+        "/import/reconcile",
+        "import_reconcile_backup",
+        IMPORT,
+        "Compare a backup with the library and merge entries",
+    )
+    .body("multipart"),
+    op(
+        "POST",
+        "/import/enrich-tmdb",
+        "import_enrich_movies_tmdb",
+        IMPORT,
+        "Fetch TMDB data for movies without it",
+    )
+    .status(202),
+    op(
+        "GET",
+        "/import/enrich-tmdb/status",
+        "import_get_enrich_status",
+        IMPORT,
+        "Progress of the TMDB enrichment",
+    ),
+    op(
+        "POST",
+        "/import/enrich-tmdb/cancel",
+        "import_cancel_enrich_tmdb",
+        IMPORT,
+        "Cancel the TMDB enrichment",
+    )
+    .admin(),
+    op(
+        "POST",
+        "/import/media-folder",
+        "import_scan_media_folder",
+        IMPORT,
+        "Propose entries for a folder of video files",
+    )
+    .admin()
+    .body("object"),
+    // Admin
+    op(
+        "GET",
+        "/settings",
+        "settings_get_settings",
+        ADMIN,
+        "All settings",
+    )
+    .admin()
+    .returns("[SettingStatus]"),
+    op(
+        "PUT",
+        "/settings/{key}",
+        "settings_update_setting",
+        ADMIN,
+        "Change a setting",
+    )
+    .admin()
+    .body("object")
+    .returns("SettingStatus"),
+    op(
+        "POST",
+        "/settings/reload",
+        "settings_reload_settings",
+        ADMIN,
+        "Reload settings from the database",
+    )
+    .admin()
+    .returns("[SettingStatus]"),
+    op(
+        "POST",
+        "/settings/test/tmdb",
+        "settings_test_tmdb",
+        ADMIN,
+        "Test the TMDB API key",
+    )
+    .admin(),
+    op(
+        "POST",
+        "/settings/test/discord",
+        "settings_test_discord",
+        ADMIN,
+        "Send a test Discord message",
+    )
+    .admin(),
+    op(
+        "POST",
+        "/settings/test/smtp",
+        "settings_test_smtp",
+        ADMIN,
+        "Send a test email to the admin",
+    )
+    .admin(),
+    op(
+        "POST",
+        "/settings/exchange-rates/refresh",
+        "settings_refresh_exchange_rates",
+        ADMIN,
+        "Fetch exchange rates now",
+    )
+    .admin(),
+    op("GET", "/users", "users_list_users", ADMIN, "All users")
+        .admin()
+        .returns("[User]"),
+    op(
+        "POST",
+        "/users",
+        "users_admin_create_user",
+        ADMIN,
+        "Create a user",
+    )
+    .admin()
+    .body("object"),
+    op(
+        "PUT",
+        "/users/{id}/role",
+        "users_update_user_role",
+        ADMIN,
+        "Change a user's role",
+    )
+    .admin()
+    .body("object")
+    .returns("User"),
+    op(
+        "DELETE",
+        "/users/{id}",
+        "users_delete_user",
+        ADMIN,
+        "Delete a user",
+    )
+    .admin(),
+    op(
+        "PUT",
+        "/users/{id}/password",
+        "users_admin_set_password",
+        ADMIN,
+        "Set a user's password",
+    )
+    .admin()
+    .body("object"),
+    op(
+        "GET",
+        "/invites",
+        "invites_list",
+        ADMIN,
+        "Invites with their uses",
+    )
+    .admin()
+    .returns("[Invite]"),
+    op(
+        "POST",
+        "/invites",
+        "invites_create",
+        ADMIN,
+        "Create an invite; the code is only returned here",
+    )
+    .admin()
+    .body("CreateInvite")
+    .created("CreatedInvite"),
+    op(
+        "DELETE",
+        "/invites/{id}",
+        "invites_delete",
+        ADMIN,
+        "Revoke an invite",
+    )
+    .admin()
+    .no_content(),
+    op(
+        "GET",
+        "/admin/logs",
+        "admin_get_logs",
+        ADMIN,
+        "Tail of the current log file",
+    )
+    .admin(),
+    op(
+        "GET",
+        "/admin/diagnostics",
+        "admin_get_diagnostics",
+        ADMIN,
+        "Support bundle",
+    )
+    .admin(),
+    op(
+        "GET",
+        "/admin/database",
+        "admin_database_snapshot",
+        ADMIN,
+        "Copy of the SQLite database; resumable with range requests",
+    )
+    .admin()
+    .returns("sqlite"),
+    op(
+        "POST",
+        "/admin/repair",
+        "admin_repair",
+        ADMIN,
+        "Find and fix invalid data (`?dry_run=true`)",
+    )
+    .admin(),
+    op(
+        "POST",
+        "/admin/broadcast",
+        "admin_broadcast",
+        ADMIN,
+        "Message to connected users",
+    )
+    .admin()
+    .body("object"),
+    op(
+        "GET",
+        "/admin/barcode-cache",
+        "admin_barcode_cache",
+        ADMIN,
+        "Barcode cache statistics",
+    )
+    .admin(),
+    op(
+        "DELETE",
+        "/admin/barcode-cache",
+        "admin_clear_barcode_cache",
+        ADMIN,
+        "Clear the barcode cache",
+    )
+    .admin(),
+    op(
+        "GET",
+        "/admin/tmdb-cache",
+        "admin_tmdb_cache",
+        ADMIN,
+        "TMDB cache statistics",
+    )
+    .admin(),
+    op(
+        "DELETE",
+        "/admin/tmdb-cache",
+        "admin_clear_tmdb_cache",
+        ADMIN,
+        "Clear the TMDB cache",
+    )
+    .admin(),
+    op(
+        "GET",
+        "/admin/metrics",
+        "admin_request_metrics",
+        ADMIN,
+        "Request latency per endpoint and counts per user",
+    )
+    .admin(),
+];
+
+/// Schema of a field in the compact notation of `SCHEMAS`: `string`,
+/// `integer`, `number`, `boolean`, `uuid`, `date`, `date-time`, `object` or a
+/// schema name; `[...]` is an array, a trailing `?` allows null
+fn field_schema(notation: &str) -> Value {
+    if let Some(inner) = notation.strip_suffix('?') {
+        let mut inner = field_schema(inner);
+        return match inner
+            .get("type")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+        {
+            Some(ty) => {
+                inner["type"] = json!([ty, "null"]);
+                inner
+            }
+            None => json!({ "anyOf": [inner, { "type": "null" }] }),
+        };
+    }
+    if let Some(item) = notation.strip_prefix('[').and_then(|n| n.strip_suffix(']')) {
+        return json!({ "type": "array", "items": field_schema(item) });
+    }
+    match notation {
+        "string" | "integer" | "number" | "boolean" | "object" => json!({ "type": notation }),
+        "uuid" | "date" | "date-time" => json!({ "type": "string", "format": notation }),
+        name => json!({ "$ref": format!("#/components/schemas/{}", name) }),
+    }
+}
+
+/// Object schema; fields whose notation ends in `?` are optional
+fn object(fields: &[(&str, &str)]) -> Value {
+    let properties: Map<String, Value> = fields
+        .iter()
+        .map(|(name, notation)| (name.to_string(), field_schema(notation)))
+        .collect();
+    let required: Vec<&str> = fields
+        .iter()
+        .filter(|(_, notation)| !notation.ends_with('?'))
+        .map(|(name, _)| *name)
+        .collect();
+    json!({ "type": "object", "properties": properties, "required": required })
+}
+
+fn string_enum(values: &[&str]) -> Value {
+    json!({ "type": "string", "enum": values })
+}
+
+/// Named schemas of requests and responses
+fn schemas() -> Map<String, Value> {
+    let movie_fields: &[(&str, &str)] = &[
+        ("id", "uuid"),
+        ("user_id", "uuid"),
+        ("collection_number", "string?"),
+        ("barcode", "string?"),
+        ("tmdb_id", "integer?"),
+        ("imdb_id", "string?"),
+        ("title", "string"),
+        ("original_title", "string?"),
+        ("sort_title", "string?"),
+        ("personal_title", "string?"),
+        ("personal_sort_title", "string?"),
+        ("description", "string?"),
+        ("tagline", "string?"),
+        ("production_year", "integer?"),
+        ("release_date", "date?"),
+        ("running_time", "integer?"),
+        ("director", "string?"),
+        ("actors", "string?"),
+        ("production_companies", "string?"),
+        ("production_countries", "string?"),
+        ("studios", "string?"),
+        ("rating", "string?"),
+        ("personal_rating", "number?"),
+        ("disc_type", "string?"),
+        ("media_type", "string?"),
+        ("discs", "integer?"),
+        ("region_codes", "string?"),
+        ("video_standard", "string?"),
+        ("aspect_ratio", "string?"),
+        ("audio_tracks", "string?"),
+        ("subtitles", "string?"),
+        ("is_3d", "boolean"),
+        ("mastered_in_4k", "boolean"),
+        ("genres", "string?"),
+        ("categories", "string?"),
+        ("tags", "string?"),
+        ("group", "string?"),
+        ("is_collection", "boolean"),
+        ("parent_collection_id", "uuid?"),
+        ("disc_number", "integer?"),
+        ("tmdb_collection_id", "integer?"),
+        ("collection_owned_parts", "integer?"),
+        ("collection_total_parts", "integer?"),
+        ("collection_completeness", "number?"),
+        ("watched", "boolean"),
+        ("times_watched", "integer"),
+        ("last_watched_at", "date?"),
+        ("favorite", "boolean"),
+        ("digital_copies", "string?"),
+        ("status", "string?"),
+        ("condition", "string?"),
+        ("slip_cover", "boolean"),
+        ("cover_type", "string?"),
+        ("edition", "string?"),
+        ("extra_features", "string?"),
+        ("included_extras", "string?"),
+        ("purchase_date", "date?"),
+        ("price", "number?"),
+        ("currency", "string?"),
+        ("purchase_place", "string?"),
+        ("value_date", "date?"),
+        ("value_price", "number?"),
+        ("value_currency", "string?"),
+        ("lent_to", "string?"),
+        ("lent_due", "date?"),
+        ("location", "string?"),
+        ("missing", "boolean"),
+        ("availability", "MovieAvailability?"),
+        ("disposition", "Disposition?"),
+        ("disposed_at", "date?"),
+        ("disposal_price", "number?"),
+        ("disposal_currency", "string?"),
+        ("disposed_to", "string?"),
+        ("added_by", "uuid?"),
+        ("updated_by", "uuid?"),
+        ("owner_name", "string?"),
+        ("notes", "string?"),
+        ("budget", "integer?"),
+        ("revenue", "integer?"),
+        ("spoken_languages", "string?"),
+        ("imdb_rating", "number?"),
+        ("rotten_tomatoes", "integer?"),
+        ("backdrop_hash", "string?"),
+        ("added_date", "date?"),
+        ("created_at", "date-time"),
+        ("updated_at", "date-time"),
+    ];
+    // Every field of a movie except the generated ones can be changed
+    let update_movie: Vec<(&str, &str)> = movie_fields
+        .iter()
+        .filter(|(name, _)| {
+            ![
+                "id",
+                "user_id",
+                "tmdb_collection_id",
+                "collection_owned_parts",
+                "collection_total_parts",
+                "collection_completeness",
+                "times_watched",
+                "last_watched_at",
+                "included_extras",
+                "availability",
+                "disposition",
+                "disposed_at",
+                "disposal_price",
+                "disposal_currency",
+                "disposed_to",
+                "added_by",
+                "updated_by",
+                "owner_name",
+                "backdrop_hash",
+                "added_date",
+                "created_at",
+                "updated_at",
+            ]
+            .contains(name)
+        })
+        .copied()
+        .collect();
+    // Only the fields that are sent are changed
+    let mut update_movie = object(&update_movie);
+    update_movie["required"] = json!([]);
+    update_movie["properties"]["lent_to_contact"] = field_schema("uuid?");
+
+    let mut schemas = Map::new();
+    let mut add = |name: &str, schema: Value| {
+        schemas.insert(name.to_string(), schema);
+    };
+
+    add("Error", object(&[("error", "string"), ("code", "string?")]));
+    add("UserRole", string_enum(&["admin", "user"]));
+    add(
+        "ApiScope",
+        string_enum(&["movies:read", "movies:write", "admin"]),
+    );
+    add(
+        "MovieAvailability",
+        string_enum(&["available", "lent", "missing", "sold", "ordered"]),
+    );
+    add(
+        "Disposition",
+        string_enum(&["sold", "given_away", "traded"]),
+    );
+    add(
+        "RegisterRequest",
+        object(&[
+            ("username", "string"),
+            ("email", "string"),
+            ("password", "string"),
+            ("invite_code", "string?"),
+        ]),
+    );
+    add(
+        "LoginRequest",
+        object(&[
+            ("username", "string"),
+            ("password", "string"),
+            ("device", "string?"),
+        ]),
+    );
+    add("RefreshRequest", object(&[("refresh_token", "string")]));
+    add("ForgotPasswordRequest", object(&[("email", "string")]));
+    add(
+        "ResetPasswordRequest",
+        object(&[("token", "string"), ("new_password", "string")]),
+    );
+    add(
+        "AuthResponse",
+        object(&[
+            ("token", "string"),
+            ("refresh_token", "string"),
+            ("expires_in", "integer"),
+            ("user", "User"),
+        ]),
+    );
+    add(
+        "MovieDefaults",
+        object(&[
+            ("disc_type", "string?"),
+            ("location", "string?"),
+            ("media_type", "string?"),
+            ("currency", "string?"),
+        ]),
+    );
+    add(
+        "LoanReminderSettings",
+        object(&[("enabled", "boolean"), ("days_before", "integer")]),
+    );
+    add(
+        "DigestSettings",
+        object(&[
+            ("enabled", "boolean"),
+            ("weekday", "integer"),
+            ("hour", "integer"),
+        ]),
+    );
+    add(
+        "TmdbPreferences",
+        object(&[
+            ("poster_size", "string?"),
+            ("backdrop_size", "string?"),
+            ("languages", "string?"),
+        ]),
+    );
+    add(
+        "EmailMessage",
+        object(&[("to", "string"), ("subject", "string"), ("body", "string")]),
+    );
+    add(
+        "User",
+        object(&[
+            ("id", "uuid"),
+            ("username", "string"),
+            ("email", "string"),
+            ("role", "UserRole"),
+            ("language", "string?"),
+            ("include_adult", "boolean"),
+            ("avatar_path", "string?"),
+            ("theme", "string?"),
+            ("card_size", "string?"),
+            ("player_regions", "string?"),
+            ("preferred_currency", "string?"),
+            ("movie_defaults", "MovieDefaults"),
+            ("loan_reminders", "LoanReminderSettings"),
+            ("digest", "DigestSettings"),
+            ("tmdb", "TmdbPreferences"),
+            ("timezone", "string?"),
+            ("created_at", "date-time"),
+            ("updated_at", "date-time"),
+        ]),
+    );
+    add(
+        "Session",
+        object(&[
+            ("id", "uuid"),
+            ("device", "string?"),
+            ("created_at", "date-time"),
+            ("last_used_at", "date-time"),
+            ("expires_at", "date-time"),
+            ("current", "boolean"),
+        ]),
+    );
+    add(
+        "ApiKey",
+        object(&[
+            ("id", "uuid"),
+            ("name", "string"),
+            ("token_prefix", "string"),
+            ("scopes", "[ApiScope]"),
+            ("created_at", "date-time"),
+            ("last_used_at", "date-time?"),
+        ]),
+    );
+    add(
+        "CreateApiKey",
+        object(&[("name", "string"), ("scopes", "[ApiScope]")]),
+    );
+    add(
+        "ImportPreset",
+        object(&[
+            ("name", "string"),
+            ("columns", "object"),
+            ("list_separator", "string?"),
+            ("values", "object?"),
+            ("builtin", "boolean"),
+        ]),
+    );
+    add(
+        "CreatedApiKey",
+        object(&[("token", "string"), ("key", "ApiKey")]),
+    );
+    add(
+        "Capabilities",
+        object(&[
+            ("version", "string"),
+            ("schema_version", "integer?"),
+            ("tmdb", "string"),
+            ("email", "string"),
+            ("integrations", "object"),
+            ("registration", "boolean"),
+            ("registration_invite_only", "boolean"),
+            ("read_only", "boolean"),
+            ("demo_mode", "boolean"),
+            ("sharing", "boolean"),
+            ("barcode_providers", "[string]"),
+            ("ocr", "string?"),
+            ("storage", "string"),
+        ]),
+    );
+    add("Movie", object(movie_fields));
+    let summary_fields: Vec<(&str, &str)> = movie_fields
+        .iter()
+        .copied()
+        .filter(|(name, _)| MOVIE_SUMMARY_FIELDS.contains(name) || *name == "owner_name")
+        .collect();
+    add("MovieSummary", object(&summary_fields));
+    add(
+        "MovieSummaryList",
+        object(&[
+            ("items", "[MovieSummary]"),
+            ("total", "integer"),
+            ("limit", "integer"),
+            ("offset", "integer"),
+        ]),
+    );
+    add(
+        "MovieList",
+        object(&[
+            ("items", "[Movie]"),
+            ("total", "integer"),
+            ("limit", "integer"),
+            ("offset", "integer"),
+            ("deleted", "[uuid]?"),
+            ("server_time", "date-time?"),
+        ]),
+    );
+    add(
+        "CreateMovie",
+        object(&[
+            ("title", "string"),
+            ("barcode", "string?"),
+            ("tmdb_id", "integer?"),
+            ("original_title", "string?"),
+            ("disc_type", "string?"),
+            ("production_year", "integer?"),
+        ]),
+    );
+    add("UpdateMovie", update_movie);
+    add(
+        "RefreshTmdbBatch",
+        object(&[("ids", "[uuid]"), ("force", "boolean?")]),
+    );
+    add(
+        "Series",
+        object(&[
+            ("id", "uuid"),
+            ("title", "string"),
+            ("original_title", "string?"),
+            ("tmdb_id", "integer?"),
+            ("production_year", "integer?"),
+            ("first_aired", "date?"),
+            ("network", "string?"),
+            ("status", "string?"),
+            ("episodes_count", "integer?"),
+            ("disc_type", "string?"),
+            ("genres", "string?"),
+            ("watched", "boolean"),
+            ("location", "string?"),
+            ("notes", "string?"),
+            ("created_at", "date-time"),
+            ("updated_at", "date-time"),
+        ]),
+    );
+    add(
+        "CreateSeries",
+        object(&[
+            ("title", "string"),
+            ("barcode", "string?"),
+            ("tmdb_id", "integer?"),
+            ("disc_type", "string?"),
+        ]),
+    );
+    add(
+        "Episode",
+        object(&[
+            ("id", "uuid"),
+            ("series_id", "uuid"),
+            ("season_number", "integer"),
+            ("episode_number", "integer"),
+            ("title", "string?"),
+            ("air_date", "date?"),
+            ("runtime", "integer?"),
+            ("watched", "boolean"),
+            ("owned", "boolean"),
+            ("created_at", "date-time"),
+            ("updated_at", "date-time"),
+        ]),
+    );
+    add(
+        "Collection",
+        object(&[
+            ("id", "uuid"),
+            ("title", "string"),
+            ("sort_title", "string?"),
+            ("description", "string?"),
+            ("disc_type", "string?"),
+            ("barcode", "string?"),
+            ("location", "string?"),
+            ("notes", "string?"),
+            ("created_at", "date-time"),
+            ("updated_at", "date-time"),
+        ]),
+    );
+    add(
+        "CreateCollection",
+        object(&[
+            ("title", "string"),
+            ("barcode", "string?"),
+            ("description", "string?"),
+            ("disc_type", "string?"),
+        ]),
+    );
+    add(
+        "CollectionItem",
+        object(&[
+            ("id", "uuid"),
+            ("collection_id", "uuid"),
+            ("item_type", "string"),
+            ("movie_id", "uuid?"),
+            ("series_id", "uuid?"),
+            ("position", "integer"),
+            ("created_at", "date-time"),
+        ]),
+    );
+    add(
+        "Contact",
+        object(&[
+            ("id", "uuid"),
+            ("name", "string"),
+            ("email", "string?"),
+            ("phone", "string?"),
+            ("notes", "string?"),
+            ("active_loans", "integer"),
+            ("created_at", "date-time"),
+            ("updated_at", "date-time"),
+        ]),
+    );
+    add(
+        "MovieNight",
+        object(&[
+            ("id", "uuid"),
+            ("movie_id", "uuid"),
+            ("title", "string"),
+            ("scheduled_for", "date-time"),
+            ("created_at", "date-time"),
+        ]),
+    );
+    add(
+        "CreateContact",
+        object(&[
+            ("name", "string"),
+            ("email", "string?"),
+            ("phone", "string?"),
+            ("notes", "string?"),
+        ]),
+    );
+    add(
+        "Household",
+        object(&[
+            ("id", "uuid"),
+            ("name", "string"),
+            ("owner_id", "uuid"),
+            ("created_at", "date-time"),
+            ("members", "[object]"),
+            ("invitations", "[HouseholdInvitation]"),
+        ]),
+    );
+    add(
+        "HouseholdInvitation",
+        object(&[
+            ("household_id", "uuid"),
+            ("household_name", "string"),
+            ("user_id", "uuid"),
+            ("username", "string"),
+            ("invited_at", "date-time"),
+        ]),
+    );
+    add(
+        "Notification",
+        object(&[
+            ("id", "integer"),
+            ("kind", "string"),
+            ("level", "string"),
+            ("message", "string"),
+            ("created_at", "date-time"),
+            ("read_at", "date-time?"),
+        ]),
+    );
+    add(
+        "ActivityEntry",
+        object(&[
+            ("id", "integer"),
+            ("action", "string"),
+            ("entity_type", "string"),
+            ("entity_id", "uuid"),
+            ("title", "string?"),
+            ("changes", "object?"),
+            ("created_at", "date-time"),
+        ]),
+    );
+    add(
+        "ItemValue",
+        object(&[
+            ("entity_type", "string"),
+            ("id", "uuid"),
+            ("title", "string"),
+            ("price", "number?"),
+            ("currency", "string?"),
+            ("value_price", "number?"),
+            ("value_currency", "string?"),
+            ("value_date", "date?"),
+        ]),
+    );
+    add(
+        "SetItemValue",
+        object(&[
+            ("value_price", "number?"),
+            ("value_currency", "string?"),
+            ("value_date", "date?"),
+        ]),
+    );
+    add(
+        "BulkUpdateCurrency",
+        object(&[
+            ("currency", "string"),
+            ("from", "string?"),
+            ("convert", "boolean?"),
+        ]),
+    );
+    add(
+        "ValuationPoint",
+        object(&[
+            ("date", "date"),
+            ("totals", "[object]"),
+            ("converted", "object?"),
+        ]),
+    );
+    add(
+        "ShareLink",
+        object(&[
+            ("id", "uuid"),
+            ("collection_id", "uuid?"),
+            ("name", "string?"),
+            ("created_at", "date-time"),
+            ("expires_at", "date-time?"),
+        ]),
+    );
+    add(
+        "CreateShareLink",
+        object(&[
+            ("collection_id", "uuid?"),
+            ("name", "string?"),
+            ("expires_at", "date-time?"),
+        ]),
+    );
+    let mut created_share = object(&[("token", "string")]);
+    created_share["allOf"] = json!([field_schema("ShareLink")]);
+    add("CreatedShareLink", created_share);
+    add(
+        "SharedLibrary",
+        object(&[
+            ("owner", "string"),
+            ("collection", "string?"),
+            ("movies", "[object]"),
+        ]),
+    );
+    add(
+        "Invite",
+        object(&[
+            ("id", "uuid"),
+            ("role", "UserRole"),
+            ("max_uses", "integer"),
+            ("uses", "integer"),
+            ("note", "string?"),
+            ("created_by", "uuid?"),
+            ("created_at", "date-time"),
+            ("expires_at", "date-time?"),
+        ]),
+    );
+    add(
+        "CreateInvite",
+        object(&[
+            ("role", "UserRole?"),
+            ("max_uses", "integer?"),
+            ("note", "string?"),
+            ("expires_at", "date-time?"),
+        ]),
+    );
+    let mut created_invite = object(&[("code", "string")]);
+    created_invite["allOf"] = json!([field_schema("Invite")]);
+    add("CreatedInvite", created_invite);
+    add(
+        "SettingStatus",
+        object(&[
+            ("key", "string"),
+            ("env_var", "string"),
+            ("description", "string"),
+            ("is_configured", "boolean"),
+            ("source", "string"),
+            ("value_preview", "string?"),
+        ]),
+    );
+
+    schemas
+}
+
+fn content(schema: &str) -> Value {
+    match schema {
+        "image" => json!({ "image/*": { "schema": { "type": "string", "format": "binary" } } }),
+        "zip" => {
+            json!({ "application/zip": { "schema": { "type": "string", "format": "binary" } } })
+        }
+        "document" => json!({
+            "text/html": { "schema": { "type": "string" } },
+            "application/pdf": { "schema": { "type": "string", "format": "binary" } }
+        }),
+        "sqlite" => json!({
+            "application/vnd.sqlite3": { "schema": { "type": "string", "format": "binary" } }
+        }),
+        "multipart" => json!({ "multipart/form-data": { "schema": { "type": "object" } } }),
+        schema => json!({ "application/json": { "schema": field_schema(schema) } }),
+    }
+}
+
+fn path_parameters(operation: &Operation) -> Vec<Value> {
+    operation
+        .path
+        .split('/')
+        .filter_map(|segment| segment.strip_prefix('{')?.strip_suffix('}'))
+        .map(|name| {
+            let schema = match name {
+                "token" | "key" | "name" | "entity_type" => json!({ "type": "string" }),
+                "id" if operation.integer_id => json!({ "type": "integer" }),
+                _ => json!({ "type": "string", "format": "uuid" }),
+            };
+            json!({ "name": name, "in": "path", "required": true, "schema": schema })
+        })
+        .collect()
+}
+
+fn operation_json(operation: &Operation) -> Value {
+    let mut response = json!({ "description": "Success" });
+    if let Some(schema) = operation.response {
+        response["content"] = content(schema);
+    }
+    let mut responses = Map::new();
+    responses.insert(operation.status.to_string(), response);
+    responses.insert(
+        "default".to_string(),
+        json!({ "description": "Error", "content": content("Error") }),
+    );
+
+    let mut value = json!({
+        "operationId": operation.id,
+        "tags": [operation.tag],
+        "summary": operation.summary,
+        "responses": responses,
+    });
+    let parameters = path_parameters(operation);
+    if !parameters.is_empty() {
+        value["parameters"] = json!(parameters);
+    }
+    if let Some(body) = operation.body {
+        value["requestBody"] = json!({ "required": true, "content": content(body) });
+    }
+    match operation.access {
+        // No credentials needed
+        Access::Public => value["security"] = json!([]),
+        Access::User => {}
+        Access::Admin => {
+            value["description"] = json!("Admins only; other users get `403`.");
+            value["x-required-role"] = json!("admin");
+        }
+    }
+    value
+}
+
+/// The OpenAPI document
+pub fn spec() -> Value {
+    let mut paths = Map::new();
+    for operation in OPERATIONS {
+        let path = paths
+            .entry(format!("/api/v1{}", operation.path))
+            .or_insert_with(|| json!({}));
+        path[operation.method.to_lowercase()] = operation_json(operation);
+    }
+
+    json!({
+        "openapi": "3.1.0",
+        "info": {
+            "title": "My Movies API",
+            "version": env!("CARGO_PKG_VERSION"),
+            "description": "Movie collection management. Authenticate with the access token \
+                from `/auth/login` or an API key (`mm_...`) as bearer token. \
+                Live updates are sent over the WebSocket at `/ws?token=...`.",
+        },
+        "paths": paths,
+        "components": {
+            "schemas": schemas(),
+            "securitySchemes": {
+                "bearer": {
+                    "type": "http",
+                    "scheme": "bearer",
+                    "description": "Access token or API key",
+                },
+            },
+        },
+        "security": [{ "bearer": [] }],
+    })
+}
+
+/// `GET /api/v1/openapi.json`; built once
+pub async fn openapi_json() -> impl IntoResponse {
+    static SPEC: OnceLock<Value> = OnceLock::new();
+    (
+        [(header::CACHE_CONTROL, "public, max-age=3600")],
+        Json(SPEC.get_or_init(spec).clone()),
+    )
+}
+
+/// `GET /api/docs`: Swagger UI for the document, loaded from a CDN
+pub async fn swagger_ui() -> Html<&'static str> {
+    Html(
+        r##"<!doctype html>
+<html>
+<head>
+  <meta charset="utf-8">
+  <title>My Movies API</title>
+  <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css">
+</head>
+<body>
+  <div id="swagger-ui"></div>
+  <script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js"></script>
+  <script>
+    SwaggerUIBundle({ url: "/api/v1/openapi.json", dom_id: "#swagger-ui" });
+  </script>
+</body>
+</html>"##,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::route_matches;
+
+    #[test]
+    fn test_operations_are_unique() {
+        let mut seen = std::collections::HashSet::new();
+        let mut ids = std::collections::HashSet::new();
+        for operation in OPERATIONS {
+            assert!(
+                seen.insert((operation.method, operation.path)),
+                "{} {} is listed twice",
+                operation.method,
+                operation.path
+            );
+            assert!(ids.insert(operation.id), "{} is used twice", operation.id);
+        }
+    }
+
+    #[test]
+    fn test_operations_match_router() {
+        let tables = [
+            (crate::public_routes(), Access::Public),
+            (crate::account_routes(), Access::User),
+            (crate::library_routes(), Access::User),
+            (crate::admin_routes(), Access::Admin),
+        ];
+        let routes: Vec<_> = tables
+            .iter()
+            .flat_map(|(table, access)| {
+                table.routes().iter().filter_map(move |(method, path)| {
+                    // Public routes are mounted at the root
+                    let path = match access {
+                        Access::Public => path.strip_prefix("/api/v1")?,
+                        _ => path,
+                    };
+                    Some((method.as_str(), path, *access))
+                })
+            })
+            .collect();
+
+        for (method, path, access) in &routes {
+            let operation = OPERATIONS
+                .iter()
+                .find(|o| o.method == *method && route_matches(path, o.path))
+                .unwrap_or_else(|| panic!("{} {} is missing from OPERATIONS", method, path));
+            assert_eq!(operation.access, *access, "{} {}", method, path);
+        }
+        for operation in OPERATIONS {
+            assert!(
+                routes
+                    .iter()
+                    .any(|(method, path, _)| operation.method == *method
+                        && route_matches(path, operation.path)),
+                "{} {} is not a route",
+                operation.method,
+                operation.path
+            );
+        }
+    }
+
+    #[test]
+    fn test_references_resolve() {
+        let spec = spec();
+        let schemas = spec["components"]["schemas"].as_object().unwrap();
+
+        fn refs(value: &Value, found: &mut Vec<String>) {
+            match value {
+                Value::Object(map) => {
+                    if let Some(Value::String(target)) = map.get("$ref") {
+                        found.push(target.clone());
+                    }
+                    map.values().for_each(|v| refs(v, found));
+                }
+                Value::Array(items) => items.iter().for_each(|v| refs(v, found)),
+                _ => {}
+            }
+        }
+        let mut found = Vec::new();
+        refs(&spec, &mut found);
+        assert!(!found.is_empty());
+        for target in found {
+            let name = target.trim_start_matches("#/components/schemas/");
+            assert!(schemas.contains_key(name), "{} is not defined", target);
+        }
+    }
+
+    #[test]
+    fn test_operation_json() {
+        let spec = spec();
+        let get = &spec["paths"]["/api/v1/movies/{id}"]["get"];
+        assert_eq!(get["operationId"], "movies_get");
+        assert_eq!(get["parameters"][0]["schema"]["format"], "uuid");
+        assert_eq!(
+            get["responses"]["200"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/Movie"
+        );
+
+        let login = &spec["paths"]["/api/v1/auth/login"]["post"];
+        assert_eq!(login["security"], json!([]));
+        let delete = &spec["paths"]["/api/v1/notifications/{id}"]["delete"];
+        assert_eq!(delete["parameters"][0]["schema"]["type"], "integer");
+        assert!(delete["responses"]["204"].get("content").is_none());
+        let settings = &spec["paths"]["/api/v1/settings"]["get"];
+        assert_eq!(settings["x-required-role"], "admin");
+    }
+
+    #[test]
+    fn test_field_schema() {
+        assert_eq!(
+            field_schema("string?"),
+            json!({ "type": ["string", "null"] })
+        );
+        assert_eq!(
+            field_schema("[uuid]"),
+            json!({ "type": "array", "items": { "type": "string", "format": "uuid" } })
+        );
+        assert_eq!(
+            field_schema("User?"),
+            json!({ "anyOf": [{ "$ref": "#/components/schemas/User" }, { "type": "null" }] })
+        );
+    }
+}
@@ -0,0 +1,206 @@
+//! Client side of instance-to-instance sync: sends this server's changes to each
+//! paired peer, applies what comes back, and records the result on the peer.
+
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde_json::json;
+
+use my_movies_core::models::{SyncExchangeRequest, SyncExchangeResponse, SyncOutcome, SyncPeer};
+
+use crate::AppState;
+use crate::routes::ws::WsEvent;
+
+/// How often all peers are synced
+const SYNC_INTERVAL: Duration = Duration::from_secs(15 * 60);
+/// A whole library can take a while on the first run
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(300);
+/// How far the next run reaches back before the last one, for clock skew
+/// between the servers and changes committed while a run was in flight
+const SINCE_MARGIN: chrono::TimeDelta = chrono::TimeDelta::minutes(5);
+
+/// Sync every paired peer periodically in the background
+pub fn spawn_peer_sync(state: Arc<AppState>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(SYNC_INTERVAL);
+        // Don't sync right at startup
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            // Applying a peer's changes would write to the library
+            if state.read_only.load(Ordering::Relaxed) {
+                continue;
+            }
+            let peers = match state.sync_service.all_peers().await {
+                Ok(peers) => peers,
+                Err(e) => {
+                    tracing::error!("Failed to load sync peers: {}", e);
+                    continue;
+                }
+            };
+            for peer in peers {
+                if let Err(e) = sync_peer(&state, &peer).await {
+                    tracing::warn!("Sync with {} failed: {}", peer.url, e);
+                }
+            }
+        }
+    });
+}
+
+/// Run one sync with `peer`, store the result and notify the owner's clients
+pub async fn sync_peer(state: &AppState, peer: &SyncPeer) -> Result<SyncOutcome, String> {
+    let started = Utc::now();
+    let result = exchange(state, peer).await;
+    let synced_at = match &result {
+        Ok((_, server_time)) => next_since(started, *server_time),
+        Err(_) => started,
+    };
+    let result = result.map(|(outcome, _)| outcome);
+
+    if let Err(e) = state
+        .sync_service
+        .record_run(peer.id, synced_at, result.as_ref().map_err(Clone::clone))
+        .await
+    {
+        tracing::error!("Failed to record sync result: {}", e);
+    }
+
+    let msg = json!({
+        "type": "sync_completed",
+        "payload": {
+            "peer_id": peer.id,
+            "user_id": peer.user_id,
+            "outcome": result.as_ref().ok(),
+            "error": result.as_ref().err(),
+        }
+    });
+    let _ = state.ws_broadcast.send(WsEvent::user(peer.user_id, &msg));
+
+    result
+}
+
+/// Where the next run starts: each side picks changes by its own clock, so
+/// the earlier of both, minus a margin. Changes seen twice are skipped.
+fn next_since(started: DateTime<Utc>, server_time: DateTime<Utc>) -> DateTime<Utc> {
+    started.min(server_time) - SINCE_MARGIN
+}
+
+/// Swap changes with `peer`; returns the outcome and the peer's clock
+async fn exchange(
+    state: &AppState,
+    peer: &SyncPeer,
+) -> Result<(SyncOutcome, DateTime<Utc>), String> {
+    let since = peer.last_synced_at;
+    let token = state
+        .sync_service
+        .peer_token(peer)
+        .map_err(|e| e.to_string())?;
+    let changes = state
+        .sync_service
+        .changes_since(peer.user_id, since)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // A peer only ever answers itself; redirects could point anywhere
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let response = client
+        .post(format!("{}/api/v1/sync/exchange", peer.url))
+        .bearer_auth(&token)
+        .json(&SyncExchangeRequest { since, changes })
+        .send()
+        .await
+        .map_err(|e| {
+            tracing::warn!("Sync peer {} unreachable: {}", peer.url, e);
+            "Peer unreachable".to_string()
+        })?;
+
+    if !response.status().is_success() {
+        return Err(format!("Peer returned {}", response.status()));
+    }
+
+    let remote: SyncExchangeResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid response from peer: {}", e))?;
+
+    let outcome = state
+        .sync_service
+        .apply(peer.user_id, since, remote.changes)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok((outcome, remote.server_time))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{Router, response::Redirect, routing::post};
+
+    use my_movies_core::models::CreateSyncPeer;
+
+    use super::*;
+    use crate::test_helpers::create_test_app;
+
+    #[test]
+    fn test_next_since_follows_the_earlier_clock() {
+        let started = Utc::now();
+
+        // The peer's clock is behind: it would skip its own changes made
+        // between its clock and ours
+        let behind = started - chrono::TimeDelta::minutes(10);
+        assert_eq!(next_since(started, behind), behind - SINCE_MARGIN);
+
+        let ahead = started + chrono::TimeDelta::minutes(10);
+        assert_eq!(next_since(started, ahead), started - SINCE_MARGIN);
+    }
+
+    #[tokio::test]
+    async fn test_redirects_are_not_followed() {
+        let app = create_test_app().await;
+        // Would answer 401 to the peer's token if the redirect were followed
+        let target = format!("{}/api/v1/sync/exchange", app.serve().await);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let router = Router::new().route(
+            "/api/v1/sync/exchange",
+            post(move || async move { Redirect::temporary(&target) }),
+        );
+        tokio::spawn(async move {
+            axum::serve(listener, router).await.ok();
+        });
+
+        let admin = app
+            .state
+            .auth_service
+            .get_user_by_username("admin")
+            .await
+            .unwrap();
+        let peer = app
+            .state
+            .sync_service
+            .create_peer(
+                admin.id,
+                CreateSyncPeer {
+                    name: "Elsewhere".to_string(),
+                    url,
+                    token: "mm_abc".to_string(),
+                },
+            )
+            .await
+            .unwrap();
+        let peer = app
+            .state
+            .sync_service
+            .get_peer(admin.id, peer.id)
+            .await
+            .unwrap();
+
+        let error = sync_peer(&app.state, &peer).await.unwrap_err();
+        assert_eq!(error, "Peer returned 307 Temporary Redirect");
+    }
+}
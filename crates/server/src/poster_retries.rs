@@ -0,0 +1,80 @@
+//! Retries poster downloads that failed, with the backoff kept by
+//! `PosterRetryService`. Downloads that keep failing end up in the
+//! missing-metadata report.
+
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use serde_json::json;
+
+use crate::AppState;
+use crate::routes::movies::{fetch_image, poster_source_url, tmdb_options};
+use crate::routes::ws::WsEvent;
+
+/// How often due retries are looked for; matches the shortest backoff
+const RETRY_INTERVAL: Duration = Duration::from_secs(15 * 60);
+/// Downloads per run, so a TMDB outage doesn't turn into a burst afterwards
+const RETRIES_PER_RUN: i64 = 50;
+
+/// Retry failed poster downloads periodically in the background
+pub fn spawn_poster_retries(state: Arc<AppState>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(RETRY_INTERVAL);
+        loop {
+            interval.tick().await;
+            // Storing a poster would write to the library
+            if state.read_only.load(Ordering::Relaxed) {
+                continue;
+            }
+            retry_due(&state).await;
+        }
+    });
+}
+
+async fn retry_due(state: &AppState) {
+    let due = match state
+        .poster_retry_service
+        .due(chrono::Utc::now(), RETRIES_PER_RUN)
+        .await
+    {
+        Ok(due) => due,
+        Err(e) => {
+            tracing::error!("Failed to load poster retries: {}", e);
+            return;
+        }
+    };
+
+    for retry in due {
+        let options = match state.auth_service.get_user(retry.user_id).await {
+            Ok(user) => tmdb_options(state, &user),
+            Err(_) => state.tmdb_service.options(None, None),
+        };
+        match fetch_image(&poster_source_url(&retry.source, &options.poster_size)).await {
+            Ok(data) => {
+                // Also removes the retry entry
+                match state
+                    .movie_service
+                    .update_movie_poster_data(retry.user_id, retry.movie_id, Some(data))
+                    .await
+                {
+                    Ok(movie) => {
+                        tracing::info!("Downloaded poster of {} on retry", retry.title);
+                        let msg = json!({ "type": "movie_updated", "payload": movie });
+                        let _ = state.ws_broadcast.send(WsEvent::user(retry.user_id, &msg));
+                    }
+                    Err(e) => tracing::warn!("Failed to store poster of {}: {}", retry.title, e),
+                }
+            }
+            Err(e) => {
+                if let Err(e) = state
+                    .poster_retry_service
+                    .record_failure(retry.user_id, retry.movie_id, &retry.source, &e)
+                    .await
+                {
+                    tracing::error!("Failed to record poster retry: {}", e);
+                }
+            }
+        }
+    }
+}
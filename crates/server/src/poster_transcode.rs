@@ -0,0 +1,201 @@
+//! WebP/AVIF delivery of stored posters, negotiated via the `Accept` header.
+//! Transcoded images are cached by content hash, so changed posters never hit stale entries.
+
+use std::collections::{HashMap, VecDeque};
+use std::io::Cursor;
+use std::sync::Mutex;
+
+/// Upper bound for all cached transcoded images
+const CACHE_MAX_BYTES: usize = 64 * 1024 * 1024;
+
+const WEBP_QUALITY: f32 = 80.0;
+/// ravif speed (1 = slowest/best, 10 = fastest); posters are encoded on request
+const AVIF_SPEED: u8 = 8;
+const AVIF_QUALITY: u8 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PosterFormat {
+    Avif,
+    Webp,
+}
+
+impl PosterFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            PosterFormat::Avif => "image/avif",
+            PosterFormat::Webp => "image/webp",
+        }
+    }
+}
+
+/// Best format the client accepts, `None` to serve the original.
+/// AVIF is preferred over WebP; entries with `q=0` are treated as not accepted.
+pub fn negotiate(accept: Option<&str>) -> Option<PosterFormat> {
+    let accept = accept?;
+    let accepts = |mime: &str| {
+        accept.split(',').any(|entry| {
+            let mut parts = entry.split(';').map(str::trim);
+            parts.next().is_some_and(|m| m.eq_ignore_ascii_case(mime))
+                && !parts.any(|p| {
+                    p.strip_prefix("q=")
+                        .and_then(|q| q.parse::<f32>().ok())
+                        .is_some_and(|q| q <= 0.0)
+                })
+        })
+    };
+
+    if accepts("image/avif") {
+        Some(PosterFormat::Avif)
+    } else if accepts("image/webp") {
+        Some(PosterFormat::Webp)
+    } else {
+        None
+    }
+}
+
+/// Re-encode an image. CPU heavy, call from a blocking task.
+pub fn transcode(data: &[u8], format: PosterFormat) -> Result<Vec<u8>, String> {
+    let img = image::ImageReader::new(Cursor::new(data))
+        .with_guessed_format()
+        .map_err(|e| format!("Failed to read image format: {}", e))?
+        .decode()
+        .map_err(|e| format!("Failed to decode image: {}", e))?;
+
+    match format {
+        PosterFormat::Webp => {
+            // image only encodes lossless WebP, which is larger than the source JPEG
+            let rgb = image::DynamicImage::ImageRgb8(img.to_rgb8());
+            let encoder = webp::Encoder::from_image(&rgb)
+                .map_err(|e| format!("Failed to encode WebP: {}", e))?;
+            Ok(encoder.encode(WEBP_QUALITY).to_vec())
+        }
+        PosterFormat::Avif => {
+            let mut output = Vec::new();
+            let encoder = image::codecs::avif::AvifEncoder::new_with_speed_quality(
+                &mut output,
+                AVIF_SPEED,
+                AVIF_QUALITY,
+            );
+            image::DynamicImage::ImageRgb8(img.to_rgb8())
+                .write_with_encoder(encoder)
+                .map_err(|e| format!("Failed to encode AVIF: {}", e))?;
+            Ok(output)
+        }
+    }
+}
+
+#[derive(Default)]
+struct CacheInner {
+    entries: HashMap<(String, PosterFormat), Vec<u8>>,
+    /// Insertion order, oldest first
+    order: VecDeque<(String, PosterFormat)>,
+    bytes: usize,
+}
+
+/// Transcoded posters keyed by image hash and format, bounded by total size
+#[derive(Default)]
+pub struct TranscodeCache {
+    inner: Mutex<CacheInner>,
+}
+
+impl TranscodeCache {
+    pub fn get(&self, hash: &str, format: PosterFormat) -> Option<Vec<u8>> {
+        let inner = self.inner.lock().ok()?;
+        inner.entries.get(&(hash.to_string(), format)).cloned()
+    }
+
+    pub fn insert(&self, hash: String, format: PosterFormat, data: Vec<u8>) {
+        let Ok(mut inner) = self.inner.lock() else {
+            return;
+        };
+        if data.len() > CACHE_MAX_BYTES {
+            return;
+        }
+
+        let key = (hash, format);
+        if let Some(previous) = inner.entries.remove(&key) {
+            inner.bytes -= previous.len();
+            inner.order.retain(|k| k != &key);
+        }
+
+        while inner.bytes + data.len() > CACHE_MAX_BYTES {
+            let Some(oldest) = inner.order.pop_front() else {
+                break;
+            };
+            if let Some(evicted) = inner.entries.remove(&oldest) {
+                inner.bytes -= evicted.len();
+            }
+        }
+
+        inner.bytes += data.len();
+        inner.order.push_back(key.clone());
+        inner.entries.insert(key, data);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_jpeg() -> Vec<u8> {
+        let img =
+            image::RgbImage::from_fn(64, 96, |x, y| image::Rgb([x as u8 * 4, y as u8 * 2, 128]));
+        let mut output = Cursor::new(Vec::new());
+        image::DynamicImage::ImageRgb8(img)
+            .write_to(&mut output, image::ImageFormat::Jpeg)
+            .unwrap();
+        output.into_inner()
+    }
+
+    #[test]
+    fn test_negotiate_prefers_avif() {
+        assert_eq!(
+            negotiate(Some("image/avif,image/webp,image/apng,*/*;q=0.8")),
+            Some(PosterFormat::Avif)
+        );
+        assert_eq!(negotiate(Some("image/webp,*/*")), Some(PosterFormat::Webp));
+        assert_eq!(negotiate(Some("image/png,image/*;q=0.8")), None);
+        assert_eq!(negotiate(None), None);
+    }
+
+    #[test]
+    fn test_negotiate_ignores_q_zero() {
+        assert_eq!(
+            negotiate(Some("image/avif;q=0, image/webp")),
+            Some(PosterFormat::Webp)
+        );
+    }
+
+    #[test]
+    fn test_transcode_webp() {
+        let webp = transcode(&sample_jpeg(), PosterFormat::Webp).unwrap();
+        assert_eq!(&webp[0..4], b"RIFF");
+        assert_eq!(&webp[8..12], b"WEBP");
+    }
+
+    #[test]
+    fn test_transcode_avif() {
+        let avif = transcode(&sample_jpeg(), PosterFormat::Avif).unwrap();
+        assert_eq!(&avif[4..8], b"ftyp");
+    }
+
+    #[test]
+    fn test_transcode_rejects_invalid_data() {
+        assert!(transcode(b"not an image", PosterFormat::Webp).is_err());
+    }
+
+    #[test]
+    fn test_cache_evicts_oldest() {
+        let cache = TranscodeCache::default();
+        let half = vec![0u8; CACHE_MAX_BYTES / 2];
+
+        cache.insert("a".to_string(), PosterFormat::Webp, half.clone());
+        cache.insert("b".to_string(), PosterFormat::Webp, half.clone());
+        cache.insert("c".to_string(), PosterFormat::Webp, half);
+
+        assert!(cache.get("a", PosterFormat::Webp).is_none());
+        assert!(cache.get("b", PosterFormat::Webp).is_some());
+        assert!(cache.get("c", PosterFormat::Webp).is_some());
+        assert!(cache.get("c", PosterFormat::Avif).is_none());
+    }
+}
@@ -0,0 +1,143 @@
+//! Token bucket rate limiting for endpoints that could be hammered: the TMDB
+//! search proxy (per user), borrow requests through share links (per link)
+//! and password reset emails (per email address)
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use axum::{
+    Json,
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+use uuid::Uuid;
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Token bucket rate limiter keyed by user, share link or email address
+pub struct RateLimiter<K = Uuid> {
+    buckets: Mutex<HashMap<K, Bucket>>,
+    /// Requests a key can make in a burst
+    capacity: f64,
+    /// Requests per second added back to the budget
+    refill_per_second: f64,
+}
+
+impl<K: Eq + Hash> RateLimiter<K> {
+    pub fn new(capacity: f64, refill_per_second: f64) -> Self {
+        Self {
+            buckets: Mutex::new(HashMap::new()),
+            capacity,
+            refill_per_second,
+        }
+    }
+
+    /// Take one request from the key's budget.
+    /// Returns how long to wait if the budget is used up.
+    pub fn check(&self, key: K) -> Result<(), Duration> {
+        self.check_at(key, Instant::now())
+    }
+
+    fn check_at(&self, key: K, now: Instant) -> Result<(), Duration> {
+        let Ok(mut buckets) = self.buckets.lock() else {
+            return Ok(());
+        };
+
+        // Full buckets carry no information, drop them to keep the map small
+        buckets.retain(|_, b| {
+            b.tokens + now.duration_since(b.updated).as_secs_f64() * self.refill_per_second
+                < self.capacity
+        });
+
+        let bucket = buckets.entry(key).or_insert(Bucket {
+            tokens: self.capacity,
+            updated: now,
+        });
+
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.refill_per_second).min(self.capacity);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - bucket.tokens) / self.refill_per_second,
+            ))
+        }
+    }
+}
+
+/// 429 response telling the client when to try again
+pub fn too_many_requests(retry_after: Duration, message: &str) -> Response {
+    let seconds = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+    (
+        StatusCode::TOO_MANY_REQUESTS,
+        [(header::RETRY_AFTER, seconds.to_string())],
+        Json(json!({ "error": message })),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CAPACITY: f64 = 20.0;
+
+    #[test]
+    fn test_rate_limiter_allows_burst_then_limits() {
+        let limiter = RateLimiter::new(CAPACITY, 2.0);
+        let user = Uuid::new_v4();
+        let now = Instant::now();
+
+        for _ in 0..CAPACITY as usize {
+            assert!(limiter.check_at(user, now).is_ok());
+        }
+
+        let retry_after = limiter.check_at(user, now).unwrap_err();
+        assert!(retry_after > Duration::ZERO);
+        assert!(retry_after <= Duration::from_secs(1));
+
+        // Other users have their own budget
+        assert!(limiter.check_at(Uuid::new_v4(), now).is_ok());
+    }
+
+    #[test]
+    fn test_rate_limiter_refills() {
+        let limiter = RateLimiter::new(CAPACITY, 2.0);
+        let user = Uuid::new_v4();
+        let now = Instant::now();
+
+        for _ in 0..CAPACITY as usize {
+            limiter.check_at(user, now).unwrap();
+        }
+        assert!(limiter.check_at(user, now).is_err());
+
+        assert!(limiter.check_at(user, now + Duration::from_secs(1)).is_ok());
+    }
+
+    #[test]
+    fn test_slow_refill() {
+        // One request per minute
+        let limiter = RateLimiter::new(1.0, 1.0 / 60.0);
+        let link = Uuid::new_v4();
+        let now = Instant::now();
+
+        limiter.check_at(link, now).unwrap();
+        let retry_after = limiter.check_at(link, now).unwrap_err();
+        assert!(retry_after > Duration::from_secs(59));
+        assert!(
+            limiter
+                .check_at(link, now + Duration::from_secs(61))
+                .is_ok()
+        );
+    }
+}
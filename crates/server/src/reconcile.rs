@@ -0,0 +1,391 @@
+//! Three-way comparison of a backup with the live library, so a backup from
+//! another instance (or an older one of this instance) can be merged
+//! selectively instead of being imported wholesale
+
+use std::collections::{HashMap, HashSet};
+use std::io::{Cursor, Read};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+use my_movies_core::models::{Movie, Tombstone};
+
+use crate::backup::MOVIES_FILE;
+use crate::routes::movies::{ExportData, ExportMovie};
+
+/// In the backup, not in the library
+#[derive(Debug, Clone, Serialize)]
+pub struct NewEntry {
+    pub backup_id: String,
+    pub title: String,
+    pub production_year: Option<i32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub backup: Value,
+    pub live: Value,
+}
+
+/// In both, with different values
+#[derive(Debug, Clone, Serialize)]
+pub struct ChangedEntry {
+    pub backup_id: String,
+    pub movie_id: Uuid,
+    pub title: String,
+    pub fields: Vec<FieldChange>,
+    /// The library entry was edited after the backup was taken
+    pub changed_since_backup: bool,
+}
+
+/// In the backup, deleted from the library after the backup was taken
+#[derive(Debug, Clone, Serialize)]
+pub struct DeletedEntry {
+    pub backup_id: String,
+    pub movie_id: Uuid,
+    pub title: String,
+    pub deleted_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ReconcileReport {
+    pub exported_at: Option<DateTime<Utc>>,
+    pub new: Vec<NewEntry>,
+    pub changed: Vec<ChangedEntry>,
+    pub deleted: Vec<DeletedEntry>,
+    pub unchanged: usize,
+    /// Library ID for every matched backup ID
+    #[serde(skip)]
+    pub matches: HashMap<String, Uuid>,
+}
+
+/// Backup entries to apply, by backup ID. New and deleted entries are created
+/// from the backup, changed ones take the backup's values.
+#[derive(Debug, Default, Deserialize)]
+pub struct ReconcileSelection {
+    #[serde(default)]
+    pub new: Vec<String>,
+    #[serde(default)]
+    pub changed: Vec<String>,
+    #[serde(default)]
+    pub deleted: Vec<String>,
+}
+
+fn usable_barcode(barcode: &Option<String>) -> Option<&str> {
+    barcode
+        .as_deref()
+        .map(str::trim)
+        .filter(|b| !b.is_empty() && !b.chars().all(|c| c == '0'))
+}
+
+/// Library entry for a backup entry: same ID (backup of this instance), else
+/// same barcode, else same TMDB ID and title
+fn find_match<'a>(
+    entry: &ExportMovie,
+    live: &'a [Movie],
+    taken: &HashSet<Uuid>,
+) -> Option<&'a Movie> {
+    let free = || live.iter().filter(|m| !taken.contains(&m.id));
+
+    if let Ok(id) = entry.id.parse::<Uuid>()
+        && let Some(movie) = free().find(|m| m.id == id)
+    {
+        return Some(movie);
+    }
+    if let Some(barcode) = usable_barcode(&entry.barcode)
+        && let Some(movie) = free().find(|m| usable_barcode(&m.barcode) == Some(barcode))
+    {
+        return Some(movie);
+    }
+    let tmdb_id = entry.tmdb_id.filter(|id| *id > 0)?;
+    free().find(|m| m.tmdb_id == Some(tmdb_id) && m.title.eq_ignore_ascii_case(&entry.title))
+}
+
+/// Fields carried by backups that differ between the backup and the library
+pub fn diff_fields(entry: &ExportMovie, movie: &Movie) -> Vec<FieldChange> {
+    let pairs = [
+        ("title", json!(entry.title), json!(movie.title)),
+        (
+            "original_title",
+            json!(entry.original_title),
+            json!(movie.original_title),
+        ),
+        (
+            "sort_title",
+            json!(entry.sort_title),
+            json!(movie.sort_title),
+        ),
+        (
+            "description",
+            json!(entry.description),
+            json!(movie.description),
+        ),
+        (
+            "production_year",
+            json!(entry.production_year),
+            json!(movie.production_year),
+        ),
+        ("disc_type", json!(entry.disc_type), json!(movie.disc_type)),
+        (
+            "running_time",
+            json!(entry.running_time),
+            json!(movie.running_time),
+        ),
+        ("genres", json!(entry.genres), json!(movie.genres)),
+        ("director", json!(entry.director), json!(movie.director)),
+        ("actors", json!(entry.actors), json!(movie.actors)),
+        ("watched", json!(entry.watched), json!(movie.watched)),
+        ("favorite", json!(entry.favorite), json!(movie.favorite)),
+        ("location", json!(entry.location), json!(movie.location)),
+        ("rating", json!(entry.rating), json!(movie.rating)),
+        (
+            "personal_rating",
+            json!(entry.personal_rating),
+            json!(movie.personal_rating),
+        ),
+        ("notes", json!(entry.notes), json!(movie.notes)),
+        ("tags", json!(entry.tags), json!(movie.tags)),
+        ("lent_to", json!(entry.lent_to), json!(movie.lent_to)),
+        ("lent_due", json!(entry.lent_due), json!(movie.lent_due)),
+        (
+            "disc_number",
+            json!(entry.disc_number),
+            json!(movie.disc_number),
+        ),
+    ];
+
+    pairs
+        .into_iter()
+        .filter(|(_, backup, live)| backup != live)
+        .map(|(field, backup, live)| FieldChange {
+            field,
+            backup,
+            live,
+        })
+        .collect()
+}
+
+/// Movies and posters (by backup ID) of a ZIP backup or a plain movies.json
+pub fn read_backup(data: &[u8]) -> Result<(ExportData, HashMap<String, Vec<u8>>), String> {
+    if !data.starts_with(b"PK") {
+        let export = serde_json::from_slice(data).map_err(|e| format!("Invalid backup: {}", e))?;
+        return Ok((export, HashMap::new()));
+    }
+
+    let mut archive =
+        zip::ZipArchive::new(Cursor::new(data)).map_err(|e| format!("Invalid ZIP file: {}", e))?;
+    let mut json = Vec::new();
+    archive
+        .by_name(MOVIES_FILE)
+        .map_err(|_| format!("Backup does not contain {}", MOVIES_FILE))?
+        .read_to_end(&mut json)
+        .map_err(|e| format!("Failed to read {}: {}", MOVIES_FILE, e))?;
+    let export =
+        serde_json::from_slice(&json).map_err(|e| format!("Invalid {}: {}", MOVIES_FILE, e))?;
+
+    let mut posters = HashMap::new();
+    for i in 0..archive.len() {
+        let Ok(mut file) = archive.by_index(i) else {
+            continue;
+        };
+        let Some(id) = file
+            .name()
+            .strip_prefix("posters/")
+            .and_then(|name| name.strip_suffix(".jpg"))
+            .map(str::to_string)
+        else {
+            continue;
+        };
+        let mut poster = Vec::new();
+        if file.read_to_end(&mut poster).is_ok() {
+            posters.insert(id, poster);
+        }
+    }
+
+    Ok((export, posters))
+}
+
+/// Compare a backup with the library. `deletions` are the library's movie
+/// tombstones; entries deleted after the backup was taken are reported as
+/// deleted instead of new.
+pub fn reconcile(backup: &ExportData, live: &[Movie], deletions: &[Tombstone]) -> ReconcileReport {
+    let exported_at = DateTime::parse_from_rfc3339(&backup.exported_at)
+        .ok()
+        .map(|d| d.with_timezone(&Utc));
+    let deleted_at: HashMap<Uuid, DateTime<Utc>> = deletions
+        .iter()
+        .filter(|t| exported_at.is_none_or(|e| t.deleted_at > e))
+        .map(|t| (t.entity_id, t.deleted_at))
+        .collect();
+
+    let mut report = ReconcileReport {
+        exported_at,
+        ..Default::default()
+    };
+    let mut taken = HashSet::new();
+
+    for entry in &backup.movies {
+        if let Some(movie) = find_match(entry, live, &taken) {
+            taken.insert(movie.id);
+            report.matches.insert(entry.id.clone(), movie.id);
+            let fields = diff_fields(entry, movie);
+            if fields.is_empty() {
+                report.unchanged += 1;
+            } else {
+                report.changed.push(ChangedEntry {
+                    backup_id: entry.id.clone(),
+                    movie_id: movie.id,
+                    title: movie.title.clone(),
+                    fields,
+                    changed_since_backup: exported_at.is_none_or(|e| movie.updated_at > e),
+                });
+            }
+            continue;
+        }
+
+        let deletion = entry
+            .id
+            .parse::<Uuid>()
+            .ok()
+            .and_then(|id| deleted_at.get(&id).map(|at| (id, *at)));
+        match deletion {
+            Some((movie_id, deleted_at)) => report.deleted.push(DeletedEntry {
+                backup_id: entry.id.clone(),
+                movie_id,
+                title: entry.title.clone(),
+                deleted_at,
+            }),
+            None => report.new.push(NewEntry {
+                backup_id: entry.id.clone(),
+                title: entry.title.clone(),
+                production_year: entry.production_year,
+            }),
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use my_movies_core::models::EntityType;
+
+    fn export_movie(id: &str, title: &str) -> ExportMovie {
+        serde_json::from_value(json!({
+            "id": id,
+            "barcode": null,
+            "tmdb_id": null,
+            "title": title,
+            "original_title": null,
+            "sort_title": null,
+            "description": null,
+            "production_year": null,
+            "disc_type": null,
+            "running_time": null,
+            "genres": null,
+            "director": null,
+            "actors": null,
+            "watched": false,
+            "location": null,
+            "rating": null,
+            "personal_rating": null,
+            "notes": null,
+            "is_collection": false,
+            "parent_collection_id": null,
+            "created_at": "2024-01-01T00:00:00Z",
+            "updated_at": "2024-01-01T00:00:00Z",
+        }))
+        .unwrap()
+    }
+
+    fn live_movie(id: Uuid, title: &str, updated_at: &str) -> Movie {
+        serde_json::from_value(json!({
+            "id": id,
+            "user_id": Uuid::nil(),
+            "title": title,
+            "is_3d": false,
+            "mastered_in_4k": false,
+            "is_collection": false,
+            "watched": false,
+            "favorite": false,
+            "slip_cover": false,
+            "created_at": "2024-01-01T00:00:00Z",
+            "updated_at": updated_at,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_reconcile() {
+        let same = Uuid::new_v4();
+        let edited = Uuid::new_v4();
+        let gone = Uuid::new_v4();
+
+        let mut barcode_match = export_movie("other-instance-id", "Alien");
+        barcode_match.barcode = Some("4010232001234".to_string());
+        let mut edited_entry = export_movie(&edited.to_string(), "Heat");
+        edited_entry.watched = true;
+
+        let backup = ExportData {
+            version: "1.0".to_string(),
+            schema_version: 3,
+            exported_at: "2024-06-01T00:00:00Z".to_string(),
+            total_movies: 5,
+            movies: vec![
+                export_movie(&same.to_string(), "Up"),
+                edited_entry,
+                export_movie(&gone.to_string(), "Coco"),
+                barcode_match,
+                export_movie("new-id", "Titanic"),
+            ],
+            locations: Vec::new(),
+            account: None,
+            unknown_sections: Default::default(),
+        };
+
+        let mut alien = live_movie(Uuid::new_v4(), "Alien", "2024-01-01T00:00:00Z");
+        alien.barcode = Some("4010232001234".to_string());
+        let live = vec![
+            live_movie(same, "Up", "2024-01-01T00:00:00Z"),
+            live_movie(edited, "Heat", "2024-07-01T00:00:00Z"),
+            alien,
+        ];
+        let deletions = vec![
+            Tombstone {
+                entity_type: EntityType::Movie,
+                entity_id: gone,
+                deleted_at: "2024-07-01T00:00:00Z".parse().unwrap(),
+            },
+            // Deleted before the backup, so the backup can't contain it
+            Tombstone {
+                entity_type: EntityType::Movie,
+                entity_id: Uuid::new_v4(),
+                deleted_at: "2024-05-01T00:00:00Z".parse().unwrap(),
+            },
+        ];
+
+        let report = reconcile(&backup, &live, &deletions);
+        assert_eq!(report.unchanged, 2);
+
+        assert_eq!(report.changed.len(), 1);
+        assert_eq!(report.changed[0].movie_id, edited);
+        assert!(report.changed[0].changed_since_backup);
+        assert_eq!(
+            report.changed[0].fields,
+            vec![FieldChange {
+                field: "watched",
+                backup: json!(true),
+                live: json!(false),
+            }]
+        );
+
+        assert_eq!(report.deleted.len(), 1);
+        assert_eq!(report.deleted[0].movie_id, gone);
+        assert_eq!(report.new.len(), 1);
+        assert_eq!(report.new[0].backup_id, "new-id");
+    }
+}
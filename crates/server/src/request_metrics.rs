@@ -0,0 +1,313 @@
+//! Latency and request counts of the last 24 hours per endpoint and per user,
+//! kept in memory in a ring buffer, to find the client or endpoint that keeps a
+//! small server busy
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use uuid::Uuid;
+
+/// How long requests are kept
+pub const WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+/// Requests kept at most; the oldest are dropped first (about 6 MB)
+const MAX_SAMPLES: usize = 100_000;
+
+struct Sample {
+    at: Instant,
+    /// Method and route template, e.g. `GET /api/v1/movies/{id}`
+    endpoint: Arc<str>,
+    user_id: Option<Uuid>,
+    status: u16,
+    duration: Duration,
+}
+
+/// Ring buffer of the requests of the last 24 hours
+pub struct RequestMetrics {
+    samples: Mutex<VecDeque<Sample>>,
+}
+
+impl Default for RequestMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RequestMetrics {
+    pub fn new() -> Self {
+        Self {
+            samples: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    pub fn record(&self, endpoint: &str, user_id: Option<Uuid>, status: u16, duration: Duration) {
+        self.record_at(endpoint, user_id, status, duration, Instant::now());
+    }
+
+    fn record_at(
+        &self,
+        endpoint: &str,
+        user_id: Option<Uuid>,
+        status: u16,
+        duration: Duration,
+        now: Instant,
+    ) {
+        let Ok(mut samples) = self.samples.lock() else {
+            return;
+        };
+
+        while samples
+            .front()
+            .is_some_and(|s| samples.len() >= MAX_SAMPLES || now.duration_since(s.at) > WINDOW)
+        {
+            samples.pop_front();
+        }
+
+        // Reuse the endpoint of an earlier request instead of allocating per request
+        let endpoint = samples
+            .iter()
+            .rev()
+            .take(64)
+            .find(|s| &*s.endpoint == endpoint)
+            .map(|s| s.endpoint.clone())
+            .unwrap_or_else(|| Arc::from(endpoint));
+
+        samples.push_back(Sample {
+            at: now,
+            endpoint,
+            user_id,
+            status,
+            duration,
+        });
+    }
+
+    /// Percentiles per endpoint and counts per user of the requests in `window`
+    pub fn report(&self, window: Duration) -> RequestMetricsReport {
+        self.report_at(window, Instant::now())
+    }
+
+    fn report_at(&self, window: Duration, now: Instant) -> RequestMetricsReport {
+        let mut endpoints: HashMap<Arc<str>, (Vec<Duration>, usize)> = HashMap::new();
+        let mut users: HashMap<Uuid, (usize, usize)> = HashMap::new();
+        let mut report = RequestMetricsReport {
+            window_seconds: window.as_secs(),
+            covered_seconds: 0,
+            requests: 0,
+            anonymous_requests: 0,
+            endpoints: Vec::new(),
+            users: Vec::new(),
+        };
+
+        if let Ok(samples) = self.samples.lock() {
+            for sample in samples
+                .iter()
+                .filter(|s| now.duration_since(s.at) <= window)
+            {
+                if report.requests == 0 {
+                    report.covered_seconds = now.duration_since(sample.at).as_secs();
+                }
+                report.requests += 1;
+
+                let server_error = sample.status >= 500;
+                let (durations, errors) = endpoints.entry(sample.endpoint.clone()).or_default();
+                durations.push(sample.duration);
+                *errors += usize::from(server_error);
+
+                match sample.user_id {
+                    Some(user_id) => {
+                        let (requests, errors) = users.entry(user_id).or_default();
+                        *requests += 1;
+                        *errors += usize::from(sample.status >= 400);
+                    }
+                    None => report.anonymous_requests += 1,
+                }
+            }
+        }
+
+        report.endpoints = endpoints
+            .into_iter()
+            .map(|(endpoint, (mut durations, server_errors))| {
+                durations.sort_unstable();
+                let total: Duration = durations.iter().sum();
+                EndpointMetrics {
+                    endpoint: endpoint.to_string(),
+                    requests: durations.len(),
+                    server_errors,
+                    total_ms: millis(total),
+                    p50_ms: millis(percentile(&durations, 50)),
+                    p95_ms: millis(percentile(&durations, 95)),
+                    p99_ms: millis(percentile(&durations, 99)),
+                    max_ms: millis(durations.last().copied().unwrap_or_default()),
+                }
+            })
+            .collect();
+        report.endpoints.sort_by(|a, b| {
+            b.requests
+                .cmp(&a.requests)
+                .then_with(|| a.endpoint.cmp(&b.endpoint))
+        });
+
+        report.users = users
+            .into_iter()
+            .map(|(user_id, (requests, errors))| UserRequestCount {
+                user_id,
+                username: None,
+                requests,
+                errors,
+            })
+            .collect();
+        report
+            .users
+            .sort_by(|a, b| b.requests.cmp(&a.requests).then(a.user_id.cmp(&b.user_id)));
+
+        report
+    }
+}
+
+/// Nearest-rank percentile of sorted durations
+fn percentile(sorted: &[Duration], p: usize) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = (sorted.len() * p).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+fn millis(duration: Duration) -> f64 {
+    (duration.as_secs_f64() * 1000.0 * 10.0).round() / 10.0
+}
+
+#[derive(Debug, Serialize)]
+pub struct RequestMetricsReport {
+    pub window_seconds: u64,
+    /// Age of the oldest request in the window; less than `window_seconds`
+    /// after a restart or when more requests came in than are kept
+    pub covered_seconds: u64,
+    pub requests: usize,
+    /// Requests without a signed-in user (login, share links, health checks)
+    pub anonymous_requests: usize,
+    /// Most requested first
+    pub endpoints: Vec<EndpointMetrics>,
+    /// Most active first
+    pub users: Vec<UserRequestCount>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EndpointMetrics {
+    /// Method and route, e.g. `GET /api/v1/movies/{id}`
+    pub endpoint: String,
+    pub requests: usize,
+    /// Responses with a 5xx status
+    pub server_errors: usize,
+    /// Time spent answering this endpoint
+    pub total_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UserRequestCount {
+    pub user_id: Uuid,
+    pub username: Option<String>,
+    pub requests: usize,
+    /// Responses with a 4xx or 5xx status
+    pub errors: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn test_report_percentiles_per_endpoint() {
+        let metrics = RequestMetrics::new();
+        let now = Instant::now();
+        for i in 1..=100 {
+            metrics.record_at("GET /api/v1/movies", None, 200, ms(i), now);
+        }
+        metrics.record_at("GET /api/v1/movies/{id}", None, 500, ms(7), now);
+
+        let report = metrics.report_at(WINDOW, now);
+        assert_eq!(report.requests, 101);
+        assert_eq!(report.anonymous_requests, 101);
+        assert_eq!(report.endpoints.len(), 2);
+
+        let movies = &report.endpoints[0];
+        assert_eq!(movies.endpoint, "GET /api/v1/movies");
+        assert_eq!(movies.requests, 100);
+        assert_eq!(movies.server_errors, 0);
+        assert_eq!(movies.p50_ms, 50.0);
+        assert_eq!(movies.p95_ms, 95.0);
+        assert_eq!(movies.p99_ms, 99.0);
+        assert_eq!(movies.max_ms, 100.0);
+        assert_eq!(movies.total_ms, 5050.0);
+
+        let movie = &report.endpoints[1];
+        assert_eq!(movie.server_errors, 1);
+        assert_eq!(movie.p50_ms, 7.0);
+    }
+
+    #[test]
+    fn test_report_counts_per_user() {
+        let metrics = RequestMetrics::new();
+        let now = Instant::now();
+        let busy = Uuid::new_v4();
+        let quiet = Uuid::new_v4();
+        for _ in 0..5 {
+            metrics.record_at("GET /api/v1/movies", Some(busy), 200, ms(1), now);
+        }
+        metrics.record_at("GET /api/v1/movies", Some(busy), 404, ms(1), now);
+        metrics.record_at("GET /api/v1/movies", Some(quiet), 200, ms(1), now);
+        metrics.record_at("POST /api/v1/auth/login", None, 401, ms(1), now);
+
+        let report = metrics.report_at(WINDOW, now);
+        assert_eq!(report.anonymous_requests, 1);
+        assert_eq!(report.users.len(), 2);
+        assert_eq!(report.users[0].user_id, busy);
+        assert_eq!(report.users[0].requests, 6);
+        assert_eq!(report.users[0].errors, 1);
+        assert_eq!(report.users[1].user_id, quiet);
+        assert_eq!(report.users[1].requests, 1);
+    }
+
+    #[test]
+    fn test_old_requests_leave_the_window() {
+        let metrics = RequestMetrics::new();
+        let start = Instant::now();
+        metrics.record_at("GET /api/v1/movies", None, 200, ms(1), start);
+        metrics.record_at(
+            "GET /api/v1/series",
+            None,
+            200,
+            ms(1),
+            start + Duration::from_secs(3600),
+        );
+
+        let later = start + Duration::from_secs(2 * 3600);
+        let last_hour = metrics.report_at(Duration::from_secs(3600), later);
+        assert_eq!(last_hour.requests, 1);
+        assert_eq!(last_hour.endpoints[0].endpoint, "GET /api/v1/series");
+        assert_eq!(last_hour.covered_seconds, 3600);
+
+        // Recording after a day drops the expired request from the buffer
+        let next_day = start + WINDOW + Duration::from_secs(1);
+        metrics.record_at("GET /api/v1/movies", None, 200, ms(1), next_day);
+        assert_eq!(metrics.samples.lock().unwrap().len(), 2);
+        assert_eq!(metrics.report_at(WINDOW, next_day).requests, 2);
+    }
+
+    #[test]
+    fn test_percentile_of_few_samples() {
+        assert_eq!(percentile(&[], 95), Duration::ZERO);
+        assert_eq!(percentile(&[ms(3)], 50), ms(3));
+        assert_eq!(percentile(&[ms(1), ms(2)], 50), ms(1));
+        assert_eq!(percentile(&[ms(1), ms(2)], 95), ms(2));
+    }
+}
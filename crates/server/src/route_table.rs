@@ -0,0 +1,90 @@
+//! Route tables: each group of routes is registered through a `RouteTable`,
+//! which builds the router and keeps the method and path of every route.
+//! axum can't list a router's routes, so tests check route lists (admin
+//! routes, the OpenAPI document) against these tables.
+
+use std::sync::Arc;
+
+use axum::{
+    Router,
+    handler::Handler,
+    http::Method,
+    routing::{self, MethodRouter},
+};
+
+use crate::AppState;
+
+#[derive(Default)]
+pub struct RouteTable {
+    router: Router<Arc<AppState>>,
+    routes: Vec<(Method, &'static str)>,
+}
+
+impl RouteTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `(method, path)` of every route, in the order registered. GET routes
+    /// also answer HEAD.
+    pub fn routes(&self) -> &[(Method, &'static str)] {
+        &self.routes
+    }
+
+    pub fn into_router(self) -> Router<Arc<AppState>> {
+        self.router
+    }
+
+    pub fn get<H, T>(self, path: &'static str, handler: H) -> Self
+    where
+        H: Handler<T, Arc<AppState>>,
+        T: 'static,
+    {
+        self.add(Method::GET, path, routing::get(handler))
+    }
+
+    pub fn post<H, T>(self, path: &'static str, handler: H) -> Self
+    where
+        H: Handler<T, Arc<AppState>>,
+        T: 'static,
+    {
+        self.add(Method::POST, path, routing::post(handler))
+    }
+
+    pub fn put<H, T>(self, path: &'static str, handler: H) -> Self
+    where
+        H: Handler<T, Arc<AppState>>,
+        T: 'static,
+    {
+        self.add(Method::PUT, path, routing::put(handler))
+    }
+
+    pub fn patch<H, T>(self, path: &'static str, handler: H) -> Self
+    where
+        H: Handler<T, Arc<AppState>>,
+        T: 'static,
+    {
+        self.add(Method::PATCH, path, routing::patch(handler))
+    }
+
+    pub fn delete<H, T>(self, path: &'static str, handler: H) -> Self
+    where
+        H: Handler<T, Arc<AppState>>,
+        T: 'static,
+    {
+        self.add(Method::DELETE, path, routing::delete(handler))
+    }
+
+    /// Routes of one path are merged by axum, so every method can be added
+    /// on its own
+    fn add(
+        mut self,
+        method: Method,
+        path: &'static str,
+        route: MethodRouter<Arc<AppState>>,
+    ) -> Self {
+        self.router = self.router.route(path, route);
+        self.routes.push((method, path));
+        self
+    }
+}
@@ -0,0 +1,20 @@
+use axum::{
+    Extension, Json,
+    extract::{Query, State},
+};
+use std::sync::Arc;
+
+use my_movies_core::models::{ActivityEntry, ActivityFilter, Claims};
+
+use crate::{ApiError, AppState};
+
+/// Created, changed, deleted and imported movies, series and collections,
+/// newest first
+pub async fn list(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Query(filter): Query<ActivityFilter>,
+) -> Result<Json<Vec<ActivityEntry>>, ApiError> {
+    let entries = state.activity_service.list(claims.id, &filter).await?;
+    Ok(Json(entries))
+}
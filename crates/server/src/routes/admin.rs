@@ -0,0 +1,381 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::{HeaderMap, Method, header},
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use my_movies_core::models::{BarcodeCacheEntry, BarcodeCacheStats, BroadcastMessage};
+use my_movies_core::services::{DatabaseDiagnostics, RepairReport, SettingStatus, TmdbCacheStats};
+
+use crate::request_metrics::{self, RequestMetricsReport};
+use crate::routes::ws::WsEvent;
+use crate::{ApiError, AppState, downloads, logging};
+
+const DEFAULT_TAIL_LINES: usize = 200;
+const MAX_TAIL_LINES: usize = 5000;
+
+/// Number of log lines scanned for warnings/errors in diagnostics
+const DIAGNOSTICS_LOG_SCAN_LINES: usize = 2000;
+
+#[derive(Debug, Deserialize)]
+pub struct RepairQuery {
+    /// Only report what would be fixed
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Find and fix invalid dates, negative runtimes, out of range ratings,
+/// box set entries marked as box sets and empty titles in all libraries
+pub async fn repair(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<RepairQuery>,
+) -> Result<Json<RepairReport>, ApiError> {
+    let report = state.repair_service.repair(query.dry_run).await?;
+    if !report.dry_run && report.fixed > 0 {
+        tracing::info!("Repair fixed {} of {} issues", report.fixed, report.found);
+    }
+    Ok(Json(report))
+}
+
+/// Send a `server_message` WebSocket event (to everyone or `user_ids`) and
+/// keep the message in the recipients' notification centers, for those who
+/// are offline. Clients show it if they are a recipient.
+pub async fn broadcast(
+    State(state): State<Arc<AppState>>,
+    Json(input): Json<BroadcastMessage>,
+) -> Result<impl IntoResponse, ApiError> {
+    let recipients = state
+        .notification_service
+        .notify(
+            input.user_ids.as_deref(),
+            "server_message",
+            input.level,
+            &input.message,
+        )
+        .await?;
+
+    let msg = json!({
+        "type": "server_message",
+        "payload": {
+            "message": input.message.trim(),
+            "level": input.level,
+            "user_ids": input.user_ids,
+            "sent_at": chrono::Utc::now().to_rfc3339(),
+        }
+    });
+    let event = match input.user_ids {
+        Some(user_ids) => WsEvent::users(user_ids, &msg),
+        None => WsEvent::everyone(&msg),
+    };
+    let _ = state.ws_broadcast.send(event);
+    tracing::info!("Broadcast a server message to {} users", recipients);
+
+    Ok(Json(json!({ "recipients": recipients })))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BarcodeCacheQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BarcodeCacheResponse {
+    pub stats: BarcodeCacheStats,
+    /// Most recently cached first
+    pub entries: Vec<BarcodeCacheEntry>,
+}
+
+/// Hit/miss counters and the cached barcode lookups
+pub async fn barcode_cache(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<BarcodeCacheQuery>,
+) -> Result<Json<BarcodeCacheResponse>, ApiError> {
+    let limit = query.limit.unwrap_or(100).clamp(1, 1000);
+    let offset = query.offset.unwrap_or(0).max(0);
+    Ok(Json(BarcodeCacheResponse {
+        stats: state.barcode_cache_service.stats().await?,
+        entries: state.barcode_cache_service.list(limit, offset).await?,
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ClearBarcodeCacheQuery {
+    /// Only forget this barcode
+    pub barcode: Option<String>,
+}
+
+/// Forget cached barcode lookups, so the next scans ask the providers again
+pub async fn clear_barcode_cache(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ClearBarcodeCacheQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let removed = state
+        .barcode_cache_service
+        .clear(query.barcode.as_deref())
+        .await?;
+    Ok(Json(json!({ "removed": removed })))
+}
+
+/// Mode, TTL, size and hit/miss counters of the TMDB response cache
+pub async fn tmdb_cache(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<TmdbCacheStats>, ApiError> {
+    Ok(Json(state.tmdb_service.cache_stats().await?))
+}
+
+/// Forget cached TMDB responses, so the next requests go to TMDB again
+pub async fn clear_tmdb_cache(
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, ApiError> {
+    let removed = state.tmdb_service.clear_cache().await?;
+    Ok(Json(json!({ "removed": removed })))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RequestMetricsQuery {
+    /// Only the last hours, 1 to 24
+    pub hours: Option<u64>,
+}
+
+/// Latency percentiles per endpoint and request counts per user of the last
+/// 24 hours (or `hours`), kept in memory since the server started
+pub async fn request_metrics(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<RequestMetricsQuery>,
+) -> Result<Json<RequestMetricsReport>, ApiError> {
+    let window = match query.hours {
+        Some(hours) => std::time::Duration::from_secs(hours.clamp(1, 24) * 60 * 60),
+        None => request_metrics::WINDOW,
+    };
+    let mut report = state.request_metrics.report(window);
+
+    let users = state.auth_service.list_all_users().await?;
+    for entry in &mut report.users {
+        entry.username = users
+            .iter()
+            .find(|u| u.id == entry.user_id)
+            .map(|u| u.username.clone());
+    }
+
+    Ok(Json(report))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LogTailQuery {
+    pub lines: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LogTailResponse {
+    pub file: String,
+    pub lines: Vec<String>,
+}
+
+/// Get the tail of the current log file (admin only)
+pub async fn get_logs(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<LogTailQuery>,
+) -> Result<Json<LogTailResponse>, ApiError> {
+    let log_dir = state
+        .log_dir
+        .as_deref()
+        .ok_or_else(|| ApiError::not_found("File logging is not enabled (set LOG_DIR)"))?;
+
+    let path = logging::current_log_file(std::path::Path::new(log_dir))
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to read log directory: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("No log file found"))?;
+
+    let lines = query
+        .lines
+        .unwrap_or(DEFAULT_TAIL_LINES)
+        .min(MAX_TAIL_LINES);
+    let lines = logging::tail_log_file(&path, lines)
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to read log file: {}", e)))?;
+
+    Ok(Json(LogTailResponse {
+        file: path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        lines,
+    }))
+}
+
+#[derive(Debug, Serialize)]
+pub struct DiagnosticsResponse {
+    pub version: &'static str,
+    pub os: &'static str,
+    pub arch: &'static str,
+    pub generated_at: String,
+    pub uptime_seconds: u64,
+    pub database: DatabaseDiagnostics,
+    pub barcode_cache: BarcodeCacheStats,
+    pub tmdb_cache: TmdbCacheStats,
+    /// Settings status; values are never included
+    pub settings: Vec<SettingStatus>,
+    pub features: DiagnosticsFeatures,
+    /// Recent warnings and errors from the log file (if file logging is enabled)
+    pub recent_log_summary: Vec<DiagnosticsLogSummary>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DiagnosticsFeatures {
+    pub file_logging: bool,
+    pub demo_mode: bool,
+    pub read_only: bool,
+    pub cors_restricted: bool,
+    pub discord_notifications: bool,
+    pub error_reporting: bool,
+}
+
+/// Warnings or errors logged by one target. Messages are left out: they
+/// name users, movies and files.
+#[derive(Debug, Serialize)]
+pub struct DiagnosticsLogSummary {
+    pub level: String,
+    pub target: String,
+    pub count: usize,
+    pub last_seen: Option<String>,
+}
+
+/// Anonymized support bundle for debugging issue reports (admin only)
+pub async fn get_diagnostics(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<DiagnosticsResponse>, ApiError> {
+    let database = state.diagnostics_service.database().await?;
+    let barcode_cache = state.barcode_cache_service.stats().await?;
+    let tmdb_cache = state.tmdb_service.cache_stats().await?;
+    let settings = state.settings_service.get_status().await?;
+    let recent_log_summary = recent_log_summary(state.log_dir.as_deref()).await;
+
+    Ok(Json(DiagnosticsResponse {
+        version: env!("CARGO_PKG_VERSION"),
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        uptime_seconds: state.started_at.elapsed().as_secs(),
+        database,
+        barcode_cache,
+        tmdb_cache,
+        settings,
+        features: DiagnosticsFeatures {
+            file_logging: state.log_dir.is_some(),
+            demo_mode: state.demo_mode,
+            read_only: state.read_only.load(std::sync::atomic::Ordering::Relaxed),
+            cors_restricted: !state.cors_allowed_origins.is_empty(),
+            discord_notifications: state
+                .discord_service
+                .is_enabled(my_movies_core::services::DiscordEvent::MovieAdded),
+            error_reporting: state.error_reporter.is_enabled(),
+        },
+        recent_log_summary,
+    }))
+}
+
+/// Download a consistent copy of the SQLite database (admin only). A plain
+/// `GET` takes a new snapshot; `HEAD` and range requests use the latest one
+/// while it is kept, so downloads can be resumed.
+pub async fn database_snapshot(
+    State(state): State<Arc<AppState>>,
+    method: Method,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    const KEY: &str = "database";
+    const CONTENT_TYPE: &str = "application/vnd.sqlite3";
+
+    if (method == Method::HEAD || headers.contains_key(header::RANGE))
+        && let Some(download) = state.downloads.get(KEY)
+    {
+        return downloads::serve(&download, &method, &headers, CONTENT_TYPE).await;
+    }
+
+    let download = downloads::Download::new(format!(
+        "my-movies-{}.db",
+        chrono::Utc::now().format("%Y%m%d-%H%M%S")
+    ));
+    if let Err(e) = state.diagnostics_service.snapshot(&download.path).await {
+        download.remove();
+        return Err(e.into());
+    }
+    state.downloads.insert(KEY.to_string(), download.clone());
+    downloads::serve(&download, &method, &headers, CONTENT_TYPE).await
+}
+
+/// Count recent WARN/ERROR entries of the JSON log file per level and target
+async fn recent_log_summary(log_dir: Option<&str>) -> Vec<DiagnosticsLogSummary> {
+    let Some(log_dir) = log_dir else {
+        return Vec::new();
+    };
+
+    let lines = match logging::current_log_file(std::path::Path::new(log_dir)).await {
+        Ok(Some(path)) => logging::tail_log_file(&path, DIAGNOSTICS_LOG_SCAN_LINES)
+            .await
+            .unwrap_or_default(),
+        _ => return Vec::new(),
+    };
+
+    let mut summary: BTreeMap<(String, String), DiagnosticsLogSummary> = BTreeMap::new();
+    for entry in lines
+        .iter()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+    {
+        let level = match entry["level"].as_str() {
+            Some(level @ ("WARN" | "ERROR")) => level.to_string(),
+            _ => continue,
+        };
+        let target = entry["target"].as_str().unwrap_or_default().to_string();
+        let item =
+            summary
+                .entry((level.clone(), target.clone()))
+                .or_insert(DiagnosticsLogSummary {
+                    level,
+                    target,
+                    count: 0,
+                    last_seen: None,
+                });
+        item.count += 1;
+        item.last_seen = entry["timestamp"].as_str().map(String::from);
+    }
+    summary.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_diagnostics_leave_out_log_messages() {
+        let dir = tempfile::tempdir().unwrap();
+        let lines = [
+            r#"{"timestamp":"2026-10-01T10:00:00Z","level":"WARN","target":"my_movies_server::digests","fields":{"message":"Digest for alice failed: connection refused"}}"#,
+            r#"{"timestamp":"2026-10-01T11:00:00Z","level":"WARN","target":"my_movies_server::digests","fields":{"message":"Digest for alice failed: timeout"}}"#,
+            r#"{"timestamp":"2026-10-01T12:00:00Z","level":"INFO","target":"my_movies_server","fields":{"message":"alice logged in"}}"#,
+        ];
+        tokio::fs::write(
+            dir.path().join("my-movies.2026-10-01.log"),
+            lines.join("\n"),
+        )
+        .await
+        .unwrap();
+
+        let summary = recent_log_summary(dir.path().to_str()).await;
+
+        assert_eq!(summary.len(), 1);
+        assert_eq!(summary[0].target, "my_movies_server::digests");
+        assert_eq!(summary[0].count, 2);
+        assert_eq!(
+            summary[0].last_seen.as_deref(),
+            Some("2026-10-01T11:00:00Z")
+        );
+        let bundle = serde_json::to_string(&summary).unwrap();
+        assert!(!bundle.contains("alice"), "{bundle}");
+    }
+}
@@ -0,0 +1,51 @@
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde_json::json;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use my_movies_core::models::{ApiKeyPublic, Claims, CreateApiKey};
+
+use crate::routes::ws::{Audience, WsEvent};
+use crate::{ApiError, AppState};
+
+/// List the current user's API keys
+pub async fn list(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Vec<ApiKeyPublic>>, ApiError> {
+    let keys = state.api_key_service.list(claims.id).await?;
+    Ok(Json(keys))
+}
+
+/// Create an API key. The key is only returned in this response.
+pub async fn create(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(input): Json<CreateApiKey>,
+) -> Result<impl IntoResponse, ApiError> {
+    let created = state
+        .api_key_service
+        .create(claims.id, &claims.role, input)
+        .await?;
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+/// Revoke an API key
+pub async fn delete(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    state.api_key_service.delete(claims.id, id).await?;
+    // Sockets don't know their key, so all of the user's key sockets close;
+    // those with a remaining key reconnect
+    let _ = state
+        .ws_broadcast
+        .send(WsEvent::close(Audience::ApiKeys(claims.id)));
+    Ok(Json(json!({ "message": "API key deleted" })))
+}
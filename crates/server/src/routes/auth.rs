@@ -4,38 +4,135 @@ use axum::body::Body;
 use axum::{
     Extension, Json,
     extract::{Multipart, Path, State},
-    http::{StatusCode, header},
+    http::{HeaderMap, StatusCode, header},
     response::{IntoResponse, Response},
 };
 use serde_json::json;
 use uuid::Uuid;
 
 use my_movies_core::models::{
-    Claims, CreateUser, ForgotPasswordRequest, LoginRequest, ResetPasswordRequest,
+    Claims, DigestSettings, ForgotPasswordRequest, LoanReminderSettings, LoginRequest,
+    MovieDefaults, RefreshRequest, RegisterRequest, ResetPasswordRequest, SettingKey,
+    TmdbPreferences,
 };
+use my_movies_core::services::{DigestService, EmailMessage, Mailer};
 
+use crate::rate_limit::too_many_requests;
+use crate::routes::ws::{Audience, WsEvent};
 use crate::{ApiError, AppState};
 
+/// Password reset emails an address can get in a burst
+pub const PASSWORD_RESET_BURST: f64 = 3.0;
+/// Then one more every 10 minutes
+pub const PASSWORD_RESETS_PER_SECOND: f64 = 1.0 / 600.0;
+
+/// Create an account. While open registration is disabled an invite code is
+/// required (except for the first account); the invite decides the role.
 pub async fn register(
     State(state): State<Arc<AppState>>,
-    Json(input): Json<CreateUser>,
+    Json(input): Json<RegisterRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
-    let auth_response = state.auth_service.register(input).await?;
+    let open = state
+        .settings_service
+        .get_bool(SettingKey::OpenRegistration, true)
+        .await?;
+    let code = input
+        .invite_code
+        .as_deref()
+        .map(str::trim)
+        .filter(|c| !c.is_empty());
+    let invite = match code {
+        Some(code) => Some(state.invite_service.redeem(code).await?),
+        None if open || !state.auth_service.has_users().await? => None,
+        None => {
+            return Err(ApiError::new(
+                StatusCode::FORBIDDEN,
+                "Registration requires an invite",
+            ));
+        }
+    };
+
+    let role = invite.as_ref().map(|i| i.role.clone()).unwrap_or_default();
+    let auth_response = match state.auth_service.register_as(input.user, role).await {
+        Ok(response) => response,
+        Err(e) => {
+            if let Some(invite) = &invite
+                && let Err(release) = state.invite_service.release(invite.id).await
+            {
+                tracing::warn!("Failed to give back invite use: {}", release);
+            }
+            return Err(e.into());
+        }
+    };
 
     let msg = json!({ "type": "user_created", "payload": &auth_response.user });
-    let _ = state.ws_broadcast.send(msg.to_string());
+    let _ = state.ws_broadcast.send(WsEvent::admins(None, &msg));
 
     Ok((StatusCode::CREATED, Json(auth_response)))
 }
 
 pub async fn login(
     State(state): State<Arc<AppState>>,
-    Json(input): Json<LoginRequest>,
+    headers: HeaderMap,
+    Json(mut input): Json<LoginRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
+    if input.device.is_none() {
+        input.device = headers
+            .get(header::USER_AGENT)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+    }
     let auth_response = state.auth_service.login(input).await?;
     Ok((StatusCode::OK, Json(auth_response)))
 }
 
+/// New access token for a refresh token; the refresh token is rotated
+pub async fn refresh(
+    State(state): State<Arc<AppState>>,
+    Json(input): Json<RefreshRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let auth_response = state.auth_service.refresh(&input.refresh_token).await?;
+    Ok((StatusCode::OK, Json(auth_response)))
+}
+
+/// Revoke the session of the access token used
+pub async fn logout(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<impl IntoResponse, ApiError> {
+    if let Some(sid) = claims.sid {
+        state.auth_service.revoke_session(claims.id, sid).await?;
+        let _ = state
+            .ws_broadcast
+            .send(WsEvent::close(Audience::Session(sid)));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Devices signed in to the account
+pub async fn list_sessions(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<impl IntoResponse, ApiError> {
+    let sessions = state
+        .auth_service
+        .list_sessions(claims.id, claims.sid)
+        .await?;
+    Ok((StatusCode::OK, Json(sessions)))
+}
+
+pub async fn revoke_session(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    state.auth_service.revoke_session(claims.id, id).await?;
+    let _ = state
+        .ws_broadcast
+        .send(WsEvent::close(Audience::Session(id)));
+    Ok(StatusCode::NO_CONTENT)
+}
+
 pub async fn me(
     State(state): State<Arc<AppState>>,
     Extension(claims): Extension<Claims>,
@@ -44,22 +141,61 @@ pub async fn me(
     Ok((StatusCode::OK, Json(user)))
 }
 
+/// Send a password reset link. The endpoint is public, so the emails to an
+/// address are rate limited to keep it from flooding inboxes and the SMTP quota.
 pub async fn forgot_password(
     State(state): State<Arc<AppState>>,
     Json(input): Json<ForgotPasswordRequest>,
-) -> Result<impl IntoResponse, ApiError> {
-    state.auth_service.request_password_reset(input).await?;
+) -> Result<Response, ApiError> {
+    let email = input.email.trim().to_lowercase();
+    if let Err(retry_after) = state.password_reset_rate_limiter.check(email) {
+        return Ok(too_many_requests(
+            retry_after,
+            "Zu viele Anfragen zum Zurücksetzen des Passworts, bitte später erneut versuchen",
+        ));
+    }
+    let reset_page = web_link(&state, "/reset-password")?;
+    let (user, token) = state.auth_service.request_password_reset(input).await?;
+    let link = format!("{}?token={}", reset_page, token);
+    state
+        .mail_service
+        .send(&EmailMessage::password_reset(
+            &user.username,
+            &user.email,
+            &link,
+        ))
+        .await?;
     Ok((
         StatusCode::OK,
         Json(json!({ "message": "Password reset email sent" })),
-    ))
+    )
+        .into_response())
+}
+
+/// Link into the web app for emails, absolute with the `public_url` setting.
+/// A relative link is useless in an email, so this fails while it is unset.
+pub(crate) fn web_link(state: &AppState, path: &str) -> Result<String, ApiError> {
+    let snapshot = state.settings_service.snapshot();
+    let base = snapshot
+        .get(SettingKey::PublicUrl)
+        .filter(|url| !url.trim().is_empty())
+        .ok_or_else(|| {
+            my_movies_core::Error::Configuration(
+                "public_url must be set to send emails with links".to_string(),
+            )
+        })?;
+    Ok(format!("{}{}", base.trim_end_matches('/'), path))
 }
 
 pub async fn reset_password(
     State(state): State<Arc<AppState>>,
     Json(input): Json<ResetPasswordRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
-    state.auth_service.reset_password(input).await?;
+    let user_id = state.auth_service.reset_password(input).await?;
+    // Every session was signed out
+    let _ = state
+        .ws_broadcast
+        .send(WsEvent::close(Audience::Users(vec![user_id])));
     Ok((
         StatusCode::OK,
         Json(json!({ "message": "Password reset successfully" })),
@@ -82,7 +218,7 @@ pub async fn update_language(
         .await?;
 
     let msg = json!({ "type": "user_updated", "payload": user });
-    let _ = state.ws_broadcast.send(msg.to_string());
+    let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
 
     Ok((StatusCode::OK, Json(user)))
 }
@@ -103,7 +239,7 @@ pub async fn update_include_adult(
         .await?;
 
     let msg = json!({ "type": "user_updated", "payload": user });
-    let _ = state.ws_broadcast.send(msg.to_string());
+    let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
 
     Ok((StatusCode::OK, Json(user)))
 }
@@ -124,7 +260,150 @@ pub async fn update_theme(
         .await?;
 
     let msg = json!({ "type": "user_updated", "payload": user });
-    let _ = state.ws_broadcast.send(msg.to_string());
+    let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
+
+    Ok((StatusCode::OK, Json(user)))
+}
+
+#[derive(serde::Deserialize)]
+pub struct UpdatePlayerRegionsRequest {
+    /// e.g. "R2, RB"; `null` or empty clears them
+    pub player_regions: Option<String>,
+}
+
+pub async fn update_player_regions(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(body): Json<UpdatePlayerRegionsRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let user = state
+        .auth_service
+        .update_user_player_regions(claims.id, body.player_regions)
+        .await?;
+
+    let msg = json!({ "type": "user_updated", "payload": user });
+    let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
+
+    Ok((StatusCode::OK, Json(user)))
+}
+
+#[derive(serde::Deserialize)]
+pub struct UpdatePreferredCurrencyRequest {
+    /// ISO code such as "EUR"; `null` or empty clears it
+    pub currency: Option<String>,
+}
+
+pub async fn update_preferred_currency(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(body): Json<UpdatePreferredCurrencyRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let user = state
+        .auth_service
+        .update_user_preferred_currency(claims.id, body.currency)
+        .await?;
+
+    let msg = json!({ "type": "user_updated", "payload": user });
+    let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
+
+    Ok((StatusCode::OK, Json(user)))
+}
+
+#[derive(serde::Deserialize)]
+pub struct UpdateTimezoneRequest {
+    /// IANA timezone such as "Europe/Berlin"; `null` or "UTC" clears it
+    pub timezone: Option<String>,
+}
+
+pub async fn update_timezone(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(body): Json<UpdateTimezoneRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let user = state
+        .auth_service
+        .update_user_timezone(claims.id, body.timezone)
+        .await?;
+
+    let msg = json!({ "type": "user_updated", "payload": user });
+    let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
+
+    Ok((StatusCode::OK, Json(user)))
+}
+
+/// When to be reminded of lent movies
+pub async fn update_loan_reminders(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(body): Json<LoanReminderSettings>,
+) -> Result<impl IntoResponse, ApiError> {
+    let user = state
+        .auth_service
+        .update_user_loan_reminders(claims.id, body)
+        .await?;
+
+    let msg = json!({ "type": "user_updated", "payload": user });
+    let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
+
+    Ok((StatusCode::OK, Json(user)))
+}
+
+/// TMDB image sizes and metadata languages used for your refreshes
+pub async fn update_tmdb(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(body): Json<TmdbPreferences>,
+) -> Result<impl IntoResponse, ApiError> {
+    let user = state.auth_service.update_user_tmdb(claims.id, body).await?;
+
+    let msg = json!({ "type": "user_updated", "payload": user });
+    let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
+
+    Ok((StatusCode::OK, Json(user)))
+}
+
+/// When to get the weekly digest email
+pub async fn update_digest(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(body): Json<DigestSettings>,
+) -> Result<impl IntoResponse, ApiError> {
+    let user = state
+        .auth_service
+        .update_user_digest(claims.id, body)
+        .await?;
+
+    let msg = json!({ "type": "user_updated", "payload": user });
+    let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
+
+    Ok((StatusCode::OK, Json(user)))
+}
+
+/// The digest email as it would be sent now
+pub async fn preview_digest(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<impl IntoResponse, ApiError> {
+    let digest = state
+        .digest_service
+        .preview(claims.id, chrono::Utc::now())
+        .await?;
+    Ok((StatusCode::OK, Json(DigestService::render(&digest))))
+}
+
+/// Values new movies get when created without them
+pub async fn update_movie_defaults(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(body): Json<MovieDefaults>,
+) -> Result<impl IntoResponse, ApiError> {
+    let user = state
+        .auth_service
+        .update_user_movie_defaults(claims.id, body)
+        .await?;
+
+    let msg = json!({ "type": "user_updated", "payload": user });
+    let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
 
     Ok((StatusCode::OK, Json(user)))
 }
@@ -145,7 +424,7 @@ pub async fn update_card_size(
         .await?;
 
     let msg = json!({ "type": "user_updated", "payload": user });
-    let _ = state.ws_broadcast.send(msg.to_string());
+    let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
 
     Ok((StatusCode::OK, Json(user)))
 }
@@ -210,7 +489,7 @@ pub async fn upload_avatar(
                 .await?;
 
             let msg = json!({ "type": "user_updated", "payload": user });
-            let _ = state.ws_broadcast.send(msg.to_string());
+            let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
 
             return Ok((
                 StatusCode::OK,
@@ -233,7 +512,7 @@ pub async fn delete_avatar(
         .await?;
 
     let msg = json!({ "type": "user_updated", "payload": user });
-    let _ = state.ws_broadcast.send(msg.to_string());
+    let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
 
     Ok((
         StatusCode::OK,
@@ -281,3 +560,90 @@ fn detect_image_type(data: &[u8]) -> &'static str {
     }
     "image/jpeg"
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{Method, StatusCode};
+    use serde_json::json;
+
+    use super::PASSWORD_RESET_BURST;
+    use crate::test_helpers::create_test_app;
+
+    #[tokio::test]
+    async fn test_forgot_password_needs_public_url() {
+        let app = create_test_app().await;
+        let body = json!({ "email": "user@test.com" });
+
+        // A relative link would be useless in the email
+        let status = app
+            .request(
+                Method::POST,
+                "/api/v1/auth/forgot-password",
+                None,
+                body.clone(),
+            )
+            .await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+
+        let status = app
+            .request(
+                Method::PUT,
+                "/api/v1/settings/public_url",
+                Some(&app.admin_token),
+                json!({ "value": "https://movies.example.com" }),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+        let status = app
+            .request(Method::POST, "/api/v1/auth/forgot-password", None, body)
+            .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_forgot_password_is_rate_limited() {
+        let app = create_test_app().await;
+        let status = app
+            .request(
+                Method::PUT,
+                "/api/v1/settings/public_url",
+                Some(&app.admin_token),
+                json!({ "value": "https://movies.example.com" }),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+
+        for _ in 0..PASSWORD_RESET_BURST as usize {
+            let status = app
+                .request(
+                    Method::POST,
+                    "/api/v1/auth/forgot-password",
+                    None,
+                    json!({ "email": "user@test.com" }),
+                )
+                .await;
+            assert_eq!(status, StatusCode::OK);
+        }
+        // Spelling the address differently doesn't get around the limit
+        let status = app
+            .request(
+                Method::POST,
+                "/api/v1/auth/forgot-password",
+                None,
+                json!({ "email": " User@Test.com" }),
+            )
+            .await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+
+        // Other addresses have their own budget
+        let status = app
+            .request(
+                Method::POST,
+                "/api/v1/auth/forgot-password",
+                None,
+                json!({ "email": "admin@test.com" }),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+}
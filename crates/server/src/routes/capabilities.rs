@@ -0,0 +1,106 @@
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+
+use axum::{Json, extract::State};
+use serde::Serialize;
+
+use my_movies_core::models::SettingKey;
+use my_movies_core::services::storage_kind;
+
+use crate::{ApiError, AppState};
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CapabilityStatus {
+    Configured,
+    Unconfigured,
+}
+
+impl From<bool> for CapabilityStatus {
+    fn from(configured: bool) -> Self {
+        if configured {
+            CapabilityStatus::Configured
+        } else {
+            CapabilityStatus::Unconfigured
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Integrations {
+    /// Ratings from OMDb
+    pub omdb: CapabilityStatus,
+    /// Discord webhook notifications
+    pub discord: CapabilityStatus,
+    /// Forwarding of server errors to the error report URL
+    pub error_reporting: CapabilityStatus,
+    /// Push notifications to devices registered with `POST /devices`
+    pub push: CapabilityStatus,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Capabilities {
+    pub version: &'static str,
+    /// Highest applied database migration
+    pub schema_version: Option<i64>,
+    /// TMDB search, refresh and enrichment; routes needing it answer `503`
+    /// with code `tmdb_unconfigured` while no API key is set
+    pub tmdb: CapabilityStatus,
+    /// Outgoing mail. Without it password reset links only go to the server log.
+    pub email: CapabilityStatus,
+    pub integrations: Integrations,
+    /// Whether `POST /auth/register` accepts new accounts
+    pub registration: bool,
+    /// Whether registering needs an invite code (open registration disabled)
+    pub registration_invite_only: bool,
+    pub read_only: bool,
+    pub demo_mode: bool,
+    /// Whether users may create share links
+    pub sharing: bool,
+    /// Barcode lookup providers in the order they are asked
+    pub barcode_providers: Vec<&'static str>,
+    /// Text recognition for `POST /movies/identify-cover`: `tesseract`,
+    /// `google`, or `None` when disabled
+    pub ocr: Option<&'static str>,
+    /// Where new images are stored: `db`, `fs` or `s3`
+    pub storage: &'static str,
+}
+
+/// What this instance supports, so clients can hide what doesn't work.
+/// Public: clients ask before logging in.
+pub async fn get(State(state): State<Arc<AppState>>) -> Result<Json<Capabilities>, ApiError> {
+    let read_only = state.read_only.load(Ordering::Relaxed);
+    let sharing = state
+        .settings_service
+        .get_bool(SettingKey::SharingEnabled, true)
+        .await?;
+    let open_registration = state
+        .settings_service
+        .get_bool(SettingKey::OpenRegistration, true)
+        .await?;
+
+    Ok(Json(Capabilities {
+        version: env!("CARGO_PKG_VERSION"),
+        schema_version: state.diagnostics_service.schema_version().await?,
+        tmdb: state.tmdb_service.is_configured().into(),
+        email: CapabilityStatus::Unconfigured,
+        integrations: Integrations {
+            omdb: state.omdb_service.is_configured().into(),
+            discord: state.discord_service.is_configured().into(),
+            error_reporting: state.error_reporter.is_enabled().into(),
+            push: state
+                .notification_service
+                .push_provider_name()
+                .is_some()
+                .into(),
+        },
+        registration: !read_only && !state.demo_mode,
+        registration_invite_only: !open_registration,
+        read_only,
+        demo_mode: state.demo_mode,
+        sharing,
+        barcode_providers: state.ean_service.provider_names(),
+        ocr: state.ocr_service.provider_name(),
+        storage: storage_kind(),
+    }))
+}
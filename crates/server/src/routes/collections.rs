@@ -6,6 +6,7 @@ use axum::{
     http::StatusCode,
     response::IntoResponse,
 };
+use serde::Deserialize;
 use serde_json::json;
 use uuid::Uuid;
 
@@ -20,6 +21,14 @@ pub async fn list(
     Extension(claims): Extension<Claims>,
     Query(filter): Query<CollectionFilter>,
 ) -> Result<impl IntoResponse, ApiError> {
+    if filter.with_stats {
+        let collections = state
+            .collection_service
+            .list_with_stats(claims.id, filter)
+            .await?;
+        return Ok((StatusCode::OK, Json(json!(collections))));
+    }
+
     let collections = state.collection_service.list(claims.id, filter).await?;
     Ok((StatusCode::OK, Json(json!(collections))))
 }
@@ -64,12 +73,38 @@ pub async fn delete(
     Ok(StatusCode::NO_CONTENT)
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ItemsQuery {
+    /// Include title, year, watched state and thumbnail of each movie/series
+    #[serde(default)]
+    pub expand: bool,
+}
+
 pub async fn get_items(
     State(state): State<Arc<AppState>>,
     Extension(claims): Extension<Claims>,
     Path(id): Path<Uuid>,
+    Query(query): Query<ItemsQuery>,
 ) -> Result<impl IntoResponse, ApiError> {
-    let items = state.collection_service.get_items(claims.id, id).await?;
+    if !query.expand {
+        let items = state.collection_service.get_items(claims.id, id).await?;
+        return Ok((StatusCode::OK, Json(json!(items))));
+    }
+
+    let mut items = state
+        .collection_service
+        .get_item_details(claims.id, id)
+        .await?;
+    for details in &mut items {
+        if !details.has_poster {
+            continue;
+        }
+        details.thumb = match (details.item.movie_id, details.item.series_id) {
+            (Some(movie_id), _) => Some(format!("/api/v1/movies/{}/thumbnail", movie_id)),
+            (None, Some(series_id)) => Some(format!("/api/v1/series/{}/poster", series_id)),
+            (None, None) => None,
+        };
+    }
     Ok((StatusCode::OK, Json(json!(items))))
 }
 
@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+use my_movies_core::models::{Claims, CreateContact, UpdateContact};
+
+use crate::{ApiError, AppState};
+
+pub async fn list(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<impl IntoResponse, ApiError> {
+    let contacts = state.contact_service.list(claims.id).await?;
+    Ok((StatusCode::OK, Json(json!(contacts))))
+}
+
+pub async fn get(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    let contact = state.contact_service.get_by_id(claims.id, id).await?;
+    Ok((StatusCode::OK, Json(json!(contact))))
+}
+
+pub async fn create(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(input): Json<CreateContact>,
+) -> Result<impl IntoResponse, ApiError> {
+    let contact = state.contact_service.create(claims.id, input).await?;
+    Ok((StatusCode::CREATED, Json(json!(contact))))
+}
+
+pub async fn update(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+    Json(input): Json<UpdateContact>,
+) -> Result<impl IntoResponse, ApiError> {
+    let contact = state.contact_service.update(claims.id, id, input).await?;
+    Ok((StatusCode::OK, Json(json!(contact))))
+}
+
+pub async fn delete(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    state.contact_service.delete(claims.id, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LoansQuery {
+    /// Only movies the contact still has
+    #[serde(default)]
+    pub active: bool,
+}
+
+pub async fn loans(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<LoansQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let loans = state
+        .contact_service
+        .loans(claims.id, id, query.active)
+        .await?;
+    Ok((StatusCode::OK, Json(json!(loans))))
+}
@@ -0,0 +1,130 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use uuid::Uuid;
+
+use my_movies_core::models::{Claims, CreateHousehold, InviteHouseholdMember, MessageLevel};
+
+use crate::{ApiError, AppState};
+
+/// The user's household with its members
+pub async fn get(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<impl IntoResponse, ApiError> {
+    let household = state
+        .household_service
+        .get(claims.id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Not in a household"))?;
+    Ok(Json(household))
+}
+
+pub async fn create(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(input): Json<CreateHousehold>,
+) -> Result<impl IntoResponse, ApiError> {
+    let household = state
+        .household_service
+        .create(claims.id, &input.name)
+        .await?;
+    Ok((StatusCode::CREATED, Json(household)))
+}
+
+/// Dissolve the household (owner only)
+pub async fn delete(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<impl IntoResponse, ApiError> {
+    state.household_service.delete(claims.id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Invite a user by username (owner only); they join by accepting
+pub async fn invite(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(input): Json<InviteHouseholdMember>,
+) -> Result<impl IntoResponse, ApiError> {
+    let (household, user_id) = state
+        .household_service
+        .invite(claims.id, &input.username)
+        .await?;
+
+    let message = format!(
+        "{} invited you to the household {}",
+        claims.username, household.name
+    );
+    if let Err(e) = state
+        .notification_service
+        .notify(
+            Some(&[user_id]),
+            "household_invitation",
+            MessageLevel::Info,
+            &message,
+        )
+        .await
+    {
+        tracing::warn!(
+            "Failed to notify {} of a household invitation: {}",
+            user_id,
+            e
+        );
+    }
+    Ok(Json(household))
+}
+
+/// Invitations to households the user hasn't answered
+pub async fn invitations(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<impl IntoResponse, ApiError> {
+    let invitations = state.household_service.invitations(claims.id).await?;
+    Ok(Json(invitations))
+}
+
+/// Join the household that sent the invitation
+pub async fn accept(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(household_id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    let household = state
+        .household_service
+        .accept(claims.id, household_id)
+        .await?;
+    Ok(Json(household))
+}
+
+/// Turn an invitation down
+pub async fn decline(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(household_id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    state
+        .household_service
+        .decline(claims.id, household_id)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Remove a member or withdraw an invitation (owner), or leave with the own
+/// user id
+pub async fn remove_member(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(user_id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    state
+        .household_service
+        .remove_member(claims.id, user_id)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -1,15 +1,31 @@
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 
-use axum::extract::{Multipart, Query};
+use axum::extract::{Multipart, Path, Query};
 use axum::{Extension, Json, extract::State, http::StatusCode, response::IntoResponse};
+use chrono::{DateTime, Utc};
 use serde_json::json;
 use tokio::time::{Duration, sleep};
 
-use my_movies_core::models::{Claims, MovieFilter};
-
-use crate::routes::movies::{TmdbRefreshResult, refresh_movie_tmdb_internal};
-use crate::{ApiError, AppState};
+use my_movies_core::models::{
+    Claims, EntityType, ImportPreset, Movie, MovieFilter, SaveImportPreset, SettingKey, UpdateMovie,
+};
+use my_movies_core::services::import::ImportResult;
+use my_movies_core::services::media_folder::{self, MediaFile};
+use my_movies_core::services::{
+    PushMessage, TmdbMovie, TmdbOptions, builtin_import_presets, find_import_preset,
+};
+use uuid::Uuid;
+
+use crate::reconcile::{self, ReconcileReport, ReconcileSelection};
+use crate::routes::movies::{
+    ExportData, ExportMovie, TmdbRefreshResult, refresh_movie_tmdb_internal, restore_disposal,
+    tmdb_options,
+};
+use crate::routes::ws::WsEvent;
+use crate::{ApiError, AppState, backup};
 
 /// Global state for TMDB enrichment
 static ENRICH_CANCELLED: AtomicBool = AtomicBool::new(false);
@@ -19,11 +35,28 @@ static ENRICH_CURRENT: AtomicU32 = AtomicU32::new(0);
 static ENRICH_UPDATED: AtomicU32 = AtomicU32::new(0);
 static ENRICH_ERRORS: AtomicU32 = AtomicU32::new(0);
 
+#[derive(Debug, serde::Deserialize)]
+pub struct ImportCsvQuery {
+    /// Name of a built-in or saved import preset the file is read with
+    pub preset: Option<String>,
+}
+
 pub async fn import_csv(
     State(state): State<Arc<AppState>>,
     Extension(claims): Extension<Claims>,
+    Query(query): Query<ImportCsvQuery>,
     mut multipart: Multipart,
 ) -> Result<impl IntoResponse, ApiError> {
+    let preset = match query.preset.as_deref().filter(|p| !p.trim().is_empty()) {
+        Some(name) => {
+            let presets = state.auth_service.get_preferences(claims.id).await?;
+            let preset = find_import_preset(presets.import_presets, name)
+                .ok_or_else(|| ApiError::not_found(format!("Unknown import preset '{}'", name)))?;
+            Some(preset)
+        }
+        None => None,
+    };
+
     while let Some(field) = multipart.next_field().await.unwrap_or(None) {
         let name = field.name().unwrap_or("").to_string();
 
@@ -34,19 +67,91 @@ pub async fn import_csv(
                 .map_err(|e| ApiError::bad_request(format!("Failed to read file: {}", e)))?;
 
             let cursor = std::io::Cursor::new(data);
-            let result = state.import_service.import_csv(claims.id, cursor).await?;
+            let result = state
+                .import_service
+                .import_csv(claims.id, cursor, preset.as_ref())
+                .await?;
+            return Ok((
+                StatusCode::OK,
+                Json(finish_import(&state, &claims, result).await),
+            ));
+        }
+    }
 
-            let msg = json!({ "type": "collection_imported" });
-            let _ = state.ws_broadcast.send(msg.to_string());
+    Err(ApiError::bad_request("No file provided"))
+}
 
+/// Built-in import presets followed by the user's own
+pub async fn list_presets(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<impl IntoResponse, ApiError> {
+    let mut presets = builtin_import_presets();
+    presets.extend(
+        state
+            .auth_service
+            .get_preferences(claims.id)
+            .await?
+            .import_presets,
+    );
+    Ok((StatusCode::OK, Json(presets)))
+}
+
+/// Save an import preset under the name, replacing one with the same name
+pub async fn save_preset(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(name): Path<String>,
+    Json(body): Json<SaveImportPreset>,
+) -> Result<impl IntoResponse, ApiError> {
+    let preset = ImportPreset {
+        name,
+        columns: body.columns,
+        list_separator: body.list_separator,
+        values: body.values,
+        builtin: false,
+    };
+    let preset = state
+        .auth_service
+        .save_import_preset(claims.id, preset)
+        .await?;
+    Ok((StatusCode::OK, Json(preset)))
+}
+
+pub async fn delete_preset(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(name): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    state
+        .auth_service
+        .delete_import_preset(claims.id, &name)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Import a DVD Profiler `collection.xml` export
+pub async fn import_dvdprofiler(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    mut multipart: Multipart,
+) -> Result<impl IntoResponse, ApiError> {
+    while let Some(field) = multipart.next_field().await.unwrap_or(None) {
+        let name = field.name().unwrap_or("").to_string();
+
+        if name == "file" {
+            let data = field
+                .bytes()
+                .await
+                .map_err(|e| ApiError::bad_request(format!("Failed to read file: {}", e)))?;
+
+            let result = state
+                .import_service
+                .import_dvdprofiler_xml(claims.id, &data)
+                .await?;
             return Ok((
                 StatusCode::OK,
-                Json(json!({
-                    "movies_imported": result.movies_imported,
-                    "series_imported": result.series_imported,
-                    "collections_imported": result.collections_imported,
-                    "errors": result.errors
-                })),
+                Json(finish_import(&state, &claims, result).await),
             ));
         }
     }
@@ -54,6 +159,430 @@ pub async fn import_csv(
     Err(ApiError::bad_request("No file provided"))
 }
 
+/// Notify the user's clients of a finished file import, prefetch posters of
+/// the new movies and build the response
+async fn finish_import(
+    state: &Arc<AppState>,
+    claims: &Claims,
+    result: ImportResult,
+) -> serde_json::Value {
+    let msg = json!({ "type": "collection_imported" });
+    let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
+    push_import_finished(
+        state,
+        claims.id,
+        "collection_imported",
+        format!(
+            "{} Filme und {} Serien importiert.",
+            result.movies_imported, result.series_imported
+        ),
+    )
+    .await;
+
+    let poster_prefetch = prefetch_after_import(state, claims, &result.movie_ids).await;
+
+    json!({
+        "movies_imported": result.movies_imported,
+        "series_imported": result.series_imported,
+        "collections_imported": result.collections_imported,
+        "errors": result.errors,
+        "poster_prefetch": poster_prefetch
+    })
+}
+
+/// Check a ZIP backup's structure and checksums without importing anything
+pub async fn verify_backup(mut multipart: Multipart) -> Result<impl IntoResponse, ApiError> {
+    while let Some(field) = multipart.next_field().await.unwrap_or(None) {
+        let name = field.name().unwrap_or("").to_string();
+
+        if name == "file" {
+            let data = field
+                .bytes()
+                .await
+                .map_err(|e| ApiError::bad_request(format!("Failed to read file: {}", e)))?;
+
+            let report = tokio::task::spawn_blocking(move || backup::verify_archive(&data))
+                .await
+                .map_err(|e| ApiError::internal(format!("Verification failed: {}", e)))?;
+
+            return Ok((StatusCode::OK, Json(report)));
+        }
+    }
+
+    Err(ApiError::bad_request("No file provided"))
+}
+
+/// Result of merging the selected entries of a reconciled backup
+#[derive(Debug, Default, serde::Serialize)]
+pub struct ReconcileApplied {
+    pub created: usize,
+    pub updated: usize,
+    pub restored: usize,
+    pub errors: Vec<String>,
+}
+
+/// Compare a backup (ZIP or movies.json in `file`) with the library: entries
+/// that are new, changed or were deleted since the backup. With an `apply`
+/// field (`{"new": [...], "changed": [...], "deleted": [...]}`, backup IDs) the
+/// selected entries are merged into the library.
+pub async fn reconcile_backup(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    mut multipart: Multipart,
+) -> Result<impl IntoResponse, ApiError> {
+    let mut data = None;
+    let mut selection: Option<ReconcileSelection> = None;
+    while let Some(field) = multipart.next_field().await.unwrap_or(None) {
+        match field.name().unwrap_or("") {
+            "file" => {
+                data =
+                    Some(field.bytes().await.map_err(|e| {
+                        ApiError::bad_request(format!("Failed to read file: {}", e))
+                    })?);
+            }
+            "apply" => {
+                let text = field.text().await.map_err(|e| {
+                    ApiError::bad_request(format!("Failed to read selection: {}", e))
+                })?;
+                selection = Some(
+                    serde_json::from_str(&text)
+                        .map_err(|e| ApiError::bad_request(format!("Invalid selection: {}", e)))?,
+                );
+            }
+            _ => {}
+        }
+    }
+    let data = data.ok_or_else(|| ApiError::bad_request("No file provided"))?;
+
+    let (backup, posters) = tokio::task::spawn_blocking(move || reconcile::read_backup(&data))
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to read backup: {}", e)))?
+        .map_err(ApiError::bad_request)?;
+
+    let live = state
+        .movie_service
+        .list(
+            claims.id,
+            MovieFilter {
+                exclude_collection_children: Some(false),
+                ..Default::default()
+            },
+        )
+        .await?;
+    let since = DateTime::parse_from_rfc3339(&backup.exported_at)
+        .map(|d| d.with_timezone(&Utc))
+        .unwrap_or_default();
+    let deletions = state
+        .tombstone_service
+        .since(claims.id, since, Some(EntityType::Movie))
+        .await?;
+
+    let report = reconcile::reconcile(&backup, &live, &deletions);
+    let applied = match selection {
+        Some(selection) => {
+            let applied =
+                apply_reconcile(&state, claims.id, &backup, &posters, &report, selection).await;
+            let msg = json!({
+                "type": "collection_imported",
+                "payload": { "count": applied.created + applied.restored }
+            });
+            let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
+            Some(applied)
+        }
+        None => None,
+    };
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({ "report": report, "applied": applied })),
+    ))
+}
+
+async fn apply_reconcile(
+    state: &Arc<AppState>,
+    user_id: Uuid,
+    backup: &ExportData,
+    posters: &HashMap<String, Vec<u8>>,
+    report: &ReconcileReport,
+    selection: ReconcileSelection,
+) -> ReconcileApplied {
+    let mut applied = ReconcileApplied::default();
+    let mut id_map = report.matches.clone();
+    let parent_of = |entry: &ExportMovie, id_map: &HashMap<String, Uuid>| {
+        entry
+            .parent_collection_id
+            .as_ref()
+            .and_then(|old_id| id_map.get(old_id).copied())
+    };
+
+    let new_ids: HashSet<&str> = report.new.iter().map(|e| e.backup_id.as_str()).collect();
+    let deleted_ids: HashSet<&str> = report
+        .deleted
+        .iter()
+        .map(|e| e.backup_id.as_str())
+        .collect();
+    for id in &selection.new {
+        if !new_ids.contains(id.as_str()) {
+            applied.errors.push(format!("'{}' is not a new entry", id));
+        }
+    }
+    for id in &selection.deleted {
+        if !deleted_ids.contains(id.as_str()) {
+            applied
+                .errors
+                .push(format!("'{}' is not a deleted entry", id));
+        }
+    }
+
+    // Collections first, so their children can be linked
+    let mut to_create: Vec<&ExportMovie> = backup
+        .movies
+        .iter()
+        .filter(|m| {
+            (new_ids.contains(m.id.as_str()) && selection.new.contains(&m.id))
+                || (deleted_ids.contains(m.id.as_str()) && selection.deleted.contains(&m.id))
+        })
+        .collect();
+    to_create.sort_by_key(|m| std::cmp::Reverse(m.is_collection));
+
+    for entry in to_create {
+        let movie = match state
+            .movie_service
+            .create_without_defaults(user_id, entry.to_create())
+            .await
+        {
+            Ok(movie) => movie,
+            Err(e) => {
+                applied
+                    .errors
+                    .push(format!("Error importing '{}': {}", entry.title, e));
+                continue;
+            }
+        };
+        id_map.insert(entry.id.clone(), movie.id);
+
+        let update = UpdateMovie {
+            parent_collection_id: parent_of(entry, &id_map),
+            poster_data: posters.get(&entry.id).cloned(),
+            ..entry.to_update()
+        };
+        if let Err(e) = state.movie_service.update(user_id, movie.id, update).await {
+            applied
+                .errors
+                .push(format!("Error updating '{}': {}", entry.title, e));
+        }
+        restore_disposal(state, user_id, movie.id, entry, &mut applied.errors).await;
+
+        if deleted_ids.contains(entry.id.as_str()) {
+            applied.restored += 1;
+        } else {
+            applied.created += 1;
+        }
+    }
+
+    for id in &selection.changed {
+        let Some(changed) = report.changed.iter().find(|c| &c.backup_id == id) else {
+            applied
+                .errors
+                .push(format!("'{}' is not a changed entry", id));
+            continue;
+        };
+        let Some(entry) = backup.movies.iter().find(|m| &m.id == id) else {
+            continue;
+        };
+
+        let update = UpdateMovie {
+            title: Some(entry.title.clone()),
+            original_title: entry.original_title.clone(),
+            production_year: entry.production_year,
+            disc_type: entry.disc_type.clone(),
+            parent_collection_id: parent_of(entry, &id_map),
+            ..entry.to_update()
+        };
+        match state
+            .movie_service
+            .update(user_id, changed.movie_id, update)
+            .await
+        {
+            Ok(_) => applied.updated += 1,
+            Err(e) => applied
+                .errors
+                .push(format!("Error updating '{}': {}", entry.title, e)),
+        }
+    }
+
+    applied
+}
+
+/// Disc type proposed for files found by a media folder scan
+pub const DIGITAL_DISC_TYPE: &str = "Digital";
+
+#[derive(Debug, serde::Deserialize)]
+pub struct MediaFolderRequest {
+    pub path: String,
+    /// Include subfolders, defaults to true
+    pub recursive: Option<bool>,
+}
+
+/// A movie entry proposed for a video file. Nothing is created; the client
+/// creates the entries the user accepts.
+#[derive(Debug, serde::Serialize)]
+pub struct MediaFolderProposal {
+    pub file: MediaFile,
+    pub title: String,
+    pub original_title: Option<String>,
+    pub production_year: Option<i32>,
+    pub tmdb_id: Option<i64>,
+    pub poster_path: Option<String>,
+    pub disc_type: &'static str,
+    /// Movie already in the catalog (same TMDB ID or title), e.g. the disc the file was ripped from
+    pub existing_movie_id: Option<Uuid>,
+}
+
+/// Scan a folder of video files on the server and propose digital movie entries
+pub async fn scan_media_folder(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(req): Json<MediaFolderRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let root = PathBuf::from(req.path.trim());
+    let recursive = req.recursive.unwrap_or(true);
+    let files =
+        tokio::task::spawn_blocking(move || media_folder::scan_media_folder(&root, recursive))
+            .await
+            .map_err(|e| ApiError::internal(format!("Folder scan failed: {}", e)))??;
+
+    let user = state.auth_service.get_user(claims.id).await?;
+    let language = user.language.as_deref();
+    let tmdb_configured = state.tmdb_service.is_configured();
+
+    let mut proposals = Vec::with_capacity(files.len());
+    for file in files {
+        let tmdb_match = if tmdb_configured {
+            match_media_file(&state, &file, language, user.include_adult).await
+        } else {
+            None
+        };
+
+        let mut proposal = MediaFolderProposal {
+            title: file.title.clone(),
+            original_title: None,
+            production_year: file.year,
+            tmdb_id: None,
+            poster_path: None,
+            disc_type: DIGITAL_DISC_TYPE,
+            existing_movie_id: None,
+            file,
+        };
+
+        if let Some(tmdb) = tmdb_match {
+            proposal.title = tmdb.title;
+            proposal.original_title = tmdb.original_title;
+            proposal.production_year = tmdb
+                .release_date
+                .as_deref()
+                .and_then(|d| d.get(..4))
+                .and_then(|y| y.parse().ok())
+                .or(proposal.production_year);
+            proposal.tmdb_id = Some(tmdb.id);
+            proposal.poster_path = tmdb.poster_path;
+
+            if let Some(existing) = state
+                .movie_service
+                .find_by_tmdb_id(claims.id, tmdb.id)
+                .await?
+            {
+                proposal.existing_movie_id = Some(existing.id);
+            }
+        }
+
+        if proposal.existing_movie_id.is_none() {
+            proposal.existing_movie_id = state
+                .movie_service
+                .find_by_title(claims.id, &proposal.title)
+                .await?
+                .first()
+                .map(|m| m.id);
+        }
+
+        proposals.push(proposal);
+    }
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "total": proposals.len(),
+            "tmdb_matched": proposals.iter().filter(|p| p.tmdb_id.is_some()).count(),
+            "proposals": proposals
+        })),
+    ))
+}
+
+/// Best TMDB match for a scanned file; retries without the year, which is
+/// often the digital release year rather than the original one
+async fn match_media_file(
+    state: &Arc<AppState>,
+    file: &MediaFile,
+    language: Option<&str>,
+    include_adult: bool,
+) -> Option<TmdbMovie> {
+    let search = move |year: Option<i32>| {
+        state
+            .tmdb_service
+            .search_movies(&file.title, year, language, include_adult)
+    };
+
+    if file.year.is_some()
+        && let Ok(results) = search(file.year).await
+        && let Some(first) = results.into_iter().next()
+    {
+        return Some(first);
+    }
+
+    search(None).await.ok()?.into_iter().next()
+}
+
+/// Start TMDB enrichment for freshly imported movies, so they get posters
+/// without a manual enrichment run. Progress is reported like a manual run.
+/// Returns whether the job was started.
+async fn prefetch_after_import(state: &Arc<AppState>, claims: &Claims, movie_ids: &[Uuid]) -> bool {
+    if movie_ids.is_empty() || !state.tmdb_service.is_configured() {
+        return false;
+    }
+
+    match state
+        .settings_service
+        .get_bool(SettingKey::PosterPrefetchAfterImport, true)
+        .await
+    {
+        Ok(true) => {}
+        Ok(false) => return false,
+        Err(e) => {
+            tracing::warn!("Failed to read poster prefetch setting: {}", e);
+            return false;
+        }
+    }
+
+    let mut movies = Vec::with_capacity(movie_ids.len());
+    for id in movie_ids {
+        if let Ok(movie) = state.movie_service.get_by_id(claims.id, *id).await {
+            movies.push(movie);
+        }
+    }
+
+    match start_enrichment(state, claims.id, movies, false).await {
+        Ok(total) => {
+            tracing::info!("Fetching posters for {} imported movies", total);
+            true
+        }
+        // A running enrichment (e.g. a second import) is not an error for the import
+        Err(e) => {
+            tracing::info!("Poster prefetch after import not started: {}", e.message);
+            false
+        }
+    }
+}
+
 #[derive(Debug, serde::Deserialize)]
 pub struct EnrichTmdbQuery {
     #[serde(default)]
@@ -66,6 +595,9 @@ pub async fn enrich_movies_tmdb(
     Extension(claims): Extension<Claims>,
     Query(params): Query<EnrichTmdbQuery>,
 ) -> Result<impl IntoResponse, ApiError> {
+    if !state.tmdb_service.is_configured() {
+        return Err(ApiError::tmdb_unconfigured());
+    }
     let filter = MovieFilter {
         limit: Some(10000),
         ..Default::default()
@@ -94,7 +626,11 @@ pub async fn enrich_movies_tmdb(
 
         let filtered: Vec<_> = movies
             .into_iter()
-            .filter(|m| m.tmdb_id.is_none() || !movies_with_poster.contains(&m.id))
+            .filter(|m| {
+                m.tmdb_id.is_none()
+                    || !movies_with_poster.contains(&m.id)
+                    || (m.is_collection && m.collection_completeness.is_none())
+            })
             .collect();
 
         tracing::info!("After filter: {} movies need enrichment", filtered.len());
@@ -102,9 +638,7 @@ pub async fn enrich_movies_tmdb(
         filtered
     };
 
-    let total = movies_to_enrich.len();
-
-    if total == 0 {
+    if movies_to_enrich.is_empty() {
         return Ok((
             StatusCode::OK,
             Json(json!({
@@ -114,58 +648,75 @@ pub async fn enrich_movies_tmdb(
         ));
     }
 
-    if ENRICH_RUNNING.load(Ordering::SeqCst) {
+    let total = start_enrichment(&state, claims.id, movies_to_enrich, params.force).await?;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(json!({ "message": "TMDB enrichment started", "total": total })),
+    ))
+}
+
+/// Run enrichment for `movies` in the background. Fails if one is already running.
+async fn start_enrichment(
+    state: &Arc<AppState>,
+    user_id: Uuid,
+    movies_to_enrich: Vec<Movie>,
+    force: bool,
+) -> Result<usize, ApiError> {
+    let total = movies_to_enrich.len();
+
+    if ENRICH_RUNNING.swap(true, Ordering::SeqCst) {
         return Err(ApiError::conflict("TMDB enrichment is already running"));
     }
 
     // Reset state
     ENRICH_CANCELLED.store(false, Ordering::SeqCst);
-    ENRICH_RUNNING.store(true, Ordering::SeqCst);
     ENRICH_TOTAL.store(total as u32, Ordering::SeqCst);
     ENRICH_CURRENT.store(0, Ordering::SeqCst);
     ENRICH_UPDATED.store(0, Ordering::SeqCst);
     ENRICH_ERRORS.store(0, Ordering::SeqCst);
 
     let msg = json!({ "type": "tmdb_enrich_started", "payload": { "total": total } });
-    let _ = state.ws_broadcast.send(msg.to_string());
+    let _ = state.ws_broadcast.send(WsEvent::user(user_id, &msg));
 
-    let user = state.auth_service.get_user(claims.id).await?;
-    let language = user.language.clone();
+    let user = match state.auth_service.get_user(user_id).await {
+        Ok(user) => user,
+        Err(e) => {
+            ENRICH_RUNNING.store(false, Ordering::SeqCst);
+            return Err(e.into());
+        }
+    };
+    let options = tmdb_options(state, &user);
     let include_adult = user.include_adult;
 
     let state_clone = state.clone();
-    let user_id = claims.id;
 
     tokio::spawn(async move {
         run_enrichment(
             state_clone,
             user_id,
             movies_to_enrich,
-            language,
+            options,
             include_adult,
-            params.force,
+            force,
         )
         .await;
     });
 
-    Ok((
-        StatusCode::ACCEPTED,
-        Json(json!({ "message": "TMDB enrichment started", "total": total })),
-    ))
+    Ok(total)
 }
 
 async fn run_enrichment(
     state: Arc<AppState>,
     user_id: uuid::Uuid,
-    movies: Vec<my_movies_core::models::Movie>,
-    language: Option<String>,
+    movies: Vec<Movie>,
+    options: TmdbOptions,
     include_adult: bool,
     force: bool,
 ) {
     let total = movies.len();
     let mut enriched = 0;
     let mut errors: Vec<String> = Vec::new();
-    let lang = language.as_deref();
     let mut cancelled = false;
 
     for (index, movie) in movies.iter().enumerate() {
@@ -175,11 +726,12 @@ async fn run_enrichment(
                 "type": "tmdb_enrich_cancelled",
                 "payload": { "current": index, "total": total, "enriched": enriched }
             });
-            let _ = state.ws_broadcast.send(msg.to_string());
+            let _ = state.ws_broadcast.send(WsEvent::user(user_id, &msg));
             break;
         }
 
-        match refresh_movie_tmdb_internal(&state, user_id, movie, lang, include_adult, force).await
+        match refresh_movie_tmdb_internal(&state, user_id, movie, &options, include_adult, force)
+            .await
         {
             TmdbRefreshResult::Success(_) => enriched += 1,
             TmdbRefreshResult::NotFound(msg) | TmdbRefreshResult::Error(msg) => errors.push(msg),
@@ -199,7 +751,7 @@ async fn run_enrichment(
                     "errors_count": errors.len()
                 }
             });
-            let _ = state.ws_broadcast.send(msg.to_string());
+            let _ = state.ws_broadcast.send(WsEvent::user(user_id, &msg));
         }
 
         sleep(Duration::from_millis(250)).await;
@@ -212,18 +764,32 @@ async fn run_enrichment(
             "type": "tmdb_enrich_complete",
             "payload": { "total": total, "enriched": enriched, "errors": errors }
         });
-        let _ = state.ws_broadcast.send(msg.to_string());
+        let _ = state.ws_broadcast.send(WsEvent::user(user_id, &msg));
+        push_import_finished(
+            &state,
+            user_id,
+            "tmdb_enrich_complete",
+            format!("TMDB-Daten für {} von {} Filmen geladen.", enriched, total),
+        )
+        .await;
     }
 }
 
-/// Cancel the running TMDB enrichment
-pub async fn cancel_enrich_tmdb(
-    Extension(claims): Extension<Claims>,
-) -> Result<impl IntoResponse, ApiError> {
-    if claims.role != my_movies_core::models::UserRole::Admin {
-        return Err(ApiError::from(my_movies_core::Error::Forbidden));
+/// Tell the user's devices that an import or enrichment is done, so they
+/// hear about it without keeping the app open
+async fn push_import_finished(state: &AppState, user_id: Uuid, kind: &str, body: String) {
+    let message = PushMessage {
+        kind: kind.to_string(),
+        title: "Import abgeschlossen".to_string(),
+        body,
+    };
+    if let Err(e) = state.notification_service.push(user_id, &message).await {
+        tracing::warn!("Failed to push {}: {}", kind, e);
     }
+}
 
+/// Cancel the running TMDB enrichment
+pub async fn cancel_enrich_tmdb() -> Result<impl IntoResponse, ApiError> {
     if !ENRICH_RUNNING.load(Ordering::SeqCst) {
         return Ok((
             StatusCode::OK,
@@ -0,0 +1,38 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use uuid::Uuid;
+
+use my_movies_core::models::{Claims, CreateInvite};
+
+use crate::{ApiError, AppState};
+
+/// All invites, newest first (admin only)
+pub async fn list(State(state): State<Arc<AppState>>) -> Result<impl IntoResponse, ApiError> {
+    let invites = state.invite_service.list().await?;
+    Ok(Json(invites))
+}
+
+/// Create an invite; the code is only returned in this response (admin only)
+pub async fn create(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(input): Json<CreateInvite>,
+) -> Result<impl IntoResponse, ApiError> {
+    let invite = state.invite_service.create(claims.id, input).await?;
+    Ok((StatusCode::CREATED, Json(invite)))
+}
+
+/// Revoke an invite (admin only)
+pub async fn delete(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    state.invite_service.delete(id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,40 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+use my_movies_core::models::Claims;
+
+use crate::{ApiError, AppState};
+
+/// Status and progress of one of your jobs
+pub async fn get(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    let job = state
+        .jobs
+        .get(claims.id, id)
+        .ok_or_else(|| ApiError::not_found("Job not found"))?;
+    Ok((StatusCode::OK, Json(json!(job.info()))))
+}
+
+/// Ask a running job to stop after the current item
+pub async fn cancel(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    let job = state
+        .jobs
+        .cancel(claims.id, id)
+        .ok_or_else(|| ApiError::not_found("Job not found"))?;
+    Ok((StatusCode::OK, Json(json!(job))))
+}
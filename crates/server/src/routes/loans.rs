@@ -0,0 +1,75 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+use my_movies_core::models::{ApproveBorrowRequest, BorrowRequestStatus, Claims};
+
+use crate::routes::ws::WsEvent;
+use crate::{ApiError, AppState};
+
+#[derive(Debug, Deserialize)]
+pub struct BorrowRequestsQuery {
+    /// pending, approved or declined; all when missing
+    pub status: Option<BorrowRequestStatus>,
+}
+
+/// Borrow requests for the user's movies, newest first
+pub async fn list_borrow_requests(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<BorrowRequestsQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let requests = state
+        .loan_service
+        .borrow_requests(claims.id, query.status)
+        .await?;
+    Ok((StatusCode::OK, Json(json!(requests))))
+}
+
+/// Lend the movie to the requester
+pub async fn approve_borrow_request(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+    Json(input): Json<ApproveBorrowRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request = state
+        .loan_service
+        .approve_borrow_request(claims.id, id, input)
+        .await?;
+
+    let movie = state
+        .movie_service
+        .get_by_id(claims.id, request.movie_id)
+        .await?;
+    let msg = json!({ "type": "movie_updated", "payload": movie });
+    let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
+    let msg = json!({ "type": "borrow_request_updated", "payload": request });
+    let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
+
+    Ok((StatusCode::OK, Json(request)))
+}
+
+pub async fn decline_borrow_request(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    let request = state
+        .loan_service
+        .decline_borrow_request(claims.id, id)
+        .await?;
+
+    let msg = json!({ "type": "borrow_request_updated", "payload": request });
+    let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
+
+    Ok((StatusCode::OK, Json(request)))
+}
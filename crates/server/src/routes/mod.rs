@@ -1,11 +1,26 @@
+pub mod activity;
+pub mod admin;
+pub mod api_keys;
 pub mod auth;
+pub mod capabilities;
 pub mod collections;
+pub mod contacts;
+pub mod households;
 pub mod import;
+pub mod invites;
+pub mod jobs;
+pub mod loans;
 pub mod movies;
+pub mod notifications;
+pub mod planner;
 pub mod scan;
+pub mod search;
 pub mod series;
 pub mod settings;
+pub mod shares;
+pub mod sync;
 pub mod users;
+pub mod valuation;
 pub mod ws;
 
 // Re-export ApiError as AppError for backward compatibility
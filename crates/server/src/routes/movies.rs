@@ -1,51 +1,56 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use axum::body::Body;
 use axum::{
     Extension, Json,
-    extract::{Multipart, Path, Query, State},
-    http::{StatusCode, header},
+    extract::{Multipart, Path, Query, RawQuery, State},
+    http::{HeaderMap, Method, StatusCode, header},
     response::{IntoResponse, Response},
 };
+use chrono::Datelike;
 use serde_json::json;
 use uuid::Uuid;
 
-use my_movies_core::models::{Claims, CreateMovie, Movie, MovieFilter, UpdateMovie};
-use my_movies_core::services::{TmdbCollectionOverview, TmdbMovie, TmdbService};
+use my_movies_core::models::{
+    AcquireMovie, BulkUpdateMovies, Claims, CreateMovie, CreateSeries, DiscCondition, DisposeMovie,
+    EntityType, ExportedApiKey, FixOrphans, LogWatch, Movie, MovieFilter, MovieStatus, UpdateMovie,
+    UpdateSeries, UserPreferences, UserPublic, normalize_currency, parse_movie_fields,
+    parse_region_codes,
+};
+use my_movies_core::services::{
+    DiscordEvent, TmdbCollection, TmdbCollectionOverview, TmdbMovie, TmdbOptions, TmdbService,
+    image_hash,
+    tmdb::{best_match, is_image_path},
+};
 use serde::{Deserialize, Serialize};
 
-use crate::{ApiError, AppState};
-
-/// Download poster image from TMDB URL and return as bytes
-async fn download_poster_image(poster_path: &str) -> Option<Vec<u8>> {
-    // Build full TMDB image URL (use w500 for good quality)
-    let image_url = TmdbService::poster_url(poster_path, "w500");
-
-    // Download the image
-    match reqwest::get(&image_url).await {
-        Ok(response) => {
-            if response.status().is_success() {
-                match response.bytes().await {
-                    Ok(bytes) => {
-                        let data = bytes.to_vec();
-                        // Validate it's actually an image (basic check)
-                        if data.len() >= 8 {
-                            Some(data)
-                        } else {
-                            tracing::warn!("Downloaded poster too small: {} bytes", data.len());
-                            None
-                        }
-                    }
-                    Err(e) => {
-                        tracing::warn!("Failed to read poster bytes: {}", e);
-                        None
-                    }
-                }
-            } else {
-                tracing::warn!("Failed to download poster: HTTP {}", response.status());
-                None
-            }
-        }
+use crate::backup::{AVATAR_FILE, BackupManifest, MANIFEST_FILE, MOVIES_FILE};
+use crate::jobs::Job;
+use crate::routes::ws::WsEvent;
+use crate::{ApiError, AppState, downloads, poster_transcode, spine_index};
+
+/// TMDB image sizes and languages for a user's refreshes
+pub(crate) fn tmdb_options(state: &AppState, user: &UserPublic) -> TmdbOptions {
+    state
+        .tmdb_service
+        .options(Some(&user.tmdb), user.language.as_deref())
+}
+
+/// Download poster image from TMDB URL in the given size (see `TmdbOptions`)
+pub(crate) async fn download_poster_image(poster_path: &str, size: &str) -> Option<Vec<u8>> {
+    download_image_url(&TmdbService::poster_url(poster_path, size)).await
+}
+
+/// Download a TMDB backdrop in the given size
+async fn download_backdrop_image(backdrop_path: &str, size: &str) -> Option<Vec<u8>> {
+    download_image_url(&TmdbService::poster_url(backdrop_path, size)).await
+}
+
+/// Download a poster from a full URL (e.g. OMDb posters)
+async fn download_image_url(image_url: &str) -> Option<Vec<u8>> {
+    match fetch_image(image_url).await {
+        Ok(data) => Some(data),
         Err(e) => {
             tracing::warn!("Failed to download poster from {}: {}", image_url, e);
             None
@@ -53,11 +58,296 @@ async fn download_poster_image(poster_path: &str) -> Option<Vec<u8>> {
     }
 }
 
+/// Download an image, with the reason if that fails
+pub(crate) async fn fetch_image(image_url: &str) -> Result<Vec<u8>, String> {
+    let response = reqwest::get(image_url).await.map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("HTTP {}", response.status()));
+    }
+    let data = response
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to read poster bytes: {}", e))?
+        .to_vec();
+    // Validate it's actually an image (basic check)
+    if data.len() < 8 {
+        return Err(format!("Downloaded poster too small: {} bytes", data.len()));
+    }
+    Ok(data)
+}
+
+/// URL of a poster source: a TMDB image path (in the given size) or already
+/// a full URL
+pub(crate) fn poster_source_url(source: &str, size: &str) -> String {
+    if source.starts_with("http://") || source.starts_with("https://") {
+        source.to_string()
+    } else {
+        TmdbService::poster_url(source, size)
+    }
+}
+
+/// Download a movie's poster from a TMDB path or URL. A failed download is
+/// queued and retried in the background (see `poster_retries`).
+async fn download_movie_poster(
+    state: &AppState,
+    user_id: Uuid,
+    movie_id: Uuid,
+    source: &str,
+    size: &str,
+) -> Option<Vec<u8>> {
+    match fetch_image(&poster_source_url(source, size)).await {
+        Ok(data) => Some(data),
+        Err(e) => {
+            tracing::warn!("Failed to download poster {}: {}", source, e);
+            if let Err(e) = state
+                .poster_retry_service
+                .record_failure(user_id, movie_id, source, &e)
+                .await
+            {
+                tracing::error!("Failed to queue poster retry: {}", e);
+            }
+            None
+        }
+    }
+}
+
+/// List movies as summaries. With `updated_since` (or an `If-Modified-Since`
+/// header) only full movies changed after that time are returned, plus the ids
+/// of deleted movies.
 pub async fn list(
     State(state): State<Arc<AppState>>,
     Extension(claims): Extension<Claims>,
-    Query(filter): Query<MovieFilter>,
+    Query(mut filter): Query<MovieFilter>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    // Taken before querying, so changes made meanwhile show up in the next sync
+    let server_time = chrono::Utc::now();
+
+    let if_modified_since = headers
+        .get(header::IF_MODIFIED_SINCE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| chrono::DateTime::parse_from_rfc2822(v).ok())
+        .map(|t| t.with_timezone(&chrono::Utc));
+    if filter.updated_since.is_none() {
+        filter.updated_since = if_modified_since;
+    }
+    let updated_since = filter.updated_since;
+    // Sold or given away movies only show up in history (`disposed=true`);
+    // delta syncs include them so clients notice the change. An explicit
+    // availability (e.g. `sold`, `ordered`) already says what to list.
+    let defaults = updated_since.is_none() && filter.availability.is_none();
+    if filter.disposed.is_none() && defaults {
+        filter.disposed = Some(false);
+    }
+    // Wishlist and ordered movies have their own list (`/wishlist`, `status=ordered`)
+    if filter.status.is_none() && defaults {
+        filter.status = Some(MovieStatus::Owned);
+    }
+
+    // `?fields=id,title` returns slim items with just these fields
+    let fields = filter
+        .fields
+        .as_deref()
+        .map(parse_movie_fields)
+        .transpose()
+        .map_err(ApiError::bad_request)?;
+
+    let total = state.movie_service.count(claims.id, &filter).await?;
+    let limit = filter.limit;
+    let offset = filter.offset.unwrap_or(0);
+    let (movies, empty) = match fields {
+        Some(fields) => {
+            let movies = state
+                .movie_service
+                .list_fields(claims.id, filter, &fields)
+                .await?;
+            (json!(movies), movies.is_empty())
+        }
+        // Delta syncs keep full movies so clients can update their copies
+        None if updated_since.is_some() || filter.full == Some(true) => {
+            let movies = state.movie_service.list(claims.id, filter).await?;
+            (json!(movies), movies.is_empty())
+        }
+        None => {
+            let movies = state
+                .movie_service
+                .list_summaries(claims.id, filter)
+                .await?;
+            (json!(movies), movies.is_empty())
+        }
+    };
+
+    let Some(since) = updated_since else {
+        return Ok((
+            StatusCode::OK,
+            Json(json!({
+                "items": movies,
+                "total": total,
+                "limit": limit.unwrap_or(total),
+                "offset": offset
+            })),
+        )
+            .into_response());
+    };
+
+    let deleted: Vec<Uuid> = state
+        .tombstone_service
+        .since(claims.id, since, Some(EntityType::Movie))
+        .await?
+        .into_iter()
+        .map(|t| t.entity_id)
+        .collect();
+
+    if if_modified_since.is_some() && empty && deleted.is_empty() {
+        return Ok(StatusCode::NOT_MODIFIED.into_response());
+    }
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "items": movies,
+            "deleted": deleted,
+            "total": total,
+            "limit": limit.unwrap_or(total),
+            "offset": offset,
+            "server_time": server_time.to_rfc3339()
+        })),
+    )
+        .into_response())
+}
+
+/// Maximum number of suggestions returned by `/movies/suggest`
+const MAX_SUGGESTIONS: i64 = 10;
+
+#[derive(Debug, Deserialize)]
+pub struct SuggestQuery {
+    pub q: String,
+}
+
+/// Title suggestions for search-as-you-type
+pub async fn suggest(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<SuggestQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let mut suggestions = state
+        .movie_service
+        .suggest(claims.id, &query.q, MAX_SUGGESTIONS)
+        .await?;
+
+    for suggestion in &mut suggestions {
+        if suggestion.has_poster {
+            suggestion.thumb = Some(format!("/api/v1/movies/{}/thumbnail", suggestion.id));
+        }
+    }
+
+    Ok((StatusCode::OK, Json(suggestions)))
+}
+
+/// Record that the user opened a movie's detail view
+pub async fn record_view(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    state.movie_service.record_view(claims.id, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RecentViewsQuery {
+    pub limit: Option<i64>,
+    /// Sort by number of views instead of recency
+    #[serde(default)]
+    pub most_viewed: bool,
+}
+
+/// The user's recently viewed movies
+pub async fn recent_views(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<RecentViewsQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let views = state
+        .movie_service
+        .recent_views(
+            claims.id,
+            query.limit.unwrap_or(20).clamp(1, 100),
+            query.most_viewed,
+        )
+        .await?;
+    Ok((StatusCode::OK, Json(views)))
+}
+
+/// Discs that won't play on the user's players (see `/auth/player-regions`)
+pub async fn region_report(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<impl IntoResponse, ApiError> {
+    let user = state.auth_service.get_user(claims.id).await?;
+    let Some(player_regions) = user.player_regions else {
+        return Err(ApiError::bad_request(
+            "No player regions configured, set them via /auth/player-regions",
+        ));
+    };
+    let player_regions = parse_region_codes(&player_regions).map_err(ApiError::bad_request)?;
+
+    let report = state
+        .movie_service
+        .region_report(claims.id, &player_regions)
+        .await?;
+    Ok((StatusCode::OK, Json(report)))
+}
+
+/// Movies without TMDB match, poster or description, and poster downloads
+/// that failed too often to be retried
+pub async fn missing_metadata(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<impl IntoResponse, ApiError> {
+    let mut report = state.movie_service.missing_metadata(claims.id).await?;
+    report.failing_posters = state.poster_retry_service.given_up(claims.id).await?;
+    Ok(Json(report))
+}
+
+/// Mark a movie as sold, given away or traded
+pub async fn dispose(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+    Json(mut input): Json<DisposeMovie>,
+) -> Result<impl IntoResponse, ApiError> {
+    if input.date.is_none() {
+        input.date = Some(state.auth_service.get_user(claims.id).await?.today());
+    }
+    let movie = state.movie_service.dispose(claims.id, id, input).await?;
+
+    let msg = json!({ "type": "movie_updated", "payload": movie });
+    let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
+
+    Ok((StatusCode::OK, Json(movie)))
+}
+
+/// Movie counts per availability (available, lent, missing, sold, ordered),
+/// on the wishlist and watched this year
+pub async fn stats(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
 ) -> Result<impl IntoResponse, ApiError> {
+    let year = state.auth_service.get_user(claims.id).await?.today().year();
+    let stats = state.movie_service.stats(claims.id, year).await?;
+    Ok((StatusCode::OK, Json(stats)))
+}
+
+/// Movies on the wishlist; takes the same filters as the movie list
+pub async fn wishlist(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Query(mut filter): Query<MovieFilter>,
+) -> Result<impl IntoResponse, ApiError> {
+    filter.status = Some(MovieStatus::Wishlist);
+    filter.disposed = Some(false);
+
     let total = state.movie_service.count(claims.id, &filter).await?;
     let limit = filter.limit;
     let offset = filter.offset.unwrap_or(0);
@@ -74,6 +364,286 @@ pub async fn list(
     ))
 }
 
+/// Move a wishlist or ordered movie into the collection, optionally
+/// refreshing its TMDB data
+pub async fn move_to_collection(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+    Json(mut input): Json<AcquireMovie>,
+) -> Result<impl IntoResponse, ApiError> {
+    if input.purchase_date.is_none() {
+        input.purchase_date = Some(state.auth_service.get_user(claims.id).await?.today());
+    }
+    let refresh = input.refresh_tmdb;
+    let mut movie = state
+        .movie_service
+        .move_to_collection(claims.id, id, input)
+        .await?;
+
+    if refresh {
+        let user = state.auth_service.get_user(claims.id).await?;
+        match refresh_movie_tmdb_internal(
+            &state,
+            claims.id,
+            &movie,
+            &tmdb_options(&state, &user),
+            user.include_adult,
+            true,
+        )
+        .await
+        {
+            TmdbRefreshResult::Success(refreshed) => movie = *refreshed,
+            // The move itself succeeded, keep the old data
+            TmdbRefreshResult::NotFound(msg) | TmdbRefreshResult::Error(msg) => {
+                tracing::warn!("TMDB refresh after moving movie {} failed: {}", id, msg);
+            }
+        }
+    }
+
+    let msg = json!({ "type": "movie_updated", "payload": movie });
+    let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
+
+    Ok((StatusCode::OK, Json(movie)))
+}
+
+/// Put a sold or given away movie back into the collection
+pub async fn restore_disposed(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    let movie = state.movie_service.restore_disposed(claims.id, id).await?;
+
+    let msg = json!({ "type": "movie_updated", "payload": movie });
+    let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
+
+    Ok((StatusCode::OK, Json(movie)))
+}
+
+/// Condition grade and included extras of a disc
+pub async fn get_condition(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    let condition = state.movie_service.get_condition(claims.id, id).await?;
+    Ok((StatusCode::OK, Json(condition)))
+}
+
+/// Replace condition grade and included extras
+pub async fn set_condition(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+    Json(input): Json<DiscCondition>,
+) -> Result<impl IntoResponse, ApiError> {
+    let movie = state
+        .movie_service
+        .set_condition(claims.id, id, input)
+        .await?;
+
+    let msg = json!({ "type": "movie_updated", "payload": movie });
+    let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
+
+    Ok((StatusCode::OK, Json(movie)))
+}
+
+/// Text for a marketplace listing, in the user's language
+pub async fn listing_text(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    let movie = state.movie_service.get_by_id(claims.id, id).await?;
+    let user = state.auth_service.get_user(claims.id).await?;
+    let german = user.language.as_deref().is_none_or(|l| l.starts_with("de"));
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({ "text": my_movies_core::services::listing_text(&movie, german) })),
+    ))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SpineIndexQuery {
+    /// `html` (default) or `pdf`
+    pub format: Option<String>,
+    /// Only discs whose location code starts with this, e.g. `A` for shelf A
+    pub location: Option<String>,
+    pub tag: Option<String>,
+}
+
+/// Printable index of the owned discs with their location codes, sorted by
+/// sort title and grouped by letter
+pub async fn spine_index(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<SpineIndexQuery>,
+) -> Result<Response, ApiError> {
+    let pdf = match query.format.as_deref() {
+        None | Some("html") => false,
+        Some("pdf") => true,
+        Some(other) => {
+            return Err(ApiError::bad_request(format!(
+                "Unknown format '{}', expected html or pdf",
+                other
+            )));
+        }
+    };
+
+    let filter = MovieFilter {
+        disposed: Some(false),
+        status: Some(MovieStatus::Owned),
+        exclude_collection_children: Some(true),
+        tag: query.tag,
+        ..Default::default()
+    };
+    let mut movies = state.movie_service.list(claims.id, filter).await?;
+    if let Some(prefix) = query.location.as_deref().map(str::trim)
+        && !prefix.is_empty()
+    {
+        let prefix = prefix.to_lowercase();
+        movies.retain(|m| {
+            m.location
+                .as_deref()
+                .is_some_and(|l| l.trim().to_lowercase().starts_with(&prefix))
+        });
+    }
+
+    let user = state.auth_service.get_user(claims.id).await?;
+    let labels =
+        spine_index::Labels::new(user.language.as_deref().is_none_or(|l| l.starts_with("de")));
+    let entries = spine_index::entries(movies);
+
+    let response = if pdf {
+        Response::builder()
+            .header(header::CONTENT_TYPE, "application/pdf")
+            .header(
+                header::CONTENT_DISPOSITION,
+                "inline; filename=\"spine-index.pdf\"",
+            )
+            .body(Body::from(spine_index::render_pdf(
+                &entries,
+                &labels,
+                user.today(),
+            )))
+    } else {
+        Response::builder()
+            .header(header::CONTENT_TYPE, "text/html; charset=utf-8")
+            .body(Body::from(spine_index::render_html(
+                &entries,
+                &labels,
+                user.today(),
+            )))
+    };
+    response.map_err(|e| ApiError::internal(format!("Failed to build response: {}", e)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ValueReportQuery {
+    /// Convert totals into this currency instead of the preferred one
+    pub currency: Option<String>,
+}
+
+/// Purchase prices, values and sale proceeds per currency, plus totals
+/// converted into the requested or preferred currency
+pub async fn value_report(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<ValueReportQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let mut report = state.movie_service.value_report(claims.id).await?;
+    let user = state.auth_service.get_user(claims.id).await?;
+    report.as_of = Some(user.today());
+
+    let currency = query.currency.or(user.preferred_currency);
+    if let Some(currency) = currency {
+        let currency = normalize_currency(&currency).map_err(ApiError::bad_request)?;
+        let rates = state.currency_service.rates().await?;
+        report.converted = Some(rates.convert_report(&report, &currency));
+    }
+
+    Ok((StatusCode::OK, Json(report)))
+}
+
+/// Toggle the favorite flag of a movie
+pub async fn toggle_favorite(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    let movie = state.movie_service.toggle_favorite(claims.id, id).await?;
+
+    let msg = json!({ "type": "movie_updated", "payload": movie });
+    let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
+
+    Ok((StatusCode::OK, Json(json!(movie))))
+}
+
+/// Log a viewing of a movie; the date defaults to today in the user's timezone
+pub async fn log_watch(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+    Json(mut input): Json<LogWatch>,
+) -> Result<impl IntoResponse, ApiError> {
+    if input.date.is_none() {
+        input.date = Some(state.auth_service.get_user(claims.id).await?.today());
+    }
+    let event = state.watch_service.log(claims.id, id, input).await?;
+
+    let movie = state.movie_service.get_by_id(claims.id, id).await?;
+    let msg = json!({ "type": "movie_updated", "payload": movie });
+    let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
+
+    Ok((StatusCode::CREATED, Json(event)))
+}
+
+/// Watch history of a movie, most recent first
+pub async fn list_watches(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    let events = state.watch_service.list(claims.id, id).await?;
+    Ok((StatusCode::OK, Json(events)))
+}
+
+pub async fn delete_watch(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path((id, watch_id)): Path<(Uuid, i64)>,
+) -> Result<impl IntoResponse, ApiError> {
+    state.watch_service.delete(claims.id, id, watch_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Movies whose parent collection no longer exists
+pub async fn find_orphans(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<impl IntoResponse, ApiError> {
+    let groups = state.movie_service.find_orphans(claims.id).await?;
+    Ok((StatusCode::OK, Json(groups)))
+}
+
+/// Clear dangling parent links or regroup orphans into new collections
+pub async fn fix_orphans(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(input): Json<FixOrphans>,
+) -> Result<impl IntoResponse, ApiError> {
+    let result = state.movie_service.fix_orphans(claims.id, input).await?;
+
+    for collection in &result.collections_created {
+        let msg = json!({ "type": "movie_added", "payload": collection });
+        let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
+    }
+
+    Ok((StatusCode::OK, Json(result)))
+}
+
 pub async fn get(
     State(state): State<Arc<AppState>>,
     Extension(claims): Extension<Claims>,
@@ -91,11 +661,47 @@ pub async fn create(
     let movie = state.movie_service.create(claims.id, input).await?;
 
     let msg = json!({ "type": "movie_added", "payload": movie });
-    let _ = state.ws_broadcast.send(msg.to_string());
+    let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
+
+    if state.discord_service.is_enabled(DiscordEvent::MovieAdded) {
+        let state = state.clone();
+        let movie = movie.clone();
+        tokio::spawn(async move {
+            notify_discord_movie_added(&state, &movie).await;
+        });
+    }
 
     Ok((StatusCode::CREATED, Json(json!(movie))))
 }
 
+/// Thumbnail for a Discord notification: the TMDB poster when the movie has no
+/// stored poster yet (a stored poster is attached instead)
+pub(crate) async fn discord_poster_url(state: &AppState, movie: &Movie) -> Option<String> {
+    match movie.tmdb_id {
+        Some(tmdb_id) if movie.poster_data.is_none() => state
+            .tmdb_service
+            .get_movie_details(tmdb_id, None)
+            .await
+            .ok()
+            .and_then(|details| details.poster_path)
+            .map(|path| TmdbService::poster_url(&path, "w185")),
+        _ => None,
+    }
+}
+
+/// Post a "movie added" notification to Discord
+async fn notify_discord_movie_added(state: &AppState, movie: &Movie) {
+    let poster_url = discord_poster_url(state, movie).await;
+
+    if let Err(e) = state
+        .discord_service
+        .notify_movie_added(movie, poster_url)
+        .await
+    {
+        tracing::warn!("Failed to send Discord notification: {}", e);
+    }
+}
+
 pub async fn update(
     State(state): State<Arc<AppState>>,
     Extension(claims): Extension<Claims>,
@@ -105,11 +711,27 @@ pub async fn update(
     let movie = state.movie_service.update(claims.id, id, input).await?;
 
     let msg = json!({ "type": "movie_updated", "payload": movie });
-    let _ = state.ws_broadcast.send(msg.to_string());
+    let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
 
     Ok((StatusCode::OK, Json(json!(movie))))
 }
 
+/// Apply the same fields to many movies at once
+pub async fn bulk_update(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(input): Json<BulkUpdateMovies>,
+) -> Result<impl IntoResponse, ApiError> {
+    let result = state.movie_service.bulk_update(claims.id, input).await?;
+
+    if !result.updated.is_empty() {
+        let msg = json!({ "type": "movies_updated", "payload": { "ids": result.updated } });
+        let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
+    }
+
+    Ok((StatusCode::OK, Json(result)))
+}
+
 pub async fn delete(
     State(state): State<Arc<AppState>>,
     Extension(claims): Extension<Claims>,
@@ -118,7 +740,7 @@ pub async fn delete(
     state.movie_service.delete(claims.id, id).await?;
 
     let msg = json!({ "type": "movie_deleted", "payload": { "id": id } });
-    let _ = state.ws_broadcast.send(msg.to_string());
+    let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
 
     Ok(StatusCode::NO_CONTENT)
 }
@@ -131,7 +753,7 @@ pub async fn delete_all(
     let count = state.movie_service.delete_all(claims.id).await?;
 
     let msg = json!({ "type": "all_movies_deleted", "payload": { "count": count } });
-    let _ = state.ws_broadcast.send(msg.to_string());
+    let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
 
     Ok((
         StatusCode::OK,
@@ -156,22 +778,207 @@ pub struct ExportMovie {
     pub director: Option<String>,
     pub actors: Option<String>,
     pub watched: bool,
+    #[serde(default)]
+    pub favorite: bool,
     pub location: Option<String>,
     pub rating: Option<String>,
     pub personal_rating: Option<f64>,
     pub notes: Option<String>,
+    #[serde(default)]
+    pub tags: Option<String>,
+    /// Loan: who has the disc and when it is due back
+    #[serde(default)]
+    pub lent_to: Option<String>,
+    #[serde(default)]
+    pub lent_due: Option<chrono::NaiveDate>,
+    /// Set when the movie was sold, given away or traded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disposal: Option<DisposeMovie>,
     pub is_collection: bool,
     pub parent_collection_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disc_number: Option<i32>,
     pub created_at: String,
     pub updated_at: String,
 }
 
+impl ExportMovie {
+    pub fn to_create(&self) -> CreateMovie {
+        CreateMovie {
+            barcode: self.barcode.clone(),
+            tmdb_id: self.tmdb_id,
+            title: self.title.clone(),
+            original_title: self.original_title.clone(),
+            production_year: self.production_year,
+            disc_type: self.disc_type.clone(),
+        }
+    }
+
+    /// Fields restored after creating the movie (everything `CreateMovie` doesn't
+    /// cover except the collection link and poster)
+    pub fn to_update(&self) -> UpdateMovie {
+        UpdateMovie {
+            sort_title: self.sort_title.clone(),
+            description: self.description.clone(),
+            running_time: self.running_time,
+            genres: self.genres.clone(),
+            director: self.director.clone(),
+            actors: self.actors.clone(),
+            watched: Some(self.watched),
+            favorite: Some(self.favorite),
+            location: self.location.clone(),
+            rating: self.rating.clone(),
+            personal_rating: self.personal_rating,
+            notes: self.notes.clone(),
+            tags: self.tags.clone(),
+            lent_to: self.lent_to.clone(),
+            lent_due: self.lent_due,
+            is_collection: Some(self.is_collection),
+            disc_number: self.disc_number,
+            ..Default::default()
+        }
+    }
+}
+
+/// Current backup schema version.
+/// 1: movies only; 2: adds tags, loans and `locations`; 3: adds dispositions;
+/// 4: adds `account`
+pub const BACKUP_SCHEMA_VERSION: u32 = 4;
+
+fn legacy_schema_version() -> u32 {
+    1
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct ExportData {
+    pub version: String,
+    /// Backups without this field are schema version 1
+    #[serde(default = "legacy_schema_version")]
+    pub schema_version: u32,
+    pub exported_at: String,
+    pub total_movies: usize,
+    pub movies: Vec<ExportMovie>,
+    /// Storage locations in use (informational, locations are stored per movie)
+    #[serde(default)]
+    pub locations: Vec<String>,
+    /// The exporting user's account; only in backups made with a regular login
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<ExportAccount>,
+    /// Sections written by newer versions; kept so they can be reported on restore
+    #[serde(flatten, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub unknown_sections: std::collections::BTreeMap<String, serde_json::Value>,
+}
+
+impl ExportData {
+    /// Warnings for backups written by a newer version. Restoring still works,
+    /// but data this version doesn't know about is skipped.
+    pub fn compatibility_warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if self.schema_version > BACKUP_SCHEMA_VERSION {
+            warnings.push(format!(
+                "Backup schema version {} is newer than supported version {}",
+                self.schema_version, BACKUP_SCHEMA_VERSION
+            ));
+        }
+        for section in self.unknown_sections.keys() {
+            warnings.push(format!("Skipped unknown backup section '{}'", section));
+        }
+        warnings
+    }
+}
+
+/// Preferences, API keys and avatar of the user who made a backup, so an
+/// account can move to another instance without losing its setup
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
-pub struct ExportData {
-    pub version: String,
-    pub exported_at: String,
-    pub total_movies: usize,
-    pub movies: Vec<ExportMovie>,
+pub struct ExportAccount {
+    /// Informational; restoring applies to the importing user
+    pub username: String,
+    #[serde(default)]
+    pub preferences: UserPreferences,
+    #[serde(default)]
+    pub api_keys: Vec<ExportedApiKey>,
+    /// Whether `avatar.jpg` is in the archive
+    #[serde(default)]
+    pub has_avatar: bool,
+}
+
+/// The account section for a backup; `None` for API keys, which must not
+/// read other keys
+async fn export_account(
+    state: &AppState,
+    claims: &Claims,
+) -> Result<Option<(ExportAccount, Option<Vec<u8>>)>, ApiError> {
+    if claims.scopes.is_some() {
+        return Ok(None);
+    }
+    let avatar = state.auth_service.get_user_avatar_data(claims.id).await?;
+    let account = ExportAccount {
+        username: claims.username.clone(),
+        preferences: state.auth_service.get_preferences(claims.id).await?,
+        api_keys: state.api_key_service.export(claims.id).await?,
+        has_avatar: avatar.is_some(),
+    };
+    Ok(Some((account, avatar)))
+}
+
+/// Apply the account section of a backup to the importing user. Skipped for
+/// API keys, so a key can't create others. Returns whether it was applied.
+async fn restore_account(
+    state: &AppState,
+    claims: &Claims,
+    account: ExportAccount,
+    avatar: Option<Vec<u8>>,
+    errors: &mut Vec<String>,
+) -> bool {
+    if claims.scopes.is_some() {
+        return false;
+    }
+    if let Err(e) = state
+        .auth_service
+        .restore_preferences(claims.id, account.preferences)
+        .await
+    {
+        errors.push(format!("Error restoring preferences: {}", e));
+    }
+    if let Err(e) = state
+        .api_key_service
+        .restore(claims.id, &claims.role, &account.api_keys)
+        .await
+    {
+        errors.push(format!("Error restoring API keys: {}", e));
+    }
+    if let Some(avatar) = avatar
+        && let Err(e) = state
+            .auth_service
+            .update_user_avatar_data(claims.id, Some(avatar))
+            .await
+    {
+        errors.push(format!("Error restoring avatar: {}", e));
+    }
+    true
+}
+
+/// Re-apply the disposition of a restored movie
+pub(crate) async fn restore_disposal(
+    state: &Arc<AppState>,
+    user_id: Uuid,
+    movie_id: Uuid,
+    export_movie: &ExportMovie,
+    errors: &mut Vec<String>,
+) {
+    let Some(disposal) = export_movie.disposal.clone() else {
+        return;
+    };
+    if let Err(e) = state
+        .movie_service
+        .dispose(user_id, movie_id, disposal)
+        .await
+    {
+        errors.push(format!(
+            "Error restoring disposition of '{}': {}",
+            export_movie.title, e
+        ));
+    }
 }
 
 /// Import result
@@ -179,37 +986,134 @@ pub struct ExportData {
 pub struct JsonImportResult {
     pub imported: usize,
     pub skipped: usize,
+    /// Whether preferences and API keys from the backup were applied
+    pub account_restored: bool,
     pub errors: Vec<String>,
+    pub warnings: Vec<String>,
 }
 
-/// Export all movies as ZIP with JSON metadata and poster images
+/// Export movies as ZIP with JSON metadata and poster images. All movies by
+/// default; the list filters (e.g. `collection_id`, `tag`, `genre`) export a
+/// subset with only its posters.
+///
+/// The ZIP is written on a blocking thread and streamed while it is built, so
+/// posters are loaded one at a time and the download starts right away. A
+/// copy goes to a temporary file; `HEAD` and range requests (resuming) are
+/// answered from that file, identified by the `ETag`, or from a new one.
 pub async fn export(
     State(state): State<Arc<AppState>>,
     Extension(claims): Extension<Claims>,
-) -> Result<impl IntoResponse, ApiError> {
-    use std::io::{Cursor, Write};
-    use zip::ZipWriter;
-    use zip::write::SimpleFileOptions;
+    method: Method,
+    headers: HeaderMap,
+    RawQuery(query): RawQuery,
+    Query(filter): Query<MovieFilter>,
+) -> Result<Response, ApiError> {
+    let key = format!("export:{}:{}", claims.id, query.unwrap_or_default());
+    let wants_file = method == Method::HEAD || headers.contains_key(header::RANGE);
 
-    let filter = MovieFilter {
-        exclude_collection_children: Some(false),
-        ..Default::default()
-    };
+    if wants_file && let Some(download) = state.downloads.get(&key) {
+        return downloads::serve(&download, &method, &headers, "application/zip").await;
+    }
+
+    let export = prepare_export(&state, &claims, filter).await?;
+    let download = downloads::Download::new(format!(
+        "my-movies-backup-{}.zip",
+        chrono::Utc::now().format("%Y%m%d-%H%M%S")
+    ));
+    let file = std::fs::File::create(&download.path)
+        .map_err(|e| ApiError::internal(format!("Failed to create export file: {}", e)))?;
+    let runtime = tokio::runtime::Handle::current();
+    let user_id = claims.id;
+
+    if wants_file {
+        let task_state = state.clone();
+        let written = tokio::task::spawn_blocking(move || {
+            let writer = std::io::BufWriter::with_capacity(EXPORT_CHUNK_SIZE, file);
+            let file = write_export_zip(&runtime, &task_state, user_id, export, writer)?
+                .into_inner()
+                .map_err(|e| e.into_error())?;
+            file.sync_all()?;
+            Ok::<_, zip::result::ZipError>(())
+        })
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|written| written.map_err(|e| e.to_string()));
+        if let Err(e) = written {
+            download.remove();
+            return Err(ApiError::internal(format!("Export failed: {}", e)));
+        }
+        state.downloads.insert(key, download.clone());
+        return downloads::serve(&download, &method, &headers, "application/zip").await;
+    }
+
+    let (tx, rx) = tokio::sync::mpsc::channel::<std::io::Result<Vec<u8>>>(EXPORT_CHANNEL_CHUNKS);
+    let response = Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/zip")
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", download.filename),
+        )
+        .header(header::ACCEPT_RANGES, "bytes")
+        .header(header::ETAG, download.etag.as_str());
+
+    tokio::task::spawn_blocking(move || {
+        let tee = TeeWriter {
+            file: Some(file),
+            client: Some(ChannelWriter { tx: tx.clone() }),
+        };
+        let writer = std::io::BufWriter::with_capacity(EXPORT_CHUNK_SIZE, tee);
+        let written = write_export_zip(&runtime, &state, user_id, export, writer)
+            .and_then(|writer| writer.into_inner().map_err(|e| e.into_error().into()));
+        match written {
+            // Complete copy: a resumed download continues from it
+            Ok(TeeWriter {
+                file: Some(file), ..
+            }) if file.sync_all().is_ok() => state.downloads.insert(key, download),
+            Ok(_) => download.remove(),
+            Err(e) => {
+                tracing::warn!("Export aborted: {}", e);
+                download.remove();
+                // Ends the download with an error instead of a truncated ZIP that looks complete
+                let _ = tx.blocking_send(Err(std::io::Error::other(e.to_string())));
+            }
+        }
+    });
+
+    let chunks = futures::stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|chunk| (chunk, rx))
+    });
+    response
+        .body(Body::from_stream(chunks))
+        .map_err(|e| ApiError::internal(format!("Failed to build response: {}", e)))
+}
+
+/// Movies, poster IDs and account section of an export
+async fn prepare_export(
+    state: &AppState,
+    claims: &Claims,
+    mut filter: MovieFilter,
+) -> Result<ExportContents, ApiError> {
+    if let Some(collection_id) = filter.collection_id {
+        // Ensures the collection exists and belongs to the user
+        state
+            .collection_service
+            .get_by_id(claims.id, collection_id)
+            .await?;
+    }
+    filter.exclude_collection_children = Some(false);
 
     let movies = state.movie_service.list(claims.id, filter).await?;
+    let account = export_account(state, claims).await?;
 
     // Get list of movie IDs that have poster data
     // (list() doesn't include poster_data for performance, so we need to check separately)
-    let poster_ids_result = state
+    let movies_with_poster: std::collections::HashSet<Uuid> = match state
         .movie_service
         .get_movie_ids_with_poster(claims.id)
-        .await;
-
-    let movies_with_poster: std::collections::HashSet<uuid::Uuid> = match &poster_ids_result {
-        Ok(ids) => {
-            tracing::info!("get_movie_ids_with_poster returned {} IDs", ids.len());
-            ids.clone().into_iter().collect()
-        }
+        .await
+    {
+        Ok(ids) => ids.into_iter().collect(),
         Err(e) => {
             tracing::error!("get_movie_ids_with_poster failed: {:?}", e);
             std::collections::HashSet::new()
@@ -217,141 +1121,245 @@ pub async fn export(
     };
 
     tracing::info!(
-        "Export: {} movies total, {} have posters (HashSet size: {})",
+        "Export: {} movies total, {} have posters",
         movies.len(),
-        poster_ids_result.as_ref().map(|v| v.len()).unwrap_or(0),
         movies_with_poster.len()
     );
 
-    // Create ZIP in memory
-    let mut zip_buffer = Cursor::new(Vec::new());
-    {
-        let mut zip = ZipWriter::new(&mut zip_buffer);
-        let options = SimpleFileOptions::default()
-            .compression_method(zip::CompressionMethod::Deflated)
-            .compression_level(Some(6));
+    Ok(ExportContents {
+        movies,
+        movies_with_poster,
+        account,
+    })
+}
+
+/// Bytes buffered before a chunk is handed to the response body
+const EXPORT_CHUNK_SIZE: usize = 64 * 1024;
+/// Chunks waiting for a slow client before the ZIP writer blocks
+const EXPORT_CHANNEL_CHUNKS: usize = 8;
+
+/// Writes into the channel feeding the export response. Fails once the
+/// client is gone.
+struct ChannelWriter {
+    tx: tokio::sync::mpsc::Sender<std::io::Result<Vec<u8>>>,
+}
 
-        let mut export_movies: Vec<ExportMovie> = Vec::new();
-        let mut posters_included = 0;
+impl std::io::Write for ChannelWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.tx.blocking_send(Ok(buf.to_vec())).map_err(|_| {
+            std::io::Error::new(
+                std::io::ErrorKind::BrokenPipe,
+                "Export download was aborted",
+            )
+        })?;
+        Ok(buf.len())
+    }
 
-        let mut checked_count = 0;
-        let mut fetch_errors = 0;
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
 
-        for movie in &movies {
-            let movie_id = movie.id.to_string();
-            let has_poster = movies_with_poster.contains(&movie.id);
+/// Writes the export to the client and to its temporary file. Whichever
+/// fails is dropped; the export continues while one is left, so an aborted
+/// download can still be resumed from the finished file.
+struct TeeWriter {
+    file: Option<std::fs::File>,
+    client: Option<ChannelWriter>,
+}
 
-            // Add poster to ZIP if exists - use get_poster_data() to fetch only poster bytes
-            if has_poster {
-                checked_count += 1;
-                match state
-                    .movie_service
-                    .get_poster_data(claims.id, movie.id)
-                    .await
-                {
-                    Ok(Some(poster_data)) => {
-                        let poster_filename = format!("posters/{}.jpg", movie_id);
-                        match zip.start_file(&poster_filename, options) {
-                            Ok(_) => {
-                                if zip.write_all(&poster_data).is_ok() {
-                                    posters_included += 1;
-                                }
-                            }
-                            Err(e) => {
-                                tracing::warn!("Failed to start file in ZIP: {}", e);
-                            }
-                        }
-                    }
-                    Ok(None) => {
-                        // Poster was expected but not found
-                        fetch_errors += 1;
-                        if fetch_errors <= 5 {
-                            tracing::warn!("Poster not found for movie {}", movie_id);
-                        }
+impl std::io::Write for TeeWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if let Some(file) = self.file.as_mut()
+            && let Err(e) = file.write_all(buf)
+        {
+            tracing::warn!("Failed to write export file: {}", e);
+            self.file = None;
+        }
+        if let Some(client) = self.client.as_mut()
+            && client.write_all(buf).is_err()
+        {
+            self.client = None;
+        }
+        if self.file.is_none() && self.client.is_none() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::BrokenPipe,
+                "Export download was aborted",
+            ));
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        if let Some(file) = self.file.as_mut() {
+            file.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// What goes into an export besides the posters loaded while writing
+struct ExportContents {
+    movies: Vec<Movie>,
+    movies_with_poster: std::collections::HashSet<Uuid>,
+    account: Option<(ExportAccount, Option<Vec<u8>>)>,
+}
+
+/// Write the backup ZIP to `writer`: posters first, each loaded right before
+/// it is written, then the avatar, movies.json and the manifest. Runs on a
+/// blocking thread; `runtime` is used to load the posters.
+fn write_export_zip<W: std::io::Write>(
+    runtime: &tokio::runtime::Handle,
+    state: &AppState,
+    user_id: Uuid,
+    export: ExportContents,
+    writer: W,
+) -> zip::result::ZipResult<W> {
+    use std::io::Write;
+    use zip::ZipWriter;
+    use zip::write::SimpleFileOptions;
+
+    let ExportContents {
+        movies,
+        movies_with_poster,
+        account,
+    } = export;
+
+    let mut zip = ZipWriter::new_stream(writer);
+    let options = SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .compression_level(Some(6));
+
+    let mut export_movies: Vec<ExportMovie> = Vec::with_capacity(movies.len());
+    let mut posters_included = 0;
+    let mut manifest = BackupManifest::new();
+
+    let mut checked_count = 0;
+    let mut fetch_errors = 0;
+
+    for movie in &movies {
+        let movie_id = movie.id.to_string();
+
+        // Only this poster is in memory while it is written
+        if movies_with_poster.contains(&movie.id) {
+            checked_count += 1;
+            match runtime.block_on(state.movie_service.get_poster_data(user_id, movie.id)) {
+                Ok(Some(poster_data)) => {
+                    let poster_filename = format!("posters/{}.jpg", movie_id);
+                    zip.start_file(&poster_filename, options)?;
+                    zip.write_all(&poster_data)?;
+                    manifest.add_file(&poster_filename, &poster_data);
+                    posters_included += 1;
+                }
+                Ok(None) => {
+                    // Poster was expected but not found
+                    fetch_errors += 1;
+                    if fetch_errors <= 5 {
+                        tracing::warn!("Poster not found for movie {}", movie_id);
                     }
-                    Err(e) => {
-                        fetch_errors += 1;
-                        if fetch_errors <= 5 {
-                            tracing::warn!(
-                                "Failed to fetch poster for movie {}: {:?}",
-                                movie_id,
-                                e
-                            );
-                        }
+                }
+                Err(e) => {
+                    fetch_errors += 1;
+                    if fetch_errors <= 5 {
+                        tracing::warn!("Failed to fetch poster for movie {}: {:?}", movie_id, e);
                     }
                 }
             }
-
-            export_movies.push(ExportMovie {
-                id: movie_id.clone(),
-                barcode: movie.barcode.clone(),
-                tmdb_id: movie.tmdb_id,
-                title: movie.title.clone(),
-                original_title: movie.original_title.clone(),
-                sort_title: movie.sort_title.clone(),
-                description: movie.description.clone(),
-                production_year: movie.production_year,
-                disc_type: movie.disc_type.clone(),
-                running_time: movie.running_time,
-                genres: movie.genres.clone(),
-                director: movie.director.clone(),
-                actors: movie.actors.clone(),
-                watched: movie.watched,
-                location: movie.location.clone(),
-                rating: movie.rating.clone(),
-                personal_rating: movie.personal_rating,
-                notes: movie.notes.clone(),
-                is_collection: movie.is_collection,
-                parent_collection_id: movie.parent_collection_id.map(|id| id.to_string()),
-                created_at: movie.created_at.to_rfc3339(),
-                updated_at: movie.updated_at.to_rfc3339(),
-            });
         }
 
-        let export_data = ExportData {
-            version: "1.0".to_string(),
-            exported_at: chrono::Utc::now().to_rfc3339(),
-            total_movies: export_movies.len(),
-            movies: export_movies,
-        };
+        export_movies.push(ExportMovie {
+            id: movie_id.clone(),
+            barcode: movie.barcode.clone(),
+            tmdb_id: movie.tmdb_id,
+            title: movie.title.clone(),
+            original_title: movie.original_title.clone(),
+            sort_title: movie.sort_title.clone(),
+            description: movie.description.clone(),
+            production_year: movie.production_year,
+            disc_type: movie.disc_type.clone(),
+            running_time: movie.running_time,
+            genres: movie.genres.clone(),
+            director: movie.director.clone(),
+            actors: movie.actors.clone(),
+            watched: movie.watched,
+            favorite: movie.favorite,
+            location: movie.location.clone(),
+            rating: movie.rating.clone(),
+            personal_rating: movie.personal_rating,
+            notes: movie.notes.clone(),
+            tags: movie.tags.clone(),
+            lent_to: movie.lent_to.clone(),
+            lent_due: movie.lent_due,
+            disposal: movie.disposition.map(|disposition| DisposeMovie {
+                disposition,
+                date: movie.disposed_at,
+                price: movie.disposal_price,
+                currency: movie.disposal_currency.clone(),
+                recipient: movie.disposed_to.clone(),
+            }),
+            is_collection: movie.is_collection,
+            parent_collection_id: movie.parent_collection_id.map(|id| id.to_string()),
+            disc_number: movie.disc_number,
+            created_at: movie.created_at.to_rfc3339(),
+            updated_at: movie.updated_at.to_rfc3339(),
+        });
+    }
 
-        // Add movies.json to ZIP
-        let json_content = serde_json::to_string_pretty(&export_data).unwrap_or_default();
-        if zip.start_file("movies.json", options).is_ok() {
-            let _ = zip.write_all(json_content.as_bytes());
+    let mut locations: Vec<String> = export_movies
+        .iter()
+        .filter_map(|m| m.location.clone())
+        .filter(|l| !l.trim().is_empty())
+        .collect();
+    locations.sort();
+    locations.dedup();
+
+    let account = match account {
+        Some((account, avatar)) => {
+            if let Some(avatar) = avatar {
+                zip.start_file(AVATAR_FILE, options)?;
+                zip.write_all(&avatar)?;
+                manifest.add_file(AVATAR_FILE, &avatar);
+            }
+            Some(account)
         }
+        None => None,
+    };
 
-        tracing::info!(
-            "Export created: {} movies, checked {} with posters, {} fetch errors, {} posters written to ZIP",
-            export_data.total_movies,
-            checked_count,
-            fetch_errors,
-            posters_included
-        );
+    let export_data = ExportData {
+        version: "1.0".to_string(),
+        schema_version: BACKUP_SCHEMA_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        total_movies: export_movies.len(),
+        movies: export_movies,
+        locations,
+        account,
+        unknown_sections: Default::default(),
+    };
 
-        let _ = zip.finish();
-    }
+    // Add movies.json to ZIP
+    let json_content = serde_json::to_string_pretty(&export_data).unwrap_or_default();
+    zip.start_file(MOVIES_FILE, options)?;
+    zip.write_all(json_content.as_bytes())?;
+    manifest.add_file(MOVIES_FILE, json_content.as_bytes());
 
-    let zip_data = zip_buffer.into_inner();
-    let filename = format!(
-        "my-movies-backup-{}.zip",
-        chrono::Utc::now().format("%Y%m%d-%H%M%S")
-    );
+    // Manifest last, so it covers every other file
+    manifest.movie_count = export_data.total_movies;
+    manifest.poster_count = posters_included;
+    let manifest_content = serde_json::to_string_pretty(&manifest).unwrap_or_default();
+    zip.start_file(MANIFEST_FILE, options)?;
+    zip.write_all(manifest_content.as_bytes())?;
 
-    Ok((
-        StatusCode::OK,
-        [
-            (
-                axum::http::header::CONTENT_TYPE,
-                "application/zip".to_string(),
-            ),
-            (
-                axum::http::header::CONTENT_DISPOSITION,
-                format!("attachment; filename=\"{}\"", filename),
-            ),
-        ],
-        zip_data,
-    ))
+    let mut writer = zip.finish()?;
+    writer.flush()?;
+
+    tracing::info!(
+        "Export created: {} movies, checked {} with posters, {} fetch errors, {} posters written to ZIP",
+        export_data.total_movies,
+        checked_count,
+        fetch_errors,
+        posters_included
+    );
+    Ok(writer.into_inner())
 }
 
 /// Import movies from JSON export
@@ -365,14 +1373,23 @@ pub async fn import_json(
     let mut imported = 0;
     let mut skipped = 0;
     let mut errors: Vec<String> = Vec::new();
+    let warnings = import_data.compatibility_warnings();
+    for warning in &warnings {
+        tracing::warn!("JSON import: {}", warning);
+    }
 
     // Map old IDs to new IDs (for collection relationships)
     let mut id_map: HashMap<String, Uuid> = HashMap::new();
 
+    let account_restored = match import_data.account {
+        Some(account) => restore_account(&state, &claims, account, None, &mut errors).await,
+        None => false,
+    };
+
     // First pass: Import all movies (collections first to establish parent relationships)
     // Sort so collections come first
     let mut movies_to_import = import_data.movies;
-    movies_to_import.sort_by(|a, b| b.is_collection.cmp(&a.is_collection));
+    movies_to_import.sort_by_key(|m| !m.is_collection);
 
     for export_movie in movies_to_import {
         // Check if movie with same barcode or tmdb_id already exists
@@ -419,40 +1436,21 @@ pub async fn import_json(
             .and_then(|old_id| id_map.get(old_id).copied());
 
         // Create the movie
-        let create_movie = CreateMovie {
-            barcode: export_movie.barcode.clone(),
-            tmdb_id: export_movie.tmdb_id,
-            title: export_movie.title.clone(),
-            original_title: export_movie.original_title.clone(),
-            production_year: export_movie.production_year,
-            disc_type: export_movie.disc_type.clone(),
-        };
+        let create_movie = export_movie.to_create();
 
-        match state.movie_service.create(claims.id, create_movie).await {
+        match state
+            .movie_service
+            .create_without_defaults(claims.id, create_movie)
+            .await
+        {
             Ok(new_movie) => {
                 // Map old ID to new ID
                 id_map.insert(export_movie.id.clone(), new_movie.id);
 
                 // Update additional fields that aren't in CreateMovie
                 let update = UpdateMovie {
-                    title: None,
-                    original_title: None,
-                    sort_title: export_movie.sort_title.clone(),
-                    description: export_movie.description.clone(),
-                    production_year: None,
-                    disc_type: None,
-                    running_time: export_movie.running_time,
-                    genres: export_movie.genres.clone(),
-                    director: export_movie.director.clone(),
-                    actors: export_movie.actors.clone(),
-                    watched: Some(export_movie.watched),
-                    location: export_movie.location.clone(),
-                    rating: export_movie.rating.clone(),
-                    personal_rating: export_movie.personal_rating,
-                    notes: export_movie.notes.clone(),
-                    is_collection: Some(export_movie.is_collection),
                     parent_collection_id,
-                    ..Default::default()
+                    ..export_movie.to_update()
                 };
 
                 if let Err(e) = state
@@ -462,6 +1460,7 @@ pub async fn import_json(
                 {
                     errors.push(format!("Error updating '{}': {}", export_movie.title, e));
                 }
+                restore_disposal(&state, claims.id, new_movie.id, &export_movie, &mut errors).await;
 
                 imported += 1;
             }
@@ -476,14 +1475,16 @@ pub async fn import_json(
         "type": "collection_imported",
         "payload": { "count": imported }
     });
-    let _ = state.ws_broadcast.send(msg.to_string());
+    let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
 
     (
         StatusCode::OK,
         Json(JsonImportResult {
             imported,
             skipped,
+            account_restored,
             errors,
+            warnings,
         }),
     )
 }
@@ -563,10 +1564,16 @@ pub async fn import_zip(
 
             // Extract poster images from ZIP into a map
             let mut posters: HashMap<String, Vec<u8>> = HashMap::new();
+            let mut avatar: Option<Vec<u8>> = None;
             for i in 0..archive.len() {
                 if let Ok(mut file) = archive.by_index(i) {
                     let name = file.name().to_string();
-                    if name.starts_with("posters/") && name.ends_with(".jpg") {
+                    if name == AVATAR_FILE {
+                        let mut avatar_data = Vec::new();
+                        if file.read_to_end(&mut avatar_data).is_ok() {
+                            avatar = Some(avatar_data);
+                        }
+                    } else if name.starts_with("posters/") && name.ends_with(".jpg") {
                         // Extract movie ID from filename (posters/uuid.jpg)
                         if let Some(id_str) = name
                             .strip_prefix("posters/")
@@ -587,15 +1594,27 @@ pub async fn import_zip(
                 posters.len()
             );
 
+            let warnings = export_data.compatibility_warnings();
+            for warning in &warnings {
+                tracing::warn!("ZIP import: {}", warning);
+            }
+
             // Import movies
             let mut imported = 0;
             let mut skipped = 0;
             let mut errors: Vec<String> = Vec::new();
             let mut id_map: HashMap<String, Uuid> = HashMap::new();
 
+            let account_restored = match export_data.account {
+                Some(account) => {
+                    restore_account(&state, &claims, account, avatar, &mut errors).await
+                }
+                None => false,
+            };
+
             // Sort so collections come first
             let mut movies_to_import = export_data.movies;
-            movies_to_import.sort_by(|a, b| b.is_collection.cmp(&a.is_collection));
+            movies_to_import.sort_by_key(|m| !m.is_collection);
 
             for export_movie in movies_to_import {
                 // Check if movie with same barcode already exists
@@ -627,16 +1646,13 @@ pub async fn import_zip(
                     .and_then(|old_id| id_map.get(old_id).copied());
 
                 // Create the movie
-                let create_movie = CreateMovie {
-                    barcode: export_movie.barcode.clone(),
-                    tmdb_id: export_movie.tmdb_id,
-                    title: export_movie.title.clone(),
-                    original_title: export_movie.original_title.clone(),
-                    production_year: export_movie.production_year,
-                    disc_type: export_movie.disc_type.clone(),
-                };
+                let create_movie = export_movie.to_create();
 
-                match state.movie_service.create(claims.id, create_movie).await {
+                match state
+                    .movie_service
+                    .create_without_defaults(claims.id, create_movie)
+                    .await
+                {
                     Ok(new_movie) => {
                         id_map.insert(export_movie.id.clone(), new_movie.id);
 
@@ -645,25 +1661,9 @@ pub async fn import_zip(
 
                         // Update additional fields and poster
                         let update = UpdateMovie {
-                            title: None,
-                            original_title: None,
-                            sort_title: export_movie.sort_title.clone(),
-                            description: export_movie.description.clone(),
-                            production_year: None,
-                            disc_type: None,
-                            running_time: export_movie.running_time,
-                            genres: export_movie.genres.clone(),
-                            director: export_movie.director.clone(),
-                            actors: export_movie.actors.clone(),
-                            watched: Some(export_movie.watched),
-                            location: export_movie.location.clone(),
-                            rating: export_movie.rating.clone(),
-                            personal_rating: export_movie.personal_rating,
-                            notes: export_movie.notes.clone(),
-                            is_collection: Some(export_movie.is_collection),
                             parent_collection_id,
                             poster_data,
-                            ..Default::default()
+                            ..export_movie.to_update()
                         };
 
                         if let Err(e) = state
@@ -673,6 +1673,14 @@ pub async fn import_zip(
                         {
                             errors.push(format!("Error updating '{}': {}", export_movie.title, e));
                         }
+                        restore_disposal(
+                            &state,
+                            claims.id,
+                            new_movie.id,
+                            &export_movie,
+                            &mut errors,
+                        )
+                        .await;
 
                         imported += 1;
                     }
@@ -687,7 +1695,7 @@ pub async fn import_zip(
                 "type": "collection_imported",
                 "payload": { "count": imported }
             });
-            let _ = state.ws_broadcast.send(msg.to_string());
+            let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
 
             return (
                 StatusCode::OK,
@@ -695,7 +1703,9 @@ pub async fn import_zip(
                     "imported": imported,
                     "skipped": skipped,
                     "posters_restored": posters.len(),
-                    "errors": errors
+                    "account_restored": account_restored,
+                    "errors": errors,
+                    "warnings": warnings
                 })),
             )
                 .into_response();
@@ -728,13 +1738,15 @@ pub async fn refresh_movie_tmdb_internal(
     state: &Arc<AppState>,
     user_id: Uuid,
     movie: &Movie,
-    language: Option<&str>,
+    options: &TmdbOptions,
     include_adult: bool,
     force: bool,
 ) -> TmdbRefreshResult {
+    let language = Some(options.language());
+
     // Special handling for collections
     if movie.is_collection {
-        return match handle_collection_refresh_internal(state, user_id, movie.id, movie, language)
+        return match handle_collection_refresh_internal(state, user_id, movie.id, movie, options)
             .await
         {
             Ok(m) => TmdbRefreshResult::Success(Box::new(m)),
@@ -751,7 +1763,7 @@ pub async fn refresh_movie_tmdb_internal(
         // Use existing TMDB ID - try movie first
         tmdb_details = state
             .tmdb_service
-            .get_movie_details(tmdb_id, language)
+            .get_movie_details_localized(tmdb_id, options)
             .await
             .ok();
 
@@ -759,7 +1771,7 @@ pub async fn refresh_movie_tmdb_internal(
         if tmdb_details.is_none() {
             tv_details = state
                 .tmdb_service
-                .get_tv_details(tmdb_id, language)
+                .get_tv_details_localized(tmdb_id, options)
                 .await
                 .ok();
             if tv_details.is_some() {
@@ -772,19 +1784,17 @@ pub async fn refresh_movie_tmdb_internal(
 
         // Search by title - first as movie
         let year = movie.production_year;
-        match state
+        if let Ok(results) = state
             .tmdb_service
             .search_movies(&clean_search_title, year, language, include_adult)
             .await
+            && let Some(best) = best_match(&results, &clean_search_title, year)
         {
-            Ok(results) if !results.is_empty() => {
-                tmdb_details = state
-                    .tmdb_service
-                    .get_movie_details(results[0].id, language)
-                    .await
-                    .ok();
-            }
-            _ => {}
+            tmdb_details = state
+                .tmdb_service
+                .get_movie_details_localized(best.id, options)
+                .await
+                .ok();
         }
 
         // If no movie found, try as TV series
@@ -795,7 +1805,7 @@ pub async fn refresh_movie_tmdb_internal(
                 Ok(results) if !results.is_empty() => {
                     tv_details = state
                         .tmdb_service
-                        .get_tv_details(results[0].id, language)
+                        .get_tv_details_localized(results[0].id, options)
                         .await
                         .ok();
                     if tv_details.is_some() {
@@ -848,11 +1858,11 @@ pub async fn refresh_movie_tmdb_internal(
                         .tmdb_service
                         .search_movies(&search_title, year, language, include_adult)
                         .await
-                    && !results.is_empty()
+                    && let Some(best) = best_match(&results, &search_title, year)
                 {
                     tmdb_details = state
                         .tmdb_service
-                        .get_movie_details(results[0].id, language)
+                        .get_movie_details_localized(best.id, options)
                         .await
                         .ok();
                     if tmdb_details.is_some() {
@@ -868,7 +1878,7 @@ pub async fn refresh_movie_tmdb_internal(
                 {
                     tv_details = state
                         .tmdb_service
-                        .get_tv_details(results[0].id, language)
+                        .get_tv_details_localized(results[0].id, options)
                         .await
                         .ok();
                     if tv_details.is_some() {
@@ -880,9 +1890,9 @@ pub async fn refresh_movie_tmdb_internal(
         }
     }
 
-    // Check if we found anything
+    // Nothing on TMDB (or no TMDB key): try OMDb
     if tmdb_details.is_none() && tv_details.is_none() {
-        return TmdbRefreshResult::NotFound(format!("No TMDB data found: {}", movie.title));
+        return refresh_movie_omdb(state, user_id, movie, options, force).await;
     }
 
     // Build update based on whether we found a movie or TV series
@@ -1001,7 +2011,7 @@ pub async fn refresh_movie_tmdb_internal(
 
     let poster_data = if should_download_poster {
         if let Some(ref path) = poster_path {
-            download_poster_image(path).await
+            download_movie_poster(state, user_id, movie.id, path, &options.poster_size).await
         } else {
             None
         }
@@ -1009,6 +2019,18 @@ pub async fn refresh_movie_tmdb_internal(
         None
     };
 
+    let backdrop_path = match (&tmdb_details, &tv_details) {
+        (Some(details), _) => details.backdrop_path.clone(),
+        (None, Some(details)) => details.backdrop_path.clone(),
+        (None, None) => None,
+    };
+    let backdrop_data = match backdrop_path {
+        Some(ref path) if force || movie.backdrop_hash.is_none() => {
+            download_backdrop_image(path, &options.backdrop_size).await
+        }
+        _ => None,
+    };
+
     // Build update - only include fields that are missing or if force=true
     let mut update = my_movies_core::models::UpdateMovie {
         tmdb_id: Some(tmdb_id),
@@ -1048,6 +2070,21 @@ pub async fn refresh_movie_tmdb_internal(
         }
     }
 
+    // Ratings aren't on TMDB; OMDb has them by IMDb ID
+    if state.omdb_service.is_configured()
+        && (force || movie.imdb_rating.is_none())
+        && let Some(imdb_id) = update.imdb_id.as_deref().or(movie.imdb_id.as_deref())
+    {
+        match state.omdb_service.get_by_imdb_id(imdb_id).await {
+            Ok(Some(omdb)) => {
+                update.imdb_rating = omdb.imdb_score();
+                update.rotten_tomatoes = omdb.rotten_tomatoes();
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Failed to fetch OMDb ratings of {}: {}", imdb_id, e),
+        }
+    }
+
     // Update the movie data
     let updated_movie = match state.movie_service.update(user_id, movie.id, update).await {
         Ok(m) => m,
@@ -1056,43 +2093,200 @@ pub async fn refresh_movie_tmdb_internal(
         }
     };
 
-    // Update poster data if we downloaded it
-    let final_movie = if let Some(data) = poster_data {
-        match state
-            .movie_service
-            .update_movie_poster_data(user_id, movie.id, Some(data))
-            .await
-        {
-            Ok(m) => m,
-            Err(_) => updated_movie,
+    // Update poster data if we downloaded it
+    let final_movie = if let Some(data) = poster_data {
+        match state
+            .movie_service
+            .update_movie_poster_data(user_id, movie.id, Some(data))
+            .await
+        {
+            Ok(m) => m,
+            Err(_) => updated_movie,
+        }
+    } else {
+        updated_movie
+    };
+
+    let final_movie = if let Some(data) = backdrop_data {
+        match state
+            .movie_service
+            .update_movie_backdrop_data(user_id, movie.id, Some(data))
+            .await
+        {
+            Ok(m) => m,
+            Err(_) => final_movie,
+        }
+    } else {
+        final_movie
+    };
+
+    TmdbRefreshResult::Success(Box::new(final_movie))
+}
+
+/// Fallback for movies TMDB doesn't know (e.g. some German releases): look
+/// them up on OMDb by IMDb ID or title and fill in what's missing
+async fn refresh_movie_omdb(
+    state: &Arc<AppState>,
+    user_id: Uuid,
+    movie: &Movie,
+    options: &TmdbOptions,
+    force: bool,
+) -> TmdbRefreshResult {
+    let not_found = || TmdbRefreshResult::NotFound(format!("No TMDB data found: {}", movie.title));
+    if !state.omdb_service.is_configured() {
+        return not_found();
+    }
+
+    let found = match movie.imdb_id.as_deref() {
+        Some(imdb_id) => state.omdb_service.get_by_imdb_id(imdb_id).await,
+        None => {
+            let title = clean_title_for_search(&movie.title);
+            state
+                .omdb_service
+                .find_by_title(&title, movie.production_year)
+                .await
+        }
+    };
+    let omdb = match found {
+        Ok(Some(omdb)) => omdb,
+        Ok(None) => {
+            return TmdbRefreshResult::NotFound(format!(
+                "No TMDB or OMDb data found: {}",
+                movie.title
+            ));
+        }
+        Err(e) => {
+            tracing::warn!("OMDb lookup of '{}' failed: {}", movie.title, e);
+            return not_found();
+        }
+    };
+
+    let mut update = UpdateMovie {
+        imdb_rating: omdb.imdb_score(),
+        rotten_tomatoes: omdb.rotten_tomatoes(),
+        ..Default::default()
+    };
+    if force || movie.imdb_id.is_none() {
+        update.imdb_id = omdb.imdb_id();
+    }
+    if force || movie.production_year.is_none() {
+        update.production_year = omdb.production_year();
+    }
+    if force || movie.description.is_none() {
+        update.description = omdb.plot();
+    }
+    if force || movie.running_time.is_none() {
+        update.running_time = omdb.runtime_minutes();
+    }
+    if force || movie.director.is_none() {
+        update.director = omdb.director();
+    }
+    if force || movie.actors.is_none() {
+        update.actors = omdb.actors();
+    }
+    if force || movie.genres.is_none() {
+        update.genres = omdb.genre();
+    }
+
+    let updated_movie = match state.movie_service.update(user_id, movie.id, update).await {
+        Ok(m) => m,
+        Err(e) => {
+            return TmdbRefreshResult::Error(format!("Failed to update {}: {}", movie.title, e));
+        }
+    };
+
+    if (force || movie.poster_data.is_none())
+        && let Some(url) = omdb.poster_url()
+        && let Some(data) =
+            download_movie_poster(state, user_id, movie.id, &url, &options.poster_size).await
+        && let Ok(m) = state
+            .movie_service
+            .update_movie_poster_data(user_id, movie.id, Some(data))
+            .await
+    {
+        return TmdbRefreshResult::Success(Box::new(m));
+    }
+
+    TmdbRefreshResult::Success(Box::new(updated_movie))
+}
+
+/// Internal version of handle_collection_refresh that returns a Result
+/// Contains all strategies for finding collection posters
+/// Link a box set to its TMDB collection (searching by name if not linked yet)
+/// and store how many of the collection's parts the user owns
+async fn refresh_collection_completeness(
+    state: &Arc<AppState>,
+    user_id: Uuid,
+    collection: &Movie,
+    language: &str,
+) -> Option<TmdbCollection> {
+    let tmdb_collection_id = match collection.tmdb_collection_id {
+        Some(id) => id,
+        None => {
+            let search_term = extract_collection_name(&collection.title);
+            state
+                .tmdb_service
+                .search_collections(&search_term, Some(language))
+                .await
+                .ok()?
+                .into_iter()
+                .next()?
+                .id
+        }
+    };
+
+    let tmdb_collection = state
+        .tmdb_service
+        .get_collection_details(tmdb_collection_id, Some(language))
+        .await
+        .ok()?;
+
+    let part_ids: Vec<i64> = tmdb_collection.parts.iter().map(|p| p.id).collect();
+    let result = match state
+        .movie_service
+        .count_owned_tmdb_ids(user_id, &part_ids)
+        .await
+    {
+        Ok(owned) => {
+            state
+                .movie_service
+                .update_collection_completeness(
+                    user_id,
+                    collection.id,
+                    tmdb_collection_id,
+                    owned,
+                    part_ids.len() as i32,
+                )
+                .await
         }
-    } else {
-        updated_movie
+        Err(e) => Err(e),
     };
+    if let Err(e) = result {
+        tracing::warn!(
+            "Failed to update completeness of '{}': {}",
+            collection.title,
+            e
+        );
+    }
 
-    TmdbRefreshResult::Success(Box::new(final_movie))
+    Some(tmdb_collection)
 }
 
-/// Internal version of handle_collection_refresh that returns a Result
-/// Contains all strategies for finding collection posters
 async fn handle_collection_refresh_internal(
     state: &Arc<AppState>,
     user_id: Uuid,
     collection_id: Uuid,
     collection: &Movie,
-    language: Option<&str>,
+    options: &TmdbOptions,
 ) -> Result<Movie, String> {
-    let lang = language.unwrap_or("de-DE");
+    let lang = options.language();
+    let language = Some(lang);
 
     // Strategy 1: Try to find a TMDB collection and use its poster
-    let collection_search_term = extract_collection_name(&collection.title);
-    if let Ok(collections) = state
-        .tmdb_service
-        .search_collections(&collection_search_term, Some(lang))
-        .await
-        && let Some(tmdb_collection) = collections.into_iter().next()
+    let tmdb_collection = refresh_collection_completeness(state, user_id, collection, lang).await;
+    if let Some(tmdb_collection) = tmdb_collection
         && let Some(ref poster_path) = tmdb_collection.poster_path
-        && let Some(poster_data) = download_poster_image(poster_path).await
+        && let Some(poster_data) = download_poster_image(poster_path, &options.poster_size).await
     {
         state
             .movie_service
@@ -1126,7 +2320,8 @@ async fn handle_collection_refresh_internal(
                     .get_movie_details(tmdb_id, language)
                     .await
                 && let Some(ref poster_path) = details.poster_path
-                && let Some(poster_data) = download_poster_image(poster_path).await
+                && let Some(poster_data) =
+                    download_poster_image(poster_path, &options.poster_size).await
             {
                 state
                     .movie_service
@@ -1166,7 +2361,8 @@ async fn handle_collection_refresh_internal(
                     .await
                     && let Some(first_result) = results.into_iter().next()
                     && let Some(ref poster_path) = first_result.poster_path
-                    && let Some(poster_data) = download_poster_image(poster_path).await
+                    && let Some(poster_data) =
+                        download_poster_image(poster_path, &options.poster_size).await
                 {
                     state
                         .movie_service
@@ -1193,7 +2389,8 @@ async fn handle_collection_refresh_internal(
             .await
             && let Some(first_result) = results.into_iter().next()
             && let Some(ref poster_path) = first_result.poster_path
-            && let Some(poster_data) = download_poster_image(poster_path).await
+            && let Some(poster_data) =
+                download_poster_image(poster_path, &options.poster_size).await
         {
             state
                 .movie_service
@@ -1219,7 +2416,7 @@ async fn handle_collection_refresh_internal(
         .await
         && let Some(first_result) = results.into_iter().next()
         && let Some(ref poster_path) = first_result.poster_path
-        && let Some(poster_data) = download_poster_image(poster_path).await
+        && let Some(poster_data) = download_poster_image(poster_path, &options.poster_size).await
     {
         state
             .movie_service
@@ -1237,7 +2434,7 @@ async fn handle_collection_refresh_internal(
     if let Ok(results) = state.tmdb_service.search_tv(&clean_base, language).await
         && !results.is_empty()
         && let Some(ref poster_path) = results[0].poster_path
-        && let Some(poster_data) = download_poster_image(poster_path).await
+        && let Some(poster_data) = download_poster_image(poster_path, &options.poster_size).await
     {
         state
             .movie_service
@@ -1263,16 +2460,18 @@ pub async fn refresh_tmdb(
     Path(id): Path<Uuid>,
     Query(params): Query<RefreshTmdbQuery>,
 ) -> Result<impl IntoResponse, ApiError> {
+    if !state.tmdb_service.is_configured() {
+        return Err(ApiError::tmdb_unconfigured());
+    }
     let movie = state.movie_service.get_by_id(claims.id, id).await?;
     let user = state.auth_service.get_user(claims.id).await?;
-    let language = user.language.as_deref();
     let include_adult = user.include_adult;
 
     match refresh_movie_tmdb_internal(
         &state,
         claims.id,
         &movie,
-        language,
+        &tmdb_options(&state, &user),
         include_adult,
         params.force,
     )
@@ -1280,7 +2479,7 @@ pub async fn refresh_tmdb(
     {
         TmdbRefreshResult::Success(final_movie) => {
             let msg = json!({ "type": "movie_updated", "payload": final_movie });
-            let _ = state.ws_broadcast.send(msg.to_string());
+            let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
             Ok((StatusCode::OK, Json(json!(final_movie))))
         }
         TmdbRefreshResult::NotFound(msg) => Err(ApiError::not_found(msg)),
@@ -1288,11 +2487,165 @@ pub async fn refresh_tmdb(
     }
 }
 
+/// Most movies one batch refresh may include
+const REFRESH_BATCH_LIMIT: usize = 1000;
+
+/// Pause between movies of a batch refresh, to stay below TMDB's rate limit
+const REFRESH_BATCH_DELAY: Duration = Duration::from_millis(250);
+
+#[derive(Debug, serde::Deserialize)]
+pub struct RefreshTmdbBatchRequest {
+    pub ids: Vec<Uuid>,
+    /// Reload all data even if already present
+    #[serde(default)]
+    pub force: bool,
+}
+
+/// Refresh the given movies from TMDB, e.g. the results of a filter. Returns a
+/// job ID right away; the movies are refreshed one by one in the background.
+pub async fn refresh_tmdb_batch(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(request): Json<RefreshTmdbBatchRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    if !state.tmdb_service.is_configured() {
+        return Err(ApiError::tmdb_unconfigured());
+    }
+    let mut ids = request.ids;
+    let mut seen = std::collections::HashSet::new();
+    ids.retain(|id| seen.insert(*id));
+    if ids.is_empty() {
+        return Err(ApiError::bad_request("No movies given"));
+    }
+    if ids.len() > REFRESH_BATCH_LIMIT {
+        return Err(ApiError::bad_request(format!(
+            "At most {} movies can be refreshed at once",
+            REFRESH_BATCH_LIMIT
+        )));
+    }
+
+    let user = state.auth_service.get_user(claims.id).await?;
+    let options = tmdb_options(&state, &user);
+
+    let total = ids.len();
+    let job = state.jobs.start(claims.id, "tmdb_refresh", total);
+    let job_id = job.id;
+
+    tokio::spawn(run_refresh_tmdb_batch(
+        state.clone(),
+        claims.id,
+        ids,
+        options,
+        user.include_adult,
+        request.force,
+        job,
+    ));
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(json!({
+            "message": "TMDB refresh started",
+            "job_id": job_id,
+            "total": total,
+        })),
+    ))
+}
+
+/// Refresh the movies of a batch. Runs as a job: reports `tmdb_refresh_progress`
+/// and `movie_updated` per movie and stops early when cancelled.
+async fn run_refresh_tmdb_batch(
+    state: Arc<AppState>,
+    user_id: Uuid,
+    ids: Vec<Uuid>,
+    options: TmdbOptions,
+    include_adult: bool,
+    force: bool,
+    job: Arc<Job>,
+) {
+    let total = ids.len();
+    let mut refreshed = Vec::new();
+    let mut errors = Vec::new();
+    let mut cancelled = false;
+
+    for (index, id) in ids.into_iter().enumerate() {
+        if job.is_cancelled() {
+            cancelled = true;
+            break;
+        }
+        if index > 0 {
+            tokio::time::sleep(REFRESH_BATCH_DELAY).await;
+        }
+
+        // Movies deleted in the meantime or of other users are skipped
+        let title = match state.movie_service.get_by_id(user_id, id).await {
+            Ok(movie) => {
+                match refresh_movie_tmdb_internal(
+                    &state,
+                    user_id,
+                    &movie,
+                    &options,
+                    include_adult,
+                    force,
+                )
+                .await
+                {
+                    TmdbRefreshResult::Success(updated) => {
+                        let msg = json!({ "type": "movie_updated", "payload": updated });
+                        let _ = state.ws_broadcast.send(WsEvent::user(user_id, &msg));
+                        refreshed.push(id);
+                    }
+                    TmdbRefreshResult::NotFound(e) | TmdbRefreshResult::Error(e) => {
+                        errors.push(json!({ "id": id, "title": movie.title, "error": e }));
+                    }
+                }
+                Some(movie.title)
+            }
+            Err(e) => {
+                errors.push(json!({ "id": id, "title": null, "error": e.to_string() }));
+                None
+            }
+        };
+
+        job.set_progress(index + 1);
+        let msg = json!({
+            "type": "tmdb_refresh_progress",
+            "payload": {
+                "job_id": job.id,
+                "current": index + 1,
+                "total": total,
+                "movie_id": id,
+                "title": title,
+            }
+        });
+        let _ = state.ws_broadcast.send(WsEvent::user(user_id, &msg));
+    }
+
+    job.finish();
+
+    let _ = state.ws_broadcast.send(WsEvent::user(
+        user_id,
+        &json!({
+            "type": "tmdb_refresh_complete",
+            "payload": {
+                "job_id": job.id,
+                "total": total,
+                "refreshed_count": refreshed.len(),
+                "refreshed": refreshed,
+                "errors": errors,
+                "cancelled": cancelled,
+            }
+        }),
+    ));
+}
+
 #[derive(Debug, serde::Deserialize)]
 pub struct CheckDuplicateQuery {
     pub title: String,
     pub barcode: Option<String>,
     pub tmdb_id: Option<i64>,
+    /// Also check the movies of the other household members
+    #[serde(default)]
+    pub household: bool,
 }
 
 /// Check for duplicates before adding a movie
@@ -1301,10 +2654,16 @@ pub async fn check_duplicates(
     Extension(claims): Extension<Claims>,
     Query(query): Query<CheckDuplicateQuery>,
 ) -> Result<impl IntoResponse, ApiError> {
+    let owners = if query.household {
+        state.household_service.member_ids(claims.id).await?
+    } else {
+        vec![claims.id]
+    };
     let duplicates = state
         .movie_service
-        .find_duplicates(
+        .find_duplicates_across(
             claims.id,
+            &owners,
             &query.title,
             query.barcode.as_deref(),
             query.tmdb_id,
@@ -1373,7 +2732,7 @@ pub async fn upload_poster(
                 .await?;
 
             let msg = json!({ "type": "movie_updated", "payload": movie });
-            let _ = state.ws_broadcast.send(msg.to_string());
+            let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
 
             return Ok((
                 StatusCode::OK,
@@ -1439,7 +2798,7 @@ pub async fn set_poster_from_url(
         .await?;
 
     let msg = json!({ "type": "movie_updated", "payload": movie });
-    let _ = state.ws_broadcast.send(msg.to_string());
+    let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
 
     Ok((
         StatusCode::OK,
@@ -1450,11 +2809,67 @@ pub async fn set_poster_from_url(
     ))
 }
 
-/// Get poster image for a movie
+/// Posters and backdrops TMDB has for the movie, to pick one with
+/// `poster/from-tmdb`
+pub async fn tmdb_posters(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    let movie = state.movie_service.get_by_id(claims.id, id).await?;
+    let tmdb_id = movie
+        .tmdb_id
+        .filter(|id| *id > 0)
+        .ok_or_else(|| ApiError::bad_request("Movie is not linked to TMDB"))?;
+    let user = state.auth_service.get_user(claims.id).await?;
+
+    let images = state
+        .tmdb_service
+        .get_movie_images(tmdb_id, user.language.as_deref())
+        .await?;
+    Ok(Json(images))
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct TmdbPosterRequest {
+    /// `file_path` of a poster or backdrop from `tmdb-posters`
+    pub file_path: String,
+}
+
+/// Replace the poster with one of the TMDB images
+pub async fn set_poster_from_tmdb(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+    Json(input): Json<TmdbPosterRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    state.movie_service.get_by_id(claims.id, id).await?;
+    if !is_image_path(&input.file_path) {
+        return Err(ApiError::bad_request("Invalid TMDB image path"));
+    }
+
+    let user = state.auth_service.get_user(claims.id).await?;
+    let size = tmdb_options(&state, &user).poster_size;
+    let poster_data = download_poster_image(&input.file_path, &size)
+        .await
+        .ok_or_else(|| ApiError::new(StatusCode::BAD_GATEWAY, "Failed to download poster"))?;
+    let movie = state
+        .movie_service
+        .update_movie_poster_data(claims.id, id, Some(poster_data))
+        .await?;
+
+    let msg = json!({ "type": "movie_updated", "payload": movie });
+    let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
+
+    Ok(Json(movie))
+}
+
+/// Get poster image for a movie, as AVIF/WebP if the client accepts it
 pub async fn get_poster(
     State(state): State<Arc<AppState>>,
     Extension(claims): Extension<Claims>,
     Path(id): Path<Uuid>,
+    headers: HeaderMap,
 ) -> Result<Response, ApiError> {
     state.movie_service.get_by_id(claims.id, id).await?;
 
@@ -1464,16 +2879,78 @@ pub async fn get_poster(
         .await?
         .ok_or_else(|| ApiError::not_found("Poster not found"))?;
 
-    let content_type = detect_image_type(&data);
+    let accept = headers.get(header::ACCEPT).and_then(|v| v.to_str().ok());
+    let (content_type, data) = match poster_transcode::negotiate(accept) {
+        Some(format) if detect_image_type(&data) != format.content_type() => {
+            match transcoded_poster(&state, data.clone(), format).await {
+                Some(transcoded) => (format.content_type(), transcoded),
+                None => (detect_image_type(&data), data),
+            }
+        }
+        _ => (detect_image_type(&data), data),
+    };
 
     Response::builder()
         .status(StatusCode::OK)
         .header(header::CONTENT_TYPE, content_type)
+        .header(header::VARY, "Accept")
+        .body(Body::from(data))
+        .map_err(|e| ApiError::internal(format!("Failed to build response: {}", e)))
+}
+
+/// The wide backdrop image downloaded from TMDB on refresh
+pub async fn get_backdrop(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> Result<Response, ApiError> {
+    let data = state
+        .movie_service
+        .get_backdrop_data(claims.id, id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Backdrop not found"))?;
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, detect_image_type(&data))
         .body(Body::from(data))
         .map_err(|e| ApiError::internal(format!("Failed to build response: {}", e)))
 }
 
-fn detect_image_type(data: &[u8]) -> &'static str {
+/// Transcoded poster from the cache or freshly encoded.
+/// `None` if encoding fails or doesn't make the image smaller.
+async fn transcoded_poster(
+    state: &AppState,
+    data: Vec<u8>,
+    format: poster_transcode::PosterFormat,
+) -> Option<Vec<u8>> {
+    let hash = image_hash(&data);
+    if let Some(cached) = state.poster_transcode_cache.get(&hash, format) {
+        return Some(cached);
+    }
+
+    let original_len = data.len();
+    let result = tokio::task::spawn_blocking(move || poster_transcode::transcode(&data, format))
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|r| r);
+
+    match result {
+        Ok(transcoded) if transcoded.len() < original_len => {
+            state
+                .poster_transcode_cache
+                .insert(hash, format, transcoded.clone());
+            Some(transcoded)
+        }
+        Ok(_) => None,
+        Err(e) => {
+            tracing::warn!("Failed to transcode poster to {:?}: {}", format, e);
+            None
+        }
+    }
+}
+
+pub(crate) fn detect_image_type(data: &[u8]) -> &'static str {
     if data.len() >= 8 {
         if data[0..8] == [0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A] {
             return "image/png";
@@ -1627,8 +3104,21 @@ pub struct SplitCollectionRequest {
 
 #[derive(Debug, Deserialize)]
 pub struct SelectedMovie {
+    /// Extracted title, used to search TMDB when no ID is given
     pub title: String,
+    /// TMDB movie ID, or TMDB TV ID for `as_series` items
     pub tmdb_id: Option<i64>,
+    /// Title to store instead of the TMDB title
+    pub custom_title: Option<String>,
+    /// Production year to store instead of the TMDB release year
+    pub year: Option<i32>,
+    /// Disc of the box set this item is on
+    pub disc_number: Option<i32>,
+    #[serde(default)]
+    pub skip_poster: bool,
+    /// Create a series instead of a movie
+    #[serde(default)]
+    pub as_series: bool,
 }
 
 /// Analyze a movie to detect if it's a collection and extract individual films
@@ -2220,7 +3710,8 @@ pub async fn analyze_collection(
     (StatusCode::OK, Json(result)).into_response()
 }
 
-/// Split a collection into individual movie entries
+/// Split a collection into individual movie entries. Returns a job ID right
+/// away; the entries are created in the background.
 pub async fn split_collection(
     State(state): State<Arc<AppState>>,
     Extension(claims): Extension<Claims>,
@@ -2239,12 +3730,12 @@ pub async fn split_collection(
         }
     };
 
-    // Get user's language preference for TMDB
+    // Get user's TMDB preferences
     let user = state.auth_service.get_user(claims.id).await.ok();
-    let language = user
-        .as_ref()
-        .and_then(|u| u.language.clone())
-        .unwrap_or_else(|| "de-DE".to_string());
+    let options = match &user {
+        Some(user) => tmdb_options(&state, user),
+        None => state.tmdb_service.options(None, None),
+    };
     let include_adult = user.map(|u| u.include_adult).unwrap_or(false);
 
     // Mark original as collection
@@ -2267,6 +3758,45 @@ pub async fn split_collection(
             .into_response();
     }
 
+    let total = request.selected_movies.len();
+    let job = state.jobs.start(claims.id, "collection_split", total);
+    let job_id = job.id;
+
+    tokio::spawn(run_split_collection(
+        state.clone(),
+        claims.id,
+        original,
+        request,
+        options,
+        include_adult,
+        job,
+    ));
+
+    (
+        StatusCode::ACCEPTED,
+        Json(json!({
+            "message": "Collection split started",
+            "job_id": job_id,
+            "total": total,
+        })),
+    )
+        .into_response()
+}
+
+/// Create the selected entries of a split collection. Runs as a job: reports
+/// `collection_split_progress` per item and stops early when cancelled.
+async fn run_split_collection(
+    state: Arc<AppState>,
+    user_id: Uuid,
+    original: Movie,
+    request: SplitCollectionRequest,
+    options: TmdbOptions,
+    include_adult: bool,
+    job: Arc<Job>,
+) {
+    let movie_id = original.id;
+    let language = options.language().to_string();
+
     // If collection doesn't have a poster, try to get one
     let collection_needs_poster = original.poster_data.is_none();
     let mut first_movie_poster_path: Option<String> = None;
@@ -2274,20 +3804,66 @@ pub async fn split_collection(
     // If we have a TMDB collection poster, download it for the collection
     if collection_needs_poster && let Some(ref poster_path) = request.collection_poster_path {
         tracing::debug!("Downloading collection poster from TMDB: {}", poster_path);
-        if let Some(poster_data) = download_poster_image(poster_path).await {
+        if let Some(poster_data) =
+            download_movie_poster(&state, user_id, movie_id, poster_path, &options.poster_size)
+                .await
+        {
             let _ = state
                 .movie_service
-                .update_movie_poster_data(claims.id, movie_id, Some(poster_data))
+                .update_movie_poster_data(user_id, movie_id, Some(poster_data))
                 .await;
         }
     }
 
     let mut created_movies = Vec::new();
+    let mut created_series = Vec::new();
     let mut errors = Vec::new();
 
+    let total = request.selected_movies.len();
+    let mut cancelled = false;
+
     // Create individual movie entries
-    for selected in request.selected_movies {
+    for (index, selected) in request.selected_movies.into_iter().enumerate() {
+        if job.is_cancelled() {
+            cancelled = true;
+            break;
+        }
+
         let selected_title = selected.title.clone(); // Clone for error message
+        let msg = json!({
+            "type": "collection_split_progress",
+            "payload": {
+                "job_id": job.id,
+                "collection_id": movie_id,
+                "current": index + 1,
+                "total": total,
+                "title": selected_title,
+            }
+        });
+        let _ = state.ws_broadcast.send(WsEvent::user(user_id, &msg));
+        let custom_title = selected
+            .custom_title
+            .as_deref()
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(str::to_string);
+
+        if selected.as_series {
+            match create_split_series(&state, user_id, &original, selected, custom_title, &options)
+                .await
+            {
+                Ok((series_id, poster_path)) => {
+                    if first_movie_poster_path.is_none() {
+                        first_movie_poster_path = poster_path;
+                    }
+                    created_series.push(series_id.to_string());
+                }
+                Err(e) => {
+                    errors.push(format!("Failed to create '{}': {}", selected_title, e));
+                }
+            }
+            continue;
+        }
 
         // Try to get TMDB details if we have an ID
         let tmdb_details = if let Some(tmdb_id) = selected.tmdb_id {
@@ -2321,24 +3897,26 @@ pub async fn split_collection(
         let create_input = CreateMovie {
             barcode: None, // Don't copy barcode to individual movies
             tmdb_id: tmdb_details.as_ref().map(|d| d.id),
-            title: tmdb_details
-                .as_ref()
-                .map(|d| d.title.clone())
+            title: custom_title
+                .or_else(|| tmdb_details.as_ref().map(|d| d.title.clone()))
                 .unwrap_or(selected.title),
             original_title: tmdb_details.as_ref().and_then(|d| d.original_title.clone()),
             disc_type: original.disc_type.clone(),
-            production_year: tmdb_details.as_ref().and_then(|d| {
-                d.release_date
-                    .as_ref()
-                    .and_then(|rd| rd.split('-').next().and_then(|y| y.parse().ok()))
+            production_year: selected.year.or_else(|| {
+                tmdb_details.as_ref().and_then(|d| {
+                    d.release_date
+                        .as_ref()
+                        .and_then(|rd| rd.split('-').next().and_then(|y| y.parse().ok()))
+                })
             }),
         };
 
-        match state.movie_service.create(claims.id, create_input).await {
+        match state.movie_service.create(user_id, create_input).await {
             Ok(new_movie) => {
                 // Update with more details and link to parent collection
                 let mut update = UpdateMovie {
                     parent_collection_id: Some(movie_id),
+                    disc_number: selected.disc_number,
                     location: original.location.clone(),
                     ..Default::default()
                 };
@@ -2362,20 +3940,26 @@ pub async fn split_collection(
                     }
 
                     // Download poster
-                    if let Some(ref poster_path) = details.poster_path {
+                    if !selected.skip_poster
+                        && let Some(ref poster_path) = details.poster_path
+                    {
                         // Store first movie's poster path as fallback for collection
                         if first_movie_poster_path.is_none() {
                             first_movie_poster_path = Some(poster_path.clone());
                         }
 
-                        if let Some(poster_data) = download_poster_image(poster_path).await {
+                        if let Some(poster_data) = download_movie_poster(
+                            &state,
+                            user_id,
+                            new_movie.id,
+                            poster_path,
+                            &options.poster_size,
+                        )
+                        .await
+                        {
                             let _ = state
                                 .movie_service
-                                .update_movie_poster_data(
-                                    claims.id,
-                                    new_movie.id,
-                                    Some(poster_data),
-                                )
+                                .update_movie_poster_data(user_id, new_movie.id, Some(poster_data))
                                 .await;
                         }
                     }
@@ -2413,7 +3997,7 @@ pub async fn split_collection(
 
                 let _ = state
                     .movie_service
-                    .update(claims.id, new_movie.id, update)
+                    .update(user_id, new_movie.id, update)
                     .await;
                 created_movies.push(new_movie.id.to_string());
             }
@@ -2421,6 +4005,8 @@ pub async fn split_collection(
                 errors.push(format!("Failed to create '{}': {}", selected_title, e));
             }
         }
+
+        job.set_progress(index + 1);
     }
 
     // Fallback: If collection still has no poster and we have a first movie poster, use it
@@ -2429,35 +4015,152 @@ pub async fn split_collection(
         && let Some(ref poster_path) = first_movie_poster_path
     {
         tracing::debug!("Using first movie poster for collection: {}", poster_path);
-        if let Some(poster_data) = download_poster_image(poster_path).await {
+        if let Some(poster_data) =
+            download_movie_poster(&state, user_id, movie_id, poster_path, &options.poster_size)
+                .await
+        {
             let _ = state
                 .movie_service
-                .update_movie_poster_data(claims.id, movie_id, Some(poster_data))
+                .update_movie_poster_data(user_id, movie_id, Some(poster_data))
                 .await;
         }
     }
 
+    job.finish();
+
     // Broadcast update
-    let _ = state.ws_broadcast.send(
-        serde_json::to_string(&json!({
+    let _ = state.ws_broadcast.send(WsEvent::user(
+        user_id,
+        &json!({
             "type": "collection_split",
             "payload": {
+                "job_id": job.id,
                 "collection_id": movie_id.to_string(),
                 "created_count": created_movies.len(),
+                "created_series_count": created_series.len(),
+                "created_movies": created_movies,
+                "created_series": created_series,
+                "errors": errors,
+                "cancelled": cancelled,
             }
-        }))
-        .unwrap_or_default(),
-    );
+        }),
+    ));
+}
 
-    (
-        StatusCode::OK,
-        Json(json!({
-            "message": "Collection split successfully",
-            "created_movies": created_movies,
-            "errors": errors,
-        })),
-    )
-        .into_response()
+/// Create a series for a TV item of a split collection, with TMDB metadata and
+/// poster. Returns the series ID and the TMDB poster path.
+async fn create_split_series(
+    state: &AppState,
+    user_id: Uuid,
+    collection: &Movie,
+    selected: SelectedMovie,
+    custom_title: Option<String>,
+    options: &TmdbOptions,
+) -> my_movies_core::Result<(Uuid, Option<String>)> {
+    let language = options.language();
+    let tmdb_id = match selected.tmdb_id {
+        Some(id) => Some(id),
+        None => state
+            .tmdb_service
+            .search_tv(&selected.title, Some(language))
+            .await
+            .ok()
+            .and_then(|results| results.into_iter().next())
+            .map(|tv| tv.id),
+    };
+    let details = match tmdb_id {
+        Some(id) => state
+            .tmdb_service
+            .get_tv_details(id, Some(language))
+            .await
+            .ok(),
+        None => None,
+    };
+
+    let series = state
+        .series_service
+        .create(
+            user_id,
+            CreateSeries {
+                barcode: None,
+                tmdb_id: details.as_ref().map(|d| d.id),
+                title: custom_title
+                    .or_else(|| details.as_ref().map(|d| d.name.clone()))
+                    .unwrap_or(selected.title),
+                disc_type: collection.disc_type.clone(),
+            },
+        )
+        .await?;
+
+    let mut update = UpdateSeries {
+        parent_collection_id: Some(collection.id),
+        production_year: selected.year,
+        location: collection.location.clone(),
+        ..Default::default()
+    };
+
+    let mut poster_path = None;
+    if let Some(ref details) = details {
+        let first_aired = details
+            .first_air_date
+            .as_deref()
+            .and_then(|d| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").ok());
+        update.original_title = details.original_name.clone();
+        update.description = details.overview.clone();
+        update.tagline = details.tagline.clone();
+        update.first_aired = first_aired;
+        update.production_year = selected.year.or(first_aired.map(|d| d.year()));
+        update.status = details.status.clone();
+        update.episodes_count = details.number_of_episodes;
+        update.running_time = details
+            .episode_run_time
+            .as_ref()
+            .and_then(|times| times.first().copied());
+        update.network = details
+            .networks
+            .as_ref()
+            .map(|networks| join_names(networks.iter().map(|n| n.name.as_str())))
+            .filter(|n| !n.is_empty());
+        update.genres = details
+            .genres
+            .as_ref()
+            .map(|genres| join_names(genres.iter().map(|g| g.name.as_str())))
+            .filter(|g| !g.is_empty());
+
+        if let Ok(credits) = state
+            .tmdb_service
+            .get_tv_credits(details.id, Some(language))
+            .await
+        {
+            let actors = join_names(credits.cast.iter().take(10).map(|c| c.name.as_str()));
+            if !actors.is_empty() {
+                update.actors = Some(actors);
+            }
+        }
+
+        poster_path = details.poster_path.clone();
+    }
+
+    state
+        .series_service
+        .update(user_id, series.id, update)
+        .await?;
+
+    if !selected.skip_poster
+        && let Some(ref path) = poster_path
+        && let Some(poster_data) = download_poster_image(path, &options.poster_size).await
+    {
+        state
+            .series_service
+            .update_poster_data(user_id, series.id, Some(poster_data))
+            .await?;
+    }
+
+    Ok((series.id, poster_path))
+}
+
+fn join_names<'a>(names: impl Iterator<Item = &'a str>) -> String {
+    names.collect::<Vec<_>>().join(", ")
 }
 
 /// Get all movies that belong to a collection
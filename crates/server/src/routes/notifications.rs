@@ -0,0 +1,101 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Deserialize;
+use serde_json::json;
+
+use my_movies_core::models::{Claims, RegisterDevice};
+use uuid::Uuid;
+
+use crate::{ApiError, AppState};
+
+#[derive(Debug, Deserialize)]
+pub struct NotificationQuery {
+    #[serde(default)]
+    pub unread: bool,
+}
+
+/// The user's notifications, newest first (`?unread=true` for unread ones only)
+pub async fn list(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<NotificationQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let notifications = state
+        .notification_service
+        .list(claims.id, query.unread)
+        .await?;
+    Ok(Json(notifications))
+}
+
+pub async fn mark_read(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    state
+        .notification_service
+        .mark_read(claims.id, Some(id))
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn mark_all_read(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<impl IntoResponse, ApiError> {
+    let marked = state
+        .notification_service
+        .mark_read(claims.id, None)
+        .await?;
+    Ok(Json(json!({ "marked": marked })))
+}
+
+pub async fn delete(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    state.notification_service.delete(claims.id, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// The user's devices registered for push notifications
+pub async fn list_devices(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<impl IntoResponse, ApiError> {
+    let devices = state.notification_service.devices(claims.id).await?;
+    Ok(Json(devices))
+}
+
+/// Register a push token; loan reminders and finished imports are then also
+/// pushed to the device
+pub async fn register_device(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(input): Json<RegisterDevice>,
+) -> Result<impl IntoResponse, ApiError> {
+    let device = state
+        .notification_service
+        .register_device(claims.id, input)
+        .await?;
+    Ok((StatusCode::CREATED, Json(device)))
+}
+
+pub async fn remove_device(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    state
+        .notification_service
+        .remove_device(claims.id, id)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,172 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+use my_movies_core::models::Claims;
+use my_movies_core::services::DiscordEvent;
+use my_movies_core::services::planner::{PlannerQuery, ScheduleMovieNight};
+
+use crate::routes::movies::discord_poster_url;
+use crate::{ApiError, AppState};
+
+/// Movie night proposals: unwatched single films and double features that fit
+/// `available_minutes`, optionally limited to a genre and certifications
+pub async fn plan(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<PlannerQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let proposals = state.planner_service.plan(claims.id, &query).await?;
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "available_minutes": query.available_minutes,
+            "proposals": proposals,
+        })),
+    ))
+}
+
+/// Schedule a movie night for one of the user's movies and, once it is saved,
+/// announce it on Discord (`movie_night_scheduled`) when that event is enabled
+pub async fn schedule(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(input): Json<ScheduleMovieNight>,
+) -> Result<impl IntoResponse, ApiError> {
+    let night = state.planner_service.schedule(claims.id, &input).await?;
+
+    if state
+        .discord_service
+        .is_enabled(DiscordEvent::MovieNightScheduled)
+    {
+        let movie = state
+            .movie_service
+            .get_by_id(claims.id, night.movie_id)
+            .await?;
+        let state = state.clone();
+        let scheduled_for = night.scheduled_for;
+        tokio::spawn(async move {
+            let poster_url = discord_poster_url(&state, &movie).await;
+            if let Err(e) = state
+                .discord_service
+                .notify_movie_night(&movie, scheduled_for, poster_url)
+                .await
+            {
+                tracing::warn!("Failed to send Discord notification: {}", e);
+            }
+        });
+    }
+
+    Ok((StatusCode::CREATED, Json(json!(night))))
+}
+
+/// Movie nights that haven't started yet, soonest first
+pub async fn list_nights(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<impl IntoResponse, ApiError> {
+    let nights = state.planner_service.upcoming(claims.id).await?;
+    Ok((StatusCode::OK, Json(json!(nights))))
+}
+
+pub async fn cancel_night(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    state.planner_service.cancel(claims.id, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{Method, StatusCode};
+    use chrono::{Duration, Utc};
+    use serde_json::json;
+    use uuid::Uuid;
+
+    use my_movies_core::models::CreateMovie;
+
+    use crate::test_helpers::create_test_app;
+
+    #[tokio::test]
+    async fn test_schedule() {
+        let app = create_test_app().await;
+        let token = Some(app.user_token.as_str());
+        let user_id = app
+            .state
+            .auth_service
+            .authenticate(&app.user_token)
+            .await
+            .unwrap()
+            .id;
+        let movie = app
+            .state
+            .movie_service
+            .create(
+                user_id,
+                CreateMovie {
+                    barcode: None,
+                    tmdb_id: None,
+                    title: "Heat".to_string(),
+                    original_title: None,
+                    disc_type: None,
+                    production_year: Some(1995),
+                },
+            )
+            .await
+            .unwrap();
+        let tomorrow = Utc::now() + Duration::days(1);
+
+        // Without a webhook the movie night is saved but not announced
+        let status = app
+            .request(
+                Method::POST,
+                "/api/v1/planner/schedule",
+                token,
+                json!({ "movie_id": movie.id, "scheduled_for": tomorrow }),
+            )
+            .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let nights = app.state.planner_service.upcoming(user_id).await.unwrap();
+        assert_eq!(nights.len(), 1);
+        assert_eq!(nights[0].movie_id, movie.id);
+
+        let status = app
+            .request(
+                Method::POST,
+                "/api/v1/planner/schedule",
+                token,
+                json!({ "movie_id": movie.id, "scheduled_for": Utc::now() - Duration::hours(1) }),
+            )
+            .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let status = app
+            .request(
+                Method::POST,
+                "/api/v1/planner/schedule",
+                token,
+                json!({ "movie_id": Uuid::new_v4(), "scheduled_for": tomorrow }),
+            )
+            .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let status = app
+            .request(
+                Method::DELETE,
+                &format!("/api/v1/planner/nights/{}", nights[0].id),
+                token,
+                json!({}),
+            )
+            .await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+    }
+}
@@ -1,19 +1,29 @@
 use std::sync::Arc;
 
 use axum::{
-    Json,
-    extract::{Path, Query, State},
+    Extension, Json,
+    extract::{Multipart, Path, Query, State},
     http::StatusCode,
-    response::IntoResponse,
+    response::{IntoResponse, Response},
 };
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
+use my_movies_core::models::{BarcodeCacheEntry, Claims};
+use my_movies_core::services::{TmdbMovie, rank_cover_matches, title_candidates};
+
+use crate::rate_limit::too_many_requests;
+use crate::tmdb_proxy::{SearchCache, SearchKey};
 use crate::{ApiError, AppState};
 
+const SEARCH_LIMITED: &str = "Zu viele Suchanfragen, bitte kurz warten";
+
 #[derive(Debug, Deserialize)]
 pub struct BarcodeRequest {
     pub barcode: String,
+    /// Ask the barcode databases again instead of answering from the cache
+    #[serde(default)]
+    pub refresh: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -21,10 +31,16 @@ pub struct BarcodeResponse {
     pub barcode: String,
     pub title: Option<String>,
     pub vendor: Option<String>,
+    /// Barcode provider the title comes from
+    pub source: Option<String>,
+    /// DVD, Blu-ray or 4K UHD when the product says so
+    pub disc_type: Option<String>,
     pub tmdb_results: Vec<TmdbSearchResult>,
+    /// Answered from the barcode cache
+    pub cached: bool,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct TmdbSearchResult {
     pub id: i64,
     pub title: String,
@@ -37,14 +53,38 @@ pub async fn lookup_barcode(
     State(state): State<Arc<AppState>>,
     Json(input): Json<BarcodeRequest>,
 ) -> impl IntoResponse {
+    if !input.refresh {
+        match state.barcode_cache_service.get(&input.barcode).await {
+            Ok(Some(entry)) => {
+                let tmdb_results = entry
+                    .tmdb_results
+                    .as_deref()
+                    .and_then(|results| serde_json::from_str(results).ok())
+                    .unwrap_or_default();
+                let response = BarcodeResponse {
+                    barcode: input.barcode,
+                    title: entry.title,
+                    vendor: entry.vendor,
+                    source: entry.source,
+                    disc_type: entry.disc_type,
+                    tmdb_results,
+                    cached: true,
+                };
+                return (StatusCode::OK, Json(json!(response)));
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Barcode cache lookup failed: {}", e),
+        }
+    }
+
     let ean_result = state.ean_service.lookup(&input.barcode).await;
+    // Failed lookups aren't cached, so the next scan asks again
+    let mut cacheable = ean_result.is_ok();
 
-    let title = match &ean_result {
-        Ok(Some(result)) => Some(result.title.clone()),
-        _ => None,
-    };
+    let ean_result = ean_result.ok().flatten();
+    let title = ean_result.as_ref().map(|r| r.title.clone());
 
-    let tmdb_results = if let Some(ref t) = title {
+    let tmdb_results: Vec<TmdbSearchResult> = if let Some(ref t) = title {
         match state.tmdb_service.search_movies(t, None, None, false).await {
             Ok(results) => results
                 .into_iter()
@@ -62,7 +102,10 @@ pub async fn lookup_barcode(
                     poster_path: m.poster_path,
                 })
                 .collect(),
-            Err(_) => Vec::new(),
+            Err(_) => {
+                cacheable = false;
+                Vec::new()
+            }
         }
     } else {
         Vec::new()
@@ -71,10 +114,32 @@ pub async fn lookup_barcode(
     let response = BarcodeResponse {
         barcode: input.barcode,
         title,
-        vendor: ean_result.ok().flatten().and_then(|r| r.vendor),
+        source: ean_result.as_ref().map(|r| r.source.to_string()),
+        disc_type: ean_result
+            .as_ref()
+            .and_then(|r| r.disc_type.map(String::from)),
+        vendor: ean_result.as_ref().and_then(|r| r.vendor.clone()),
         tmdb_results,
+        cached: false,
     };
 
+    if cacheable {
+        let entry = BarcodeCacheEntry {
+            barcode: response.barcode.clone(),
+            title: response.title.clone(),
+            vendor: response.vendor.clone(),
+            category: ean_result.and_then(|r| r.category),
+            source: response.source.clone(),
+            disc_type: response.disc_type.clone(),
+            tmdb_id: response.tmdb_results.first().map(|r| r.id),
+            tmdb_results: serde_json::to_string(&response.tmdb_results).ok(),
+            ..Default::default()
+        };
+        if let Err(e) = state.barcode_cache_service.put(&entry).await {
+            tracing::warn!("Failed to cache barcode lookup: {}", e);
+        }
+    }
+
     (StatusCode::OK, Json(json!(response)))
 }
 
@@ -84,15 +149,37 @@ pub struct TmdbSearchQuery {
     pub year: Option<i32>,
 }
 
+/// Answer from the search cache, or take a request from the user's TMDB budget.
+/// Returns a 429 response with Retry-After when the budget is used up.
+fn cached_or_limited(state: &AppState, claims: &Claims, key: &SearchKey) -> Option<Response> {
+    if let Some(cached) = state.tmdb_search_cache.get(key) {
+        return Some((StatusCode::OK, Json(cached)).into_response());
+    }
+
+    state
+        .tmdb_rate_limiter
+        .check(claims.id)
+        .err()
+        .map(|retry_after| too_many_requests(retry_after, SEARCH_LIMITED))
+}
+
 pub async fn search_tmdb_movies(
     State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
     Query(params): Query<TmdbSearchQuery>,
-) -> Result<impl IntoResponse, ApiError> {
+) -> Result<Response, ApiError> {
+    if !state.tmdb_service.is_configured() {
+        return Err(ApiError::tmdb_unconfigured());
+    }
+    let key = SearchCache::key("movie", &params.query, params.year);
+    if let Some(response) = cached_or_limited(&state, &claims, &key) {
+        return Ok(response);
+    }
+
     let results = state
         .tmdb_service
         .search_movies(&params.query, params.year, None, false)
-        .await
-        .map_err(|e| ApiError::internal(e.to_string()))?;
+        .await?;
 
     let results: Vec<TmdbSearchResult> = results
         .into_iter()
@@ -111,18 +198,26 @@ pub async fn search_tmdb_movies(
         })
         .collect();
 
-    Ok((StatusCode::OK, Json(json!(results))))
+    let results = json!(results);
+    state.tmdb_search_cache.insert(key, results.clone());
+
+    Ok((StatusCode::OK, Json(results)).into_response())
 }
 
 pub async fn search_tmdb_tv(
     State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
     Query(params): Query<TmdbSearchQuery>,
-) -> Result<impl IntoResponse, ApiError> {
-    let results = state
-        .tmdb_service
-        .search_tv(&params.query, None)
-        .await
-        .map_err(|e| ApiError::internal(e.to_string()))?;
+) -> Result<Response, ApiError> {
+    if !state.tmdb_service.is_configured() {
+        return Err(ApiError::tmdb_unconfigured());
+    }
+    let key = SearchCache::key("tv", &params.query, None);
+    if let Some(response) = cached_or_limited(&state, &claims, &key) {
+        return Ok(response);
+    }
+
+    let results = state.tmdb_service.search_tv(&params.query, None).await?;
 
     let results: Vec<TmdbSearchResult> = results
         .into_iter()
@@ -141,18 +236,17 @@ pub async fn search_tmdb_tv(
         })
         .collect();
 
-    Ok((StatusCode::OK, Json(json!(results))))
+    let results = json!(results);
+    state.tmdb_search_cache.insert(key, results.clone());
+
+    Ok((StatusCode::OK, Json(results)).into_response())
 }
 
 pub async fn get_tmdb_movie(
     State(state): State<Arc<AppState>>,
     Path(id): Path<i64>,
 ) -> Result<impl IntoResponse, ApiError> {
-    let details = state
-        .tmdb_service
-        .get_movie_details(id, None)
-        .await
-        .map_err(|e| ApiError::internal(e.to_string()))?;
+    let details = state.tmdb_service.get_movie_details(id, None).await?;
 
     Ok((StatusCode::OK, Json(json!(details))))
 }
@@ -161,11 +255,113 @@ pub async fn get_tmdb_tv(
     State(state): State<Arc<AppState>>,
     Path(id): Path<i64>,
 ) -> Result<impl IntoResponse, ApiError> {
-    let details = state
-        .tmdb_service
-        .get_tv_details(id, None)
-        .await
-        .map_err(|e| ApiError::internal(e.to_string()))?;
+    let details = state.tmdb_service.get_tv_details(id, None).await?;
 
     Ok((StatusCode::OK, Json(json!(details))))
 }
+
+/// Largest cover photo accepted by `identify_cover`
+const MAX_COVER_SIZE: usize = 10 * 1024 * 1024;
+
+#[derive(Debug, Serialize)]
+pub struct CoverMatchResult {
+    #[serde(flatten)]
+    pub movie: TmdbSearchResult,
+    /// Text from the cover the movie was found with
+    pub query: String,
+    /// 0 - 1, higher is more likely
+    pub score: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IdentifyCoverResponse {
+    /// All text recognized on the cover
+    pub lines: Vec<String>,
+    /// Lines searched on TMDB, most likely title first
+    pub candidates: Vec<String>,
+    pub matches: Vec<CoverMatchResult>,
+}
+
+fn search_result(movie: TmdbMovie) -> TmdbSearchResult {
+    TmdbSearchResult {
+        id: movie.id,
+        title: movie.title,
+        year: movie
+            .release_date
+            .and_then(|d| d.get(..4).map(|s| s.to_string())),
+        poster_url: movie
+            .poster_path
+            .as_ref()
+            .map(|p| my_movies_core::services::TmdbService::poster_url(p, "w200")),
+        poster_path: movie.poster_path,
+    }
+}
+
+/// Read the title from a photo of a disc cover (multipart field `file`) and
+/// return ranked TMDB matches, for discs whose barcode can't be scanned
+pub async fn identify_cover(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    mut multipart: Multipart,
+) -> Result<Response, ApiError> {
+    if !state.tmdb_service.is_configured() {
+        return Err(ApiError::tmdb_unconfigured());
+    }
+
+    let mut image = None;
+    while let Some(field) = multipart.next_field().await.unwrap_or(None) {
+        if field.name() == Some("file") {
+            let data = field
+                .bytes()
+                .await
+                .map_err(|e| ApiError::bad_request(format!("Failed to read file: {}", e)))?;
+            image = Some(data);
+            break;
+        }
+    }
+    let image = image.ok_or_else(|| ApiError::bad_request("No file provided"))?;
+    if image.len() < 8 {
+        return Err(ApiError::bad_request("File too small to be a valid image"));
+    }
+    if image.len() > MAX_COVER_SIZE {
+        return Err(ApiError::bad_request(format!(
+            "File too large. Maximum size is 10MB, got {} bytes",
+            image.len()
+        )));
+    }
+
+    let lines = state.ocr_service.recognize(&image).await?;
+    let candidates = title_candidates(&lines);
+
+    // One identification counts as one search against the user's budget
+    if !candidates.is_empty()
+        && let Err(retry_after) = state.tmdb_rate_limiter.check(claims.id)
+    {
+        return Ok(too_many_requests(retry_after, SEARCH_LIMITED));
+    }
+
+    let mut searches = Vec::new();
+    for candidate in &candidates {
+        let results = state
+            .tmdb_service
+            .search_movies(candidate, None, None, false)
+            .await?;
+        searches.push((candidate.clone(), results));
+    }
+
+    let matches = rank_cover_matches(searches)
+        .into_iter()
+        .map(|m| CoverMatchResult {
+            movie: search_result(m.movie),
+            query: m.query,
+            score: m.score,
+        })
+        .collect();
+
+    Ok(Json(IdentifyCoverResponse {
+        lines: lines.into_iter().map(|line| line.text).collect(),
+        candidates,
+        matches,
+    })
+    .into_response())
+}
@@ -0,0 +1,50 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension, Json,
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Deserialize;
+
+use my_movies_core::models::{Claims, EntityType};
+
+use crate::{ApiError, AppState};
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 200;
+
+#[derive(Debug, Deserialize)]
+pub struct SearchQuery {
+    pub q: String,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// Ranked full-text search over the user's movies and series
+pub async fn search(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<SearchQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let offset = query.offset.unwrap_or(0).max(0);
+    let mut results = state
+        .search_service
+        .search(claims.id, &query.q, limit, offset)
+        .await?;
+
+    for result in &mut results {
+        if !result.has_poster {
+            continue;
+        }
+        result.thumb = match result.entity_type {
+            EntityType::Movie => Some(format!("/api/v1/movies/{}/thumbnail", result.id)),
+            EntityType::Series => Some(format!("/api/v1/series/{}/poster", result.id)),
+            EntityType::Collection => None,
+        };
+    }
+
+    Ok((StatusCode::OK, Json(results)))
+}
@@ -1,16 +1,21 @@
 use std::sync::Arc;
 
+use axum::body::Body;
 use axum::{
     Extension, Json,
     extract::{Path, Query, State},
-    http::StatusCode,
-    response::IntoResponse,
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
 };
 use serde_json::json;
 use uuid::Uuid;
 
-use my_movies_core::models::{Claims, CreateSeries, SeriesFilter, UpdateSeries};
+use my_movies_core::models::{
+    Claims, CreateEpisode, CreateSeries, EpisodeFilter, SeriesFilter, UpdateEpisode, UpdateSeries,
+};
 
+use super::movies::detect_image_type;
+use crate::routes::ws::WsEvent;
 use crate::{ApiError, AppState};
 
 pub async fn list(
@@ -38,7 +43,7 @@ pub async fn create(
 ) -> Result<impl IntoResponse, ApiError> {
     let series = state.series_service.create(claims.id, input).await?;
     let msg = json!({ "type": "series_added", "payload": series });
-    let _ = state.ws_broadcast.send(msg.to_string());
+    let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
     Ok((StatusCode::CREATED, Json(json!(series))))
 }
 
@@ -50,10 +55,30 @@ pub async fn update(
 ) -> Result<impl IntoResponse, ApiError> {
     let series = state.series_service.update(claims.id, id, input).await?;
     let msg = json!({ "type": "series_updated", "payload": series });
-    let _ = state.ws_broadcast.send(msg.to_string());
+    let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
     Ok((StatusCode::OK, Json(json!(series))))
 }
 
+pub async fn get_poster(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> Result<Response, ApiError> {
+    state.series_service.get_by_id(claims.id, id).await?;
+
+    let data = state
+        .series_service
+        .get_poster_data(claims.id, id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Poster not found"))?;
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, detect_image_type(&data))
+        .body(Body::from(data))
+        .map_err(|e| ApiError::internal(format!("Failed to build response: {}", e)))
+}
+
 pub async fn delete(
     State(state): State<Arc<AppState>>,
     Extension(claims): Extension<Claims>,
@@ -61,6 +86,66 @@ pub async fn delete(
 ) -> Result<impl IntoResponse, ApiError> {
     state.series_service.delete(claims.id, id).await?;
     let msg = json!({ "type": "series_deleted", "payload": { "id": id } });
-    let _ = state.ws_broadcast.send(msg.to_string());
+    let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Episodes of a series with per-season counts; supports filters
+/// (`season`, `unaired`, `watched`, `owned`) and sorting
+pub async fn list_episodes(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+    Query(filter): Query<EpisodeFilter>,
+) -> Result<impl IntoResponse, ApiError> {
+    let episodes = state
+        .series_service
+        .list_episodes(claims.id, id, filter)
+        .await?;
+    Ok((StatusCode::OK, Json(json!(episodes))))
+}
+
+pub async fn create_episode(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+    Json(input): Json<CreateEpisode>,
+) -> Result<impl IntoResponse, ApiError> {
+    let episode = state
+        .series_service
+        .create_episode(claims.id, id, input)
+        .await?;
+    let msg = json!({ "type": "episode_added", "payload": episode });
+    let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
+    Ok((StatusCode::CREATED, Json(json!(episode))))
+}
+
+pub async fn update_episode(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path((id, episode_id)): Path<(Uuid, Uuid)>,
+    Json(input): Json<UpdateEpisode>,
+) -> Result<impl IntoResponse, ApiError> {
+    let episode = state
+        .series_service
+        .update_episode(claims.id, id, episode_id, input)
+        .await?;
+    let msg = json!({ "type": "episode_updated", "payload": episode });
+    let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
+    Ok((StatusCode::OK, Json(json!(episode))))
+}
+
+pub async fn delete_episode(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path((id, episode_id)): Path<(Uuid, Uuid)>,
+) -> Result<impl IntoResponse, ApiError> {
+    state
+        .series_service
+        .delete_episode(claims.id, id, episode_id)
+        .await?;
+    let msg =
+        json!({ "type": "episode_deleted", "payload": { "id": episode_id, "series_id": id } });
+    let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
     Ok(StatusCode::NO_CONTENT)
 }
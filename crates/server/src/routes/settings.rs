@@ -2,8 +2,14 @@ use axum::{Extension, Json, extract::State};
 use std::sync::Arc;
 
 use my_movies_core::{
-    models::{Claims, SettingKey, SettingUpdate, UserRole},
-    services::SettingStatus,
+    models::{Claims, ExchangeRate, SettingKey, SettingUpdate},
+    services::{
+        EmailMessage, Mailer, SettingStatus,
+        currency::parse_manual_rates,
+        set_blob_store,
+        tmdb::{BACKDROP_SIZES, POSTER_SIZES, normalize_image_size, normalize_tmdb_languages},
+        tmdb_cache::{cache_mode, cache_ttl},
+    },
 };
 
 use crate::{ApiError, AppState};
@@ -11,12 +17,7 @@ use crate::{ApiError, AppState};
 /// Get all settings status (for admin UI)
 pub async fn get_settings(
     State(state): State<Arc<AppState>>,
-    Extension(claims): Extension<Claims>,
 ) -> Result<Json<Vec<SettingStatus>>, ApiError> {
-    if claims.role != UserRole::Admin {
-        return Err(ApiError::from(my_movies_core::Error::Forbidden));
-    }
-
     let statuses = state.settings_service.get_status().await?;
     Ok(Json(statuses))
 }
@@ -24,27 +25,112 @@ pub async fn get_settings(
 /// Update a setting
 pub async fn update_setting(
     State(state): State<Arc<AppState>>,
-    Extension(claims): Extension<Claims>,
     axum::extract::Path(key): axum::extract::Path<String>,
-    Json(update): Json<SettingUpdate>,
+    Json(mut update): Json<SettingUpdate>,
 ) -> Result<Json<SettingStatus>, ApiError> {
-    if claims.role != UserRole::Admin {
-        return Err(ApiError::from(my_movies_core::Error::Forbidden));
-    }
+    let setting_key =
+        SettingKey::from_key(&key).ok_or_else(|| ApiError::not_found("Setting not found"))?;
 
-    let setting_key = match key.as_str() {
-        "tmdb_api_key" => SettingKey::TmdbApiKey,
-        _ => return Err(ApiError::not_found("Setting not found")),
-    };
-
-    // Update runtime services directly (no restart needed!)
+    // Check the value and update runtime services directly (no restart needed!)
     match setting_key {
-        SettingKey::TmdbApiKey => {
-            state.tmdb_service.set_api_key(update.value.clone());
+        // Read from the settings snapshot on every call
+        SettingKey::TmdbApiKey
+        | SettingKey::OmdbApiKey
+        | SettingKey::DiscordWebhookUrl
+        | SettingKey::DiscordEvents => {}
+        SettingKey::ErrorReportUrl => {
+            state.error_reporter.set_url(update.value.clone());
+        }
+        // Read when an import finishes
+        SettingKey::PosterPrefetchAfterImport => {}
+        // Checked on every request to a share link
+        SettingKey::SharingEnabled => {}
+        // Checked on every registration
+        SettingKey::OpenRegistration => {}
+        // Checked here, applied after saving
+        SettingKey::BarcodeProviders
+        | SettingKey::AmazonAccessKey
+        | SettingKey::AmazonSecretKey
+        | SettingKey::AmazonPartnerTag
+        | SettingKey::AmazonMarketplace => {
+            state
+                .settings_service
+                .barcode_providers(Some((setting_key, &update.value)))
+                .await
+                .map_err(|e| ApiError::bad_request(e.to_string()))?;
+        }
+        SettingKey::ExchangeRates => {
+            parse_manual_rates(&update.value).map_err(ApiError::bad_request)?;
+        }
+        // Fetched by the daily maintenance run or via /settings/exchange-rates/refresh
+        SettingKey::ExchangeRateUrl => {}
+        // Applied after saving (READ_ONLY in the environment wins)
+        SettingKey::ReadOnly => {}
+        // Checked here, applied after saving
+        SettingKey::ImageStorage | SettingKey::S3AccessKeyId | SettingKey::S3SecretAccessKey => {
+            state
+                .settings_service
+                .blob_store(Some((setting_key, &update.value)))
+                .await
+                .map_err(|e| ApiError::bad_request(e.to_string()))?;
+        }
+        // Read from the settings snapshot on every TMDB request
+        SettingKey::TmdbCache => {
+            cache_mode(Some(&update.value)).map_err(|e| ApiError::bad_request(e.to_string()))?;
+        }
+        SettingKey::TmdbCacheTtlHours => {
+            cache_ttl(Some(&update.value)).map_err(|e| ApiError::bad_request(e.to_string()))?;
+        }
+        // Read from the settings snapshot on every TMDB refresh, stored normalized
+        SettingKey::TmdbPosterSize | SettingKey::TmdbBackdropSize => {
+            let sizes = match setting_key {
+                SettingKey::TmdbPosterSize => POSTER_SIZES,
+                _ => BACKDROP_SIZES,
+            };
+            update.value = normalize_image_size(&update.value, sizes)
+                .map_err(ApiError::bad_request)?
+                .unwrap_or_default();
+        }
+        SettingKey::TmdbLanguages => {
+            update.value = normalize_tmdb_languages(&update.value)
+                .map_err(ApiError::bad_request)?
+                .unwrap_or_default();
+        }
+        SettingKey::PushProvider | SettingKey::PushCredentials => {
+            state
+                .settings_service
+                .push_provider(Some((setting_key, &update.value)))
+                .await
+                .map_err(|e| ApiError::bad_request(e.to_string()))?;
+        }
+        SettingKey::SmtpUrl | SettingKey::MailFrom => {
+            state
+                .settings_service
+                .smtp_mailer(Some((setting_key, &update.value)))
+                .await
+                .map_err(|e| ApiError::bad_request(e.to_string()))?;
+        }
+        // Read from the settings snapshot whenever an email is sent
+        SettingKey::PublicUrl => {
+            let url = update.value.trim();
+            if !url.is_empty() && !url.starts_with("http://") && !url.starts_with("https://") {
+                return Err(ApiError::bad_request(
+                    "public_url must start with http:// or https://",
+                ));
+            }
+        }
+        SettingKey::OcrProvider | SettingKey::OcrApiKey => {
+            state
+                .settings_service
+                .ocr_provider(Some((setting_key, &update.value)))
+                .await
+                .map_err(|e| ApiError::bad_request(e.to_string()))?;
         }
     }
 
+    // Saving reloads the settings snapshot
     state.settings_service.update(setting_key, update).await?;
+    apply_settings(&state).await?;
 
     let statuses = state.settings_service.get_status().await?;
     let status = statuses
@@ -55,20 +141,54 @@ pub async fn update_setting(
     Ok(Json(status))
 }
 
+/// Read all settings again, e.g. after they were changed in the database by
+/// another process. Services reading the settings snapshot pick them up on
+/// their next call.
+pub async fn reload_settings(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<SettingStatus>>, ApiError> {
+    state.settings_service.reload().await?;
+    apply_settings(&state).await?;
+    tracing::info!("Settings reloaded");
+
+    let statuses = state.settings_service.get_status().await?;
+    Ok(Json(statuses))
+}
+
+/// Rebuild what is derived from settings rather than read per call: the
+/// read-only flag (READ_ONLY in the environment wins), the image store
+/// (only new images go to a new store), the barcode, push and OCR providers
+/// and the SMTP server
+pub(crate) async fn apply_settings(state: &AppState) -> Result<(), ApiError> {
+    let read_only = state
+        .settings_service
+        .get_bool(SettingKey::ReadOnly, false)
+        .await?;
+    state
+        .read_only
+        .store(read_only, std::sync::atomic::Ordering::Relaxed);
+
+    set_blob_store(state.settings_service.blob_store(None).await?);
+
+    let providers = state.settings_service.barcode_providers(None).await?;
+    state.ean_service.set_providers(providers);
+
+    let push_provider = state.settings_service.push_provider(None).await?;
+    state.notification_service.set_push_provider(push_provider);
+
+    let ocr_provider = state.settings_service.ocr_provider(None).await?;
+    state.ocr_service.set_provider(ocr_provider);
+
+    let smtp = state.settings_service.smtp_mailer(None).await?;
+    state.mail_service.set_smtp(smtp);
+    Ok(())
+}
+
 /// Test TMDB API key
 pub async fn test_tmdb(
     State(state): State<Arc<AppState>>,
-    Extension(claims): Extension<Claims>,
 ) -> Result<Json<TmdbTestResult>, ApiError> {
-    if claims.role != UserRole::Admin {
-        return Err(ApiError::from(my_movies_core::Error::Forbidden));
-    }
-
-    match state
-        .tmdb_service
-        .search_movies("test", None, None, false)
-        .await
-    {
+    match state.tmdb_service.check_api_key().await {
         Ok(_) => Ok(Json(TmdbTestResult {
             success: true,
             message: "TMDB API key is valid".to_string(),
@@ -80,8 +200,137 @@ pub async fn test_tmdb(
     }
 }
 
+/// Send a test message to the configured Discord webhook
+pub async fn test_discord(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<TmdbTestResult>, ApiError> {
+    match state.discord_service.send_test().await {
+        Ok(_) => Ok(Json(TmdbTestResult {
+            success: true,
+            message: "Discord test message sent".to_string(),
+        })),
+        Err(e) => Ok(Json(TmdbTestResult {
+            success: false,
+            message: format!("Discord webhook error: {}", e),
+        })),
+    }
+}
+
+/// Send a test email through the configured SMTP server to the admin
+pub async fn test_smtp(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<TmdbTestResult>, ApiError> {
+    let smtp = state
+        .mail_service
+        .smtp()
+        .ok_or_else(|| ApiError::bad_request("smtp_url is not configured"))?;
+    let user = state.auth_service.get_user(claims.id).await?;
+    let message = EmailMessage {
+        to: user.email.clone(),
+        subject: "My Movies: Test-E-Mail".to_string(),
+        body: "Der E-Mail-Versand von My Movies funktioniert.\n".to_string(),
+    };
+
+    let result = match smtp.test_connection().await {
+        Ok(()) => smtp.send(&message).await,
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(()) => Ok(Json(TmdbTestResult {
+            success: true,
+            message: format!("Test email sent to {}", user.email),
+        })),
+        Err(e) => Ok(Json(TmdbTestResult {
+            success: false,
+            message: e.to_string(),
+        })),
+    }
+}
+
+/// Exchange rates in effect (manual and fetched)
+pub async fn get_exchange_rates(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<ExchangeRate>>, ApiError> {
+    Ok(Json(state.currency_service.list().await?))
+}
+
+/// Fetch exchange rates from the configured endpoint now
+pub async fn refresh_exchange_rates(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<TmdbTestResult>, ApiError> {
+    match state.currency_service.refresh().await {
+        Ok(Some(count)) => Ok(Json(TmdbTestResult {
+            success: true,
+            message: format!("Fetched {} exchange rates", count),
+        })),
+        Ok(None) => Err(ApiError::bad_request("exchange_rate_url is not configured")),
+        Err(e) => Ok(Json(TmdbTestResult {
+            success: false,
+            message: format!("Exchange rate error: {}", e),
+        })),
+    }
+}
+
 #[derive(serde::Serialize)]
 pub struct TmdbTestResult {
     pub success: bool,
     pub message: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{Method, StatusCode};
+    use serde_json::json;
+
+    use crate::test_helpers::create_test_app;
+
+    #[tokio::test]
+    async fn test_smtp_settings() {
+        let app = create_test_app().await;
+        let token = Some(app.admin_token.as_str());
+
+        // Nothing to test until a server is configured
+        let status = app
+            .request(Method::POST, "/api/v1/settings/test/smtp", token, json!({}))
+            .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        // A server without sender is refused
+        let status = app
+            .request(
+                Method::PUT,
+                "/api/v1/settings/smtp_url",
+                token,
+                json!({ "value": "smtp://localhost:2525" }),
+            )
+            .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        for (key, value) in [
+            ("mail_from", "My Movies <movies@example.com>"),
+            ("smtp_url", "smtp://localhost:2525"),
+        ] {
+            let status = app
+                .request(
+                    Method::PUT,
+                    &format!("/api/v1/settings/{}", key),
+                    token,
+                    json!({ "value": value }),
+                )
+                .await;
+            assert_eq!(status, StatusCode::OK);
+        }
+        assert!(app.state.mail_service.smtp().is_some());
+
+        let status = app
+            .request(
+                Method::PUT,
+                "/api/v1/settings/public_url",
+                token,
+                json!({ "value": "movies.example.com" }),
+            )
+            .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
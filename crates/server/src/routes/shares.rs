@@ -0,0 +1,209 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension, Json,
+    body::Body,
+    extract::{Path, State},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+use uuid::Uuid;
+
+use my_movies_core::models::{Claims, CreateBorrowRequest, CreateShareLink, SettingKey, ShareLink};
+
+use crate::rate_limit::too_many_requests;
+use crate::routes::movies::detect_image_type;
+use crate::routes::ws::WsEvent;
+use crate::{ApiError, AppState};
+
+/// Borrow requests a share link can send in a burst
+pub const BORROW_REQUEST_BURST: f64 = 5.0;
+/// Then one more every 10 minutes
+pub const BORROW_REQUESTS_PER_SECOND: f64 = 1.0 / 600.0;
+
+async fn sharing_enabled(state: &AppState) -> Result<bool, ApiError> {
+    Ok(state
+        .settings_service
+        .get_bool(SettingKey::SharingEnabled, true)
+        .await?)
+}
+
+/// The link behind a token; everything looks like a missing link while
+/// sharing is disabled
+async fn resolve(state: &AppState, token: &str) -> Result<ShareLink, ApiError> {
+    if !sharing_enabled(state).await? {
+        return Err(ApiError::not_found("Share link not found"));
+    }
+    Ok(state.share_service.resolve(token).await?)
+}
+
+/// The user's share links, newest first
+pub async fn list(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<impl IntoResponse, ApiError> {
+    let links = state.share_service.list(claims.id).await?;
+    Ok(Json(links))
+}
+
+pub async fn create(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(input): Json<CreateShareLink>,
+) -> Result<impl IntoResponse, ApiError> {
+    if !sharing_enabled(&state).await? {
+        return Err(ApiError::new(
+            StatusCode::FORBIDDEN,
+            "Sharing is disabled on this server",
+        ));
+    }
+    let link = state.share_service.create(claims.id, input).await?;
+    Ok((StatusCode::CREATED, Json(link)))
+}
+
+/// Revoke a share link
+pub async fn delete(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    state.share_service.delete(claims.id, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Public, read-only movie list behind a share link
+pub async fn shared_library(
+    State(state): State<Arc<AppState>>,
+    Path(token): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let link = resolve(&state, &token).await?;
+    let library = state.share_service.library(&link).await?;
+    Ok(Json(library))
+}
+
+pub async fn shared_poster(
+    State(state): State<Arc<AppState>>,
+    Path((token, id)): Path<(String, Uuid)>,
+) -> Result<Response, ApiError> {
+    let link = resolve(&state, &token).await?;
+    if !state.share_service.shares_movie(&link, id).await? {
+        return Err(ApiError::not_found("Poster not found"));
+    }
+
+    let data = state
+        .movie_service
+        .get_movie_poster_data(link.user_id, id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Poster not found"))?;
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, detect_image_type(&data))
+        .body(Body::from(data))
+        .map_err(|e| ApiError::internal(format!("Failed to build response: {}", e)))
+}
+
+/// A guest asks the owner to borrow a shared movie
+pub async fn request_borrow(
+    State(state): State<Arc<AppState>>,
+    Path((token, id)): Path<(String, Uuid)>,
+    Json(input): Json<CreateBorrowRequest>,
+) -> Result<Response, ApiError> {
+    let link = resolve(&state, &token).await?;
+    // The link is public, so it must not be able to flood its owner
+    if let Err(retry_after) = state.borrow_rate_limiter.check(link.id) {
+        return Ok(too_many_requests(
+            retry_after,
+            "Zu viele Ausleihanfragen, bitte später erneut versuchen",
+        ));
+    }
+    if !state.share_service.shares_movie(&link, id).await? {
+        return Err(ApiError::not_found("Movie not found"));
+    }
+
+    let request = state
+        .loan_service
+        .request_borrow(link.user_id, id, input)
+        .await?;
+
+    let msg = json!({ "type": "borrow_request_created", "payload": request });
+    let _ = state.ws_broadcast.send(WsEvent::user(link.user_id, &msg));
+
+    Ok((StatusCode::CREATED, Json(request)).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{Method, StatusCode};
+    use serde_json::json;
+
+    use my_movies_core::models::{CreateMovie, CreateShareLink};
+
+    use super::BORROW_REQUEST_BURST;
+    use crate::test_helpers::create_test_app;
+
+    #[tokio::test]
+    async fn test_borrow_requests_are_rate_limited() {
+        let app = create_test_app().await;
+        let user_id = app
+            .state
+            .auth_service
+            .authenticate(&app.user_token)
+            .await
+            .unwrap()
+            .id;
+        let movie = app
+            .state
+            .movie_service
+            .create(
+                user_id,
+                CreateMovie {
+                    barcode: None,
+                    tmdb_id: None,
+                    title: "Heat".to_string(),
+                    original_title: None,
+                    disc_type: None,
+                    production_year: None,
+                },
+            )
+            .await
+            .unwrap();
+        let link = app
+            .state
+            .share_service
+            .create(
+                user_id,
+                CreateShareLink {
+                    collection_id: None,
+                    name: None,
+                    expires_at: None,
+                },
+            )
+            .await
+            .unwrap();
+        let path = format!("/api/v1/shared/{}/movies/{}/borrow", link.token, movie.id);
+
+        for guest in 0..BORROW_REQUEST_BURST as usize {
+            let status = app
+                .request(
+                    Method::POST,
+                    &path,
+                    None,
+                    json!({ "name": format!("Gast {}", guest) }),
+                )
+                .await;
+            assert_eq!(status, StatusCode::CREATED);
+        }
+
+        let status = app
+            .request(
+                Method::POST,
+                &path,
+                None,
+                json!({ "name": "Noch ein Gast" }),
+            )
+            .await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    }
+}
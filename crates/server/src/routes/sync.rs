@@ -0,0 +1,145 @@
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use my_movies_core::models::{
+    ApiScope, Claims, CreateApiKey, CreateSyncPeer, EntityType, SyncExchangeRequest,
+    SyncExchangeResponse, SyncPeerPublic, Tombstone,
+};
+
+use crate::routes::ws::WsEvent;
+use crate::{ApiError, AppState, peer_sync};
+
+#[derive(Debug, Deserialize)]
+pub struct TombstoneQuery {
+    pub since: DateTime<Utc>,
+    pub entity_type: Option<EntityType>,
+}
+
+/// Movies, series and collections deleted after `since`
+pub async fn tombstones(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<TombstoneQuery>,
+) -> Result<Json<Vec<Tombstone>>, ApiError> {
+    let tombstones = state
+        .tombstone_service
+        .since(claims.id, query.since, query.entity_type)
+        .await?;
+    Ok(Json(tombstones))
+}
+
+/// Called by a paired server: returns this server's changes since `since` and
+/// applies the caller's
+pub async fn exchange(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(request): Json<SyncExchangeRequest>,
+) -> Result<Json<SyncExchangeResponse>, ApiError> {
+    let server_time = Utc::now();
+    // Collected first so the caller doesn't get its own changes back
+    let changes = state
+        .sync_service
+        .changes_since(claims.id, request.since)
+        .await?;
+    let outcome = state
+        .sync_service
+        .apply(claims.id, request.since, request.changes)
+        .await?;
+
+    if outcome.created + outcome.updated + outcome.deleted > 0 {
+        let msg = json!({
+            "type": "sync_completed",
+            "payload": { "user_id": claims.id, "outcome": outcome }
+        });
+        let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
+    }
+
+    Ok(Json(SyncExchangeResponse {
+        changes,
+        outcome,
+        server_time,
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PairingTokenRequest {
+    /// Name of the server that will use the token
+    pub name: String,
+}
+
+/// Create a pairing token for another server: an API key that can read and
+/// write the library. Only returned in this response.
+pub async fn create_pairing_token(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(input): Json<PairingTokenRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let name = input.name.trim();
+    if name.is_empty() {
+        return Err(ApiError::bad_request("Name is required"));
+    }
+
+    let created = state
+        .api_key_service
+        .create(
+            claims.id,
+            &claims.role,
+            CreateApiKey {
+                name: format!("Sync: {}", name),
+                scopes: vec![ApiScope::MoviesRead, ApiScope::MoviesWrite],
+            },
+        )
+        .await?;
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+/// Servers the current user's library is synced with
+pub async fn list_peers(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Vec<SyncPeerPublic>>, ApiError> {
+    let peers = state.sync_service.list_peers(claims.id).await?;
+    Ok(Json(peers))
+}
+
+/// Pair with another server using a token created there
+pub async fn create_peer(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(input): Json<CreateSyncPeer>,
+) -> Result<impl IntoResponse, ApiError> {
+    let peer = state.sync_service.create_peer(claims.id, input).await?;
+    Ok((StatusCode::CREATED, Json(peer)))
+}
+
+/// Stop syncing with a server. The token on the peer stays valid until revoked there.
+pub async fn delete_peer(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    state.sync_service.delete_peer(claims.id, id).await?;
+    Ok(Json(json!({ "message": "Sync peer deleted" })))
+}
+
+/// Sync with a peer now instead of waiting for the next periodic run
+pub async fn run_peer(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    let peer = state.sync_service.get_peer(claims.id, id).await?;
+    let outcome = peer_sync::sync_peer(&state, &peer)
+        .await
+        .map_err(|e| ApiError::new(StatusCode::BAD_GATEWAY, e))?;
+    Ok(Json(outcome))
+}
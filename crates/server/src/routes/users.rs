@@ -4,18 +4,16 @@ use std::sync::Arc;
 use uuid::Uuid;
 
 use my_movies_core::models::{Claims, UserPublic, UserRole};
+use my_movies_core::services::{EmailMessage, Mailer};
 
+use crate::routes::auth::web_link;
+use crate::routes::ws::{Audience, WsEvent};
 use crate::{ApiError, AppState};
 
 /// List all users (admin only)
 pub async fn list_users(
     State(state): State<Arc<AppState>>,
-    Extension(claims): Extension<Claims>,
 ) -> Result<Json<Vec<UserPublic>>, ApiError> {
-    if claims.role != UserRole::Admin {
-        return Err(ApiError::from(my_movies_core::Error::Forbidden));
-    }
-
     let users = state.auth_service.list_all_users().await?;
     Ok(Json(users))
 }
@@ -32,10 +30,6 @@ pub async fn update_user_role(
     axum::extract::Path(user_id): axum::extract::Path<String>,
     Json(body): Json<UpdateRoleRequest>,
 ) -> Result<Json<UserPublic>, ApiError> {
-    if claims.role != UserRole::Admin {
-        return Err(ApiError::from(my_movies_core::Error::Forbidden));
-    }
-
     let user_id =
         Uuid::parse_str(&user_id).map_err(|_| ApiError::bad_request("Invalid user ID"))?;
 
@@ -57,7 +51,13 @@ pub async fn update_user_role(
         .await?;
 
     let msg = json!({ "type": "user_updated", "payload": user });
-    let _ = state.ws_broadcast.send(msg.to_string());
+    let _ = state
+        .ws_broadcast
+        .send(WsEvent::admins(Some(user_id), &msg));
+    // Sockets reconnect and authenticate with the new role
+    let _ = state
+        .ws_broadcast
+        .send(WsEvent::close(Audience::Users(vec![user_id])));
 
     Ok(Json(user))
 }
@@ -68,10 +68,6 @@ pub async fn delete_user(
     Extension(claims): Extension<Claims>,
     axum::extract::Path(user_id): axum::extract::Path<String>,
 ) -> Result<Json<DeleteResponse>, ApiError> {
-    if claims.role != UserRole::Admin {
-        return Err(ApiError::from(my_movies_core::Error::Forbidden));
-    }
-
     let user_id =
         Uuid::parse_str(&user_id).map_err(|_| ApiError::bad_request("Invalid user ID"))?;
 
@@ -80,6 +76,9 @@ pub async fn delete_user(
     }
 
     state.auth_service.delete_user(user_id).await?;
+    let _ = state
+        .ws_broadcast
+        .send(WsEvent::close(Audience::Users(vec![user_id])));
     Ok(Json(DeleteResponse {
         message: "User deleted successfully".to_string(),
     }))
@@ -98,14 +97,9 @@ pub struct SetPasswordRequest {
 /// Admin set password for a user
 pub async fn admin_set_password(
     State(state): State<Arc<AppState>>,
-    Extension(claims): Extension<Claims>,
     axum::extract::Path(user_id): axum::extract::Path<String>,
     Json(body): Json<SetPasswordRequest>,
 ) -> Result<Json<PasswordResetResponse>, ApiError> {
-    if claims.role != UserRole::Admin {
-        return Err(ApiError::from(my_movies_core::Error::Forbidden));
-    }
-
     let user_id =
         Uuid::parse_str(&user_id).map_err(|_| ApiError::bad_request("Invalid user ID"))?;
 
@@ -119,6 +113,10 @@ pub async fn admin_set_password(
         .auth_service
         .admin_set_password(user_id, &body.password)
         .await?;
+    // Every session was signed out
+    let _ = state
+        .ws_broadcast
+        .send(WsEvent::close(Audience::Users(vec![user_id])));
     Ok(Json(PasswordResetResponse {
         message: "Password updated successfully".to_string(),
     }))
@@ -145,13 +143,8 @@ pub struct AdminCreateUserResponse {
 /// Admin create a new user
 pub async fn admin_create_user(
     State(state): State<Arc<AppState>>,
-    Extension(claims): Extension<Claims>,
     Json(body): Json<AdminCreateUserRequest>,
 ) -> Result<Json<AdminCreateUserResponse>, ApiError> {
-    if claims.role != UserRole::Admin {
-        return Err(ApiError::from(my_movies_core::Error::Forbidden));
-    }
-
     if body.username.len() < 2 {
         return Err(ApiError::bad_request(
             "Username muss mindestens 2 Zeichen lang sein",
@@ -176,7 +169,39 @@ pub async fn admin_create_user(
         .await?;
 
     let msg = json!({ "type": "user_created", "payload": user });
-    let _ = state.ws_broadcast.send(msg.to_string());
+    let _ = state.ws_broadcast.send(WsEvent::admins(None, &msg));
+
+    // The account exists either way, so a failed email is only logged
+    if let Err(e) = send_account_email(&state, &user, reset_token.as_deref()).await {
+        tracing::warn!(
+            "Failed to send account email to {}: {}",
+            user.username,
+            e.message
+        );
+    }
 
     Ok(Json(AdminCreateUserResponse { user, reset_token }))
 }
+
+/// Welcome email for an account an admin created
+async fn send_account_email(
+    state: &AppState,
+    user: &UserPublic,
+    reset_token: Option<&str>,
+) -> Result<(), ApiError> {
+    let set_password_link = match reset_token {
+        Some(token) => Some(web_link(
+            state,
+            &format!("/reset-password?token={}", token),
+        )?),
+        None => None,
+    };
+    let email = EmailMessage::account_created(
+        &user.username,
+        &user.email,
+        &web_link(state, "/login")?,
+        set_password_link.as_deref(),
+    );
+    state.mail_service.send(&email).await?;
+    Ok(())
+}
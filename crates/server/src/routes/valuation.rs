@@ -0,0 +1,108 @@
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+};
+use serde_json::json;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use my_movies_core::models::{
+    BulkUpdateCurrency, BulkUpdateCurrencyResult, Claims, EntityType, ItemValue, SetItemValue,
+    ValuationHistoryQuery, ValuationPoint, normalize_currency,
+};
+
+use crate::routes::ws::WsEvent;
+use crate::{ApiError, AppState};
+
+/// Purchase price and current value of a movie, series or collection
+pub async fn get(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path((entity_type, id)): Path<(EntityType, Uuid)>,
+) -> Result<Json<ItemValue>, ApiError> {
+    let value = state
+        .valuation_service
+        .get(claims.id, entity_type, id)
+        .await?;
+    Ok(Json(value))
+}
+
+/// Record what a movie, series or collection is worth now
+pub async fn set_value(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path((entity_type, id)): Path<(EntityType, Uuid)>,
+    Json(mut input): Json<SetItemValue>,
+) -> Result<Json<ItemValue>, ApiError> {
+    if input.value_price.is_some() && input.value_date.is_none() {
+        let user = state.auth_service.get_user(claims.id).await?;
+        input.value_date = Some(user.today());
+    }
+    let value = state
+        .valuation_service
+        .set_value(claims.id, entity_type, id, input)
+        .await?;
+
+    if entity_type == EntityType::Movie {
+        let movie = state.movie_service.get_by_id(claims.id, id).await?;
+        let msg = json!({ "type": "movie_updated", "payload": movie });
+        let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
+    }
+
+    Ok(Json(value))
+}
+
+/// Set the currency of purchase prices and values, e.g. of all amounts
+/// entered without one
+pub async fn bulk_update_currency(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(input): Json<BulkUpdateCurrency>,
+) -> Result<Json<BulkUpdateCurrencyResult>, ApiError> {
+    let rates = state.currency_service.rates().await?;
+    let result = state
+        .valuation_service
+        .bulk_update_currency(claims.id, &input, &rates)
+        .await?;
+
+    if result.updated > 0 {
+        // Clients reload movies, series and collections
+        let msg = json!({ "type": "collection_imported" });
+        let _ = state.ws_broadcast.send(WsEvent::user(claims.id, &msg));
+    }
+
+    Ok(Json(result))
+}
+
+/// Purchase cost and current value of the collection per day, from the
+/// daily snapshots and today's totals
+pub async fn history(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<ValuationHistoryQuery>,
+) -> Result<Json<Vec<ValuationPoint>>, ApiError> {
+    let mut points = state
+        .valuation_service
+        .history(claims.id, query.from, query.to)
+        .await?;
+
+    let currency = match query.currency {
+        Some(currency) => Some(currency),
+        None => {
+            state
+                .auth_service
+                .get_user(claims.id)
+                .await?
+                .preferred_currency
+        }
+    };
+    if let Some(currency) = currency {
+        let currency = normalize_currency(&currency).map_err(ApiError::bad_request)?;
+        let rates = state.currency_service.rates().await?;
+        for point in &mut points {
+            point.converted = Some(rates.convert_valuation(&point.totals, &currency));
+        }
+    }
+
+    Ok(Json(points))
+}
@@ -1,14 +1,21 @@
 use std::sync::Arc;
 
 use axum::{
+    Json,
     extract::{
         Query, State,
-        ws::{Message, WebSocket, WebSocketUpgrade},
+        ws::{CloseFrame, Message, WebSocket, WebSocketUpgrade, close_code},
     },
+    http::StatusCode,
     response::IntoResponse,
 };
 use futures::{SinkExt, StreamExt};
 use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+use my_movies_core::models::{Claims, UserRole};
+use my_movies_core::services::ApiKeyService;
 
 use crate::AppState;
 
@@ -17,32 +24,152 @@ pub struct WsQuery {
     pub token: Option<String>,
 }
 
+/// Who receives a WebSocket event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Audience {
+    /// Server-wide events (demo reset, server messages to everyone)
+    Everyone,
+    /// The owners of the library the event is about
+    Users(Vec<Uuid>),
+    /// Administrators (user management), plus the user the event is about
+    Admins(Option<Uuid>),
+    /// Sockets opened with an access token of the session
+    Session(Uuid),
+    /// Sockets of the user opened with an API key
+    ApiKeys(Uuid),
+}
+
+/// Message for connected clients; only sockets of the audience get it
+#[derive(Debug, Clone)]
+pub struct WsEvent {
+    pub audience: Audience,
+    /// `None` closes the sockets instead, see `WsEvent::close`
+    pub message: Option<String>,
+}
+
+impl WsEvent {
+    /// Event about the user's own library or account
+    pub fn user(user_id: Uuid, message: &serde_json::Value) -> Self {
+        Self::users(vec![user_id], message)
+    }
+
+    pub fn users(user_ids: Vec<Uuid>, message: &serde_json::Value) -> Self {
+        Self {
+            audience: Audience::Users(user_ids),
+            message: Some(message.to_string()),
+        }
+    }
+
+    pub fn admins(about: Option<Uuid>, message: &serde_json::Value) -> Self {
+        Self {
+            audience: Audience::Admins(about),
+            message: Some(message.to_string()),
+        }
+    }
+
+    pub fn everyone(message: &serde_json::Value) -> Self {
+        Self {
+            audience: Audience::Everyone,
+            message: Some(message.to_string()),
+        }
+    }
+
+    /// Close the sockets of an audience whose access was revoked (session
+    /// signed out, user deleted, role changed); a client whose token is still
+    /// valid can reconnect
+    pub fn close(audience: Audience) -> Self {
+        Self {
+            audience,
+            message: None,
+        }
+    }
+
+    /// Whether a socket authenticated as `claims` receives the event
+    pub fn is_for(&self, claims: &Claims) -> bool {
+        match &self.audience {
+            Audience::Everyone => true,
+            Audience::Users(user_ids) => user_ids.contains(&claims.id),
+            Audience::Admins(about) => claims.role == UserRole::Admin || *about == Some(claims.id),
+            Audience::Session(sid) => claims.sid == Some(*sid),
+            Audience::ApiKeys(user_id) => claims.id == *user_id && claims.scopes.is_some(),
+        }
+    }
+}
+
+/// Upgrade to a WebSocket authenticated with `?token=` (session token or API
+/// key, as for the REST API); the socket only gets events meant for its user
 pub async fn websocket_handler(
     ws: WebSocketUpgrade,
     State(state): State<Arc<AppState>>,
     Query(query): Query<WsQuery>,
 ) -> impl IntoResponse {
-    // Verify token if provided
-    if let Some(token) = &query.token
-        && state.auth_service.verify_token(token).is_err()
-    {
-        // Could return 401 but WebSocket upgrade doesn't support that cleanly
-        // Instead we'll accept but immediately close
-    }
+    let Some(token) = query.token.as_deref() else {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({ "error": "Missing token" })),
+        )
+            .into_response();
+    };
 
-    ws.on_upgrade(move |socket| handle_socket(socket, state))
+    let claims = if ApiKeyService::is_api_key(token) {
+        state.api_key_service.authenticate(token).await
+    } else {
+        state.auth_service.authenticate(token).await
+    };
+    let claims = match claims {
+        Ok(claims) => claims,
+        Err(e) => {
+            return (
+                StatusCode::UNAUTHORIZED,
+                Json(json!({ "error": e.to_string() })),
+            )
+                .into_response();
+        }
+    };
+
+    ws.on_upgrade(move |socket| handle_socket(socket, state, claims))
 }
 
-async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
+async fn handle_socket(socket: WebSocket, state: Arc<AppState>, claims: Claims) {
     let (mut sender, mut receiver) = socket.split();
 
     // Subscribe to broadcast channel
     let mut rx = state.ws_broadcast.subscribe();
 
-    // Spawn task to forward broadcasts to this client
+    // Spawn task to forward this user's events to the client, until its
+    // token expires
+    let expiry = tokio::time::sleep(time_left(&claims, chrono::Utc::now().timestamp()));
     let send_task = tokio::spawn(async move {
-        while let Ok(msg) = rx.recv().await {
-            if sender.send(Message::Text(msg.into())).await.is_err() {
+        tokio::pin!(expiry);
+        loop {
+            let event = tokio::select! {
+                event = rx.recv() => event,
+                _ = &mut expiry => {
+                    let _ = sender.send(close(close_code::POLICY, "Token expired")).await;
+                    break;
+                }
+            };
+            let event = match event {
+                Ok(event) => event,
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                    // Dropped events may have been a close; the client
+                    // reconnects, authenticating again, and reloads
+                    tracing::debug!("WebSocket client skipped {} events", skipped);
+                    let _ = sender.send(close(close_code::AGAIN, "Missed events")).await;
+                    break;
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            };
+            if !event.is_for(&claims) {
+                continue;
+            }
+            let Some(message) = event.message else {
+                let _ = sender
+                    .send(close(close_code::POLICY, "Access revoked"))
+                    .await;
+                break;
+            };
+            if sender.send(Message::Text(message.into())).await.is_err() {
                 break;
             }
         }
@@ -78,3 +205,93 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
 
     tracing::debug!("WebSocket connection closed");
 }
+
+/// How long the token behind `claims` stays valid after `now` (unix seconds)
+fn time_left(claims: &Claims, now: i64) -> std::time::Duration {
+    std::time::Duration::from_secs(u64::try_from(claims.exp.saturating_sub(now)).unwrap_or(0))
+}
+
+fn close(code: u16, reason: &'static str) -> Message {
+    Message::Close(Some(CloseFrame {
+        code,
+        reason: reason.into(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claims(id: Uuid, role: UserRole) -> Claims {
+        Claims {
+            id,
+            username: "test".to_string(),
+            role,
+            exp: 0,
+            iat: 0,
+            sid: None,
+            scopes: None,
+        }
+    }
+
+    #[test]
+    fn test_event_audience() {
+        let alice = claims(Uuid::new_v4(), UserRole::User);
+        let bob = claims(Uuid::new_v4(), UserRole::User);
+        let admin = claims(Uuid::new_v4(), UserRole::Admin);
+        let msg = json!({ "type": "movie_added", "payload": {} });
+
+        let event = WsEvent::user(alice.id, &msg);
+        assert!(event.is_for(&alice));
+        assert!(!event.is_for(&bob));
+        // Admins don't see other users' libraries either
+        assert!(!event.is_for(&admin));
+
+        let event = WsEvent::admins(Some(bob.id), &msg);
+        assert!(event.is_for(&admin) && event.is_for(&bob));
+        assert!(!event.is_for(&alice));
+
+        assert!(WsEvent::everyone(&msg).is_for(&alice));
+    }
+
+    #[test]
+    fn test_close_audience() {
+        let sid = Uuid::new_v4();
+        let user = Uuid::new_v4();
+        let session = Claims {
+            sid: Some(sid),
+            ..claims(user, UserRole::User)
+        };
+        let api_key = Claims {
+            scopes: Some(Vec::new()),
+            ..claims(user, UserRole::User)
+        };
+
+        let event = WsEvent::close(Audience::Session(sid));
+        assert_eq!(event.message, None);
+        assert!(event.is_for(&session));
+        assert!(!event.is_for(&api_key));
+
+        let event = WsEvent::close(Audience::ApiKeys(user));
+        assert!(event.is_for(&api_key));
+        assert!(!event.is_for(&session));
+
+        let event = WsEvent::close(Audience::Users(vec![user]));
+        assert!(event.is_for(&session) && event.is_for(&api_key));
+    }
+
+    #[test]
+    fn test_time_left() {
+        let mut session = claims(Uuid::new_v4(), UserRole::User);
+        session.exp = 1_000;
+        assert_eq!(
+            time_left(&session, 400),
+            std::time::Duration::from_secs(600)
+        );
+        assert_eq!(time_left(&session, 2_000), std::time::Duration::ZERO);
+
+        // API keys don't expire
+        session.exp = i64::MAX;
+        assert!(time_left(&session, 400) > std::time::Duration::from_secs(100 * 365 * 86_400));
+    }
+}
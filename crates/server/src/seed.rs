@@ -0,0 +1,104 @@
+use std::io::Cursor;
+
+use my_movies_core::{
+    Config,
+    db::create_pool_with,
+    services::{SeedOptions, SeedService, seed::SEED_PASSWORD},
+};
+
+const USAGE: &str = "Usage: my-movies-server seed [--users N] [--movies N] [--series N] \
+[--collections N] [--posters] [--seed N]
+
+Generates fake users, movies, series and collections for development and
+performance testing. Counts for movies, series and collections are per user.";
+
+/// Number of distinct placeholder posters generated with --posters
+const PLACEHOLDER_POSTERS: usize = 12;
+
+/// Run the `seed` command with the arguments following it
+pub async fn run(config: &Config, args: &[String]) -> anyhow::Result<()> {
+    let options = parse_args(args)?;
+
+    let pool = create_pool_with(&config.database_url, config.database_options()).await?;
+    let service = SeedService::new(pool);
+
+    tracing::info!(
+        "Seeding {} user(s) with {} movies, {} series and {} collections each...",
+        options.users,
+        options.movies,
+        options.series,
+        options.collections
+    );
+    let started = std::time::Instant::now();
+    let report = service.seed(&options).await?;
+
+    tracing::info!(
+        "Created {} users, {} movies, {} series, {} collections in {:.1}s (password: {})",
+        report.users_created,
+        report.movies_created,
+        report.series_created,
+        report.collections_created,
+        started.elapsed().as_secs_f64(),
+        SEED_PASSWORD
+    );
+
+    Ok(())
+}
+
+fn parse_args(args: &[String]) -> anyhow::Result<SeedOptions> {
+    let mut options = SeedOptions::default();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        let mut value = || -> anyhow::Result<u64> {
+            iter.next()
+                .and_then(|v| v.parse().ok())
+                .ok_or_else(|| anyhow::anyhow!("{} expects a number\n\n{}", arg, USAGE))
+        };
+
+        match arg.as_str() {
+            "--users" => options.users = value()? as usize,
+            "--movies" => options.movies = value()? as usize,
+            "--series" => options.series = value()? as usize,
+            "--collections" => options.collections = value()? as usize,
+            "--seed" => options.seed = value()?,
+            "--posters" => options.posters = placeholder_posters()?,
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                std::process::exit(0);
+            }
+            other => anyhow::bail!("Unknown argument: {}\n\n{}", other, USAGE),
+        }
+    }
+
+    Ok(options)
+}
+
+/// Generate simple single-color JPEG posters
+fn placeholder_posters() -> anyhow::Result<Vec<Vec<u8>>> {
+    (0..PLACEHOLDER_POSTERS)
+        .map(|i| {
+            let hue = (i * 360 / PLACEHOLDER_POSTERS) as f32;
+            let color = hue_to_rgb(hue);
+            let img = image::RgbImage::from_pixel(200, 300, image::Rgb(color));
+
+            let mut output = Cursor::new(Vec::new());
+            img.write_to(&mut output, image::ImageFormat::Jpeg)?;
+            Ok(output.into_inner())
+        })
+        .collect()
+}
+
+/// Fully saturated color for a hue in degrees
+fn hue_to_rgb(hue: f32) -> [u8; 3] {
+    let x = 1.0 - ((hue / 60.0) % 2.0 - 1.0).abs();
+    let (r, g, b) = match (hue / 60.0) as u32 {
+        0 => (1.0, x, 0.0),
+        1 => (x, 1.0, 0.0),
+        2 => (0.0, 1.0, x),
+        3 => (0.0, x, 1.0),
+        4 => (x, 0.0, 1.0),
+        _ => (1.0, 0.0, x),
+    };
+    [(r * 200.0) as u8, (g * 200.0) as u8, (b * 200.0) as u8]
+}
@@ -0,0 +1,439 @@
+//! Printable index of the shelf: every disc with its location code, sorted
+//! like the shelf (sort title first), small enough to tape inside a shelf
+//! door. Rendered as HTML for the browser's print dialog or as a PDF.
+
+use chrono::NaiveDate;
+
+use my_movies_core::models::Movie;
+use my_movies_core::parsing::title::fold_title;
+
+/// A4 in points
+const PAGE_WIDTH: f64 = 595.0;
+const PAGE_HEIGHT: f64 = 842.0;
+/// About 10 mm
+const MARGIN: f64 = 28.0;
+const COLUMNS: usize = 3;
+const COLUMN_GAP: f64 = 10.0;
+const FONT_SIZE: f64 = 7.0;
+const LINE_HEIGHT: f64 = 8.5;
+/// Space at the top of each page for the heading
+const HEADER_HEIGHT: f64 = 18.0;
+/// Part of a column kept free for the location code
+const LOCATION_WIDTH: f64 = 36.0;
+/// Average Helvetica character width relative to the font size, for cutting
+/// titles that don't fit
+const AVERAGE_CHAR_WIDTH: f64 = 0.5;
+
+/// One disc on the index
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpineEntry {
+    pub title: String,
+    pub year: Option<i32>,
+    pub location: Option<String>,
+    /// Folded sort title the index is ordered by
+    sort_key: String,
+}
+
+impl SpineEntry {
+    /// Letter the entry is listed under; `#` for titles starting with a digit
+    fn group(&self) -> char {
+        match self.sort_key.chars().next() {
+            Some(c) if c.is_alphabetic() => c.to_uppercase().next().unwrap_or(c),
+            _ => '#',
+        }
+    }
+
+    fn label(&self) -> String {
+        match self.year {
+            Some(year) => format!("{} ({})", self.title, year),
+            None => self.title.clone(),
+        }
+    }
+}
+
+/// Entries for the movies in shelf order: by personal sort title, sort title,
+/// personal title or title, whichever is set first
+pub fn entries(movies: Vec<Movie>) -> Vec<SpineEntry> {
+    let mut entries: Vec<SpineEntry> = movies
+        .into_iter()
+        .map(|movie| {
+            let sort_title = [
+                &movie.personal_sort_title,
+                &movie.sort_title,
+                &movie.personal_title,
+            ]
+            .into_iter()
+            .flatten()
+            .find(|t| !t.trim().is_empty())
+            .unwrap_or(&movie.title);
+            SpineEntry {
+                sort_key: fold_title(sort_title),
+                title: movie
+                    .personal_title
+                    .clone()
+                    .filter(|t| !t.trim().is_empty())
+                    .unwrap_or_else(|| movie.title.clone()),
+                year: movie.production_year,
+                location: movie
+                    .location
+                    .map(|l| l.trim().to_string())
+                    .filter(|l| !l.is_empty()),
+            }
+        })
+        .collect();
+    entries.sort_by(|a, b| a.sort_key.cmp(&b.sort_key).then(a.year.cmp(&b.year)));
+    entries
+}
+
+/// Labels in the user's language
+pub struct Labels {
+    german: bool,
+}
+
+impl Labels {
+    pub fn new(german: bool) -> Self {
+        Self { german }
+    }
+
+    fn heading(&self, count: usize, date: NaiveDate) -> String {
+        if self.german {
+            format!("Regalindex – {} Filme – {}", count, date.format("%d.%m.%Y"))
+        } else {
+            format!(
+                "Spine index – {} movies – {}",
+                count,
+                date.format("%Y-%m-%d")
+            )
+        }
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Three columns per A4 page, a letter heading before each group
+pub fn render_html(entries: &[SpineEntry], labels: &Labels, date: NaiveDate) -> String {
+    let heading = escape_html(&labels.heading(entries.len(), date));
+    let mut html = format!(
+        r#"<!doctype html>
+<html lang="{}">
+<head>
+<meta charset="utf-8">
+<title>{}</title>
+<style>
+@page {{ size: A4; margin: 10mm; }}
+body {{ font: 8pt/1.25 Helvetica, Arial, sans-serif; margin: 0; }}
+h1 {{ font-size: 10pt; margin: 0 0 4pt; }}
+.index {{ column-count: {}; column-gap: 10pt; }}
+h2 {{ font-size: 8pt; margin: 4pt 0 1pt; break-after: avoid; }}
+.entry {{ display: flex; gap: 4pt; break-inside: avoid; }}
+.title {{ flex: 1; overflow: hidden; white-space: nowrap; text-overflow: ellipsis; }}
+.location {{ font-weight: bold; }}
+</style>
+</head>
+<body>
+<h1>{}</h1>
+<div class="index">
+"#,
+        if labels.german { "de" } else { "en" },
+        heading,
+        COLUMNS,
+        heading
+    );
+
+    let mut group = None;
+    for entry in entries {
+        if group != Some(entry.group()) {
+            group = Some(entry.group());
+            html.push_str(&format!("<h2>{}</h2>\n", entry.group()));
+        }
+        html.push_str(&format!(
+            "<div class=\"entry\"><span class=\"title\">{}</span><span class=\"location\">{}</span></div>\n",
+            escape_html(&entry.label()),
+            escape_html(entry.location.as_deref().unwrap_or_default())
+        ));
+    }
+    html.push_str("</div>\n</body>\n</html>\n");
+    html
+}
+
+/// Text as a PDF string literal in WinAnsiEncoding; characters it lacks
+/// become `?`
+fn pdf_string(text: &str) -> Vec<u8> {
+    let mut bytes = vec![b'('];
+    for c in text.chars() {
+        let byte = match c {
+            '(' | ')' | '\\' => {
+                bytes.push(b'\\');
+                c as u8
+            }
+            ' '..='~' => c as u8,
+            '\u{a0}'..='\u{ff}' => c as u32 as u8,
+            '…' => 0x85,
+            '‘' => 0x91,
+            '’' => 0x92,
+            '“' => 0x93,
+            '”' => 0x94,
+            '–' => 0x96,
+            '—' => 0x97,
+            _ => b'?',
+        };
+        bytes.push(byte);
+    }
+    bytes.push(b')');
+    bytes
+}
+
+/// Cut text to about `width` points, ending in an ellipsis
+fn fit(text: &str, width: f64) -> String {
+    let max_chars = (width / (FONT_SIZE * AVERAGE_CHAR_WIDTH)) as usize;
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    cut.truncate(cut.trim_end().len());
+    cut.push('…');
+    cut
+}
+
+enum Row<'a> {
+    Group(char),
+    Entry(&'a SpineEntry),
+}
+
+/// The same index as a PDF: Helvetica, three columns per A4 page
+pub fn render_pdf(entries: &[SpineEntry], labels: &Labels, date: NaiveDate) -> Vec<u8> {
+    let mut rows = Vec::new();
+    let mut group = None;
+    for entry in entries {
+        if group != Some(entry.group()) {
+            group = Some(entry.group());
+            rows.push(Row::Group(entry.group()));
+        }
+        rows.push(Row::Entry(entry));
+    }
+
+    let rows_per_column =
+        ((PAGE_HEIGHT - 2.0 * MARGIN - HEADER_HEIGHT) / LINE_HEIGHT).floor() as usize;
+    let column_width =
+        (PAGE_WIDTH - 2.0 * MARGIN - COLUMN_GAP * (COLUMNS - 1) as f64) / COLUMNS as f64;
+    let heading = labels.heading(entries.len(), date);
+
+    let mut pages: Vec<Vec<u8>> = Vec::new();
+    let mut rows = rows.into_iter().peekable();
+    loop {
+        let mut content = Vec::new();
+        content.extend_from_slice(
+            format!(
+                "BT /F2 9 Tf {:.1} {:.1} Td ",
+                MARGIN,
+                PAGE_HEIGHT - MARGIN - 9.0
+            )
+            .as_bytes(),
+        );
+        content.extend(pdf_string(&heading));
+        content.extend_from_slice(b" Tj ET\n");
+
+        for column in 0..COLUMNS {
+            let x = MARGIN + column as f64 * (column_width + COLUMN_GAP);
+            let mut line = 0;
+            while line < rows_per_column {
+                // A letter heading doesn't end a column
+                if matches!(rows.peek(), Some(Row::Group(_))) && line + 1 == rows_per_column {
+                    break;
+                }
+                let Some(row) = rows.next() else {
+                    break;
+                };
+                let y = PAGE_HEIGHT - MARGIN - HEADER_HEIGHT - (line + 1) as f64 * LINE_HEIGHT;
+                match row {
+                    Row::Group(letter) => {
+                        content.extend_from_slice(
+                            format!("BT /F2 {:.1} Tf {:.1} {:.1} Td ", FONT_SIZE, x, y).as_bytes(),
+                        );
+                        content.extend(pdf_string(&letter.to_string()));
+                        content.extend_from_slice(b" Tj ET\n");
+                    }
+                    Row::Entry(entry) => {
+                        let title = fit(&entry.label(), column_width - LOCATION_WIDTH);
+                        content.extend_from_slice(
+                            format!("BT /F1 {:.1} Tf {:.1} {:.1} Td ", FONT_SIZE, x, y).as_bytes(),
+                        );
+                        content.extend(pdf_string(&title));
+                        content.extend_from_slice(b" Tj ET\n");
+                        if let Some(ref location) = entry.location {
+                            let location = fit(location, LOCATION_WIDTH - 2.0);
+                            content.extend_from_slice(
+                                format!(
+                                    "BT /F2 {:.1} Tf {:.1} {:.1} Td ",
+                                    FONT_SIZE,
+                                    x + column_width - LOCATION_WIDTH + 2.0,
+                                    y
+                                )
+                                .as_bytes(),
+                            );
+                            content.extend(pdf_string(&location));
+                            content.extend_from_slice(b" Tj ET\n");
+                        }
+                    }
+                }
+                line += 1;
+            }
+        }
+
+        pages.push(content);
+        if rows.peek().is_none() {
+            break;
+        }
+    }
+
+    write_pdf(&pages)
+}
+
+/// A PDF document with one content stream per page
+fn write_pdf(pages: &[Vec<u8>]) -> Vec<u8> {
+    // 1: catalog, 2: page tree, 3 and 4: fonts, then page and content per page
+    let page_ids: Vec<usize> = (0..pages.len()).map(|i| 5 + 2 * i).collect();
+    let mut objects: Vec<Vec<u8>> = vec![
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            page_ids
+                .iter()
+                .map(|id| format!("{} 0 R", id))
+                .collect::<Vec<_>>()
+                .join(" "),
+            pages.len()
+        )
+        .into_bytes(),
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+            .to_vec(),
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>"
+            .to_vec(),
+    ];
+    for (page_id, content) in page_ids.iter().zip(pages) {
+        objects.push(
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+                 /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                PAGE_WIDTH,
+                PAGE_HEIGHT,
+                page_id + 1
+            )
+            .into_bytes(),
+        );
+        let mut stream = format!("<< /Length {} >>\nstream\n", content.len()).into_bytes();
+        stream.extend_from_slice(content);
+        stream.extend_from_slice(b"\nendstream");
+        objects.push(stream);
+    }
+
+    let mut pdf = b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+        pdf.extend_from_slice(object);
+        pdf.extend_from_slice(b"\nendobj\n");
+    }
+
+    let xref = pdf.len();
+    pdf.extend_from_slice(
+        format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes(),
+    );
+    for offset in offsets {
+        pdf.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+    }
+    pdf.extend_from_slice(
+        format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref
+        )
+        .as_bytes(),
+    );
+    pdf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(title: &str, year: Option<i32>, location: Option<&str>) -> SpineEntry {
+        SpineEntry {
+            title: title.to_string(),
+            year,
+            location: location.map(String::from),
+            sort_key: fold_title(title),
+        }
+    }
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, 16).unwrap()
+    }
+
+    #[test]
+    fn test_render_html() {
+        let entries = [
+            entry("12 Monkeys", Some(1995), Some("A1")),
+            entry("Alien", Some(1979), Some("B3")),
+            entry("Amélie & <Nino>", None, None),
+        ];
+        let html = render_html(&entries, &Labels::new(true), date());
+        assert!(html.contains("<h1>Regalindex – 3 Filme – 16.10.2026</h1>"));
+        assert!(html.contains("<h2>#</h2>"));
+        assert_eq!(html.matches("<h2>A</h2>").count(), 1);
+        assert!(html.contains(
+            "<span class=\"title\">Alien (1979)</span><span class=\"location\">B3</span>"
+        ));
+        assert!(html.contains("Amélie &amp; &lt;Nino&gt;"));
+    }
+
+    #[test]
+    fn test_render_pdf() {
+        let entries: Vec<SpineEntry> = (0..500)
+            .map(|i| entry(&format!("Film {} (Teil)", i), Some(2000), Some("R2")))
+            .collect();
+        let pdf = render_pdf(&entries, &Labels::new(false), date());
+        assert!(pdf.starts_with(b"%PDF-1.4"));
+        assert!(pdf.ends_with(b"%%EOF\n"));
+        // 90 rows per column, 3 columns: 500 entries and a heading need two pages
+        assert!(String::from_utf8_lossy(&pdf).contains("/Count 2"));
+
+        let xref = String::from_utf8_lossy(&pdf);
+        let start: usize = xref
+            .rsplit("startxref\n")
+            .next()
+            .and_then(|s| s.lines().next())
+            .and_then(|s| s.parse().ok())
+            .unwrap();
+        assert!(pdf[start..].starts_with(b"xref"));
+    }
+
+    #[test]
+    fn test_pdf_string() {
+        assert_eq!(pdf_string("Léon (1994)"), b"(L\xe9on \\(1994\\))".to_vec());
+        assert_eq!(
+            pdf_string("Spirited Away – 千と千尋"),
+            b"(Spirited Away \x96 ????)".to_vec()
+        );
+    }
+
+    #[test]
+    fn test_fit() {
+        assert_eq!(fit("Alien", 100.0), "Alien");
+        let cut = fit("Dr. Strangelove or: How I Learned to Stop Worrying", 50.0);
+        assert_eq!(cut, "Dr. Strangelo…");
+    }
+}
@@ -0,0 +1,145 @@
+//! Test helpers for building the full router against a temporary database
+
+use std::sync::Arc;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Method, Request, StatusCode},
+};
+use tower::ServiceExt;
+
+use my_movies_core::{Config, models::CreateUser};
+
+use crate::{AppState, create_app_state, create_router};
+
+pub const TEST_JWT_SECRET: &str = "test-secret-key-for-testing";
+
+/// Router, state and the temp dir holding the database (deleted on drop)
+pub struct TestApp {
+    pub router: Router,
+    pub state: Arc<AppState>,
+    pub admin_token: String,
+    pub user_token: String,
+    _dir: tempfile::TempDir,
+}
+
+/// Build the app with a fresh SQLite database, an admin and a regular user
+pub async fn create_test_app() -> TestApp {
+    let dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let db_path = dir.path().join("test.db");
+
+    let config = Config {
+        database_url: format!("sqlite:{}?mode=rwc", db_path.display()),
+        skip_migrations: false,
+        backup_before_migrate: false,
+        slow_query_ms: None,
+        jwt_secret: TEST_JWT_SECRET.to_string(),
+        jwt_algorithm: None,
+        jwt_private_key_file: None,
+        jwt_public_key_file: None,
+        jwt_previous_secret: None,
+        jwt_previous_public_key_file: None,
+        tmdb_api_key: String::new(),
+        host: "127.0.0.1".to_string(),
+        port: 0,
+        log_dir: None,
+        log_max_files: 1,
+        static_dir: None,
+        cors_allowed_origins: Vec::new(),
+        swagger_ui: false,
+        discord_webhook_url: None,
+        discord_events: None,
+        error_report_url: None,
+        demo_mode: false,
+        demo_reset_minutes: 60,
+    };
+
+    let state = create_app_state(&config)
+        .await
+        .expect("Failed to create app state");
+
+    // The first registered user becomes admin
+    let admin = state
+        .auth_service
+        .register(CreateUser {
+            username: "admin".to_string(),
+            email: "admin@test.com".to_string(),
+            password: "password".to_string(),
+        })
+        .await
+        .expect("Failed to create admin");
+    let user = state
+        .auth_service
+        .register(CreateUser {
+            username: "user".to_string(),
+            email: "user@test.com".to_string(),
+            password: "password".to_string(),
+        })
+        .await
+        .expect("Failed to create user");
+
+    TestApp {
+        router: create_router(state.clone(), None),
+        state,
+        admin_token: admin.token,
+        user_token: user.token,
+        _dir: dir,
+    }
+}
+
+impl TestApp {
+    /// Serve the router on a free local port, returning its base URL
+    pub async fn serve(&self) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("Failed to bind test server");
+        let addr = listener.local_addr().expect("No local address");
+        let router = self.router.clone();
+        tokio::spawn(async move {
+            axum::serve(listener, router).await.ok();
+        });
+        format!("http://{}", addr)
+    }
+
+    /// Send a request with an optional bearer token and JSON body, returning the status
+    pub async fn request(
+        &self,
+        method: Method,
+        path: &str,
+        token: Option<&str>,
+        body: serde_json::Value,
+    ) -> StatusCode {
+        let mut builder = Request::builder()
+            .method(method)
+            .uri(path)
+            .header("content-type", "application/json");
+        if let Some(token) = token {
+            builder = builder.header("Authorization", format!("Bearer {}", token));
+        }
+
+        let request = builder
+            .body(Body::from(body.to_string()))
+            .expect("Failed to build request");
+
+        self.router
+            .clone()
+            .oneshot(request)
+            .await
+            .expect("Request failed")
+            .status()
+    }
+}
+
+/// Whether a request to `path` (query ignored) goes to the route `template`;
+/// `{param}` segments match any segment
+pub fn route_matches(template: &str, path: &str) -> bool {
+    let path = path.split('?').next().unwrap_or_default();
+    let (template, path): (Vec<_>, Vec<_>) =
+        (template.split('/').collect(), path.split('/').collect());
+    template.len() == path.len()
+        && template
+            .iter()
+            .zip(&path)
+            .all(|(t, p)| t == p || t.starts_with('{'))
+}
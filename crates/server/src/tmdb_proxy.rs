@@ -0,0 +1,102 @@
+//! Per-user request budgets and a short-lived result cache for the TMDB search proxy,
+//! so search-as-you-type can't burn the shared TMDB quota (budgets are kept by
+//! [`crate::rate_limit::RateLimiter`])
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Searches a user can make in a burst
+pub const BUCKET_CAPACITY: f64 = 20.0;
+/// Searches per second added back to the budget
+pub const REFILL_PER_SECOND: f64 = 2.0;
+
+/// How long identical queries are answered from the cache
+const CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+const CACHE_MAX_ENTRIES: usize = 1000;
+
+/// Cache key for a search: kind ("movie"/"tv"), normalized query and year
+pub type SearchKey = (&'static str, String, Option<i32>);
+
+/// Caches TMDB search results for identical queries
+pub struct SearchCache {
+    entries: Mutex<HashMap<SearchKey, (Instant, serde_json::Value)>>,
+}
+
+impl Default for SearchCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SearchCache {
+    pub fn new() -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Build a cache key; queries differing only in case or whitespace share an entry
+    pub fn key(kind: &'static str, query: &str, year: Option<i32>) -> SearchKey {
+        let query = query.split_whitespace().collect::<Vec<_>>().join(" ");
+        (kind, query.to_lowercase(), year)
+    }
+
+    pub fn get(&self, key: &SearchKey) -> Option<serde_json::Value> {
+        let entries = self.entries.lock().ok()?;
+        entries
+            .get(key)
+            .filter(|(stored, _)| stored.elapsed() < CACHE_TTL)
+            .map(|(_, value)| value.clone())
+    }
+
+    pub fn insert(&self, key: SearchKey, value: serde_json::Value) {
+        let Ok(mut entries) = self.entries.lock() else {
+            return;
+        };
+
+        if entries.len() >= CACHE_MAX_ENTRIES {
+            entries.retain(|_, (stored, _)| stored.elapsed() < CACHE_TTL);
+        }
+        if entries.len() >= CACHE_MAX_ENTRIES
+            && let Some(oldest) = entries
+                .iter()
+                .min_by_key(|(_, (stored, _))| *stored)
+                .map(|(k, _)| k.clone())
+        {
+            entries.remove(&oldest);
+        }
+
+        entries.insert(key, (Instant::now(), value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_cache_normalizes_queries() {
+        let cache = SearchCache::new();
+        cache.insert(
+            SearchCache::key("movie", "The  Matrix ", None),
+            serde_json::json!([1]),
+        );
+
+        assert!(
+            cache
+                .get(&SearchCache::key("movie", "the matrix", None))
+                .is_some()
+        );
+        assert!(
+            cache
+                .get(&SearchCache::key("tv", "the matrix", None))
+                .is_none()
+        );
+        assert!(
+            cache
+                .get(&SearchCache::key("movie", "the matrix", Some(1999)))
+                .is_none()
+        );
+    }
+}